@@ -10,6 +10,7 @@ use std::sync::Arc;
 use aws_smithy_async::rt::sleep::AsyncSleep;
 use aws_smithy_client::erase::DynConnector;
 use aws_smithy_client::http_connector::HttpSettings;
+use aws_smithy_client::tls::TlsError;
 
 // unused when all crate features are disabled
 /// Unwrap an [`Option<DynConnector>`](aws_smithy_client::erase::DynConnector), and panic with a helpful error message if it's `None`
@@ -31,30 +32,75 @@ fn base(
 }
 
 /// Given `HttpSettings` and an `AsyncSleep`, create a `DynConnector` from defaults depending on what cargo features are activated.
+///
+/// Returns an error if the TLS context of `settings` can't be applied to the TLS backend.
 #[cfg(feature = "rustls")]
-pub fn default_connector(
+pub fn try_default_connector(
     settings: &HttpSettings,
     sleep: Option<Arc<dyn AsyncSleep>>,
-) -> Option<DynConnector> {
-    let hyper = base(settings, sleep).build(aws_smithy_client::conns::https());
-    Some(DynConnector::new(hyper))
+) -> Result<Option<DynConnector>, TlsError> {
+    let https = aws_smithy_client::conns::https_with_tls_context(
+        &settings.tcp_settings,
+        &settings.tls_context,
+    )?;
+    let hyper = base(settings, sleep).build(https);
+    Ok(Some(DynConnector::from(hyper)))
 }
 
 /// Given `HttpSettings` and an `AsyncSleep`, create a `DynConnector` from defaults depending on what cargo features are activated.
+///
+/// Returns an error if the TLS context of `settings` can't be applied to the TLS backend.
 #[cfg(all(not(feature = "rustls"), feature = "native-tls"))]
-pub fn default_connector(
+pub fn try_default_connector(
     settings: &HttpSettings,
     sleep: Option<Arc<dyn AsyncSleep>>,
-) -> Option<DynConnector> {
-    let hyper = base(settings, sleep).build(aws_smithy_client::conns::native_tls());
-    Some(DynConnector::new(hyper))
+) -> Result<Option<DynConnector>, TlsError> {
+    let https = aws_smithy_client::conns::native_tls_with_tls_context(
+        &settings.tcp_settings,
+        &settings.tls_context,
+    )?;
+    let hyper = base(settings, sleep).build(https);
+    Ok(Some(DynConnector::from(hyper)))
 }
 
 /// Given `HttpSettings` and an `AsyncSleep`, create a `DynConnector` from defaults depending on what cargo features are activated.
+///
+/// Returns an error if the TLS context of `settings` can't be applied to the TLS backend.
 #[cfg(not(any(feature = "rustls", feature = "native-tls")))]
-pub fn default_connector(
+pub fn try_default_connector(
     _settings: &HttpSettings,
     _sleep: Option<Arc<dyn AsyncSleep>>,
+) -> Result<Option<DynConnector>, TlsError> {
+    Ok(None)
+}
+
+/// Given `HttpSettings` and an `AsyncSleep`, create a `DynConnector` from defaults depending on what cargo features are activated.
+///
+/// Returns `None` if the TLS context of `settings` can't be applied to the TLS backend. The default
+/// TLS context is always valid; use [`try_default_connector`] to find out why a custom one isn't.
+pub fn default_connector(
+    settings: &HttpSettings,
+    sleep: Option<Arc<dyn AsyncSleep>>,
 ) -> Option<DynConnector> {
-    None
+    try_default_connector(settings, sleep).ok().flatten()
+}
+
+#[cfg(all(test, feature = "rustls"))]
+mod test {
+    use super::try_default_connector;
+    use aws_smithy_client::http_connector::HttpSettings;
+    use aws_smithy_client::tls::{TlsContext, TrustStore};
+
+    #[test]
+    fn invalid_tls_context_is_an_error() {
+        let tls_context = TlsContext::new()
+            .with_trust_store(TrustStore::new().with_der_certificate(vec![1, 2, 3]));
+        let settings = HttpSettings::default().with_tls_context(tls_context);
+        let err = try_default_connector(&settings, None).expect_err("invalid CA certificate");
+        assert!(
+            err.to_string().contains("invalid CA certificate"),
+            "{}",
+            err
+        );
+    }
 }
@@ -0,0 +1,455 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Load Credentials from an external process
+//!
+//! The `credential_process` profile setting names a command that prints credentials to stdout:
+//! ```ini
+//! [profile default]
+//! credential_process = /opt/bin/awscreds-retriever --username susan
+//! ```
+//!
+//! The command must print a JSON document in the following format, exiting with status `0`:
+//! ```json
+//! {
+//!   "Version": 1,
+//!   "AccessKeyId": "an AWS access key",
+//!   "SecretAccessKey": "your AWS secret access key",
+//!   "SessionToken": "the AWS session token for temporary credentials",
+//!   "Expiration": "ISO8601 timestamp when the credentials expire"
+//! }
+//! ```
+//! `SessionToken` and `Expiration` are optional. Credentials without an `Expiration` are treated
+//! as long-lived credentials.
+//!
+//! _Note: Configuration of the credential process via a shared profile is only supported
+//! when using the [`ProfileFileCredentialsProvider`](crate::profile::credentials)._
+
+use crate::json_credentials::{json_parse_loop_with_numbers, InvalidJsonCredentials, JsonValue};
+use crate::provider_config::ProviderConfig;
+use aws_smithy_async::future::timeout::Timeout;
+use aws_smithy_async::rt::sleep::{default_async_sleep, AsyncSleep};
+use aws_smithy_types::date_time::Format;
+use aws_smithy_types::{DateTime, Number};
+use aws_types::credentials::{self, future, CredentialsError, ProvideCredentials};
+use aws_types::Credentials;
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+use std::process::{ExitStatus, Stdio};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::process::Command;
+
+const PROVIDER_NAME: &str = "CredentialProcess";
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Credentials provider that runs a command and parses credentials from its output
+///
+/// See the [module documentation](crate::credential_process) for the output format the command
+/// must follow.
+pub struct CredentialProcessProvider {
+    command: String,
+    timeout: Duration,
+    sleep: Option<Arc<dyn AsyncSleep>>,
+}
+
+impl fmt::Debug for CredentialProcessProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CredentialProcessProvider")
+            .field("command", &RedactedCommand(&self.command))
+            .field("timeout", &self.timeout)
+            .field("sleep", &self.sleep)
+            .finish()
+    }
+}
+
+/// Formats a command with its arguments redacted, since they often contain secrets
+struct RedactedCommand<'a>(&'a str);
+
+impl fmt::Debug for RedactedCommand<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let command = self.0.trim();
+        match command.find(char::is_whitespace) {
+            Some(index) => write!(f, "{} ** arguments redacted **", &command[..index]),
+            None => write!(f, "{}", command),
+        }
+    }
+}
+
+impl CredentialProcessProvider {
+    /// Creates a new builder for `CredentialProcessProvider`
+    pub fn builder() -> Builder {
+        Builder::default()
+    }
+
+    async fn credentials(&self) -> credentials::Result {
+        if self.command.trim().is_empty() {
+            return Err(CredentialsError::invalid_configuration(
+                "`credential_process` must not be empty",
+            ));
+        }
+        tracing::debug!("loading credentials from external process");
+
+        let mut command = shell_command(&self.command);
+        command
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            // if the command times out, make sure it doesn't outlive the provider
+            .kill_on_drop(true);
+        let output = command.output();
+        let output = match &self.sleep {
+            Some(sleep) => Timeout::new(output, sleep.sleep(self.timeout))
+                .await
+                .map_err(|_| CredentialsError::provider_timed_out(self.timeout))?,
+            None => output.await,
+        }
+        .map_err(|err| CredentialsError::provider_error(CredentialProcessError::Spawn(err)))?;
+
+        if !output.status.success() {
+            return Err(CredentialsError::provider_error(
+                CredentialProcessError::NonZeroExit {
+                    status: output.status,
+                    stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+                },
+            ));
+        }
+        let output = std::str::from_utf8(&output.stdout).map_err(|err| {
+            CredentialsError::unhandled(CredentialProcessError::InvalidOutput(err.into()))
+        })?;
+        parse_credential_process_output(output).map_err(|err| {
+            CredentialsError::unhandled(CredentialProcessError::InvalidOutput(err.into()))
+        })
+    }
+}
+
+impl ProvideCredentials for CredentialProcessProvider {
+    fn provide_credentials<'a>(&'a self) -> future::ProvideCredentials<'a>
+    where
+        Self: 'a,
+    {
+        future::ProvideCredentials::new(self.credentials())
+    }
+}
+
+#[cfg(windows)]
+fn shell_command(command: &str) -> Command {
+    let mut cmd = Command::new("cmd.exe");
+    cmd.args(&["/C", command]);
+    cmd
+}
+
+#[cfg(not(windows))]
+fn shell_command(command: &str) -> Command {
+    let mut cmd = Command::new("sh");
+    cmd.args(&["-c", command]);
+    cmd
+}
+
+/// Builder for [`CredentialProcessProvider`]
+#[derive(Default)]
+pub struct Builder {
+    command: Option<String>,
+    timeout: Option<Duration>,
+    sleep: Option<Arc<dyn AsyncSleep>>,
+}
+
+impl fmt::Debug for Builder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Builder")
+            .field("command", &self.command.as_deref().map(RedactedCommand))
+            .field("timeout", &self.timeout)
+            .field("sleep", &self.sleep)
+            .finish()
+    }
+}
+
+impl Builder {
+    /// The command to run, which is passed to the platform's shell (`sh -c` or `cmd.exe /C`)
+    pub fn command(mut self, command: impl Into<String>) -> Self {
+        self.command = Some(command.into());
+        self
+    }
+
+    /// Maximum time to wait for the command to exit
+    ///
+    /// The command is killed if it takes longer than this. Defaults to 60 seconds.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Override configuration for the [Builder]
+    pub fn configure(mut self, provider_config: &ProviderConfig) -> Self {
+        self.sleep = provider_config.sleep();
+        self
+    }
+
+    /// Builds the [`CredentialProcessProvider`]
+    ///
+    /// # Panics
+    /// This will panic if no command was given.
+    pub fn build(self) -> CredentialProcessProvider {
+        CredentialProcessProvider {
+            command: self.command.expect("command is required"),
+            timeout: self.timeout.unwrap_or(DEFAULT_TIMEOUT),
+            sleep: self.sleep.or_else(default_async_sleep),
+        }
+    }
+}
+
+/// Failure to load credentials from an external process
+#[non_exhaustive]
+#[derive(Debug)]
+pub enum CredentialProcessError {
+    /// The command could not be run
+    Spawn(std::io::Error),
+
+    /// The command exited with a non-zero status
+    NonZeroExit {
+        /// The exit status of the command
+        status: ExitStatus,
+        /// Everything the command printed to stderr
+        stderr: String,
+    },
+
+    /// The command's output could not be parsed as credentials
+    InvalidOutput(Box<dyn Error + Send + Sync>),
+}
+
+impl fmt::Display for CredentialProcessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CredentialProcessError::Spawn(_) => write!(f, "failed to run `credential_process`"),
+            CredentialProcessError::NonZeroExit { status, stderr } => write!(
+                f,
+                "`credential_process` exited unsuccessfully ({}): {}",
+                status, stderr
+            ),
+            CredentialProcessError::InvalidOutput(err) => write!(
+                f,
+                "`credential_process` returned invalid credentials: {}",
+                err
+            ),
+        }
+    }
+}
+
+impl Error for CredentialProcessError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            CredentialProcessError::Spawn(err) => Some(err),
+            CredentialProcessError::NonZeroExit { .. } => None,
+            CredentialProcessError::InvalidOutput(err) => Some(err.as_ref() as _),
+        }
+    }
+}
+
+/// Parses the JSON printed by a credential process
+fn parse_credential_process_output(output: &str) -> Result<Credentials, InvalidJsonCredentials> {
+    let mut version = None;
+    let mut access_key_id = None;
+    let mut secret_access_key = None;
+    let mut session_token = None;
+    let mut expiration = None;
+    let mut account_id = None;
+    json_parse_loop_with_numbers(output.as_bytes(), |key, value| match (key, value) {
+        (key, JsonValue::Number(value)) if key.eq_ignore_ascii_case("Version") => {
+            version = Some(value)
+        }
+        (key, JsonValue::String(value)) if key.eq_ignore_ascii_case("AccessKeyId") => {
+            access_key_id = Some(value)
+        }
+        (key, JsonValue::String(value)) if key.eq_ignore_ascii_case("SecretAccessKey") => {
+            secret_access_key = Some(value)
+        }
+        (key, JsonValue::String(value)) if key.eq_ignore_ascii_case("SessionToken") => {
+            session_token = Some(value)
+        }
+        (key, JsonValue::String(value)) if key.eq_ignore_ascii_case("Expiration") => {
+            expiration = Some(value)
+        }
+        (key, JsonValue::String(value)) if key.eq_ignore_ascii_case("AccountId") => {
+            account_id = Some(value)
+        }
+        _ => {}
+    })?;
+
+    match version {
+        Some(Number::PosInt(1)) => {}
+        None => return Err(InvalidJsonCredentials::MissingField("Version")),
+        Some(other) => {
+            return Err(InvalidJsonCredentials::InvalidField {
+                field: "Version",
+                err: format!("unsupported version {:?}, expected 1", other).into(),
+            })
+        }
+    }
+    let access_key_id = access_key_id.ok_or(InvalidJsonCredentials::MissingField("AccessKeyId"))?;
+    let secret_access_key =
+        secret_access_key.ok_or(InvalidJsonCredentials::MissingField("SecretAccessKey"))?;
+    let expiration = expiration
+        .map(|expiration| {
+            let date_time =
+                DateTime::from_str(expiration.as_ref(), Format::DateTime).map_err(|err| {
+                    InvalidJsonCredentials::InvalidField {
+                        field: "Expiration",
+                        err: err.into(),
+                    }
+                })?;
+            SystemTime::try_from(date_time).map_err(|_| {
+                InvalidJsonCredentials::Other(
+                    "credential expiration time cannot be represented by a SystemTime".into(),
+                )
+            })
+        })
+        .transpose()?;
+    let credentials = Credentials::new(
+        access_key_id,
+        secret_access_key,
+        session_token.map(|token| token.into_owned()),
+        expiration,
+        PROVIDER_NAME,
+    );
+    match account_id {
+        Some(account_id) => Ok(credentials.with_account_id(account_id)),
+        None => Ok(credentials),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse_credential_process_output, CredentialProcessProvider};
+    use crate::provider_config::ProviderConfig;
+    use aws_smithy_async::rt::sleep::TokioSleep;
+    use aws_types::credentials::{CredentialsError, ProvideCredentials};
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    #[test]
+    fn parse_full_output() {
+        let creds = parse_credential_process_output(
+            r#"{
+                "Version": 1,
+                "AccessKeyId": "ASIARTESTID",
+                "SecretAccessKey": "TESTSECRETKEY",
+                "SessionToken": "TESTSESSIONTOKEN",
+                "Expiration": "2022-05-02T18:36:00+00:00",
+                "AccountId": "123456789012"
+            }"#,
+        )
+        .expect("valid output");
+        assert_eq!(Some("123456789012"), creds.account_id());
+        assert_eq!("ASIARTESTID", creds.access_key_id());
+        assert_eq!("TESTSECRETKEY", creds.secret_access_key());
+        assert_eq!(Some("TESTSESSIONTOKEN"), creds.session_token());
+        assert_eq!(
+            Some(UNIX_EPOCH + Duration::from_secs(1651516560)),
+            creds.expiry()
+        );
+    }
+
+    #[test]
+    fn debug_redacts_the_command_arguments() {
+        let builder = CredentialProcessProvider::builder()
+            .command("/opt/bin/awscreds-retriever --password hunter2");
+        assert!(!format!("{:?}", builder).contains("hunter2"));
+        let provider = builder.build();
+        let debug = format!("{:?}", provider);
+        assert!(!debug.contains("hunter2"), "{}", debug);
+        assert!(debug.contains("/opt/bin/awscreds-retriever ** arguments redacted **"));
+    }
+
+    #[test]
+    fn parse_long_lived_credentials() {
+        let creds = parse_credential_process_output(
+            r#"{"Version": 1, "AccessKeyId": "AKIDTEST", "SecretAccessKey": "TESTSECRETKEY"}"#,
+        )
+        .expect("valid output");
+        assert_eq!(None, creds.session_token());
+        assert_eq!(None::<SystemTime>, creds.expiry());
+    }
+
+    #[test]
+    fn parse_invalid_output() {
+        let missing_version = parse_credential_process_output(
+            r#"{"AccessKeyId": "AKIDTEST", "SecretAccessKey": "TESTSECRETKEY"}"#,
+        )
+        .expect_err("version is required");
+        assert!(missing_version.to_string().contains("Version"));
+
+        let wrong_version = parse_credential_process_output(
+            r#"{"Version": 2, "AccessKeyId": "AKIDTEST", "SecretAccessKey": "TESTSECRETKEY"}"#,
+        )
+        .expect_err("only version 1 is supported");
+        assert!(wrong_version.to_string().contains("unsupported version"));
+
+        let missing_secret =
+            parse_credential_process_output(r#"{"Version": 1, "AccessKeyId": "AKIDTEST"}"#)
+                .expect_err("secret is required");
+        assert!(missing_secret.to_string().contains("SecretAccessKey"));
+
+        parse_credential_process_output("not json").expect_err("not JSON");
+    }
+
+    #[cfg(not(windows))]
+    #[tokio::test]
+    async fn load_credentials_from_process() {
+        let provider = CredentialProcessProvider::builder()
+            .command(r#"echo '{"Version": 1, "AccessKeyId": "AKIDTEST", "SecretAccessKey": "TESTSECRETKEY"}'"#)
+            .build();
+        let creds = provider.provide_credentials().await.expect("valid creds");
+        assert_eq!("AKIDTEST", creds.access_key_id());
+        assert_eq!("TESTSECRETKEY", creds.secret_access_key());
+    }
+
+    #[cfg(not(windows))]
+    #[tokio::test]
+    async fn failing_process_is_a_provider_error() {
+        let provider = CredentialProcessProvider::builder()
+            .command("echo 'bad things happened' >&2; exit 1")
+            .build();
+        let err = provider.provide_credentials().await.expect_err("exit 1");
+        assert!(
+            matches!(err, CredentialsError::ProviderError { .. }),
+            "{:?}",
+            err
+        );
+        assert!(format!("{:?}", err).contains("bad things happened"));
+    }
+
+    #[cfg(not(windows))]
+    #[tokio::test]
+    async fn invalid_output_is_unhandled() {
+        let provider = CredentialProcessProvider::builder()
+            .command("echo 'not json'")
+            .build();
+        let err = provider
+            .provide_credentials()
+            .await
+            .expect_err("bad output");
+        assert!(
+            matches!(err, CredentialsError::Unhandled { .. }),
+            "{:?}",
+            err
+        );
+    }
+
+    #[cfg(not(windows))]
+    #[tokio::test]
+    async fn slow_process_times_out() {
+        let provider = CredentialProcessProvider::builder()
+            .command("sleep 10")
+            .timeout(Duration::from_millis(100))
+            .configure(&ProviderConfig::no_configuration().with_sleep(TokioSleep::new()))
+            .build();
+        let err = provider.provide_credentials().await.expect_err("timeout");
+        assert!(
+            matches!(err, CredentialsError::ProviderTimedOut(_)),
+            "{:?}",
+            err
+        );
+    }
+}
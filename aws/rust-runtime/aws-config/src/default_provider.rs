@@ -4,7 +4,9 @@
  */
 
 //! Default Provider chains for [`region`](default_provider::region), [`credentials`](default_provider::credentials),
-//! [retries](default_provider::retry_config), [timeouts](default_provider::timeout_config) and [app name](default_provider::app_name).
+//! [retries](default_provider::retry_config), [timeouts](default_provider::timeout_config), [endpoint URLs](default_provider::endpoint_url),
+//! [FIPS and dual-stack endpoints](default_provider::endpoint_variants), [STS regional endpoints](default_provider::sts_regional_endpoints),
+//! [defaults mode](default_provider::defaults_mode) and [app name](default_provider::app_name).
 //!
 //! Typically, this module is used via [`load_from_env`](crate::load_from_env) or [`from_env`](crate::from_env). It should only be used directly
 //! if you need to set custom configuration options to override the default resolution chain.
@@ -27,6 +29,30 @@ pub mod retry_config;
 /// if you need to set custom configuration options to override the default resolution chain.
 pub mod app_name;
 
+/// Default endpoint URL provider chain
+///
+/// Typically, this module is used via [`load_from_env`](crate::load_from_env) or [`from_env`](crate::from_env). It should only be used directly
+/// if you need to set custom configuration options to override the default resolution chain.
+pub mod endpoint_url;
+
+/// Default FIPS and dual-stack endpoint settings provider chain
+///
+/// Typically, this module is used via [`load_from_env`](crate::load_from_env) or [`from_env`](crate::from_env). It should only be used directly
+/// if you need to set custom configuration options to override the default resolution chain.
+pub mod endpoint_variants;
+
+/// Default STS regional endpoints setting provider chain
+///
+/// Typically, this module is used via [`load_from_env`](crate::load_from_env) or [`from_env`](crate::from_env). It should only be used directly
+/// if you need to set custom configuration options to override the default resolution chain.
+pub mod sts_regional_endpoints;
+
+/// Default [defaults mode](crate::defaults_mode::DefaultsMode) provider chain
+///
+/// Typically, this module is used via [`load_from_env`](crate::load_from_env) or [`from_env`](crate::from_env). It should only be used directly
+/// if you need to set custom configuration options to override the default resolution chain.
+pub mod defaults_mode;
+
 /// Default timeout configuration provider chain
 ///
 /// Typically, this module is used via [`load_from_env`](crate::load_from_env) or [`from_env`](crate::from_env). It should only be used directly
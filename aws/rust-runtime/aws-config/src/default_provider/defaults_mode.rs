@@ -0,0 +1,187 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use crate::defaults_mode::DefaultsMode;
+use crate::environment::defaults_mode::EnvironmentVariableDefaultsModeProvider;
+use crate::imds::region::ImdsRegionProvider;
+use crate::profile::defaults_mode;
+use crate::provider_config::ProviderConfig;
+use aws_types::os_shim_internal::Env;
+use aws_types::region::Region;
+
+const ENV_VAR_EXECUTION_ENV: &str = "AWS_EXECUTION_ENV";
+const ENV_VAR_REGION: &str = "AWS_REGION";
+const ENV_VAR_DEFAULT_REGION: &str = "AWS_DEFAULT_REGION";
+
+/// Default defaults mode provider chain
+///
+/// This provider will check the following sources in order:
+/// 1. [Environment variables](EnvironmentVariableDefaultsModeProvider)
+/// 2. [Profile file](crate::profile::defaults_mode::ProfileFileDefaultsModeProvider)
+pub fn default_provider() -> Builder {
+    Builder::default()
+}
+
+/// Default provider builder for the defaults mode
+#[derive(Default)]
+pub struct Builder {
+    env_provider: EnvironmentVariableDefaultsModeProvider,
+    profile_file: defaults_mode::Builder,
+}
+
+impl Builder {
+    #[doc(hidden)]
+    /// Configure the default chain
+    ///
+    /// Exposed for overriding the environment when unit-testing providers
+    pub fn configure(mut self, configuration: &ProviderConfig) -> Self {
+        self.env_provider =
+            EnvironmentVariableDefaultsModeProvider::new_with_env(configuration.env());
+        self.profile_file = self.profile_file.configure(configuration);
+        self
+    }
+
+    /// Override the profile name used by this provider
+    pub fn profile_name(mut self, name: &str) -> Self {
+        self.profile_file = self.profile_file.profile_name(name);
+        self
+    }
+
+    /// Resolve the defaults mode from the default chain
+    pub async fn defaults_mode(self) -> Option<DefaultsMode> {
+        match self.env_provider.defaults_mode() {
+            Some(mode) => Some(mode),
+            None => self.profile_file.build().defaults_mode().await,
+        }
+    }
+}
+
+/// Resolves [`DefaultsMode::Auto`] to a concrete mode for a client in `region`
+///
+/// Any other mode is returned as is. `Auto` resolves to:
+/// 1. `Mobile` on iOS and Android.
+/// 2. `InRegion` or `CrossRegion` when running in an AWS execution environment (`AWS_EXECUTION_ENV`
+///    is set), depending on whether `AWS_REGION` or `AWS_DEFAULT_REGION` matches `region`.
+/// 3. `InRegion` or `CrossRegion` depending on whether the region reported by the EC2 instance
+///    metadata service matches `region`. This is skipped if IMDS is disabled.
+/// 4. `Standard` otherwise, including when `region` is not known.
+pub(crate) async fn resolve_auto(
+    mode: DefaultsMode,
+    region: Option<&Region>,
+    configuration: &ProviderConfig,
+) -> DefaultsMode {
+    if mode != DefaultsMode::Auto {
+        return mode;
+    }
+    if cfg!(any(target_os = "ios", target_os = "android")) {
+        return DefaultsMode::Mobile;
+    }
+    let region = match region {
+        Some(region) => region,
+        None => return DefaultsMode::Standard,
+    };
+    let env = configuration.env();
+    let execution_region = match execution_env_region(&env) {
+        Some(execution_region) => Some(execution_region),
+        None => {
+            ImdsRegionProvider::builder()
+                .configure(configuration)
+                .build()
+                .region()
+                .await
+        }
+    };
+    let resolved = match execution_region {
+        Some(execution_region) if &execution_region == region => DefaultsMode::InRegion,
+        Some(_) => DefaultsMode::CrossRegion,
+        None => DefaultsMode::Standard,
+    };
+    tracing::debug!(mode = resolved.as_str(), "resolved `auto` defaults mode");
+    resolved
+}
+
+/// The region of the AWS execution environment (e.g. Lambda) the application runs in, if any
+fn execution_env_region(env: &Env) -> Option<Region> {
+    env.get(ENV_VAR_EXECUTION_ENV).ok()?;
+    env.get(ENV_VAR_REGION)
+        .or_else(|_| env.get(ENV_VAR_DEFAULT_REGION))
+        .ok()
+        .map(Region::new)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider_config::ProviderConfig;
+    use crate::test_case::no_traffic_connector;
+    use aws_types::os_shim_internal::{Env, Fs};
+
+    fn provider_config(env: &[(&str, &str)]) -> ProviderConfig {
+        ProviderConfig::no_configuration()
+            .with_fs(Fs::from_slice(&[]))
+            .with_env(Env::from_slice(env))
+            .with_http_connector(no_traffic_connector())
+    }
+
+    #[tokio::test]
+    async fn prefer_env_to_profile() {
+        let fs = Fs::from_slice(&[("test_config", "[default]\ndefaults_mode = mobile")]);
+        let env = Env::from_slice(&[
+            ("AWS_CONFIG_FILE", "test_config"),
+            ("AWS_DEFAULTS_MODE", "standard"),
+        ]);
+        let provider = Builder::default().configure(
+            &ProviderConfig::no_configuration()
+                .with_fs(fs)
+                .with_env(env)
+                .with_http_connector(no_traffic_connector()),
+        );
+
+        assert_eq!(Some(DefaultsMode::Standard), provider.defaults_mode().await);
+    }
+
+    #[tokio::test]
+    async fn concrete_modes_are_not_changed() {
+        let conf = provider_config(&[]);
+        let region = Region::new("us-east-1");
+        assert_eq!(
+            DefaultsMode::Legacy,
+            resolve_auto(DefaultsMode::Legacy, Some(&region), &conf).await
+        );
+        assert_eq!(
+            DefaultsMode::CrossRegion,
+            resolve_auto(DefaultsMode::CrossRegion, Some(&region), &conf).await
+        );
+    }
+
+    #[tokio::test]
+    async fn auto_in_execution_environment() {
+        let conf = provider_config(&[
+            ("AWS_EXECUTION_ENV", "AWS_Lambda_provided.al2"),
+            ("AWS_REGION", "us-west-2"),
+        ]);
+        assert_eq!(
+            DefaultsMode::InRegion,
+            resolve_auto(DefaultsMode::Auto, Some(&Region::new("us-west-2")), &conf).await
+        );
+        assert_eq!(
+            DefaultsMode::CrossRegion,
+            resolve_auto(DefaultsMode::Auto, Some(&Region::new("eu-west-1")), &conf).await
+        );
+    }
+
+    #[tokio::test]
+    async fn auto_without_region_or_imds() {
+        let conf = provider_config(&[("AWS_EC2_METADATA_DISABLED", "true")]);
+        assert_eq!(
+            DefaultsMode::Standard,
+            resolve_auto(DefaultsMode::Auto, Some(&Region::new("us-west-2")), &conf).await
+        );
+        assert_eq!(
+            DefaultsMode::Standard,
+            resolve_auto(DefaultsMode::Auto, None, &conf).await
+        );
+    }
+}
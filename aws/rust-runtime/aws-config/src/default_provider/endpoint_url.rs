@@ -0,0 +1,108 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use crate::environment::endpoint_url::EnvironmentVariableEndpointUrlProvider;
+use crate::profile::endpoint_url;
+use crate::provider_config::ProviderConfig;
+use http::Uri;
+
+/// Default Endpoint URL Provider chain
+///
+/// The endpoint URL used for all services is loaded from the following sources in order:
+/// 1. [Environment variables](EnvironmentVariableEndpointUrlProvider) (`AWS_ENDPOINT_URL`)
+/// 2. [Profile file](crate::profile::endpoint_url::ProfileFileEndpointUrlProvider) (`endpoint_url`)
+///
+/// Service-specific endpoint URLs are loaded from `AWS_ENDPOINT_URL_<SERVICE>` environment variables
+/// and take precedence over the endpoint URL used for all services.
+pub fn default_provider() -> Builder {
+    Builder::default()
+}
+
+/// Default provider builder for endpoint URLs
+#[derive(Default)]
+pub struct Builder {
+    env_provider: EnvironmentVariableEndpointUrlProvider,
+    profile_file: endpoint_url::Builder,
+}
+
+impl Builder {
+    #[doc(hidden)]
+    /// Configure the default chain
+    ///
+    /// Exposed for overriding the environment when unit-testing providers
+    pub fn configure(mut self, configuration: &ProviderConfig) -> Self {
+        self.env_provider =
+            EnvironmentVariableEndpointUrlProvider::new_with_env(configuration.env());
+        self.profile_file = self.profile_file.configure(configuration);
+        self
+    }
+
+    /// Override the profile name used by this provider
+    pub fn profile_name(mut self, name: &str) -> Self {
+        self.profile_file = self.profile_file.profile_name(name);
+        self
+    }
+
+    /// Load the endpoint URL used for all services from the default chain
+    pub async fn endpoint_url(&self) -> Option<Uri> {
+        match self.env_provider.endpoint_url() {
+            Some(endpoint_url) => Some(endpoint_url),
+            None => self.profile_file.clone().build().endpoint_url().await,
+        }
+    }
+
+    /// Load service-specific endpoint URLs as `(SERVICE, endpoint URL)` pairs
+    pub fn service_endpoint_urls(&self) -> Vec<(String, Uri)> {
+        self.env_provider.service_endpoint_urls()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider_config::ProviderConfig;
+    use crate::test_case::no_traffic_connector;
+    use aws_types::os_shim_internal::{Env, Fs};
+
+    fn provider(fs: Fs, env: Env) -> Builder {
+        Builder::default().configure(
+            &ProviderConfig::no_configuration()
+                .with_fs(fs)
+                .with_env(env)
+                .with_http_connector(no_traffic_connector()),
+        )
+    }
+
+    #[tokio::test]
+    async fn prefer_env_to_profile() {
+        let fs = Fs::from_slice(&[("test_config", "[default]\nendpoint_url = http://wrong")]);
+        let env = Env::from_slice(&[
+            ("AWS_CONFIG_FILE", "test_config"),
+            ("AWS_ENDPOINT_URL", "http://correct"),
+        ]);
+        assert_eq!(
+            Some(Uri::from_static("http://correct")),
+            provider(fs, env).endpoint_url().await
+        );
+    }
+
+    #[tokio::test]
+    async fn load_from_profile() {
+        let fs = Fs::from_slice(&[("test_config", "[default]\nendpoint_url = http://correct")]);
+        let env = Env::from_slice(&[
+            ("AWS_CONFIG_FILE", "test_config"),
+            ("AWS_ENDPOINT_URL_S3", "http://s3"),
+        ]);
+        let provider = provider(fs, env);
+        assert_eq!(
+            Some(Uri::from_static("http://correct")),
+            provider.endpoint_url().await
+        );
+        assert_eq!(
+            vec![("S3".to_string(), Uri::from_static("http://s3"))],
+            provider.service_endpoint_urls()
+        );
+    }
+}
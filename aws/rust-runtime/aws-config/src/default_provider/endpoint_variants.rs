@@ -0,0 +1,94 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use crate::environment::endpoint_variants::EnvironmentVariableEndpointVariantsProvider;
+use crate::profile::endpoint_variants::{self, ProfileFileEndpointVariantsProvider};
+use crate::provider_config::ProviderConfig;
+
+/// Default FIPS and dual-stack endpoint settings provider chain
+///
+/// This provider will check the following sources in order:
+/// 1. [Environment variables](EnvironmentVariableEndpointVariantsProvider)
+/// 2. [Profile file](crate::profile::endpoint_variants::ProfileFileEndpointVariantsProvider)
+///
+/// Each setting is resolved separately, so FIPS may be enabled by an environment variable while
+/// dual-stack is enabled by the profile.
+pub fn default_provider() -> Builder {
+    Builder::default()
+}
+
+/// Default provider builder for the FIPS and dual-stack endpoint settings
+#[derive(Default)]
+pub struct Builder {
+    env_provider: EnvironmentVariableEndpointVariantsProvider,
+    profile_file: endpoint_variants::Builder,
+    profile_provider: ProfileFileEndpointVariantsProvider,
+}
+
+impl Builder {
+    #[doc(hidden)]
+    /// Configure the default chain
+    ///
+    /// Exposed for overriding the environment when unit-testing providers
+    pub fn configure(mut self, configuration: &ProviderConfig) -> Self {
+        self.env_provider =
+            EnvironmentVariableEndpointVariantsProvider::new_with_env(configuration.env());
+        self.profile_file = self.profile_file.configure(configuration);
+        self.profile_provider = self.profile_file.clone().build();
+        self
+    }
+
+    /// Override the profile name used by this provider
+    pub fn profile_name(mut self, name: &str) -> Self {
+        self.profile_file = self.profile_file.profile_name(name);
+        self.profile_provider = self.profile_file.clone().build();
+        self
+    }
+
+    /// Resolve whether to use FIPS endpoints from the default chain
+    pub async fn use_fips(&self) -> Option<bool> {
+        match self.env_provider.use_fips() {
+            Some(use_fips) => Some(use_fips),
+            None => self.profile_provider.use_fips().await,
+        }
+    }
+
+    /// Resolve whether to use dual-stack endpoints from the default chain
+    pub async fn use_dual_stack(&self) -> Option<bool> {
+        match self.env_provider.use_dual_stack() {
+            Some(use_dual_stack) => Some(use_dual_stack),
+            None => self.profile_provider.use_dual_stack().await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider_config::ProviderConfig;
+    use crate::test_case::no_traffic_connector;
+    use aws_types::os_shim_internal::{Env, Fs};
+
+    #[tokio::test]
+    async fn prefer_env_to_profile() {
+        let fs = Fs::from_slice(&[(
+            "test_config",
+            "[default]\nuse_fips_endpoint = false\nuse_dualstack_endpoint = true",
+        )]);
+        let env = Env::from_slice(&[
+            ("AWS_CONFIG_FILE", "test_config"),
+            ("AWS_USE_FIPS_ENDPOINT", "true"),
+        ]);
+        let provider = Builder::default().configure(
+            &ProviderConfig::no_configuration()
+                .with_fs(fs)
+                .with_env(env)
+                .with_http_connector(no_traffic_connector()),
+        );
+
+        assert_eq!(Some(true), provider.use_fips().await);
+        assert_eq!(Some(true), provider.use_dual_stack().await);
+    }
+}
@@ -5,6 +5,7 @@
 
 use aws_smithy_types::retry::RetryConfig;
 
+use crate::defaults_mode::DefaultsMode;
 use crate::environment::retry_config::EnvironmentVariableRetryConfigProvider;
 use crate::profile;
 use crate::provider_config::ProviderConfig;
@@ -54,6 +55,7 @@ pub fn default_provider() -> Builder {
 pub struct Builder {
     env_provider: EnvironmentVariableRetryConfigProvider,
     profile_file: profile::retry_config::Builder,
+    defaults_mode: DefaultsMode,
 }
 
 impl Builder {
@@ -73,10 +75,18 @@ impl Builder {
         self
     }
 
+    /// Set the [`DefaultsMode`] whose retry settings are used when neither the environment nor
+    /// the profile file configures them
+    pub fn defaults_mode(mut self, defaults_mode: DefaultsMode) -> Self {
+        self.defaults_mode = defaults_mode;
+        self
+    }
+
     /// Attempt to create a [RetryConfig](aws_smithy_types::retry::RetryConfig) from following sources in order:
     /// 1. [Environment variables](crate::environment::retry_config::EnvironmentVariableRetryConfigProvider)
     /// 2. [Profile file](crate::profile::retry_config::ProfileFileRetryConfigProvider)
-    /// 3. [RetryConfig::default()](aws_smithy_types::retry::RetryConfig::default)
+    /// 3. The [defaults mode](Self::defaults_mode)
+    /// 4. [RetryConfig::default()](aws_smithy_types::retry::RetryConfig::default)
     ///
     /// Precedence is considered on a per-field basis
     ///
@@ -99,6 +109,7 @@ impl Builder {
 
         builder_from_env
             .take_unset_from(builder_from_profile)
+            .take_unset_from(self.defaults_mode.retry_config_builder())
             .build()
     }
 }
@@ -0,0 +1,80 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use crate::environment::sts_regional_endpoints::EnvironmentVariableStsRegionalEndpointsProvider;
+use crate::profile::sts_regional_endpoints;
+use crate::provider_config::ProviderConfig;
+use aws_types::endpoint::StsRegionalEndpoints;
+
+/// Default STS regional endpoints setting provider chain
+///
+/// This provider will check the following sources in order:
+/// 1. [Environment variables](EnvironmentVariableStsRegionalEndpointsProvider)
+/// 2. [Profile file](crate::profile::sts_regional_endpoints::ProfileFileStsRegionalEndpointsProvider)
+pub fn default_provider() -> Builder {
+    Builder::default()
+}
+
+/// Default provider builder for the STS regional endpoints setting
+#[derive(Default)]
+pub struct Builder {
+    env_provider: EnvironmentVariableStsRegionalEndpointsProvider,
+    profile_file: sts_regional_endpoints::Builder,
+}
+
+impl Builder {
+    #[doc(hidden)]
+    /// Configure the default chain
+    ///
+    /// Exposed for overriding the environment when unit-testing providers
+    pub fn configure(mut self, configuration: &ProviderConfig) -> Self {
+        self.env_provider =
+            EnvironmentVariableStsRegionalEndpointsProvider::new_with_env(configuration.env());
+        self.profile_file = self.profile_file.configure(configuration);
+        self
+    }
+
+    /// Override the profile name used by this provider
+    pub fn profile_name(mut self, name: &str) -> Self {
+        self.profile_file = self.profile_file.profile_name(name);
+        self
+    }
+
+    /// Resolve the STS regional endpoints setting from the default chain
+    pub async fn sts_regional_endpoints(self) -> Option<StsRegionalEndpoints> {
+        match self.env_provider.sts_regional_endpoints() {
+            Some(setting) => Some(setting),
+            None => self.profile_file.build().sts_regional_endpoints().await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider_config::ProviderConfig;
+    use crate::test_case::no_traffic_connector;
+    use aws_types::os_shim_internal::{Env, Fs};
+
+    #[tokio::test]
+    async fn prefer_env_to_profile() {
+        let fs = Fs::from_slice(&[("test_config", "[default]\nsts_regional_endpoints = legacy")]);
+        let env = Env::from_slice(&[
+            ("AWS_CONFIG_FILE", "test_config"),
+            ("AWS_STS_REGIONAL_ENDPOINTS", "regional"),
+        ]);
+        let provider = Builder::default().configure(
+            &ProviderConfig::no_configuration()
+                .with_fs(fs)
+                .with_env(env)
+                .with_http_connector(no_traffic_connector()),
+        );
+
+        assert_eq!(
+            Some(StsRegionalEndpoints::Regional),
+            provider.sts_regional_endpoints().await
+        );
+    }
+}
@@ -5,6 +5,7 @@
 
 use aws_smithy_types::timeout;
 
+use crate::defaults_mode::DefaultsMode;
 use crate::environment::timeout_config::EnvironmentVariableTimeoutConfigProvider;
 use crate::profile;
 use crate::provider_config::ProviderConfig;
@@ -49,6 +50,7 @@ pub fn default_provider() -> Builder {
 pub struct Builder {
     env_provider: EnvironmentVariableTimeoutConfigProvider,
     profile_file: profile::timeout_config::Builder,
+    defaults_mode: DefaultsMode,
 }
 
 impl Builder {
@@ -68,9 +70,17 @@ impl Builder {
         self
     }
 
+    /// Set the [`DefaultsMode`] whose timeouts are used when neither the environment nor the
+    /// profile file configures them
+    pub fn defaults_mode(mut self, defaults_mode: DefaultsMode) -> Self {
+        self.defaults_mode = defaults_mode;
+        self
+    }
+
     /// Attempt to create a [`timeout::Config`](aws_smithy_types::timeout::Config) from following sources in order:
     /// 1. [Environment variables](crate::environment::timeout_config::EnvironmentVariableTimeoutConfigProvider)
     /// 2. [Profile file](crate::profile::timeout_config::ProfileFileTimeoutConfigProvider)
+    /// 3. The [defaults mode](Self::defaults_mode)
     ///
     /// Precedence is considered on a per-field basis. If no timeout is specified, requests will never time out.
     ///
@@ -92,6 +102,8 @@ impl Builder {
             Err(err) => panic!("{}", err),
         };
 
-        builder_from_env.take_unset_from(builder_from_profile)
+        builder_from_env
+            .take_unset_from(builder_from_profile)
+            .take_unset_from(self.defaults_mode.timeout_config())
     }
 }
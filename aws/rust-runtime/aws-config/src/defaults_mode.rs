@@ -0,0 +1,235 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Defaults modes: bundles of default settings that are tuned for the environment an application runs in.
+//!
+//! A defaults mode is selected with the `AWS_DEFAULTS_MODE` environment variable, the `defaults_mode`
+//! profile key, or [`ConfigLoader::defaults_mode`](crate::ConfigLoader::defaults_mode). The settings
+//! a mode provides have the lowest precedence: any value configured explicitly, in the environment,
+//! or in the profile file takes precedence over the value provided by the defaults mode.
+
+use aws_smithy_types::retry::{RetryConfigBuilder, RetryMode};
+use aws_smithy_types::timeout;
+use aws_smithy_types::tristate::TriState;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+use std::time::Duration;
+
+/// A bundle of default settings, tuned for the environment an application runs in
+///
+/// The settings each mode provides are:
+///
+/// | Mode           | Retry mode | Connect timeout | TLS negotiation timeout |
+/// |----------------|------------|-----------------|-------------------------|
+/// | `legacy`       | -          | -               | -                       |
+/// | `standard`     | standard   | 3.1 seconds     | 3.1 seconds             |
+/// | `in-region`    | standard   | 1.1 seconds     | 1.1 seconds             |
+/// | `cross-region` | standard   | 3.1 seconds     | 3.1 seconds             |
+/// | `mobile`       | standard   | 30 seconds      | 30 seconds              |
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DefaultsMode {
+    /// Provides no defaults beyond those the SDK has always used
+    Legacy,
+    /// Provides the recommended defaults that are safe to use in most scenarios
+    Standard,
+    /// Optimized for applications that call AWS services in the same region they run in
+    InRegion,
+    /// Optimized for applications that call AWS services in a different region than they run in
+    CrossRegion,
+    /// Optimized for mobile applications, with more tolerance for high latency networks
+    Mobile,
+    /// Picks between [`InRegion`](DefaultsMode::InRegion), [`CrossRegion`](DefaultsMode::CrossRegion),
+    /// [`Mobile`](DefaultsMode::Mobile) and [`Standard`](DefaultsMode::Standard) based on the
+    /// environment the application runs in
+    ///
+    /// Resolving this mode may require a call to the EC2 instance metadata service.
+    Auto,
+}
+
+// `#[default]` on enum variants requires Rust 1.62, which is newer than the MSRV
+#[allow(clippy::derivable_impls)]
+impl Default for DefaultsMode {
+    fn default() -> Self {
+        DefaultsMode::Legacy
+    }
+}
+
+impl DefaultsMode {
+    /// Returns the name of this mode as it is written in the environment and profile file
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DefaultsMode::Legacy => "legacy",
+            DefaultsMode::Standard => "standard",
+            DefaultsMode::InRegion => "in-region",
+            DefaultsMode::CrossRegion => "cross-region",
+            DefaultsMode::Mobile => "mobile",
+            DefaultsMode::Auto => "auto",
+        }
+    }
+
+    /// The default retry mode of this mode, if it sets one
+    pub fn retry_mode(&self) -> Option<RetryMode> {
+        match self {
+            DefaultsMode::Legacy | DefaultsMode::Auto => None,
+            _ => Some(RetryMode::Standard),
+        }
+    }
+
+    /// The default connect timeout of this mode, if it sets one
+    pub fn connect_timeout(&self) -> Option<Duration> {
+        match self {
+            DefaultsMode::Legacy | DefaultsMode::Auto => None,
+            DefaultsMode::InRegion => Some(Duration::from_millis(1100)),
+            DefaultsMode::Standard | DefaultsMode::CrossRegion => Some(Duration::from_millis(3100)),
+            DefaultsMode::Mobile => Some(Duration::from_millis(30000)),
+        }
+    }
+
+    /// The default TLS negotiation timeout of this mode, if it sets one
+    pub fn tls_negotiation_timeout(&self) -> Option<Duration> {
+        // every mode currently uses the same value for both timeouts
+        self.connect_timeout()
+    }
+
+    /// The retry settings of this mode
+    ///
+    /// `Auto` must be resolved to a concrete mode before its settings are known, so it provides none.
+    pub fn retry_config_builder(&self) -> RetryConfigBuilder {
+        let mut builder = RetryConfigBuilder::new();
+        builder.set_mode(self.retry_mode());
+        builder
+    }
+
+    /// The timeout settings of this mode
+    ///
+    /// `Auto` must be resolved to a concrete mode before its settings are known, so it provides none.
+    pub fn timeout_config(&self) -> timeout::Config {
+        let connect_timeout = match self.connect_timeout() {
+            Some(timeout) => TriState::Set(timeout),
+            None => TriState::Unset,
+        };
+        let tls_negotiation_timeout = match self.tls_negotiation_timeout() {
+            Some(timeout) => TriState::Set(timeout),
+            None => TriState::Unset,
+        };
+        timeout::Config::new().with_http_timeouts(
+            timeout::Http::new()
+                .with_connect_timeout(connect_timeout)
+                .with_tls_negotiation_timeout(tls_negotiation_timeout),
+        )
+    }
+}
+
+const VALID_DEFAULTS_MODES: &[&str] = &[
+    "legacy",
+    "standard",
+    "in-region",
+    "cross-region",
+    "mobile",
+    "auto",
+];
+
+/// Failure to parse a [`DefaultsMode`] from a string
+#[derive(Debug)]
+pub struct DefaultsModeParseErr(String);
+
+impl Display for DefaultsModeParseErr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "error parsing string '{}' as DefaultsMode, valid options are: {:#?}",
+            self.0, VALID_DEFAULTS_MODES
+        )
+    }
+}
+
+impl Error for DefaultsModeParseErr {}
+
+impl FromStr for DefaultsMode {
+    type Err = DefaultsModeParseErr;
+
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        let string = string.trim();
+        [
+            DefaultsMode::Legacy,
+            DefaultsMode::Standard,
+            DefaultsMode::InRegion,
+            DefaultsMode::CrossRegion,
+            DefaultsMode::Mobile,
+            DefaultsMode::Auto,
+        ]
+        .into_iter()
+        .find(|mode| string.eq_ignore_ascii_case(mode.as_str()))
+        .ok_or_else(|| DefaultsModeParseErr(string.to_owned()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::DefaultsMode;
+    use aws_smithy_types::retry::RetryMode;
+    use aws_smithy_types::tristate::TriState;
+    use std::time::Duration;
+
+    #[test]
+    fn parse_defaults_mode() {
+        assert_eq!(Ok(DefaultsMode::Legacy), "legacy".parse().map_err(|_| ()));
+        assert_eq!(
+            Ok(DefaultsMode::InRegion),
+            " In-Region ".parse().map_err(|_| ())
+        );
+        assert_eq!(
+            Ok(DefaultsMode::CrossRegion),
+            "CROSS-REGION".parse().map_err(|_| ())
+        );
+        assert_eq!(Ok(DefaultsMode::Auto), "auto".parse().map_err(|_| ()));
+        let err = "in_region"
+            .parse::<DefaultsMode>()
+            .expect_err("invalid mode");
+        assert!(format!("{}", err).contains("in-region"));
+    }
+
+    #[test]
+    fn legacy_sets_nothing() {
+        let mode = DefaultsMode::Legacy;
+        assert_eq!(None, mode.retry_mode());
+        assert!(!mode.timeout_config().has_timeouts());
+    }
+
+    #[test]
+    fn mode_settings() {
+        let connect_timeout =
+            |mode: DefaultsMode| mode.timeout_config().http_timeouts().connect_timeout();
+        assert_eq!(
+            TriState::Set(Duration::from_millis(3100)),
+            connect_timeout(DefaultsMode::Standard)
+        );
+        assert_eq!(
+            TriState::Set(Duration::from_millis(1100)),
+            connect_timeout(DefaultsMode::InRegion)
+        );
+        assert_eq!(
+            TriState::Set(Duration::from_millis(3100)),
+            connect_timeout(DefaultsMode::CrossRegion)
+        );
+        assert_eq!(
+            TriState::Set(Duration::from_secs(30)),
+            connect_timeout(DefaultsMode::Mobile)
+        );
+        assert_eq!(
+            TriState::Set(Duration::from_millis(1100)),
+            DefaultsMode::InRegion
+                .timeout_config()
+                .http_timeouts()
+                .tls_negotiation_timeout()
+        );
+        assert_eq!(
+            RetryMode::Standard,
+            DefaultsMode::Mobile.retry_config_builder().build().mode()
+        );
+    }
+}
@@ -0,0 +1,74 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use crate::defaults_mode::DefaultsMode;
+use aws_types::os_shim_internal::Env;
+
+const ENV_VAR_DEFAULTS_MODE: &str = "AWS_DEFAULTS_MODE";
+
+/// Load the defaults mode from the `AWS_DEFAULTS_MODE` environment variable
+#[derive(Debug, Default)]
+pub struct EnvironmentVariableDefaultsModeProvider {
+    env: Env,
+}
+
+impl EnvironmentVariableDefaultsModeProvider {
+    /// Create a new `EnvironmentVariableDefaultsModeProvider`
+    pub fn new() -> Self {
+        Self { env: Env::real() }
+    }
+
+    #[doc(hidden)]
+    /// Create a defaults mode provider from a given `Env`
+    ///
+    /// This method is used for tests that need to override environment variables.
+    pub fn new_with_env(env: Env) -> Self {
+        Self { env }
+    }
+
+    /// Attempts to load the defaults mode from `AWS_DEFAULTS_MODE`
+    ///
+    /// Invalid values are logged and ignored.
+    pub fn defaults_mode(&self) -> Option<DefaultsMode> {
+        let value = self.env.get(ENV_VAR_DEFAULTS_MODE).ok()?;
+        value
+            .parse()
+            .map_err(
+                |err| tracing::warn!(err = %err, "invalid value for `{}`", ENV_VAR_DEFAULTS_MODE),
+            )
+            .ok()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::defaults_mode::DefaultsMode;
+    use crate::environment::EnvironmentVariableDefaultsModeProvider;
+    use aws_types::os_shim_internal::Env;
+    use tracing_test::traced_test;
+
+    fn provider(vars: &[(&str, &str)]) -> EnvironmentVariableDefaultsModeProvider {
+        EnvironmentVariableDefaultsModeProvider::new_with_env(Env::from_slice(vars))
+    }
+
+    #[test]
+    fn not_set() {
+        assert_eq!(None, provider(&[]).defaults_mode());
+    }
+
+    #[test]
+    fn set() {
+        let provider = provider(&[("AWS_DEFAULTS_MODE", "in-region")]);
+        assert_eq!(Some(DefaultsMode::InRegion), provider.defaults_mode());
+    }
+
+    #[traced_test]
+    #[test]
+    fn invalid_value() {
+        let provider = provider(&[("AWS_DEFAULTS_MODE", "fast")]);
+        assert_eq!(None, provider.defaults_mode());
+        assert!(logs_contain("invalid value for `AWS_DEFAULTS_MODE`"));
+    }
+}
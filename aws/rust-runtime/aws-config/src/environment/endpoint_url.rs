@@ -0,0 +1,122 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use aws_types::os_shim_internal::Env;
+use http::Uri;
+
+const ENV_VAR_ENDPOINT_URL: &str = "AWS_ENDPOINT_URL";
+const ENV_VAR_SERVICE_ENDPOINT_URL_PREFIX: &str = "AWS_ENDPOINT_URL_";
+
+/// Load endpoint URLs from the `AWS_ENDPOINT_URL` and `AWS_ENDPOINT_URL_<SERVICE>` environment variables.
+///
+/// `<SERVICE>` is the service's SDK ID in upper case with spaces replaced by underscores,
+/// e.g. `AWS_ENDPOINT_URL_DYNAMODB` or `AWS_ENDPOINT_URL_SSO_OIDC`.
+#[derive(Debug, Default)]
+pub struct EnvironmentVariableEndpointUrlProvider {
+    env: Env,
+}
+
+impl EnvironmentVariableEndpointUrlProvider {
+    /// Create a new `EnvironmentVariableEndpointUrlProvider`
+    pub fn new() -> Self {
+        Self { env: Env::real() }
+    }
+
+    #[doc(hidden)]
+    /// Create an endpoint URL provider from a given `Env`
+    ///
+    /// This method is used for tests that need to override environment variables.
+    pub fn new_with_env(env: Env) -> Self {
+        Self { env }
+    }
+
+    /// Attempts to load the endpoint URL used for all services from `AWS_ENDPOINT_URL`
+    pub fn endpoint_url(&self) -> Option<Uri> {
+        self.env
+            .get(ENV_VAR_ENDPOINT_URL)
+            .ok()
+            .and_then(|url| parse_endpoint_url(ENV_VAR_ENDPOINT_URL, &url))
+    }
+
+    /// Loads service-specific endpoint URLs from `AWS_ENDPOINT_URL_<SERVICE>`
+    ///
+    /// Returns a list of `(SERVICE, endpoint URL)` pairs.
+    pub fn service_endpoint_urls(&self) -> Vec<(String, Uri)> {
+        self.env
+            .vars_with_prefix(ENV_VAR_SERVICE_ENDPOINT_URL_PREFIX)
+            .into_iter()
+            .filter_map(|(name, url)| {
+                let service = name.strip_prefix(ENV_VAR_SERVICE_ENDPOINT_URL_PREFIX)?;
+                if service.is_empty() {
+                    return None;
+                }
+                let url = parse_endpoint_url(&name, &url)?;
+                Some((service.to_string(), url))
+            })
+            .collect()
+    }
+}
+
+/// Parse an endpoint URL, logging a warning if it is invalid
+pub(crate) fn parse_endpoint_url(source: &str, url: &str) -> Option<Uri> {
+    match url.parse::<Uri>() {
+        Ok(uri) if uri.scheme().is_some() && uri.authority().is_some() => Some(uri),
+        Ok(_) => {
+            tracing::warn!(
+                "endpoint URL `{}` from `{}` was invalid: it must include a scheme and host",
+                url,
+                source
+            );
+            None
+        }
+        Err(err) => {
+            tracing::warn!(err = %err, "endpoint URL `{}` from `{}` was invalid", url, source);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::environment::EnvironmentVariableEndpointUrlProvider;
+    use aws_types::os_shim_internal::Env;
+    use http::Uri;
+
+    #[test]
+    fn env_var_not_set() {
+        let provider = EnvironmentVariableEndpointUrlProvider::new_with_env(Env::from_slice(&[]));
+        assert_eq!(None, provider.endpoint_url());
+        assert!(provider.service_endpoint_urls().is_empty());
+    }
+
+    #[test]
+    fn global_and_service_endpoint_urls() {
+        let provider = EnvironmentVariableEndpointUrlProvider::new_with_env(Env::from_slice(&[
+            ("AWS_ENDPOINT_URL", "http://localhost:4566"),
+            ("AWS_ENDPOINT_URL_DYNAMODB", "http://localhost:8000"),
+        ]));
+        assert_eq!(
+            Some(Uri::from_static("http://localhost:4566")),
+            provider.endpoint_url()
+        );
+        assert_eq!(
+            vec![(
+                "DYNAMODB".to_string(),
+                Uri::from_static("http://localhost:8000")
+            )],
+            provider.service_endpoint_urls()
+        );
+    }
+
+    #[test]
+    fn invalid_endpoint_urls_are_ignored() {
+        let provider = EnvironmentVariableEndpointUrlProvider::new_with_env(Env::from_slice(&[
+            ("AWS_ENDPOINT_URL", "localhost"),
+            ("AWS_ENDPOINT_URL_S3", "not a url"),
+        ]));
+        assert_eq!(None, provider.endpoint_url());
+        assert!(provider.service_endpoint_urls().is_empty());
+    }
+}
@@ -0,0 +1,99 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use aws_types::os_shim_internal::Env;
+
+const ENV_VAR_USE_FIPS: &str = "AWS_USE_FIPS_ENDPOINT";
+const ENV_VAR_USE_DUAL_STACK: &str = "AWS_USE_DUALSTACK_ENDPOINT";
+
+/// Load the FIPS and dual-stack endpoint settings from the `AWS_USE_FIPS_ENDPOINT` and
+/// `AWS_USE_DUALSTACK_ENDPOINT` environment variables.
+#[derive(Debug, Default)]
+pub struct EnvironmentVariableEndpointVariantsProvider {
+    env: Env,
+}
+
+impl EnvironmentVariableEndpointVariantsProvider {
+    /// Create a new `EnvironmentVariableEndpointVariantsProvider`
+    pub fn new() -> Self {
+        Self { env: Env::real() }
+    }
+
+    #[doc(hidden)]
+    /// Create an endpoint variants provider from a given `Env`
+    ///
+    /// This method is used for tests that need to override environment variables.
+    pub fn new_with_env(env: Env) -> Self {
+        Self { env }
+    }
+
+    /// Attempts to load whether to use FIPS endpoints from `AWS_USE_FIPS_ENDPOINT`
+    pub fn use_fips(&self) -> Option<bool> {
+        self.env
+            .get(ENV_VAR_USE_FIPS)
+            .ok()
+            .and_then(|value| parse_bool(ENV_VAR_USE_FIPS, &value))
+    }
+
+    /// Attempts to load whether to use dual-stack endpoints from `AWS_USE_DUALSTACK_ENDPOINT`
+    pub fn use_dual_stack(&self) -> Option<bool> {
+        self.env
+            .get(ENV_VAR_USE_DUAL_STACK)
+            .ok()
+            .and_then(|value| parse_bool(ENV_VAR_USE_DUAL_STACK, &value))
+    }
+}
+
+/// Parse `true` or `false` (ignoring case), logging a warning if `value` is neither
+pub(crate) fn parse_bool(source: &str, value: &str) -> Option<bool> {
+    if value.eq_ignore_ascii_case("true") {
+        Some(true)
+    } else if value.eq_ignore_ascii_case("false") {
+        Some(false)
+    } else {
+        tracing::warn!(
+            "`{}` from `{}` was invalid: expected `true` or `false`",
+            value,
+            source
+        );
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::environment::EnvironmentVariableEndpointVariantsProvider;
+    use aws_types::os_shim_internal::Env;
+    use tracing_test::traced_test;
+
+    fn provider(vars: &[(&str, &str)]) -> EnvironmentVariableEndpointVariantsProvider {
+        EnvironmentVariableEndpointVariantsProvider::new_with_env(Env::from_slice(vars))
+    }
+
+    #[test]
+    fn not_set() {
+        let provider = provider(&[]);
+        assert_eq!(None, provider.use_fips());
+        assert_eq!(None, provider.use_dual_stack());
+    }
+
+    #[test]
+    fn set() {
+        let provider = provider(&[
+            ("AWS_USE_FIPS_ENDPOINT", "TRUE"),
+            ("AWS_USE_DUALSTACK_ENDPOINT", "false"),
+        ]);
+        assert_eq!(Some(true), provider.use_fips());
+        assert_eq!(Some(false), provider.use_dual_stack());
+    }
+
+    #[traced_test]
+    #[test]
+    fn invalid_value() {
+        let provider = provider(&[("AWS_USE_FIPS_ENDPOINT", "yes")]);
+        assert_eq!(None, provider.use_fips());
+        assert!(logs_contain("expected `true` or `false`"));
+    }
+}
@@ -7,6 +7,18 @@
 pub mod app_name;
 pub use app_name::EnvironmentVariableAppNameProvider;
 
+/// Load the defaults mode from the environment
+pub mod defaults_mode;
+pub use defaults_mode::EnvironmentVariableDefaultsModeProvider;
+
+/// Load endpoint URLs from the environment
+pub mod endpoint_url;
+pub use endpoint_url::EnvironmentVariableEndpointUrlProvider;
+
+/// Load FIPS and dual-stack endpoint settings from the environment
+pub mod endpoint_variants;
+pub use endpoint_variants::EnvironmentVariableEndpointVariantsProvider;
+
 /// Load credentials from the environment
 pub mod credentials;
 pub use credentials::EnvironmentVariableCredentialsProvider;
@@ -19,6 +31,10 @@ pub use region::EnvironmentVariableRegionProvider;
 pub mod retry_config;
 pub use retry_config::EnvironmentVariableRetryConfigProvider;
 
+/// Load the STS regional endpoints setting from the environment
+pub mod sts_regional_endpoints;
+pub use sts_regional_endpoints::EnvironmentVariableStsRegionalEndpointsProvider;
+
 /// Load timeout configuration from the environment
 pub mod timeout_config;
 pub use timeout_config::EnvironmentVariableTimeoutConfigProvider;
@@ -0,0 +1,79 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use aws_types::endpoint::StsRegionalEndpoints;
+use aws_types::os_shim_internal::Env;
+
+const ENV_VAR_STS_REGIONAL_ENDPOINTS: &str = "AWS_STS_REGIONAL_ENDPOINTS";
+
+/// Load the STS regional endpoints setting from the `AWS_STS_REGIONAL_ENDPOINTS` environment variable
+#[derive(Debug, Default)]
+pub struct EnvironmentVariableStsRegionalEndpointsProvider {
+    env: Env,
+}
+
+impl EnvironmentVariableStsRegionalEndpointsProvider {
+    /// Create a new `EnvironmentVariableStsRegionalEndpointsProvider`
+    pub fn new() -> Self {
+        Self { env: Env::real() }
+    }
+
+    #[doc(hidden)]
+    /// Create an STS regional endpoints provider from a given `Env`
+    ///
+    /// This method is used for tests that need to override environment variables.
+    pub fn new_with_env(env: Env) -> Self {
+        Self { env }
+    }
+
+    /// Attempts to load the STS regional endpoints setting from `AWS_STS_REGIONAL_ENDPOINTS`
+    ///
+    /// Invalid values are logged and ignored.
+    pub fn sts_regional_endpoints(&self) -> Option<StsRegionalEndpoints> {
+        let value = self.env.get(ENV_VAR_STS_REGIONAL_ENDPOINTS).ok()?;
+        value
+            .parse()
+            .map_err(|err| {
+                tracing::warn!(err = %err, "invalid value for `{}`", ENV_VAR_STS_REGIONAL_ENDPOINTS)
+            })
+            .ok()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::environment::EnvironmentVariableStsRegionalEndpointsProvider;
+    use aws_types::endpoint::StsRegionalEndpoints;
+    use aws_types::os_shim_internal::Env;
+    use tracing_test::traced_test;
+
+    fn provider(vars: &[(&str, &str)]) -> EnvironmentVariableStsRegionalEndpointsProvider {
+        EnvironmentVariableStsRegionalEndpointsProvider::new_with_env(Env::from_slice(vars))
+    }
+
+    #[test]
+    fn not_set() {
+        assert_eq!(None, provider(&[]).sts_regional_endpoints());
+    }
+
+    #[test]
+    fn set() {
+        let provider = provider(&[("AWS_STS_REGIONAL_ENDPOINTS", "legacy")]);
+        assert_eq!(
+            Some(StsRegionalEndpoints::Legacy),
+            provider.sts_regional_endpoints()
+        );
+    }
+
+    #[traced_test]
+    #[test]
+    fn invalid_value() {
+        let provider = provider(&[("AWS_STS_REGIONAL_ENDPOINTS", "global")]);
+        assert_eq!(None, provider.sts_regional_endpoints());
+        assert!(logs_contain(
+            "invalid value for `AWS_STS_REGIONAL_ENDPOINTS`"
+        ));
+    }
+}
@@ -14,11 +14,11 @@ use aws_types::os_shim_internal::Env;
 use std::time::Duration;
 
 // Currently unsupported timeouts
-const ENV_VAR_CONNECT_TIMEOUT: &str = "AWS_CONNECT_TIMEOUT";
 const ENV_VAR_TLS_NEGOTIATION_TIMEOUT: &str = "AWS_TLS_NEGOTIATION_TIMEOUT";
-const ENV_VAR_READ_TIMEOUT: &str = "AWS_READ_TIMEOUT";
 
 // Supported timeouts
+const ENV_VAR_CONNECT_TIMEOUT: &str = "AWS_CONNECT_TIMEOUT";
+const ENV_VAR_READ_TIMEOUT: &str = "AWS_READ_TIMEOUT";
 const ENV_VAR_API_CALL_ATTEMPT_TIMEOUT: &str = "AWS_API_CALL_ATTEMPT_TIMEOUT";
 const ENV_VAR_API_CALL_TIMEOUT: &str = "AWS_API_CALL_TIMEOUT";
 
@@ -27,6 +27,8 @@ const ENV_VAR_API_CALL_TIMEOUT: &str = "AWS_API_CALL_TIMEOUT";
 /// This provider will check the values of the following variables in order to build a
 /// [`timeout::Config`](aws_smithy_types::timeout::Config)
 ///
+/// - `AWS_CONNECT_TIMEOUT`
+/// - `AWS_READ_TIMEOUT`
 /// - `AWS_API_CALL_ATTEMPT_TIMEOUT`
 /// - `AWS_API_CALL_TIMEOUT`
 ///
@@ -53,15 +55,11 @@ impl EnvironmentVariableTimeoutConfigProvider {
 
     /// Attempt to create a new [`timeout::Config`](aws_smithy_types::timeout::Config) from environment variables
     pub fn timeout_config(&self) -> Result<timeout::Config, timeout::ConfigError> {
-        // Warn users that set unsupported timeouts in their profile
-        for timeout in [
-            ENV_VAR_CONNECT_TIMEOUT,
-            ENV_VAR_TLS_NEGOTIATION_TIMEOUT,
-            ENV_VAR_READ_TIMEOUT,
-        ] {
-            warn_if_unsupported_timeout_is_set(&self.env, timeout);
-        }
+        // Warn users that set unsupported timeouts in their environment
+        warn_if_unsupported_timeout_is_set(&self.env, ENV_VAR_TLS_NEGOTIATION_TIMEOUT);
 
+        let connect_timeout = construct_timeout_from_env_var(&self.env, ENV_VAR_CONNECT_TIMEOUT)?;
+        let read_timeout = construct_timeout_from_env_var(&self.env, ENV_VAR_READ_TIMEOUT)?;
         let api_call_attempt_timeout =
             construct_timeout_from_env_var(&self.env, ENV_VAR_API_CALL_ATTEMPT_TIMEOUT)?;
         let api_call_timeout = construct_timeout_from_env_var(&self.env, ENV_VAR_API_CALL_TIMEOUT)?;
@@ -70,8 +68,13 @@ impl EnvironmentVariableTimeoutConfigProvider {
             .with_call_timeout(api_call_timeout)
             .with_call_attempt_timeout(api_call_attempt_timeout);
 
-        // Only API-related timeouts are currently supported
-        Ok(timeout::Config::new().with_api_timeouts(api_timeouts))
+        let http_timeouts = timeout::Http::new()
+            .with_connect_timeout(connect_timeout)
+            .with_read_timeout(read_timeout);
+
+        Ok(timeout::Config::new()
+            .with_api_timeouts(api_timeouts)
+            .with_http_timeouts(http_timeouts))
     }
 }
 
@@ -100,7 +103,7 @@ fn warn_if_unsupported_timeout_is_set(env: &Env, var: &'static str) {
 mod test {
     use super::{
         EnvironmentVariableTimeoutConfigProvider, ENV_VAR_API_CALL_ATTEMPT_TIMEOUT,
-        ENV_VAR_API_CALL_TIMEOUT,
+        ENV_VAR_API_CALL_TIMEOUT, ENV_VAR_CONNECT_TIMEOUT, ENV_VAR_READ_TIMEOUT,
     };
     use aws_smithy_types::timeout;
     use aws_smithy_types::tristate::TriState;
@@ -126,12 +129,19 @@ mod test {
             // Some floats can't be represented as f32 so this duration will end up equalling the
             // duration from the env.
             .with_call_timeout(TriState::Set(Duration::from_secs_f32(900012350.0)));
-        let expected_timeouts = timeout::Config::new().with_api_timeouts(expected_api_timeouts);
+        let expected_http_timeouts = timeout::Http::new()
+            .with_connect_timeout(TriState::Set(Duration::from_secs_f32(1.5)))
+            .with_read_timeout(TriState::Set(Duration::from_secs(2)));
+        let expected_timeouts = timeout::Config::new()
+            .with_api_timeouts(expected_api_timeouts)
+            .with_http_timeouts(expected_http_timeouts);
 
         assert_eq!(
             test_provider(&[
                 (ENV_VAR_API_CALL_ATTEMPT_TIMEOUT, "04.000"),
-                (ENV_VAR_API_CALL_TIMEOUT, "900012345.0")
+                (ENV_VAR_API_CALL_TIMEOUT, "900012345.0"),
+                (ENV_VAR_CONNECT_TIMEOUT, "1.5"),
+                (ENV_VAR_READ_TIMEOUT, "2"),
             ])
             .timeout_config()
             .unwrap(),
@@ -3,25 +3,13 @@
  * SPDX-License-Identifier: Apache-2.0.
  */
 
-use aws_types::os_shim_internal;
-
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
-pub(crate) enum Os {
-    Windows,
-    NotWindows,
-}
-
-impl Os {
-    pub fn real() -> Self {
-        match std::env::consts::OS {
-            "windows" => Os::Windows,
-            _ => Os::NotWindows,
-        }
-    }
-}
+use aws_types::os_shim_internal::{self, Os};
 
 /// Resolve a home directory given a set of environment variables
-pub(crate) fn home_dir(env_var: &os_shim_internal::Env, os: Os) -> Option<String> {
+///
+/// Which variables are checked depends on the environment's [`Os`].
+pub(crate) fn home_dir(env_var: &os_shim_internal::Env) -> Option<String> {
+    let os = env_var.os();
     if let Ok(home) = env_var.get("HOME") {
         tracing::debug!(src = "HOME", "loaded home directory");
         return Some(home);
@@ -54,9 +42,9 @@ mod test {
         // windows specific variables should only be considered when the platform is windows
         let env = Env::from_slice(&[("USERPROFILE", "C:\\Users\\name")]);
         assert_eq!(
-            home_dir(&env, Os::Windows),
+            home_dir(&env.clone().with_os(Os::Windows)),
             Some("C:\\Users\\name".to_string())
         );
-        assert_eq!(home_dir(&env, Os::NotWindows), None);
+        assert_eq!(home_dir(&env.with_os(Os::NotWindows)), None);
     }
 }
@@ -165,7 +165,7 @@ impl ParseStrictResponse for CredentialsResponseParser {
 }
 
 #[derive(Clone, Debug)]
-struct HttpCredentialRetryPolicy;
+pub(crate) struct HttpCredentialRetryPolicy;
 
 impl ClassifyResponse<SdkSuccess<Credentials>, SdkError<CredentialsError>>
     for HttpCredentialRetryPolicy
@@ -24,7 +24,7 @@ use aws_smithy_http::operation::{Metadata, Operation};
 use aws_smithy_http::response::ParseStrictResponse;
 use aws_smithy_http::retry::ClassifyResponse;
 use aws_smithy_http_tower::map_request::{
-    AsyncMapRequestLayer, AsyncMapRequestService, MapRequestLayer, MapRequestService,
+    AsyncMapRequestLayer, AsyncMapRequestService, MapRequestLayer, MapRequestService, StageError,
 };
 use aws_smithy_types::retry::{ErrorKind, RetryKind};
 use aws_smithy_types::timeout;
@@ -185,10 +185,17 @@ impl Client {
     pub async fn get(&self, path: &str) -> Result<String, ImdsError> {
         let operation = self.make_operation(path)?;
         self.inner.call(operation).await.map_err(|err| match err {
-            SdkError::ConstructionFailure(err) => match err.downcast::<ImdsError>() {
-                Ok(token_failure) => *token_failure,
-                Err(other) => ImdsError::Unexpected(other),
-            },
+            SdkError::ConstructionFailure(err) => {
+                // token failures come from the `TokenMiddleware` stage
+                let err = match err.downcast::<StageError>() {
+                    Ok(stage_error) => stage_error.into_source(),
+                    Err(other) => other,
+                };
+                match err.downcast::<ImdsError>() {
+                    Ok(token_failure) => *token_failure,
+                    Err(other) => ImdsError::Unexpected(other),
+                }
+            }
             SdkError::TimeoutError(err) => ImdsError::IoError(err),
             SdkError::DispatchFailure(err) => ImdsError::IoError(err.into()),
             SdkError::ResponseError { err, .. } => ImdsError::IoError(err),
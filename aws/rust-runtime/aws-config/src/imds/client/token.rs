@@ -163,6 +163,10 @@ impl AsyncMapRequest for TokenMiddleware {
         let this = self.clone();
         Box::pin(async move { this.add_token(request).await })
     }
+
+    fn name(&self) -> &'static str {
+        "imds_token"
+    }
 }
 
 #[derive(Clone)]
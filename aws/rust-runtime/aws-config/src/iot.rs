@@ -0,0 +1,595 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! AWS IoT Core Credentials Provider
+//!
+//! This credentials provider exchanges an X.509 device certificate for temporary credentials using
+//! the [AWS IoT Core credentials provider](https://docs.aws.amazon.com/iot/latest/developerguide/authorizing-direct-aws.html).
+//! The device authenticates with mutual TLS, and receives credentials for the IAM role that the
+//! configured role alias points to.
+//!
+//! The provider can be configured in code:
+//!
+//! ```no_run
+//! use aws_config::iot::IotCredentialsProvider;
+//! use aws_config::meta::credentials::CredentialsProviderChain;
+//! use aws_smithy_client::tls::ClientIdentity;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let identity = ClientIdentity::from_pem(
+//!     std::fs::read("device-cert.pem")?,
+//!     std::fs::read("device-key.pem")?,
+//! )?;
+//! let iot = IotCredentialsProvider::builder()
+//!     .endpoint("c2example.credentials.iot.us-east-1.amazonaws.com")
+//!     .role_alias("my-role-alias")
+//!     .thing_name("my-thing")
+//!     .client_identity(identity)
+//!     .build()?;
+//! let provider = CredentialsProviderChain::first_try("Iot", iot)
+//!     .or_default_provider()
+//!     .await;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! It isn't part of the default credentials chain, devices must build it explicitly.
+//!
+//! ## Credentials Format
+//! Credentials are returned in the following JSON format:
+//! ```json
+//! {
+//!   "credentials": {
+//!     "accessKeyId": "ASIA...",
+//!     "secretAccessKey": "wJal...",
+//!     "sessionToken": "AQoD...",
+//!     "expiration": "2017-09-12T19:08:36Z"
+//!   }
+//! }
+//! ```
+
+use crate::http_credential_provider::HttpCredentialRetryPolicy;
+use crate::json_credentials::InvalidJsonCredentials;
+use crate::provider_config::ProviderConfig;
+
+use aws_smithy_client::erase::DynConnector;
+use aws_smithy_client::http_connector::HttpSettings;
+use aws_smithy_client::tls::{ClientIdentity, TlsContext, TlsError, TrustStore};
+use aws_smithy_http::body::SdkBody;
+use aws_smithy_http::operation::{Operation, Request};
+use aws_smithy_http::response::ParseStrictResponse;
+use aws_smithy_http::result::SdkError;
+use aws_smithy_json::deserialize::token::skip_value;
+use aws_smithy_json::deserialize::{json_token_iter, Token};
+use aws_smithy_types::date_time::Format;
+use aws_smithy_types::timeout;
+use aws_smithy_types::tristate::TriState;
+use aws_smithy_types::DateTime;
+use aws_types::credentials::{self, future, CredentialsError, ProvideCredentials};
+use aws_types::Credentials;
+
+use bytes::Bytes;
+use http::header::{HeaderValue, InvalidHeaderValue, ACCEPT};
+use http::uri::InvalidUri;
+use http::{Response, Uri};
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::time::{Duration, SystemTime};
+use tower::layer::util::Identity;
+
+const DEFAULT_READ_TIMEOUT: Duration = Duration::from_secs(5);
+const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+const THING_NAME_HEADER: &str = "x-amzn-iot-thingname";
+const PROVIDER_NAME: &str = "IoT";
+
+/// Credentials provider for AWS IoT Core devices
+///
+/// See the [module](crate::iot) documentation for more details.
+#[derive(Debug)]
+pub struct IotCredentialsProvider {
+    uri: Uri,
+    thing_name: Option<HeaderValue>,
+    client: aws_smithy_client::Client<DynConnector, Identity>,
+}
+
+impl IotCredentialsProvider {
+    /// Builder for [`IotCredentialsProvider`]
+    pub fn builder() -> Builder {
+        Builder::default()
+    }
+
+    /// Load credentials from the AWS IoT Core credentials provider
+    pub async fn credentials(&self) -> credentials::Result {
+        let operation = operation(&self.uri, self.thing_name.as_ref());
+        match self.client.call(operation).await {
+            Ok(creds) => Ok(creds),
+            Err(SdkError::ServiceError { err, .. }) => Err(err),
+            Err(other) => Err(CredentialsError::unhandled(other)),
+        }
+    }
+}
+
+fn operation(
+    uri: &Uri,
+    thing_name: Option<&HeaderValue>,
+) -> Operation<IotCredentialsResponseParser, HttpCredentialRetryPolicy> {
+    let mut http_req = http::Request::builder()
+        .uri(uri)
+        .header(ACCEPT, "application/json");
+    if let Some(thing_name) = thing_name {
+        http_req = http_req.header(THING_NAME_HEADER, thing_name);
+    }
+    let http_req = http_req.body(SdkBody::empty()).expect("valid request");
+    Operation::new(Request::new(http_req), IotCredentialsResponseParser)
+        .with_retry_policy(HttpCredentialRetryPolicy)
+}
+
+impl ProvideCredentials for IotCredentialsProvider {
+    fn provide_credentials<'a>(&'a self) -> future::ProvideCredentials<'a>
+    where
+        Self: 'a,
+    {
+        future::ProvideCredentials::new(self.credentials())
+    }
+}
+
+/// Builder for [`IotCredentialsProvider`]
+#[derive(Default, Debug, Clone)]
+pub struct Builder {
+    provider_config: Option<ProviderConfig>,
+    endpoint: Option<String>,
+    role_alias: Option<String>,
+    thing_name: Option<String>,
+    client_identity: Option<ClientIdentity>,
+    trust_store: Option<TrustStore>,
+    connect_timeout: Option<Duration>,
+    read_timeout: Option<Duration>,
+}
+
+/// Error constructing an [`IotCredentialsProvider`]
+#[derive(Debug)]
+pub enum BuildError {
+    /// A required setting wasn't set on the builder
+    MissingField(&'static str),
+
+    /// The endpoint and role alias don't form a valid URI
+    InvalidEndpointUri(InvalidUri),
+
+    /// The thing name isn't a valid header value
+    InvalidThingName(InvalidHeaderValue),
+
+    /// The default HTTPS connector rejected the TLS settings, e.g. the client identity
+    InvalidTlsContext(TlsError),
+
+    /// No HTTPS connector is available, enable the `rustls` or `native-tls` feature or configure
+    /// a connector
+    NoConnector,
+}
+
+impl Display for BuildError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "failed to build IoT credentials provider: ")?;
+        match self {
+            BuildError::MissingField(field) => write!(f, "`{}` must be set", field),
+            BuildError::InvalidEndpointUri(e) => write!(f, "{}", e),
+            BuildError::InvalidThingName(e) => write!(f, "invalid thing name: {}", e),
+            BuildError::InvalidTlsContext(e) => write!(f, "{}", e),
+            BuildError::NoConnector => write!(f, "no HTTPS connector is available"),
+        }
+    }
+}
+
+impl Error for BuildError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            BuildError::InvalidEndpointUri(e) => Some(e),
+            BuildError::InvalidThingName(e) => Some(e),
+            BuildError::InvalidTlsContext(e) => Some(e),
+            BuildError::MissingField(_) | BuildError::NoConnector => None,
+        }
+    }
+}
+
+impl Builder {
+    /// Override the configuration used for this provider
+    ///
+    /// The client certificate is only presented by the default HTTPS connectors. A custom
+    /// connector must be configured for mutual TLS itself.
+    pub fn configure(mut self, provider_config: &ProviderConfig) -> Self {
+        self.provider_config = Some(provider_config.clone());
+        self
+    }
+
+    /// Set the credentials provider endpoint of the AWS account
+    ///
+    /// The endpoint is specific to each account and can be retrieved with
+    /// `aws iot describe-endpoint --endpoint-type iot:CredentialProvider`. Both a host name
+    /// (`c2example.credentials.iot.us-east-1.amazonaws.com`) and a URL are accepted.
+    pub fn endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = Some(endpoint.into());
+        self
+    }
+
+    /// Set the role alias pointing to the IAM role to get credentials for
+    pub fn role_alias(mut self, role_alias: impl Into<String>) -> Self {
+        self.role_alias = Some(role_alias.into());
+        self
+    }
+
+    /// Set the name of the IoT thing the device certificate is attached to
+    ///
+    /// This is required when the role's policy uses thing attributes or the thing name.
+    pub fn thing_name(mut self, thing_name: impl Into<String>) -> Self {
+        self.thing_name = Some(thing_name.into());
+        self
+    }
+
+    /// Set the device certificate and private key used to authenticate with mutual TLS
+    pub fn client_identity(mut self, client_identity: ClientIdentity) -> Self {
+        self.client_identity = Some(client_identity);
+        self
+    }
+
+    /// Override the certificate authorities trusted when connecting to the endpoint
+    ///
+    /// By default, the platform's root certificates are trusted.
+    pub fn trust_store(mut self, trust_store: TrustStore) -> Self {
+        self.trust_store = Some(trust_store);
+        self
+    }
+
+    /// Override the connect timeout for the HTTP client
+    ///
+    /// This value defaults to 2 seconds
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Override the read timeout for the HTTP client
+    ///
+    /// This value defaults to 5 seconds
+    pub fn read_timeout(mut self, timeout: Duration) -> Self {
+        self.read_timeout = Some(timeout);
+        self
+    }
+
+    /// Create an [`IotCredentialsProvider`] from this builder
+    ///
+    /// [`endpoint`](Self::endpoint) and [`role_alias`](Self::role_alias) are required. The HTTPS
+    /// client presenting the [`client_identity`](Self::client_identity) is created here, so that
+    /// it is reused every time credentials are loaded.
+    pub fn build(self) -> Result<IotCredentialsProvider, BuildError> {
+        let provider_config = self.provider_config.unwrap_or_default();
+        let endpoint = self.endpoint.ok_or(BuildError::MissingField("endpoint"))?;
+        let role_alias = self
+            .role_alias
+            .ok_or(BuildError::MissingField("role_alias"))?;
+        let uri =
+            credentials_uri(&endpoint, &role_alias).map_err(BuildError::InvalidEndpointUri)?;
+        let http_timeout_config = timeout::Http::new()
+            .with_connect_timeout(TriState::Set(
+                self.connect_timeout.unwrap_or(DEFAULT_CONNECT_TIMEOUT),
+            ))
+            .with_read_timeout(TriState::Set(
+                self.read_timeout.unwrap_or(DEFAULT_READ_TIMEOUT),
+            ));
+        let tls_context = TlsContext::new()
+            .with_trust_store(self.trust_store.unwrap_or_default())
+            .with_client_identity(self.client_identity);
+        let http_settings = HttpSettings::default()
+            .with_http_timeout_config(http_timeout_config)
+            .with_tls_context(tls_context);
+        let connector = provider_config
+            .try_connector(&http_settings)
+            .map_err(BuildError::InvalidTlsContext)?
+            .ok_or(BuildError::NoConnector)?;
+        let client = aws_smithy_client::Builder::new()
+            .connector(connector)
+            .sleep_impl(provider_config.sleep())
+            .build();
+        Ok(IotCredentialsProvider {
+            uri,
+            thing_name,
+            client,
+        })
+    }
+}
+
+fn credentials_uri(endpoint: &str, role_alias: &str) -> Result<Uri, InvalidUri> {
+    let endpoint = endpoint.trim_end_matches('/');
+    let base = if endpoint.contains("://") {
+        endpoint.to_string()
+    } else {
+        format!("https://{}", endpoint)
+    };
+    format!(
+        "{}/role-aliases/{}/credentials",
+        base,
+        aws_smithy_http::label::fmt_string(role_alias, false)
+    )
+    .parse()
+}
+
+#[derive(Clone, Debug)]
+struct IotCredentialsResponseParser;
+
+impl ParseStrictResponse for IotCredentialsResponseParser {
+    type Output = credentials::Result;
+
+    fn parse(&self, response: &Response<Bytes>) -> Self::Output {
+        if !response.status().is_success() {
+            let message = parse_error_message(response.body())
+                .unwrap_or_else(|| String::from_utf8_lossy(response.body()).into_owned());
+            return Err(CredentialsError::provider_error(format!(
+                "Non-success status from IoT credentials provider: {:?}: {}",
+                response.status(),
+                message
+            )));
+        }
+        parse_iot_credentials(response.body()).map_err(CredentialsError::unhandled)
+    }
+}
+
+/// Parse the `credentials` object of a successful response
+fn parse_iot_credentials(input: &[u8]) -> Result<Credentials, InvalidJsonCredentials> {
+    let mut access_key_id = None;
+    let mut secret_access_key = None;
+    let mut session_token = None;
+    let mut expiration = None;
+
+    let mut tokens = json_token_iter(input).peekable();
+    if !matches!(tokens.next().transpose()?, Some(Token::StartObject { .. })) {
+        return Err(InvalidJsonCredentials::JsonError(
+            "expected a JSON document starting with `{`".into(),
+        ));
+    }
+    loop {
+        match tokens.next().transpose()? {
+            Some(Token::EndObject { .. }) => break,
+            Some(Token::ObjectKey { key, .. }) if key.to_unescaped()? == "credentials" => {
+                if !matches!(tokens.next().transpose()?, Some(Token::StartObject { .. })) {
+                    return Err(InvalidJsonCredentials::InvalidField {
+                        field: "credentials",
+                        err: "expected a JSON object".into(),
+                    });
+                }
+                loop {
+                    match tokens.next().transpose()? {
+                        Some(Token::EndObject { .. }) => break,
+                        Some(Token::ObjectKey { key, .. }) => {
+                            if let Some(Ok(Token::ValueString { value, .. })) = tokens.peek() {
+                                let value = value.to_unescaped()?.into_owned();
+                                match &*key.to_unescaped()? {
+                                    "accessKeyId" => access_key_id = Some(value),
+                                    "secretAccessKey" => secret_access_key = Some(value),
+                                    "sessionToken" => session_token = Some(value),
+                                    "expiration" => expiration = Some(value),
+                                    _ => {}
+                                }
+                            }
+                            skip_value(&mut tokens)?;
+                        }
+                        other => {
+                            return Err(InvalidJsonCredentials::Other(
+                                format!("expected object key, found: {:?}", other).into(),
+                            ))
+                        }
+                    }
+                }
+            }
+            Some(Token::ObjectKey { .. }) => skip_value(&mut tokens)?,
+            other => {
+                return Err(InvalidJsonCredentials::Other(
+                    format!("expected object key, found: {:?}", other).into(),
+                ))
+            }
+        }
+    }
+
+    let access_key_id = access_key_id.ok_or(InvalidJsonCredentials::MissingField("accessKeyId"))?;
+    let secret_access_key =
+        secret_access_key.ok_or(InvalidJsonCredentials::MissingField("secretAccessKey"))?;
+    let session_token =
+        session_token.ok_or(InvalidJsonCredentials::MissingField("sessionToken"))?;
+    let expiration = expiration.ok_or(InvalidJsonCredentials::MissingField("expiration"))?;
+    let expiration = SystemTime::try_from(
+        DateTime::from_str(&expiration, Format::DateTime).map_err(|err| {
+            InvalidJsonCredentials::InvalidField {
+                field: "expiration",
+                err: err.into(),
+            }
+        })?,
+    )
+    .map_err(|_| {
+        InvalidJsonCredentials::Other(
+            "credential expiration time cannot be represented by a SystemTime".into(),
+        )
+    })?;
+    Ok(Credentials::new(
+        access_key_id,
+        secret_access_key,
+        Some(session_token),
+        Some(expiration),
+        PROVIDER_NAME,
+    ))
+}
+
+/// Parse the `message` of an error response, e.g. `{"message": "Access Denied"}`
+fn parse_error_message(input: &[u8]) -> Option<String> {
+    let mut message = None;
+    crate::json_credentials::json_parse_loop(input, |key, value| {
+        if key.eq_ignore_ascii_case("message") {
+            message = Some(value.into_owned());
+        }
+    })
+    .ok()?;
+    message
+}
+
+#[cfg(test)]
+mod test {
+    use crate::iot::{credentials_uri, parse_iot_credentials, BuildError, IotCredentialsProvider};
+    use crate::json_credentials::InvalidJsonCredentials;
+    use crate::provider_config::ProviderConfig;
+    use aws_smithy_async::rt::sleep::TokioSleep;
+    use aws_smithy_client::erase::DynConnector;
+    use aws_smithy_client::test_connection::TestConnection;
+    use aws_smithy_http::body::SdkBody;
+    use aws_types::credentials::{CredentialsError, ProvideCredentials};
+    use std::time::{Duration, UNIX_EPOCH};
+
+    const CREDENTIALS_URI: &str =
+        "https://c2example.credentials.iot.us-east-1.amazonaws.com/role-aliases/my-alias/credentials";
+
+    const OK_RESPONSE: &str = r#"{
+        "credentials": {
+            "accessKeyId": "ASIAEXAMPLE",
+            "secretAccessKey": "SECRET",
+            "sessionToken": "TOKEN",
+            "expiration": "2009-02-13T23:31:30Z"
+        }
+    }"#;
+
+    fn provider(connector: &TestConnection<&'static str>) -> IotCredentialsProvider {
+        IotCredentialsProvider::builder()
+            .configure(
+                &ProviderConfig::no_configuration()
+                    .with_http_connector(DynConnector::new(connector.clone()))
+                    .with_sleep(TokioSleep::new()),
+            )
+            .endpoint("c2example.credentials.iot.us-east-1.amazonaws.com")
+            .role_alias("my-alias")
+            .thing_name("my-thing")
+            .build()
+            .expect("valid configuration")
+    }
+
+    fn request() -> http::Request<SdkBody> {
+        http::Request::builder()
+            .uri(CREDENTIALS_URI)
+            .header("x-amzn-iot-thingname", "my-thing")
+            .body(SdkBody::empty())
+            .unwrap()
+    }
+
+    #[test]
+    fn build_credentials_uri() {
+        assert_eq!(
+            CREDENTIALS_URI,
+            credentials_uri(
+                "c2example.credentials.iot.us-east-1.amazonaws.com",
+                "my-alias"
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            "http://localhost:8080/role-aliases/my%20alias/credentials",
+            credentials_uri("http://localhost:8080/", "my alias").unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_credentials() {
+        let creds = parse_iot_credentials(OK_RESPONSE.as_bytes()).expect("valid credentials");
+        assert_eq!("ASIAEXAMPLE", creds.access_key_id());
+        assert_eq!("SECRET", creds.secret_access_key());
+        assert_eq!(Some("TOKEN"), creds.session_token());
+        assert_eq!(
+            Some(UNIX_EPOCH + Duration::from_secs(1234567890)),
+            creds.expiry()
+        );
+
+        let err = parse_iot_credentials(br#"{"credentials": {"accessKeyId": "ASIAEXAMPLE"}}"#)
+            .expect_err("missing fields");
+        assert!(
+            matches!(err, InvalidJsonCredentials::MissingField("secretAccessKey")),
+            "{:?}",
+            err
+        );
+    }
+
+    #[tokio::test]
+    async fn load_credentials() {
+        let connector = TestConnection::new(vec![(
+            request(),
+            http::Response::builder()
+                .status(200)
+                .body(OK_RESPONSE)
+                .unwrap(),
+        )]);
+        let creds = provider(&connector)
+            .provide_credentials()
+            .await
+            .expect("valid credentials");
+        assert_eq!("ASIAEXAMPLE", creds.access_key_id());
+        connector.assert_requests_match(&[]);
+    }
+
+    #[tokio::test]
+    async fn error_response() {
+        let connector = TestConnection::new(vec![(
+            request(),
+            http::Response::builder()
+                .status(403)
+                .body(r#"{"message": "Access Denied"}"#)
+                .unwrap(),
+        )]);
+        let err = provider(&connector)
+            .provide_credentials()
+            .await
+            .expect_err("access denied");
+        assert!(
+            matches!(err, CredentialsError::ProviderError { .. }),
+            "{:?}",
+            err
+        );
+        assert!(format!("{}", err).contains("Access Denied"), "{}", err);
+    }
+
+    #[test]
+    fn build_errors() {
+        let builder = || {
+            IotCredentialsProvider::builder().configure(
+                &ProviderConfig::no_configuration()
+                    .with_http_connector(DynConnector::new(TestConnection::<&str>::new(vec![]))),
+            )
+        };
+        let err = builder()
+            .endpoint("c2example.credentials.iot.us-east-1.amazonaws.com")
+            .build()
+            .expect_err("no role alias");
+        assert!(
+            matches!(err, BuildError::MissingField("role_alias")),
+            "{:?}",
+            err
+        );
+        let err = builder()
+            .endpoint("not a host")
+            .role_alias("my-alias")
+            .build()
+            .expect_err("invalid endpoint");
+        assert!(
+            matches!(err, BuildError::InvalidEndpointUri(_)),
+            "{:?}",
+            err
+        );
+        let err = IotCredentialsProvider::builder()
+            .configure(&ProviderConfig::no_configuration())
+            .endpoint("c2example.credentials.iot.us-east-1.amazonaws.com")
+            .role_alias("my-alias")
+            .build()
+            .expect_err("no connector");
+        assert!(matches!(err, BuildError::NoConnector), "{:?}", err);
+        let err = builder()
+            .endpoint("c2example.credentials.iot.us-east-1.amazonaws.com")
+            .role_alias("my-alias")
+            .thing_name("my\nthing")
+            .build()
+            .expect_err("invalid thing name");
+        assert!(matches!(err, BuildError::InvalidThingName(_)), "{:?}", err);
+    }
+}
@@ -6,7 +6,7 @@
 use aws_smithy_json::deserialize::token::skip_value;
 use aws_smithy_json::deserialize::{json_token_iter, EscapeError, Token};
 use aws_smithy_types::date_time::Format;
-use aws_smithy_types::DateTime;
+use aws_smithy_types::{DateTime, Number};
 use std::borrow::Cow;
 use std::convert::TryFrom;
 use std::error::Error;
@@ -175,9 +175,28 @@ pub(crate) fn parse_json_credentials(
     }
 }
 
+/// A string or number value found at the top level of a JSON object
+#[derive(Debug)]
+pub(crate) enum JsonValue<'a> {
+    String(Cow<'a, str>),
+    Number(Number),
+}
+
 pub(crate) fn json_parse_loop<'a>(
     input: &'a [u8],
     mut f: impl FnMut(Cow<'a, str>, Cow<'a, str>),
+) -> Result<(), InvalidJsonCredentials> {
+    json_parse_loop_with_numbers(input, |key, value| {
+        if let JsonValue::String(value) = value {
+            f(key, value)
+        }
+    })
+}
+
+/// Like [`json_parse_loop`], but also yields number values
+pub(crate) fn json_parse_loop_with_numbers<'a>(
+    input: &'a [u8],
+    mut f: impl FnMut(Cow<'a, str>, JsonValue<'a>),
 ) -> Result<(), InvalidJsonCredentials> {
     let mut tokens = json_token_iter(input).peekable();
     if !matches!(tokens.next().transpose()?, Some(Token::StartObject { .. })) {
@@ -189,10 +208,17 @@ pub(crate) fn json_parse_loop<'a>(
         match tokens.next().transpose()? {
             Some(Token::EndObject { .. }) => break,
             Some(Token::ObjectKey { key, .. }) => {
-                if let Some(Ok(Token::ValueString { value, .. })) = tokens.peek() {
-                    let key = key.to_unescaped()?;
-                    let value = value.to_unescaped()?;
-                    f(key, value)
+                match tokens.peek() {
+                    Some(Ok(Token::ValueString { value, .. })) => {
+                        let key = key.to_unescaped()?;
+                        let value = value.to_unescaped()?;
+                        f(key, JsonValue::String(value))
+                    }
+                    Some(Ok(Token::ValueNumber { value, .. })) => {
+                        let value = *value;
+                        f(key.to_unescaped()?, JsonValue::Number(value))
+                    }
+                    _ => {}
                 }
                 skip_value(&mut tokens)?;
             }
@@ -87,6 +87,8 @@
 #[allow(dead_code)]
 const PKG_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+pub mod defaults_mode;
+
 /// Providers that implement the default AWS provider chain
 pub mod default_provider;
 
@@ -107,6 +109,8 @@ pub mod web_identity_token;
 
 pub mod ecs;
 
+pub mod iot;
+
 pub mod provider_config;
 
 mod cache;
@@ -123,6 +127,8 @@ pub mod sso;
 
 pub mod connector;
 
+pub mod credential_process;
+
 pub(crate) mod parsing;
 
 // Re-export types from smithy-types
@@ -159,16 +165,21 @@ mod loader {
     use std::sync::Arc;
 
     use crate::connector::default_connector;
+    use crate::defaults_mode::DefaultsMode;
     use aws_smithy_async::rt::sleep::{default_async_sleep, AsyncSleep};
     use aws_smithy_client::http_connector::{HttpConnector, HttpSettings};
     use aws_smithy_types::retry::RetryConfig;
     use aws_smithy_types::timeout;
     use aws_types::app_name::AppName;
     use aws_types::credentials::{ProvideCredentials, SharedCredentialsProvider};
-    use aws_types::endpoint::ResolveAwsEndpoint;
+    use aws_types::endpoint::{ResolveAwsEndpoint, StsRegionalEndpoints};
     use aws_types::SdkConfig;
+    use http::Uri;
 
-    use crate::default_provider::{app_name, credentials, region, retry_config, timeout_config};
+    use crate::default_provider::{
+        app_name, credentials, defaults_mode, endpoint_url, endpoint_variants, region,
+        retry_config, sts_regional_endpoints, timeout_config,
+    };
     use crate::meta::region::ProvideRegion;
     use crate::provider_config::ProviderConfig;
 
@@ -183,12 +194,17 @@ mod loader {
         app_name: Option<AppName>,
         credentials_provider: Option<SharedCredentialsProvider>,
         endpoint_resolver: Option<Arc<dyn ResolveAwsEndpoint>>,
+        endpoint_url: Option<Uri>,
         region: Option<Box<dyn ProvideRegion>>,
         retry_config: Option<RetryConfig>,
         sleep: Option<Arc<dyn AsyncSleep>>,
         timeout_config: Option<timeout::Config>,
         provider_config: Option<ProviderConfig>,
         http_connector: Option<HttpConnector>,
+        use_fips: Option<bool>,
+        use_dual_stack: Option<bool>,
+        sts_regional_endpoints: Option<StsRegionalEndpoints>,
+        defaults_mode: Option<DefaultsMode>,
     }
 
     impl ConfigLoader {
@@ -311,6 +327,97 @@ mod loader {
             self
         }
 
+        /// Override the endpoint URL used for **all** AWS Services
+        ///
+        /// By default, the endpoint URL is loaded from the `AWS_ENDPOINT_URL` environment variable or the
+        /// `endpoint_url` profile key, and service-specific endpoint URLs are loaded from
+        /// `AWS_ENDPOINT_URL_<SERVICE>` environment variables. Setting an endpoint URL here skips loading
+        /// both. An [`endpoint_resolver`](Self::endpoint_resolver), if set, takes precedence over endpoint URLs.
+        ///
+        /// # Examples
+        /// ```no_run
+        /// # async fn doc() {
+        /// let sdk_config = aws_config::from_env()
+        ///   .endpoint_url("http://localhost:4566".parse().expect("valid URI"))
+        ///   .load().await;
+        /// # }
+        /// ```
+        pub fn endpoint_url(mut self, endpoint_url: Uri) -> Self {
+            self.endpoint_url = Some(endpoint_url);
+            self
+        }
+
+        /// Override whether FIPS endpoints are used for **all** AWS services
+        ///
+        /// By default, this is loaded from the `AWS_USE_FIPS_ENDPOINT` environment variable or the
+        /// `use_fips_endpoint` profile key.
+        ///
+        /// # Examples
+        /// ```no_run
+        /// # async fn doc() {
+        /// let sdk_config = aws_config::from_env().use_fips(true).load().await;
+        /// # }
+        /// ```
+        pub fn use_fips(mut self, use_fips: bool) -> Self {
+            self.use_fips = Some(use_fips);
+            self
+        }
+
+        /// Override whether dual-stack endpoints are used for **all** AWS services
+        ///
+        /// By default, this is loaded from the `AWS_USE_DUALSTACK_ENDPOINT` environment variable or
+        /// the `use_dualstack_endpoint` profile key.
+        pub fn use_dual_stack(mut self, use_dual_stack: bool) -> Self {
+            self.use_dual_stack = Some(use_dual_stack);
+            self
+        }
+
+        /// Override whether STS requests in the legacy global regions use the global STS endpoint
+        ///
+        /// By default, this is loaded from the `AWS_STS_REGIONAL_ENDPOINTS` environment variable or
+        /// the `sts_regional_endpoints` profile key. When unset, STS clients always use the
+        /// endpoint for their region.
+        ///
+        /// # Examples
+        /// ```no_run
+        /// # async fn doc() {
+        /// use aws_types::endpoint::StsRegionalEndpoints;
+        /// let sdk_config = aws_config::from_env()
+        ///     .sts_regional_endpoints(StsRegionalEndpoints::Legacy)
+        ///     .load()
+        ///     .await;
+        /// # }
+        /// ```
+        pub fn sts_regional_endpoints(
+            mut self,
+            sts_regional_endpoints: StsRegionalEndpoints,
+        ) -> Self {
+            self.sts_regional_endpoints = Some(sts_regional_endpoints);
+            self
+        }
+
+        /// Override the [`DefaultsMode`] used to pick default retry and timeout settings
+        ///
+        /// By default, this is loaded from the `AWS_DEFAULTS_MODE` environment variable or the
+        /// `defaults_mode` profile key, and is [`DefaultsMode::Legacy`] when unset. Settings from
+        /// the defaults mode only apply to values that aren't configured explicitly, in the
+        /// environment, or in the profile file.
+        ///
+        /// # Examples
+        /// ```no_run
+        /// # async fn doc() {
+        /// use aws_config::defaults_mode::DefaultsMode;
+        /// let sdk_config = aws_config::from_env()
+        ///     .defaults_mode(DefaultsMode::InRegion)
+        ///     .load()
+        ///     .await;
+        /// # }
+        /// ```
+        pub fn defaults_mode(mut self, defaults_mode: DefaultsMode) -> Self {
+            self.defaults_mode = Some(defaults_mode);
+            self
+        }
+
         /// Set configuration for all sub-loaders (credentials, region etc.)
         ///
         /// Update the `ProviderConfig` used for all nested loaders. This can be used to override
@@ -355,11 +462,23 @@ mod loader {
                     .await
             };
 
+            let defaults_mode = match self.defaults_mode {
+                Some(defaults_mode) => defaults_mode,
+                None => defaults_mode::default_provider()
+                    .configure(&conf)
+                    .defaults_mode()
+                    .await
+                    .unwrap_or_default(),
+            };
+            let defaults_mode =
+                defaults_mode::resolve_auto(defaults_mode, region.as_ref(), &conf).await;
+
             let retry_config = if let Some(retry_config) = self.retry_config {
                 retry_config
             } else {
                 retry_config::default_provider()
                     .configure(&conf)
+                    .defaults_mode(defaults_mode)
                     .retry_config()
                     .await
             };
@@ -388,24 +507,56 @@ mod loader {
                 self.sleep
             };
 
+            let timeout_config = if let Some(timeout_config) = self.timeout_config {
+                timeout_config.take_unset_from(defaults_mode.timeout_config())
+            } else {
+                timeout_config::default_provider()
+                    .configure(&conf)
+                    .defaults_mode(defaults_mode)
+                    .timeout_config()
+                    .await
+            };
+
+            // The default connector is configured with the resolved timeouts so that connect and
+            // read timeouts from the environment or profile apply to it
             let http_connector = if let Some(http_connector) = self.http_connector {
                 http_connector
             } else {
-                let timeouts = self.timeout_config.clone().unwrap_or_default();
                 let settings = HttpSettings::default()
-                    .with_http_timeout_config(timeouts.http_timeouts())
-                    .with_tcp_timeout_config(timeouts.tcp_timeouts());
+                    .with_http_timeout_config(timeout_config.http_timeouts())
+                    .with_tcp_timeout_config(timeout_config.tcp_timeouts());
                 let sleep_impl = sleep_impl.clone();
                 HttpConnector::Prebuilt(default_connector(&settings, sleep_impl))
             };
 
-            let timeout_config = if let Some(timeout_config) = self.timeout_config {
-                timeout_config
+            let (endpoint_url, service_endpoint_urls) = if self.endpoint_url.is_some() {
+                (self.endpoint_url, vec![])
             } else {
-                timeout_config::default_provider()
-                    .configure(&conf)
-                    .timeout_config()
-                    .await
+                let provider = endpoint_url::default_provider().configure(&conf);
+                (
+                    provider.endpoint_url().await,
+                    provider.service_endpoint_urls(),
+                )
+            };
+
+            let endpoint_variants = endpoint_variants::default_provider().configure(&conf);
+            let use_fips = match self.use_fips {
+                Some(use_fips) => Some(use_fips),
+                None => endpoint_variants.use_fips().await,
+            };
+            let use_dual_stack = match self.use_dual_stack {
+                Some(use_dual_stack) => Some(use_dual_stack),
+                None => endpoint_variants.use_dual_stack().await,
+            };
+
+            let sts_regional_endpoints = match self.sts_regional_endpoints {
+                Some(sts_regional_endpoints) => Some(sts_regional_endpoints),
+                None => {
+                    sts_regional_endpoints::default_provider()
+                        .configure(&conf)
+                        .sts_regional_endpoints()
+                        .await
+                }
             };
 
             let credentials_provider = if let Some(provider) = self.credentials_provider {
@@ -426,6 +577,13 @@ mod loader {
                 .http_connector(http_connector);
 
             builder.set_endpoint_resolver(endpoint_resolver);
+            builder.set_endpoint_url(endpoint_url);
+            for (service_id, url) in service_endpoint_urls {
+                builder.set_service_endpoint_url(&service_id, Some(url));
+            }
+            builder.set_use_fips(use_fips);
+            builder.set_use_dual_stack(use_dual_stack);
+            builder.set_sts_regional_endpoints(sts_regional_endpoints);
             builder.set_app_name(app_name);
             builder.set_sleep_impl(sleep_impl);
             builder.build()
@@ -434,12 +592,18 @@ mod loader {
 
     #[cfg(test)]
     mod test {
+        use crate::defaults_mode::DefaultsMode;
         use crate::from_env;
         use crate::provider_config::ProviderConfig;
         use aws_smithy_client::erase::DynConnector;
         use aws_smithy_client::never::NeverConnector;
+        use aws_smithy_types::retry::RetryMode;
+        use aws_smithy_types::tristate::TriState;
         use aws_types::credentials::ProvideCredentials;
-        use aws_types::os_shim_internal::Env;
+        use aws_types::endpoint::StsRegionalEndpoints;
+        use aws_types::os_shim_internal::{Env, Fs};
+        use aws_types::region::Region;
+        use std::time::Duration;
 
         #[tokio::test]
         async fn provider_config_used() {
@@ -470,5 +634,182 @@ mod loader {
                 "akid"
             );
         }
+
+        #[tokio::test]
+        async fn retry_and_timeout_config_loaded_from_env_and_profile() {
+            let env = Env::from_slice(&[
+                ("AWS_CONFIG_FILE", "test_config"),
+                ("AWS_MAX_ATTEMPTS", "4"),
+                ("AWS_READ_TIMEOUT", "2"),
+            ]);
+            let fs = Fs::from_slice(&[(
+                "test_config",
+                "[default]\n\
+                 max_attempts = 10\n\
+                 retry_mode = standard\n\
+                 api_call_timeout = 5\n\
+                 connect_timeout = 1\n\
+                 read_timeout = 7\n",
+            )]);
+            let config = from_env()
+                .configure(
+                    ProviderConfig::empty()
+                        .with_env(env)
+                        .with_fs(fs)
+                        .with_http_connector(DynConnector::new(NeverConnector::new())),
+                )
+                .load()
+                .await;
+
+            // the environment takes precedence over the profile
+            let retry_config = config.retry_config().unwrap();
+            assert_eq!(retry_config.max_attempts(), 4);
+            assert_eq!(retry_config.mode(), RetryMode::Standard);
+            let timeout_config = config.timeout_config().unwrap();
+            assert_eq!(
+                timeout_config.http_timeouts().read_timeout(),
+                TriState::Set(Duration::from_secs(2))
+            );
+            assert_eq!(
+                timeout_config.http_timeouts().connect_timeout(),
+                TriState::Set(Duration::from_secs(1))
+            );
+            assert_eq!(
+                timeout_config.api_timeouts().call_timeout(),
+                TriState::Set(Duration::from_secs(5))
+            );
+        }
+
+        #[tokio::test]
+        async fn defaults_mode_settings_have_lowest_precedence() {
+            let provider_config = |vars: &[(&str, &str)]| {
+                ProviderConfig::empty()
+                    .with_env(Env::from_slice(vars))
+                    .with_http_connector(DynConnector::new(NeverConnector::new()))
+            };
+            let connect_timeout = |config: &aws_types::SdkConfig| {
+                config
+                    .timeout_config()
+                    .unwrap()
+                    .http_timeouts()
+                    .connect_timeout()
+            };
+
+            let config = from_env()
+                .configure(provider_config(&[("AWS_DEFAULTS_MODE", "in-region")]))
+                .load()
+                .await;
+            assert_eq!(
+                connect_timeout(&config),
+                TriState::Set(Duration::from_millis(1100))
+            );
+
+            let config = from_env()
+                .configure(provider_config(&[
+                    ("AWS_DEFAULTS_MODE", "in-region"),
+                    ("AWS_CONNECT_TIMEOUT", "5"),
+                ]))
+                .load()
+                .await;
+            assert_eq!(
+                connect_timeout(&config),
+                TriState::Set(Duration::from_secs(5))
+            );
+
+            // `auto` compares the region of the execution environment to the client's region
+            let config = from_env()
+                .configure(provider_config(&[
+                    ("AWS_DEFAULTS_MODE", "auto"),
+                    ("AWS_EXECUTION_ENV", "AWS_Lambda_provided.al2"),
+                    ("AWS_REGION", "us-west-2"),
+                ]))
+                .region(Region::new("eu-west-1"))
+                .load()
+                .await;
+            assert_eq!(
+                connect_timeout(&config),
+                TriState::Set(Duration::from_millis(3100))
+            );
+
+            let config = from_env()
+                .configure(provider_config(&[("AWS_DEFAULTS_MODE", "mobile")]))
+                .defaults_mode(DefaultsMode::Legacy)
+                .load()
+                .await;
+            assert_eq!(connect_timeout(&config), TriState::Unset);
+        }
+
+        #[tokio::test]
+        async fn endpoint_urls_loaded_from_env() {
+            let env = Env::from_slice(&[
+                ("AWS_ENDPOINT_URL", "http://localhost:4566"),
+                ("AWS_ENDPOINT_URL_DYNAMODB", "http://localhost:8000"),
+            ]);
+            let config = from_env()
+                .configure(
+                    ProviderConfig::empty()
+                        .with_env(env)
+                        .with_http_connector(DynConnector::new(NeverConnector::new())),
+                )
+                .load()
+                .await;
+            assert_eq!(
+                config
+                    .service_endpoint_url("DynamoDB")
+                    .map(|uri| uri.to_string()),
+                Some("http://localhost:8000/".to_string())
+            );
+            assert_eq!(
+                config.service_endpoint_url("S3").map(|uri| uri.to_string()),
+                Some("http://localhost:4566/".to_string())
+            );
+        }
+
+        #[tokio::test]
+        async fn endpoint_variants_loaded_from_env_unless_overridden() {
+            let provider_config = || {
+                ProviderConfig::empty()
+                    .with_env(Env::from_slice(&[
+                        ("AWS_USE_FIPS_ENDPOINT", "true"),
+                        ("AWS_USE_DUALSTACK_ENDPOINT", "true"),
+                    ]))
+                    .with_http_connector(DynConnector::new(NeverConnector::new()))
+            };
+            let config = from_env().configure(provider_config()).load().await;
+            assert_eq!(config.use_fips(), Some(true));
+            assert_eq!(config.use_dual_stack(), Some(true));
+
+            let config = from_env()
+                .configure(provider_config())
+                .use_fips(false)
+                .load()
+                .await;
+            assert_eq!(config.use_fips(), Some(false));
+            assert_eq!(config.use_dual_stack(), Some(true));
+        }
+
+        #[tokio::test]
+        async fn sts_regional_endpoints_loaded_from_env_unless_overridden() {
+            let provider_config = || {
+                ProviderConfig::empty()
+                    .with_env(Env::from_slice(&[("AWS_STS_REGIONAL_ENDPOINTS", "legacy")]))
+                    .with_http_connector(DynConnector::new(NeverConnector::new()))
+            };
+            let config = from_env().configure(provider_config()).load().await;
+            assert_eq!(
+                config.sts_regional_endpoints(),
+                Some(StsRegionalEndpoints::Legacy)
+            );
+
+            let config = from_env()
+                .configure(provider_config())
+                .sts_regional_endpoints(StsRegionalEndpoints::Regional)
+                .load()
+                .await;
+            assert_eq!(
+                config.sts_regional_endpoints(),
+                Some(StsRegionalEndpoints::Regional)
+            );
+        }
     }
 }
@@ -6,10 +6,11 @@
 //! Lazy, caching, credentials provider implementation
 
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use aws_smithy_async::future::timeout::Timeout;
 use aws_smithy_async::rt::sleep::AsyncSleep;
+use aws_smithy_http::metrics::{CredentialsLoadMetrics, EmitMetrics, SharedMetricsEmitter};
 use tracing::{trace_span, Instrument};
 
 use aws_types::credentials::{future, CredentialsError, ProvideCredentials};
@@ -27,6 +28,11 @@ const DEFAULT_BUFFER_TIME: Duration = Duration::from_secs(10);
 /// For example, you can provide an [`ProvideCredentials`] implementation that calls
 /// AWS STS's AssumeRole operation to get temporary credentials, and `LazyCachingCredentialsProvider`
 /// will cache those credentials until they expire.
+///
+/// Credentials are refreshed a little before they expire: the configured buffer time, plus a
+/// random jitter of up to the buffer time again, so that many providers loading credentials at the
+/// same time don't all refresh them at the same time. Concurrent requests for credentials while a
+/// refresh is in progress wait for that refresh rather than starting their own.
 #[derive(Debug)]
 pub struct LazyCachingCredentialsProvider {
     time: TimeSource,
@@ -35,9 +41,13 @@ pub struct LazyCachingCredentialsProvider {
     loader: Arc<dyn ProvideCredentials>,
     load_timeout: Duration,
     default_credential_expiration: Duration,
+    buffer_time: Duration,
+    buffer_time_jitter_fraction: fn() -> f64,
+    metrics_emitter: Option<SharedMetricsEmitter>,
 }
 
 impl LazyCachingCredentialsProvider {
+    #[allow(clippy::too_many_arguments)]
     fn new(
         time: TimeSource,
         sleeper: Arc<dyn AsyncSleep>,
@@ -45,6 +55,8 @@ impl LazyCachingCredentialsProvider {
         load_timeout: Duration,
         default_credential_expiration: Duration,
         buffer_time: Duration,
+        buffer_time_jitter_fraction: fn() -> f64,
+        metrics_emitter: Option<SharedMetricsEmitter>,
     ) -> Self {
         LazyCachingCredentialsProvider {
             time,
@@ -53,6 +65,9 @@ impl LazyCachingCredentialsProvider {
             loader,
             load_timeout,
             default_credential_expiration,
+            buffer_time,
+            buffer_time_jitter_fraction,
+            metrics_emitter,
         }
     }
 
@@ -73,6 +88,10 @@ impl ProvideCredentials for LazyCachingCredentialsProvider {
         let load_timeout = self.load_timeout;
         let cache = self.cache.clone();
         let default_credential_expiration = self.default_credential_expiration;
+        let jitter = self
+            .buffer_time
+            .mul_f64((self.buffer_time_jitter_fraction)().clamp(0.0, 1.0));
+        let metrics_emitter = self.metrics_emitter.clone();
 
         future::ProvideCredentials::new(async move {
             // Attempt to get cached credentials, or clear the cache if they're expired
@@ -89,13 +108,27 @@ impl ProvideCredentials for LazyCachingCredentialsProvider {
                 cache
                     .get_or_load(|| {
                         async move {
-                            let credentials = future.await.map_err(|_err| {
-                                CredentialsError::provider_timed_out(load_timeout)
-                            })??;
+                            let start = Instant::now();
+                            let result = future
+                                .await
+                                .map_err(|_err| CredentialsError::provider_timed_out(load_timeout));
+                            let credentials = match result {
+                                Ok(Ok(credentials)) => Ok(credentials),
+                                Ok(Err(err)) | Err(err) => Err(err),
+                            };
+                            if let Some(emitter) = &metrics_emitter {
+                                emitter.emit_credentials_load(&CredentialsLoadMetrics::new(
+                                    start.elapsed(),
+                                    credentials.is_ok(),
+                                ));
+                            }
+                            let credentials = credentials?;
                             // If the credentials don't have an expiration time, then create a default one
                             let expiry = credentials
                                 .expiry()
                                 .unwrap_or(now + default_credential_expiration);
+                            // Refresh a random amount of time early, on top of the buffer time
+                            let expiry = expiry.checked_sub(jitter).unwrap_or(expiry);
                             Ok((credentials, expiry))
                         }
                         // Only instrument the the actual load future so that no span
@@ -116,6 +149,7 @@ mod builder {
     use std::time::Duration;
 
     use aws_smithy_async::rt::sleep::{default_async_sleep, AsyncSleep};
+    use aws_smithy_http::metrics::{EmitMetrics, SharedMetricsEmitter};
     use aws_types::credentials::ProvideCredentials;
 
     use super::{
@@ -148,7 +182,9 @@ mod builder {
         load: Option<Arc<dyn ProvideCredentials>>,
         load_timeout: Option<Duration>,
         buffer_time: Option<Duration>,
+        buffer_time_jitter_fraction: Option<fn() -> f64>,
         default_credential_expiration: Option<Duration>,
+        metrics_emitter: Option<SharedMetricsEmitter>,
     }
 
     impl Builder {
@@ -238,6 +274,45 @@ mod builder {
             self
         }
 
+        /// A function that returns a random fraction between 0 and 1, used to jitter the buffer time.
+        ///
+        /// Credentials are refreshed `buffer_time + buffer_time * buffer_time_jitter_fraction()`
+        /// before they expire, so that clients created at the same time don't refresh their
+        /// credentials at the same time. Return `0.0` to disable the jitter.
+        ///
+        /// Defaults to a uniformly distributed random fraction.
+        pub fn buffer_time_jitter_fraction(mut self, jitter_fraction: fn() -> f64) -> Self {
+            self.set_buffer_time_jitter_fraction(Some(jitter_fraction));
+            self
+        }
+
+        /// A function that returns a random fraction between 0 and 1, used to jitter the buffer time.
+        ///
+        /// Credentials are refreshed `buffer_time + buffer_time * buffer_time_jitter_fraction()`
+        /// before they expire, so that clients created at the same time don't refresh their
+        /// credentials at the same time. Return `0.0` to disable the jitter.
+        ///
+        /// Defaults to a uniformly distributed random fraction.
+        pub fn set_buffer_time_jitter_fraction(
+            &mut self,
+            jitter_fraction: Option<fn() -> f64>,
+        ) -> &mut Self {
+            self.buffer_time_jitter_fraction = jitter_fraction;
+            self
+        }
+
+        /// Emitter for metrics on how long credentials took to load, and whether loading succeeded.
+        pub fn metrics_emitter(mut self, emitter: impl EmitMetrics + 'static) -> Self {
+            self.set_metrics_emitter(Some(SharedMetricsEmitter::new(emitter)));
+            self
+        }
+
+        /// Emitter for metrics on how long credentials took to load, and whether loading succeeded.
+        pub fn set_metrics_emitter(&mut self, emitter: Option<SharedMetricsEmitter>) -> &mut Self {
+            self.metrics_emitter = emitter;
+            self
+        }
+
         /// Default expiration time to set on credentials if they don't have an expiration time.
         ///
         /// This is only used if the given [`ProvideCredentials`] returns
@@ -288,6 +363,8 @@ mod builder {
                 self.load_timeout.unwrap_or(DEFAULT_LOAD_TIMEOUT),
                 default_credential_expiration,
                 self.buffer_time.unwrap_or(DEFAULT_BUFFER_TIME),
+                self.buffer_time_jitter_fraction.unwrap_or(fastrand::f64),
+                self.metrics_emitter,
             )
         }
     }
@@ -305,11 +382,13 @@ mod tests {
     use tracing_test::traced_test;
 
     use crate::meta::credentials::credential_fn::provide_credentials_fn;
+    use crate::provider_config::ProviderConfig;
 
     use super::{
         LazyCachingCredentialsProvider, TimeSource, DEFAULT_BUFFER_TIME,
         DEFAULT_CREDENTIAL_EXPIRATION, DEFAULT_LOAD_TIMEOUT,
     };
+    use aws_smithy_http::metrics::{CredentialsLoadMetrics, EmitMetrics};
     use aws_types::os_shim_internal::ManualTimeSource;
 
     fn test_provider(
@@ -331,6 +410,8 @@ mod tests {
             DEFAULT_LOAD_TIMEOUT,
             DEFAULT_CREDENTIAL_EXPIRATION,
             DEFAULT_BUFFER_TIME,
+            || 0.0,
+            None,
         )
     }
 
@@ -365,6 +446,8 @@ mod tests {
             DEFAULT_LOAD_TIMEOUT,
             DEFAULT_CREDENTIAL_EXPIRATION,
             DEFAULT_BUFFER_TIME,
+            || 0.0,
+            None,
         );
         assert_eq!(
             epoch_secs(1000),
@@ -478,6 +561,8 @@ mod tests {
             Duration::from_millis(5),
             DEFAULT_CREDENTIAL_EXPIRATION,
             DEFAULT_BUFFER_TIME,
+            || 0.0,
+            None,
         );
 
         assert!(matches!(
@@ -485,4 +570,73 @@ mod tests {
             Err(CredentialsError::ProviderTimedOut { .. })
         ));
     }
+
+    #[traced_test]
+    #[tokio::test]
+    async fn buffer_time_is_jittered() {
+        let mut time = ManualTimeSource::new(epoch_secs(100));
+        let load_list = Arc::new(Mutex::new(vec![
+            Ok(credentials(1000)),
+            Ok(credentials(2000)),
+        ]));
+        let provider = LazyCachingCredentialsProvider::builder()
+            .configure(
+                &ProviderConfig::no_configuration()
+                    .with_time_source(TimeSource::manual(&time))
+                    .with_sleep(TokioSleep::new()),
+            )
+            .load(provide_credentials_fn(move || {
+                let list = load_list.clone();
+                async move { list.lock().unwrap().remove(0) }
+            }))
+            .buffer_time(Duration::from_secs(10))
+            .buffer_time_jitter_fraction(|| 0.5)
+            .build();
+
+        expect_creds(1000, &provider).await;
+        // buffer time (10s) + jitter (5s) before expiry, the cached credentials are still valid
+        time.set_time(epoch_secs(984));
+        expect_creds(1000, &provider).await;
+        time.set_time(epoch_secs(986));
+        expect_creds(2000, &provider).await;
+    }
+
+    #[derive(Clone, Debug, Default)]
+    struct RecordLoads(Arc<Mutex<Vec<bool>>>);
+
+    impl EmitMetrics for RecordLoads {
+        fn emit_credentials_load(&self, metrics: &CredentialsLoadMetrics) {
+            self.0.lock().unwrap().push(metrics.success);
+        }
+    }
+
+    #[traced_test]
+    #[tokio::test]
+    async fn load_metrics_are_emitted() {
+        let mut time = ManualTimeSource::new(epoch_secs(100));
+        let load_list = Arc::new(Mutex::new(vec![
+            Ok(credentials(1000)),
+            Err(CredentialsError::not_loaded("failed")),
+        ]));
+        let loads = RecordLoads::default();
+        let provider = LazyCachingCredentialsProvider::builder()
+            .configure(
+                &ProviderConfig::no_configuration()
+                    .with_time_source(TimeSource::manual(&time))
+                    .with_sleep(TokioSleep::new()),
+            )
+            .load(provide_credentials_fn(move || {
+                let list = load_list.clone();
+                async move { list.lock().unwrap().remove(0) }
+            }))
+            .metrics_emitter(loads.clone())
+            .build();
+
+        expect_creds(1000, &provider).await;
+        // cached credentials don't cause a load
+        expect_creds(1000, &provider).await;
+        time.set_time(epoch_secs(1500));
+        assert!(provider.provide_credentials().await.is_err());
+        assert_eq!(vec![true, false], *loads.0.lock().unwrap());
+    }
 }
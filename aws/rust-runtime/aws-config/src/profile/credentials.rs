@@ -134,6 +134,15 @@ impl ProvideCredentials for ProfileFileCredentialsProvider {
 ///
 /// SSO can also be used as a source profile for assume role chains.
 ///
+/// ### Loading Credentials from an External Process
+/// ```ini
+/// [default]
+/// credential_process = /opt/bin/awscreds-retriever --username susan
+/// ```
+///
+/// The command must print credentials as JSON; see [`credential_process`](crate::credential_process)
+/// for the format. Like SSO, it can also be used as a source profile for assume role chains.
+///
 /// ## Location of Profile Files
 /// * The location of the config file will be loaded from the `AWS_CONFIG_FILE` environment variable
 /// with a fallback to `~/.aws/config`
@@ -11,6 +11,7 @@ use aws_types::region::Region;
 
 use super::repr::{self, BaseProvider};
 
+use crate::credential_process::CredentialProcessProvider;
 use crate::profile::credentials::ProfileFileError;
 use crate::provider_config::ProviderConfig;
 use crate::sso::{SsoConfig, SsoCredentialsProvider};
@@ -59,13 +60,16 @@ impl AssumeRoleProvider {
             .make_operation(&config)
             .await
             .expect("valid operation");
-        let assume_role_creds = client_config
+        let assumed = client_config
             .sts_client
             .call(operation)
             .await
-            .map_err(CredentialsError::provider_error)?
-            .credentials;
-        sts::util::into_credentials(assume_role_creds, "AssumeRoleProvider")
+            .map_err(CredentialsError::provider_error)?;
+        sts::util::into_credentials(
+            assumed.credentials,
+            assumed.assumed_role_user,
+            "AssumeRoleProvider",
+        )
     }
 }
 
@@ -117,7 +121,14 @@ impl ProviderChain {
                     .build();
                 Arc::new(provider)
             }
+            BaseProvider::CredentialProcess(command) => Arc::new(
+                CredentialProcessProvider::builder()
+                    .command(*command)
+                    .configure(provider_config)
+                    .build(),
+            ),
             BaseProvider::Sso {
+                sso_session_name,
                 sso_account_id,
                 sso_region,
                 sso_role_name,
@@ -128,6 +139,7 @@ impl ProviderChain {
                     role_name: sso_role_name.to_string(),
                     start_url: sso_start_url.to_string(),
                     region: Region::new(sso_region.to_string()),
+                    session_name: sso_session_name.map(|name| name.to_string()),
                 };
                 Arc::new(SsoCredentialsProvider::new(provider_config, sso_config))
             }
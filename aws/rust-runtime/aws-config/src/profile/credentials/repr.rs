@@ -75,8 +75,31 @@ pub enum BaseProvider<'a> {
         session_name: Option<&'a str>,
     },
 
+    /// A provider that runs an external process to load credentials
+    ///
+    /// Example
+    /// ```ini
+    /// [profile C]
+    /// credential_process = /opt/bin/awscreds-retriever --username susan
+    /// ```
+    CredentialProcess(&'a str),
+
     /// An SSO Provider
+    ///
+    /// The start URL and region may either be configured directly on the profile or by referencing
+    /// an `[sso-session]` section with `sso_session`:
+    /// ```ini
+    /// [profile C]
+    /// sso_session = my-sso
+    /// sso_account_id = 012345678901
+    /// sso_role_name = SampleRole
+    ///
+    /// [sso-session my-sso]
+    /// sso_region = us-east-1
+    /// sso_start_url = https://d-abc123.awsapps.com/start
+    /// ```
     Sso {
+        sso_session_name: Option<&'a str>,
         sso_account_id: &'a str,
         sso_region: &'a str,
         sso_role_name: &'a str,
@@ -154,7 +177,7 @@ pub fn resolve_chain<'a>(
                 chain.push(role_provider);
                 next
             } else {
-                break base_provider(profile).map_err(|err| {
+                break base_provider(profile_set, profile).map_err(|err| {
                     // It's possible for base_provider to return a `ProfileFileError::ProfileDidNotContainCredentials`
                     // if we're still looking at the first provider we want to surface it. However,
                     // if we're looking at any provider after the first we want to instead return a `ProfileFileError::InvalidCredentialSource`
@@ -175,7 +198,7 @@ pub fn resolve_chain<'a>(
                 // self referential profile, don't go through the loop because it will error
                 // on the infinite loop check. Instead, reload this profile as a base profile
                 // and exit.
-                break base_provider(profile)?;
+                break base_provider(profile_set, profile)?;
             }
             NextProfile::Named(name) => source_profile_name = name,
         }
@@ -198,25 +221,35 @@ mod sso {
     pub const REGION: &str = "sso_region";
     pub const ROLE_NAME: &str = "sso_role_name";
     pub const START_URL: &str = "sso_start_url";
+    pub const SESSION_NAME: &str = "sso_session";
 }
 
 mod web_identity_token {
     pub const TOKEN_FILE: &str = "web_identity_token_file";
 }
 
+mod credential_process {
+    pub const CREDENTIAL_PROCESS: &str = "credential_process";
+}
+
 mod static_credentials {
     pub const AWS_ACCESS_KEY_ID: &str = "aws_access_key_id";
     pub const AWS_SECRET_ACCESS_KEY: &str = "aws_secret_access_key";
     pub const AWS_SESSION_TOKEN: &str = "aws_session_token";
+    pub const AWS_ACCOUNT_ID: &str = "aws_account_id";
 }
 const PROVIDER_NAME: &str = "ProfileFile";
 
-fn base_provider(profile: &Profile) -> Result<BaseProvider, ProfileFileError> {
+fn base_provider<'a>(
+    profile_set: &'a ProfileSet,
+    profile: &'a Profile,
+) -> Result<BaseProvider<'a>, ProfileFileError> {
     // the profile must define either a `CredentialsSource` or a concrete set of access keys
     match profile.get(role::CREDENTIAL_SOURCE) {
         Some(source) => Ok(BaseProvider::NamedSource(source)),
         None => web_identity_token_from_profile(profile)
-            .or_else(|| sso_from_profile(profile))
+            .or_else(|| sso_from_profile(profile_set, profile))
+            .or_else(|| credential_process_from_profile(profile))
             .unwrap_or_else(|| Ok(BaseProvider::AccessKey(static_creds_from_profile(profile)?))),
     }
 }
@@ -268,7 +301,10 @@ fn role_arn_from_profile(profile: &Profile) -> Option<RoleArn> {
     })
 }
 
-fn sso_from_profile(profile: &Profile) -> Option<Result<BaseProvider, ProfileFileError>> {
+fn sso_from_profile<'a>(
+    profile_set: &'a ProfileSet,
+    profile: &'a Profile,
+) -> Option<Result<BaseProvider<'a>, ProfileFileError>> {
     /*
     Sample:
     [profile sample-profile]
@@ -276,12 +312,23 @@ fn sso_from_profile(profile: &Profile) -> Option<Result<BaseProvider, ProfileFil
     sso_region = us-east-1
     sso_role_name = SampleRole
     sso_start_url = https://d-abc123.awsapps.com/start-beta
+
+    or, using an sso-session:
+    [profile sample-profile]
+    sso_session = dev
+    sso_account_id = 012345678901
+    sso_role_name = SampleRole
+
+    [sso-session dev]
+    sso_region = us-east-1
+    sso_start_url = https://d-abc123.awsapps.com/start-beta
     */
+    let sso_session_name = profile.get(sso::SESSION_NAME);
     let account_id = profile.get(sso::ACCOUNT_ID);
     let region = profile.get(sso::REGION);
     let role_name = profile.get(sso::ROLE_NAME);
     let start_url = profile.get(sso::START_URL);
-    if [account_id, region, role_name, start_url]
+    if [sso_session_name, account_id, region, role_name, start_url]
         .iter()
         .all(|field| field.is_none())
     {
@@ -289,11 +336,31 @@ fn sso_from_profile(profile: &Profile) -> Option<Result<BaseProvider, ProfileFil
     }
     let missing_field = |s| move || ProfileFileError::missing_field(profile, s);
     let parse_profile = || {
+        let (region, start_url) = match sso_session_name {
+            Some(session_name) => {
+                let session = profile_set.sso_session(session_name).ok_or_else(|| {
+                    ProfileFileError::MissingProfile {
+                        profile: profile.name().to_string(),
+                        message: format!(
+                            "could not find sso-session `{}` referenced from profile",
+                            session_name
+                        )
+                        .into(),
+                    }
+                })?;
+                (
+                    merge_sso_session_field(profile, session, sso::REGION)?,
+                    merge_sso_session_field(profile, session, sso::START_URL)?,
+                )
+            }
+            None => (region, start_url),
+        };
         let sso_account_id = account_id.ok_or_else(missing_field(sso::ACCOUNT_ID))?;
         let sso_region = region.ok_or_else(missing_field(sso::REGION))?;
         let sso_role_name = role_name.ok_or_else(missing_field(sso::ROLE_NAME))?;
         let sso_start_url = start_url.ok_or_else(missing_field(sso::START_URL))?;
         Ok(BaseProvider::Sso {
+            sso_session_name,
             sso_account_id,
             sso_region,
             sso_role_name,
@@ -303,6 +370,39 @@ fn sso_from_profile(profile: &Profile) -> Option<Result<BaseProvider, ProfileFil
     Some(parse_profile())
 }
 
+/// Load `field` from an sso-session, erroring if the profile also sets it to a different value
+fn merge_sso_session_field<'a>(
+    profile: &'a Profile,
+    session: &'a Profile,
+    field: &'static str,
+) -> Result<Option<&'a str>, ProfileFileError> {
+    match (profile.get(field), session.get(field)) {
+        (Some(from_profile), Some(from_session)) if from_profile != from_session => {
+            Err(ProfileFileError::InvalidCredentialSource {
+                profile: profile.name().to_string(),
+                message: format!(
+                    "`{}` in the profile ({}) does not match `{}` in sso-session `{}` ({})",
+                    field,
+                    from_profile,
+                    field,
+                    session.name(),
+                    from_session
+                )
+                .into(),
+            })
+        }
+        (from_profile, from_session) => Ok(from_session.or(from_profile)),
+    }
+}
+
+fn credential_process_from_profile(
+    profile: &Profile,
+) -> Option<Result<BaseProvider, ProfileFileError>> {
+    profile
+        .get(credential_process::CREDENTIAL_PROCESS)
+        .map(|command| Ok(BaseProvider::CredentialProcess(command)))
+}
+
 fn web_identity_token_from_profile(
     profile: &Profile,
 ) -> Option<Result<BaseProvider, ProfileFileError>> {
@@ -354,13 +454,17 @@ fn static_creds_from_profile(profile: &Profile) -> Result<Credentials, ProfileFi
         message: "profile missing aws_secret_access_key".into(),
     })?;
     // There might not be an active session token so we don't error out if it's missing
-    Ok(Credentials::new(
+    let credentials = Credentials::new(
         access_key,
         secret_key,
         session_token.map(|s| s.to_string()),
         None,
         PROVIDER_NAME,
-    ))
+    );
+    match profile.get(AWS_ACCOUNT_ID) {
+        Some(account_id) => Ok(credentials.with_account_id(account_id)),
+        None => Ok(credentials),
+    }
 }
 
 #[cfg(test)]
@@ -385,7 +489,10 @@ mod tests {
     }
 
     fn check(test_case: TestCase) {
-        let source = ProfileSet::new(test_case.input.profile, test_case.input.selected_profile);
+        let mut source = ProfileSet::new(test_case.input.profile, test_case.input.selected_profile);
+        for (name, session) in test_case.input.sso_session {
+            source.insert_sso_session(name, session);
+        }
         let actual = resolve_chain(&source, None);
         let expected = test_case.output;
         match (expected, actual) {
@@ -415,6 +522,8 @@ mod tests {
     #[derive(Deserialize)]
     struct TestInput {
         profile: HashMap<String, HashMap<String, String>>,
+        #[serde(default)]
+        sso_session: HashMap<String, HashMap<String, String>>,
         selected_profile: String,
     }
 
@@ -436,12 +545,17 @@ mod tests {
                 web_identity_token_file: web_identity_token_file.into(),
                 role_session_name: session_name.map(|sess| sess.to_string()),
             }),
+            BaseProvider::CredentialProcess(command) => {
+                output.push(Provider::CredentialProcess(command.into()))
+            }
             BaseProvider::Sso {
+                sso_session_name,
                 sso_account_id,
                 sso_region,
                 sso_role_name,
                 sso_start_url,
             } => output.push(Provider::Sso {
+                sso_session_name: sso_session_name.map(|name| name.to_string()),
                 sso_account_id: sso_account_id.into(),
                 sso_region: sso_region.into(),
                 sso_role_name: sso_role_name.into(),
@@ -477,12 +591,14 @@ mod tests {
             session_token: Option<String>,
         },
         NamedSource(String),
+        CredentialProcess(String),
         WebIdentityToken {
             role_arn: String,
             web_identity_token_file: String,
             role_session_name: Option<String>,
         },
         Sso {
+            sso_session_name: Option<String>,
             sso_account_id: String,
             sso_region: String,
             sso_role_name: String,
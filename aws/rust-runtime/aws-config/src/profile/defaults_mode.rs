@@ -0,0 +1,147 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Load the defaults mode from an AWS profile
+
+use crate::defaults_mode::DefaultsMode;
+use crate::provider_config::ProviderConfig;
+use aws_types::os_shim_internal::{Env, Fs};
+
+const PROFILE_KEY_DEFAULTS_MODE: &str = "defaults_mode";
+
+/// Loads the defaults mode from a profile file
+///
+/// This provider will attempt to shared AWS shared configuration and then read the
+/// `defaults_mode` property from the active profile.
+///
+/// # Examples
+///
+/// **Selects defaults based on the environment the application runs in**
+/// ```ini
+/// [default]
+/// defaults_mode = auto
+/// ```
+///
+/// This provider is part of the [default defaults mode provider chain](crate::default_provider::defaults_mode).
+#[derive(Debug, Default)]
+pub struct ProfileFileDefaultsModeProvider {
+    fs: Fs,
+    env: Env,
+    profile_override: Option<String>,
+}
+
+impl ProfileFileDefaultsModeProvider {
+    /// Create a new [`ProfileFileDefaultsModeProvider`]
+    ///
+    /// To override the selected profile, set the `AWS_PROFILE` environment variable or use the [`Builder`].
+    pub fn new() -> Self {
+        Self {
+            fs: Fs::real(),
+            env: Env::real(),
+            profile_override: None,
+        }
+    }
+
+    /// [`Builder`] to construct a [`ProfileFileDefaultsModeProvider`]
+    pub fn builder() -> Builder {
+        Builder::default()
+    }
+
+    /// Parses the profile config and attempts to find the defaults mode.
+    pub async fn defaults_mode(&self) -> Option<DefaultsMode> {
+        let profile = super::parser::load(&self.fs, &self.env)
+            .await
+            .map_err(|err| tracing::warn!(err = %err, "failed to parse profile"))
+            .ok()?;
+        let selected_profile_name = self
+            .profile_override
+            .as_deref()
+            .unwrap_or_else(|| profile.selected_profile());
+        let selected_profile = profile.get_profile(selected_profile_name)?;
+        let value = selected_profile.get(PROFILE_KEY_DEFAULTS_MODE)?;
+        value
+            .parse()
+            .map_err(|err| {
+                tracing::warn!(
+                    err = %err,
+                    "invalid value for `{}` in profile `{}`",
+                    PROFILE_KEY_DEFAULTS_MODE,
+                    selected_profile_name
+                )
+            })
+            .ok()
+    }
+}
+
+/// Builder for [`ProfileFileDefaultsModeProvider`]
+#[derive(Default, Clone)]
+pub struct Builder {
+    config: Option<ProviderConfig>,
+    profile_override: Option<String>,
+}
+
+impl Builder {
+    /// Override the configuration for this provider
+    pub fn configure(mut self, config: &ProviderConfig) -> Self {
+        self.config = Some(config.clone());
+        self
+    }
+
+    /// Override the profile name used by the [`ProfileFileDefaultsModeProvider`]
+    pub fn profile_name(mut self, profile_name: impl Into<String>) -> Self {
+        self.profile_override = Some(profile_name.into());
+        self
+    }
+
+    /// Build a [`ProfileFileDefaultsModeProvider`] from this builder
+    pub fn build(self) -> ProfileFileDefaultsModeProvider {
+        let conf = self.config.unwrap_or_default();
+        ProfileFileDefaultsModeProvider {
+            env: conf.env(),
+            fs: conf.fs(),
+            profile_override: self.profile_override,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ProfileFileDefaultsModeProvider;
+    use crate::defaults_mode::DefaultsMode;
+    use crate::provider_config::ProviderConfig;
+    use crate::test_case::no_traffic_connector;
+    use aws_types::os_shim_internal::{Env, Fs};
+
+    fn provider(config_contents: &str) -> ProfileFileDefaultsModeProvider {
+        let fs = Fs::from_slice(&[("test_config", config_contents)]);
+        let env = Env::from_slice(&[("AWS_CONFIG_FILE", "test_config")]);
+        ProfileFileDefaultsModeProvider::builder()
+            .configure(
+                &ProviderConfig::empty()
+                    .with_fs(fs)
+                    .with_env(env)
+                    .with_http_connector(no_traffic_connector()),
+            )
+            .build()
+    }
+
+    #[tokio::test]
+    async fn not_set() {
+        let provider = provider("[default]\n");
+        assert_eq!(None, provider.defaults_mode().await);
+    }
+
+    #[tokio::test]
+    async fn set() {
+        let provider = provider("[default]\ndefaults_mode = mobile");
+        assert_eq!(Some(DefaultsMode::Mobile), provider.defaults_mode().await);
+    }
+
+    #[tokio::test]
+    async fn invalid_value_is_ignored() {
+        let provider = provider("[default]\ndefaults_mode = fast");
+        assert_eq!(None, provider.defaults_mode().await);
+    }
+}
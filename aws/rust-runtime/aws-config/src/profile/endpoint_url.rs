@@ -0,0 +1,169 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Load an endpoint URL from an AWS profile
+
+use crate::environment::endpoint_url::parse_endpoint_url;
+use crate::provider_config::ProviderConfig;
+use aws_types::os_shim_internal::{Env, Fs};
+use http::Uri;
+
+const PROFILE_KEY_ENDPOINT_URL: &str = "endpoint_url";
+
+/// Loads an endpoint URL from a profile file
+///
+/// This provider will attempt to shared AWS shared configuration and then read the
+/// `endpoint_url` property from the active profile. The endpoint URL is used for all services.
+///
+/// # Examples
+///
+/// **Sends requests for all services to `http://localhost:4566`**
+/// ```ini
+/// [default]
+/// endpoint_url = http://localhost:4566
+/// ```
+///
+/// This provider is part of the [default endpoint URL provider chain](crate::default_provider::endpoint_url).
+#[derive(Debug, Default)]
+pub struct ProfileFileEndpointUrlProvider {
+    fs: Fs,
+    env: Env,
+    profile_override: Option<String>,
+}
+
+impl ProfileFileEndpointUrlProvider {
+    /// Create a new [`ProfileFileEndpointUrlProvider`]
+    ///
+    /// To override the selected profile, set the `AWS_PROFILE` environment variable or use the [`Builder`].
+    pub fn new() -> Self {
+        Self {
+            fs: Fs::real(),
+            env: Env::real(),
+            profile_override: None,
+        }
+    }
+
+    /// [`Builder`] to construct a [`ProfileFileEndpointUrlProvider`]
+    pub fn builder() -> Builder {
+        Builder::default()
+    }
+
+    /// Parses the profile config and attempts to find an endpoint URL.
+    pub async fn endpoint_url(&self) -> Option<Uri> {
+        let profile = super::parser::load(&self.fs, &self.env)
+            .await
+            .map_err(|err| tracing::warn!(err = %err, "failed to parse profile"))
+            .ok()?;
+        let selected_profile_name = self
+            .profile_override
+            .as_deref()
+            .unwrap_or_else(|| profile.selected_profile());
+        let selected_profile = profile.get_profile(selected_profile_name)?;
+        selected_profile
+            .get(PROFILE_KEY_ENDPOINT_URL)
+            .and_then(|url| {
+                parse_endpoint_url(
+                    &format!(
+                        "`{}` in profile `{}`",
+                        PROFILE_KEY_ENDPOINT_URL, selected_profile_name
+                    ),
+                    url,
+                )
+            })
+    }
+}
+
+/// Builder for [`ProfileFileEndpointUrlProvider`]
+#[derive(Default, Clone)]
+pub struct Builder {
+    config: Option<ProviderConfig>,
+    profile_override: Option<String>,
+}
+
+impl Builder {
+    /// Override the configuration for this provider
+    pub fn configure(mut self, config: &ProviderConfig) -> Self {
+        self.config = Some(config.clone());
+        self
+    }
+
+    /// Override the profile name used by the [`ProfileFileEndpointUrlProvider`]
+    pub fn profile_name(mut self, profile_name: impl Into<String>) -> Self {
+        self.profile_override = Some(profile_name.into());
+        self
+    }
+
+    /// Build a [`ProfileFileEndpointUrlProvider`] from this builder
+    pub fn build(self) -> ProfileFileEndpointUrlProvider {
+        let conf = self.config.unwrap_or_default();
+        ProfileFileEndpointUrlProvider {
+            env: conf.env(),
+            fs: conf.fs(),
+            profile_override: self.profile_override,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ProfileFileEndpointUrlProvider;
+    use crate::provider_config::ProviderConfig;
+    use crate::test_case::no_traffic_connector;
+    use aws_types::os_shim_internal::{Env, Fs};
+    use http::Uri;
+    use tracing_test::traced_test;
+
+    fn provider_config(config_contents: &str) -> ProviderConfig {
+        let fs = Fs::from_slice(&[("test_config", config_contents)]);
+        let env = Env::from_slice(&[("AWS_CONFIG_FILE", "test_config")]);
+        ProviderConfig::empty()
+            .with_fs(fs)
+            .with_env(env)
+            .with_http_connector(no_traffic_connector())
+    }
+
+    fn default_provider(config_contents: &str) -> ProfileFileEndpointUrlProvider {
+        ProfileFileEndpointUrlProvider::builder()
+            .configure(&provider_config(config_contents))
+            .build()
+    }
+
+    #[tokio::test]
+    async fn no_endpoint_url() {
+        assert_eq!(None, default_provider("[default]\n").endpoint_url().await);
+    }
+
+    #[tokio::test]
+    async fn endpoint_url_other_profile() {
+        let config = "\
+            [default]\n\
+            endpoint_url = http://localhost:4566\n\
+            \n\
+            [profile other]\n\
+            endpoint_url = https://vpce-123.example.com\n
+        ";
+        assert_eq!(
+            Some(Uri::from_static("https://vpce-123.example.com")),
+            ProfileFileEndpointUrlProvider::builder()
+                .profile_name("other")
+                .configure(&provider_config(config))
+                .build()
+                .endpoint_url()
+                .await
+        );
+    }
+
+    #[traced_test]
+    #[tokio::test]
+    async fn invalid_endpoint_url() {
+        assert_eq!(
+            None,
+            default_provider("[default]\nendpoint_url = localhost")
+                .endpoint_url()
+                .await
+        );
+        assert!(logs_contain("endpoint URL `localhost`"));
+    }
+}
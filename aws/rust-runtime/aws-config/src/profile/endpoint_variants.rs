@@ -0,0 +1,183 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Load the FIPS and dual-stack endpoint settings from an AWS profile
+
+use super::parser::ProfileSet;
+use crate::environment::endpoint_variants::parse_bool;
+use crate::provider_config::ProviderConfig;
+use aws_types::os_shim_internal::{Env, Fs};
+use tokio::sync::OnceCell;
+
+const PROFILE_KEY_USE_FIPS: &str = "use_fips_endpoint";
+const PROFILE_KEY_USE_DUAL_STACK: &str = "use_dualstack_endpoint";
+
+/// Loads the FIPS and dual-stack endpoint settings from a profile file
+///
+/// This provider will attempt to shared AWS shared configuration and then read the
+/// `use_fips_endpoint` and `use_dualstack_endpoint` properties from the active profile.
+///
+/// # Examples
+///
+/// **Uses FIPS endpoints for all services**
+/// ```ini
+/// [default]
+/// use_fips_endpoint = true
+/// ```
+///
+/// The profile file is only parsed once, so loading both settings from the same provider reads
+/// the file a single time.
+///
+/// This provider is part of the [default endpoint variants provider chain](crate::default_provider::endpoint_variants).
+#[derive(Debug, Default)]
+pub struct ProfileFileEndpointVariantsProvider {
+    fs: Fs,
+    env: Env,
+    profile_override: Option<String>,
+    profile: OnceCell<Option<ProfileSet>>,
+}
+
+impl ProfileFileEndpointVariantsProvider {
+    /// Create a new [`ProfileFileEndpointVariantsProvider`]
+    ///
+    /// To override the selected profile, set the `AWS_PROFILE` environment variable or use the [`Builder`].
+    pub fn new() -> Self {
+        Self {
+            fs: Fs::real(),
+            env: Env::real(),
+            profile_override: None,
+            profile: OnceCell::new(),
+        }
+    }
+
+    /// [`Builder`] to construct a [`ProfileFileEndpointVariantsProvider`]
+    pub fn builder() -> Builder {
+        Builder::default()
+    }
+
+    /// Parses the profile config and attempts to find whether to use FIPS endpoints.
+    pub async fn use_fips(&self) -> Option<bool> {
+        self.load_bool(PROFILE_KEY_USE_FIPS).await
+    }
+
+    /// Parses the profile config and attempts to find whether to use dual-stack endpoints.
+    pub async fn use_dual_stack(&self) -> Option<bool> {
+        self.load_bool(PROFILE_KEY_USE_DUAL_STACK).await
+    }
+
+    async fn load_bool(&self, key: &str) -> Option<bool> {
+        let profile = self
+            .profile
+            .get_or_init(|| async {
+                super::parser::load(&self.fs, &self.env)
+                    .await
+                    .map_err(|err| tracing::warn!(err = %err, "failed to parse profile"))
+                    .ok()
+            })
+            .await
+            .as_ref()?;
+        let selected_profile_name = self
+            .profile_override
+            .as_deref()
+            .unwrap_or_else(|| profile.selected_profile());
+        let selected_profile = profile.get_profile(selected_profile_name)?;
+        selected_profile.get(key).and_then(|value| {
+            parse_bool(
+                &format!("`{}` in profile `{}`", key, selected_profile_name),
+                value,
+            )
+        })
+    }
+}
+
+/// Builder for [`ProfileFileEndpointVariantsProvider`]
+#[derive(Default, Clone)]
+pub struct Builder {
+    config: Option<ProviderConfig>,
+    profile_override: Option<String>,
+}
+
+impl Builder {
+    /// Override the configuration for this provider
+    pub fn configure(mut self, config: &ProviderConfig) -> Self {
+        self.config = Some(config.clone());
+        self
+    }
+
+    /// Override the profile name used by the [`ProfileFileEndpointVariantsProvider`]
+    pub fn profile_name(mut self, profile_name: impl Into<String>) -> Self {
+        self.profile_override = Some(profile_name.into());
+        self
+    }
+
+    /// Build a [`ProfileFileEndpointVariantsProvider`] from this builder
+    pub fn build(self) -> ProfileFileEndpointVariantsProvider {
+        let conf = self.config.unwrap_or_default();
+        ProfileFileEndpointVariantsProvider {
+            env: conf.env(),
+            fs: conf.fs(),
+            profile_override: self.profile_override,
+            profile: OnceCell::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ProfileFileEndpointVariantsProvider;
+    use crate::provider_config::ProviderConfig;
+    use crate::test_case::no_traffic_connector;
+    use aws_types::os_shim_internal::{Env, Fs};
+
+    fn provider(config_contents: &str) -> ProfileFileEndpointVariantsProvider {
+        let fs = Fs::from_slice(&[("test_config", config_contents)]);
+        let env = Env::from_slice(&[("AWS_CONFIG_FILE", "test_config")]);
+        ProfileFileEndpointVariantsProvider::builder()
+            .configure(
+                &ProviderConfig::empty()
+                    .with_fs(fs)
+                    .with_env(env)
+                    .with_http_connector(no_traffic_connector()),
+            )
+            .build()
+    }
+
+    #[tokio::test]
+    async fn not_set() {
+        let provider = provider("[default]\n");
+        assert_eq!(None, provider.use_fips().await);
+        assert_eq!(None, provider.use_dual_stack().await);
+    }
+
+    #[tokio::test]
+    async fn set() {
+        let provider =
+            provider("[default]\nuse_fips_endpoint = true\nuse_dualstack_endpoint = false");
+        assert_eq!(Some(true), provider.use_fips().await);
+        assert_eq!(Some(false), provider.use_dual_stack().await);
+    }
+
+    #[tokio::test]
+    async fn profile_is_parsed_once() {
+        let fs = Fs::from_slice(&[(
+            "test_config",
+            "[default]\nuse_fips_endpoint = true\nuse_dualstack_endpoint = true",
+        )]);
+        let env = Env::from_slice(&[("AWS_CONFIG_FILE", "test_config")]);
+        let provider = ProfileFileEndpointVariantsProvider::builder()
+            .configure(
+                &ProviderConfig::empty()
+                    .with_fs(fs.clone())
+                    .with_env(env)
+                    .with_http_connector(no_traffic_connector()),
+            )
+            .build();
+        assert_eq!(Some(true), provider.use_fips().await);
+        fs.write("test_config", "[default]\n")
+            .await
+            .expect("in-memory write succeeds");
+        assert_eq!(Some(true), provider.use_dual_stack().await);
+    }
+}
@@ -14,8 +14,12 @@ pub use parser::{load, Profile, ProfileParseError, ProfileSet, Property};
 
 pub mod app_name;
 pub mod credentials;
+pub mod defaults_mode;
+pub mod endpoint_url;
+pub mod endpoint_variants;
 pub mod region;
 pub mod retry_config;
+pub mod sts_regional_endpoints;
 pub mod timeout_config;
 
 #[doc(inline)]
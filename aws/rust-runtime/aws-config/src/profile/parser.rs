@@ -75,6 +75,7 @@ pub async fn load(fs: &Fs, env: &Env) -> Result<ProfileSet, ProfileParseError> {
 #[derive(Debug, Eq, Clone, PartialEq)]
 pub struct ProfileSet {
     profiles: HashMap<String, Profile>,
+    sso_sessions: HashMap<String, Profile>,
     selected_profile: Cow<'static, str>,
 }
 
@@ -110,6 +111,21 @@ impl ProfileSet {
         base
     }
 
+    /// Add an `[sso-session <name>]` section directly to the profile set with no normalization
+    #[cfg(test)]
+    pub(crate) fn insert_sso_session(&mut self, name: String, session: HashMap<String, String>) {
+        self.sso_sessions.insert(
+            name.clone(),
+            Profile::new(
+                name,
+                session
+                    .into_iter()
+                    .map(|(k, v)| (k.clone(), Property::new(k, v)))
+                    .collect(),
+            ),
+        );
+    }
+
     /// Retrieves a key-value pair from the currently selected profile
     pub fn get(&self, key: &str) -> Option<&str> {
         self.profiles
@@ -122,6 +138,11 @@ impl ProfileSet {
         self.profiles.get(profile_name)
     }
 
+    /// Retrieves a named `[sso-session <name>]` section from the profile set
+    pub fn sso_session(&self, session_name: &str) -> Option<&Profile> {
+        self.sso_sessions.get(session_name)
+    }
+
     /// Returns the name of the currently selected profile
     pub fn selected_profile(&self) -> &str {
         self.selected_profile.as_ref()
@@ -157,6 +178,7 @@ impl ProfileSet {
     fn empty() -> Self {
         Self {
             profiles: Default::default(),
+            sso_sessions: Default::default(),
             selected_profile: "default".into(),
         }
     }
@@ -12,6 +12,7 @@ use crate::profile::{Profile, ProfileSet, Property};
 
 const DEFAULT: &str = "default";
 const PROFILE_PREFIX: &str = "profile";
+const SSO_SESSION_PREFIX: &str = "sso-session";
 
 #[derive(Eq, PartialEq, Hash, Debug)]
 struct ProfileName<'a> {
@@ -61,6 +62,19 @@ impl ProfileName<'_> {
     }
 }
 
+/// Parse the name of an `[sso-session <name>]` section
+///
+/// Returns `None` if `input` is not an sso-session section header.
+fn sso_session_name(input: &str) -> Option<&str> {
+    match input
+        .trim_matches(WHITESPACE)
+        .strip_prefix(SSO_SESSION_PREFIX)
+    {
+        Some(stripped) if stripped.starts_with(WHITESPACE) => Some(stripped.trim()),
+        _ => None,
+    }
+}
+
 /// Normalize a raw profile into a `MergedProfile`
 ///
 /// This function follows the following rules, codified in the tests & the reference Java implementation
@@ -68,7 +82,30 @@ impl ProfileName<'_> {
 /// - Profile names are validated (see `validate_profile_name`)
 /// - A profile named `profile default` takes priority over a profile named `default`.
 /// - Profiles with identical names are merged
+/// - In config files, `[sso-session <name>]` sections are collected separately from profiles
 pub fn merge_in(base: &mut ProfileSet, raw_profile_set: RawProfileSet, kind: FileKind) {
+    // sso-session sections are only valid in the config file
+    let (sso_sessions, raw_profile_set): (Vec<_>, Vec<_>) =
+        raw_profile_set.into_iter().partition(|(name, _)| {
+            matches!(kind, FileKind::Config) && sso_session_name(name).is_some()
+        });
+    for (name, raw_session) in sso_sessions {
+        let session_name = sso_session_name(name).expect("partitioned above");
+        if validate_identifier(session_name).is_err() {
+            tracing::warn!(
+                "sso-session `{}` ignored because `{}` was not a valid identifier",
+                session_name,
+                session_name
+            );
+            continue;
+        }
+        let session = base
+            .sso_sessions
+            .entry(session_name.to_string())
+            .or_insert_with(|| Profile::new(session_name.to_string(), Default::default()));
+        merge_into_base(session, raw_session)
+    }
+
     // parse / validate profile names
     let validated_profiles = raw_profile_set
         .into_iter()
@@ -238,4 +275,27 @@ mod tests {
         merge_in(&mut ProfileSet::empty(), profile, FileKind::Config);
         assert!(logs_contain("profile `foo` ignored"));
     }
+
+    #[test]
+    fn sso_sessions_are_separate_from_profiles() {
+        let mut profile: RawProfileSet = HashMap::new();
+        profile.insert("sso-session   my-session ", {
+            let mut out = HashMap::new();
+            out.insert("sso_region", "us-east-1".into());
+            out
+        });
+        let mut base = ProfileSet::empty();
+        merge_in(&mut base, profile.clone(), FileKind::Config);
+        assert_eq!(
+            Some("us-east-1"),
+            base.sso_session("my-session")
+                .and_then(|session| session.get("sso_region"))
+        );
+        assert!(base.get_profile("my-session").is_none());
+
+        // sso-session sections are not supported in the credentials file
+        let mut base = ProfileSet::empty();
+        merge_in(&mut base, profile, FileKind::Credentials);
+        assert!(base.sso_session("my-session").is_none());
+    }
 }
@@ -3,7 +3,7 @@
  * SPDX-License-Identifier: Apache-2.0.
  */
 
-use crate::fs_util::{home_dir, Os};
+use crate::fs_util::home_dir;
 use aws_types::os_shim_internal;
 use std::borrow::Cow;
 use std::io::ErrorKind;
@@ -54,7 +54,7 @@ impl FileKind {
 
 /// Load a [Source](Source) from a given environment and filesystem.
 pub async fn load(proc_env: &os_shim_internal::Env, fs: &os_shim_internal::Fs) -> Source {
-    let home = home_dir(proc_env, Os::real());
+    let home = home_dir(proc_env);
     let config = load_config_file(FileKind::Config, &home, fs, proc_env)
         .instrument(tracing::debug_span!("load_config_file"))
         .await;
@@ -190,7 +190,7 @@ fn check_is_likely_running_on_a_lambda(environment: &aws_types::os_shim_internal
 #[cfg(test)]
 mod tests {
     use crate::profile::parser::source::{expand_home, load, load_config_file, FileKind};
-    use aws_types::os_shim_internal::{Env, Fs};
+    use aws_types::os_shim_internal::{Env, Fs, Os};
     use serde::Deserialize;
     use std::collections::HashMap;
     use std::error::Error;
@@ -258,6 +258,28 @@ mod tests {
         assert!(logs_contain("performing home directory substitution"));
     }
 
+    #[test]
+    fn home_directory_depends_on_the_environments_os() {
+        let vars = [("USERPROFILE", "C:\\Users\\name")];
+        let fs = Fs::from_slice(&[]);
+
+        let windows = Env::from_slice(&vars).with_os(Os::Windows);
+        let source = load(&windows, &fs).now_or_never().unwrap();
+        assert!(
+            source.config_file.path.starts_with("C:\\Users\\name"),
+            "{}",
+            source.config_file.path
+        );
+
+        let not_windows = Env::from_slice(&vars).with_os(Os::NotWindows);
+        let source = load(&not_windows, &fs).now_or_never().unwrap();
+        assert!(
+            source.config_file.path.starts_with('~'),
+            "{}",
+            source.config_file.path
+        );
+    }
+
     #[traced_test]
     #[test]
     fn load_config_file_should_not_emit_warning_on_lambda() {
@@ -0,0 +1,150 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Load the STS regional endpoints setting from an AWS profile
+
+use crate::provider_config::ProviderConfig;
+use aws_types::endpoint::StsRegionalEndpoints;
+use aws_types::os_shim_internal::{Env, Fs};
+
+const PROFILE_KEY_STS_REGIONAL_ENDPOINTS: &str = "sts_regional_endpoints";
+
+/// Loads the STS regional endpoints setting from a profile file
+///
+/// This provider will attempt to shared AWS shared configuration and then read the
+/// `sts_regional_endpoints` property from the active profile.
+///
+/// # Examples
+///
+/// **Sends STS requests in the legacy global regions to the global endpoint**
+/// ```ini
+/// [default]
+/// sts_regional_endpoints = legacy
+/// ```
+///
+/// This provider is part of the [default STS regional endpoints provider chain](crate::default_provider::sts_regional_endpoints).
+#[derive(Debug, Default)]
+pub struct ProfileFileStsRegionalEndpointsProvider {
+    fs: Fs,
+    env: Env,
+    profile_override: Option<String>,
+}
+
+impl ProfileFileStsRegionalEndpointsProvider {
+    /// Create a new [`ProfileFileStsRegionalEndpointsProvider`]
+    ///
+    /// To override the selected profile, set the `AWS_PROFILE` environment variable or use the [`Builder`].
+    pub fn new() -> Self {
+        Self {
+            fs: Fs::real(),
+            env: Env::real(),
+            profile_override: None,
+        }
+    }
+
+    /// [`Builder`] to construct a [`ProfileFileStsRegionalEndpointsProvider`]
+    pub fn builder() -> Builder {
+        Builder::default()
+    }
+
+    /// Parses the profile config and attempts to find the STS regional endpoints setting.
+    pub async fn sts_regional_endpoints(&self) -> Option<StsRegionalEndpoints> {
+        let profile = super::parser::load(&self.fs, &self.env)
+            .await
+            .map_err(|err| tracing::warn!(err = %err, "failed to parse profile"))
+            .ok()?;
+        let selected_profile_name = self
+            .profile_override
+            .as_deref()
+            .unwrap_or_else(|| profile.selected_profile());
+        let selected_profile = profile.get_profile(selected_profile_name)?;
+        let value = selected_profile.get(PROFILE_KEY_STS_REGIONAL_ENDPOINTS)?;
+        value
+            .parse()
+            .map_err(|err| {
+                tracing::warn!(
+                    err = %err,
+                    "invalid value for `{}` in profile `{}`",
+                    PROFILE_KEY_STS_REGIONAL_ENDPOINTS,
+                    selected_profile_name
+                )
+            })
+            .ok()
+    }
+}
+
+/// Builder for [`ProfileFileStsRegionalEndpointsProvider`]
+#[derive(Default, Clone)]
+pub struct Builder {
+    config: Option<ProviderConfig>,
+    profile_override: Option<String>,
+}
+
+impl Builder {
+    /// Override the configuration for this provider
+    pub fn configure(mut self, config: &ProviderConfig) -> Self {
+        self.config = Some(config.clone());
+        self
+    }
+
+    /// Override the profile name used by the [`ProfileFileStsRegionalEndpointsProvider`]
+    pub fn profile_name(mut self, profile_name: impl Into<String>) -> Self {
+        self.profile_override = Some(profile_name.into());
+        self
+    }
+
+    /// Build a [`ProfileFileStsRegionalEndpointsProvider`] from this builder
+    pub fn build(self) -> ProfileFileStsRegionalEndpointsProvider {
+        let conf = self.config.unwrap_or_default();
+        ProfileFileStsRegionalEndpointsProvider {
+            env: conf.env(),
+            fs: conf.fs(),
+            profile_override: self.profile_override,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ProfileFileStsRegionalEndpointsProvider;
+    use crate::provider_config::ProviderConfig;
+    use crate::test_case::no_traffic_connector;
+    use aws_types::endpoint::StsRegionalEndpoints;
+    use aws_types::os_shim_internal::{Env, Fs};
+
+    fn provider(config_contents: &str) -> ProfileFileStsRegionalEndpointsProvider {
+        let fs = Fs::from_slice(&[("test_config", config_contents)]);
+        let env = Env::from_slice(&[("AWS_CONFIG_FILE", "test_config")]);
+        ProfileFileStsRegionalEndpointsProvider::builder()
+            .configure(
+                &ProviderConfig::empty()
+                    .with_fs(fs)
+                    .with_env(env)
+                    .with_http_connector(no_traffic_connector()),
+            )
+            .build()
+    }
+
+    #[tokio::test]
+    async fn not_set() {
+        let provider = provider("[default]\n");
+        assert_eq!(None, provider.sts_regional_endpoints().await);
+    }
+
+    #[tokio::test]
+    async fn set() {
+        let provider = provider("[default]\nsts_regional_endpoints = regional");
+        assert_eq!(
+            Some(StsRegionalEndpoints::Regional),
+            provider.sts_regional_endpoints().await
+        );
+    }
+
+    #[tokio::test]
+    async fn invalid_value_is_ignored() {
+        let provider = provider("[default]\nsts_regional_endpoints = global");
+        assert_eq!(None, provider.sts_regional_endpoints().await);
+    }
+}
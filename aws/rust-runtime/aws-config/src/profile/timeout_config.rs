@@ -16,11 +16,11 @@ use aws_types::os_shim_internal::{Env, Fs};
 use std::time::Duration;
 
 // Currently unsupported timeouts
-const PROFILE_VAR_CONNECT_TIMEOUT: &str = "connect_timeout";
 const PROFILE_VAR_TLS_NEGOTIATION_TIMEOUT: &str = "tls_negotiation_timeout";
-const PROFILE_VAR_READ_TIMEOUT: &str = "read_timeout";
 
 // Supported timeouts
+const PROFILE_VAR_CONNECT_TIMEOUT: &str = "connect_timeout";
+const PROFILE_VAR_READ_TIMEOUT: &str = "read_timeout";
 const PROFILE_VAR_API_CALL_ATTEMPT_TIMEOUT: &str = "api_call_attempt_timeout";
 const PROFILE_VAR_API_CALL_TIMEOUT: &str = "api_call_timeout";
 
@@ -38,6 +38,8 @@ const PROFILE_VAR_API_CALL_TIMEOUT: &str = "api_call_timeout";
 /// [default]
 /// api_call_attempt_timeout = 2
 /// api_call_timeout = 3
+/// connect_timeout = 1
+/// read_timeout = 1.5
 /// ```
 ///
 /// **Sets the `api_call_attempt_timeout` to 0.5 seconds _if and only if_ the `other` profile is selected.**
@@ -134,14 +136,12 @@ impl ProfileFileTimeoutConfigProvider {
         };
 
         // Warn users that set unsupported timeouts in their profile
-        for timeout in [
-            PROFILE_VAR_CONNECT_TIMEOUT,
-            PROFILE_VAR_TLS_NEGOTIATION_TIMEOUT,
-            PROFILE_VAR_READ_TIMEOUT,
-        ] {
-            warn_if_unsupported_timeout_is_set(selected_profile, timeout);
-        }
+        warn_if_unsupported_timeout_is_set(selected_profile, PROFILE_VAR_TLS_NEGOTIATION_TIMEOUT);
 
+        let connect_timeout =
+            construct_timeout_from_profile_var(selected_profile, PROFILE_VAR_CONNECT_TIMEOUT)?;
+        let read_timeout =
+            construct_timeout_from_profile_var(selected_profile, PROFILE_VAR_READ_TIMEOUT)?;
         let api_call_attempt_timeout = construct_timeout_from_profile_var(
             selected_profile,
             PROFILE_VAR_API_CALL_ATTEMPT_TIMEOUT,
@@ -153,8 +153,13 @@ impl ProfileFileTimeoutConfigProvider {
             .with_call_timeout(api_call_timeout)
             .with_call_attempt_timeout(api_call_attempt_timeout);
 
-        // Only API-related timeouts are currently supported
-        Ok(timeout::Config::new().with_api_timeouts(api_timeouts))
+        let http_timeouts = timeout::Http::new()
+            .with_connect_timeout(connect_timeout)
+            .with_read_timeout(read_timeout);
+
+        Ok(timeout::Config::new()
+            .with_api_timeouts(api_timeouts)
+            .with_http_timeouts(http_timeouts))
     }
 }
 
@@ -7,6 +7,7 @@
 
 use aws_smithy_async::rt::sleep::{default_async_sleep, AsyncSleep};
 use aws_smithy_client::erase::DynConnector;
+use aws_smithy_client::tls::TlsError;
 use aws_types::os_shim_internal::{Env, Fs, TimeSource};
 use aws_types::{
     http_connector::{HttpConnector, HttpSettings},
@@ -17,7 +18,7 @@ use std::error::Error;
 use std::fmt::{Debug, Formatter};
 use std::sync::Arc;
 
-use crate::connector::default_connector;
+use crate::connector::{default_connector, try_default_connector};
 use http::Uri;
 use hyper::client::connect::Connection;
 use tokio::io::{AsyncRead, AsyncWrite};
@@ -36,6 +37,8 @@ pub struct ProviderConfig {
     fs: Fs,
     time_source: TimeSource,
     connector: HttpConnector,
+    /// True while `connector` is the default connector, which can report invalid TLS contexts
+    uses_default_connector: bool,
     sleep: Option<Arc<dyn AsyncSleep>>,
     region: Option<Region>,
 }
@@ -64,6 +67,7 @@ impl Default for ProviderConfig {
             fs: Fs::default(),
             time_source: TimeSource::default(),
             connector,
+            uses_default_connector: true,
             sleep: default_async_sleep(),
             region: None,
         }
@@ -85,6 +89,7 @@ impl ProviderConfig {
             fs: Fs::from_raw_map(HashMap::new()),
             time_source: TimeSource::manual(&ManualTimeSource::new(UNIX_EPOCH)),
             connector: HttpConnector::Prebuilt(None),
+            uses_default_connector: false,
             sleep: None,
             region: None,
         }
@@ -124,6 +129,7 @@ impl ProviderConfig {
             fs: Fs::default(),
             time_source: TimeSource::default(),
             connector: HttpConnector::Prebuilt(None),
+            uses_default_connector: false,
             sleep: None,
             region: None,
         }
@@ -173,6 +179,22 @@ impl ProviderConfig {
         self.connector.connector(settings, self.sleep.clone())
     }
 
+    /// Like [`connector`](Self::connector), but returns an error if the TLS context of `settings`
+    /// is rejected by the default connector
+    ///
+    /// Custom connectors don't use the TLS context, so they never fail.
+    #[allow(dead_code)]
+    pub(crate) fn try_connector(
+        &self,
+        settings: &HttpSettings,
+    ) -> Result<Option<DynConnector>, TlsError> {
+        if self.uses_default_connector {
+            try_default_connector(settings, self.sleep.clone())
+        } else {
+            Ok(self.connector(settings))
+        }
+    }
+
     #[allow(dead_code)]
     pub(crate) fn sleep(&self) -> Option<Arc<dyn AsyncSleep>> {
         self.sleep.clone()
@@ -229,6 +251,7 @@ impl ProviderConfig {
     pub fn with_http_connector(self, connector: DynConnector) -> Self {
         ProviderConfig {
             connector: HttpConnector::Prebuilt(Some(connector)),
+            uses_default_connector: false,
             ..self
         }
     }
@@ -257,6 +280,7 @@ impl ProviderConfig {
         };
         ProviderConfig {
             connector: HttpConnector::ConnectorFn(Arc::new(connector_fn)),
+            uses_default_connector: false,
             ..self
         }
     }
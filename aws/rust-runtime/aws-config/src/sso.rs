@@ -8,19 +8,25 @@
 //! This credentials provider enables loading credentials from `~/.aws/sso/cache`. For more information,
 //! see [Using AWS SSO Credentials](https://docs.aws.amazon.com/toolkit-for-vscode/latest/userguide/sso-credentials.html)
 //!
+//! When the profile uses an `sso_session`, cached tokens that have expired (or are about to expire)
+//! are refreshed with the SSO OIDC `CreateToken` API if the cache contains a refresh token. Refreshed
+//! tokens are written back to the cache so that other processes can use them.
+//!
 //! This provider is included automatically when profiles are loaded.
 
-use crate::fs_util::{home_dir, Os};
+use crate::fs_util::home_dir;
 use crate::json_credentials::{json_parse_loop, InvalidJsonCredentials};
 use crate::provider_config::ProviderConfig;
 
 use aws_sdk_sso::middleware::DefaultMiddleware as SsoMiddleware;
 use aws_sdk_sso::model::RoleCredentials;
+use aws_sdk_ssooidc::middleware::DefaultMiddleware as SsoOidcMiddleware;
 use aws_smithy_client::erase::DynConnector;
+use aws_smithy_json::serialize::JsonObjectWriter;
 use aws_smithy_types::date_time::Format;
 use aws_smithy_types::DateTime;
 use aws_types::credentials::{CredentialsError, ProvideCredentials};
-use aws_types::os_shim_internal::{Env, Fs};
+use aws_types::os_shim_internal::{Env, Fs, TimeSource};
 use aws_types::region::Region;
 use aws_types::{credentials, Credentials};
 
@@ -29,6 +35,8 @@ use std::error::Error;
 use std::fmt::{Display, Formatter};
 use std::io;
 use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
 
 use ring::digest;
 use zeroize::Zeroizing;
@@ -45,20 +53,39 @@ impl crate::provider_config::ProviderConfig {
             .sleep_impl(self.sleep())
             .build()
     }
+
+    pub(crate) fn sso_oidc_client(
+        &self,
+    ) -> aws_smithy_client::Client<aws_smithy_client::erase::DynConnector, SsoOidcMiddleware> {
+        use crate::connector::expect_connector;
+        use aws_smithy_client::http_connector::HttpSettings;
+
+        aws_smithy_client::Builder::<(), SsoOidcMiddleware>::new()
+            .connector(expect_connector(self.connector(&HttpSettings::default())))
+            .sleep_impl(self.sleep())
+            .build()
+    }
 }
 
+/// Tokens within this window of their expiration will be refreshed if possible
+const TOKEN_REFRESH_WINDOW: Duration = Duration::from_secs(5 * 60);
+
 /// SSO Credentials Provider
 ///
 /// _Note: This provider is part of the default credentials chain and is integrated with the profile-file provider._
 ///
 /// This credentials provider will use cached SSO tokens stored in `~/.aws/sso/cache/<hash>.json`.
-/// `<hash>` is computed based on the configured [`start_url`](Builder::start_url).
+/// `<hash>` is computed based on the configured [`session_name`](Builder::session_name) if set,
+/// otherwise the [`start_url`](Builder::start_url).
 #[derive(Debug)]
 pub struct SsoCredentialsProvider {
     fs: Fs,
     env: Env,
+    time_source: TimeSource,
     sso_config: SsoConfig,
     client: aws_smithy_client::Client<DynConnector, SsoMiddleware>,
+    oidc_client: aws_smithy_client::Client<DynConnector, SsoOidcMiddleware>,
+    refreshed_token: Mutex<Option<SsoToken>>,
 }
 
 impl SsoCredentialsProvider {
@@ -74,13 +101,62 @@ impl SsoCredentialsProvider {
         SsoCredentialsProvider {
             fs,
             env,
+            time_source: provider_config.time_source(),
             client: provider_config.sso_client(),
+            oidc_client: provider_config.sso_oidc_client(),
             sso_config,
+            refreshed_token: Mutex::new(None),
         }
     }
 
     async fn credentials(&self) -> credentials::Result {
-        load_sso_credentials(&self.sso_config, &self.client, &self.env, &self.fs).await
+        let token = self.token().await?;
+        load_sso_credentials(&self.sso_config, &token, &self.client).await
+    }
+
+    /// Load the SSO token, refreshing it if it is close to expiring
+    async fn token(&self) -> Result<SsoToken, CredentialsError> {
+        let now = self.time_source.now();
+        let refresh_after = DateTime::from(now + TOKEN_REFRESH_WINDOW);
+        let now = DateTime::from(now);
+        let refreshed = self.refreshed_token.lock().unwrap().clone();
+        let token = match refreshed {
+            Some(token) => token,
+            None => load_token(self.sso_config.token_cache_key(), &self.env, &self.fs)
+                .await
+                .map_err(CredentialsError::provider_error)?,
+        };
+        // legacy (non-session) tokens can't be refreshed, so use them until they expire
+        let refreshable = self.sso_config.session_name.is_some();
+        if token.expires_at > refresh_after || (!refreshable && token.expires_at > now) {
+            return Ok(token);
+        }
+        let refresh_result = match refreshable {
+            true => refresh_token(&self.sso_config, &token, now, &self.oidc_client).await,
+            false => Err(LoadTokenError::ExpiredToken),
+        };
+        match refresh_result {
+            Ok(refreshed) => {
+                *self.refreshed_token.lock().unwrap() = Some(refreshed.clone());
+                if let Err(err) = save_token(
+                    self.sso_config.token_cache_key(),
+                    &refreshed,
+                    &self.env,
+                    &self.fs,
+                )
+                .await
+                {
+                    tracing::warn!(error = %err, "failed to write the refreshed SSO token to the cache");
+                }
+                Ok(refreshed)
+            }
+            // the token is still usable, even though we couldn't refresh it
+            Err(err) if token.expires_at > now => {
+                tracing::warn!(error = %err, "failed to refresh SSO token, using cached token");
+                Ok(token)
+            }
+            Err(err) => Err(CredentialsError::provider_error(err)),
+        }
     }
 }
 
@@ -101,6 +177,7 @@ pub struct Builder {
     role_name: Option<String>,
     start_url: Option<String>,
     region: Option<Region>,
+    session_name: Option<String>,
 }
 
 impl Builder {
@@ -139,6 +216,15 @@ impl Builder {
         self
     }
 
+    /// Set the `sso-session` name used for SSO
+    ///
+    /// When set, the cached token is looked up by session name rather than start URL, and
+    /// expired tokens will be refreshed via SSO OIDC when possible.
+    pub fn session_name(mut self, session_name: impl Into<String>) -> Self {
+        self.session_name = Some(session_name.into());
+        self
+    }
+
     /// Construct an SsoCredentialsProvider from the builder
     ///
     /// # Panics
@@ -154,6 +240,7 @@ impl Builder {
             role_name: self.role_name.expect("role_name must be set"),
             start_url: self.start_url.expect("start_url must be set"),
             region: self.region.expect("region must be set"),
+            session_name: self.session_name,
         };
         SsoCredentialsProvider::new(&provider_config, sso_config)
     }
@@ -164,6 +251,9 @@ pub(crate) enum LoadTokenError {
     InvalidCredentials(InvalidJsonCredentials),
     NoHomeDirectory,
     IoError { err: io::Error, path: PathBuf },
+    WriteError { err: io::Error, path: PathBuf },
+    ExpiredToken,
+    RefreshFailed(Box<dyn Error + Send + Sync>),
 }
 
 impl Display for LoadTokenError {
@@ -176,6 +266,14 @@ impl Display for LoadTokenError {
             LoadTokenError::IoError { err, path } => {
                 write!(f, "failed to read `{}`: {}", path.display(), err)
             }
+            LoadTokenError::WriteError { err, path } => {
+                write!(f, "failed to write `{}`: {}", path.display(), err)
+            }
+            LoadTokenError::ExpiredToken => write!(
+                f,
+                "the SSO token has expired and cannot be refreshed. Run `aws sso login` to refresh it"
+            ),
+            LoadTokenError::RefreshFailed(_) => write!(f, "failed to refresh the SSO token"),
         }
     }
 }
@@ -186,6 +284,9 @@ impl Error for LoadTokenError {
             LoadTokenError::InvalidCredentials(err) => Some(err as _),
             LoadTokenError::NoHomeDirectory => None,
             LoadTokenError::IoError { err, .. } => Some(err as _),
+            LoadTokenError::WriteError { err, .. } => Some(err as _),
+            LoadTokenError::ExpiredToken => None,
+            LoadTokenError::RefreshFailed(err) => Some(err.as_ref() as _),
         }
     }
 }
@@ -196,17 +297,73 @@ pub(crate) struct SsoConfig {
     pub(crate) role_name: String,
     pub(crate) start_url: String,
     pub(crate) region: Region,
+    pub(crate) session_name: Option<String>,
+}
+
+impl SsoConfig {
+    /// The key used to compute the name of the token cache file
+    fn token_cache_key(&self) -> &str {
+        self.session_name.as_deref().unwrap_or(&self.start_url)
+    }
+}
+
+/// Refresh `token` with the SSO OIDC `CreateToken` API
+async fn refresh_token(
+    sso_config: &SsoConfig,
+    token: &SsoToken,
+    now: DateTime,
+    oidc: &aws_smithy_client::Client<DynConnector, SsoOidcMiddleware>,
+) -> Result<SsoToken, LoadTokenError> {
+    let (refresh_token, client_id, client_secret) =
+        match (&token.refresh_token, &token.client_id, &token.client_secret) {
+            (Some(refresh_token), Some(client_id), Some(client_secret)) => {
+                (refresh_token, client_id, client_secret)
+            }
+            _ => return Err(LoadTokenError::ExpiredToken),
+        };
+    if matches!(token.registration_expires_at, Some(expires_at) if expires_at <= now) {
+        return Err(LoadTokenError::ExpiredToken);
+    }
+    let config = aws_sdk_ssooidc::Config::builder()
+        .region(sso_config.region.clone())
+        .build();
+    let operation = aws_sdk_ssooidc::operation::CreateToken::builder()
+        .client_id(client_id)
+        .client_secret(&**client_secret)
+        .grant_type("refresh_token")
+        .refresh_token(&**refresh_token)
+        .build()
+        .map_err(|err| LoadTokenError::RefreshFailed(err.into()))?
+        .make_operation(&config)
+        .await
+        .map_err(|err| LoadTokenError::RefreshFailed(err.into()))?;
+    let resp = oidc
+        .call(operation)
+        .await
+        .map_err(|err| LoadTokenError::RefreshFailed(err.into()))?;
+    let access_token = resp.access_token.ok_or_else(|| {
+        LoadTokenError::RefreshFailed("SSO OIDC did not return an access token".into())
+    })?;
+    Ok(SsoToken {
+        access_token: Zeroizing::new(access_token),
+        expires_at: DateTime::from_secs(now.secs() + i64::from(resp.expires_in)),
+        region: token.region.clone(),
+        start_url: token.start_url.clone(),
+        refresh_token: resp
+            .refresh_token
+            .map(Zeroizing::new)
+            .or_else(|| token.refresh_token.clone()),
+        client_id: token.client_id.clone(),
+        client_secret: token.client_secret.clone(),
+        registration_expires_at: token.registration_expires_at,
+    })
 }
 
 async fn load_sso_credentials(
     sso_config: &SsoConfig,
+    token: &SsoToken,
     sso: &aws_smithy_client::Client<DynConnector, SsoMiddleware>,
-    env: &Env,
-    fs: &Fs,
 ) -> credentials::Result {
-    let token = load_token(&sso_config.start_url, env, fs)
-        .await
-        .map_err(CredentialsError::provider_error)?;
     let config = aws_sdk_sso::Config::builder()
         .region(sso_config.region.clone())
         .build();
@@ -248,13 +405,16 @@ async fn load_sso_credentials(
         credentials.session_token,
         Some(expiration),
         "SSO",
-    ))
+    )
+    .with_account_id(&sso_config.account_id))
 }
 
-/// Load the token for `start_url` from `~/.aws/sso/cache/<hashofstarturl>.json`
-async fn load_token(start_url: &str, env: &Env, fs: &Fs) -> Result<SsoToken, LoadTokenError> {
-    let home = home_dir(env, Os::real()).ok_or(LoadTokenError::NoHomeDirectory)?;
-    let path = sso_token_path(start_url, &home);
+/// Load the token for `cache_key` from `~/.aws/sso/cache/<hashofcachekey>.json`
+///
+/// `cache_key` is either the `sso-session` name or, for legacy profiles, the start URL.
+async fn load_token(cache_key: &str, env: &Env, fs: &Fs) -> Result<SsoToken, LoadTokenError> {
+    let home = home_dir(env).ok_or(LoadTokenError::NoHomeDirectory)?;
+    let path = sso_token_path(cache_key, &home);
     let data =
         Zeroizing::new(
             fs.read_to_end(&path)
@@ -268,11 +428,31 @@ async fn load_token(start_url: &str, env: &Env, fs: &Fs) -> Result<SsoToken, Loa
     Ok(token)
 }
 
+/// Write `token` to `~/.aws/sso/cache/<hashofcachekey>.json`, replacing the cached token
+async fn save_token(
+    cache_key: &str,
+    token: &SsoToken,
+    env: &Env,
+    fs: &Fs,
+) -> Result<(), LoadTokenError> {
+    let home = home_dir(env).ok_or(LoadTokenError::NoHomeDirectory)?;
+    let path = sso_token_path(cache_key, &home);
+    let data = Zeroizing::new(token_json(token));
+    fs.write(&path, data.as_bytes())
+        .await
+        .map_err(|err| LoadTokenError::WriteError { err, path })
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub(crate) struct SsoToken {
     access_token: Zeroizing<String>,
     expires_at: DateTime,
     region: Option<Region>,
+    start_url: Option<String>,
+    refresh_token: Option<Zeroizing<String>>,
+    client_id: Option<String>,
+    client_secret: Option<Zeroizing<String>>,
+    registration_expires_at: Option<DateTime>,
 }
 
 /// Parse SSO token JSON from input
@@ -283,17 +463,35 @@ fn parse_token_json(input: &[u8]) -> Result<SsoToken, InvalidJsonCredentials> {
         "accessToken": "base64string",
         "expiresAt": "2019-11-14T04:05:45Z",
         "region": "us-west-2",
-        "startUrl": "https://d-abc123.awsapps.com/start"
+        "startUrl": "https://d-abc123.awsapps.com/start",
+        "refreshToken": "base64string",
+        "clientId": "clientid",
+        "clientSecret": "base64string",
+        "registrationExpiresAt": "2019-12-14T04:05:45Z"
     }*/
     let mut acccess_token = None;
     let mut expires_at = None;
     let mut region = None;
     let mut start_url = None;
+    let mut refresh_token = None;
+    let mut client_id = None;
+    let mut client_secret = None;
+    let mut registration_expires_at = None;
     json_parse_loop(input, |key, value| match key {
         key if key.eq_ignore_ascii_case("accessToken") => acccess_token = Some(value.to_string()),
         key if key.eq_ignore_ascii_case("expiresAt") => expires_at = Some(value),
         key if key.eq_ignore_ascii_case("region") => region = Some(value.to_string()),
         key if key.eq_ignore_ascii_case("startUrl") => start_url = Some(value.to_string()),
+        key if key.eq_ignore_ascii_case("refreshToken") => {
+            refresh_token = Some(Zeroizing::new(value.to_string()))
+        }
+        key if key.eq_ignore_ascii_case("clientId") => client_id = Some(value.to_string()),
+        key if key.eq_ignore_ascii_case("clientSecret") => {
+            client_secret = Some(Zeroizing::new(value.to_string()))
+        }
+        key if key.eq_ignore_ascii_case("registrationExpiresAt") => {
+            registration_expires_at = Some(value)
+        }
         _other => {} // ignored
     })?;
     let access_token =
@@ -305,23 +503,72 @@ fn parse_token_json(input: &[u8]) -> Result<SsoToken, InvalidJsonCredentials> {
             err: e.into(),
         }
     })?;
+    let registration_expires_at = registration_expires_at
+        .map(|expires_at| {
+            DateTime::from_str(expires_at.as_ref(), Format::DateTime).map_err(|e| {
+                InvalidJsonCredentials::InvalidField {
+                    field: "registrationExpiresAt",
+                    err: e.into(),
+                }
+            })
+        })
+        .transpose()?;
     let region = region.map(Region::new);
     Ok(SsoToken {
         access_token,
         expires_at,
         region,
+        start_url,
+        refresh_token,
+        client_id,
+        client_secret,
+        registration_expires_at,
     })
 }
 
-/// Determine the SSO token path for a given start_url
-fn sso_token_path(start_url: &str, home: &str) -> PathBuf {
+/// Serialize `token` in the same format that [`parse_token_json`] reads
+fn token_json(token: &SsoToken) -> String {
+    let mut out = String::new();
+    let mut object = JsonObjectWriter::new(&mut out);
+    object.key("accessToken").string(&token.access_token);
+    object
+        .key("expiresAt")
+        .date_time(&token.expires_at, Format::DateTime)
+        .expect("SSO token expirations are always formattable");
+    if let Some(region) = &token.region {
+        object.key("region").string(region.as_ref());
+    }
+    if let Some(start_url) = &token.start_url {
+        object.key("startUrl").string(start_url);
+    }
+    if let Some(refresh_token) = &token.refresh_token {
+        object.key("refreshToken").string(refresh_token);
+    }
+    if let Some(client_id) = &token.client_id {
+        object.key("clientId").string(client_id);
+    }
+    if let Some(client_secret) = &token.client_secret {
+        object.key("clientSecret").string(client_secret);
+    }
+    if let Some(registration_expires_at) = &token.registration_expires_at {
+        object
+            .key("registrationExpiresAt")
+            .date_time(registration_expires_at, Format::DateTime)
+            .expect("SSO registration expirations are always formattable");
+    }
+    object.finish();
+    out
+}
+
+/// Determine the SSO token path for a given cache key (session name or start URL)
+fn sso_token_path(cache_key: &str, home: &str) -> PathBuf {
     // hex::encode returns a lowercase string
     let mut out = PathBuf::with_capacity(home.len() + "/.aws/sso/cache".len() + ".json".len() + 40);
     out.push(home);
     out.push(".aws/sso/cache");
     out.push(&hex::encode(digest::digest(
         &digest::SHA1_FOR_LEGACY_USE_ONLY,
-        start_url.as_bytes(),
+        cache_key.as_bytes(),
     )));
     out.set_extension("json");
     out
@@ -330,10 +577,19 @@ fn sso_token_path(start_url: &str, home: &str) -> PathBuf {
 #[cfg(test)]
 mod test {
     use crate::json_credentials::InvalidJsonCredentials;
-    use crate::sso::{load_token, parse_token_json, sso_token_path, LoadTokenError, SsoToken};
+    use crate::provider_config::ProviderConfig;
+    use crate::sso::{
+        load_token, parse_token_json, save_token, sso_token_path, LoadTokenError, SsoConfig,
+        SsoCredentialsProvider, SsoToken,
+    };
+    use aws_smithy_client::erase::DynConnector;
+    use aws_smithy_client::test_connection::TestConnection;
     use aws_smithy_types::DateTime;
-    use aws_types::os_shim_internal::{Env, Fs};
+    use aws_types::credentials::ProvideCredentials;
+    use aws_types::os_shim_internal::{Env, Fs, ManualTimeSource, TimeSource};
     use aws_types::region::Region;
+    use std::collections::HashMap;
+    use std::time::{Duration, UNIX_EPOCH};
     use zeroize::Zeroizing;
 
     #[test]
@@ -350,7 +606,12 @@ mod test {
             SsoToken {
                 access_token: Zeroizing::new("base64string".into()),
                 expires_at: DateTime::from_secs(1234567890),
-                region: Some(Region::from_static("us-west-2"))
+                region: Some(Region::from_static("us-west-2")),
+                start_url: Some("https://d-abc123.awsapps.com/start".into()),
+                refresh_token: None,
+                client_id: None,
+                client_secret: None,
+                registration_expires_at: None,
             }
         );
 
@@ -363,7 +624,40 @@ mod test {
             SsoToken {
                 access_token: Zeroizing::new("base64string".into()),
                 expires_at: DateTime::from_secs(1234567890),
-                region: None
+                region: None,
+                start_url: None,
+                refresh_token: None,
+                client_id: None,
+                client_secret: None,
+                registration_expires_at: None,
+            }
+        );
+    }
+
+    #[test]
+    fn deserialize_refreshable_token() {
+        let token = br#"
+        {
+            "accessToken": "base64string",
+            "expiresAt": "2009-02-13T23:31:30Z",
+            "region": "us-west-2",
+            "startUrl": "https://d-abc123.awsapps.com/start",
+            "refreshToken": "refreshtoken",
+            "clientId": "clientid",
+            "clientSecret": "clientsecret",
+            "registrationExpiresAt": "2009-02-13T23:31:31Z"
+        }"#;
+        assert_eq!(
+            parse_token_json(token).expect("valid"),
+            SsoToken {
+                access_token: Zeroizing::new("base64string".into()),
+                expires_at: DateTime::from_secs(1234567890),
+                region: Some(Region::from_static("us-west-2")),
+                start_url: Some("https://d-abc123.awsapps.com/start".into()),
+                refresh_token: Some(Zeroizing::new("refreshtoken".into())),
+                client_id: Some("clientid".into()),
+                client_secret: Some(Zeroizing::new("clientsecret".into())),
+                registration_expires_at: Some(DateTime::from_secs(1234567891)),
             }
         );
     }
@@ -426,6 +720,87 @@ mod test {
         );
     }
 
+    #[test]
+    fn session_name_is_used_as_cache_key() {
+        let mut config = SsoConfig {
+            account_id: "012345678901".into(),
+            role_name: "SampleRole".into(),
+            start_url: "https://d-92671207e4.awsapps.com/start".into(),
+            region: Region::from_static("us-east-1"),
+            session_name: None,
+        };
+        assert_eq!(
+            "https://d-92671207e4.awsapps.com/start",
+            config.token_cache_key()
+        );
+        config.session_name = Some("my-sso".into());
+        assert_eq!("my-sso", config.token_cache_key());
+    }
+
+    #[tokio::test]
+    async fn expired_token_without_session_is_an_error() {
+        let start_url = "https://d-92671207e4.awsapps.com/start";
+        let mut fs = HashMap::new();
+        fs.insert(
+            sso_token_path(start_url, "/home/me")
+                .to_str()
+                .unwrap()
+                .to_string(),
+            r#"{
+                "accessToken": "base64string",
+                "expiresAt": "2009-02-13T23:31:30Z",
+                "refreshToken": "refreshtoken",
+                "clientId": "clientid",
+                "clientSecret": "clientsecret"
+            }"#,
+        );
+        let time_source = ManualTimeSource::new(UNIX_EPOCH + Duration::from_secs(1234567890));
+        let conn: TestConnection<String> = TestConnection::new(vec![]);
+        let provider_config = ProviderConfig::no_configuration()
+            .with_fs(Fs::from_map(fs))
+            .with_env(Env::from_slice(&[("HOME", "/home/me")]))
+            .with_time_source(TimeSource::manual(&time_source))
+            .with_http_connector(DynConnector::new(conn.clone()));
+        let provider = SsoCredentialsProvider::builder()
+            .configure(&provider_config)
+            .account_id("012345678901")
+            .role_name("SampleRole")
+            .start_url(start_url)
+            .region(Region::from_static("us-east-1"))
+            .build();
+        let err = provider
+            .provide_credentials()
+            .await
+            .expect_err("token is expired");
+        assert!(format!("{}", err).contains("has expired"), "{:?}", err);
+        assert!(conn.requests().is_empty(), "no refresh should be attempted");
+    }
+
+    #[tokio::test]
+    async fn saved_tokens_can_be_loaded() {
+        let token = SsoToken {
+            access_token: Zeroizing::new("base64string".into()),
+            expires_at: DateTime::from_secs(1234567890),
+            region: Some(Region::from_static("us-west-2")),
+            start_url: Some("https://d-abc123.awsapps.com/start".into()),
+            refresh_token: Some(Zeroizing::new("refreshtoken".into())),
+            client_id: Some("clientid".into()),
+            client_secret: Some(Zeroizing::new("clientsecret".into())),
+            registration_expires_at: Some(DateTime::from_secs(1234567891)),
+        };
+        let env = Env::from_slice(&[("HOME", "/home/me")]);
+        let fs = Fs::from_slice(&[]);
+        save_token("my-sso", &token, &env, &fs)
+            .await
+            .expect("token is saved");
+        assert_eq!(
+            token,
+            load_token("my-sso", &env, &fs)
+                .await
+                .expect("saved token is loaded")
+        );
+    }
+
     #[tokio::test]
     async fn gracefully_handle_missing_files() {
         let err = load_token(
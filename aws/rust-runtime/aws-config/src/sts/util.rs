@@ -3,7 +3,7 @@
  * SPDX-License-Identifier: Apache-2.0.
  */
 
-use aws_sdk_sts::model::Credentials as StsCredentials;
+use aws_sdk_sts::model::{AssumedRoleUser, Credentials as StsCredentials};
 use aws_types::credentials::{self, CredentialsError};
 use aws_types::Credentials as AwsCredentials;
 
@@ -11,8 +11,11 @@ use std::convert::TryFrom;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 /// Convert STS credentials to aws_auth::Credentials
+///
+/// The account ID of the credentials is taken from the ARN of the assumed role, if STS returned one.
 pub(crate) fn into_credentials(
     sts_credentials: Option<StsCredentials>,
+    assumed_role_user: Option<AssumedRoleUser>,
     provider_name: &'static str,
 ) -> credentials::Result {
     let sts_credentials = sts_credentials
@@ -27,7 +30,7 @@ pub(crate) fn into_credentials(
             "credential expiration time cannot be represented by a SystemTime",
         )
     })?;
-    Ok(AwsCredentials::new(
+    let credentials = AwsCredentials::new(
         sts_credentials
             .access_key_id
             .ok_or_else(|| CredentialsError::unhandled("access key id missing from result"))?,
@@ -37,7 +40,23 @@ pub(crate) fn into_credentials(
         sts_credentials.session_token,
         Some(expiration),
         provider_name,
-    ))
+    );
+    match assumed_role_user
+        .as_ref()
+        .and_then(|user| user.arn.as_deref())
+        .and_then(account_id_from_arn)
+    {
+        Some(account_id) => Ok(credentials.with_account_id(account_id)),
+        None => Ok(credentials),
+    }
+}
+
+/// Returns the account ID from an ARN (`arn:partition:service:region:account-id:resource`)
+fn account_id_from_arn(arn: &str) -> Option<&str> {
+    match arn.splitn(6, ':').nth(4) {
+        Some(account_id) if !account_id.is_empty() => Some(account_id),
+        _ => None,
+    }
 }
 
 /// Create a default STS session name
@@ -51,3 +70,18 @@ pub(crate) fn default_session_name(base: &str) -> String {
         .expect("post epoch");
     format!("{}-{}", base, now.as_millis())
 }
+
+#[cfg(test)]
+mod test {
+    use super::account_id_from_arn;
+
+    #[test]
+    fn account_id_from_assumed_role_arn() {
+        assert_eq!(
+            Some("123456789012"),
+            account_id_from_arn("arn:aws:sts::123456789012:assumed-role/my-role/my-session")
+        );
+        assert_eq!(None, account_id_from_arn("arn:aws:s3:::my-bucket"));
+        assert_eq!(None, account_id_from_arn("not-an-arn"));
+    }
+}
@@ -0,0 +1,111 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Account ID based endpoint routing
+//!
+//! Some services route requests to an endpoint specific to the caller's account by prefixing the
+//! host with the account ID, e.g. `123456789012.service.us-east-1.amazonaws.com`. For operations
+//! marked with [`set_account_id_routing`], the account ID of the resolved credentials is added to
+//! the host. When the credentials don't carry an account ID, the regular endpoint is used.
+
+use aws_smithy_http::endpoint::EndpointPrefix;
+use aws_smithy_http::property_bag::PropertyBag;
+use aws_types::endpoint::BoxError;
+use aws_types::Credentials;
+
+/// Marks an operation as routed by the account ID of its credentials
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AccountIdRouting;
+
+/// Routes the request to an endpoint for the account ID of its credentials
+pub fn set_account_id_routing(properties: &mut PropertyBag) {
+    properties.insert(AccountIdRouting);
+}
+
+/// Returns true if `account_id` is a valid AWS account ID (12 digits)
+pub(crate) fn is_valid_account_id(account_id: &str) -> bool {
+    account_id.len() == 12 && account_id.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// Returns the endpoint prefix for the request, including the account ID if the request is routed by it
+pub(crate) fn endpoint_prefix(
+    properties: &PropertyBag,
+) -> Result<Option<EndpointPrefix>, BoxError> {
+    let prefix = properties.get::<EndpointPrefix>();
+    let account_id = match (
+        properties.get::<AccountIdRouting>(),
+        properties
+            .get::<Credentials>()
+            .and_then(|creds| creds.account_id()),
+    ) {
+        (Some(_), Some(account_id)) => account_id,
+        _ => return Ok(prefix.cloned()),
+    };
+    if !is_valid_account_id(account_id) {
+        return Err(format!(
+            "`{}` is not a valid account ID, account IDs must be 12 digits",
+            account_id
+        )
+        .into());
+    }
+    let prefix = format!(
+        "{}.{}",
+        account_id,
+        prefix.map(|prefix| prefix.as_str()).unwrap_or_default()
+    );
+    Ok(Some(EndpointPrefix::new(prefix)?))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{endpoint_prefix, set_account_id_routing};
+    use aws_smithy_http::endpoint::EndpointPrefix;
+    use aws_smithy_http::property_bag::PropertyBag;
+    use aws_types::Credentials;
+
+    fn credentials(account_id: Option<&str>) -> Credentials {
+        let creds = Credentials::new("akid", "secret", None, None, "test");
+        match account_id {
+            Some(account_id) => creds.with_account_id(account_id),
+            None => creds,
+        }
+    }
+
+    fn prefix(properties: &PropertyBag) -> Option<String> {
+        endpoint_prefix(properties)
+            .expect("valid prefix")
+            .map(|prefix| prefix.as_str().to_string())
+    }
+
+    #[test]
+    fn account_id_is_only_used_when_routing_by_it() {
+        let mut properties = PropertyBag::new();
+        properties.insert(credentials(Some("123456789012")));
+        assert_eq!(None, prefix(&properties));
+
+        set_account_id_routing(&mut properties);
+        assert_eq!(Some("123456789012.".to_string()), prefix(&properties));
+
+        properties.insert(EndpointPrefix::new("data-").unwrap());
+        assert_eq!(Some("123456789012.data-".to_string()), prefix(&properties));
+    }
+
+    #[test]
+    fn credentials_without_account_id_use_the_regular_endpoint() {
+        let mut properties = PropertyBag::new();
+        properties.insert(credentials(None));
+        set_account_id_routing(&mut properties);
+        assert_eq!(None, prefix(&properties));
+    }
+
+    #[test]
+    fn invalid_account_id() {
+        let mut properties = PropertyBag::new();
+        properties.insert(credentials(Some("not-an-account")));
+        set_account_id_routing(&mut properties);
+        endpoint_prefix(&properties).expect_err("account ID must be 12 digits");
+    }
+}
@@ -6,14 +6,19 @@
 #[doc(hidden)]
 pub mod partition;
 
+pub mod account_id;
+pub mod params;
+pub mod s3;
+pub mod sts;
+
 #[doc(hidden)]
 pub use partition::Partition;
 #[doc(hidden)]
 pub use partition::PartitionResolver;
 
-use aws_smithy_http::endpoint::EndpointPrefix;
 use aws_smithy_http::middleware::MapRequest;
 use aws_smithy_http::operation::Request;
+use aws_smithy_http::pipeline::{PipelineConfig, Stage};
 use aws_smithy_http::property_bag::PropertyBag;
 use aws_types::region::{Region, SigningRegion};
 use aws_types::SigningService;
@@ -22,7 +27,9 @@ use std::fmt;
 use std::fmt::{Debug, Display, Formatter};
 use std::sync::Arc;
 
-pub use aws_types::endpoint::{AwsEndpoint, BoxError, CredentialScope, ResolveAwsEndpoint};
+pub use aws_types::endpoint::{
+    AwsEndpoint, BoxError, CredentialScope, EndpointVariant, ResolveAwsEndpoint,
+};
 
 type AwsEndpointResolver = Arc<dyn ResolveAwsEndpoint>;
 pub fn get_endpoint_resolver(properties: &PropertyBag) -> Option<&AwsEndpointResolver> {
@@ -33,12 +40,20 @@ pub fn set_endpoint_resolver(properties: &mut PropertyBag, provider: AwsEndpoint
     properties.insert(provider);
 }
 
+/// Sets the [`EndpointVariant`] (e.g. FIPS or dual-stack) to resolve for the request
+pub fn set_endpoint_variant(properties: &mut PropertyBag, variant: EndpointVariant) {
+    properties.insert(variant);
+}
+
 /// Middleware Stage to Add an Endpoint to a Request
 ///
-/// AwsEndpointStage implements [`MapRequest`](aws_smithy_http::middleware::MapRequest). It will:
+/// AwsEndpointStage implements [`MapRequest`](aws_smithy_http::middleware::MapRequest). Unless the
+/// operation's [`PipelineConfig`] skips [`Stage::Endpoint`], it will:
 /// 1. Load an endpoint provider from the property bag.
-/// 2. Load an endpoint given the [`Region`](aws_types::region::Region) in the property bag.
-/// 3. Apply the endpoint to the URI in the request
+/// 2. Load an endpoint given the [`Region`](aws_types::region::Region) and [`EndpointVariant`] in the property bag.
+/// 3. Apply the endpoint to the URI in the request, moving the bucket into the host for S3 requests (see [`s3`]),
+///    using the global endpoint for STS requests when legacy STS endpoints are enabled (see [`sts`])
+///    and adding the account ID to the host for services that route by it (see [`account_id`])
 /// 4. Set the `SigningRegion` and `SigningService` in the property bag to drive downstream
 /// signing middleware.
 #[derive(Clone, Debug)]
@@ -63,15 +78,33 @@ impl MapRequest for AwsEndpointStage {
 
     fn apply(&self, request: Request) -> Result<Request, Self::Error> {
         request.augment(|mut http_req, props| {
+            if PipelineConfig::skips(props, Stage::Endpoint) {
+                tracing::debug!(uri = %http_req.uri(), "endpoint resolution is skipped for this operation");
+                return Ok(http_req);
+            }
             let provider =
                 get_endpoint_resolver(props).ok_or(AwsEndpointStageError::NoEndpointResolver)?;
             let region = props
                 .get::<Region>()
                 .ok_or(AwsEndpointStageError::NoRegion)?;
-            let endpoint = provider
-                .resolve_endpoint(region)
-                .map_err(AwsEndpointStageError::EndpointResolutionError)?;
-            tracing::debug!(endpoint = ?endpoint, base_region = ?region, "resolved endpoint");
+            let variant = props.get::<EndpointVariant>().copied().unwrap_or_default();
+            let bucket = props.get::<s3::S3Bucket>().cloned();
+            let access_point = match &bucket {
+                Some(bucket) => s3::access_point_endpoint(bucket, region, variant)
+                    .map_err(|err| AwsEndpointStageError::EndpointResolutionError(err.into()))?,
+                None => None,
+            };
+            let is_access_point = access_point.is_some();
+            let endpoint = match access_point {
+                Some(endpoint) => endpoint,
+                None => provider
+                    .resolve_endpoint_variant(
+                        &sts::resolution_region(region, variant, props),
+                        variant,
+                    )
+                    .map_err(AwsEndpointStageError::EndpointResolutionError)?,
+            };
+            tracing::debug!(endpoint = ?endpoint, base_region = ?region, variant = %variant, "resolved endpoint");
             let signing_region = endpoint
                 .credential_scope()
                 .region()
@@ -81,10 +114,23 @@ impl MapRequest for AwsEndpointStage {
             if let Some(signing_service) = endpoint.credential_scope().service() {
                 props.insert::<SigningService>(signing_service.clone());
             }
-            endpoint.set_endpoint(http_req.uri_mut(), props.get::<EndpointPrefix>());
+            let prefix = account_id::endpoint_prefix(props)
+                .map_err(AwsEndpointStageError::EndpointResolutionError)?;
+            endpoint.set_endpoint(http_req.uri_mut(), prefix.as_ref());
+            match &bucket {
+                // immutable endpoints (e.g. a local test server) never have the bucket moved into the host
+                Some(bucket) if is_access_point || !endpoint.endpoint().is_immutable() => {
+                    s3::apply_addressing(http_req.uri_mut(), bucket, is_access_point, props);
+                }
+                _ => {}
+            }
             Ok(http_req)
         })
     }
+
+    fn name(&self) -> &'static str {
+        "endpoint"
+    }
 }
 
 #[cfg(test)]
@@ -95,13 +141,21 @@ mod test {
     use http::Uri;
 
     use aws_smithy_http::body::SdkBody;
+    use aws_smithy_http::endpoint::Endpoint;
     use aws_smithy_http::middleware::MapRequest;
     use aws_smithy_http::operation;
+    use aws_smithy_http::pipeline::{PipelineConfig, Stage};
     use aws_types::region::{Region, SigningRegion};
     use aws_types::SigningService;
 
     use crate::partition::endpoint::{Metadata, Protocol, SignatureVersion};
-    use crate::{set_endpoint_resolver, AwsEndpointStage, CredentialScope};
+    use crate::partition::{Partition, PartitionResolver, Regionalized};
+    use crate::{
+        account_id, s3, set_endpoint_resolver, set_endpoint_variant, sts, AwsEndpointStage,
+        CredentialScope, EndpointVariant,
+    };
+    use aws_types::endpoint::StsRegionalEndpoints;
+    use aws_types::Credentials;
 
     #[test]
     fn default_endpoint_updates_request() {
@@ -165,4 +219,186 @@ mod test {
             Some(&SigningService::from_static("qldb-override"))
         );
     }
+
+    #[test]
+    fn s3_buckets_use_virtual_hosted_addressing() {
+        let provider = Arc::new(Metadata {
+            uri_template: "s3.{region}.amazonaws.com",
+            protocol: Protocol::Https,
+            credential_scope: Default::default(),
+            signature_versions: SignatureVersion::V4,
+        });
+        let req = http::Request::builder()
+            .uri("/my-bucket/key?x-id=GetObject")
+            .body(SdkBody::from(""))
+            .unwrap();
+        let mut req = operation::Request::new(req);
+        {
+            let mut props = req.properties_mut();
+            props.insert(Region::new("us-east-1"));
+            set_endpoint_resolver(&mut props, provider);
+            s3::set_bucket(&mut props, "my-bucket");
+        };
+        let req = AwsEndpointStage.apply(req).expect("should succeed");
+        let (req, _conf) = req.into_parts();
+        assert_eq!(
+            req.uri(),
+            &Uri::from_static("https://my-bucket.s3.us-east-1.amazonaws.com/key?x-id=GetObject")
+        );
+    }
+
+    #[test]
+    fn s3_buckets_are_not_moved_into_immutable_endpoints() {
+        let provider = Arc::new(Endpoint::immutable(Uri::from_static(
+            "http://localhost:9000",
+        )));
+        let req = http::Request::builder()
+            .uri("/my-bucket/key")
+            .body(SdkBody::from(""))
+            .unwrap();
+        let mut req = operation::Request::new(req);
+        {
+            let mut props = req.properties_mut();
+            props.insert(Region::new("us-east-1"));
+            set_endpoint_resolver(&mut props, provider);
+            s3::set_bucket(&mut props, "my-bucket");
+        };
+        let req = AwsEndpointStage.apply(req).expect("should succeed");
+        let (req, _conf) = req.into_parts();
+        assert_eq!(
+            req.uri(),
+            &Uri::from_static("http://localhost:9000/my-bucket/key")
+        );
+    }
+
+    #[test]
+    fn legacy_sts_endpoints_use_the_global_endpoint() {
+        let partition = Partition::builder()
+            .id("aws")
+            .region_regex(r#"^(us|eu|af)\-\w+\-\d+$"#)
+            .default_endpoint(Metadata {
+                uri_template: "sts.{region}.amazonaws.com",
+                protocol: Protocol::Https,
+                credential_scope: Default::default(),
+                signature_versions: SignatureVersion::V4,
+            })
+            .regionalized(Regionalized::Regionalized)
+            .endpoint(
+                "aws-global",
+                Metadata {
+                    uri_template: "sts.amazonaws.com",
+                    protocol: Protocol::Https,
+                    credential_scope: CredentialScope::builder().region("us-east-1").build(),
+                    signature_versions: SignatureVersion::V4,
+                },
+            )
+            .build()
+            .expect("valid partition");
+        let provider = Arc::new(PartitionResolver::new(partition, vec![]));
+        let resolve = |region: &'static str, setting: StsRegionalEndpoints| {
+            let mut req = operation::Request::new(http::Request::new(SdkBody::from("")));
+            {
+                let mut props = req.properties_mut();
+                props.insert(Region::new(region));
+                set_endpoint_resolver(&mut props, provider.clone());
+                sts::set_sts_regional_endpoints(&mut props, setting);
+            };
+            let req = AwsEndpointStage.apply(req).expect("should succeed");
+            let out = (
+                req.http().uri().clone(),
+                req.properties().get::<SigningRegion>().cloned(),
+            );
+            out
+        };
+
+        assert_eq!(
+            resolve("us-west-2", StsRegionalEndpoints::Legacy),
+            (
+                Uri::from_static("https://sts.amazonaws.com"),
+                Some(SigningRegion::from_static("us-east-1"))
+            )
+        );
+        assert_eq!(
+            resolve("us-west-2", StsRegionalEndpoints::Regional),
+            (
+                Uri::from_static("https://sts.us-west-2.amazonaws.com"),
+                Some(SigningRegion::from_static("us-west-2"))
+            )
+        );
+        // regions launched after regional endpoints were introduced are unaffected
+        assert_eq!(
+            resolve("af-south-1", StsRegionalEndpoints::Legacy),
+            (
+                Uri::from_static("https://sts.af-south-1.amazonaws.com"),
+                Some(SigningRegion::from_static("af-south-1"))
+            )
+        );
+    }
+
+    #[test]
+    fn unsupported_variant_is_an_error() {
+        let provider = Arc::new(Metadata {
+            uri_template: "kinesis.{region}.amazonaws.com",
+            protocol: Protocol::Https,
+            credential_scope: Default::default(),
+            signature_versions: SignatureVersion::V4,
+        });
+        let req = http::Request::new(SdkBody::from(""));
+        let mut req = operation::Request::new(req);
+        {
+            let mut props = req.properties_mut();
+            props.insert(Region::new("us-east-1"));
+            set_endpoint_resolver(&mut props, provider);
+            set_endpoint_variant(&mut props, EndpointVariant::new().with_fips(true));
+        };
+        let err = AwsEndpointStage
+            .apply(req)
+            .expect_err("fips is not supported");
+        assert!(format!("{}", err).contains("FIPS"), "{}", err);
+    }
+
+    #[test]
+    fn account_id_routing_prefixes_the_host() {
+        let provider = Arc::new(Metadata {
+            uri_template: "service.{region}.amazonaws.com",
+            protocol: Protocol::Https,
+            credential_scope: Default::default(),
+            signature_versions: SignatureVersion::V4,
+        });
+        let mut req = operation::Request::new(http::Request::new(SdkBody::from("")));
+        {
+            let mut props = req.properties_mut();
+            props.insert(Region::new("us-east-1"));
+            props.insert(
+                Credentials::new("akid", "secret", None, None, "test")
+                    .with_account_id("123456789012"),
+            );
+            set_endpoint_resolver(&mut props, provider);
+            account_id::set_account_id_routing(&mut props);
+        };
+        let req = AwsEndpointStage.apply(req).expect("should succeed");
+        assert_eq!(
+            req.http().uri(),
+            &Uri::from_static("https://123456789012.service.us-east-1.amazonaws.com")
+        );
+    }
+
+    #[test]
+    fn skipped_endpoint_stage_leaves_the_uri_unchanged() {
+        let req = http::Request::builder()
+            .uri("https://example.com/presigned?X-Amz-Signature=abc")
+            .body(SdkBody::from(""))
+            .unwrap();
+        let mut req = operation::Request::new(req);
+        // no resolver or region: the stage would fail if it ran
+        PipelineConfig::new()
+            .skip(Stage::Endpoint)
+            .apply(&mut req.properties_mut());
+        let req = AwsEndpointStage.apply(req).expect("stage is skipped");
+        assert_eq!(
+            req.http().uri(),
+            &Uri::from_static("https://example.com/presigned?X-Amz-Signature=abc")
+        );
+        assert!(req.properties().get::<SigningRegion>().is_none());
+    }
 }
@@ -0,0 +1,322 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Typed parameters for resolving AWS endpoints with [`ResolveEndpoint`]
+//!
+//! This is the groundwork for rules-based endpoint resolution: rather than resolving an endpoint
+//! from a region alone, a resolver receives every parameter that may influence the endpoint and
+//! returns a [`SmithyEndpoint`] whose `authSchemes` property describes how requests must be signed.
+
+use crate::partition::PartitionResolver;
+use crate::s3;
+use aws_smithy_http::endpoint::{
+    Endpoint, ResolveEndpoint, ResolveEndpointError, ResolveEndpointResult, SmithyEndpoint,
+};
+use aws_smithy_types::Document;
+use aws_types::endpoint::{AwsEndpoint, CredentialScope, EndpointVariant, ResolveAwsEndpoint};
+use aws_types::region::{Region, SigningRegion};
+use http::Uri;
+use std::collections::HashMap;
+
+/// Parameters used to resolve an AWS endpoint
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Params {
+    region: Option<Region>,
+    use_fips: bool,
+    use_dual_stack: bool,
+    endpoint: Option<String>,
+    bucket: Option<String>,
+    force_path_style: bool,
+}
+
+impl Params {
+    /// Returns a builder for [`Params`]
+    pub fn builder() -> Builder {
+        Builder::default()
+    }
+
+    /// The region requests will be sent to
+    pub fn region(&self) -> Option<&Region> {
+        self.region.as_ref()
+    }
+
+    /// When true, a FIPS-compliant endpoint must be used
+    pub fn use_fips(&self) -> bool {
+        self.use_fips
+    }
+
+    /// When true, an endpoint supporting both IPv4 and IPv6 must be used
+    pub fn use_dual_stack(&self) -> bool {
+        self.use_dual_stack
+    }
+
+    /// A custom endpoint URL that overrides endpoint resolution
+    pub fn endpoint(&self) -> Option<&str> {
+        self.endpoint.as_deref()
+    }
+
+    /// The S3 bucket (or access point ARN) targeted by the request
+    pub fn bucket(&self) -> Option<&str> {
+        self.bucket.as_deref()
+    }
+
+    /// When true, S3 buckets are never moved into the host
+    pub fn force_path_style(&self) -> bool {
+        self.force_path_style
+    }
+
+    fn variant(&self) -> EndpointVariant {
+        EndpointVariant::new()
+            .with_fips(self.use_fips)
+            .with_dual_stack(self.use_dual_stack)
+    }
+}
+
+/// Builder for [`Params`]
+#[derive(Clone, Debug, Default)]
+pub struct Builder {
+    params: Params,
+}
+
+impl Builder {
+    /// Sets the region requests will be sent to
+    pub fn region(mut self, region: impl Into<Option<Region>>) -> Self {
+        self.params.region = region.into();
+        self
+    }
+
+    /// Sets whether a FIPS-compliant endpoint must be used
+    pub fn use_fips(mut self, use_fips: bool) -> Self {
+        self.params.use_fips = use_fips;
+        self
+    }
+
+    /// Sets whether an endpoint supporting both IPv4 and IPv6 must be used
+    pub fn use_dual_stack(mut self, use_dual_stack: bool) -> Self {
+        self.params.use_dual_stack = use_dual_stack;
+        self
+    }
+
+    /// Sets a custom endpoint URL that overrides endpoint resolution
+    pub fn endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.params.endpoint = Some(endpoint.into());
+        self
+    }
+
+    /// Sets the S3 bucket (or access point ARN) targeted by the request
+    pub fn bucket(mut self, bucket: impl Into<String>) -> Self {
+        self.params.bucket = Some(bucket.into());
+        self
+    }
+
+    /// Sets whether S3 buckets should never be moved into the host
+    pub fn force_path_style(mut self, force_path_style: bool) -> Self {
+        self.params.force_path_style = force_path_style;
+        self
+    }
+
+    /// Constructs the parameters
+    pub fn build(self) -> Params {
+        self.params
+    }
+}
+
+impl ResolveEndpoint<Params> for PartitionResolver {
+    fn resolve_endpoint(&self, params: &Params) -> ResolveEndpointResult {
+        resolve(self, params)
+    }
+}
+
+/// Resolves `params` into an endpoint using `resolver` for region-based resolution
+///
+/// When a bucket is set, access point ARNs resolve to the access point's endpoint and DNS-compatible
+/// buckets are moved into the host (unless path-style addressing is forced). In both cases, the bucket
+/// is expected to be omitted from the request path.
+fn resolve(resolver: &dyn ResolveAwsEndpoint, params: &Params) -> ResolveEndpointResult {
+    let region = params.region().ok_or_else(|| {
+        ResolveEndpointError::message("a region must be set to resolve an endpoint")
+    })?;
+    let bucket = params.bucket().map(s3::S3Bucket::new);
+    let access_point = match &bucket {
+        Some(bucket) => {
+            s3::access_point_endpoint(bucket, region, params.variant()).map_err(|err| {
+                ResolveEndpointError::message("invalid access point").with_source(Some(err.into()))
+            })?
+        }
+        None => None,
+    };
+    let is_access_point = access_point.is_some();
+    let endpoint = match (access_point, params.endpoint()) {
+        (Some(endpoint), _) => endpoint,
+        (None, Some(url)) => {
+            let uri = url.parse::<Uri>().map_err(|err| {
+                ResolveEndpointError::message(format!(
+                    "custom endpoint `{}` is not a valid URI",
+                    url
+                ))
+                .with_source(Some(err.into()))
+            })?;
+            AwsEndpoint::new(Endpoint::immutable(uri), CredentialScope::default())
+        }
+        (None, None) => resolver
+            .resolve_endpoint_variant(region, params.variant())
+            .map_err(|err| {
+                ResolveEndpointError::message(format!(
+                    "failed to resolve an endpoint for `{}`",
+                    region
+                ))
+                .with_source(Some(err))
+            })?,
+    };
+
+    let uri = endpoint.endpoint().uri();
+    let scheme = uri.scheme_str().unwrap_or("https");
+    let authority = uri.authority().map(|a| a.as_str()).unwrap_or_default();
+    let virtual_host = match &bucket {
+        Some(bucket)
+            if !is_access_point
+                && !params.force_path_style()
+                && !endpoint.endpoint().is_immutable()
+                && s3::is_virtual_hostable(bucket.as_str(), scheme == "https") =>
+        {
+            Some(bucket.as_str())
+        }
+        _ => None,
+    };
+    let url = format!(
+        "{}://{}{}{}",
+        scheme,
+        virtual_host.map(|b| format!("{}.", b)).unwrap_or_default(),
+        authority,
+        uri.path().trim_end_matches('/')
+    );
+
+    let signing_region = endpoint
+        .credential_scope()
+        .region()
+        .cloned()
+        .unwrap_or_else(|| SigningRegion::from(region.clone()));
+    let mut sigv4 = HashMap::new();
+    sigv4.insert("name".to_string(), Document::from("sigv4"));
+    sigv4.insert(
+        "signingRegion".to_string(),
+        Document::from(signing_region.as_ref()),
+    );
+    if let Some(service) = endpoint.credential_scope().service() {
+        sigv4.insert("signingName".to_string(), Document::from(service.as_ref()));
+    }
+    Ok(SmithyEndpoint::builder()
+        .url(url)
+        .property("authSchemes", vec![Document::Object(sigv4)])
+        .build())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::params::Params;
+    use crate::partition::endpoint::{Metadata, Protocol, SignatureVersion};
+    use crate::partition::{Partition, PartitionResolver};
+    use crate::CredentialScope;
+    use aws_smithy_http::endpoint::{ResolveEndpoint, SmithyEndpoint};
+    use aws_smithy_types::Document;
+    use aws_types::region::Region;
+    use aws_types::SigningService;
+
+    fn resolver() -> PartitionResolver {
+        PartitionResolver::new(
+            Partition::builder()
+                .id("aws")
+                .region_regex(r#"^(us|eu)\-\w+\-\d+$"#)
+                .default_endpoint(Metadata {
+                    uri_template: "s3.{region}.amazonaws.com",
+                    protocol: Protocol::Https,
+                    credential_scope: CredentialScope::builder()
+                        .service(SigningService::from_static("s3"))
+                        .build(),
+                    signature_versions: SignatureVersion::V4,
+                })
+                .regionalized(crate::partition::Regionalized::Regionalized)
+                .build()
+                .expect("valid partition"),
+            vec![],
+        )
+    }
+
+    fn signing_region(endpoint: &SmithyEndpoint) -> Option<&str> {
+        match endpoint.properties().get("authSchemes") {
+            Some(Document::Array(schemes)) => match schemes.first() {
+                Some(Document::Object(scheme)) => match scheme.get("signingRegion") {
+                    Some(Document::String(region)) => Some(region),
+                    _ => None,
+                },
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn region_is_required() {
+        resolver()
+            .resolve_endpoint(&Params::builder().build())
+            .expect_err("no region");
+    }
+
+    #[test]
+    fn resolves_regional_endpoint_with_auth_scheme() {
+        let endpoint = resolver()
+            .resolve_endpoint(&Params::builder().region(Region::new("us-west-2")).build())
+            .expect("valid endpoint");
+        assert_eq!(endpoint.url(), "https://s3.us-west-2.amazonaws.com");
+        assert_eq!(signing_region(&endpoint), Some("us-west-2"));
+    }
+
+    #[test]
+    fn bucket_addressing() {
+        let params = Params::builder()
+            .region(Region::new("us-west-2"))
+            .bucket("my-bucket");
+        let endpoint = resolver()
+            .resolve_endpoint(&params.clone().build())
+            .expect("valid endpoint");
+        assert_eq!(
+            endpoint.url(),
+            "https://my-bucket.s3.us-west-2.amazonaws.com"
+        );
+
+        let endpoint = resolver()
+            .resolve_endpoint(&params.force_path_style(true).build())
+            .expect("valid endpoint");
+        assert_eq!(endpoint.url(), "https://s3.us-west-2.amazonaws.com");
+
+        let endpoint = resolver()
+            .resolve_endpoint(
+                &Params::builder()
+                    .region(Region::new("us-west-2"))
+                    .bucket("arn:aws:s3:us-west-2:123456789012:accesspoint/my-ap")
+                    .build(),
+            )
+            .expect("valid endpoint");
+        assert_eq!(
+            endpoint.url(),
+            "https://my-ap-123456789012.s3-accesspoint.us-west-2.amazonaws.com"
+        );
+    }
+
+    #[test]
+    fn custom_endpoint_is_used_verbatim() {
+        let endpoint = resolver()
+            .resolve_endpoint(
+                &Params::builder()
+                    .region(Region::new("us-west-2"))
+                    .endpoint("http://localhost:9000")
+                    .bucket("my-bucket")
+                    .build(),
+            )
+            .expect("valid endpoint");
+        assert_eq!(endpoint.url(), "http://localhost:9000");
+        assert_eq!(signing_region(&endpoint), Some("us-west-2"));
+    }
+}
@@ -5,7 +5,7 @@
 
 pub mod endpoint;
 
-use aws_types::endpoint::{AwsEndpoint, BoxError, ResolveAwsEndpoint};
+use aws_types::endpoint::{AwsEndpoint, BoxError, EndpointVariant, ResolveAwsEndpoint};
 use aws_types::region::Region;
 use regex::Regex;
 use std::collections::HashMap;
@@ -48,16 +48,30 @@ impl ResolveAwsEndpoint for PartitionResolver {
             .unwrap_or(&self.base);
         matching_partition.resolve_endpoint(region)
     }
+
+    fn resolve_endpoint_variant(
+        &self,
+        region: &Region,
+        variant: EndpointVariant,
+    ) -> Result<AwsEndpoint, BoxError> {
+        let matching_partition = self
+            .partitions()
+            .find(|partition| partition.can_resolve(region))
+            .unwrap_or(&self.base);
+        matching_partition.resolve_endpoint_variant(region, variant)
+    }
 }
 
 #[derive(Debug)]
 pub struct Partition {
-    _id: &'static str,
+    id: &'static str,
     region_regex: Regex,
     partition_endpoint: Option<Region>,
     regionalized: Regionalized,
     default_endpoint: endpoint::Metadata,
     endpoints: HashMap<Region, endpoint::Metadata>,
+    default_variants: HashMap<EndpointVariant, endpoint::Metadata>,
+    endpoint_variants: HashMap<(Region, EndpointVariant), endpoint::Metadata>,
 }
 
 #[derive(Default)]
@@ -68,6 +82,8 @@ pub struct Builder {
     regionalized: Option<Regionalized>,
     default_endpoint: Option<endpoint::Metadata>,
     endpoints: HashMap<Region, endpoint::Metadata>,
+    default_variants: HashMap<EndpointVariant, endpoint::Metadata>,
+    endpoint_variants: HashMap<(Region, EndpointVariant), endpoint::Metadata>,
 }
 
 impl Builder {
@@ -106,6 +122,28 @@ impl Builder {
         self
     }
 
+    /// Set the endpoint template used for `variant` in regions without a modeled variant endpoint
+    pub fn default_variant(
+        mut self,
+        variant: EndpointVariant,
+        endpoint: endpoint::Metadata,
+    ) -> Self {
+        self.default_variants.insert(variant, endpoint);
+        self
+    }
+
+    /// Set the endpoint used for `variant` in `region`
+    pub fn endpoint_variant(
+        mut self,
+        region: &'static str,
+        variant: EndpointVariant,
+        endpoint: endpoint::Metadata,
+    ) -> Self {
+        self.endpoint_variants
+            .insert((Region::new(region), variant), endpoint);
+        self
+    }
+
     /// Construct a Partition from the builder
     ///
     /// Returns `None` if:
@@ -115,12 +153,14 @@ impl Builder {
         let default_endpoint = self.default_endpoint?;
         let endpoints = self.endpoints.into_iter().collect();
         Some(Partition {
-            _id: self.id?,
+            id: self.id?,
             region_regex: self.region_regex?,
             partition_endpoint: self.partition_endpoint,
             regionalized: self.regionalized.unwrap_or_default(),
             default_endpoint,
             endpoints,
+            default_variants: self.default_variants,
+            endpoint_variants: self.endpoint_variants,
         })
     }
 }
@@ -145,6 +185,14 @@ impl Partition {
     pub fn builder() -> Builder {
         Builder::default()
     }
+
+    /// The region used to look up endpoints after an exact match for the region fails
+    fn resolved_region<'a>(&'a self, region: &'a Region) -> Option<&'a Region> {
+        match self.regionalized {
+            Regionalized::NotRegionalized => self.partition_endpoint.as_ref(),
+            Regionalized::Regionalized => Some(region),
+        }
+    }
 }
 
 impl ResolveAwsEndpoint for Partition {
@@ -152,15 +200,47 @@ impl ResolveAwsEndpoint for Partition {
         if let Some(endpoint) = self.endpoints.get(region) {
             return endpoint.resolve_endpoint(region);
         }
-        let resolved_region = match self.regionalized {
-            Regionalized::NotRegionalized => self.partition_endpoint.as_ref(),
-            Regionalized::Regionalized => Some(region),
-        };
-        let endpoint_for_region = resolved_region
+        let endpoint_for_region = self
+            .resolved_region(region)
             .and_then(|region| self.endpoints.get(region))
             .unwrap_or(&self.default_endpoint);
         endpoint_for_region.resolve_endpoint(region)
     }
+
+    /// Resolve an endpoint variant
+    ///
+    /// Endpoints modeled for the variant in the region (or the partition endpoint for non-regionalized
+    /// services) take priority. Otherwise, the partition's default template for the variant is used
+    /// with the credential scope of the standard endpoint. If the partition has no such template, the
+    /// variant is not supported and an error is returned.
+    fn resolve_endpoint_variant(
+        &self,
+        region: &Region,
+        variant: EndpointVariant,
+    ) -> Result<AwsEndpoint, BoxError> {
+        if variant.is_default() {
+            return self.resolve_endpoint(region);
+        }
+        let modeled = iter::once(region)
+            .chain(self.resolved_region(region))
+            .find_map(|region| self.endpoint_variants.get(&(region.clone(), variant)));
+        if let Some(endpoint) = modeled {
+            return endpoint.resolve_endpoint(region);
+        }
+        let template = self.default_variants.get(&variant).ok_or_else(|| {
+            format!(
+                "no {} endpoint is available in the `{}` partition (region: {})",
+                variant, self.id, region
+            )
+        })?;
+        let standard = self.resolve_endpoint(region)?;
+        let templated_region = self.resolved_region(region).unwrap_or(region);
+        let endpoint = template.resolve_endpoint(templated_region)?;
+        Ok(AwsEndpoint::new(
+            endpoint.endpoint().clone(),
+            standard.credential_scope().clone(),
+        ))
+    }
 }
 
 #[cfg(test)]
@@ -170,7 +250,8 @@ mod test {
     use crate::partition::endpoint::SignatureVersion::{self, V4};
     use crate::partition::{endpoint, Partition};
     use crate::partition::{PartitionResolver, Regionalized};
-    use crate::{CredentialScope, ResolveAwsEndpoint};
+    use crate::{AwsEndpoint, CredentialScope, ResolveAwsEndpoint};
+    use aws_types::endpoint::EndpointVariant;
     use aws_types::region::{Region, SigningRegion};
     use aws_types::SigningService;
     use http::Uri;
@@ -367,6 +448,96 @@ mod test {
         }
     }
 
+    fn variant_partition() -> Partition {
+        let metadata = |uri_template| Metadata {
+            uri_template,
+            protocol: Https,
+            credential_scope: CredentialScope::default(),
+            signature_versions: V4,
+        };
+        Partition::builder()
+            .id("part-id-4")
+            .region_regex(r#"^(us)-\w+-\d+$"#)
+            .default_endpoint(metadata("service.{region}.amazonaws.com"))
+            .endpoint(
+                "us-west-2",
+                Metadata {
+                    uri_template: "service.us-west-2.amazonaws.com",
+                    protocol: Https,
+                    credential_scope: CredentialScope::builder()
+                        .region(SigningRegion::from_static("us-west-2-signing"))
+                        .build(),
+                    signature_versions: V4,
+                },
+            )
+            .default_variant(
+                EndpointVariant::new().with_fips(true),
+                metadata("service-fips.{region}.amazonaws.com"),
+            )
+            .endpoint_variant(
+                "us-west-1",
+                EndpointVariant::new().with_fips(true),
+                metadata("service-fips.us-west-1.modeled.com"),
+            )
+            .build()
+            .expect("valid partition")
+    }
+
+    #[test]
+    fn resolve_endpoint_variants() {
+        let partition = variant_partition();
+        let fips = EndpointVariant::new().with_fips(true);
+        let uri = |endpoint: AwsEndpoint| {
+            let mut uri = Uri::from_static("/");
+            endpoint.set_endpoint(&mut uri, None);
+            uri
+        };
+
+        // modeled variant endpoints are preferred
+        let endpoint = partition
+            .resolve_endpoint_variant(&Region::new("us-west-1"), fips)
+            .expect("fips is supported");
+        assert_eq!(
+            uri(endpoint),
+            Uri::from_static("https://service-fips.us-west-1.modeled.com")
+        );
+
+        // otherwise, the default template is used with the standard credential scope
+        let endpoint = partition
+            .resolve_endpoint_variant(&Region::new("us-west-2"), fips)
+            .expect("fips is supported");
+        assert_eq!(
+            endpoint.credential_scope().region(),
+            Some(&SigningRegion::from_static("us-west-2-signing"))
+        );
+        assert_eq!(
+            uri(endpoint),
+            Uri::from_static("https://service-fips.us-west-2.amazonaws.com")
+        );
+
+        // the default variant is the standard endpoint
+        let endpoint = partition
+            .resolve_endpoint_variant(&Region::new("us-east-1"), EndpointVariant::new())
+            .expect("standard endpoints are always supported");
+        assert_eq!(
+            uri(endpoint),
+            Uri::from_static("https://service.us-east-1.amazonaws.com")
+        );
+
+        // variants without a template are unsupported
+        let err = partition
+            .resolve_endpoint_variant(
+                &Region::new("us-east-1"),
+                EndpointVariant::new().with_dual_stack(true),
+            )
+            .expect_err("dual-stack is not supported");
+        assert!(
+            err.to_string().contains("no dual-stack endpoint"),
+            "{}",
+            err
+        );
+    }
+
     #[track_caller]
     fn check_endpoint(resolver: &impl ResolveAwsEndpoint, test_case: &TestCase) {
         let endpoint = resolver
@@ -0,0 +1,497 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! S3-specific endpoint customizations
+//!
+//! S3 requests are modeled with the bucket as the first segment of the request path (path-style
+//! addressing). When an [`S3Bucket`] is present in the property bag, [`AwsEndpointStage`](crate::AwsEndpointStage)
+//! will instead:
+//! - move the bucket into the host (virtual-hosted-style addressing) when the bucket name is DNS compatible
+//!   and path-style addressing has not been forced with [`set_force_path_style`]. Immutable endpoints
+//!   (see [`Endpoint::immutable`]) are never rewritten.
+//! - route requests for access point ARNs to the access point's own endpoint.
+
+use aws_smithy_http::endpoint::Endpoint;
+use aws_smithy_http::property_bag::PropertyBag;
+use aws_types::endpoint::{AwsEndpoint, CredentialScope, EndpointVariant};
+use aws_types::region::{Region, SigningRegion};
+use aws_types::SigningService;
+use http::uri::{Authority, PathAndQuery};
+use http::Uri;
+use std::error::Error;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+
+/// The bucket targeted by an S3 request
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct S3Bucket(String);
+
+impl S3Bucket {
+    /// Creates a new `S3Bucket` from a bucket name or access point ARN
+    pub fn new(bucket: impl Into<String>) -> Self {
+        Self(bucket.into())
+    }
+
+    /// Returns the bucket as a string slice
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// When true, path-style addressing will be used for all buckets
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct ForcePathStyle(bool);
+
+/// Sets the bucket targeted by the request
+pub fn set_bucket(properties: &mut PropertyBag, bucket: impl Into<String>) {
+    properties.insert(S3Bucket::new(bucket));
+}
+
+/// Forces path-style addressing (e.g. `https://s3.us-east-1.amazonaws.com/bucket/key`) for the request
+pub fn set_force_path_style(properties: &mut PropertyBag, force_path_style: bool) {
+    properties.insert(ForcePathStyle(force_path_style));
+}
+
+/// Errors that may occur when resolving an endpoint for an access point ARN
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum AccessPointError {
+    /// The ARN could not be parsed as an S3 access point ARN
+    InvalidArn {
+        /// The invalid ARN
+        arn: String,
+        /// Why the ARN is invalid
+        message: &'static str,
+    },
+
+    /// The ARN is for a region other than the client's region
+    RegionMismatch {
+        /// The region from the ARN
+        arn_region: String,
+        /// The region the client is configured for
+        client_region: Region,
+    },
+
+    /// The ARN is for a partition without a known DNS suffix
+    UnknownPartition(String),
+}
+
+impl Display for AccessPointError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            AccessPointError::InvalidArn { arn, message } => {
+                write!(f, "invalid access point ARN `{}`: {}", arn, message)
+            }
+            AccessPointError::RegionMismatch {
+                arn_region,
+                client_region,
+            } => write!(
+                f,
+                "the access point ARN is in `{}` but the client is configured for `{}`",
+                arn_region, client_region
+            ),
+            AccessPointError::UnknownPartition(partition) => {
+                write!(
+                    f,
+                    "access points are not supported in the `{}` partition",
+                    partition
+                )
+            }
+        }
+    }
+}
+
+impl Error for AccessPointError {}
+
+/// A parsed access point ARN, e.g. `arn:aws:s3:us-west-2:123456789012:accesspoint/my-access-point`
+#[derive(Debug, PartialEq, Eq)]
+struct AccessPointArn<'a> {
+    partition: &'a str,
+    region: &'a str,
+    account_id: &'a str,
+    name: &'a str,
+}
+
+impl<'a> AccessPointArn<'a> {
+    /// Parses `bucket` as an access point ARN
+    ///
+    /// Returns `Ok(None)` if `bucket` is not an ARN at all.
+    fn parse(bucket: &'a str) -> Result<Option<Self>, AccessPointError> {
+        if !bucket.starts_with("arn:") {
+            return Ok(None);
+        }
+        let invalid = |message| AccessPointError::InvalidArn {
+            arn: bucket.to_string(),
+            message,
+        };
+        let mut parts = bucket.splitn(6, ':');
+        let (partition, service, region, account_id, resource) = match (
+            parts.next(),
+            parts.next(),
+            parts.next(),
+            parts.next(),
+            parts.next(),
+            parts.next(),
+        ) {
+            (
+                Some("arn"),
+                Some(partition),
+                Some(service),
+                Some(region),
+                Some(account_id),
+                Some(resource),
+            ) => (partition, service, region, account_id, resource),
+            _ => return Err(invalid("ARNs must have six `:` separated components")),
+        };
+        if service != "s3" {
+            return Err(invalid("only `s3` ARNs may be used as a bucket"));
+        }
+        if region.is_empty() {
+            return Err(invalid("the ARN must include a region"));
+        }
+        if !crate::account_id::is_valid_account_id(account_id) {
+            return Err(invalid("the account ID must be 12 digits"));
+        }
+        let name = resource
+            .strip_prefix("accesspoint/")
+            .or_else(|| resource.strip_prefix("accesspoint:"))
+            .ok_or_else(|| invalid("only access point ARNs are supported"))?;
+        if name.is_empty() || !name.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-') {
+            return Err(invalid(
+                "access point names may only contain letters, numbers and `-`",
+            ));
+        }
+        Ok(Some(AccessPointArn {
+            partition,
+            region,
+            account_id,
+            name,
+        }))
+    }
+
+    fn dns_suffix(&self) -> Result<&'static str, AccessPointError> {
+        match self.partition {
+            "aws" | "aws-us-gov" => Ok("amazonaws.com"),
+            "aws-cn" => Ok("amazonaws.com.cn"),
+            "aws-iso" => Ok("c2s.ic.gov"),
+            "aws-iso-b" => Ok("sc2s.sgov.gov"),
+            other => Err(AccessPointError::UnknownPartition(other.to_string())),
+        }
+    }
+}
+
+/// Returns the endpoint for `bucket` if it is an access point ARN
+///
+/// Access point requests are signed for the region in the ARN, which must match the client region.
+pub(crate) fn access_point_endpoint(
+    bucket: &S3Bucket,
+    region: &Region,
+    variant: EndpointVariant,
+) -> Result<Option<AwsEndpoint>, AccessPointError> {
+    let arn = match AccessPointArn::parse(bucket.as_str())? {
+        Some(arn) => arn,
+        None => return Ok(None),
+    };
+    if arn.region != region.as_ref() {
+        return Err(AccessPointError::RegionMismatch {
+            arn_region: arn.region.to_string(),
+            client_region: region.clone(),
+        });
+    }
+    let uri = format!(
+        "https://{name}-{account_id}.s3-accesspoint{fips}{dualstack}.{region}.{dns_suffix}",
+        name = arn.name,
+        account_id = arn.account_id,
+        fips = if variant.use_fips() { "-fips" } else { "" },
+        dualstack = if variant.use_dual_stack() {
+            ".dualstack"
+        } else {
+            ""
+        },
+        region = arn.region,
+        dns_suffix = arn.dns_suffix()?,
+    );
+    let uri = Uri::try_from(uri).map_err(|_| AccessPointError::InvalidArn {
+        arn: bucket.as_str().to_string(),
+        message: "the ARN does not form a valid hostname",
+    })?;
+    Ok(Some(AwsEndpoint::new(
+        Endpoint::mutable(uri),
+        CredentialScope::builder()
+            .region(SigningRegion::from(Region::new(arn.region.to_string())))
+            .service(SigningService::from_static("s3"))
+            .build(),
+    )))
+}
+
+/// Rewrites a path-style `uri` for `bucket` once the endpoint has been applied
+///
+/// - Access point requests have the bucket removed from the path (the access point is in the host).
+/// - Virtual-hostable buckets are moved from the path into the host unless path-style addressing is forced.
+/// - Otherwise, the URI is left untouched.
+pub(crate) fn apply_addressing(
+    uri: &mut Uri,
+    bucket: &S3Bucket,
+    is_access_point: bool,
+    properties: &PropertyBag,
+) {
+    let force_path_style = properties
+        .get::<ForcePathStyle>()
+        .copied()
+        .unwrap_or_default()
+        .0;
+    if is_access_point {
+        remove_bucket_from_path(uri, None);
+    } else if !force_path_style
+        && is_virtual_hostable(bucket.as_str(), uri.scheme_str() == Some("https"))
+    {
+        remove_bucket_from_path(uri, Some(bucket.as_str()));
+    }
+}
+
+/// Returns true if `bucket` can be used as a DNS label
+///
+/// Buckets containing `.` can't be verified against S3's wildcard TLS certificate, so they're
+/// only virtual-hostable over plain HTTP.
+pub(crate) fn is_virtual_hostable(bucket: &str, https: bool) -> bool {
+    let valid_chars = bucket
+        .bytes()
+        .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'.' || b == b'-');
+    let starts_and_ends_alphanumeric = bucket
+        .bytes()
+        .next()
+        .zip(bucket.bytes().last())
+        .map(|(first, last)| first.is_ascii_alphanumeric() && last.is_ascii_alphanumeric())
+        .unwrap_or(false);
+    let looks_like_ip = bucket.split('.').count() == 4
+        && bucket
+            .split('.')
+            .all(|part| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit()));
+    (3..=63).contains(&bucket.len())
+        && valid_chars
+        && starts_and_ends_alphanumeric
+        && !bucket.contains("..")
+        && !bucket.contains(".-")
+        && !bucket.contains("-.")
+        && !looks_like_ip
+        && !(https && bucket.contains('.'))
+}
+
+/// Removes the first path segment (the bucket) from `uri`, optionally prefixing the host with `host_prefix`
+fn remove_bucket_from_path(uri: &mut Uri, host_prefix: Option<&str>) {
+    let path = uri.path();
+    // the bucket is a non-greedy label, so any `/` it contains has been percent-encoded
+    let remaining_path = match path.trim_start_matches('/').split_once('/') {
+        Some((_bucket, rest)) => format!("/{}", rest),
+        None => "/".to_string(),
+    };
+    let path_and_query = match uri.query() {
+        Some(query) => format!("{}?{}", remaining_path, query),
+        None => remaining_path,
+    };
+    let authority = match (host_prefix, uri.authority()) {
+        (Some(prefix), Some(authority)) => format!("{}.{}", prefix, authority),
+        (_, authority) => authority.map(|a| a.to_string()).unwrap_or_default(),
+    };
+    let mut parts = uri.clone().into_parts();
+    parts.path_and_query = Some(
+        path_and_query
+            .parse::<PathAndQuery>()
+            .expect("derived from a valid path"),
+    );
+    if !authority.is_empty() {
+        match authority.parse::<Authority>() {
+            Ok(authority) => parts.authority = Some(authority),
+            // the bucket was validated as a DNS label, so this is unreachable in practice
+            Err(_) => return,
+        }
+    }
+    *uri = Uri::from_parts(parts).expect("derived from a valid URI");
+}
+
+#[cfg(test)]
+mod test {
+    use crate::s3::{
+        access_point_endpoint, apply_addressing, is_virtual_hostable, set_force_path_style,
+        AccessPointArn, AccessPointError, S3Bucket,
+    };
+    use aws_smithy_http::property_bag::PropertyBag;
+    use aws_types::endpoint::EndpointVariant;
+    use aws_types::region::{Region, SigningRegion};
+    use http::Uri;
+
+    fn addressed(uri: &'static str, bucket: &str, force_path_style: bool) -> Uri {
+        let mut uri = Uri::from_static(uri);
+        let mut props = PropertyBag::new();
+        set_force_path_style(&mut props, force_path_style);
+        apply_addressing(&mut uri, &S3Bucket::new(bucket), false, &props);
+        uri
+    }
+
+    #[test]
+    fn virtual_hostable_buckets() {
+        assert!(is_virtual_hostable("my-bucket", true));
+        assert!(is_virtual_hostable("abc", true));
+        assert!(is_virtual_hostable("my.bucket", false));
+        assert!(!is_virtual_hostable("my.bucket", true));
+        assert!(!is_virtual_hostable("ab", true));
+        assert!(!is_virtual_hostable(&"a".repeat(64), true));
+        assert!(!is_virtual_hostable("My-Bucket", true));
+        assert!(!is_virtual_hostable("my_bucket", true));
+        assert!(!is_virtual_hostable("-bucket", true));
+        assert!(!is_virtual_hostable("bucket-", true));
+        assert!(!is_virtual_hostable("my..bucket", false));
+        assert!(!is_virtual_hostable("my-.bucket", false));
+        assert!(!is_virtual_hostable("192.168.1.1", false));
+    }
+
+    #[test]
+    fn bucket_moves_into_host() {
+        assert_eq!(
+            addressed(
+                "https://s3.us-east-1.amazonaws.com/my-bucket/some/key?x-id=GetObject",
+                "my-bucket",
+                false
+            ),
+            Uri::from_static(
+                "https://my-bucket.s3.us-east-1.amazonaws.com/some/key?x-id=GetObject"
+            )
+        );
+        assert_eq!(
+            addressed(
+                "https://s3.us-east-1.amazonaws.com/my-bucket?list-type=2",
+                "my-bucket",
+                false
+            ),
+            Uri::from_static("https://my-bucket.s3.us-east-1.amazonaws.com/?list-type=2")
+        );
+        assert_eq!(
+            addressed("http://localhost:9000/my-bucket/key", "my-bucket", false),
+            Uri::from_static("http://my-bucket.localhost:9000/key")
+        );
+    }
+
+    #[test]
+    fn path_style_fallback() {
+        assert_eq!(
+            addressed(
+                "https://s3.us-east-1.amazonaws.com/my-bucket/key",
+                "my-bucket",
+                true
+            ),
+            Uri::from_static("https://s3.us-east-1.amazonaws.com/my-bucket/key")
+        );
+        assert_eq!(
+            addressed(
+                "https://s3.us-east-1.amazonaws.com/My_Bucket/key",
+                "My_Bucket",
+                false
+            ),
+            Uri::from_static("https://s3.us-east-1.amazonaws.com/My_Bucket/key")
+        );
+        assert_eq!(
+            addressed(
+                "https://s3.us-east-1.amazonaws.com/my.bucket/key",
+                "my.bucket",
+                false
+            ),
+            Uri::from_static("https://s3.us-east-1.amazonaws.com/my.bucket/key")
+        );
+    }
+
+    #[test]
+    fn parse_access_point_arns() {
+        assert_eq!(AccessPointArn::parse("my-bucket").unwrap(), None);
+        assert_eq!(
+            AccessPointArn::parse("arn:aws:s3:us-west-2:123456789012:accesspoint/my-ap").unwrap(),
+            Some(AccessPointArn {
+                partition: "aws",
+                region: "us-west-2",
+                account_id: "123456789012",
+                name: "my-ap"
+            })
+        );
+        assert_eq!(
+            AccessPointArn::parse("arn:aws:s3:us-west-2:123456789012:accesspoint:my-ap")
+                .unwrap()
+                .map(|arn| arn.name),
+            Some("my-ap")
+        );
+        for invalid in [
+            "arn:aws:s3",
+            "arn:aws:sqs:us-west-2:123456789012:accesspoint/my-ap",
+            "arn:aws:s3::123456789012:accesspoint/my-ap",
+            "arn:aws:s3:us-west-2:1234:accesspoint/my-ap",
+            "arn:aws:s3:us-west-2:123456789012:bucket/my-bucket",
+            "arn:aws:s3:us-west-2:123456789012:accesspoint/my_ap",
+        ] {
+            assert!(
+                matches!(
+                    AccessPointArn::parse(invalid),
+                    Err(AccessPointError::InvalidArn { .. })
+                ),
+                "{} should be invalid",
+                invalid
+            );
+        }
+    }
+
+    #[test]
+    fn access_point_endpoints() {
+        let bucket = S3Bucket::new("arn:aws:s3:us-west-2:123456789012:accesspoint/my-ap");
+        let region = Region::new("us-west-2");
+        let resolve = |variant| {
+            let endpoint = access_point_endpoint(&bucket, &region, variant)
+                .expect("valid access point")
+                .expect("is an access point");
+            let mut uri = Uri::from_static(
+                "/arn%3Aaws%3As3%3Aus-west-2%3A123456789012%3Aaccesspoint%2Fmy-ap/key",
+            );
+            endpoint.set_endpoint(&mut uri, None);
+            (endpoint, uri)
+        };
+
+        let (endpoint, mut uri) = resolve(EndpointVariant::new());
+        apply_addressing(&mut uri, &bucket, true, &PropertyBag::new());
+        assert_eq!(
+            uri,
+            Uri::from_static(
+                "https://my-ap-123456789012.s3-accesspoint.us-west-2.amazonaws.com/key"
+            )
+        );
+        assert_eq!(
+            endpoint.credential_scope().region(),
+            Some(&SigningRegion::from(Region::new("us-west-2")))
+        );
+
+        let (_, uri) = resolve(EndpointVariant::new().with_fips(true).with_dual_stack(true));
+        assert_eq!(
+            uri.host(),
+            Some("my-ap-123456789012.s3-accesspoint-fips.dualstack.us-west-2.amazonaws.com")
+        );
+    }
+
+    #[test]
+    fn access_point_region_must_match() {
+        let bucket = S3Bucket::new("arn:aws:s3:us-west-2:123456789012:accesspoint/my-ap");
+        let err = access_point_endpoint(&bucket, &Region::new("us-east-1"), EndpointVariant::new())
+            .expect_err("regions differ");
+        assert!(
+            matches!(err, AccessPointError::RegionMismatch { .. }),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn buckets_are_not_access_points() {
+        let bucket = S3Bucket::new("my-bucket");
+        assert!(
+            access_point_endpoint(&bucket, &Region::new("us-east-1"), EndpointVariant::new())
+                .unwrap()
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,103 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! STS-specific endpoint customizations
+//!
+//! When [`StsRegionalEndpoints::Legacy`] is set with [`set_sts_regional_endpoints`],
+//! [`AwsEndpointStage`](crate::AwsEndpointStage) resolves the `aws-global` endpoint
+//! (`sts.amazonaws.com`, signed for `us-east-1`) instead of the regional endpoint for requests made
+//! in one of the regions that predate regional STS endpoints. Only the standard endpoint variant is
+//! affected: FIPS and dual-stack requests always use the regional endpoint.
+
+use aws_smithy_http::property_bag::PropertyBag;
+use aws_types::endpoint::{EndpointVariant, StsRegionalEndpoints};
+use aws_types::region::Region;
+use std::borrow::Cow;
+
+/// Regions in which STS requests went to the global endpoint before regional endpoints existed
+const LEGACY_GLOBAL_REGIONS: &[&str] = &[
+    "ap-northeast-1",
+    "ap-south-1",
+    "ap-southeast-1",
+    "ap-southeast-2",
+    "aws-global",
+    "ca-central-1",
+    "eu-central-1",
+    "eu-north-1",
+    "eu-west-1",
+    "eu-west-2",
+    "eu-west-3",
+    "sa-east-1",
+    "us-east-1",
+    "us-east-2",
+    "us-west-1",
+    "us-west-2",
+];
+
+const GLOBAL_REGION: &str = "aws-global";
+
+/// Sets whether STS requests in the legacy global regions use the global STS endpoint
+pub fn set_sts_regional_endpoints(properties: &mut PropertyBag, setting: StsRegionalEndpoints) {
+    properties.insert(setting);
+}
+
+/// Returns the region to resolve an endpoint for, replacing `region` with `aws-global` when legacy
+/// STS endpoints are enabled for the request
+pub(crate) fn resolution_region<'a>(
+    region: &'a Region,
+    variant: EndpointVariant,
+    properties: &PropertyBag,
+) -> Cow<'a, Region> {
+    let legacy = properties.get::<StsRegionalEndpoints>() == Some(&StsRegionalEndpoints::Legacy);
+    if legacy && variant.is_default() && LEGACY_GLOBAL_REGIONS.contains(&region.as_ref()) {
+        Cow::Owned(Region::from_static(GLOBAL_REGION))
+    } else {
+        Cow::Borrowed(region)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::sts::{resolution_region, set_sts_regional_endpoints};
+    use aws_smithy_http::property_bag::PropertyBag;
+    use aws_types::endpoint::{EndpointVariant, StsRegionalEndpoints};
+    use aws_types::region::Region;
+
+    fn resolve(region: &'static str, setting: Option<StsRegionalEndpoints>) -> Region {
+        let mut properties = PropertyBag::new();
+        if let Some(setting) = setting {
+            set_sts_regional_endpoints(&mut properties, setting);
+        }
+        resolution_region(
+            &Region::from_static(region),
+            EndpointVariant::default(),
+            &properties,
+        )
+        .into_owned()
+    }
+
+    #[test]
+    fn legacy_regions_use_the_global_endpoint() {
+        let legacy = Some(StsRegionalEndpoints::Legacy);
+        assert_eq!(resolve("us-west-2", legacy), Region::new("aws-global"));
+        assert_eq!(resolve("af-south-1", legacy), Region::new("af-south-1"));
+        assert_eq!(
+            resolve("us-west-2", Some(StsRegionalEndpoints::Regional)),
+            Region::new("us-west-2")
+        );
+        assert_eq!(resolve("us-west-2", None), Region::new("us-west-2"));
+    }
+
+    #[test]
+    fn variants_always_use_the_regional_endpoint() {
+        let mut properties = PropertyBag::new();
+        set_sts_regional_endpoints(&mut properties, StsRegionalEndpoints::Legacy);
+        let region = Region::new("us-east-1");
+        assert_eq!(
+            *resolution_region(&region, EndpointVariant::new().with_fips(true), &properties),
+            region
+        );
+    }
+}
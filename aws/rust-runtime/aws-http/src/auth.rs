@@ -3,10 +3,16 @@
  * SPDX-License-Identifier: Apache-2.0.
  */
 
+use aws_smithy_async::time::Instant;
+use aws_smithy_http::diagnostic::DiagnosticContext;
 use aws_smithy_http::middleware::AsyncMapRequest;
 use aws_smithy_http::operation::Request;
+use aws_smithy_http::pipeline::{PipelineConfig, Stage};
 use aws_smithy_http::property_bag::PropertyBag;
-use aws_types::credentials::{CredentialsError, ProvideCredentials, SharedCredentialsProvider};
+use aws_smithy_http::timing::TimingInfo;
+use aws_types::credentials::{
+    Anonymous, CredentialsError, ProvideCredentials, SharedCredentialsProvider,
+};
 use std::future::Future;
 use std::pin::Pin;
 
@@ -15,13 +21,20 @@ pub fn set_provider(bag: &mut PropertyBag, provider: SharedCredentialsProvider)
     bag.insert(provider);
 }
 
+/// Marks the request in the given property bag as [`Anonymous`], so that no credentials are loaded for it.
+pub fn set_anonymous(bag: &mut PropertyBag) {
+    bag.insert(Anonymous::new());
+}
+
 /// Middleware stage that loads credentials from a [CredentialsProvider](aws_types::credentials::ProvideCredentials)
 /// and places them in the property bag of the request.
 ///
 /// [CredentialsStage] implements [`AsyncMapRequest`](aws_smithy_http::middleware::AsyncMapRequest), and:
-/// 1. Retrieves a `CredentialsProvider` from the property bag.
-/// 2. Calls the credential provider's `provide_credentials` and awaits its result.
-/// 3. Places returned `Credentials` into the property bad to drive downstream signing middleware.
+/// 1. Returns the request unchanged if it is marked as [`Anonymous`] or its
+///    [`PipelineConfig`](aws_smithy_http::pipeline::PipelineConfig) skips signing.
+/// 2. Retrieves a `CredentialsProvider` from the property bag.
+/// 3. Calls the credential provider's `provide_credentials` and awaits its result.
+/// 4. Places returned `Credentials` into the property bad to drive downstream signing middleware.
 #[derive(Clone, Debug, Default)]
 #[non_exhaustive]
 pub struct CredentialsStage;
@@ -33,6 +46,16 @@ impl CredentialsStage {
     }
 
     async fn load_creds(mut request: Request) -> Result<Request, CredentialsStageError> {
+        if request.properties().get::<Anonymous>().is_some() {
+            tracing::debug!("request is anonymous, skipping credentials resolution");
+            return Ok(request);
+        }
+        if PipelineConfig::skips(&request.properties(), Stage::Signing) {
+            tracing::debug!(
+                "signing is skipped for this operation, skipping credentials resolution"
+            );
+            return Ok(request);
+        }
         let provider = request
             .properties()
             .get::<SharedCredentialsProvider>()
@@ -44,9 +67,15 @@ impl CredentialsStage {
                 return Ok(request);
             }
         };
-        match provider.provide_credentials().await {
+        let start = Instant::now();
+        let creds = provider.provide_credentials().await;
+        TimingInfo::record(&mut request.properties_mut()).credentials = Some(start.elapsed());
+        match creds {
             Ok(creds) => {
-                request.properties_mut().insert(creds);
+                let mut properties = request.properties_mut();
+                DiagnosticContext::record(&mut properties).credentials_source =
+                    Some(creds.provider_name().to_string());
+                properties.insert(creds);
             }
             // ignore the case where there is no provider wired up
             Err(CredentialsError::CredentialsNotLoaded { .. }) => {
@@ -74,7 +103,15 @@ mod error {
         CredentialsLoadingError(CredentialsError),
     }
 
-    impl StdError for CredentialsStageError {}
+    impl StdError for CredentialsStageError {
+        fn source(&self) -> Option<&(dyn StdError + 'static)> {
+            use CredentialsStageError::*;
+            match self {
+                MissingCredentialsProvider => None,
+                CredentialsLoadingError(err) => Some(err as _),
+            }
+        }
+    }
 
     impl fmt::Display for CredentialsStageError {
         fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -110,15 +147,20 @@ impl AsyncMapRequest for CredentialsStage {
     fn apply(&self, request: Request) -> BoxFuture<Result<Request, Self::Error>> {
         Box::pin(Self::load_creds(request))
     }
+
+    fn name(&self) -> &'static str {
+        "credentials"
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::set_provider;
     use super::CredentialsStage;
+    use super::{set_anonymous, set_provider};
     use aws_smithy_http::body::SdkBody;
     use aws_smithy_http::middleware::AsyncMapRequest;
     use aws_smithy_http::operation;
+    use aws_smithy_http::pipeline::{PipelineConfig, Stage};
     use aws_types::credentials::{
         future, CredentialsError, ProvideCredentials, SharedCredentialsProvider,
     };
@@ -168,6 +210,59 @@ mod tests {
             .expect_err("no credential provider should not populate credentials");
     }
 
+    #[tokio::test]
+    async fn provider_failure_exposes_credentials_error() {
+        let mut req = operation::Request::new(http::Request::new(SdkBody::from("some body")));
+        set_provider(
+            &mut req.properties_mut(),
+            SharedCredentialsProvider::new(Unhandled),
+        );
+        let err = CredentialsStage::new()
+            .apply(req)
+            .await
+            .expect_err("provider failed");
+        let source = std::error::Error::source(&err)
+            .and_then(|source| source.downcast_ref::<CredentialsError>())
+            .expect("the source should be a CredentialsError");
+        assert!(
+            matches!(source, CredentialsError::Unhandled { .. }),
+            "{:?}",
+            source
+        );
+    }
+
+    #[tokio::test]
+    async fn anonymous_request_skips_provider() {
+        let mut req = operation::Request::new(http::Request::new(SdkBody::from("some body")));
+        set_provider(
+            &mut req.properties_mut(),
+            SharedCredentialsProvider::new(Unhandled),
+        );
+        set_anonymous(&mut req.properties_mut());
+        let req = CredentialsStage::new()
+            .apply(req)
+            .await
+            .expect("the provider should never be called for an anonymous request");
+        assert!(req.properties().get::<Credentials>().is_none());
+    }
+
+    #[tokio::test]
+    async fn skipped_signing_skips_provider() {
+        let mut req = operation::Request::new(http::Request::new(SdkBody::from("some body")));
+        set_provider(
+            &mut req.properties_mut(),
+            SharedCredentialsProvider::new(Unhandled),
+        );
+        PipelineConfig::new()
+            .skip(Stage::Signing)
+            .apply(&mut req.properties_mut());
+        let req = CredentialsStage::new()
+            .apply(req)
+            .await
+            .expect("the provider should never be called when signing is skipped");
+        assert!(req.properties().get::<Credentials>().is_none());
+    }
+
     #[tokio::test]
     async fn credentials_not_loaded_is_ok() {
         let mut req = operation::Request::new(http::Request::new(SdkBody::from("some body")));
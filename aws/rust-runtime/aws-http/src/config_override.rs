@@ -0,0 +1,155 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use aws_smithy_http::property_bag::PropertyBag;
+use aws_smithy_types::retry::RetryConfig;
+use aws_types::credentials::{ProvideCredentials, SharedCredentialsProvider};
+use aws_types::endpoint::ResolveAwsEndpoint;
+use aws_types::region::Region;
+use std::sync::Arc;
+
+/// Overrides of client configuration for a single operation.
+///
+/// Service configuration is applied to every operation when the operation is constructed. To
+/// change it for one call only, build a `ConfigOverride` and [apply](ConfigOverride::apply) it to
+/// the operation's property bag before sending it, e.g. from a fluent builder's `customize()`:
+///
+/// ```rust,ignore
+/// let overrides = ConfigOverride::new().region(Region::new("eu-west-1"));
+/// let response = client
+///     .list_tables()
+///     .customize()
+///     .await?
+///     .map_properties(|properties| overrides.apply(properties))
+///     .send()
+///     .await?;
+/// ```
+///
+/// The signing region is derived from the overridden region by the endpoint middleware.
+#[derive(Clone, Debug, Default)]
+pub struct ConfigOverride {
+    region: Option<Region>,
+    credentials_provider: Option<SharedCredentialsProvider>,
+    endpoint_resolver: Option<Arc<dyn ResolveAwsEndpoint>>,
+    retry_config: Option<RetryConfig>,
+}
+
+impl ConfigOverride {
+    /// Creates a new `ConfigOverride` that doesn't override anything.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Overrides the region the operation is sent to.
+    pub fn region(mut self, region: Region) -> Self {
+        self.set_region(Some(region));
+        self
+    }
+
+    /// Overrides the region the operation is sent to.
+    pub fn set_region(&mut self, region: Option<Region>) -> &mut Self {
+        self.region = region;
+        self
+    }
+
+    /// Overrides the credentials provider used to sign the operation.
+    pub fn credentials_provider(mut self, provider: impl ProvideCredentials + 'static) -> Self {
+        self.set_credentials_provider(Some(SharedCredentialsProvider::new(provider)));
+        self
+    }
+
+    /// Overrides the credentials provider used to sign the operation.
+    pub fn set_credentials_provider(
+        &mut self,
+        provider: Option<SharedCredentialsProvider>,
+    ) -> &mut Self {
+        self.credentials_provider = provider;
+        self
+    }
+
+    /// Overrides the endpoint resolver used for the operation.
+    pub fn endpoint_resolver(mut self, resolver: impl ResolveAwsEndpoint + 'static) -> Self {
+        self.set_endpoint_resolver(Some(Arc::new(resolver)));
+        self
+    }
+
+    /// Overrides the endpoint resolver used for the operation.
+    pub fn set_endpoint_resolver(
+        &mut self,
+        resolver: Option<Arc<dyn ResolveAwsEndpoint>>,
+    ) -> &mut Self {
+        self.endpoint_resolver = resolver;
+        self
+    }
+
+    /// Overrides the retry configuration for the operation.
+    ///
+    /// Only the maximum number of attempts can be overridden per operation.
+    pub fn retry_config(mut self, retry_config: RetryConfig) -> Self {
+        self.set_retry_config(Some(retry_config));
+        self
+    }
+
+    /// Overrides the retry configuration for the operation.
+    pub fn set_retry_config(&mut self, retry_config: Option<RetryConfig>) -> &mut Self {
+        self.retry_config = retry_config;
+        self
+    }
+
+    /// Applies the overrides to an operation's property bag, replacing the values set from
+    /// the service configuration.
+    pub fn apply(&self, properties: &mut PropertyBag) {
+        if let Some(region) = &self.region {
+            properties.insert(region.clone());
+        }
+        if let Some(provider) = &self.credentials_provider {
+            crate::auth::set_provider(properties, provider.clone());
+        }
+        if let Some(resolver) = &self.endpoint_resolver {
+            properties.insert(resolver.clone());
+        }
+        if let Some(retry_config) = &self.retry_config {
+            properties.insert(retry_config.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config_override::ConfigOverride;
+    use aws_smithy_http::property_bag::PropertyBag;
+    use aws_smithy_types::retry::RetryConfig;
+    use aws_types::credentials::SharedCredentialsProvider;
+    use aws_types::region::Region;
+    use aws_types::Credentials;
+
+    #[test]
+    fn overrides_replace_existing_properties() {
+        let mut properties = PropertyBag::new();
+        properties.insert(Region::new("us-east-1"));
+
+        ConfigOverride::new()
+            .region(Region::new("eu-west-1"))
+            .credentials_provider(Credentials::new("akid", "secret", None, None, "test"))
+            .retry_config(RetryConfig::new().with_max_attempts(1))
+            .apply(&mut properties);
+
+        assert_eq!(properties.get::<Region>(), Some(&Region::new("eu-west-1")));
+        assert!(properties.get::<SharedCredentialsProvider>().is_some());
+        assert_eq!(
+            properties.get::<RetryConfig>().map(|c| c.max_attempts()),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn empty_override_changes_nothing() {
+        let mut properties = PropertyBag::new();
+        properties.insert(Region::new("us-east-1"));
+        ConfigOverride::new().apply(&mut properties);
+        assert_eq!(properties.get::<Region>(), Some(&Region::new("us-east-1")));
+        assert!(properties.get::<SharedCredentialsProvider>().is_none());
+    }
+}
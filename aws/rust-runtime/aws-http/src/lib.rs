@@ -16,9 +16,15 @@
 /// Credentials middleware
 pub mod auth;
 
+/// Per-operation overrides of client configuration
+pub mod config_override;
+
 /// Recursion Detection middleware
 pub mod recursion_detection;
 
+/// Attempt information middleware
+pub mod request_info;
+
 /// AWS-specific retry logic
 pub mod retry;
 
@@ -39,6 +39,10 @@ impl MapRequest for RecursionDetectionStage {
             Ok(req)
         })
     }
+
+    fn name(&self) -> &'static str {
+        "recursion_detection"
+    }
 }
 
 const TRACE_ID_HEADER: &str = "x-amzn-trace-id";
@@ -0,0 +1,169 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use aws_smithy_http::middleware::MapRequest;
+use aws_smithy_http::operation::{InvocationId, Request};
+use aws_smithy_http::retry::{AttemptLimits, RequestAttempts};
+use aws_smithy_types::date_time::{DateTime, Format};
+use http::HeaderValue;
+use std::time::SystemTime;
+
+/// Request Info Middleware
+///
+/// This middleware sets the `amz-sdk-invocation-id` header to the [`InvocationId`] of the
+/// operation, which is the same for every attempt. It also sets the `amz-sdk-request` header to
+/// the attempt number of the request and the maximum number of attempts, e.g. `attempt=2; max=3`.
+/// If the operation has a deadline, it is included as `ttl`, which lets services skip work for
+/// requests the client has given up on.
+///
+/// All of this information is read from the property bag. Headers for information that isn't in
+/// the property bag are not set.
+#[non_exhaustive]
+#[derive(Default, Debug, Clone)]
+pub struct RequestInfoStage;
+
+impl RequestInfoStage {
+    /// Creates a new `RequestInfoStage`
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl MapRequest for RequestInfoStage {
+    type Error = std::convert::Infallible;
+
+    fn apply(&self, request: Request) -> Result<Request, Self::Error> {
+        request.augment(|mut req, conf| {
+            if let Some(invocation_id) = conf.get::<InvocationId>() {
+                if let Ok(value) = HeaderValue::from_str(invocation_id.as_str()) {
+                    req.headers_mut().insert(AMZ_SDK_INVOCATION_ID, value);
+                }
+            }
+            if let Some(attempts) = conf.get::<RequestAttempts>() {
+                let value = header_value(*attempts, conf.get::<AttemptLimits>());
+                req.headers_mut().insert(AMZ_SDK_REQUEST, value);
+            }
+            Ok(req)
+        })
+    }
+
+    fn name(&self) -> &'static str {
+        "request_info"
+    }
+}
+
+const AMZ_SDK_INVOCATION_ID: &str = "amz-sdk-invocation-id";
+const AMZ_SDK_REQUEST: &str = "amz-sdk-request";
+
+fn header_value(attempts: RequestAttempts, limits: Option<&AttemptLimits>) -> HeaderValue {
+    let mut value = format!("attempt={}", attempts.attempts());
+    if let Some(max_attempts) = limits.and_then(|limits| limits.max_attempts()) {
+        value.push_str(&format!("; max={}", max_attempts));
+    }
+    if let Some(ttl) = limits
+        .and_then(|limits| limits.deadline())
+        .and_then(format_ttl)
+    {
+        value.push_str(&format!("; ttl={}", ttl));
+    }
+    HeaderValue::try_from(value).expect("header value is ASCII")
+}
+
+/// Formats `deadline` as a basic ISO-8601 timestamp with second precision, e.g. `20220101T000000Z`
+fn format_ttl(deadline: SystemTime) -> Option<String> {
+    let seconds = DateTime::from_secs(DateTime::from(deadline).secs());
+    let formatted = seconds.fmt(Format::DateTime).ok()?;
+    Some(formatted.replace(['-', ':'], ""))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::request_info::RequestInfoStage;
+    use aws_smithy_http::body::SdkBody;
+    use aws_smithy_http::middleware::MapRequest;
+    use aws_smithy_http::operation;
+    use aws_smithy_http::operation::InvocationId;
+    use aws_smithy_http::retry::{AttemptLimits, RequestAttempts};
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn header(req: &operation::Request) -> Option<&str> {
+        req.http()
+            .headers()
+            .get("amz-sdk-request")
+            .map(|value| value.to_str().unwrap())
+    }
+
+    fn request() -> operation::Request {
+        operation::Request::new(http::Request::new(SdkBody::empty()))
+    }
+
+    #[test]
+    fn no_attempts_no_header() {
+        let req = RequestInfoStage::new().apply(request()).unwrap();
+        assert_eq!(header(&req), None);
+    }
+
+    #[test]
+    fn attempt_and_max() {
+        let mut req = request();
+        req.properties_mut().insert(RequestAttempts::new(2));
+        req.properties_mut()
+            .insert(AttemptLimits::new().with_max_attempts(3));
+        let req = RequestInfoStage::new().apply(req).unwrap();
+        assert_eq!(header(&req), Some("attempt=2; max=3"));
+    }
+
+    #[test]
+    fn invocation_id() {
+        let mut req = request();
+        req.properties_mut()
+            .insert(InvocationId::new("6b1d9c8e-5a5c-4c3e-9f0e-0d4a6f5b8a21"));
+        let req = RequestInfoStage::new().apply(req).unwrap();
+        assert_eq!(
+            req.http().headers().get("amz-sdk-invocation-id").unwrap(),
+            "6b1d9c8e-5a5c-4c3e-9f0e-0d4a6f5b8a21"
+        );
+        assert_eq!(header(&req), None);
+    }
+
+    #[test]
+    fn attempt_without_limits() {
+        let mut req = request();
+        req.properties_mut().insert(RequestAttempts::new(1));
+        let req = RequestInfoStage::new().apply(req).unwrap();
+        assert_eq!(header(&req), Some("attempt=1"));
+    }
+
+    #[test]
+    fn ttl_from_deadline() {
+        let mut req = request();
+        req.properties_mut().insert(RequestAttempts::new(1));
+        req.properties_mut().insert(
+            AttemptLimits::new()
+                .with_max_attempts(3)
+                .with_deadline(UNIX_EPOCH + Duration::from_millis(1_640_995_200_500)),
+        );
+        let req = RequestInfoStage::new().apply(req).unwrap();
+        assert_eq!(header(&req), Some("attempt=1; max=3; ttl=20220101T000000Z"));
+    }
+
+    #[test]
+    fn header_is_replaced_on_retry() {
+        let mut req = request();
+        req.properties_mut().insert(RequestAttempts::new(1));
+        let mut req = RequestInfoStage::new().apply(req).unwrap();
+        req.properties_mut().insert(RequestAttempts::new(2));
+        let req = RequestInfoStage::new().apply(req).unwrap();
+        assert_eq!(
+            req.http()
+                .headers()
+                .get_all("amz-sdk-request")
+                .iter()
+                .count(),
+            1
+        );
+        assert_eq!(header(&req), Some("attempt=2"));
+    }
+}
@@ -4,18 +4,25 @@
  */
 //! AWS-specific retry logic
 
-use aws_smithy_http::result::SdkError;
-use aws_smithy_http::retry::ClassifyResponse;
+use aws_smithy_http::response::IncompleteBody;
+use aws_smithy_http::result::{ConnectorErrorKind, SdkError};
+use aws_smithy_http::retry::{ClassifyResponse, Idempotent, RetryClassification};
 use aws_smithy_types::retry::{ErrorKind, ProvideErrorKind, RetryKind};
 use std::time::Duration;
 
 /// A retry policy that models AWS error codes as outlined in the SEP
 ///
 /// In order of priority:
-/// 1. The `x-amz-retry-after` header is checked
-/// 2. The modeled error retry mode is checked
-/// 3. The code is checked against a predetermined list of throttling errors & transient error codes
-/// 4. The status code is checked against a predetermined list of status codes
+/// 1. A [`RetryClassification`] chosen by the operation's
+///    [`ParsedResponseHook`](aws_smithy_http::response::ParsedResponseHook) is checked
+/// 2. The `x-amz-retry-after` header is checked
+/// 3. The modeled error retry mode is checked
+/// 4. The code is checked against a predetermined list of throttling errors, transient error codes,
+///    and clock skew error codes
+/// 5. The status code is checked against a predetermined list of status codes
+///
+/// Responses whose body failed mid-read (e.g. because the connection was reset) are retried as
+/// transient errors, but only for operations marked [`Idempotent`].
 #[non_exhaustive]
 #[derive(Clone, Debug)]
 pub struct AwsErrorRetryPolicy;
@@ -38,6 +45,12 @@ const THROTTLING_ERRORS: &[&str] = &[
     "EC2ThrottledException",
 ];
 const TRANSIENT_ERRORS: &[&str] = &["RequestTimeout", "RequestTimeoutException"];
+// These are retried because the middleware corrects for clock skew after receiving them
+const CLOCK_SKEW_ERRORS: &[&str] = &[
+    "RequestTimeTooSkewed",
+    "RequestExpired",
+    "RequestInTheFuture",
+];
 
 impl AwsErrorRetryPolicy {
     /// Create an `AwsErrorRetryPolicy` with the default set of known error & status codes
@@ -61,16 +74,28 @@ where
             Ok(_) => return RetryKind::Unnecessary,
             Err(SdkError::ServiceError { err, raw }) => (err, raw),
             Err(SdkError::DispatchFailure(err)) => {
-                return if err.is_timeout() || err.is_io() {
+                return match err.kind() {
+                    ConnectorErrorKind::Timeout | ConnectorErrorKind::Io => {
+                        RetryKind::Error(ErrorKind::TransientError)
+                    }
+                    ConnectorErrorKind::Other(Some(kind)) => RetryKind::Error(kind),
+                    _ => RetryKind::UnretryableFailure,
+                }
+            }
+            Err(SdkError::ResponseError { err, raw }) => {
+                return if err.downcast_ref::<IncompleteBody>().is_some()
+                    && raw.properties().get::<Idempotent>().is_some()
+                {
                     RetryKind::Error(ErrorKind::TransientError)
-                } else if let Some(ek) = err.is_other() {
-                    RetryKind::Error(ek)
                 } else {
                     RetryKind::UnretryableFailure
                 }
             }
             Err(_) => return RetryKind::UnretryableFailure,
         };
+        if let Some(classification) = response.properties().get::<RetryClassification>() {
+            return classification.kind().clone();
+        }
         if let Some(retry_after_delay) = response
             .http()
             .headers()
@@ -87,7 +112,7 @@ where
             if THROTTLING_ERRORS.contains(&code) {
                 return RetryKind::Error(ErrorKind::ThrottlingError);
             }
-            if TRANSIENT_ERRORS.contains(&code) {
+            if TRANSIENT_ERRORS.contains(&code) || CLOCK_SKEW_ERRORS.contains(&code) {
                 return RetryKind::Error(ErrorKind::TransientError);
             }
         };
@@ -104,8 +129,9 @@ mod test {
     use crate::retry::AwsErrorRetryPolicy;
     use aws_smithy_http::body::SdkBody;
     use aws_smithy_http::operation;
-    use aws_smithy_http::result::{SdkError, SdkSuccess};
-    use aws_smithy_http::retry::ClassifyResponse;
+    use aws_smithy_http::response::IncompleteBody;
+    use aws_smithy_http::result::{ConnectorError, SdkError, SdkSuccess};
+    use aws_smithy_http::retry::{ClassifyResponse, Idempotent, RetryClassification};
     use aws_smithy_types::retry::{ErrorKind, ProvideErrorKind, RetryKind};
     use std::time::Duration;
 
@@ -203,6 +229,20 @@ mod test {
                 .as_ref()
             ),
             RetryKind::Error(ErrorKind::TransientError)
+        );
+
+        let test_response = http::Response::new("OK");
+        assert_eq!(
+            policy.classify(
+                make_err(
+                    CodedError {
+                        code: "RequestTimeTooSkewed"
+                    },
+                    test_response
+                )
+                .as_ref()
+            ),
+            RetryKind::Error(ErrorKind::TransientError)
         )
     }
 
@@ -253,4 +293,106 @@ mod test {
             RetryKind::Explicit(Duration::from_millis(5000))
         );
     }
+
+    /// Classifies `err` as the error of an operation without output
+    fn classify_err<E: ProvideErrorKind>(err: SdkError<E>) -> RetryKind {
+        AwsErrorRetryPolicy::new().classify(Err::<&SdkSuccess<()>, _>(&err))
+    }
+
+    #[test]
+    fn classify_with_retry_classification() {
+        let classified = |code: &'static str, classification: Option<RetryKind>| {
+            let mut raw = operation::Response::new(
+                http::Response::builder()
+                    .status(200)
+                    .body(SdkBody::from("error!"))
+                    .unwrap(),
+            );
+            if let Some(kind) = classification {
+                raw.properties_mut().insert(RetryClassification::new(kind));
+            }
+            SdkError::ServiceError {
+                err: CodedError { code },
+                raw,
+            }
+        };
+
+        assert_eq!(
+            classify_err(classified(
+                "InternalError",
+                Some(RetryKind::Error(ErrorKind::TransientError))
+            )),
+            RetryKind::Error(ErrorKind::TransientError)
+        );
+        // without a classification, the default behavior applies
+        assert_eq!(
+            classify_err(classified("InternalError", None)),
+            RetryKind::UnretryableFailure
+        );
+        assert_eq!(
+            classify_err(classified("SlowDown", None)),
+            RetryKind::Error(ErrorKind::ThrottlingError)
+        );
+    }
+
+    #[test]
+    fn incomplete_bodies_are_retried_when_idempotent() {
+        let incomplete_body = |idempotent: bool| {
+            let mut raw = operation::Response::new(http::Response::new(SdkBody::taken()));
+            if idempotent {
+                raw.properties_mut().insert(Idempotent);
+            }
+            SdkError::<UnmodeledError>::ResponseError {
+                err: Box::new(IncompleteBody::new("connection reset".into())),
+                raw,
+            }
+        };
+
+        assert_eq!(
+            classify_err(incomplete_body(true)),
+            RetryKind::Error(ErrorKind::TransientError)
+        );
+        assert_eq!(
+            classify_err(incomplete_body(false)),
+            RetryKind::UnretryableFailure
+        );
+
+        let mut raw = operation::Response::new(http::Response::new(SdkBody::taken()));
+        raw.properties_mut().insert(Idempotent);
+        let other_error = SdkError::<UnmodeledError>::ResponseError {
+            err: "failed to decode".into(),
+            raw,
+        };
+        assert_eq!(classify_err(other_error), RetryKind::UnretryableFailure);
+    }
+
+    #[test]
+    fn classify_dispatch_failures_by_kind() {
+        let dispatch_failure =
+            |err: ConnectorError| classify_err(SdkError::<UnmodeledError>::DispatchFailure(err));
+
+        assert_eq!(
+            dispatch_failure(ConnectorError::io("reset".into())),
+            RetryKind::Error(ErrorKind::TransientError)
+        );
+        assert_eq!(
+            dispatch_failure(ConnectorError::timeout("timeout".into())),
+            RetryKind::Error(ErrorKind::TransientError)
+        );
+        assert_eq!(
+            dispatch_failure(ConnectorError::user("bad request".into())),
+            RetryKind::UnretryableFailure
+        );
+        assert_eq!(
+            dispatch_failure(ConnectorError::other(
+                "throttled".into(),
+                Some(ErrorKind::ThrottlingError)
+            )),
+            RetryKind::Error(ErrorKind::ThrottlingError)
+        );
+        assert_eq!(
+            dispatch_failure(ConnectorError::other("unknown".into(), None)),
+            RetryKind::UnretryableFailure
+        );
+    }
 }
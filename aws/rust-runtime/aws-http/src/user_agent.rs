@@ -4,10 +4,12 @@
  */
 
 use aws_smithy_http::middleware::MapRequest;
-use aws_smithy_http::operation::Request;
+use aws_smithy_http::operation::{Metadata, Request};
+use aws_smithy_http::pipeline::{PipelineConfig, Stage};
 use aws_types::app_name::AppName;
 use aws_types::build_metadata::{OsFamily, BUILD_METADATA};
 use aws_types::os_shim_internal::Env;
+use aws_types::Credentials;
 use http::header::{HeaderName, InvalidHeaderValue, USER_AGENT};
 use http::HeaderValue;
 use std::borrow::Cow;
@@ -30,6 +32,7 @@ pub struct AwsUserAgent {
     feature_metadata: Vec<FeatureMetadata>,
     config_metadata: Vec<ConfigMetadata>,
     framework_metadata: Vec<FrameworkMetadata>,
+    business_metrics: BusinessMetrics,
     app_name: Option<AppName>,
 }
 
@@ -66,6 +69,7 @@ impl AwsUserAgent {
             feature_metadata: Default::default(),
             config_metadata: Default::default(),
             framework_metadata: Default::default(),
+            business_metrics: Default::default(),
             app_name: Default::default(),
         }
     }
@@ -96,6 +100,7 @@ impl AwsUserAgent {
             feature_metadata: Vec::new(),
             config_metadata: Vec::new(),
             framework_metadata: Vec::new(),
+            business_metrics: Default::default(),
             app_name: None,
         }
     }
@@ -142,6 +147,20 @@ impl AwsUserAgent {
         self
     }
 
+    #[doc(hidden)]
+    /// Records that a feature tracked by business metrics was used.
+    pub fn with_business_metric(mut self, metric: BusinessMetric) -> Self {
+        self.business_metrics.push(metric);
+        self
+    }
+
+    #[doc(hidden)]
+    /// Records that a feature tracked by business metrics was used.
+    pub fn add_business_metric(&mut self, metric: BusinessMetric) -> &mut Self {
+        self.business_metrics.push(metric);
+        self
+    }
+
     /// Sets the app name for the user agent.
     pub fn with_app_name(mut self, app_name: AppName) -> Self {
         self.app_name = Some(app_name);
@@ -168,6 +187,7 @@ impl AwsUserAgent {
                     *(feat-metadata RWS)
                     *(config-metadata RWS)
                     *(framework-metadata RWS)
+                    [ua-metrics RWS]
                     [appId]
         */
         let mut ua_value = String::new();
@@ -189,6 +209,9 @@ impl AwsUserAgent {
         for framework in &self.framework_metadata {
             write!(ua_value, "{} ", framework).unwrap();
         }
+        if !self.business_metrics.is_empty() {
+            write!(ua_value, "{} ", &self.business_metrics).unwrap();
+        }
         if let Some(app_name) = &self.app_name {
             write!(ua_value, "app/{}", app_name).unwrap();
         }
@@ -466,6 +489,52 @@ impl fmt::Display for FrameworkMetadata {
     }
 }
 
+#[doc(hidden)]
+/// A feature whose use is tracked by the `m/` section of the user agent.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BusinessMetric {
+    /// The credentials used for the request carried an account ID
+    ResolvedAccountId,
+}
+
+impl BusinessMetric {
+    fn id(&self) -> &'static str {
+        match self {
+            BusinessMetric::ResolvedAccountId => "T",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+struct BusinessMetrics(Vec<BusinessMetric>);
+
+impl BusinessMetrics {
+    fn push(&mut self, metric: BusinessMetric) {
+        if !self.0.contains(&metric) {
+            self.0.push(metric);
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl fmt::Display for BusinessMetrics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // ua-metrics = "m/" metric *("," metric)
+        write!(f, "m/")?;
+        for (idx, metric) in self.0.iter().enumerate() {
+            if idx > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "{}", metric.id())?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Clone, Debug)]
 struct OsMetadata {
     os_family: &'static OsFamily,
@@ -562,9 +631,28 @@ impl MapRequest for UserAgentStage {
 
     fn apply(&self, request: Request) -> Result<Request, Self::Error> {
         request.augment(|mut req, conf| {
-            let ua = conf
+            if PipelineConfig::skips(conf, Stage::UserAgent) {
+                return Ok(req);
+            }
+            let mut ua = conf
                 .get::<AwsUserAgent>()
-                .ok_or(UserAgentStageError::UserAgentMissing)?;
+                .ok_or(UserAgentStageError::UserAgentMissing)?
+                .clone();
+            if conf
+                .get::<Credentials>()
+                .and_then(|creds| creds.account_id())
+                .is_some()
+            {
+                ua.add_business_metric(BusinessMetric::ResolvedAccountId);
+            }
+            if let Some(metadata) = conf.get::<Metadata>() {
+                tracing::debug!(
+                    service = metadata.service(),
+                    operation = metadata.name(),
+                    user_agent = %ua.aws_ua_header(),
+                    "setting user agent"
+                );
+            }
             req.headers_mut()
                 .append(USER_AGENT, HeaderValue::try_from(ua.ua_header())?);
             req.headers_mut().append(
@@ -575,6 +663,10 @@ impl MapRequest for UserAgentStage {
             Ok(req)
         })
     }
+
+    fn name(&self) -> &'static str {
+        "user_agent"
+    }
 }
 
 #[cfg(test)]
@@ -587,9 +679,11 @@ mod test {
     use aws_smithy_http::body::SdkBody;
     use aws_smithy_http::middleware::MapRequest;
     use aws_smithy_http::operation;
+    use aws_smithy_http::pipeline::{PipelineConfig, Stage};
     use aws_types::app_name::AppName;
     use aws_types::build_metadata::OsFamily;
     use aws_types::os_shim_internal::Env;
+    use aws_types::Credentials;
     use http::header::USER_AGENT;
     use std::borrow::Cow;
 
@@ -756,6 +850,74 @@ mod test {
             .get(&*X_AMZ_USER_AGENT)
             .expect("UA header should be set");
     }
+
+    #[test]
+    fn ua_stage_records_resolved_account_id() {
+        let mut req = operation::Request::new(http::Request::new(SdkBody::from("some body")));
+        req.properties_mut().insert(AwsUserAgent::for_tests());
+        req.properties_mut().insert(
+            Credentials::new("akid", "secret", None, None, "test").with_account_id("123456789012"),
+        );
+        let req = UserAgentStage::new()
+            .apply(req)
+            .expect("setting user agent should succeed");
+        assert_eq!(
+            "aws-sdk-rust/0.123.test api/test-service/0.123 os/windows/XPSP3 lang/rust/1.50.0 m/T",
+            req.http().headers().get(&*X_AMZ_USER_AGENT).unwrap()
+        );
+    }
+
+    #[test]
+    fn ua_stage_logs_the_operation() {
+        use std::sync::{Arc, Mutex};
+
+        #[derive(Clone, Default)]
+        struct Logs(Arc<Mutex<Vec<u8>>>);
+
+        impl std::io::Write for Logs {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.0.lock().unwrap().extend_from_slice(buf);
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let logs = Logs::default();
+        let writer = logs.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::DEBUG)
+            .with_ansi(false)
+            .with_writer(move || writer.clone())
+            .finish();
+        let mut req = operation::Request::new(http::Request::new(SdkBody::from("some body")));
+        req.properties_mut().insert(AwsUserAgent::for_tests());
+        req.properties_mut()
+            .insert(operation::Metadata::new("ListTables", "dynamodb"));
+        tracing::subscriber::with_default(subscriber, || {
+            UserAgentStage::new()
+                .apply(req)
+                .expect("setting user agent should succeed")
+        });
+        let logs = String::from_utf8(logs.0.lock().unwrap().clone()).unwrap();
+        assert!(logs.contains("service=\"dynamodb\""), "{}", logs);
+        assert!(logs.contains("operation=\"ListTables\""), "{}", logs);
+    }
+
+    #[test]
+    fn skipped_ua_stage_adds_no_headers() {
+        let mut req = operation::Request::new(http::Request::new(SdkBody::from("some body")));
+        PipelineConfig::new()
+            .skip(Stage::UserAgent)
+            .apply(&mut req.properties_mut());
+        let req = UserAgentStage::new()
+            .apply(req)
+            .expect("a missing UA is fine when the stage is skipped");
+        assert!(req.http().headers().get(USER_AGENT).is_none());
+        assert!(req.http().headers().get(&*X_AMZ_USER_AGENT).is_none());
+    }
 }
 
 /*
@@ -779,6 +941,8 @@ env-metadata         = "exec-env/" name
 feat-metadata        = "ft/" name ["/" version] *(RWS additional-metadata)
 config-metadata      = "cfg/" config ["/" value]
 framework-metadata   = "lib/" name ["/" version] *(RWS additional-metadata)
+metric               = token
+ua-metrics           = "m/" metric *("," metric)
 appId                = "app/" name
 ua-string            = sdk-metadata RWS
                        [api-metadata RWS]
@@ -788,6 +952,7 @@ ua-string            = sdk-metadata RWS
                        *(feat-metadata RWS)
                        *(config-metadata RWS)
                        *(framework-metadata RWS)
+                       [ua-metrics RWS]
                        [appId]
 
 # New metadata field might be added in the future and they must follow this format
@@ -10,23 +10,54 @@ pub use aws_smithy_client::retry::Config as RetryConfig;
 use aws_endpoint::AwsEndpointStage;
 use aws_http::auth::CredentialsStage;
 use aws_http::recursion_detection::RecursionDetectionStage;
+use aws_http::request_info::RequestInfoStage;
 use aws_http::user_agent::UserAgentStage;
+use aws_sig_auth::clock_skew::{ClockSkew, ClockSkewLayer};
 use aws_sig_auth::middleware::SigV4SigningStage;
 use aws_sig_auth::signer::SigV4Signer;
-use aws_smithy_http_tower::map_request::{AsyncMapRequestLayer, MapRequestLayer};
+use aws_smithy_async::rt::sleep::AsyncSleep;
+use aws_smithy_checksums::payload::PayloadChecksumStage;
+use aws_smithy_http::auth::HttpAuthStage;
+use aws_smithy_http::request_validation::RequestValidationStage;
+use aws_smithy_http_tower::map_request::{AsyncMapRequestLayer, MapRequestLayer, StageTimeout};
+use aws_smithy_types::timeout::Config as TimeoutConfig;
+use aws_smithy_types::tristate::TriState;
 use std::fmt::Debug;
+use std::sync::Arc;
+use std::time::Duration;
 use tower::layer::util::{Identity, Stack};
 use tower::ServiceBuilder;
 
+/// The time limit for loading credentials when the timeout config doesn't set an API call timeout
+///
+/// This is longer than the default load timeout of the credentials cache in `aws-config`, so that
+/// the provider's own timeout is reported when there is one.
+pub const DEFAULT_CREDENTIALS_TIMEOUT: Duration = Duration::from_secs(10);
+
 type DefaultMiddlewareStack = Stack<
-    MapRequestLayer<RecursionDetectionStage>,
+    MapRequestLayer<RequestValidationStage>,
     Stack<
-        MapRequestLayer<SigV4SigningStage>,
+        MapRequestLayer<RequestInfoStage>,
         Stack<
-            AsyncMapRequestLayer<CredentialsStage>,
+            MapRequestLayer<RecursionDetectionStage>,
             Stack<
-                MapRequestLayer<UserAgentStage>,
-                Stack<MapRequestLayer<AwsEndpointStage>, Identity>,
+                MapRequestLayer<HttpAuthStage>,
+                Stack<
+                    MapRequestLayer<SigV4SigningStage>,
+                    Stack<
+                        ClockSkewLayer,
+                        Stack<
+                            AsyncMapRequestLayer<PayloadChecksumStage>,
+                            Stack<
+                                MapRequestLayer<UserAgentStage>,
+                                Stack<
+                                    MapRequestLayer<AwsEndpointStage>,
+                                    Stack<AsyncMapRequestLayer<CredentialsStage>, Identity>,
+                                >,
+                            >,
+                        >,
+                    >,
+                >,
             >,
         >,
     >,
@@ -36,47 +67,106 @@ type DefaultMiddlewareStack = Stack<
 ///
 /// This implements the middleware stack for this service. It will:
 /// 1. Load credentials asynchronously into the property bag
-/// 2. Sign the request with SigV4
-/// 3. Resolve an Endpoint for the request
-/// 4. Add a user agent to the request
+/// 2. Resolve an Endpoint for the request, which may depend on the account ID of the credentials
+/// 3. Add a user agent to the request
+/// 4. Add the payload checksums (e.g. `Content-MD5`) requested by the operation
+/// 5. Sign the request with SigV4, correcting for the skew between the client's clock and the
+///    service's clock observed in previous clock skew errors, or authenticate it with the
+///    operation's HTTP auth scheme (e.g. a bearer token) if it doesn't use SigV4
+/// 6. Tell the service which invocation and attempt of the operation the request is
+/// 7. Reject requests with header values or query strings that can't be sent
 #[derive(Debug, Default, Clone)]
 #[non_exhaustive]
-pub struct DefaultMiddleware;
+pub struct DefaultMiddleware {
+    clock_skew: ClockSkew,
+    credentials_timeout: Option<StageTimeout>,
+}
 
 impl DefaultMiddleware {
     /// Create a new `DefaultMiddleware` stack
     ///
-    /// Note: `DefaultMiddleware` holds no state.
+    /// Note: `DefaultMiddleware` holds the clock skew, which is shared by clones, and the optional
+    /// credentials timeout.
     pub fn new() -> Self {
         DefaultMiddleware::default()
     }
+
+    /// Create a `DefaultMiddleware` stack whose credentials loading is limited by `timeout_config`
+    ///
+    /// The time limit is the API call timeout of `timeout_config` when it's set, and
+    /// [`DEFAULT_CREDENTIALS_TIMEOUT`] when it's unset. There is no time limit if the API call
+    /// timeout was explicitly disabled, or without a sleep implementation to enforce it.
+    pub fn from_timeout_config(
+        timeout_config: &TimeoutConfig,
+        sleep_impl: Option<Arc<dyn AsyncSleep>>,
+    ) -> Self {
+        let duration = match timeout_config.api_timeouts().call_timeout() {
+            TriState::Set(duration) => Some(duration),
+            TriState::Unset => Some(DEFAULT_CREDENTIALS_TIMEOUT),
+            TriState::Disabled => None,
+        };
+        match (duration, sleep_impl) {
+            (Some(duration), Some(sleep_impl)) => {
+                Self::new().with_credentials_timeout(StageTimeout::new(duration, sleep_impl))
+            }
+            _ => Self::new(),
+        }
+    }
+
+    /// Fails requests whose credentials aren't loaded within `timeout`
+    ///
+    /// With [`DefaultMiddleware::new`], loading credentials has no time limit, so a credentials
+    /// provider that never responds (e.g. a hung IMDS call) will hang the request.
+    pub fn with_credentials_timeout(mut self, timeout: StageTimeout) -> Self {
+        self.credentials_timeout = Some(timeout);
+        self
+    }
 }
 
 // define the middleware stack in a non-generic location to reduce code bloat.
-fn base() -> ServiceBuilder<DefaultMiddlewareStack> {
-    let credential_provider = AsyncMapRequestLayer::for_mapper(CredentialsStage::new());
+fn base(
+    clock_skew: ClockSkew,
+    credentials_timeout: Option<StageTimeout>,
+) -> ServiceBuilder<DefaultMiddlewareStack> {
+    let mut credential_provider = AsyncMapRequestLayer::for_mapper(CredentialsStage::new());
+    if let Some(timeout) = credentials_timeout {
+        credential_provider = credential_provider.with_timeout(timeout);
+    }
+    let clock_skew = ClockSkewLayer::new(clock_skew);
     let signer = MapRequestLayer::for_mapper(SigV4SigningStage::new(SigV4Signer::new()));
+    let http_auth = MapRequestLayer::for_mapper(HttpAuthStage::new());
     let endpoint_resolver = MapRequestLayer::for_mapper(AwsEndpointStage);
     let user_agent = MapRequestLayer::for_mapper(UserAgentStage::new());
+    let payload_checksums = AsyncMapRequestLayer::for_mapper(PayloadChecksumStage::new());
     let recursion_detection = MapRequestLayer::for_mapper(RecursionDetectionStage::new());
+    let request_info = MapRequestLayer::for_mapper(RequestInfoStage::new());
+    let request_validation = MapRequestLayer::for_mapper(RequestValidationStage::new());
     // These layers can be considered as occurring in order, that is:
-    // 1. Resolve an endpoint
-    // 2. Add a user agent
-    // 3. Acquire credentials
-    // 4. Sign with credentials
-    // (5. Dispatch over the wire)
+    // 1. Acquire credentials
+    // 2. Resolve an endpoint, which may be specific to the account ID of the credentials
+    // 3. Add a user agent, which records whether the credentials had an account ID
+    // 4. Add payload checksums, so that they are signed
+    // 5. Sign with credentials, correcting for clock skew, or apply the operation's HTTP auth scheme
+    // 6. Add the trace id and attempt information, which are not signed
+    // 7. Validate the headers and query string that the previous stages produced
+    // (8. Dispatch over the wire)
     ServiceBuilder::new()
+        .layer(credential_provider)
         .layer(endpoint_resolver)
         .layer(user_agent)
-        .layer(credential_provider)
+        .layer(payload_checksums)
+        .layer(clock_skew)
         .layer(signer)
+        .layer(http_auth)
         .layer(recursion_detection)
+        .layer(request_info)
+        .layer(request_validation)
 }
 
 impl<S> tower::Layer<S> for DefaultMiddleware {
     type Service = <DefaultMiddlewareStack as tower::Layer<S>>::Service;
 
     fn layer(&self, inner: S) -> Self::Service {
-        base().service(inner)
+        base(self.clock_skew.clone(), self.credentials_timeout.clone()).service(inner)
     }
 }
@@ -3,10 +3,12 @@
  * SPDX-License-Identifier: Apache-2.0.
  */
 
+use aws_smithy_http::operation;
+use aws_smithy_http::request_id::{extended_request_id, EXTENDED_REQUEST_ID};
+use aws_smithy_http::retry::RetryClassification;
+use aws_smithy_types::retry::{ErrorKind, ProvideErrorKind, RetryKind};
 use http::{HeaderMap, HeaderValue};
 
-const EXTENDED_REQUEST_ID: &str = "s3_extended_request_id";
-
 /// S3-specific service error additions.
 pub trait ErrorExt {
     /// Returns the S3 Extended Request ID necessary when contacting AWS Support.
@@ -26,18 +28,41 @@ pub fn parse_extended_error(
     headers: &HeaderMap<HeaderValue>,
 ) -> aws_smithy_types::Error {
     let mut builder = error.into_builder();
-    let host_id = headers
-        .get("x-amz-id-2")
-        .and_then(|header_value| header_value.to_str().ok());
-    if let Some(host_id) = host_id {
+    if let Some(host_id) = extended_request_id(headers) {
         builder.custom(EXTENDED_REQUEST_ID, host_id);
     }
     builder.build()
 }
 
+/// Marks transient errors that S3 returned in the body of a `200 OK` response as retryable.
+///
+/// Operations like `CopyObject` can fail after S3 has already sent a `200 OK`. When that failure
+/// is an `InternalError`, the request can be retried, but the status code alone doesn't say so.
+/// Every other error is left to the default retry classification. This is the
+/// [`ParsedResponseHook`](aws_smithy_http::response::ParsedResponseHook) of S3 operations.
+pub fn classify_success_response_error<T, E: ProvideErrorKind>(
+    parsed: Result<T, E>,
+    response: &mut operation::Response,
+) -> Result<T, E> {
+    if let Err(err) = &parsed {
+        if response.http().status().as_u16() == 200 && err.code() == Some("InternalError") {
+            response
+                .properties_mut()
+                .insert(RetryClassification::new(RetryKind::Error(
+                    ErrorKind::TransientError,
+                )));
+        }
+    }
+    parsed
+}
+
 #[cfg(test)]
 mod test {
-    use crate::s3_errors::{parse_extended_error, ErrorExt};
+    use crate::s3_errors::{classify_success_response_error, parse_extended_error, ErrorExt};
+    use aws_smithy_http::body::SdkBody;
+    use aws_smithy_http::operation;
+    use aws_smithy_http::retry::RetryClassification;
+    use aws_smithy_types::retry::{ErrorKind, RetryKind};
 
     #[test]
     fn add_error_fields() {
@@ -74,4 +99,28 @@ mod test {
         let error = parse_extended_error(error, resp.headers());
         assert_eq!(error.extended_request_id(), None);
     }
+
+    #[test]
+    fn classify_success_response_errors() {
+        let classify = |status: u16, code: &str| {
+            let mut response = operation::Response::new(
+                http::Response::builder()
+                    .status(status)
+                    .body(SdkBody::from("<Error><Code>InternalError</Code></Error>"))
+                    .unwrap(),
+            );
+            let err = aws_smithy_types::Error::builder().code(code).build();
+            let parsed = classify_success_response_error::<(), _>(Err(err), &mut response);
+            assert_eq!(Some(code), parsed.unwrap_err().code());
+            response.properties().get::<RetryClassification>().cloned()
+        };
+        assert_eq!(
+            classify(200, "InternalError"),
+            Some(RetryClassification::new(RetryKind::Error(
+                ErrorKind::TransientError
+            )))
+        );
+        assert_eq!(classify(200, "AccessDenied"), None);
+        assert_eq!(classify(500, "InternalError"), None);
+    }
 }
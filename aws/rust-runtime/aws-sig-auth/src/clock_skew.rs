@@ -0,0 +1,404 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Clock skew detection and correction
+//!
+//! SigV4 signatures are only valid for a few minutes around the time they were created, so a
+//! client whose clock disagrees with the service's will have every request rejected.
+//! When a service rejects a request because of its timestamp (a `RequestTimeTooSkewed`,
+//! `RequestExpired` or `RequestInTheFuture` error), [`ClockSkewLayer`] compares the `Date` header
+//! of the error response to the client's clock and records the difference in a [`ClockSkew`]
+//! shared by all requests made with the client.
+//! [`SigV4SigningStage`](crate::middleware::SigV4SigningStage) corrects signing timestamps by
+//! that difference, so the retry of the rejected request is signed with the service's time.
+//!
+//! Other responses leave the skew unchanged: the `Date` header only has a precision of one
+//! second, and a response may have spent some time in flight, so a skew computed from every
+//! response would make the signing time jitter for clients whose clocks are correct.
+
+use aws_smithy_async::time::{SharedTimeSource, TimeSource};
+use aws_smithy_http::callback::BodyCallback;
+use aws_smithy_http::operation;
+use aws_smithy_types::date_time::{DateTime, Format};
+use pin_project::pin_project;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::{Duration, SystemTime};
+use tower::{Layer, Service};
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Error codes returned by AWS services when a request's signing time is too far from theirs
+const CLOCK_SKEW_ERRORS: &[&str] = &[
+    "RequestTimeTooSkewed",
+    "RequestExpired",
+    "RequestInTheFuture",
+];
+
+/// The number of bytes of an error response body searched for a clock skew error code
+///
+/// Error responses are small, and the error code comes before any details.
+const MAX_ERROR_BODY_SCAN: usize = 4096;
+
+/// The difference between the service's clock and the client's clock
+///
+/// Clones share the same underlying value, so a `ClockSkew` can be created once per client and
+/// updated by any of its requests.
+#[derive(Clone, Debug, Default)]
+pub struct ClockSkew {
+    /// Server time minus client time, in milliseconds
+    skew_millis: Arc<AtomicI64>,
+}
+
+impl ClockSkew {
+    /// Creates a new `ClockSkew` with no skew
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the skew between `server_time` and `client_time`, which should have been observed
+    /// at the same moment
+    pub fn update(&self, server_time: SystemTime, client_time: SystemTime) {
+        let skew_millis = match server_time.duration_since(client_time) {
+            Ok(ahead) => ahead.as_millis() as i64,
+            Err(behind) => -(behind.duration().as_millis() as i64),
+        };
+        self.skew_millis.store(skew_millis, Ordering::Relaxed);
+    }
+
+    /// Corrects `client_time` to the equivalent time on the service's clock
+    pub fn apply(&self, client_time: SystemTime) -> SystemTime {
+        let skew_millis = self.skew_millis.load(Ordering::Relaxed);
+        let skew = Duration::from_millis(skew_millis.unsigned_abs());
+        if skew_millis >= 0 {
+            client_time + skew
+        } else {
+            client_time - skew
+        }
+    }
+}
+
+/// Layer that records the clock skew observed in clock skew errors (see the [module docs](self))
+#[derive(Clone, Debug)]
+pub struct ClockSkewLayer {
+    clock_skew: ClockSkew,
+}
+
+impl ClockSkewLayer {
+    /// Creates a new `ClockSkewLayer` that records skew to `clock_skew`
+    pub fn new(clock_skew: ClockSkew) -> Self {
+        Self { clock_skew }
+    }
+}
+
+impl<S> Layer<S> for ClockSkewLayer {
+    type Service = ClockSkewService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ClockSkewService {
+            inner,
+            clock_skew: self.clock_skew.clone(),
+        }
+    }
+}
+
+/// Service that records the clock skew observed in clock skew errors
+///
+/// Each request has the [`ClockSkew`] inserted into its property bag so that signing can
+/// correct for it.
+#[derive(Clone, Debug)]
+pub struct ClockSkewService<S> {
+    inner: S,
+    clock_skew: ClockSkew,
+}
+
+impl<S> Service<operation::Request> for ClockSkewService<S>
+where
+    S: Service<operation::Request, Response = operation::Response>,
+{
+    type Response = operation::Response;
+    type Error = S::Error;
+    type Future = ClockSkewFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: operation::Request) -> Self::Future {
+        req.properties_mut().insert(self.clock_skew.clone());
+        ClockSkewFuture {
+            inner: self.inner.call(req),
+            clock_skew: self.clock_skew.clone(),
+        }
+    }
+}
+
+/// Future returned by [`ClockSkewService`]
+#[pin_project]
+#[derive(Debug)]
+pub struct ClockSkewFuture<F> {
+    #[pin]
+    inner: F,
+    clock_skew: ClockSkew,
+}
+
+impl<F, E> Future for ClockSkewFuture<F>
+where
+    F: Future<Output = Result<operation::Response, E>>,
+{
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let mut result = match this.inner.poll(cx) {
+            Poll::Ready(result) => result,
+            Poll::Pending => return Poll::Pending,
+        };
+        if let Ok(response) = &mut result {
+            record_skew_error(response, this.clock_skew);
+        }
+        Poll::Ready(result)
+    }
+}
+
+/// Updates `clock_skew` from `response` if it is a clock skew error
+///
+/// The error code is either in the `x-amzn-ErrorType` header, or in the body. As the body hasn't
+/// been read yet, the body is given a callback that checks it once it has been read, which
+/// happens before the request is retried.
+fn record_skew_error(response: &mut operation::Response, clock_skew: &ClockSkew) {
+    if !response.status().is_client_error() {
+        return;
+    }
+    let server_time = match server_time(response) {
+        Some(server_time) => server_time,
+        None => return,
+    };
+    let client_time = response
+        .properties()
+        .get::<SharedTimeSource>()
+        .map(|time| time.now())
+        .unwrap_or_else(SystemTime::now);
+    let error_type = response
+        .headers()
+        .get("x-amzn-errortype")
+        .and_then(|error_type| error_type.to_str().ok());
+    match error_type {
+        Some(error_type) if is_clock_skew_error(error_type.as_bytes()) => {
+            clock_skew.update(server_time, client_time)
+        }
+        Some(_) => {}
+        None => {
+            response
+                .http_mut()
+                .body_mut()
+                .with_callback(Box::new(SkewErrorCallback {
+                    clock_skew: clock_skew.clone(),
+                    server_time,
+                    client_time,
+                    body: Vec::new(),
+                }));
+        }
+    }
+}
+
+/// Returns true if `error` contains one of the [`CLOCK_SKEW_ERRORS`] codes
+///
+/// This matches the codes in any protocol's error format, e.g. `<Code>RequestExpired</Code>` or
+/// `"__type":"com.amazon.coral.service#RequestExpired"`.
+fn is_clock_skew_error(error: &[u8]) -> bool {
+    CLOCK_SKEW_ERRORS.iter().any(|code| {
+        error
+            .windows(code.len())
+            .any(|window| window == code.as_bytes())
+    })
+}
+
+/// Body callback that updates the clock skew once an error response body has been read, if it
+/// is a clock skew error
+struct SkewErrorCallback {
+    clock_skew: ClockSkew,
+    server_time: SystemTime,
+    client_time: SystemTime,
+    body: Vec<u8>,
+}
+
+impl BodyCallback for SkewErrorCallback {
+    fn update(&mut self, bytes: &[u8]) -> Result<(), BoxError> {
+        let remaining = MAX_ERROR_BODY_SCAN.saturating_sub(self.body.len());
+        self.body
+            .extend_from_slice(&bytes[..remaining.min(bytes.len())]);
+        Ok(())
+    }
+
+    fn trailers(&self) -> Result<Option<http::HeaderMap<http::HeaderValue>>, BoxError> {
+        if is_clock_skew_error(&self.body) {
+            self.clock_skew.update(self.server_time, self.client_time);
+        }
+        Ok(None)
+    }
+
+    fn make_new(&self) -> Box<dyn BodyCallback> {
+        Box::new(SkewErrorCallback {
+            clock_skew: self.clock_skew.clone(),
+            server_time: self.server_time,
+            client_time: self.client_time,
+            body: Vec::new(),
+        })
+    }
+}
+
+/// Returns the time from the response's `Date` header, if it has a valid one
+fn server_time(response: &operation::Response) -> Option<SystemTime> {
+    let date = response.headers().get(http::header::DATE)?.to_str().ok()?;
+    let date = DateTime::from_str(date, Format::HttpDate).ok()?;
+    SystemTime::try_from(date).ok()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::clock_skew::{ClockSkew, ClockSkewLayer};
+    use aws_smithy_async::test_util::StaticTimeSource;
+    use aws_smithy_async::time::SharedTimeSource;
+    use aws_smithy_http::body::SdkBody;
+    use aws_smithy_http::byte_stream::ByteStream;
+    use aws_smithy_http::operation;
+    use bytes::Bytes;
+    use std::convert::Infallible;
+    use std::sync::Mutex;
+    use std::time::{Duration, UNIX_EPOCH};
+    use tower::{Layer, ServiceExt};
+
+    #[test]
+    fn apply_skew() {
+        let skew = ClockSkew::new();
+        let client_time = UNIX_EPOCH + Duration::from_secs(1000);
+        assert_eq!(skew.apply(client_time), client_time);
+
+        skew.update(client_time + Duration::from_secs(600), client_time);
+        assert_eq!(
+            skew.apply(client_time),
+            client_time + Duration::from_secs(600)
+        );
+
+        skew.update(client_time - Duration::from_secs(600), client_time);
+        assert_eq!(
+            skew.apply(client_time),
+            client_time - Duration::from_secs(600)
+        );
+    }
+
+    #[test]
+    fn clones_share_skew() {
+        let skew = ClockSkew::new();
+        let client_time = UNIX_EPOCH + Duration::from_secs(1000);
+        skew.clone()
+            .update(client_time + Duration::from_secs(5), client_time);
+        assert_eq!(
+            skew.apply(client_time),
+            client_time + Duration::from_secs(5)
+        );
+    }
+
+    /// Sends a request through a `ClockSkewLayer` for `skew` to a service returning `response`,
+    /// reads the response body, and returns it. The client's clock is 10 minutes behind the
+    /// server's `Date`.
+    async fn send(skew: &ClockSkew, response: http::Response<SdkBody>) -> Bytes {
+        let response = Mutex::new(Some(response));
+        let svc =
+            ClockSkewLayer::new(skew.clone()).layer(tower::service_fn(
+                |req: operation::Request| {
+                    assert!(req.properties().get::<ClockSkew>().is_some());
+                    let (_, properties) = req.into_parts();
+                    let response = response.lock().unwrap().take().unwrap();
+                    async move {
+                        Ok::<_, Infallible>(operation::Response::from_parts(response, properties))
+                    }
+                },
+            ));
+        let mut req = operation::Request::new(http::Request::new(SdkBody::empty()));
+        req.properties_mut()
+            .insert(SharedTimeSource::new(StaticTimeSource::new(
+                UNIX_EPOCH + Duration::from_secs(1000),
+            )));
+        let (response, _) = svc.oneshot(req).await.unwrap().into_parts();
+        ByteStream::new(response.into_body())
+            .collect()
+            .await
+            .unwrap()
+            .into_bytes()
+    }
+
+    fn response(status: u16) -> http::response::Builder {
+        http::Response::builder()
+            .status(status)
+            .header("Date", "Thu, 01 Jan 1970 00:26:40 GMT")
+    }
+
+    fn assert_skew(skew: &ClockSkew, expected: Duration) {
+        let client_time = UNIX_EPOCH + Duration::from_secs(2000);
+        assert_eq!(skew.apply(client_time), client_time + expected);
+    }
+
+    #[tokio::test]
+    async fn skew_is_recorded_from_skew_error_body() {
+        let skew = ClockSkew::new();
+        let body = "<Error><Code>RequestTimeTooSkewed</Code>\
+            <Message>The difference between the request time and the current time is too large.</Message>\
+            </Error>";
+        let read = send(&skew, response(403).body(SdkBody::from(body)).unwrap()).await;
+        assert_eq!(body.as_bytes(), &read[..], "the body is unchanged");
+        assert_skew(&skew, Duration::from_secs(600));
+    }
+
+    #[tokio::test]
+    async fn skew_is_recorded_from_error_type_header() {
+        let skew = ClockSkew::new();
+        let response = response(400)
+            .header(
+                "x-amzn-ErrorType",
+                "RequestExpired:http://internal.amazon.com/coral/",
+            )
+            .body(SdkBody::from("{}"))
+            .unwrap();
+        send(&skew, response).await;
+        assert_skew(&skew, Duration::from_secs(600));
+    }
+
+    #[tokio::test]
+    async fn successful_responses_leave_skew_unchanged() {
+        let skew = ClockSkew::new();
+        send(&skew, response(200).body(SdkBody::from("ok")).unwrap()).await;
+        assert_skew(&skew, Duration::ZERO);
+    }
+
+    #[tokio::test]
+    async fn other_errors_leave_skew_unchanged() {
+        let skew = ClockSkew::new();
+        let body = "<Error><Code>AccessDenied</Code><Message>Access Denied</Message></Error>";
+        send(&skew, response(403).body(SdkBody::from(body)).unwrap()).await;
+        let response = response(400)
+            .header("x-amzn-ErrorType", "ValidationException")
+            .body(SdkBody::from("{}"))
+            .unwrap();
+        send(&skew, response).await;
+        assert_skew(&skew, Duration::ZERO);
+    }
+
+    #[tokio::test]
+    async fn missing_date_header_leaves_skew_unchanged() {
+        let skew = ClockSkew::new();
+        let body = "<Error><Code>RequestTimeTooSkewed</Code></Error>";
+        let response = http::Response::builder()
+            .status(403)
+            .body(SdkBody::from(body))
+            .unwrap();
+        send(&skew, response).await;
+        assert_skew(&skew, Duration::ZERO);
+    }
+}
@@ -3,7 +3,7 @@
  * SPDX-License-Identifier: Apache-2.0.
  */
 
-use crate::middleware::Signature;
+use crate::middleware::{request_time, Signature};
 use aws_sigv4::event_stream::{sign_empty_message, sign_message};
 use aws_sigv4::SigningParams;
 use aws_smithy_eventstream::frame::{Message, SignMessage, SignMessageError};
@@ -11,7 +11,6 @@ use aws_smithy_http::property_bag::{PropertyBag, SharedPropertyBag};
 use aws_types::region::SigningRegion;
 use aws_types::Credentials;
 use aws_types::SigningService;
-use std::time::SystemTime;
 
 /// Event Stream SigV4 signing implementation.
 #[derive(Debug)]
@@ -34,10 +33,7 @@ impl SigV4Signer {
         let credentials = properties.get::<Credentials>().unwrap();
         let region = properties.get::<SigningRegion>().unwrap();
         let signing_service = properties.get::<SigningService>().unwrap();
-        let time = properties
-            .get::<SystemTime>()
-            .copied()
-            .unwrap_or_else(SystemTime::now);
+        let time = request_time(properties);
         let mut builder = SigningParams::builder()
             .access_key(credentials.access_key_id())
             .secret_key(credentials.secret_access_key())
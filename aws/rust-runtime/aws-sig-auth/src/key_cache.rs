@@ -0,0 +1,221 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Cache of derived SigV4 signing keys
+//!
+//! Deriving a signing key takes four HMAC operations over the secret key, date, region, and
+//! service. The key only changes when one of those does, so it can be reused for every request
+//! signed with the same credentials on the same (UTC) day.
+
+use ring::digest::{digest, SHA256, SHA256_OUTPUT_LEN};
+use ring::hmac::Tag;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Number of keys kept by default: enough for a handful of credentials, regions and services
+pub(crate) const DEFAULT_CAPACITY: usize = 16;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct CacheKey {
+    access_key: String,
+    secret_digest: [u8; SHA256_OUTPUT_LEN],
+    day: u64,
+    region: String,
+    service: String,
+}
+
+impl CacheKey {
+    fn matches(
+        &self,
+        access_key: &str,
+        secret_digest: &[u8; SHA256_OUTPUT_LEN],
+        day: u64,
+        region: &str,
+        service: &str,
+    ) -> bool {
+        self.day == day
+            && self.access_key == access_key
+            && &self.secret_digest == secret_digest
+            && self.region == region
+            && self.service == service
+    }
+}
+
+/// Least-recently-used cache of signing keys
+///
+/// Keys are looked up by access key ID and a SHA-256 digest of the secret key, so that secrets
+/// are never held by the cache. The digest is needed because the same access key ID may be used
+/// with a different secret, e.g. after the secret was replaced or with S3-compatible services.
+#[derive(Debug)]
+pub(crate) struct SigningKeyCache {
+    capacity: usize,
+    // most recently used first
+    entries: Mutex<VecDeque<(CacheKey, Tag)>>,
+}
+
+impl Default for SigningKeyCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY)
+    }
+}
+
+impl SigningKeyCache {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Mutex::new(VecDeque::with_capacity(capacity)),
+        }
+    }
+
+    /// Returns the signing key for the given parameters, calling `derive` to create it on a miss
+    pub(crate) fn get_or_derive(
+        &self,
+        access_key: &str,
+        secret_key: &str,
+        time: SystemTime,
+        region: &str,
+        service: &str,
+        derive: impl FnOnce() -> Tag,
+    ) -> Tag {
+        if self.capacity == 0 {
+            return derive();
+        }
+        let day = match time.duration_since(UNIX_EPOCH) {
+            Ok(since_epoch) => since_epoch.as_secs() / SECONDS_PER_DAY,
+            // times before the epoch are only used in tests; don't bother caching them
+            Err(_) => return derive(),
+        };
+        let mut secret_digest = [0; SHA256_OUTPUT_LEN];
+        secret_digest.copy_from_slice(digest(&SHA256, secret_key.as_bytes()).as_ref());
+        let mut entries = self.entries.lock().unwrap();
+        let hit = entries
+            .iter()
+            .position(|(key, _)| key.matches(access_key, &secret_digest, day, region, service));
+        if let Some(index) = hit {
+            let entry = entries.remove(index).expect("index is in bounds");
+            let tag = entry.1;
+            entries.push_front(entry);
+            return tag;
+        }
+        let tag = derive();
+        if entries.len() == self.capacity {
+            entries.pop_back();
+        }
+        entries.push_front((
+            CacheKey {
+                access_key: access_key.to_string(),
+                secret_digest,
+                day,
+                region: region.to_string(),
+                service: service.to_string(),
+            },
+            tag,
+        ));
+        tag
+    }
+
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::key_cache::SigningKeyCache;
+    use aws_sigv4::sign::generate_signing_key;
+    use ring::hmac::Tag;
+    use std::cell::Cell;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    fn day(n: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(n * 86400 + 3600)
+    }
+
+    fn key(time: SystemTime, region: &str) -> Tag {
+        generate_signing_key("secret", time, region, "s3")
+    }
+
+    #[test]
+    fn keys_are_reused_until_the_date_changes() {
+        let cache = SigningKeyCache::default();
+        let derived = Cell::new(0);
+        let get = |time: SystemTime| {
+            cache.get_or_derive("AKID", "secret", time, "us-east-1", "s3", || {
+                derived.set(derived.get() + 1);
+                key(time, "us-east-1")
+            })
+        };
+
+        let first = get(day(1));
+        let same_day = get(day(1) + Duration::from_secs(600));
+        assert_eq!(first.as_ref(), same_day.as_ref());
+        assert_eq!(derived.get(), 1);
+
+        let next_day = get(day(2));
+        assert_eq!(next_day.as_ref(), key(day(2), "us-east-1").as_ref());
+        assert_ne!(first.as_ref(), next_day.as_ref());
+        assert_eq!(derived.get(), 2);
+    }
+
+    #[test]
+    fn keys_are_not_reused_when_the_secret_changes() {
+        let cache = SigningKeyCache::default();
+        let get = |secret: &'static str| {
+            cache.get_or_derive("AKID", secret, day(1), "us-east-1", "s3", || {
+                generate_signing_key(secret, day(1), "us-east-1", "s3")
+            })
+        };
+        let original = get("secret");
+        let rotated = get("rotated");
+        assert_eq!(
+            rotated.as_ref(),
+            generate_signing_key("rotated", day(1), "us-east-1", "s3").as_ref()
+        );
+        assert_ne!(original.as_ref(), rotated.as_ref());
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    fn least_recently_used_key_is_evicted() {
+        let cache = SigningKeyCache::new(2);
+        let derived = Cell::new(0);
+        let get = |region: &'static str| {
+            cache.get_or_derive("AKID", "secret", day(1), region, "s3", || {
+                derived.set(derived.get() + 1);
+                key(day(1), region)
+            });
+        };
+        get("us-east-1");
+        get("us-west-2");
+        get("us-east-1");
+        assert_eq!(derived.get(), 2);
+
+        // evicts us-west-2, which was used least recently
+        get("eu-west-1");
+        assert_eq!(cache.len(), 2);
+        get("us-east-1");
+        assert_eq!(derived.get(), 3);
+        get("us-west-2");
+        assert_eq!(derived.get(), 4);
+    }
+
+    #[test]
+    fn zero_capacity_disables_caching() {
+        let cache = SigningKeyCache::new(0);
+        let derived = Cell::new(0);
+        for _ in 0..2 {
+            cache.get_or_derive("AKID", "secret", day(1), "us-east-1", "s3", || {
+                derived.set(derived.get() + 1);
+                key(day(1), "us-east-1")
+            });
+        }
+        assert_eq!(derived.get(), 2);
+        assert_eq!(cache.len(), 0);
+    }
+}
@@ -109,5 +109,8 @@
 #[cfg(feature = "sign-eventstream")]
 pub mod event_stream;
 
+mod chunked;
+pub mod clock_skew;
+mod key_cache;
 pub mod middleware;
 pub mod signer;
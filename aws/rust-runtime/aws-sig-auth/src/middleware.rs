@@ -3,17 +3,25 @@
  * SPDX-License-Identifier: Apache-2.0.
  */
 
+use crate::clock_skew::ClockSkew;
 use crate::signer::{
     OperationSigningConfig, RequestConfig, SigV4Signer, SigningError, SigningRequirements,
 };
 use aws_sigv4::http_request::SignableBody;
+use aws_smithy_async::time::{SharedTimeSource, TimeSource};
+use aws_smithy_http::auth::{AuthSchemeId, AuthSchemeOptions, HttpAuthError, HttpAuthScheme};
+use aws_smithy_http::body::SdkBody;
+use aws_smithy_http::diagnostic::DiagnosticContext;
 use aws_smithy_http::middleware::MapRequest;
 use aws_smithy_http::operation::Request;
+use aws_smithy_http::pipeline::{PipelineConfig, Stage};
 use aws_smithy_http::property_bag::PropertyBag;
+use aws_smithy_http::timing::TimingInfo;
+use aws_types::credentials::Anonymous;
 use aws_types::region::SigningRegion;
 use aws_types::Credentials;
 use aws_types::SigningService;
-use std::time::SystemTime;
+use std::time::{Instant, SystemTime};
 use thiserror::Error;
 
 /// Container for the request signature for use in the property bag.
@@ -46,8 +54,17 @@ impl AsRef<str> for Signature {
 /// If any of these fields are missing, the middleware will return an error.
 ///
 /// The following fields MAY be present in the property bag:
-/// - [`SystemTime`](SystemTime): The timestamp to use when signing the request. If this field is not present
-///   [`SystemTime::now`](SystemTime::now) will be used.
+/// - [`SystemTime`](SystemTime): The timestamp to use when signing the request.
+/// - [`SharedTimeSource`](SharedTimeSource): The time source to read the signing timestamp from when
+///   no `SystemTime` is present.
+/// If neither field is present [`SystemTime::now`](SystemTime::now) will be used.
+/// - [`ClockSkew`](ClockSkew): The skew between the client's clock and the service's clock, which
+///   is used to correct the timestamp read from the time source.
+///
+/// Requests marked as [`Anonymous`](Anonymous) are never signed. Requests with
+/// [`AuthSchemeOptions`](AuthSchemeOptions) are left for the
+/// [`HttpAuthStage`](aws_smithy_http::auth::HttpAuthStage) to authenticate, which signs them with
+/// [`SigV4AuthScheme`] if that is the highest priority option with credentials available.
 #[derive(Clone, Debug)]
 pub struct SigV4SigningStage {
     signer: SigV4Signer,
@@ -75,6 +92,21 @@ pub enum SigningStageError {
     SigningFailure(#[from] SigningError),
 }
 
+/// Returns the time to sign the request with
+pub(crate) fn request_time(config: &PropertyBag) -> SystemTime {
+    if let Some(time) = config.get::<SystemTime>() {
+        return *time;
+    }
+    let now = config
+        .get::<SharedTimeSource>()
+        .map(|time| time.now())
+        .unwrap_or_else(SystemTime::now);
+    match config.get::<ClockSkew>() {
+        Some(skew) => skew.apply(now),
+        None => now,
+    }
+}
+
 /// Extract a signing config from a [`PropertyBag`](aws_smithy_http::property_bag::PropertyBag)
 fn signing_config(
     config: &PropertyBag,
@@ -94,10 +126,7 @@ fn signing_config(
         .ok_or(SigningStageError::MissingSigningService)?;
     let payload_override = config.get::<SignableBody<'static>>();
     let request_config = RequestConfig {
-        request_ts: config
-            .get::<SystemTime>()
-            .copied()
-            .unwrap_or_else(SystemTime::now),
+        request_ts: request_time(config),
         region,
         payload_override,
         service: signing_service,
@@ -105,11 +134,25 @@ fn signing_config(
     Ok((operation_config, request_config, credentials))
 }
 
+/// Returns the region and service that a request is signed for, for [`DiagnosticContext`]
+fn signed_for(request_config: &RequestConfig<'_>) -> (String, String) {
+    (
+        request_config.region.as_ref().to_string(),
+        request_config.service.as_ref().to_string(),
+    )
+}
+
 impl MapRequest for SigV4SigningStage {
     type Error = SigningStageError;
 
     fn apply(&self, req: Request) -> Result<Request, Self::Error> {
         req.augment(|mut req, config| {
+            if config.get::<Anonymous>().is_some()
+                || config.get::<AuthSchemeOptions>().is_some()
+                || PipelineConfig::skips(config, Stage::Signing)
+            {
+                return Ok(req);
+            }
             let operation_config = config
                 .get::<OperationSigningConfig>()
                 .ok_or(SigningStageError::MissingSigningConfig)?;
@@ -123,25 +166,97 @@ impl MapRequest for SigV4SigningStage {
                     SigningRequirements::Required => signing_config(config)?,
                 };
 
+            let start = Instant::now();
             let signature = self
                 .signer
                 .sign(operation_config, &request_config, &creds, &mut req)
                 .map_err(|err| SigningStageError::SigningFailure(err))?;
+            let (region, service) = signed_for(&request_config);
             config.insert(signature);
+            TimingInfo::record(config).signing = Some(start.elapsed());
+            let diagnostics = DiagnosticContext::record(config);
+            diagnostics.signing_region = Some(region);
+            diagnostics.signing_service = Some(service);
             Ok(req)
         })
     }
+
+    fn name(&self) -> &'static str {
+        "signing"
+    }
+}
+
+/// ID of the [`SigV4AuthScheme`]
+pub const SIGV4_AUTH_SCHEME_ID: AuthSchemeId = AuthSchemeId::new("sigv4");
+
+/// SigV4 as an [`HttpAuthScheme`], so that it can be one of an operation's [`AuthSchemeOptions`]
+///
+/// The scheme has an identity available when the request has [`Credentials`] and is not marked as
+/// [`Anonymous`]. Signing reads the same property bag fields as [`SigV4SigningStage`].
+#[derive(Clone, Debug, Default)]
+pub struct SigV4AuthScheme {
+    signer: SigV4Signer,
+}
+
+impl SigV4AuthScheme {
+    /// Creates a new SigV4 auth scheme that signs requests with `signer`
+    pub fn new(signer: SigV4Signer) -> Self {
+        Self { signer }
+    }
+}
+
+impl HttpAuthScheme for SigV4AuthScheme {
+    fn scheme_id(&self) -> AuthSchemeId {
+        SIGV4_AUTH_SCHEME_ID
+    }
+
+    fn has_identity(&self, properties: &PropertyBag) -> bool {
+        properties.get::<Anonymous>().is_none() && properties.get::<Credentials>().is_some()
+    }
+
+    fn apply(
+        &self,
+        request: &mut http::Request<SdkBody>,
+        properties: &mut PropertyBag,
+    ) -> Result<(), HttpAuthError> {
+        let failed =
+            |err: Box<dyn std::error::Error + Send + Sync>| HttpAuthError::AuthSchemeFailed {
+                scheme_id: SIGV4_AUTH_SCHEME_ID,
+                source: err,
+            };
+        let (operation_config, request_config, creds) =
+            signing_config(properties).map_err(|err| failed(err.into()))?;
+        let signature = self
+            .signer
+            .sign(operation_config, &request_config, &creds, request)
+            .map_err(failed)?;
+        let (region, service) = signed_for(&request_config);
+        properties.insert(signature);
+        let diagnostics = DiagnosticContext::record(properties);
+        diagnostics.signing_region = Some(region);
+        diagnostics.signing_service = Some(service);
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::middleware::{SigV4SigningStage, Signature, SigningStageError};
+    use crate::clock_skew::ClockSkew;
+    use crate::middleware::{
+        SigV4AuthScheme, SigV4SigningStage, Signature, SigningStageError, SIGV4_AUTH_SCHEME_ID,
+    };
     use crate::signer::{OperationSigningConfig, SigV4Signer};
     use aws_endpoint::partition::endpoint::{Protocol, SignatureVersion};
     use aws_endpoint::{set_endpoint_resolver, AwsEndpointStage};
+    use aws_smithy_async::test_util::StaticTimeSource;
+    use aws_smithy_async::time::SharedTimeSource;
+    use aws_smithy_http::auth::{AuthSchemeOptions, HttpAuthStage, NoAuth};
     use aws_smithy_http::body::SdkBody;
+    use aws_smithy_http::diagnostic::DiagnosticContext;
     use aws_smithy_http::middleware::MapRequest;
     use aws_smithy_http::operation;
+    use aws_smithy_http::pipeline::{PipelineConfig, Stage};
+    use aws_types::credentials::Anonymous;
     use aws_types::region::{Region, SigningRegion};
     use aws_types::Credentials;
     use aws_types::SigningService;
@@ -175,6 +290,234 @@ mod test {
         let property_bag = req.properties();
         let signature = property_bag.get::<Signature>();
         assert!(signature.is_some());
+        let diagnostics = property_bag.get::<DiagnosticContext>().unwrap();
+        assert_eq!(diagnostics.signing_region.as_deref(), Some("us-east-1"));
+        assert_eq!(diagnostics.signing_service.as_deref(), Some("kinesis"));
+    }
+
+    #[test]
+    fn anonymous_requests_are_not_signed() {
+        let req = http::Request::builder()
+            .uri("https://test-service.test-region.amazonaws.com/")
+            .body(SdkBody::from(""))
+            .unwrap();
+        let req = operation::Request::new(req)
+            .augment(|req, properties| {
+                properties.insert(SigningService::from_static("kinesis"));
+                properties.insert(OperationSigningConfig::default_config());
+                properties.insert(Anonymous::new());
+                Result::<_, Infallible>::Ok(req)
+            })
+            .expect("succeeds");
+
+        let signer = SigV4SigningStage::new(SigV4Signer::new());
+        let req = signer
+            .apply(req)
+            .expect("anonymous requests don't need credentials");
+        assert!(req.properties().get::<Signature>().is_none());
+        assert!(req.http().headers().get(AUTHORIZATION).is_none());
+    }
+
+    #[test]
+    fn skipped_signing_stage_does_not_sign() {
+        let req = http::Request::builder()
+            .uri("https://bucket.s3.amazonaws.com/key?X-Amz-Signature=abc")
+            .body(SdkBody::from(""))
+            .unwrap();
+        let req = operation::Request::new(req)
+            .augment(|req, properties| {
+                properties.insert(SigningService::from_static("s3"));
+                properties.insert(OperationSigningConfig::default_config());
+                PipelineConfig::new().skip(Stage::Signing).apply(properties);
+                Result::<_, Infallible>::Ok(req)
+            })
+            .expect("succeeds");
+
+        let signer = SigV4SigningStage::new(SigV4Signer::new());
+        let req = signer
+            .apply(req)
+            .expect("credentials aren't needed when signing is skipped");
+        assert!(req.properties().get::<Signature>().is_none());
+        assert!(req.http().headers().get(AUTHORIZATION).is_none());
+    }
+
+    fn sigv4_or_anonymous(credentials: Option<Credentials>) -> operation::Request {
+        let req = http::Request::builder()
+            .uri("https://test-service.test-region.amazonaws.com/")
+            .body(SdkBody::from(""))
+            .unwrap();
+        operation::Request::new(req)
+            .augment(|req, properties| {
+                properties.insert(UNIX_EPOCH + Duration::new(1611160427, 0));
+                properties.insert(SigningService::from_static("kinesis"));
+                properties.insert(SigningRegion::from(Region::new("us-east-1")));
+                properties.insert(OperationSigningConfig::default_config());
+                properties.insert(
+                    AuthSchemeOptions::new()
+                        .with_scheme(SigV4AuthScheme::new(SigV4Signer::new()))
+                        .with_scheme(NoAuth::new()),
+                );
+                if let Some(credentials) = credentials {
+                    properties.insert(credentials);
+                }
+                Result::<_, Infallible>::Ok(req)
+            })
+            .expect("succeeds")
+    }
+
+    #[test]
+    fn auth_scheme_options_prefer_sigv4() {
+        let req = sigv4_or_anonymous(Some(Credentials::new("AKIAfoo", "bar", None, None, "test")));
+        // the signing stage defers to the auth scheme options
+        let req = SigV4SigningStage::new(SigV4Signer::new())
+            .apply(req)
+            .unwrap();
+        assert!(req.properties().get::<Signature>().is_none());
+
+        let req = HttpAuthStage::new().apply(req).unwrap();
+        assert!(req.properties().get::<Signature>().is_some());
+        assert!(req.http().headers().get(AUTHORIZATION).is_some());
+        assert_eq!("sigv4", SIGV4_AUTH_SCHEME_ID.as_str());
+    }
+
+    #[test]
+    fn auth_scheme_options_fall_back_to_anonymous() {
+        let req = sigv4_or_anonymous(None);
+        let req = SigV4SigningStage::new(SigV4Signer::new())
+            .apply(req)
+            .unwrap();
+        let req = HttpAuthStage::new()
+            .apply(req)
+            .expect("anonymous access is allowed");
+        assert!(req.properties().get::<Signature>().is_none());
+        assert!(req.http().headers().get(AUTHORIZATION).is_none());
+    }
+
+    #[test]
+    fn signing_time_comes_from_time_source() {
+        let req = http::Request::builder()
+            .uri("https://test-service.test-region.amazonaws.com/")
+            .body(SdkBody::from(""))
+            .unwrap();
+        let region = Region::new("us-east-1");
+        let req = operation::Request::new(req)
+            .augment(|req, properties| {
+                properties.insert(SharedTimeSource::new(StaticTimeSource::new(
+                    UNIX_EPOCH + Duration::new(1611160427, 0),
+                )));
+                properties.insert(SigningService::from_static("kinesis"));
+                properties.insert(OperationSigningConfig::default_config());
+                properties.insert(Credentials::new("AKIAfoo", "bar", None, None, "test"));
+                properties.insert(SigningRegion::from(region));
+                Result::<_, Infallible>::Ok(req)
+            })
+            .expect("succeeds");
+
+        let signer = SigV4SigningStage::new(SigV4Signer::new());
+        let req = signer.apply(req).unwrap();
+        assert_eq!(
+            req.http().headers().get("x-amz-date").unwrap(),
+            "20210120T163347Z"
+        );
+    }
+
+    #[test]
+    fn signing_time_is_corrected_for_clock_skew() {
+        let req = http::Request::builder()
+            .uri("https://test-service.test-region.amazonaws.com/")
+            .body(SdkBody::from(""))
+            .unwrap();
+        let region = Region::new("us-east-1");
+        let client_time = UNIX_EPOCH + Duration::new(1611160427, 0);
+        let clock_skew = ClockSkew::new();
+        // The service's clock is 10 minutes ahead of the client's
+        clock_skew.update(client_time + Duration::from_secs(600), client_time);
+        let req = operation::Request::new(req)
+            .augment(|req, properties| {
+                properties.insert(SharedTimeSource::new(StaticTimeSource::new(client_time)));
+                properties.insert(clock_skew);
+                properties.insert(SigningService::from_static("kinesis"));
+                properties.insert(OperationSigningConfig::default_config());
+                properties.insert(Credentials::new("AKIAfoo", "bar", None, None, "test"));
+                properties.insert(SigningRegion::from(region));
+                Result::<_, Infallible>::Ok(req)
+            })
+            .expect("succeeds");
+
+        let signer = SigV4SigningStage::new(SigV4Signer::new());
+        let req = signer.apply(req).unwrap();
+        assert_eq!(
+            req.http().headers().get("x-amz-date").unwrap(),
+            "20210120T164347Z"
+        );
+    }
+
+    // framing headers are added on dispatch, after signing, so they must not invalidate the signature
+    #[tokio::test]
+    async fn dispatch_does_not_change_what_was_signed() {
+        use aws_smithy_http_tower::dispatch::DispatchLayer;
+        use tower::{Layer, Service, ServiceExt};
+
+        let req = http::Request::builder()
+            .method("PUT")
+            .uri("https://test-service.test-region.amazonaws.com/")
+            .body(SdkBody::from("hello"))
+            .unwrap();
+        let region = Region::new("us-east-1");
+        let req = operation::Request::new(req)
+            .augment(|req, properties| {
+                properties.insert(region.clone());
+                properties.insert(UNIX_EPOCH + Duration::new(1611160427, 0));
+                properties.insert(SigningService::from_static("kinesis"));
+                let mut config = OperationSigningConfig::default_config();
+                config.signing_options.content_sha256_header = true;
+                properties.insert(config);
+                properties.insert(Credentials::new("AKIAfoo", "bar", None, None, "test"));
+                properties.insert(SigningRegion::from(region));
+                Result::<_, Infallible>::Ok(req)
+            })
+            .expect("succeeds");
+        let req = SigV4SigningStage::new(SigV4Signer::new())
+            .apply(req)
+            .unwrap();
+        let signed_headers = req.http().headers().clone();
+        assert!(!signed_headers.contains_key("content-length"));
+
+        let connector = tower::service_fn(|req: http::Request<SdkBody>| async move {
+            let mut response = http::Response::new(SdkBody::empty());
+            *response.headers_mut() = req.headers().clone();
+            let body = req.body().bytes().expect("in-memory body").to_vec();
+            response
+                .headers_mut()
+                .insert("x-body-sha256", sha256_hex(&body).parse().unwrap());
+            Ok::<_, aws_smithy_http::result::ConnectorError>(response)
+        });
+        let response = DispatchLayer::new()
+            .layer(connector)
+            .ready()
+            .await
+            .unwrap()
+            .call(req)
+            .await
+            .unwrap();
+        let sent = response.http().headers();
+
+        assert_eq!(sent["content-length"], "5");
+        assert_eq!(sent[AUTHORIZATION], signed_headers[AUTHORIZATION]);
+        assert!(!sent[AUTHORIZATION]
+            .to_str()
+            .unwrap()
+            .contains("content-length"));
+        // the payload hash that was signed matches the body that was sent
+        assert_eq!(sent["x-amz-content-sha256"], sent["x-body-sha256"]);
+    }
+
+    fn sha256_hex(bytes: &[u8]) -> String {
+        ring::digest::digest(&ring::digest::SHA256, bytes)
+            .as_ref()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
     }
 
     // check that the endpoint middleware followed by signing middleware produce the expected result
@@ -37,7 +37,7 @@
 //! ```
 
 use crate::date_time::{format_date, format_date_time, truncate_subsecs};
-use crate::sign::{calculate_signature, generate_signing_key, sha256_hex_string};
+use crate::sign::{calculate_signature, sha256_hex_string};
 use crate::SigningOutput;
 use aws_smithy_eventstream::frame::{write_headers_to, Header, HeaderValue, Message};
 use bytes::Bytes;
@@ -117,8 +117,7 @@ fn sign_payload<'a>(
     // needs to exactly match the string formatted timestamp, which doesn't include sub-seconds.
     let time = truncate_subsecs(params.time);
 
-    let signing_key =
-        generate_signing_key(params.secret_key, time, params.region, params.service_name);
+    let signing_key = params.signing_key();
     let string_to_sign = calculate_string_to_sign(
         message_payload.as_ref().map(|v| &v[..]).unwrap_or(&[]),
         last_signature,
@@ -160,6 +159,7 @@ mod tests {
             region: "us-east-1",
             service_name: "testservice",
             time: (UNIX_EPOCH + Duration::new(123_456_789_u64, 1234u32)).into(),
+            signing_key: None,
             settings: (),
         };
 
@@ -198,6 +198,7 @@ mod tests {
             region: "us-east-1",
             service_name: "testservice",
             time: (UNIX_EPOCH + Duration::new(123_456_789_u64, 1234u32)).into(),
+            signing_key: None,
             settings: (),
         };
 
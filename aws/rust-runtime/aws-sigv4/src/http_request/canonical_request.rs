@@ -8,7 +8,7 @@ use super::{Error, PayloadChecksumKind, SignableBody, SignatureLocation, Signing
 use crate::date_time::{format_date, format_date_time};
 use crate::http_request::sign::SignableRequest;
 use crate::http_request::url_escape::percent_encode_path;
-use crate::http_request::PercentEncodingMode;
+use crate::http_request::{PercentEncodingMode, SessionTokenMode, UriPathNormalizationMode};
 use crate::sign::sha256_hex_string;
 use http::header::{HeaderName, HOST, USER_AGENT};
 use http::{HeaderMap, HeaderValue, Method, Uri};
@@ -40,6 +40,7 @@ pub(crate) mod param {
 pub(crate) const HMAC_256: &str = "AWS4-HMAC-SHA256";
 
 const UNSIGNED_PAYLOAD: &str = "UNSIGNED-PAYLOAD";
+const STREAMING_SIGNED_PAYLOAD: &str = "STREAMING-AWS4-HMAC-SHA256-PAYLOAD";
 
 #[derive(Debug, PartialEq)]
 pub(super) struct HeaderValues<'a> {
@@ -114,7 +115,10 @@ impl<'a> CanonicalRequest<'a> {
     /// ## Behavior
     ///
     /// There are several settings which alter signing behavior:
-    /// - If a `security_token` is provided as part of the credentials it will be included in the signed headers
+    /// - If a `security_token` is provided as part of the credentials it will be included in the signed headers,
+    ///   unless `settings.session_token_mode` is `Exclude`
+    /// - If `settings.uri_path_normalization_mode` is `Enabled`, `.` and `..` segments and repeated
+    ///   slashes are removed from the path before it is encoded
     /// - If `settings.percent_encoding_mode` specifies double encoding, `%` in the URL will be re-encoded as `%25`
     /// - If `settings.payload_checksum_kind` is XAmzSha256, add a x-amz-content-sha256 with the body
     ///   checksum. This is the same checksum used as the "payload_hash" in the canonical request
@@ -126,11 +130,14 @@ impl<'a> CanonicalRequest<'a> {
     ) -> Result<CanonicalRequest<'b>, Error> {
         // Path encoding: if specified, re-encode % as %25
         // Set method and path into CanonicalRequest
-        let path = req.uri().path();
+        let path = match params.settings.uri_path_normalization_mode {
+            UriPathNormalizationMode::Enabled => normalize_uri_path(req.uri().path()),
+            UriPathNormalizationMode::Disabled => Cow::Borrowed(req.uri().path()),
+        };
         let path = match params.settings.percent_encoding_mode {
             // The string is already URI encoded, we don't need to encode everything again, just `%`
-            PercentEncodingMode::Double => Cow::Owned(percent_encode_path(path)),
-            PercentEncodingMode::Single => Cow::Borrowed(path),
+            PercentEncodingMode::Double => Cow::Owned(percent_encode_path(&path)),
+            PercentEncodingMode::Single => path,
         };
         let payload_hash = Self::payload_hash(req.body());
 
@@ -138,6 +145,10 @@ impl<'a> CanonicalRequest<'a> {
         let (signed_headers, canonical_headers) =
             Self::headers(req, params, &payload_hash, &date_time)?;
         let signed_headers = SignedHeaders::new(signed_headers);
+        let canonical_security_token = match params.settings.session_token_mode {
+            SessionTokenMode::Include => params.security_token,
+            SessionTokenMode::Exclude => None,
+        };
         let values = match params.settings.signature_location {
             SignatureLocation::Headers => SignatureValues::Headers(HeaderValues {
                 content_sha256: payload_hash,
@@ -162,7 +173,7 @@ impl<'a> CanonicalRequest<'a> {
                     .expect("presigning requires expires_in")
                     .as_secs()
                     .to_string(),
-                security_token: params.security_token,
+                security_token: canonical_security_token,
                 signed_headers,
             }),
         };
@@ -204,7 +215,9 @@ impl<'a> CanonicalRequest<'a> {
         if params.settings.signature_location == SignatureLocation::Headers {
             Self::insert_date_header(&mut canonical_headers, date_time);
 
-            if let Some(security_token) = params.security_token {
+            if let (Some(security_token), SessionTokenMode::Include) =
+                (params.security_token, &params.settings.session_token_mode)
+            {
                 let mut sec_header = HeaderValue::from_str(security_token)?;
                 sec_header.set_sensitive(true);
                 canonical_headers.insert(header::X_AMZ_SECURITY_TOKEN, sec_header);
@@ -216,15 +229,30 @@ impl<'a> CanonicalRequest<'a> {
             }
         }
 
+        for required in &params.settings.required_headers {
+            if !canonical_headers.contains_key(required) {
+                return Err(format!(
+                    "the `{}` header must be signed, but it is missing from the request",
+                    required
+                )
+                .into());
+            }
+        }
+
         let mut signed_headers = Vec::with_capacity(canonical_headers.len());
         for (name, _) in &canonical_headers {
-            // The user agent header should not be signed because it may be altered by proxies
-            if name == USER_AGENT {
-                continue;
-            }
-            if params.settings.signature_location == SignatureLocation::QueryParams {
-                // The X-Amz-User-Agent header should not be signed if this is for a presigned URL
-                if name == HeaderName::from_static(header::X_AMZ_USER_AGENT) {
+            if !params.settings.required_headers.contains(name) {
+                // The user agent header should not be signed because it may be altered by proxies
+                if name == USER_AGENT {
+                    continue;
+                }
+                if params.settings.signature_location == SignatureLocation::QueryParams {
+                    // The X-Amz-User-Agent header should not be signed if this is for a presigned URL
+                    if name == HeaderName::from_static(header::X_AMZ_USER_AGENT) {
+                        continue;
+                    }
+                }
+                if params.settings.excluded_headers.contains(name) && !Self::added_by_signer(name) {
                     continue;
                 }
             }
@@ -233,6 +261,14 @@ impl<'a> CanonicalRequest<'a> {
         Ok((signed_headers, canonical_headers))
     }
 
+    /// Returns true for headers that are always signed: `host` and the headers the signer adds
+    fn added_by_signer(name: &HeaderName) -> bool {
+        name == HOST
+            || name == header::X_AMZ_DATE
+            || name == header::X_AMZ_SECURITY_TOKEN
+            || name == header::X_AMZ_CONTENT_SHA_256
+    }
+
     fn payload_hash<'b>(body: &'b SignableBody<'b>) -> Cow<'b, str> {
         // Payload hash computation
         //
@@ -241,10 +277,12 @@ impl<'a> CanonicalRequest<'a> {
         // - compute a hash
         // - use the precomputed hash
         // - use `UnsignedPayload`
+        // - use `StreamingSignedPayload`
         match body {
             SignableBody::Bytes(data) => Cow::Owned(sha256_hex_string(data)),
             SignableBody::Precomputed(digest) => Cow::Borrowed(digest.as_str()),
             SignableBody::UnsignedPayload => Cow::Borrowed(UNSIGNED_PAYLOAD),
+            SignableBody::StreamingSignedPayload => Cow::Borrowed(STREAMING_SIGNED_PAYLOAD),
         }
     }
 
@@ -372,6 +410,39 @@ fn normalize_header_value(header_value: &HeaderValue) -> HeaderValue {
     HeaderValue::from_bytes(&trimmed_value).unwrap()
 }
 
+/// Normalizes a URI path by removing `.` and `..` segments and collapsing repeated slashes,
+/// e.g. "//example/./foo/../bar/" -> "/example/bar/".
+///
+/// A trailing slash is preserved unless the normalized path is just "/".
+fn normalize_uri_path(path: &str) -> Cow<'_, str> {
+    let mut segments: Vec<&str> = Vec::new();
+    for segment in path.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop();
+            }
+            segment => segments.push(segment),
+        }
+    }
+    let trailing_slash = !segments.is_empty()
+        && (path.ends_with('/') || path.ends_with("/.") || path.ends_with("/.."));
+
+    let mut normalized = String::with_capacity(path.len() + 1);
+    for segment in &segments {
+        normalized.push('/');
+        normalized.push_str(segment);
+    }
+    if normalized.is_empty() || trailing_slash {
+        normalized.push('/');
+    }
+    if normalized == path {
+        Cow::Borrowed(path)
+    } else {
+        Cow::Owned(normalized)
+    }
+}
+
 #[derive(Debug, PartialEq, Default)]
 pub(super) struct SignedHeaders {
     headers: Vec<CanonicalHeaderName>,
@@ -490,21 +561,26 @@ impl<'a> fmt::Display for StringToSign<'a> {
 mod tests {
     use crate::date_time::test_parsers::parse_date_time;
     use crate::http_request::canonical_request::{
-        normalize_header_value, trim_all, CanonicalRequest, SigningScope, StringToSign,
+        normalize_header_value, normalize_uri_path, trim_all, CanonicalRequest, SigningScope,
+        StringToSign,
     };
     use crate::http_request::query_writer::QueryWriter;
     use crate::http_request::test::{test_canonical_request, test_request, test_sts};
     use crate::http_request::{
-        PayloadChecksumKind, SignableBody, SignableRequest, SigningSettings,
+        PayloadChecksumKind, SessionTokenMode, SignableBody, SignableRequest, SigningSettings,
+        UriPathNormalizationMode,
     };
     use crate::http_request::{SignatureLocation, SigningParams};
     use crate::sign::sha256_hex_string;
+    use http::header::{HeaderName, HOST, USER_AGENT};
     use http::HeaderValue;
     use http::Uri;
     use pretty_assertions::assert_eq;
     use proptest::proptest;
     use std::time::Duration;
 
+    const TEST_SESSION_TOKEN: &str = "AQoDYXdzEPT//////////wEXAMPLEtc764bNrC9SAPBSM22wDOk4x4HIZ8j4FZTwdQWLWsKWHGBuFqwAeMicRXmxfpSPfIeoIYRqTflfKD8YUuwthAx7mSEI/qkPpKPi/kMcGdQrmGdeehM4IC1NtBmUpp2wUE8phUZampKsburEDy0KPkyQDYwT7WZ0wq5VSXDvp75YU9HFvlRd8Tx6q6fE8YQcHNVXAkiY9q6d+xo0rKwT38xVqr7ZD0u0iPPkUL64lIZbqBAz+scqKmlzm8FDrypNC9Yjc8fPOLn9FX9KSYvKTr4rvx3iSIlTJabIQwj2ICCR/oLxBA==";
+
     fn signing_params(settings: SigningSettings) -> SigningParams<'static> {
         SigningParams {
             access_key: "test-access-key",
@@ -513,6 +589,7 @@ mod tests {
             region: "test-region",
             service_name: "testservicename",
             time: parse_date_time("20210511T154045Z").unwrap(),
+            signing_key: None,
             settings,
         }
     }
@@ -542,6 +619,44 @@ mod tests {
         assert_eq!(creq.values.signed_headers().as_str(), "host;x-amz-date");
     }
 
+    #[test]
+    fn test_excluded_and_required_headers() {
+        let mut req = test_request("get-vanilla-query-order-key-case");
+        req.headers_mut()
+            .insert("x-amzn-trace-id", HeaderValue::from_static("Root=1"));
+        req.headers_mut()
+            .insert(USER_AGENT, HeaderValue::from_static("test-agent"));
+        let req = SignableRequest::from(&req);
+        let settings = SigningSettings {
+            excluded_headers: vec![
+                HeaderName::from_static("x-amzn-trace-id"),
+                // always signed, so excluding it has no effect
+                HOST,
+            ],
+            required_headers: vec![USER_AGENT],
+            ..Default::default()
+        };
+        let signing_params = signing_params(settings);
+        let creq = CanonicalRequest::from(&req, &signing_params).unwrap();
+        assert_eq!(
+            creq.values.signed_headers().as_str(),
+            "host;user-agent;x-amz-date"
+        );
+    }
+
+    #[test]
+    fn test_missing_required_header() {
+        let req = test_request("get-vanilla-query-order-key-case");
+        let req = SignableRequest::from(&req);
+        let settings = SigningSettings {
+            required_headers: vec![HeaderName::from_static("x-custom-header")],
+            ..Default::default()
+        };
+        let signing_params = signing_params(settings);
+        let err = CanonicalRequest::from(&req, &signing_params).expect_err("header is missing");
+        assert!(err.to_string().contains("x-custom-header"), "{}", err);
+    }
+
     #[test]
     fn test_unsigned_payload() {
         let req = test_request("get-vanilla-query-order-key-case");
@@ -561,6 +676,30 @@ mod tests {
         assert!(creq.to_string().ends_with("UNSIGNED-PAYLOAD"));
     }
 
+    #[test]
+    fn test_streaming_signed_payload() {
+        let req = test_request("get-vanilla-query-order-key-case");
+        let req = SignableRequest::new(
+            req.method(),
+            req.uri(),
+            req.headers(),
+            SignableBody::StreamingSignedPayload,
+        );
+        let settings = SigningSettings {
+            payload_checksum_kind: PayloadChecksumKind::XAmzSha256,
+            ..Default::default()
+        };
+        let signing_params = signing_params(settings);
+        let creq = CanonicalRequest::from(&req, &signing_params).unwrap();
+        assert_eq!(
+            creq.values.content_sha256(),
+            "STREAMING-AWS4-HMAC-SHA256-PAYLOAD"
+        );
+        assert!(creq
+            .to_string()
+            .ends_with("STREAMING-AWS4-HMAC-SHA256-PAYLOAD"));
+    }
+
     #[test]
     fn test_precomputed_payload() {
         let payload_hash = "44ce7dd67c959e0d3524ffac1771dfbba87d2b6b4b4e99e42034a8b803f8b072";
@@ -623,6 +762,93 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn test_normalize_path() {
+        // `get-space` is omitted because its request line can't be parsed with a literal space
+        for test in [
+            "normalize-path/get-relative",
+            "normalize-path/get-relative-relative",
+            "normalize-path/get-slash",
+            "normalize-path/get-slash-dot-slash",
+            "normalize-path/get-slash-pointless-dot",
+            "normalize-path/get-slashes",
+        ] {
+            let req = test_request(test);
+            let req = SignableRequest::from(&req);
+            let settings = SigningSettings {
+                uri_path_normalization_mode: UriPathNormalizationMode::Enabled,
+                ..Default::default()
+            };
+            let mut signing_params = signing_params(settings);
+            signing_params.time = parse_date_time("20150830T123600Z").unwrap();
+            let creq = CanonicalRequest::from(&req, &signing_params).unwrap();
+
+            let expected = test_canonical_request(test);
+            let actual = format!("{}", creq);
+            assert_eq!(actual, expected, "test case: {}", test);
+        }
+    }
+
+    #[test]
+    fn test_path_normalization_disabled() {
+        let req = test_request("normalize-path/get-slashes");
+        let req = SignableRequest::from(&req);
+        let settings = SigningSettings {
+            uri_path_normalization_mode: UriPathNormalizationMode::Disabled,
+            ..Default::default()
+        };
+        let signing_params = signing_params(settings);
+        let creq = CanonicalRequest::from(&req, &signing_params).unwrap();
+        assert_eq!("//example//", creq.path);
+    }
+
+    #[test]
+    fn test_normalize_uri_path() {
+        assert_eq!("/", normalize_uri_path(""));
+        assert_eq!("/", normalize_uri_path("/"));
+        assert_eq!("/foo/bar", normalize_uri_path("/foo/bar"));
+        assert_eq!("/foo/bar/", normalize_uri_path("/foo/bar/"));
+        assert_eq!("/bar", normalize_uri_path("/foo/../bar"));
+        assert_eq!("/foo/", normalize_uri_path("/foo/bar/.."));
+        assert_eq!("/foo/bar/", normalize_uri_path("/foo/bar/."));
+        assert_eq!("/", normalize_uri_path("/../.."));
+        assert_eq!("/foo%2F..", normalize_uri_path("/foo%2F.."));
+    }
+
+    #[test]
+    fn test_session_token_included() {
+        let test = "post-sts-token/post-sts-header-before";
+        let req = test_request(test);
+        let req = SignableRequest::from(&req);
+        let mut signing_params = signing_params(SigningSettings::default());
+        signing_params.security_token = Some(TEST_SESSION_TOKEN);
+        signing_params.time = parse_date_time("20150830T123600Z").unwrap();
+        let creq = CanonicalRequest::from(&req, &signing_params).unwrap();
+
+        let expected = test_canonical_request(test);
+        let actual = format!("{}", creq);
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_session_token_excluded() {
+        let test = "post-sts-token/post-sts-header-after";
+        let req = test_request(test);
+        let req = SignableRequest::from(&req);
+        let settings = SigningSettings {
+            session_token_mode: SessionTokenMode::Exclude,
+            ..Default::default()
+        };
+        let mut signing_params = signing_params(settings);
+        signing_params.security_token = Some(TEST_SESSION_TOKEN);
+        signing_params.time = parse_date_time("20150830T123600Z").unwrap();
+        let creq = CanonicalRequest::from(&req, &signing_params).unwrap();
+
+        let expected = test_canonical_request(test);
+        let actual = format!("{}", creq);
+        assert_eq!(actual, expected);
+    }
+
     #[test]
     fn test_double_url_encode() {
         let req = test_request("double-url-encode");
@@ -42,6 +42,7 @@
 //!
 
 mod canonical_request;
+mod chunked;
 mod query_writer;
 mod settings;
 mod sign;
@@ -50,7 +51,9 @@ mod url_escape;
 #[cfg(test)]
 pub(crate) mod test;
 
+pub use chunked::{encoded_length, sign_chunk};
 pub use settings::{
-    PayloadChecksumKind, PercentEncodingMode, SignatureLocation, SigningParams, SigningSettings,
+    PayloadChecksumKind, PercentEncodingMode, SessionTokenMode, SignatureLocation, SigningParams,
+    SigningSettings, UriPathNormalizationMode,
 };
 pub use sign::{sign, Error, SignableBody, SignableRequest};
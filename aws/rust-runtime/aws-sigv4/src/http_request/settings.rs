@@ -3,6 +3,7 @@
  * SPDX-License-Identifier: Apache-2.0.
  */
 
+use http::HeaderName;
 use std::time::Duration;
 
 /// HTTP signing parameters
@@ -25,6 +26,24 @@ pub struct SigningSettings {
 
     /// For presigned requests, how long the presigned request is valid for
     pub expires_in: Option<Duration>,
+
+    /// Specifies whether the URI path should be normalized when building the canonical request.
+    /// Paths are signed as-is by default.
+    pub uri_path_normalization_mode: UriPathNormalizationMode,
+
+    /// Specifies whether the session token should be included in the canonical request
+    pub session_token_mode: SessionTokenMode,
+
+    /// Headers to leave out of the signature, such as headers that proxies may add or modify
+    ///
+    /// The `host` header and the headers added by the signer are always signed.
+    pub excluded_headers: Vec<HeaderName>,
+
+    /// Headers that must be signed
+    ///
+    /// These headers are signed even if they would otherwise be excluded (e.g. `user-agent`).
+    /// Signing fails if the request is missing any of them.
+    pub required_headers: Vec<HeaderName>,
 }
 
 /// HTTP payload checksum type
@@ -57,6 +76,34 @@ pub enum PercentEncodingMode {
     Single,
 }
 
+/// Config value to specify whether the URI path should be normalized when signing.
+///
+/// Normalization removes `.` and `..` segments and collapses repeated slashes. S3 treats the path
+/// as an opaque object key, so it must be signed exactly as it will be sent.
+#[non_exhaustive]
+#[derive(Debug, Eq, PartialEq)]
+pub enum UriPathNormalizationMode {
+    /// Normalize the URI path according to RFC 3986 before signing
+    Enabled,
+
+    /// Sign the URI path as-is
+    Disabled,
+}
+
+/// Config value to specify whether the session token is part of the signature.
+///
+/// Some services expect `X-Amz-Security-Token` to be added to the request _after_ the
+/// signature is calculated. In both modes, the token is still added to the signed request.
+#[non_exhaustive]
+#[derive(Debug, Eq, PartialEq)]
+pub enum SessionTokenMode {
+    /// Include the session token in the canonical request
+    Include,
+
+    /// Exclude the session token from the canonical request
+    Exclude,
+}
+
 impl Default for SigningSettings {
     fn default() -> Self {
         Self {
@@ -64,6 +111,10 @@ impl Default for SigningSettings {
             payload_checksum_kind: PayloadChecksumKind::NoHeader,
             signature_location: SignatureLocation::Headers,
             expires_in: None,
+            uri_path_normalization_mode: UriPathNormalizationMode::Disabled,
+            session_token_mode: SessionTokenMode::Include,
+            excluded_headers: Vec::new(),
+            required_headers: Vec::new(),
         }
     }
 }
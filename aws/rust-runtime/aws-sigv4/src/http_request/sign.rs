@@ -9,7 +9,7 @@ use crate::http_request::canonical_request::param;
 use crate::http_request::canonical_request::{CanonicalRequest, StringToSign, HMAC_256};
 use crate::http_request::query_writer::QueryWriter;
 use crate::http_request::SigningParams;
-use crate::sign::{calculate_signature, generate_signing_key, sha256_hex_string};
+use crate::sign::{calculate_signature, sha256_hex_string};
 use crate::SigningOutput;
 use http::header::HeaderValue;
 use http::{HeaderMap, Method, Uri};
@@ -101,6 +101,12 @@ pub enum SignableBody<'a> {
     /// lowercase hex encoded. Eg:
     /// `e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855`
     Precomputed(String),
+
+    /// A payload that will be sent with `aws-chunked` content encoding, where each chunk
+    /// carries its own signature.
+    ///
+    /// The request signature seeds the signature of the first chunk. See [`sign_chunk`](super::sign_chunk).
+    StreamingSignedPayload,
 }
 
 #[derive(Debug)]
@@ -189,12 +195,7 @@ fn calculate_signing_params<'a>(
         params.service_name,
         encoded_creq,
     );
-    let signing_key = generate_signing_key(
-        params.secret_key,
-        params.time,
-        params.region,
-        params.service_name,
-    );
+    let signing_key = params.signing_key();
     let signature = calculate_signature(signing_key, sts.to_string().as_bytes());
 
     let values = creq.values.into_query_params().expect("signing with query");
@@ -242,12 +243,7 @@ fn calculate_signing_headers<'a>(
     );
 
     // Step 3: https://docs.aws.amazon.com/en_pv/general/latest/gr/sigv4-calculate-signature.html
-    let signing_key = generate_signing_key(
-        params.secret_key,
-        params.time,
-        params.region,
-        params.service_name,
-    );
+    let signing_key = params.signing_key();
     let signature = calculate_signature(signing_key, sts.to_string().as_bytes());
 
     // Step 4: https://docs.aws.amazon.com/en_pv/general/latest/gr/sigv4-add-signature-to-request.html
@@ -305,7 +301,10 @@ mod tests {
         make_headers_comparable, test_request, test_signed_request,
         test_signed_request_query_params,
     };
-    use crate::http_request::{SignatureLocation, SigningParams, SigningSettings};
+    use crate::http_request::{
+        SessionTokenMode, SignatureLocation, SigningParams, SigningSettings,
+    };
+    use crate::sign::generate_signing_key;
     use http::{HeaderMap, HeaderValue};
     use pretty_assertions::assert_eq;
     use std::borrow::Cow;
@@ -329,6 +328,7 @@ mod tests {
             region: "us-east-1",
             service_name: "service",
             time: parse_date_time("20150830T123600Z").unwrap(),
+            signing_key: None,
             settings,
         };
 
@@ -347,6 +347,35 @@ mod tests {
         assert_req_eq!(expected, signed);
     }
 
+    #[test]
+    fn test_sign_with_precomputed_signing_key() {
+        let time = parse_date_time("20150830T123600Z").unwrap();
+        let secret_key = "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY";
+        let params = SigningParams {
+            access_key: "AKIDEXAMPLE",
+            // the secret key is ignored when a signing key is given
+            secret_key: "not the secret key",
+            security_token: None,
+            region: "us-east-1",
+            service_name: "service",
+            time,
+            signing_key: Some(generate_signing_key(
+                secret_key,
+                time,
+                "us-east-1",
+                "service",
+            )),
+            settings: SigningSettings::default(),
+        };
+
+        let original = test_request("get-vanilla-query-order-key-case");
+        let out = sign(SignableRequest::from(&original), &params).unwrap();
+        assert_eq!(
+            "b97d918cfa904a5beff61c982a1b6f458b799221646efd99d3219ec94cdf2500",
+            out.signature
+        );
+    }
+
     #[test]
     fn test_sign_url_escape() {
         let test = "double-encode-path";
@@ -358,6 +387,7 @@ mod tests {
             region: "us-east-1",
             service_name: "service",
             time: parse_date_time("20150830T123600Z").unwrap(),
+            signing_key: None,
             settings,
         };
 
@@ -376,11 +406,49 @@ mod tests {
         assert_req_eq!(expected, signed);
     }
 
+    #[test]
+    fn test_sign_with_session_token_excluded() {
+        let security_token = "AQoDYXdzEPT//////////wEXAMPLEtc764bNrC9SAPBSM22wDOk4x4HIZ8j4FZTwdQWLWsKWHGBuFqwAeMicRXmxfpSPfIeoIYRqTflfKD8YUuwthAx7mSEI/qkPpKPi/kMcGdQrmGdeehM4IC1NtBmUpp2wUE8phUZampKsburEDy0KPkyQDYwT7WZ0wq5VSXDvp75YU9HFvlRd8Tx6q6fE8YQcHNVXAkiY9q6d+xo0rKwT38xVqr7ZD0u0iPPkUL64lIZbqBAz+scqKmlzm8FDrypNC9Yjc8fPOLn9FX9KSYvKTr4rvx3iSIlTJabIQwj2ICCR/oLxBA==";
+        let settings = SigningSettings {
+            session_token_mode: SessionTokenMode::Exclude,
+            ..Default::default()
+        };
+        let params = SigningParams {
+            access_key: "AKIDEXAMPLE",
+            secret_key: "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
+            security_token: Some(security_token),
+            region: "us-east-1",
+            service_name: "service",
+            time: parse_date_time("20150830T123600Z").unwrap(),
+            signing_key: None,
+            settings,
+        };
+
+        let original = test_request("post-sts-token/post-sts-header-after");
+        let signable = SignableRequest::from(&original);
+        let out = sign(signable, &params).unwrap();
+        assert_eq!(
+            "5da7c1a2acd57cee7505fc6676e4e544621c30862966e37dddb68e92efbe5d6b",
+            out.signature
+        );
+        // The token isn't signed, but it still needs to be sent along with the request
+        assert_eq!(
+            security_token,
+            out.output
+                .headers()
+                .unwrap()
+                .get("x-amz-security-token")
+                .unwrap()
+        );
+    }
+
     #[test]
     fn test_sign_vanilla_with_query_params() {
-        let mut settings = SigningSettings::default();
-        settings.signature_location = SignatureLocation::QueryParams;
-        settings.expires_in = Some(Duration::from_secs(35));
+        let settings = SigningSettings {
+            signature_location: SignatureLocation::QueryParams,
+            expires_in: Some(Duration::from_secs(35)),
+            ..Default::default()
+        };
         let params = SigningParams {
             access_key: "AKIDEXAMPLE",
             secret_key: "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
@@ -388,6 +456,7 @@ mod tests {
             region: "us-east-1",
             service_name: "service",
             time: parse_date_time("20150830T123600Z").unwrap(),
+            signing_key: None,
             settings,
         };
 
@@ -416,6 +485,7 @@ mod tests {
             region: "us-east-1",
             service_name: "service",
             time: parse_date_time("20150830T123600Z").unwrap(),
+            signing_key: None,
             settings,
         };
 
@@ -466,6 +536,7 @@ mod tests {
             region: "us-east-1",
             service_name: "service",
             time: parse_date_time("20150830T123600Z").unwrap(),
+            signing_key: None,
             settings,
         };
 
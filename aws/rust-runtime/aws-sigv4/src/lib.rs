@@ -14,6 +14,7 @@
     unreachable_pub
 )]
 
+use ring::hmac::Tag;
 use std::time::SystemTime;
 
 pub mod sign;
@@ -44,6 +45,9 @@ pub struct SigningParams<'a, S> {
     /// Timestamp to use in the signature (should be `SystemTime::now()` unless testing).
     pub(crate) time: SystemTime,
 
+    /// (Optional) Signing key previously derived for the same secret key, date, region, and service.
+    pub(crate) signing_key: Option<Tag>,
+
     /// Additional signing settings. These differ between HTTP and Event Stream.
     pub(crate) settings: S,
 }
@@ -55,9 +59,19 @@ impl<'a, S: Default> SigningParams<'a, S> {
     }
 }
 
+impl<'a, S> SigningParams<'a, S> {
+    /// Returns the precomputed signing key if one was given, otherwise derives it
+    pub(crate) fn signing_key(&self) -> Tag {
+        self.signing_key.unwrap_or_else(|| {
+            sign::generate_signing_key(self.secret_key, self.time, self.region, self.service_name)
+        })
+    }
+}
+
 /// Builder and error for creating [`SigningParams`]
 pub mod signing_params {
     use super::SigningParams;
+    use ring::hmac::Tag;
     use std::error::Error;
     use std::fmt;
     use std::time::SystemTime;
@@ -90,6 +104,7 @@ pub mod signing_params {
         region: Option<&'a str>,
         service_name: Option<&'a str>,
         time: Option<SystemTime>,
+        signing_key: Option<Tag>,
         settings: Option<S>,
     }
 
@@ -154,6 +169,21 @@ pub mod signing_params {
             self.time = time;
         }
 
+        /// Sets a signing key to use instead of deriving one (optional)
+        ///
+        /// The key must have been derived with [`generate_signing_key`](crate::sign::generate_signing_key)
+        /// from the same secret key, date, region, and service name as these params. Reusing a key
+        /// avoids the four HMAC operations needed to derive it for every signature.
+        pub fn signing_key(mut self, signing_key: Tag) -> Self {
+            self.signing_key = Some(signing_key);
+            self
+        }
+
+        /// Sets a signing key to use instead of deriving one (optional)
+        pub fn set_signing_key(&mut self, signing_key: Option<Tag>) {
+            self.signing_key = signing_key;
+        }
+
         /// Sets additional signing settings (required)
         pub fn settings(mut self, settings: S) -> Self {
             self.settings = Some(settings);
@@ -184,6 +214,7 @@ pub mod signing_params {
                 time: self
                     .time
                     .ok_or_else(|| BuildError::new("time is required"))?,
+                signing_key: self.signing_key,
                 settings: self
                     .settings
                     .ok_or_else(|| BuildError::new("settings are required"))?,
@@ -36,6 +36,9 @@ struct Inner {
     expires_after: Option<SystemTime>,
 
     provider_name: &'static str,
+
+    /// The ID of the AWS account the credentials belong to, if the provider knows it
+    account_id: Option<String>,
 }
 
 impl Debug for Credentials {
@@ -45,6 +48,9 @@ impl Debug for Credentials {
             .field("provider_name", &self.0.provider_name)
             .field("access_key_id", &self.0.access_key_id.as_str())
             .field("secret_access_key", &"** redacted **");
+        if let Some(account_id) = self.account_id() {
+            creds.field("account_id", &account_id);
+        }
         if let Some(expiry) = self.expiry() {
             if let Some(formatted) = expiry.duration_since(UNIX_EPOCH).ok().and_then(|dur| {
                 aws_smithy_types::DateTime::from_secs(dur.as_secs() as _)
@@ -81,6 +87,7 @@ impl Credentials {
             session_token: Zeroizing::new(session_token),
             expires_after,
             provider_name,
+            account_id: None,
         }))
     }
 
@@ -170,6 +177,24 @@ impl Credentials {
     pub fn session_token(&self) -> Option<&str> {
         self.0.session_token.as_deref()
     }
+
+    /// Returns the ID of the AWS account these credentials belong to, if it is known.
+    pub fn account_id(&self) -> Option<&str> {
+        self.0.account_id.as_deref()
+    }
+
+    /// Sets the ID of the AWS account these credentials belong to.
+    ///
+    /// Services that route requests by account ID use it to resolve their endpoint.
+    pub fn with_account_id(mut self, account_id: impl Into<String>) -> Self {
+        Arc::make_mut(&mut self.0).account_id = Some(account_id.into());
+        self
+    }
+
+    /// Returns the name of the provider that loaded these credentials.
+    pub fn provider_name(&self) -> &'static str {
+        self.0.provider_name
+    }
 }
 
 #[cfg(test)]
@@ -191,4 +216,16 @@ mod test {
             r#"Credentials { provider_name: "debug tester", access_key_id: "akid", secret_access_key: "** redacted **", expires_after: "2009-02-13T23:31:30Z" }"#
         );
     }
+
+    #[test]
+    fn account_id() {
+        let creds = Credentials::new("akid", "secret", None, None, "test");
+        assert_eq!(None, creds.account_id());
+        let creds = creds.with_account_id("123456789012");
+        assert_eq!(Some("123456789012"), creds.account_id());
+        assert_eq!(
+            format!("{:?}", creds),
+            r#"Credentials { provider_name: "test", access_key_id: "akid", secret_access_key: "** redacted **", account_id: "123456789012" }"#
+        );
+    }
 }
@@ -76,3 +76,19 @@ pub use provider::CredentialsError;
 pub use provider::ProvideCredentials;
 pub use provider::Result;
 pub use provider::SharedCredentialsProvider;
+
+/// Marks an operation as anonymous: it will be sent without credentials or a signature.
+///
+/// Insert this into an operation's property bag to skip credential resolution entirely, for
+/// example when reading from a public S3 bucket. Operations that are modeled as unauthenticated
+/// (such as STS `AssumeRoleWithWebIdentity`) are marked as anonymous automatically.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct Anonymous;
+
+impl Anonymous {
+    /// Creates a new `Anonymous` marker.
+    pub fn new() -> Self {
+        Anonymous
+    }
+}
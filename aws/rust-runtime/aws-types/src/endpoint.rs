@@ -9,7 +9,8 @@ use crate::region::{Region, SigningRegion};
 use crate::SigningService;
 use aws_smithy_http::endpoint::{Endpoint, EndpointPrefix};
 use std::error::Error;
-use std::fmt::Debug;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::str::FromStr;
 
 /// Endpoint to connect to an AWS Service
 ///
@@ -83,6 +84,137 @@ pub trait ResolveAwsEndpoint: Send + Sync + Debug {
     /// Resolves the AWS endpoint for a given region.
     // TODO(https://github.com/awslabs/smithy-rs/issues/866): Create `ResolveEndpointError`
     fn resolve_endpoint(&self, region: &Region) -> Result<AwsEndpoint, BoxError>;
+
+    /// Resolves the AWS endpoint for a given region and [`EndpointVariant`]
+    ///
+    /// The default implementation delegates to [`resolve_endpoint`](ResolveAwsEndpoint::resolve_endpoint)
+    /// for the default variant and returns an error for any other variant.
+    fn resolve_endpoint_variant(
+        &self,
+        region: &Region,
+        variant: EndpointVariant,
+    ) -> Result<AwsEndpoint, BoxError> {
+        if variant.is_default() {
+            self.resolve_endpoint(region)
+        } else {
+            Err(format!(
+                "this endpoint resolver does not support {} endpoints (region: {})",
+                variant, region
+            )
+            .into())
+        }
+    }
+}
+
+/// Variant of an endpoint to resolve, e.g. a FIPS-compliant or dual-stack (IPv4 and IPv6) endpoint
+///
+/// The default variant is the standard endpoint for a region.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+pub struct EndpointVariant {
+    use_fips: bool,
+    use_dual_stack: bool,
+}
+
+impl EndpointVariant {
+    /// Creates the default endpoint variant
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether a FIPS-compliant endpoint should be used
+    pub fn with_fips(mut self, use_fips: bool) -> Self {
+        self.use_fips = use_fips;
+        self
+    }
+
+    /// Sets whether a dual-stack endpoint should be used
+    pub fn with_dual_stack(mut self, use_dual_stack: bool) -> Self {
+        self.use_dual_stack = use_dual_stack;
+        self
+    }
+
+    /// Returns true if a FIPS-compliant endpoint should be used
+    pub fn use_fips(&self) -> bool {
+        self.use_fips
+    }
+
+    /// Returns true if a dual-stack endpoint should be used
+    pub fn use_dual_stack(&self) -> bool {
+        self.use_dual_stack
+    }
+
+    /// Returns true if this is the standard endpoint variant
+    pub fn is_default(&self) -> bool {
+        !self.use_fips && !self.use_dual_stack
+    }
+}
+
+impl Display for EndpointVariant {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match (self.use_fips, self.use_dual_stack) {
+            (false, false) => write!(f, "standard"),
+            (true, false) => write!(f, "FIPS"),
+            (false, true) => write!(f, "dual-stack"),
+            (true, true) => write!(f, "FIPS and dual-stack"),
+        }
+    }
+}
+
+/// Whether STS requests in the legacy global regions are sent to the global STS endpoint
+///
+/// Historically, STS was only available at `sts.amazonaws.com`. With [`Legacy`](Self::Legacy),
+/// requests made in the regions that predate regional STS endpoints are sent to the global
+/// endpoint and signed for `us-east-1`. With [`Regional`](Self::Regional), the default, requests
+/// are always sent to the STS endpoint for the configured region, which avoids a cross-region
+/// round trip and allows the use of STS VPC endpoints.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum StsRegionalEndpoints {
+    /// Always use the STS endpoint for the configured region
+    Regional,
+    /// Use the global STS endpoint in the legacy global regions
+    Legacy,
+}
+
+// `#[default]` on enum variants requires Rust 1.62, which is newer than the MSRV
+#[allow(clippy::derivable_impls)]
+impl Default for StsRegionalEndpoints {
+    fn default() -> Self {
+        StsRegionalEndpoints::Regional
+    }
+}
+
+const VALID_STS_REGIONAL_ENDPOINTS: &[&str] = &["regional", "legacy"];
+
+/// Failure to parse an [`StsRegionalEndpoints`] from a string
+#[derive(Debug)]
+pub struct StsRegionalEndpointsParseErr(String);
+
+impl Display for StsRegionalEndpointsParseErr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "error parsing string '{}' as StsRegionalEndpoints, valid options are: {:#?}",
+            self.0, VALID_STS_REGIONAL_ENDPOINTS
+        )
+    }
+}
+
+impl Error for StsRegionalEndpointsParseErr {}
+
+impl FromStr for StsRegionalEndpoints {
+    type Err = StsRegionalEndpointsParseErr;
+
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        let string = string.trim();
+        if string.eq_ignore_ascii_case("regional") {
+            Ok(StsRegionalEndpoints::Regional)
+        } else if string.eq_ignore_ascii_case("legacy") {
+            Ok(StsRegionalEndpoints::Legacy)
+        } else {
+            Err(StsRegionalEndpointsParseErr(string.to_owned()))
+        }
+    }
 }
 
 /// The scope for AWS credentials.
@@ -164,13 +296,70 @@ impl ResolveAwsEndpoint for Endpoint {
             credential_scope: Default::default(),
         })
     }
+
+    /// Static endpoints are used as-is: the FIPS and dual-stack settings do not apply to them
+    fn resolve_endpoint_variant(
+        &self,
+        region: &Region,
+        _variant: EndpointVariant,
+    ) -> Result<AwsEndpoint, BoxError> {
+        self.resolve_endpoint(region)
+    }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::endpoint::CredentialScope;
-    use crate::region::SigningRegion;
+    use crate::endpoint::{
+        CredentialScope, EndpointVariant, ResolveAwsEndpoint, StsRegionalEndpoints,
+    };
+    use crate::region::{Region, SigningRegion};
     use crate::SigningService;
+    use aws_smithy_http::endpoint::Endpoint;
+    use http::Uri;
+
+    #[test]
+    fn static_endpoints_ignore_variants() {
+        let endpoint = Endpoint::immutable(Uri::from_static("http://localhost:8080"));
+        let resolved = endpoint
+            .resolve_endpoint_variant(
+                &Region::from_static("us-east-1"),
+                EndpointVariant::new().with_fips(true),
+            )
+            .expect("static endpoints support all variants");
+        let mut uri = Uri::from_static("/");
+        resolved.set_endpoint(&mut uri, None);
+        assert_eq!(uri, Uri::from_static("http://localhost:8080/"));
+    }
+
+    #[test]
+    fn endpoint_variant_display() {
+        assert!(EndpointVariant::new().is_default());
+        assert_eq!("FIPS", EndpointVariant::new().with_fips(true).to_string());
+        assert_eq!(
+            "FIPS and dual-stack",
+            EndpointVariant::new()
+                .with_fips(true)
+                .with_dual_stack(true)
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn parse_sts_regional_endpoints() {
+        assert_eq!(
+            StsRegionalEndpoints::Legacy,
+            " LEGACY".parse::<StsRegionalEndpoints>().unwrap()
+        );
+        assert_eq!(
+            StsRegionalEndpoints::Regional,
+            "regional".parse::<StsRegionalEndpoints>().unwrap()
+        );
+        assert_eq!(
+            StsRegionalEndpoints::default(),
+            StsRegionalEndpoints::Regional
+        );
+        assert!("global".parse::<StsRegionalEndpoints>().is_err());
+    }
 
     #[test]
     fn create_credentials_scope_from_strs() {
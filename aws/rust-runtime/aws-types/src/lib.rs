@@ -23,6 +23,7 @@ pub mod endpoint;
 pub mod os_shim_internal;
 pub mod region;
 pub mod sdk_config;
+pub mod service_config;
 
 pub use aws_smithy_client::http_connector;
 pub use credentials::Credentials;
@@ -6,15 +6,17 @@
 //! Abstractions for testing code that interacts with the operating system:
 //! - Reading environment variables
 //! - Reading from the file system
+//! - Determining the operating system
 
 use std::collections::HashMap;
 use std::env::VarError;
 use std::ffi::OsString;
 use std::fmt::Debug;
-use std::ops::Deref;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
-use std::time::{Duration, SystemTime};
+use std::time::SystemTime;
+
+use aws_smithy_async::time::SharedTimeSource;
 
 use crate::os_shim_internal::fs::Fake;
 use crate::os_shim_internal::time_source::Inner;
@@ -53,7 +55,7 @@ impl Fs {
     }
 
     pub fn from_raw_map(fs: HashMap<OsString, Vec<u8>>) -> Self {
-        Fs(fs::Inner::Fake(Arc::new(Fake::MapFs(fs))))
+        Fs(fs::Inner::Fake(Arc::new(Fake::MapFs(Mutex::new(fs)))))
     }
 
     pub fn from_map(data: HashMap<String, impl Into<Vec<u8>>>) -> Self {
@@ -131,6 +133,8 @@ impl Fs {
             Inner::Real => std::fs::read(path),
             Inner::Fake(fake) => match fake.as_ref() {
                 Fake::MapFs(fs) => fs
+                    .lock()
+                    .unwrap()
                     .get(path.as_os_str())
                     .cloned()
                     .ok_or_else(|| std::io::ErrorKind::NotFound.into()),
@@ -146,13 +150,52 @@ impl Fs {
             },
         }
     }
+
+    /// Write `contents` to a file, replacing it if it already exists
+    ///
+    /// On disk, the contents are written to a temporary file in the same directory, which is then
+    /// renamed over `path`. The file is therefore replaced atomically: if the write fails or the
+    /// process is interrupted, `path` keeps its previous contents rather than being truncated.
+    ///
+    /// In-memory file systems are updated in place, so later reads (including through clones of
+    /// this `Fs`) see the new contents.
+    pub async fn write(
+        &self,
+        path: impl AsRef<Path>,
+        contents: impl AsRef<[u8]>,
+    ) -> std::io::Result<()> {
+        use fs::Inner;
+        let path = path.as_ref();
+        match &self.0 {
+            Inner::Real => fs::write_atomic(path, contents.as_ref()),
+            Inner::Fake(fake) => match fake.as_ref() {
+                Fake::MapFs(fs) => {
+                    fs.lock()
+                        .unwrap()
+                        .insert(path.as_os_str().into(), contents.as_ref().to_vec());
+                    Ok(())
+                }
+                Fake::NamespacedFs {
+                    real_path,
+                    namespaced_to,
+                } => {
+                    let actual_path = path
+                        .strip_prefix(namespaced_to)
+                        .map_err(|_| std::io::Error::from(std::io::ErrorKind::NotFound))?;
+                    fs::write_atomic(&real_path.join(actual_path), contents.as_ref())
+                }
+            },
+        }
+    }
 }
 
 mod fs {
     use std::collections::HashMap;
     use std::ffi::OsString;
-    use std::path::PathBuf;
-    use std::sync::Arc;
+    use std::io::Write;
+    use std::path::{Path, PathBuf};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
 
     #[derive(Clone, Debug)]
     pub(super) enum Inner {
@@ -162,12 +205,39 @@ mod fs {
 
     #[derive(Debug)]
     pub(super) enum Fake {
-        MapFs(HashMap<OsString, Vec<u8>>),
+        MapFs(Mutex<HashMap<OsString, Vec<u8>>>),
         NamespacedFs {
             real_path: PathBuf,
             namespaced_to: PathBuf,
         },
     }
+
+    /// Writes `contents` to a temporary file next to `path`, and renames it to `path`
+    pub(super) fn write_atomic(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+        // Distinguishes the temporary files of concurrent writes from the same process
+        static WRITES: AtomicUsize = AtomicUsize::new(0);
+        let file_name = path
+            .file_name()
+            .ok_or_else(|| std::io::Error::from(std::io::ErrorKind::InvalidInput))?;
+        let mut temp_name = OsString::from(".");
+        temp_name.push(file_name);
+        temp_name.push(format!(
+            ".{}.{}.tmp",
+            std::process::id(),
+            WRITES.fetch_add(1, Ordering::Relaxed)
+        ));
+        let temp_path = path.with_file_name(temp_name);
+        let result = std::fs::File::create(&temp_path)
+            .and_then(|mut file| {
+                file.write_all(contents)?;
+                file.sync_all()
+            })
+            .and_then(|_| std::fs::rename(&temp_path, path));
+        if result.is_err() {
+            let _ = std::fs::remove_file(&temp_path);
+        }
+        result
+    }
 }
 
 /// Environment variable abstraction
@@ -180,7 +250,7 @@ mod fs {
 /// - Faked process environments are wrapped in an internal Arc
 /// - Real process environments are pointer-sized
 #[derive(Clone, Debug)]
-pub struct Env(env::Inner);
+pub struct Env(env::Inner, Os);
 
 impl Default for Env {
     fn default() -> Self {
@@ -197,6 +267,24 @@ impl Env {
         }
     }
 
+    /// Returns all environment variables whose names begin with `prefix`
+    ///
+    /// Variables whose names or values are not valid unicode are skipped.
+    pub fn vars_with_prefix(&self, prefix: &str) -> Vec<(String, String)> {
+        use env::Inner;
+        match &self.0 {
+            Inner::Real => std::env::vars_os()
+                .filter_map(|(k, v)| Some((k.into_string().ok()?, v.into_string().ok()?)))
+                .filter(|(k, _)| k.starts_with(prefix))
+                .collect(),
+            Inner::Fake(map) => map
+                .iter()
+                .filter(|(k, _)| k.starts_with(prefix))
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect(),
+        }
+    }
+
     /// Create a fake process environment from a slice of tuples.
     ///
     /// # Examples
@@ -220,13 +308,56 @@ impl Env {
     ///
     /// Calls will be delegated to [`std::env::var`](std::env::var).
     pub fn real() -> Self {
-        Self(env::Inner::Real)
+        Self(env::Inner::Real, Os::real())
+    }
+
+    /// The operating system that this environment belongs to
+    ///
+    /// This determines which environment variables are used to find the home directory. Unless set
+    /// with [`Env::with_os`], this is the operating system the program is running on for the real
+    /// environment, and [`Os::NotWindows`] for fake environments so that tests behave the same on
+    /// every platform.
+    pub fn os(&self) -> Os {
+        self.1
+    }
+
+    /// Treat this environment as belonging to `os`
+    ///
+    /// # Examples
+    /// ```rust
+    /// use aws_types::os_shim_internal::{Env, Os};
+    /// let mock_env = Env::from_slice(&[("USERPROFILE", "C:\\Users\\myname")]).with_os(Os::Windows);
+    /// assert_eq!(mock_env.os(), Os::Windows);
+    /// ```
+    pub fn with_os(mut self, os: Os) -> Self {
+        self.1 = os;
+        self
     }
 }
 
 impl From<HashMap<String, String>> for Env {
     fn from(hash_map: HashMap<String, String>) -> Self {
-        Self(env::Inner::Fake(Arc::new(hash_map)))
+        Self(env::Inner::Fake(Arc::new(hash_map)), Os::NotWindows)
+    }
+}
+
+/// Operating system family, as far as loading configuration is concerned
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Os {
+    /// Windows
+    Windows,
+    /// Any operating system other than Windows
+    NotWindows,
+}
+
+impl Os {
+    /// The operating system the program is running on
+    pub fn real() -> Self {
+        match std::env::consts::OS {
+            "windows" => Os::Windows,
+            _ => Os::NotWindows,
+        }
     }
 }
 
@@ -253,10 +384,16 @@ impl TimeSource {
         TimeSource(time_source::Inner::Manual(time_source.clone()))
     }
 
+    /// Creates a time source backed by any [`aws_smithy_async::time::TimeSource`] implementation.
+    pub fn shared(time_source: SharedTimeSource) -> Self {
+        TimeSource(time_source::Inner::Shared(time_source))
+    }
+
     pub fn now(&self) -> SystemTime {
         match &self.0 {
             Inner::Real => SystemTime::now(),
             Inner::Manual(manual) => manual.now(),
+            Inner::Shared(shared) => aws_smithy_async::time::TimeSource::now(shared),
         }
     }
 }
@@ -287,61 +424,29 @@ impl Default for TimeSource {
 /// let client = Client::with_timesource(TimeSource::manual(&time));
 /// time.advance(Duration::from_secs(100));
 /// ```
-#[derive(Clone, Debug)]
-pub struct ManualTimeSource {
-    queries: Arc<Mutex<Vec<SystemTime>>>,
-    now: Arc<Mutex<SystemTime>>,
-}
-
-impl ManualTimeSource {
-    pub fn new(start_time: SystemTime) -> Self {
-        Self {
-            queries: Default::default(),
-            now: Arc::new(Mutex::new(start_time)),
-        }
-    }
-
-    pub fn set_time(&mut self, time: SystemTime) {
-        let mut now = self.now.lock().unwrap();
-        *now = time;
-    }
-
-    pub fn advance(&mut self, delta: Duration) {
-        let mut now = self.now.lock().unwrap();
-        *now += delta;
-    }
-
-    pub fn queries(&self) -> impl Deref<Target = Vec<SystemTime>> + '_ {
-        self.queries.lock().unwrap()
-    }
-
-    pub fn now(&self) -> SystemTime {
-        let ts = *self.now.lock().unwrap();
-        self.queries.lock().unwrap().push(ts);
-        ts
-    }
-}
+pub use aws_smithy_async::test_util::ManualTimeSource;
 
 mod time_source {
     use crate::os_shim_internal::ManualTimeSource;
+    use aws_smithy_async::time::SharedTimeSource;
 
-    // in the future, if needed we can add a time source trait, however, the manual time source
-    // should cover most test use cases.
     #[derive(Debug, Clone)]
     pub(super) enum Inner {
         Real,
         Manual(ManualTimeSource),
+        Shared(SharedTimeSource),
     }
 }
 
 #[cfg(test)]
 mod test {
     use std::env::VarError;
-    use std::time::{Duration, UNIX_EPOCH};
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
+    use aws_smithy_async::time::SharedTimeSource;
     use futures_util::FutureExt;
 
-    use crate::os_shim_internal::{Env, Fs, ManualTimeSource, TimeSource};
+    use crate::os_shim_internal::{Env, Fs, ManualTimeSource, Os, TimeSource};
 
     #[test]
     fn env_works() {
@@ -353,6 +458,30 @@ mod test {
         )
     }
 
+    #[test]
+    fn env_os_can_be_faked() {
+        assert_eq!(Env::real().os(), Os::real());
+        assert_eq!(Env::from_slice(&[]).os(), Os::NotWindows);
+        let env = Env::from_slice(&[("FOO", "BAR")]).with_os(Os::Windows);
+        assert_eq!(env.os(), Os::Windows);
+        assert_eq!(env.get("FOO").unwrap(), "BAR");
+    }
+
+    #[test]
+    fn env_vars_with_prefix() {
+        let env = Env::from_slice(&[
+            ("AWS_ENDPOINT_URL", "http://global"),
+            ("AWS_ENDPOINT_URL_S3", "http://s3"),
+            ("OTHER", "value"),
+        ]);
+        let mut vars = env.vars_with_prefix("AWS_ENDPOINT_URL_");
+        vars.sort();
+        assert_eq!(
+            vars,
+            vec![("AWS_ENDPOINT_URL_S3".to_string(), "http://s3".to_string())]
+        );
+    }
+
     #[test]
     fn fs_works() {
         let fs = Fs::from_test_dir(".", "/users/test-data");
@@ -369,6 +498,63 @@ mod test {
             .expect_err("file doesnt exists");
     }
 
+    #[test]
+    fn in_memory_fs_can_be_written() {
+        let fs = Fs::from_slice(&[("config", "old")]);
+        let clone = fs.clone();
+        fs.write("config", "new")
+            .now_or_never()
+            .expect("future should not poll")
+            .expect("write succeeds");
+        fs.write("credentials", "created")
+            .now_or_never()
+            .expect("future should not poll")
+            .expect("write succeeds");
+        let read = |path| {
+            clone
+                .read_to_end(path)
+                .now_or_never()
+                .expect("future should not poll")
+                .expect("file exists")
+        };
+        assert_eq!(b"new".to_vec(), read("config"));
+        assert_eq!(b"created".to_vec(), read("credentials"));
+    }
+
+    #[test]
+    fn real_fs_writes_replace_files_atomically() {
+        let dir = std::env::temp_dir().join(format!("aws-types-fs-write-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let fs = Fs::from_test_dir(&dir, "/home/.aws");
+        let write = |contents| {
+            fs.write("/home/.aws/token.json", contents)
+                .now_or_never()
+                .expect("future should not poll")
+        };
+        write("old").expect("write succeeds");
+        write("new").expect("write succeeds");
+        assert_eq!(
+            b"new".to_vec(),
+            std::fs::read(dir.join("token.json")).unwrap()
+        );
+        // the temporary file was renamed to the destination
+        let files: Vec<_> = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(vec![std::ffi::OsString::from("token.json")], files);
+
+        // a failed write leaves the previous contents and no temporary file
+        std::fs::create_dir(dir.join("sso")).unwrap();
+        assert!(fs
+            .write("/home/.aws/sso", "replaces a directory")
+            .now_or_never()
+            .expect("future should not poll")
+            .is_err());
+        assert_eq!(2, std::fs::read_dir(&dir).unwrap().count());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
     #[test]
     fn ts_works() {
         let real = TimeSource::real();
@@ -381,4 +567,17 @@ mod test {
         manual.advance(Duration::from_secs(10));
         assert_eq!(ts.now(), UNIX_EPOCH + Duration::from_secs(10));
     }
+
+    #[test]
+    fn shared_ts_works() {
+        #[derive(Debug)]
+        struct FixedTime;
+        impl aws_smithy_async::time::TimeSource for FixedTime {
+            fn now(&self) -> SystemTime {
+                UNIX_EPOCH + Duration::from_secs(1234)
+            }
+        }
+        let ts = TimeSource::shared(SharedTimeSource::new(FixedTime));
+        assert_eq!(ts.now(), UNIX_EPOCH + Duration::from_secs(1234));
+    }
 }
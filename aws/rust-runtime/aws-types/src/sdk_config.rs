@@ -9,17 +9,21 @@
 //!
 //! This module contains an shared configuration representation that is agnostic from a specific service.
 
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use aws_smithy_async::rt::sleep::AsyncSleep;
 use aws_smithy_client::http_connector::HttpConnector;
+use aws_smithy_client::retry::RetryPartitions;
 use aws_smithy_types::retry::RetryConfig;
 use aws_smithy_types::timeout;
+use http::Uri;
 
 use crate::app_name::AppName;
 use crate::credentials::SharedCredentialsProvider;
-use crate::endpoint::ResolveAwsEndpoint;
+use crate::endpoint::{ResolveAwsEndpoint, StsRegionalEndpoints};
 use crate::region::Region;
+use crate::service_config::ServiceConfig;
 
 /// AWS Shared Configuration
 #[derive(Debug, Clone)]
@@ -28,7 +32,14 @@ pub struct SdkConfig {
     credentials_provider: Option<SharedCredentialsProvider>,
     region: Option<Region>,
     endpoint_resolver: Option<Arc<dyn ResolveAwsEndpoint>>,
+    endpoint_url: Option<Uri>,
+    service_endpoint_urls: HashMap<String, Uri>,
+    service_configs: HashMap<String, ServiceConfig>,
+    use_fips: Option<bool>,
+    use_dual_stack: Option<bool>,
+    sts_regional_endpoints: Option<StsRegionalEndpoints>,
     retry_config: Option<RetryConfig>,
+    retry_partitions: RetryPartitions,
     sleep_impl: Option<Arc<dyn AsyncSleep>>,
     timeout_config: Option<timeout::Config>,
     http_connector: Option<HttpConnector>,
@@ -41,7 +52,14 @@ pub struct Builder {
     credentials_provider: Option<SharedCredentialsProvider>,
     region: Option<Region>,
     endpoint_resolver: Option<Arc<dyn ResolveAwsEndpoint>>,
+    endpoint_url: Option<Uri>,
+    service_endpoint_urls: HashMap<String, Uri>,
+    service_configs: HashMap<String, ServiceConfig>,
+    use_fips: Option<bool>,
+    use_dual_stack: Option<bool>,
+    sts_regional_endpoints: Option<StsRegionalEndpoints>,
     retry_config: Option<RetryConfig>,
+    retry_partitions: Option<RetryPartitions>,
     sleep_impl: Option<Arc<dyn AsyncSleep>>,
     timeout_config: Option<timeout::Config>,
     http_connector: Option<HttpConnector>,
@@ -125,6 +143,181 @@ impl Builder {
         self
     }
 
+    /// Set the endpoint URL to use for all services
+    ///
+    /// The endpoint URL is only used by a service client when no endpoint resolver has been set.
+    ///
+    /// # Examples
+    /// ```
+    /// use aws_types::SdkConfig;
+    /// use http::Uri;
+    /// let config = SdkConfig::builder()
+    ///     .endpoint_url(Uri::from_static("http://localhost:4566"))
+    ///     .build();
+    /// ```
+    pub fn endpoint_url(mut self, endpoint_url: Uri) -> Self {
+        self.set_endpoint_url(Some(endpoint_url));
+        self
+    }
+
+    /// Set the endpoint URL to use for all services
+    ///
+    /// The endpoint URL is only used by a service client when no endpoint resolver has been set.
+    pub fn set_endpoint_url(&mut self, endpoint_url: Option<Uri>) -> &mut Self {
+        self.endpoint_url = endpoint_url;
+        self
+    }
+
+    /// Set the endpoint URL to use for a specific service
+    ///
+    /// `service_id` is the service's SDK ID, e.g. `DynamoDB` or `SSO OIDC`. It is matched
+    /// case-insensitively with spaces and dashes treated as underscores, so `SSO_OIDC` is equivalent
+    /// to `SSO OIDC`. Service-specific endpoint URLs take precedence over
+    /// [`endpoint_url`](Self::endpoint_url).
+    ///
+    /// # Examples
+    /// ```
+    /// use aws_types::SdkConfig;
+    /// use http::Uri;
+    /// let config = SdkConfig::builder()
+    ///     .service_endpoint_url("DynamoDB", Uri::from_static("http://localhost:8000"))
+    ///     .build();
+    /// assert!(config.service_endpoint_url("DynamoDB").is_some());
+    /// assert!(config.service_endpoint_url("S3").is_none());
+    /// ```
+    pub fn service_endpoint_url(mut self, service_id: &str, endpoint_url: Uri) -> Self {
+        self.set_service_endpoint_url(service_id, Some(endpoint_url));
+        self
+    }
+
+    /// Set or clear the endpoint URL to use for a specific service
+    ///
+    /// See [`service_endpoint_url`](Self::service_endpoint_url) for details.
+    pub fn set_service_endpoint_url(
+        &mut self,
+        service_id: &str,
+        endpoint_url: Option<Uri>,
+    ) -> &mut Self {
+        let key = normalize_service_id(service_id);
+        match endpoint_url {
+            Some(endpoint_url) => self.service_endpoint_urls.insert(key, endpoint_url),
+            None => self.service_endpoint_urls.remove(&key),
+        };
+        self
+    }
+
+    /// Set configuration that only applies to a specific service
+    ///
+    /// `service_id` is the service's SDK ID, matched the same way as for
+    /// [`service_endpoint_url`](Self::service_endpoint_url). When a client for that service is
+    /// created from this config, values in the [`ServiceConfig`] take precedence over the shared
+    /// ones.
+    ///
+    /// # Examples
+    /// ```
+    /// use aws_smithy_types::retry::RetryConfig;
+    /// use aws_types::service_config::ServiceConfig;
+    /// use aws_types::SdkConfig;
+    /// let config = SdkConfig::builder()
+    ///     .retry_config(RetryConfig::new().with_max_attempts(3))
+    ///     .service_config(
+    ///         "DynamoDB",
+    ///         ServiceConfig::new().with(RetryConfig::new().with_max_attempts(10)),
+    ///     )
+    ///     .build();
+    /// assert!(config.service_config("DynamoDB").is_some());
+    /// assert!(config.service_config("S3").is_none());
+    /// ```
+    pub fn service_config(mut self, service_id: &str, service_config: ServiceConfig) -> Self {
+        self.set_service_config(service_id, Some(service_config));
+        self
+    }
+
+    /// Set or clear the configuration that only applies to a specific service
+    ///
+    /// See [`service_config`](Self::service_config) for details.
+    pub fn set_service_config(
+        &mut self,
+        service_id: &str,
+        service_config: Option<ServiceConfig>,
+    ) -> &mut Self {
+        let key = normalize_service_id(service_id);
+        match service_config {
+            Some(service_config) => self.service_configs.insert(key, service_config),
+            None => self.service_configs.remove(&key),
+        };
+        self
+    }
+
+    /// When true, send requests to FIPS-compliant endpoints
+    ///
+    /// Endpoint resolution fails if the service has no FIPS-compliant endpoint in the configured region.
+    ///
+    /// # Examples
+    /// ```
+    /// use aws_types::SdkConfig;
+    /// let config = SdkConfig::builder().use_fips(true).build();
+    /// assert_eq!(config.use_fips(), Some(true));
+    /// ```
+    pub fn use_fips(mut self, use_fips: bool) -> Self {
+        self.set_use_fips(Some(use_fips));
+        self
+    }
+
+    /// When true, send requests to FIPS-compliant endpoints
+    pub fn set_use_fips(&mut self, use_fips: Option<bool>) -> &mut Self {
+        self.use_fips = use_fips;
+        self
+    }
+
+    /// When true, send requests to dual-stack (IPv4 and IPv6) endpoints
+    ///
+    /// Endpoint resolution fails if the service has no dual-stack endpoint in the configured region.
+    ///
+    /// # Examples
+    /// ```
+    /// use aws_types::SdkConfig;
+    /// let config = SdkConfig::builder().use_dual_stack(true).build();
+    /// assert_eq!(config.use_dual_stack(), Some(true));
+    /// ```
+    pub fn use_dual_stack(mut self, use_dual_stack: bool) -> Self {
+        self.set_use_dual_stack(Some(use_dual_stack));
+        self
+    }
+
+    /// When true, send requests to dual-stack (IPv4 and IPv6) endpoints
+    pub fn set_use_dual_stack(&mut self, use_dual_stack: Option<bool>) -> &mut Self {
+        self.use_dual_stack = use_dual_stack;
+        self
+    }
+
+    /// Set whether STS requests in the legacy global regions use the global STS endpoint
+    ///
+    /// This only affects STS clients. See [`StsRegionalEndpoints`] for details.
+    ///
+    /// # Examples
+    /// ```
+    /// use aws_types::endpoint::StsRegionalEndpoints;
+    /// use aws_types::SdkConfig;
+    /// let config = SdkConfig::builder()
+    ///     .sts_regional_endpoints(StsRegionalEndpoints::Legacy)
+    ///     .build();
+    /// assert_eq!(config.sts_regional_endpoints(), Some(StsRegionalEndpoints::Legacy));
+    /// ```
+    pub fn sts_regional_endpoints(mut self, sts_regional_endpoints: StsRegionalEndpoints) -> Self {
+        self.set_sts_regional_endpoints(Some(sts_regional_endpoints));
+        self
+    }
+
+    /// Set whether STS requests in the legacy global regions use the global STS endpoint
+    pub fn set_sts_regional_endpoints(
+        &mut self,
+        sts_regional_endpoints: Option<StsRegionalEndpoints>,
+    ) -> &mut Self {
+        self.sts_regional_endpoints = sts_regional_endpoints;
+        self
+    }
+
     /// Set the retry_config for the builder
     ///
     /// # Examples
@@ -161,6 +354,33 @@ impl Builder {
         self
     }
 
+    /// Set the retry quotas that clients created from this config will draw from
+    ///
+    /// By default, each [`SdkConfig`] gets its own [`RetryPartitions`], which are shared by every
+    /// client created from it. Set this to share retry quotas between several `SdkConfig`s.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use aws_types::SdkConfig;
+    /// use aws_smithy_client::retry::RetryPartitions;
+    ///
+    /// let partitions = RetryPartitions::new();
+    /// let first = SdkConfig::builder().retry_partitions(partitions.clone()).build();
+    /// let second = SdkConfig::builder().retry_partitions(partitions).build();
+    /// ```
+    pub fn retry_partitions(mut self, retry_partitions: RetryPartitions) -> Self {
+        self.set_retry_partitions(Some(retry_partitions));
+        self
+    }
+
+    /// Set the retry quotas that clients created from this config will draw from
+    ///
+    /// When unset, a new [`RetryPartitions`] is created when the config is built.
+    pub fn set_retry_partitions(&mut self, retry_partitions: Option<RetryPartitions>) -> &mut Self {
+        self.retry_partitions = retry_partitions;
+        self
+    }
+
     /// Set the [`timeout::Config`](aws_smithy_types::timeout::Config) for the builder
     ///
     /// # Examples
@@ -356,7 +576,14 @@ impl Builder {
             credentials_provider: self.credentials_provider,
             region: self.region,
             endpoint_resolver: self.endpoint_resolver,
+            endpoint_url: self.endpoint_url,
+            service_endpoint_urls: self.service_endpoint_urls,
+            service_configs: self.service_configs,
+            use_fips: self.use_fips,
+            use_dual_stack: self.use_dual_stack,
+            sts_regional_endpoints: self.sts_regional_endpoints,
             retry_config: self.retry_config,
+            retry_partitions: self.retry_partitions.unwrap_or_default(),
             sleep_impl: self.sleep_impl,
             timeout_config: self.timeout_config,
             http_connector: self.http_connector,
@@ -375,11 +602,51 @@ impl SdkConfig {
         self.endpoint_resolver.clone()
     }
 
+    /// Configured endpoint URL for all services
+    pub fn endpoint_url(&self) -> Option<&Uri> {
+        self.endpoint_url.as_ref()
+    }
+
+    /// Configured endpoint URL for the service with the given SDK ID
+    ///
+    /// This returns the service-specific endpoint URL if one was set, otherwise the endpoint URL
+    /// configured for all services.
+    pub fn service_endpoint_url(&self, service_id: &str) -> Option<&Uri> {
+        self.service_endpoint_urls
+            .get(&normalize_service_id(service_id))
+            .or(self.endpoint_url.as_ref())
+    }
+
+    /// Configuration that only applies to the service with the given SDK ID
+    pub fn service_config(&self, service_id: &str) -> Option<&ServiceConfig> {
+        self.service_configs.get(&normalize_service_id(service_id))
+    }
+
+    /// Configured FIPS setting
+    pub fn use_fips(&self) -> Option<bool> {
+        self.use_fips
+    }
+
+    /// Configured dual-stack setting
+    pub fn use_dual_stack(&self) -> Option<bool> {
+        self.use_dual_stack
+    }
+
+    /// Configured STS regional endpoints setting
+    pub fn sts_regional_endpoints(&self) -> Option<StsRegionalEndpoints> {
+        self.sts_regional_endpoints
+    }
+
     /// Configured retry config
     pub fn retry_config(&self) -> Option<&RetryConfig> {
         self.retry_config.as_ref()
     }
 
+    /// Retry quotas shared by clients created from this config
+    pub fn retry_partitions(&self) -> &RetryPartitions {
+        &self.retry_partitions
+    }
+
     /// Configured timeout config
     pub fn timeout_config(&self) -> Option<&timeout::Config> {
         self.timeout_config.as_ref()
@@ -411,3 +678,15 @@ impl SdkConfig {
         Builder::default()
     }
 }
+
+/// Normalize a service SDK ID into the form used by `AWS_ENDPOINT_URL_<SERVICE>`, e.g. `SSO OIDC` => `SSO_OIDC`
+fn normalize_service_id(service_id: &str) -> String {
+    service_id
+        .trim()
+        .chars()
+        .map(|c| match c {
+            ' ' | '-' => '_',
+            c => c.to_ascii_uppercase(),
+        })
+        .collect()
+}
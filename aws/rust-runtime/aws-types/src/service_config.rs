@@ -0,0 +1,155 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Service-specific overrides for shared configuration
+//!
+//! A [`ServiceConfig`] holds configuration that only applies to a single service. It is attached
+//! to an [`SdkConfig`](crate::SdkConfig) with
+//! [`service_config`](crate::sdk_config::Builder::service_config) and consulted when a client for
+//! that service is created from the shared config.
+//!
+//! Values are stored by type. Service clients read the `Region`, `RetryConfig`, and
+//! `timeout::Config` from a `ServiceConfig` in preference to the shared values. Settings that only
+//! exist for one service, such as S3's path-style addressing, can be applied to the service's
+//! config builder with [`ServiceConfig::customize`]:
+//!
+//! ```rust,ignore
+//! use aws_types::service_config::ServiceConfig;
+//! use aws_types::SdkConfig;
+//! let config = SdkConfig::builder()
+//!     .service_config(
+//!         "S3",
+//!         ServiceConfig::new()
+//!             .customize(|builder: aws_sdk_s3::config::Builder| builder.force_path_style(true)),
+//!     )
+//!     .build();
+//! ```
+
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+/// Typed configuration overrides for a single service
+#[derive(Clone, Default)]
+pub struct ServiceConfig {
+    values: HashMap<TypeId, NamedValue>,
+}
+
+#[derive(Clone)]
+struct NamedValue {
+    type_name: &'static str,
+    value: Arc<dyn Any + Send + Sync>,
+}
+
+/// A function that customizes a config builder of type `B`
+struct Customize<B>(Arc<dyn Fn(B) -> B + Send + Sync>);
+
+impl ServiceConfig {
+    /// Create an empty `ServiceConfig`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set `value`, replacing any existing value of the same type
+    ///
+    /// # Examples
+    /// ```rust
+    /// use aws_smithy_types::retry::RetryConfig;
+    /// use aws_types::service_config::ServiceConfig;
+    /// let config = ServiceConfig::new().with(RetryConfig::new().with_max_attempts(5));
+    /// assert_eq!(config.get::<RetryConfig>().unwrap().max_attempts(), 5);
+    /// ```
+    pub fn with<T: Any + Send + Sync>(mut self, value: T) -> Self {
+        self.insert(value);
+        self
+    }
+
+    /// Set `value`, replacing any existing value of the same type
+    pub fn insert<T: Any + Send + Sync>(&mut self, value: T) {
+        self.values.insert(
+            TypeId::of::<T>(),
+            NamedValue {
+                type_name: std::any::type_name::<T>(),
+                value: Arc::new(value),
+            },
+        );
+    }
+
+    /// Returns the value of type `T`, if one was set
+    pub fn get<T: Any + Send + Sync>(&self) -> Option<&T> {
+        self.values
+            .get(&TypeId::of::<T>())
+            .and_then(|named| named.value.downcast_ref())
+    }
+
+    /// Customize the service's config builder, of type `B`, when a client is created
+    ///
+    /// This runs after all other shared and service-specific configuration has been applied to the
+    /// builder, so anything it sets takes precedence. Only one customization can be set per
+    /// builder type; setting another replaces it.
+    pub fn customize<B: 'static>(self, customize: impl Fn(B) -> B + Send + Sync + 'static) -> Self {
+        self.with(Customize::<B>(Arc::new(customize)))
+    }
+
+    /// Apply the customization set for builders of type `B` with [`customize`](Self::customize)
+    ///
+    /// Returns `builder` unchanged if no customization was set.
+    pub fn apply<B: 'static>(&self, builder: B) -> B {
+        match self.get::<Customize<B>>() {
+            Some(customize) => (customize.0)(builder),
+            None => builder,
+        }
+    }
+}
+
+impl fmt::Debug for ServiceConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut type_names: Vec<_> = self.values.values().map(|v| v.type_name).collect();
+        type_names.sort_unstable();
+        f.debug_struct("ServiceConfig")
+            .field("types", &type_names)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::region::Region;
+    use crate::service_config::ServiceConfig;
+
+    #[derive(Debug, Default, PartialEq)]
+    struct Builder {
+        force_path_style: bool,
+    }
+
+    #[test]
+    fn values_are_stored_by_type() {
+        let config = ServiceConfig::new()
+            .with(Region::new("us-east-1"))
+            .with(Region::new("us-west-2"));
+        assert_eq!(config.get::<Region>(), Some(&Region::new("us-west-2")));
+        assert_eq!(config.get::<String>(), None);
+    }
+
+    #[test]
+    fn customizations_apply_to_their_builder_type() {
+        let config = ServiceConfig::new().customize(|mut builder: Builder| {
+            builder.force_path_style = true;
+            builder
+        });
+        assert_eq!(
+            config.apply(Builder::default()),
+            Builder {
+                force_path_style: true
+            }
+        );
+        assert_eq!(config.apply(5_u8), 5);
+        assert_eq!(
+            ServiceConfig::new().apply(Builder::default()),
+            Builder::default()
+        );
+    }
+}
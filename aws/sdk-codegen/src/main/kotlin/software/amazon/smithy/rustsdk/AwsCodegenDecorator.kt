@@ -8,6 +8,7 @@ package software.amazon.smithy.rustsdk
 import software.amazon.smithy.rust.codegen.smithy.customizations.DocsRsMetadataDecorator
 import software.amazon.smithy.rust.codegen.smithy.customizations.DocsRsMetadataSettings
 import software.amazon.smithy.rust.codegen.smithy.customizations.RetryConfigDecorator
+import software.amazon.smithy.rust.codegen.smithy.customizations.SerdeDecorator
 import software.amazon.smithy.rust.codegen.smithy.customizations.SleepImplDecorator
 import software.amazon.smithy.rust.codegen.smithy.customizations.TimeoutConfigDecorator
 import software.amazon.smithy.rust.codegen.smithy.customize.CombinedCodegenDecorator
@@ -17,6 +18,7 @@ import software.amazon.smithy.rustsdk.customize.ec2.Ec2Decorator
 import software.amazon.smithy.rustsdk.customize.glacier.GlacierDecorator
 import software.amazon.smithy.rustsdk.customize.route53.Route53Decorator
 import software.amazon.smithy.rustsdk.customize.s3.S3Decorator
+import software.amazon.smithy.rustsdk.customize.sts.StsDecorator
 
 val DECORATORS = listOf(
     // General AWS Decorators
@@ -36,6 +38,7 @@ val DECORATORS = listOf(
 
     // Smithy specific decorators
     RetryConfigDecorator(),
+    SerdeDecorator(),
     SleepImplDecorator(),
     TimeoutConfigDecorator(),
 
@@ -46,6 +49,7 @@ val DECORATORS = listOf(
     Ec2Decorator(),
     GlacierDecorator(),
     Route53Decorator(),
+    StsDecorator(),
 
     // Only build docs-rs for linux to reduce load on docs.rs
     DocsRsMetadataDecorator(DocsRsMetadataSettings(targets = listOf("x86_64-unknown-linux-gnu"), allFeatures = true))
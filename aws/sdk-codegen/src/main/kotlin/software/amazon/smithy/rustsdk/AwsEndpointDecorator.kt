@@ -36,6 +36,10 @@ import software.amazon.smithy.rust.codegen.util.dq
 import software.amazon.smithy.rust.codegen.util.expectTrait
 import software.amazon.smithy.rust.codegen.util.orNull
 
+private const val FIPS = "fips"
+private const val DUALSTACK = "dualstack"
+private val SUPPORTED_VARIANT_TAGS = setOf(FIPS, DUALSTACK)
+
 class AwsEndpointDecorator : RustCodegenDecorator {
     override val name: String = "AwsEndpoint"
     override val order: Byte = 0
@@ -76,12 +80,24 @@ class EndpointConfigCustomization(private val codegenContext: CodegenContext, pr
     override fun section(section: ServiceConfig): Writable = writable {
         when (section) {
             is ServiceConfig.ConfigStruct -> rust(
-                "pub (crate) endpoint_resolver: ::std::sync::Arc<dyn #T>,",
+                """
+                pub (crate) endpoint_resolver: ::std::sync::Arc<dyn #T>,
+                pub (crate) use_fips: Option<bool>,
+                pub (crate) use_dual_stack: Option<bool>,
+                pub (crate) use_account_id_endpoint: Option<bool>,
+                """,
                 resolveAwsEndpoint
             )
             is ServiceConfig.ConfigImpl -> emptySection
-            is ServiceConfig.BuilderStruct ->
-                rust("endpoint_resolver: Option<::std::sync::Arc<dyn #T>>,", resolveAwsEndpoint)
+            is ServiceConfig.BuilderStruct -> rust(
+                """
+                endpoint_resolver: Option<::std::sync::Arc<dyn #T>>,
+                use_fips: Option<bool>,
+                use_dual_stack: Option<bool>,
+                use_account_id_endpoint: Option<bool>,
+                """,
+                resolveAwsEndpoint
+            )
             ServiceConfig.BuilderImpl ->
                 rustTemplate(
                     """
@@ -111,6 +127,52 @@ class EndpointConfigCustomization(private val codegenContext: CodegenContext, pr
                         self.endpoint_resolver = endpoint_resolver;
                         self
                     }
+
+                    /// When true, send requests to a FIPS-compliant endpoint.
+                    ///
+                    /// If the service does not have a FIPS endpoint in the configured region, requests will fail
+                    /// rather than falling back to the standard endpoint.
+                    pub fn use_fips(mut self, use_fips: bool) -> Self {
+                        self.use_fips = Some(use_fips);
+                        self
+                    }
+
+                    /// When true, send requests to a FIPS-compliant endpoint.
+                    pub fn set_use_fips(&mut self, use_fips: Option<bool>) -> &mut Self {
+                        self.use_fips = use_fips;
+                        self
+                    }
+
+                    /// When true, send requests to an endpoint that supports both IPv4 and IPv6.
+                    ///
+                    /// If the service does not have a dual-stack endpoint in the configured region, requests will fail
+                    /// rather than falling back to the standard endpoint.
+                    pub fn use_dual_stack(mut self, use_dual_stack: bool) -> Self {
+                        self.use_dual_stack = Some(use_dual_stack);
+                        self
+                    }
+
+                    /// When true, send requests to an endpoint that supports both IPv4 and IPv6.
+                    pub fn set_use_dual_stack(&mut self, use_dual_stack: Option<bool>) -> &mut Self {
+                        self.use_dual_stack = use_dual_stack;
+                        self
+                    }
+
+                    /// When true, send requests to an endpoint specific to the account ID of the credentials
+                    /// (e.g. `123456789012.service.us-east-1.amazonaws.com`).
+                    ///
+                    /// Only enable this for services that route requests by account ID. When the credentials
+                    /// don't carry an account ID, requests are sent to the regular endpoint.
+                    pub fn use_account_id_endpoint(mut self, use_account_id_endpoint: bool) -> Self {
+                        self.use_account_id_endpoint = Some(use_account_id_endpoint);
+                        self
+                    }
+
+                    /// When true, send requests to an endpoint specific to the account ID of the credentials.
+                    pub fn set_use_account_id_endpoint(&mut self, use_account_id_endpoint: Option<bool>) -> &mut Self {
+                        self.use_account_id_endpoint = use_account_id_endpoint;
+                        self
+                    }
                     """,
                     "ResolveAwsEndpoint" to resolveAwsEndpoint,
                     "aws_types" to awsTypes(runtimeConfig).asType()
@@ -122,6 +184,9 @@ class EndpointConfigCustomization(private val codegenContext: CodegenContext, pr
                     endpoint_resolver: self.endpoint_resolver.unwrap_or_else(||
                         ::std::sync::Arc::new(#T())
                     ),
+                    use_fips: self.use_fips,
+                    use_dual_stack: self.use_dual_stack,
+                    use_account_id_endpoint: self.use_account_id_endpoint,
                     """,
                     resolverGenerator.resolver(),
                 )
@@ -140,8 +205,20 @@ class EndpointResolverFeature(private val runtimeConfig: RuntimeConfig, private
                 rust(
                     """
                     #T::set_endpoint_resolver(&mut ${section.request}.properties_mut(), ${section.config}.endpoint_resolver.clone());
+                    #T::set_endpoint_variant(
+                        &mut ${section.request}.properties_mut(),
+                        #T::EndpointVariant::new()
+                            .with_fips(${section.config}.use_fips.unwrap_or_default())
+                            .with_dual_stack(${section.config}.use_dual_stack.unwrap_or_default()),
+                    );
+                    if ${section.config}.use_account_id_endpoint.unwrap_or_default() {
+                        #T::account_id::set_account_id_routing(&mut ${section.request}.properties_mut());
+                    }
                     """,
-                    runtimeConfig.awsEndpoint().asType()
+                    runtimeConfig.awsEndpoint().asType(),
+                    runtimeConfig.awsEndpoint().asType(),
+                    runtimeConfig.awsEndpoint().asType(),
+                    runtimeConfig.awsEndpoint().asType(),
                 )
             }
             else -> emptySection
@@ -177,6 +254,7 @@ class EndpointResolverGenerator(codegenContext: CodegenContext, private val endp
             "Protocol" to awsEndpoint.member("partition::endpoint::Protocol"),
             "SignatureVersion" to awsEndpoint.member("partition::endpoint::SignatureVersion"),
             "PartitionResolver" to awsEndpoint.member("PartitionResolver"),
+            "EndpointVariant" to awsEndpoint.member("EndpointVariant"),
             "ResolveAwsEndpoint" to awsEndpoint.member("ResolveAwsEndpoint"),
             "SigningService" to awsTypes.member("SigningService"),
             "SigningRegion" to awsTypes.member("region::SigningRegion")
@@ -257,9 +335,26 @@ class EndpointResolverGenerator(codegenContext: CodegenContext, private val endp
                 }
             }
         }
+        partition.defaultVariants.forEach { (tags, endpoint) ->
+            withBlockTemplate(".default_variant(${variant(tags)}, ", ")", *codegenScope) {
+                with(endpoint) {
+                    render()
+                }
+            }
+        }
+        partition.endpointVariants.forEach { (region, tags, endpoint) ->
+            withBlockTemplate(".endpoint_variant(${region.dq()}, ${variant(tags)}, ", ")", *codegenScope) {
+                with(endpoint) {
+                    render()
+                }
+            }
+        }
         rust(""".build().expect("invalid partition")""")
     }
 
+    private fun variant(tags: Set<String>): String =
+        "#{EndpointVariant}::new().with_fips(${tags.contains(FIPS)}).with_dual_stack(${tags.contains(DUALSTACK)})"
+
     inner class EndpointMeta(private val endpoint: ObjectNode, service: String, dnsSuffix: String) {
         private val uriTemplate =
             (endpoint.getStringMember("hostname").orNull() ?: throw CodegenException("endpoint must be defined"))
@@ -324,12 +419,19 @@ class EndpointResolverGenerator(codegenContext: CodegenContext, private val endp
 
         val endpoints: List<Pair<String, EndpointMeta>>
 
+        // variant templates (e.g. for FIPS endpoints) used when a region has no modeled variant endpoint
+        val defaultVariants: List<Pair<Set<String>, EndpointMeta>>
+
+        // variant endpoints explicitly modeled for a region
+        val endpointVariants: List<Triple<String, Set<String>, EndpointMeta>>
+
         init {
 
             val partitionDefaults = config.expectObjectMember("defaults")
             val serviceDefaults = service.getObjectMember("defaults").orElse(Node.objectNode())
             val mergedDefaults = partitionDefaults.merge(serviceDefaults)
-            endpoints = service.getObjectMember("endpoints").orElse(Node.objectNode()).members.mapNotNull { (k, v) ->
+            val serviceEndpoints = service.getObjectMember("endpoints").orElse(Node.objectNode()).members
+            endpoints = serviceEndpoints.mapNotNull { (k, v) ->
                 val endpointObject = mergedDefaults.merge(v.expectObjectNode())
                 // There is no point in generating lots of endpoints that are just empty
                 if (endpointObject != mergedDefaults) {
@@ -340,8 +442,47 @@ class EndpointResolverGenerator(codegenContext: CodegenContext, private val endp
             }
 
             defaults = EndpointMeta(mergedDefaults, endpointPrefix, dnsSuffix)
+
+            // variants are merged by their tags rather than replaced wholesale
+            val partitionVariants = variants(partitionDefaults)
+            val serviceVariants = variants(serviceDefaults)
+            val mergedVariants = (partitionVariants.keys + serviceVariants.keys).associateWith { tags ->
+                (partitionVariants[tags] ?: Node.objectNode()).merge(serviceVariants[tags] ?: Node.objectNode())
+            }
+            val variantBase = mergedDefaults.withoutMember("variants")
+            defaultVariants = mergedVariants.mapNotNull { (tags, variant) ->
+                variant.getStringMember("hostname").orNull()?.let { hostname ->
+                    val variantDnsSuffix = variant.getStringMember("dnsSuffix").map(StringNode::getValue).orElse(dnsSuffix)
+                    tags to EndpointMeta(variantBase.withMember("hostname", hostname), endpointPrefix, variantDnsSuffix)
+                }
+            }
+            endpointVariants = serviceEndpoints.flatMap { (k, v) ->
+                val endpointObject = variantBase.merge(v.expectObjectNode()).withoutMember("variants")
+                variants(v.expectObjectNode()).mapNotNull { (tags, variant) ->
+                    val fallback = mergedVariants[tags] ?: Node.objectNode()
+                    val hostname = variant.getStringMember("hostname")
+                        .or { fallback.getStringMember("hostname") }
+                        .orNull() ?: return@mapNotNull null
+                    val variantDnsSuffix = variant.getStringMember("dnsSuffix")
+                        .or { fallback.getStringMember("dnsSuffix") }
+                        .map(StringNode::getValue)
+                        .orElse(dnsSuffix)
+                    Triple(
+                        k.value,
+                        tags,
+                        EndpointMeta(endpointObject.withMember("hostname", hostname), endpointPrefix, variantDnsSuffix)
+                    )
+                }
+            }
         }
 
+        private fun variants(node: ObjectNode): Map<Set<String>, ObjectNode> =
+            node.getArrayMember("variants").map { it.elements }.orElse(listOf())
+                .map(Node::expectObjectNode)
+                .associateBy { variant -> variant.expectArrayMember("tags").map { it.expectStringNode().value }.toSet() }
+                // only FIPS and dual-stack variants are supported
+                .filterKeys { tags -> tags.isNotEmpty() && SUPPORTED_VARIANT_TAGS.containsAll(tags) }
+
         val regionalized: Boolean = service.getBooleanMemberOrDefault("isRegionalized", true)
 
         // regionalized services always use regionalized endpoints
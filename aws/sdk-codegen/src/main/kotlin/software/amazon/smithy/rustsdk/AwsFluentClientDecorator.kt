@@ -70,6 +70,9 @@ private class AwsClientGenerics(private val types: Types) : FluentClientGenerics
 
     /** Bounds for generated `send()` functions */
     override fun sendBounds(input: Symbol, output: Symbol, error: RuntimeType): Writable = writable { }
+
+    /** Additional bounds for `send()` on a `CustomizableOperation<O, Retry>` */
+    override val customizableSendBounds = writable { }
 }
 
 class AwsFluentClientDecorator : RustCodegenDecorator {
@@ -94,6 +97,7 @@ class AwsFluentClientDecorator : RustCodegenDecorator {
         val awsSmithyClient = "aws-smithy-client"
         rustCrate.mergeFeature(Feature("rustls", default = true, listOf("$awsSmithyClient/rustls")))
         rustCrate.mergeFeature(Feature("native-tls", default = false, listOf("$awsSmithyClient/native-tls")))
+        rustCrate.mergeFeature(Feature("client-fetch", default = false, listOf("$awsSmithyClient/client-fetch")))
     }
 
     override fun libRsCustomizations(
@@ -137,10 +141,14 @@ private class AwsFluentClientExtensions(types: Types) {
                     let retry_config = conf.retry_config.as_ref().cloned().unwrap_or_default();
                     let timeout_config = conf.timeout_config.as_ref().cloned().unwrap_or_default();
                     let sleep_impl = conf.sleep_impl.clone();
+                    let middleware = #{Middleware}::from_timeout_config(&timeout_config, sleep_impl.clone());
                     let mut builder = #{aws_smithy_client}::Builder::new()
                         .connector(#{DynConnector}::new(conn))
-                        .middleware(#{DynMiddleware}::new(#{Middleware}::new()));
+                        .middleware(#{DynMiddleware}::new(middleware));
                     builder.set_retry_config(retry_config.into());
+                    if let Some(retry_partitions) = conf.retry_partitions.clone() {
+                        builder.set_retry_partitions(retry_partitions);
+                    }
                     builder.set_timeout_config(timeout_config);
                     if let Some(sleep_impl) = sleep_impl {
                         builder.set_sleep_impl(Some(sleep_impl));
@@ -161,9 +169,13 @@ private class AwsFluentClientExtensions(types: Types) {
                     let retry_config = conf.retry_config.as_ref().cloned().unwrap_or_default();
                     let timeout_config = conf.timeout_config.as_ref().cloned().unwrap_or_default();
                     let sleep_impl = conf.sleep_impl.clone();
+                    let middleware = #{Middleware}::from_timeout_config(&timeout_config, sleep_impl.clone());
                     let mut builder = #{aws_smithy_client}::Builder::dyn_https()
-                        .middleware(#{DynMiddleware}::new(#{Middleware}::new()));
+                        .middleware(#{DynMiddleware}::new(middleware));
                     builder.set_retry_config(retry_config.into());
+                    if let Some(retry_partitions) = conf.retry_partitions.clone() {
+                        builder.set_retry_partitions(retry_partitions);
+                    }
                     builder.set_timeout_config(timeout_config);
                     // the builder maintains a try-state. To avoid suppressing the warning when sleep is unset,
                     // only set it if we actually have a sleep impl.
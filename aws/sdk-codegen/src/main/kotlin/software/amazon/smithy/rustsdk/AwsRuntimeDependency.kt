@@ -49,9 +49,12 @@ object AwsRuntimeType {
     fun RuntimeConfig.defaultMiddleware() = RuntimeType.forInlineDependency(
         InlineAwsDependency.forRustFile(
             "middleware", public = true,
+            CargoDependency.SmithyAsync(this),
             CargoDependency.SmithyHttp(this),
             CargoDependency.SmithyHttpTower(this),
             CargoDependency.SmithyClient(this),
+            CargoDependency.SmithyChecksums(this),
+            CargoDependency.SmithyTypes(this),
             CargoDependency.Tower,
             awsHttp(),
             awsEndpoint(),
@@ -5,9 +5,12 @@
 
 package software.amazon.smithy.rustsdk
 
+import software.amazon.smithy.aws.traits.ServiceTrait
+import software.amazon.smithy.rust.codegen.rustlang.CargoDependency
 import software.amazon.smithy.rust.codegen.rustlang.RustModule
 import software.amazon.smithy.rust.codegen.rustlang.Writable
 import software.amazon.smithy.rust.codegen.rustlang.asType
+import software.amazon.smithy.rust.codegen.rustlang.rust
 import software.amazon.smithy.rust.codegen.rustlang.rustTemplate
 import software.amazon.smithy.rust.codegen.rustlang.writable
 import software.amazon.smithy.rust.codegen.smithy.CodegenContext
@@ -16,6 +19,9 @@ import software.amazon.smithy.rust.codegen.smithy.RustCrate
 import software.amazon.smithy.rust.codegen.smithy.customize.RustCodegenDecorator
 import software.amazon.smithy.rust.codegen.smithy.generators.config.ConfigCustomization
 import software.amazon.smithy.rust.codegen.smithy.generators.config.ServiceConfig
+import software.amazon.smithy.rust.codegen.util.dq
+import software.amazon.smithy.rust.codegen.util.expectTrait
+import software.amazon.smithy.rustsdk.customize.sts.Sts
 
 /**
  * Adds functionality for constructing `<service>::Config` objects from `aws_types::SdkConfig`s
@@ -31,12 +37,23 @@ class SdkConfigDecorator : RustCodegenDecorator {
         codegenContext: CodegenContext,
         baseCustomizations: List<ConfigCustomization>
     ): List<ConfigCustomization> {
-        return baseCustomizations + NewFromShared(codegenContext.runtimeConfig)
+        return baseCustomizations + NewFromShared(codegenContext.runtimeConfig) + RetryPartitionsConfig(codegenContext.runtimeConfig)
     }
 
     override fun extras(codegenContext: CodegenContext, rustCrate: RustCrate) {
+        val sdkId = codegenContext.serviceShape.expectTrait<ServiceTrait>().sdkId
         val codegenScope = arrayOf(
-            "SdkConfig" to awsTypes(runtimeConfig = codegenContext.runtimeConfig).asType().member("sdk_config::SdkConfig")
+            "SdkConfig" to awsTypes(runtimeConfig = codegenContext.runtimeConfig).asType().member("sdk_config::SdkConfig"),
+            "Endpoint" to CargoDependency.SmithyHttp(codegenContext.runtimeConfig).asType().member("endpoint::Endpoint"),
+            "Region" to awsTypes(runtimeConfig = codegenContext.runtimeConfig).asType().member("region::Region"),
+            "RetryConfig" to CargoDependency.SmithyTypes(codegenContext.runtimeConfig).asType().member("retry::RetryConfig"),
+            "TimeoutConfig" to CargoDependency.SmithyTypes(codegenContext.runtimeConfig).asType().member("timeout::Config"),
+            "sts_regional_endpoints" to writable {
+                // the STS regional endpoints setting only exists on the STS config builder
+                if (codegenContext.serviceShape.id == Sts) {
+                    rust("builder.set_sts_regional_endpoints(input.sts_regional_endpoints());")
+                }
+            },
         )
         rustCrate.withModule(RustModule.Config) {
             // !!NOTE!! As more items are added to aws_types::SdkConfig, use them here to configure the config builder
@@ -47,11 +64,33 @@ class SdkConfigDecorator : RustCodegenDecorator {
                         let mut builder = Builder::default();
                         builder = builder.region(input.region().cloned());
                         builder.set_endpoint_resolver(input.endpoint_resolver().clone());
+                        // an explicit endpoint resolver takes precedence over a configured endpoint URL
+                        if builder.endpoint_resolver.is_none() {
+                            if let Some(endpoint_url) = input.service_endpoint_url(${sdkId.dq()}) {
+                                builder.set_endpoint_resolver(Some(::std::sync::Arc::new(#{Endpoint}::immutable(endpoint_url.clone()))));
+                            }
+                        }
+                        builder.set_use_fips(input.use_fips());
+                        builder.set_use_dual_stack(input.use_dual_stack());
+                        #{sts_regional_endpoints:W}
                         builder.set_retry_config(input.retry_config().cloned());
+                        builder.set_retry_partitions(Some(input.retry_partitions().clone()));
                         builder.set_timeout_config(input.timeout_config().cloned());
                         builder.set_sleep_impl(input.sleep_impl().clone());
                         builder.set_credentials_provider(input.credentials_provider().cloned());
                         builder.set_app_name(input.app_name().cloned());
+                        if let Some(service_config) = input.service_config(${sdkId.dq()}) {
+                            if let Some(region) = service_config.get::<#{Region}>() {
+                                builder = builder.region(region.clone());
+                            }
+                            if let Some(retry_config) = service_config.get::<#{RetryConfig}>() {
+                                builder.set_retry_config(Some(retry_config.clone()));
+                            }
+                            if let Some(timeout_config) = service_config.get::<#{TimeoutConfig}>() {
+                                builder.set_timeout_config(Some(timeout_config.clone()));
+                            }
+                            builder = service_config.apply(builder);
+                        }
                         builder
                     }
                 }
@@ -89,3 +128,44 @@ class NewFromShared(runtimeConfig: RuntimeConfig) : ConfigCustomization() {
         }
     }
 }
+
+/**
+ * Adds `retry_partitions` to the service config so that clients created from the same `SdkConfig` share retry quota
+ */
+class RetryPartitionsConfig(runtimeConfig: RuntimeConfig) : ConfigCustomization() {
+    private val codegenScope = arrayOf(
+        "RetryPartitions" to CargoDependency.SmithyClient(runtimeConfig).asType().member("retry::RetryPartitions")
+    )
+
+    override fun section(section: ServiceConfig): Writable = writable {
+        when (section) {
+            is ServiceConfig.ConfigStruct -> rustTemplate(
+                "pub(crate) retry_partitions: Option<#{RetryPartitions}>,",
+                *codegenScope
+            )
+            is ServiceConfig.BuilderStruct ->
+                rustTemplate("retry_partitions: Option<#{RetryPartitions}>,", *codegenScope)
+            ServiceConfig.BuilderImpl -> rustTemplate(
+                """
+                /// Set the retry quotas that clients created from this config will draw from
+                ///
+                /// Clients created from the same shared config share retry quotas by default. Set this to share
+                /// them with other clients, or to give a client its own quotas.
+                pub fn retry_partitions(mut self, retry_partitions: #{RetryPartitions}) -> Self {
+                    self.set_retry_partitions(Some(retry_partitions));
+                    self
+                }
+
+                /// Set the retry quotas that clients created from this config will draw from
+                pub fn set_retry_partitions(&mut self, retry_partitions: Option<#{RetryPartitions}>) -> &mut Self {
+                    self.retry_partitions = retry_partitions;
+                    self
+                }
+                """,
+                *codegenScope
+            )
+            ServiceConfig.BuilderBuild -> rust("retry_partitions: self.retry_partitions,")
+            else -> emptySection
+        }
+    }
+}
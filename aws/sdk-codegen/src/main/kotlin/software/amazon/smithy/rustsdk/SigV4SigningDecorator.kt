@@ -141,6 +141,12 @@ fun disableDoubleEncode(service: ServiceShape) = when (service.id) {
     else -> false
 }
 
+// S3 object keys may legitimately contain `.`, `..`, and repeated slashes, so they must be signed as-is
+fun disableUriPathNormalization(service: ServiceShape) = when (service.id) {
+    ShapeId.from("com.amazonaws.s3#AmazonS3") -> true
+    else -> false
+}
+
 class SigV4SigningFeature(
     private val model: Model,
     private val operation: OperationShape,
@@ -149,7 +155,11 @@ class SigV4SigningFeature(
 ) :
     OperationCustomization() {
     private val codegenScope =
-        arrayOf("sig_auth" to runtimeConfig.sigAuth().asType(), "aws_types" to awsTypes(runtimeConfig).asType())
+        arrayOf(
+            "sig_auth" to runtimeConfig.sigAuth().asType(),
+            "aws_types" to awsTypes(runtimeConfig).asType(),
+            "http_auth" to RuntimeType("auth", CargoDependency.SmithyHttp(runtimeConfig), "${runtimeConfig.crateSrcPrefix}_http"),
+        )
 
     private val serviceIndex = ServiceIndex.of(model)
 
@@ -166,6 +176,9 @@ class SigV4SigningFeature(
                 if (disableDoubleEncode(service)) {
                     rust("signing_config.signing_options.double_uri_encode = false;")
                 }
+                if (disableUriPathNormalization(service)) {
+                    rust("signing_config.signing_options.normalize_uri_path = false;")
+                }
                 if (operation.hasTrait<UnsignedPayloadTrait>()) {
                     rust("signing_config.signing_options.content_sha256_header = true;")
                     rustTemplate(
@@ -183,9 +196,22 @@ class SigV4SigningFeature(
                 val authSchemes = serviceIndex.getEffectiveAuthSchemes(service, operation)
                 if (!authSchemes.containsKey(SigV4Trait.ID)) {
                     rustTemplate("signing_config.signing_requirements = #{sig_auth}::signer::SigningRequirements::Disabled;", *codegenScope)
+                    // unauthenticated operations shouldn't fail (or wait) on credential resolution
+                    rustTemplate("${section.request}.properties_mut().insert(#{aws_types}::credentials::Anonymous::new());", *codegenScope)
                 } else {
                     if (operation.hasTrait<OptionalAuthTrait>()) {
                         rustTemplate("signing_config.signing_requirements = #{sig_auth}::signer::SigningRequirements::Optional;", *codegenScope)
+                        // sign the request if credentials are available, otherwise send it anonymously
+                        rustTemplate(
+                            """
+                            ${section.request}.properties_mut().insert(
+                                #{http_auth}::AuthSchemeOptions::new()
+                                    .with_scheme(#{sig_auth}::middleware::SigV4AuthScheme::new(#{sig_auth}::signer::SigV4Signer::new()))
+                                    .with_scheme(#{http_auth}::NoAuth::new())
+                            );
+                            """,
+                            *codegenScope
+                        )
                     }
                 }
                 rustTemplate(
@@ -8,6 +8,8 @@ package software.amazon.smithy.rustsdk.customize.s3
 import software.amazon.smithy.aws.traits.protocols.RestXmlTrait
 import software.amazon.smithy.model.shapes.OperationShape
 import software.amazon.smithy.model.shapes.ShapeId
+import software.amazon.smithy.model.traits.HttpChecksumRequiredTrait
+import software.amazon.smithy.model.traits.HttpLabelTrait
 import software.amazon.smithy.rust.codegen.rustlang.CargoDependency
 import software.amazon.smithy.rust.codegen.rustlang.RustModule
 import software.amazon.smithy.rust.codegen.rustlang.Writable
@@ -18,14 +20,25 @@ import software.amazon.smithy.rust.codegen.rustlang.rustTemplate
 import software.amazon.smithy.rust.codegen.rustlang.writable
 import software.amazon.smithy.rust.codegen.smithy.CodegenContext
 import software.amazon.smithy.rust.codegen.smithy.RuntimeType
+import software.amazon.smithy.rust.codegen.smithy.RustCrate
+import software.amazon.smithy.rust.codegen.smithy.customizations.HttpChecksumRequiredGenerator
+import software.amazon.smithy.rust.codegen.smithy.customize.OperationCustomization
+import software.amazon.smithy.rust.codegen.smithy.customize.OperationSection
 import software.amazon.smithy.rust.codegen.smithy.customize.RustCodegenDecorator
 import software.amazon.smithy.rust.codegen.smithy.generators.LibRsCustomization
 import software.amazon.smithy.rust.codegen.smithy.generators.LibRsSection
+import software.amazon.smithy.rust.codegen.smithy.generators.config.ConfigCustomization
+import software.amazon.smithy.rust.codegen.smithy.generators.config.ServiceConfig
+import software.amazon.smithy.rust.codegen.smithy.generators.error.errorSymbol
 import software.amazon.smithy.rust.codegen.smithy.letIf
 import software.amazon.smithy.rust.codegen.smithy.protocols.ProtocolMap
 import software.amazon.smithy.rust.codegen.smithy.protocols.RestXml
 import software.amazon.smithy.rust.codegen.smithy.protocols.RestXmlFactory
+import software.amazon.smithy.rust.codegen.util.hasTrait
+import software.amazon.smithy.rust.codegen.util.inputShape
+import software.amazon.smithy.rust.codegen.util.outputShape
 import software.amazon.smithy.rustsdk.AwsRuntimeType
+import software.amazon.smithy.rustsdk.awsEndpoint
 
 /**
  * Top level decorator for S3
@@ -34,6 +47,9 @@ class S3Decorator : RustCodegenDecorator {
     override val name: String = "S3ExtendedError"
     override val order: Byte = 0
 
+    // Operations that upload potentially large payloads, which are sent with `Expect: 100-continue`
+    private val expectContinueOperations = setOf("PutObject", "UploadPart")
+
     private fun applies(serviceId: ShapeId) =
         serviceId == ShapeId.from("com.amazonaws.s3#AmazonS3")
 
@@ -55,6 +71,171 @@ class S3Decorator : RustCodegenDecorator {
             it + S3PubUse()
         }
     }
+
+    override fun configCustomizations(
+        codegenContext: CodegenContext,
+        baseCustomizations: List<ConfigCustomization>
+    ): List<ConfigCustomization> {
+        return baseCustomizations.letIf(applies(codegenContext.serviceShape.id)) {
+            it + S3AddressingConfig()
+        }
+    }
+
+    override fun operationCustomizations(
+        codegenContext: CodegenContext,
+        operation: OperationShape,
+        baseCustomizations: List<OperationCustomization>
+    ): List<OperationCustomization> {
+        return baseCustomizations.letIf(applies(codegenContext.serviceShape.id)) {
+            // `Content-MD5` is computed by the payload checksum stage of the middleware instead, which can hash
+            // streaming bodies and doesn't replace a checksum that the caller already set
+            it.filterNot { customization -> customization is HttpChecksumRequiredGenerator } +
+                S3Addressing(codegenContext, operation) + S3SuccessResponseErrors(codegenContext, operation) + listOfNotNull(
+                    S3ExpectContinue(codegenContext).takeIf { expectContinueOperations.contains(operation.id.name) },
+                    S3PayloadChecksums(codegenContext).takeIf { operation.hasTrait<HttpChecksumRequiredTrait>() }
+                )
+        }
+    }
+
+    override fun extras(codegenContext: CodegenContext, rustCrate: RustCrate) {
+        if (applies(codegenContext.serviceShape.id)) {
+            S3Transfer(codegenContext).render(rustCrate)
+        }
+    }
+}
+
+class S3AddressingConfig : ConfigCustomization() {
+    override fun section(section: ServiceConfig): Writable = writable {
+        when (section) {
+            is ServiceConfig.ConfigStruct -> rust("pub (crate) force_path_style: Option<bool>,")
+            is ServiceConfig.BuilderStruct -> rust("force_path_style: Option<bool>,")
+            ServiceConfig.BuilderImpl -> rust(
+                """
+                /// Forces the client to use path-style addressing for buckets.
+                ///
+                /// By default, the bucket is moved into the host when the bucket name is DNS compatible
+                /// (e.g. `https://bucket.s3.us-east-1.amazonaws.com/key`). When path-style addressing is forced,
+                /// the bucket is always the first segment of the path (e.g. `https://s3.us-east-1.amazonaws.com/bucket/key`),
+                /// which is often required for S3-compatible services and local test servers.
+                pub fn force_path_style(mut self, force_path_style: bool) -> Self {
+                    self.force_path_style = Some(force_path_style);
+                    self
+                }
+
+                /// Forces the client to use path-style addressing for buckets.
+                pub fn set_force_path_style(&mut self, force_path_style: Option<bool>) -> &mut Self {
+                    self.force_path_style = force_path_style;
+                    self
+                }
+                """
+            )
+            ServiceConfig.BuilderBuild -> rust("force_path_style: self.force_path_style,")
+            else -> {}
+        }
+    }
+}
+
+/**
+ * Records the bucket targeted by an operation so that the endpoint stage can pick between virtual-hosted and
+ * path-style addressing (and route access point ARNs to their own endpoint)
+ */
+class S3Addressing(codegenContext: CodegenContext, operation: OperationShape) : OperationCustomization() {
+    private val s3Endpoint = codegenContext.runtimeConfig.awsEndpoint().asType().member("s3")
+    private val bucketMember = operation.inputShape(codegenContext.model).members()
+        .find { it.memberName == "Bucket" && it.hasTrait<HttpLabelTrait>() }
+        ?.let { codegenContext.symbolProvider.toMemberName(it) }
+
+    override fun section(section: OperationSection): Writable = writable {
+        when (section) {
+            // the input may be consumed while building the request body, so the bucket is captured up front
+            is OperationSection.MutateInput -> if (bucketMember != null) {
+                rust("let s3_bucket = ${section.input}.$bucketMember.clone();")
+            }
+            is OperationSection.MutateRequest -> {
+                if (bucketMember != null) {
+                    rust(
+                        """
+                        if let Some(s3_bucket) = s3_bucket {
+                            #T::set_bucket(&mut ${section.request}.properties_mut(), s3_bucket);
+                        }
+                        """,
+                        s3Endpoint
+                    )
+                }
+                rust(
+                    "#T::set_force_path_style(&mut ${section.request}.properties_mut(), ${section.config}.force_path_style.unwrap_or_default());",
+                    s3Endpoint
+                )
+            }
+            else -> {}
+        }
+    }
+}
+
+/**
+ * Sends large uploads with `Expect: 100-continue` so that S3 can reject the request before the payload is sent
+ *
+ * The payload is held back until S3 sends `100 Continue`, for at most one second.
+ */
+class S3ExpectContinue(codegenContext: CodegenContext) : OperationCustomization() {
+    private val expectContinue = RuntimeType.operationModule(codegenContext.runtimeConfig).member("ExpectContinue")
+
+    override fun section(section: OperationSection): Writable = writable {
+        when (section) {
+            is OperationSection.MutateRequest -> rust(
+                "${section.request}.properties_mut().insert(#T::new().with_timeout(::std::time::Duration::from_secs(1)));",
+                expectContinue
+            )
+            else -> {}
+        }
+    }
+}
+
+/**
+ * Requests a `Content-MD5` header from the payload checksum stage for operations with `@httpChecksumRequired`
+ */
+class S3PayloadChecksums(codegenContext: CodegenContext) : OperationCustomization() {
+    private val payloadChecksums =
+        CargoDependency.SmithyChecksums(codegenContext.runtimeConfig).asType().member("payload::PayloadChecksums")
+
+    override fun section(section: OperationSection): Writable = writable {
+        when (section) {
+            is OperationSection.MutateRequest -> rust(
+                "${section.request}.properties_mut().insert(#T::new().with_content_md5());",
+                payloadChecksums
+            )
+            else -> {}
+        }
+    }
+}
+
+/**
+ * Marks transient errors that S3 sends in the body of a `200 OK` response (see [S3.successResponseIsError]) as
+ * retryable with a hook that runs after the response has been parsed
+ */
+class S3SuccessResponseErrors(codegenContext: CodegenContext, private val operation: OperationShape) :
+    OperationCustomization() {
+    private val symbolProvider = codegenContext.symbolProvider
+    private val model = codegenContext.model
+    private val parsedResponseHook =
+        CargoDependency.SmithyHttp(codegenContext.runtimeConfig).asType().member("response::ParsedResponseHook")
+
+    override fun section(section: OperationSection): Writable = writable {
+        when (section) {
+            is OperationSection.MutateRequest -> rustTemplate(
+                """
+                ${section.request}.properties_mut().insert(
+                    #{ParsedResponseHook}::<#{Output}, #{Error}>::new(#{S3Errors}::classify_success_response_error)
+                );
+                """,
+                "ParsedResponseHook" to parsedResponseHook,
+                "Output" to symbolProvider.toSymbol(operation.outputShape(model)),
+                "Error" to operation.errorSymbol(symbolProvider),
+                "S3Errors" to AwsRuntimeType.S3Errors,
+            )
+            else -> {}
+        }
+    }
 }
 
 class S3(codegenContext: CodegenContext) : RestXml(codegenContext) {
@@ -93,6 +274,25 @@ class S3(codegenContext: CodegenContext) : RestXml(codegenContext) {
             }
         }
     }
+
+    override fun successResponseIsError(operationShape: OperationShape): RuntimeType {
+        return RuntimeType.forInlineFun("success_response_is_error", RustModule.private("xml_deser")) {
+            it.rustBlockTemplate(
+                "pub fn success_response_is_error(response: &#{Response}<#{Bytes}>) -> bool",
+                *errorScope
+            ) {
+                rustTemplate(
+                    """
+                    // Operations like `CopyObject` and `CompleteMultipartUpload` can fail after S3 has already
+                    // sent a `200 OK`, in which case the body contains the error instead of the output.
+                    response.status().as_u16() == 200 && !response.body().is_empty() &&
+                        #{base_errors}::body_is_error(response.body().as_ref()).unwrap_or(false)
+                    """,
+                    *errorScope
+                )
+            }
+        }
+    }
 }
 
 class S3PubUse : LibRsCustomization() {
@@ -0,0 +1,214 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+package software.amazon.smithy.rustsdk.customize.s3
+
+import software.amazon.smithy.rust.codegen.rustlang.RustModule
+import software.amazon.smithy.rust.codegen.rustlang.asType
+import software.amazon.smithy.rust.codegen.rustlang.rustTemplate
+import software.amazon.smithy.rust.codegen.smithy.CodegenContext
+import software.amazon.smithy.rust.codegen.smithy.RuntimeType
+import software.amazon.smithy.rust.codegen.smithy.RustCrate
+
+/**
+ * Generates the `transfer` module, which implements the multipart transfers of `aws-smithy-transfer` with the
+ * fluent client so that every part is sent with `Client::call` and retried by the client's retry policy
+ */
+class S3Transfer(codegenContext: CodegenContext) {
+    private val moduleUseName = codegenContext.moduleUseName()
+    private val transfer = codegenContext.runtimeConfig.runtimeCrate("transfer").asType()
+    private val codegenScope = arrayOf(
+        "BoxError" to transfer.member("BoxError"),
+        "BoxFuture" to transfer.member("BoxFuture"),
+        "ByteStream" to RuntimeType.byteStream(codegenContext.runtimeConfig),
+        "Bytes" to RuntimeType.Bytes,
+        "DownloadParts" to transfer.member("download::DownloadParts"),
+        "ObjectVersion" to transfer.member("download::ObjectVersion"),
+        "UploadParts" to transfer.member("upload::UploadParts"),
+        "download" to transfer.member("download"),
+        "upload" to transfer.member("upload"),
+    )
+
+    fun render(rustCrate: RustCrate) {
+        rustCrate.withModule(
+            RustModule.public("transfer", documentation = "Concurrent multipart uploads and ranged downloads of large objects.")
+        ) { writer ->
+            writer.rustTemplate(
+                """
+                pub use #{download}::{DownloadError, Downloader, ObjectVersion};
+                pub use #{upload}::{UploadError, Uploader};
+
+                /// Uploads an object with a multipart upload
+                ///
+                /// Each part is sent with its own `UploadPart` request, which the client retries according to
+                /// its retry policy. The upload is aborted if it can't be completed.
+                ///
+                /// ```no_run
+                /// ## async fn upload(client: $moduleUseName::Client) -> Result<(), $moduleUseName::transfer::UploadError> {
+                /// use $moduleUseName::transfer::{ObjectUpload, Uploader};
+                /// use $moduleUseName::types::ByteStream;
+                ///
+                /// let body = ByteStream::from_path("large-file").await.expect("file is readable");
+                /// let upload = ObjectUpload::new(client, "bucket", "key");
+                /// Uploader::new().upload(&upload, body).await?;
+                /// ## Ok(())
+                /// ## }
+                /// ```
+                ##[derive(Clone, Debug)]
+                pub struct ObjectUpload {
+                    client: crate::Client,
+                    bucket: String,
+                    key: String,
+                }
+
+                impl ObjectUpload {
+                    /// Creates an upload of `key` to `bucket`
+                    pub fn new(client: crate::Client, bucket: impl Into<String>, key: impl Into<String>) -> Self {
+                        Self {
+                            client,
+                            bucket: bucket.into(),
+                            key: key.into(),
+                        }
+                    }
+                }
+
+                impl #{UploadParts} for ObjectUpload {
+                    type UploadId = String;
+                    type Part = crate::model::CompletedPart;
+                    type Output = crate::output::CompleteMultipartUploadOutput;
+
+                    fn create(&self) -> #{BoxFuture}<String> {
+                        let request = self.client.create_multipart_upload().bucket(&self.bucket).key(&self.key);
+                        Box::pin(async move {
+                            let output = request.send().await?;
+                            output
+                                .upload_id()
+                                .map(str::to_owned)
+                                .ok_or_else(|| #{BoxError}::from("CreateMultipartUpload didn't return an upload ID"))
+                        })
+                    }
+
+                    fn upload_part(&self, upload_id: &String, part_number: u32, body: #{Bytes}) -> #{BoxFuture}<crate::model::CompletedPart> {
+                        let request = self
+                            .client
+                            .upload_part()
+                            .bucket(&self.bucket)
+                            .key(&self.key)
+                            .upload_id(upload_id)
+                            .part_number(part_number as i32)
+                            .body(#{ByteStream}::from(body));
+                        Box::pin(async move {
+                            let output = request.send().await?;
+                            Ok(crate::model::CompletedPart::builder()
+                                .set_e_tag(output.e_tag().map(str::to_owned))
+                                .part_number(part_number as i32)
+                                .build())
+                        })
+                    }
+
+                    fn complete(
+                        &self,
+                        upload_id: &String,
+                        parts: Vec<crate::model::CompletedPart>,
+                    ) -> #{BoxFuture}<crate::output::CompleteMultipartUploadOutput> {
+                        let request = self
+                            .client
+                            .complete_multipart_upload()
+                            .bucket(&self.bucket)
+                            .key(&self.key)
+                            .upload_id(upload_id)
+                            .multipart_upload(
+                                crate::model::CompletedMultipartUpload::builder()
+                                    .set_parts(Some(parts))
+                                    .build(),
+                            );
+                        Box::pin(async move { Ok(request.send().await?) })
+                    }
+
+                    fn abort(&self, upload_id: &String) -> #{BoxFuture}<()> {
+                        let request = self
+                            .client
+                            .abort_multipart_upload()
+                            .bucket(&self.bucket)
+                            .key(&self.key)
+                            .upload_id(upload_id);
+                        Box::pin(async move {
+                            request.send().await?;
+                            Ok(())
+                        })
+                    }
+                }
+
+                /// Downloads an object in concurrent ranges
+                ///
+                /// Each range is fetched with its own `GetObject` request, which the client retries according to
+                /// its retry policy. Every range is read from the version of the object returned by `HeadObject`
+                /// when the download starts: ranges are requested with its ETag in `If-Match` and with its version
+                /// ID, so the download fails if the object is overwritten rather than mixing both versions.
+                ///
+                /// ```no_run
+                /// ## async fn download(client: $moduleUseName::Client) -> Result<(), $moduleUseName::transfer::DownloadError> {
+                /// use $moduleUseName::transfer::{Downloader, ObjectDownload};
+                ///
+                /// let download = ObjectDownload::new(client, "bucket", "key");
+                /// let body = Downloader::new().download_stream(download).await?;
+                /// ## Ok(())
+                /// ## }
+                /// ```
+                ##[derive(Clone, Debug)]
+                pub struct ObjectDownload {
+                    client: crate::Client,
+                    bucket: String,
+                    key: String,
+                }
+
+                impl ObjectDownload {
+                    /// Creates a download of `key` from `bucket`
+                    pub fn new(client: crate::Client, bucket: impl Into<String>, key: impl Into<String>) -> Self {
+                        Self {
+                            client,
+                            bucket: bucket.into(),
+                            key: key.into(),
+                        }
+                    }
+                }
+
+                impl #{DownloadParts} for ObjectDownload {
+                    fn object(&self) -> #{BoxFuture}<#{ObjectVersion}> {
+                        let request = self.client.head_object().bucket(&self.bucket).key(&self.key);
+                        Box::pin(async move {
+                            let output = request.send().await?;
+                            let e_tag = output
+                                .e_tag()
+                                .ok_or_else(|| #{BoxError}::from("HeadObject didn't return an ETag"))?;
+                            let object = #{ObjectVersion}::new(output.content_length() as u64, e_tag);
+                            Ok(match output.version_id() {
+                                Some(version_id) => object.with_version_id(version_id),
+                                None => object,
+                            })
+                        })
+                    }
+
+                    fn get_range(&self, object: &#{ObjectVersion}, range: std::ops::Range<u64>) -> #{BoxFuture}<#{Bytes}> {
+                        let request = self
+                            .client
+                            .get_object()
+                            .bucket(&self.bucket)
+                            .key(&self.key)
+                            .range(format!("bytes={}-{}", range.start, range.end - 1))
+                            .if_match(object.e_tag())
+                            .set_version_id(object.version_id().map(str::to_owned));
+                        Box::pin(async move {
+                            let output = request.send().await?;
+                            Ok(output.body.collect().await?.into_bytes())
+                        })
+                    }
+                }
+                """,
+                *codegenScope
+            )
+        }
+    }
+}
@@ -0,0 +1,135 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+package software.amazon.smithy.rustsdk.customize.sts
+
+import software.amazon.smithy.model.shapes.OperationShape
+import software.amazon.smithy.model.shapes.ShapeId
+import software.amazon.smithy.rust.codegen.rustlang.Writable
+import software.amazon.smithy.rust.codegen.rustlang.asType
+import software.amazon.smithy.rust.codegen.rustlang.rust
+import software.amazon.smithy.rust.codegen.rustlang.rustTemplate
+import software.amazon.smithy.rust.codegen.rustlang.writable
+import software.amazon.smithy.rust.codegen.smithy.CodegenContext
+import software.amazon.smithy.rust.codegen.smithy.RuntimeConfig
+import software.amazon.smithy.rust.codegen.smithy.customize.OperationCustomization
+import software.amazon.smithy.rust.codegen.smithy.customize.OperationSection
+import software.amazon.smithy.rust.codegen.smithy.customize.RustCodegenDecorator
+import software.amazon.smithy.rust.codegen.smithy.generators.LibRsCustomization
+import software.amazon.smithy.rust.codegen.smithy.generators.LibRsSection
+import software.amazon.smithy.rust.codegen.smithy.generators.config.ConfigCustomization
+import software.amazon.smithy.rust.codegen.smithy.generators.config.ServiceConfig
+import software.amazon.smithy.rust.codegen.smithy.letIf
+import software.amazon.smithy.rustsdk.awsEndpoint
+import software.amazon.smithy.rustsdk.awsTypes
+
+val Sts: ShapeId = ShapeId.from("com.amazonaws.sts#AWSSecurityTokenServiceV20110615")
+
+/**
+ * Top level decorator for STS
+ */
+class StsDecorator : RustCodegenDecorator {
+    override val name: String = "Sts"
+    override val order: Byte = 0
+
+    private fun applies(serviceId: ShapeId) = serviceId == Sts
+
+    override fun libRsCustomizations(
+        codegenContext: CodegenContext,
+        baseCustomizations: List<LibRsCustomization>
+    ): List<LibRsCustomization> {
+        return baseCustomizations.letIf(applies(codegenContext.serviceShape.id)) {
+            it + StsPubUse(codegenContext.runtimeConfig)
+        }
+    }
+
+    override fun configCustomizations(
+        codegenContext: CodegenContext,
+        baseCustomizations: List<ConfigCustomization>
+    ): List<ConfigCustomization> {
+        return baseCustomizations.letIf(applies(codegenContext.serviceShape.id)) {
+            it + StsRegionalEndpointsConfig(codegenContext.runtimeConfig)
+        }
+    }
+
+    override fun operationCustomizations(
+        codegenContext: CodegenContext,
+        operation: OperationShape,
+        baseCustomizations: List<OperationCustomization>
+    ): List<OperationCustomization> {
+        return baseCustomizations.letIf(applies(codegenContext.serviceShape.id)) {
+            it + StsRegionalEndpoints(codegenContext.runtimeConfig)
+        }
+    }
+}
+
+class StsRegionalEndpointsConfig(runtimeConfig: RuntimeConfig) : ConfigCustomization() {
+    private val codegenScope = arrayOf(
+        "StsRegionalEndpoints" to awsTypes(runtimeConfig).asType().member("endpoint::StsRegionalEndpoints")
+    )
+
+    override fun section(section: ServiceConfig): Writable = writable {
+        when (section) {
+            is ServiceConfig.ConfigStruct -> rustTemplate(
+                "pub(crate) sts_regional_endpoints: Option<#{StsRegionalEndpoints}>,",
+                *codegenScope
+            )
+            is ServiceConfig.BuilderStruct ->
+                rustTemplate("sts_regional_endpoints: Option<#{StsRegionalEndpoints}>,", *codegenScope)
+            ServiceConfig.BuilderImpl -> rustTemplate(
+                """
+                /// Sets whether requests in the legacy global regions are sent to the global STS endpoint
+                ///
+                /// By default, requests are always sent to the STS endpoint for the configured region. With
+                /// [`StsRegionalEndpoints::Legacy`](#{StsRegionalEndpoints}::Legacy), requests made in a region that
+                /// predates regional STS endpoints (e.g. `us-west-2`) are sent to `sts.amazonaws.com` instead.
+                pub fn sts_regional_endpoints(mut self, sts_regional_endpoints: #{StsRegionalEndpoints}) -> Self {
+                    self.set_sts_regional_endpoints(Some(sts_regional_endpoints));
+                    self
+                }
+
+                /// Sets whether requests in the legacy global regions are sent to the global STS endpoint
+                pub fn set_sts_regional_endpoints(&mut self, sts_regional_endpoints: Option<#{StsRegionalEndpoints}>) -> &mut Self {
+                    self.sts_regional_endpoints = sts_regional_endpoints;
+                    self
+                }
+                """,
+                *codegenScope
+            )
+            ServiceConfig.BuilderBuild -> rust("sts_regional_endpoints: self.sts_regional_endpoints,")
+            else -> {}
+        }
+    }
+}
+
+/**
+ * Records the STS regional endpoints setting so that the endpoint stage can pick the global endpoint
+ */
+class StsRegionalEndpoints(runtimeConfig: RuntimeConfig) : OperationCustomization() {
+    private val sts = runtimeConfig.awsEndpoint().asType().member("sts")
+
+    override fun section(section: OperationSection): Writable = writable {
+        when (section) {
+            is OperationSection.MutateRequest -> rust(
+                """
+                if let Some(sts_regional_endpoints) = ${section.config}.sts_regional_endpoints {
+                    #T::set_sts_regional_endpoints(&mut ${section.request}.properties_mut(), sts_regional_endpoints);
+                }
+                """,
+                sts
+            )
+            else -> {}
+        }
+    }
+}
+
+class StsPubUse(runtimeConfig: RuntimeConfig) : LibRsCustomization() {
+    private val stsRegionalEndpoints = awsTypes(runtimeConfig).asType().member("endpoint::StsRegionalEndpoints")
+
+    override fun section(section: LibRsSection): Writable = when (section) {
+        is LibRsSection.Body -> writable { rust("pub use #T;", stsRegionalEndpoints) }
+        else -> emptySection
+    }
+}
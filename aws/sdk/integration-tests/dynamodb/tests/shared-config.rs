@@ -23,3 +23,29 @@ async fn shared_config_testbed() {
         &Uri::from_static("https://dynamodb.us-east-4.amazonaws.com")
     );
 }
+
+#[tokio::test]
+async fn service_config_overrides_shared_config() {
+    use aws_types::service_config::ServiceConfig;
+
+    let shared_config = aws_types::SdkConfig::builder()
+        .region(Region::new("us-east-4"))
+        .service_config(
+            "DynamoDB",
+            ServiceConfig::new()
+                .with(Region::new("eu-west-7"))
+                .customize(|builder: aws_sdk_dynamodb::config::Builder| {
+                    builder
+                        .credentials_provider(Credentials::new("asdf", "asdf", None, None, "test"))
+                }),
+        )
+        .build();
+    let conf = aws_sdk_dynamodb::Config::from(&shared_config);
+    let (conn, request) = aws_smithy_client::test_connection::capture_request(None);
+    let svc = aws_sdk_dynamodb::Client::from_conf_conn(conf, conn);
+    let _ = svc.list_tables().send().await;
+    assert_eq!(
+        request.expect_request().uri(),
+        &Uri::from_static("https://dynamodb.eu-west-7.amazonaws.com")
+    );
+}
@@ -0,0 +1,62 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use aws_sdk_s3::{Credentials, Region};
+use aws_smithy_client::test_connection::capture_request;
+
+async fn request_uri(bucket: &str, force_path_style: bool) -> String {
+    let (conn, captured_request) = capture_request(None);
+    let creds = Credentials::new(
+        "ANOTREAL",
+        "notrealrnrELgWzOk3IfjzDKtFBhDby",
+        Some("notarealsessiontoken".to_string()),
+        None,
+        "test",
+    );
+    let conf = aws_sdk_s3::Config::builder()
+        .credentials_provider(creds)
+        .region(Region::new("us-west-2"))
+        .force_path_style(force_path_style)
+        .build();
+    let client = aws_sdk_s3::Client::from_conf_conn(conf, conn);
+    let _ = client.get_object().bucket(bucket).key("key").send().await;
+    captured_request.expect_request().uri().to_string()
+}
+
+#[tokio::test]
+async fn dns_compatible_buckets_use_virtual_hosted_addressing() {
+    assert_eq!(
+        request_uri("test-bucket", false).await,
+        "https://test-bucket.s3.us-west-2.amazonaws.com/key?x-id=GetObject"
+    );
+}
+
+#[tokio::test]
+async fn force_path_style() {
+    assert_eq!(
+        request_uri("test-bucket", true).await,
+        "https://s3.us-west-2.amazonaws.com/test-bucket/key?x-id=GetObject"
+    );
+}
+
+#[tokio::test]
+async fn buckets_that_are_not_dns_compatible_use_path_style() {
+    assert_eq!(
+        request_uri("test.bucket", false).await,
+        "https://s3.us-west-2.amazonaws.com/test.bucket/key?x-id=GetObject"
+    );
+}
+
+#[tokio::test]
+async fn access_point_arns_use_the_access_point_endpoint() {
+    assert_eq!(
+        request_uri(
+            "arn:aws:s3:us-west-2:123456789012:accesspoint/my-access-point",
+            false
+        )
+        .await,
+        "https://my-access-point-123456789012.s3-accesspoint.us-west-2.amazonaws.com/key?x-id=GetObject"
+    );
+}
@@ -0,0 +1,14 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Helpers shared by the S3 integration tests
+
+/// Returns a config builder that uses path-style addressing
+///
+/// The expected requests of the recorded tests were captured before virtual-hosted-style
+/// addressing became the default, so they put the bucket in the path.
+pub fn path_style_config() -> aws_sdk_s3::config::Builder {
+    aws_sdk_s3::Config::builder().force_path_style(true)
+}
@@ -0,0 +1,74 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use aws_http::retry::AwsErrorRetryPolicy;
+use aws_sdk_s3::error::CopyObjectError;
+use aws_sdk_s3::operation::CopyObject;
+use aws_sdk_s3::output::CopyObjectOutput;
+use aws_sdk_s3::{Credentials, Region};
+use aws_smithy_http::body::SdkBody;
+use aws_smithy_http::operation;
+use aws_smithy_http::response::{ParseHttpResponse, ParsedResponseHook};
+use aws_smithy_http::result::{SdkError, SdkSuccess};
+use aws_smithy_http::retry::ClassifyResponse;
+use aws_smithy_types::retry::{ErrorKind, RetryKind};
+use bytes::Bytes;
+
+fn copy_object_failure(code: &str) -> http::Response<Bytes> {
+    http::Response::builder()
+        .header("x-amz-request-id", "3B3C7C725673C630")
+        .status(200)
+        .body(Bytes::from(format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<Error>
+  <Code>{}</Code>
+  <Message>We encountered an internal error. Please try again.</Message>
+  <RequestId>4442587FB7D0A2F9</RequestId>
+</Error>"#,
+            code
+        )))
+        .unwrap()
+}
+
+#[tokio::test]
+async fn copy_object_errors_in_success_responses() {
+    let conf = aws_sdk_s3::Config::builder()
+        .credentials_provider(Credentials::new("ANOTREAL", "notreal", None, None, "test"))
+        .region(Region::new("us-east-1"))
+        .build();
+    let op = CopyObject::builder()
+        .bucket("test-bucket")
+        .key("test-key")
+        .copy_source("source-bucket/source-key")
+        .build()
+        .unwrap()
+        .make_operation(&conf)
+        .await
+        .unwrap();
+    let hook = op
+        .properties()
+        .get::<ParsedResponseHook<CopyObjectOutput, CopyObjectError>>()
+        .cloned()
+        .expect("S3 operations inspect errors in success responses");
+
+    let classify = |code: &str| {
+        let response = copy_object_failure(code);
+        let parsed = CopyObject::new().parse_loaded(&response);
+        let mut raw = operation::Response::new(response.map(SdkBody::from));
+        let err = hook
+            .apply(parsed, &mut raw)
+            .expect_err("the body of the 200 response was an error");
+        assert_eq!(Some(code), err.code());
+        let result: Result<SdkSuccess<CopyObjectOutput>, _> =
+            Err(SdkError::ServiceError { err, raw });
+        AwsErrorRetryPolicy::new().classify(result.as_ref())
+    };
+
+    assert_eq!(
+        RetryKind::Error(ErrorKind::TransientError),
+        classify("InternalError")
+    );
+    assert_eq!(RetryKind::UnretryableFailure, classify("AccessDenied"));
+}
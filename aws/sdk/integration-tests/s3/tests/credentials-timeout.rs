@@ -0,0 +1,61 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use aws_sdk_s3::{Client, Config, Region};
+use aws_smithy_async::rt::sleep::{AsyncSleep, TokioSleep};
+use aws_smithy_client::never::NeverService;
+use aws_smithy_http::body::SdkBody;
+use aws_smithy_http::result::{ConnectorError, SdkError};
+use aws_smithy_http_tower::map_request::{StageError, StageTimeoutError};
+use aws_types::credentials::{future, ProvideCredentials};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// A credentials provider that never responds, like a hung IMDS call
+#[derive(Debug)]
+struct NeverProvider;
+
+impl ProvideCredentials for NeverProvider {
+    fn provide_credentials<'a>(&'a self) -> future::ProvideCredentials<'a>
+    where
+        Self: 'a,
+    {
+        future::ProvideCredentials::new(std::future::pending())
+    }
+}
+
+#[tokio::test]
+async fn credentials_that_never_load_time_out_by_default() {
+    let conn: NeverService<http::Request<SdkBody>, http::Response<SdkBody>, ConnectorError> =
+        NeverService::new();
+    // no API call timeout is configured, so only the default credentials timeout can end the request
+    let sleep_impl: Arc<dyn AsyncSleep> = Arc::new(TokioSleep::new());
+    let config = Config::builder()
+        .region(Region::from_static("us-east-2"))
+        .credentials_provider(NeverProvider)
+        .sleep_impl(sleep_impl)
+        .build();
+    let client = Client::from_conf_conn(config, conn);
+
+    tokio::time::pause();
+    let err = client
+        .list_buckets()
+        .send()
+        .await
+        .expect_err("loading credentials times out");
+
+    let err = match err {
+        SdkError::ConstructionFailure(err) => err,
+        other => panic!("expected a construction failure, got {:?}", other),
+    };
+    let stage_error = err
+        .downcast_ref::<StageError>()
+        .expect("construction failures are stage errors");
+    assert_eq!("credentials", stage_error.stage_name());
+    let timeout_error = std::error::Error::source(stage_error)
+        .and_then(|source| source.downcast_ref::<StageTimeoutError>())
+        .expect("the source is a timeout");
+    assert_eq!(Duration::from_secs(10), timeout_error.duration());
+}
@@ -3,6 +3,8 @@
  * SPDX-License-Identifier: Apache-2.0.
  */
 
+mod common;
+
 use aws_sdk_s3 as s3;
 use aws_sdk_s3::presigning::request::PresignedRequest;
 use http::header::{CONTENT_LENGTH, CONTENT_TYPE};
@@ -22,7 +24,7 @@ macro_rules! presign_input {
             None,
             "test",
         );
-        let config = s3::Config::builder()
+        let config = crate::common::path_style_config()
             .credentials_provider(creds)
             .region(s3::Region::new("us-east-1"))
             .build();
@@ -0,0 +1,50 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use aws_sdk_s3::operation::{GetObject, ListBuckets};
+use aws_sdk_s3::types::RequestId;
+use aws_smithy_http::response::ParseHttpResponse;
+use bytes::Bytes;
+
+#[test]
+fn request_ids_on_success() {
+    let resp = http::Response::builder()
+        .header("x-amz-request-id", "correct-request-id")
+        .header("x-amz-id-2", "correct-extended-request-id")
+        .status(200)
+        .body(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<ListAllMyBucketsResult xmlns="http://s3.amazonaws.com/doc/2006-03-01/">
+  <Buckets></Buckets>
+</ListAllMyBucketsResult>"#,
+        )
+        .unwrap();
+    let output = ListBuckets::new()
+        .parse_loaded(&resp.map(Bytes::from))
+        .expect("valid successful response");
+    assert_eq!(Some("correct-request-id"), output.request_id());
+    assert_eq!(
+        Some("correct-extended-request-id"),
+        output.extended_request_id()
+    );
+}
+
+#[test]
+fn request_ids_on_error_without_body() {
+    let resp = http::Response::builder()
+        .header("x-amz-request-id", "correct-request-id")
+        .header("x-amz-id-2", "correct-extended-request-id")
+        .status(404)
+        .body("")
+        .unwrap();
+    let err = GetObject::new()
+        .parse_loaded(&resp.map(Bytes::from))
+        .expect_err("status was 404, this is an error");
+    assert_eq!(Some("correct-request-id"), RequestId::request_id(&err));
+    assert_eq!(
+        Some("correct-extended-request-id"),
+        err.extended_request_id()
+    );
+}
@@ -3,11 +3,13 @@
  * SPDX-License-Identifier: Apache-2.0.
  */
 
+mod common;
+
 use aws_sdk_s3::model::{
     CompressionType, CsvInput, CsvOutput, ExpressionType, FileHeaderInfo, InputSerialization,
     OutputSerialization, SelectObjectContentEventStream,
 };
-use aws_sdk_s3::{Client, Config, Credentials, Region};
+use aws_sdk_s3::{Client, Credentials, Region};
 use aws_smithy_client::dvr::{Event, ReplayingConnection};
 use aws_smithy_protocol_test::{assert_ok, validate_body, MediaType};
 use std::error::Error as StdError;
@@ -20,7 +22,7 @@ async fn test_success() {
 
     let region = Region::from_static("us-east-2");
     let credentials = Credentials::new("test", "test", None, None, "test");
-    let config = Config::builder()
+    let config = common::path_style_config()
         .region(region)
         .credentials_provider(credentials)
         .build();
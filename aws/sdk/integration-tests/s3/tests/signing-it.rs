@@ -3,6 +3,8 @@
  * SPDX-License-Identifier: Apache-2.0.
  */
 
+mod common;
+
 use aws_http::user_agent::AwsUserAgent;
 use aws_sdk_s3::middleware::DefaultMiddleware;
 use aws_sdk_s3::operation::ListObjectsV2;
@@ -22,7 +24,7 @@ async fn test_signer() -> Result<(), aws_sdk_s3::Error> {
         None,
         "test",
     );
-    let conf = aws_sdk_s3::Config::builder()
+    let conf = common::path_style_config()
         .credentials_provider(creds)
         .region(Region::new("us-east-1"))
         .build();
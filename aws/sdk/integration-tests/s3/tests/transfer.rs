@@ -0,0 +1,135 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use aws_sdk_s3::transfer::{Downloader, ObjectDownload, ObjectUpload, Uploader};
+use aws_sdk_s3::types::ByteStream;
+use aws_sdk_s3::{Client, Config, Credentials, Region};
+use aws_smithy_client::test_connection::TestConnection;
+use aws_smithy_http::body::SdkBody;
+
+fn client(conn: TestConnection<&'static str>) -> Client {
+    let conf = Config::builder()
+        .credentials_provider(Credentials::new("test", "test", None, None, "test"))
+        .region(Region::new("us-east-1"))
+        .build();
+    Client::from_conf_conn(conf, conn)
+}
+
+fn ok(body: &'static str) -> http::Response<&'static str> {
+    http::Response::builder().status(200).body(body).unwrap()
+}
+
+fn part_uploaded(e_tag: &'static str) -> http::Response<&'static str> {
+    http::Response::builder()
+        .status(200)
+        .header("ETag", e_tag)
+        .body("")
+        .unwrap()
+}
+
+/// Failed parts are retried by the client's retry policy, without restarting the upload
+#[tokio::test(start_paused = true)]
+async fn failed_parts_are_retried_by_the_client() {
+    let request = || http::Request::new(SdkBody::empty());
+    let conn = TestConnection::new(vec![
+        (
+            request(),
+            ok(r#"<?xml version="1.0" encoding="UTF-8"?>
+<InitiateMultipartUploadResult xmlns="http://s3.amazonaws.com/doc/2006-03-01/">
+  <Bucket>test-bucket</Bucket>
+  <Key>key</Key>
+  <UploadId>upload-id</UploadId>
+</InitiateMultipartUploadResult>"#),
+        ),
+        (
+            request(),
+            http::Response::builder()
+                .status(500)
+                .body(
+                    r#"<?xml version="1.0" encoding="UTF-8"?>
+<Error>
+  <Code>InternalError</Code>
+  <Message>We encountered an internal error. Please try again.</Message>
+</Error>"#,
+                )
+                .unwrap(),
+        ),
+        (request(), part_uploaded("etag-1")),
+        (request(), part_uploaded("etag-2")),
+        (
+            request(),
+            ok(r#"<?xml version="1.0" encoding="UTF-8"?>
+<CompleteMultipartUploadResult xmlns="http://s3.amazonaws.com/doc/2006-03-01/">
+  <Bucket>test-bucket</Bucket>
+  <Key>key</Key>
+  <ETag>etag-2</ETag>
+</CompleteMultipartUploadResult>"#),
+        ),
+    ]);
+    let upload = ObjectUpload::new(client(conn.clone()), "test-bucket", "key");
+    let output = Uploader::new()
+        .with_part_size(5)
+        .with_concurrency(1)
+        .upload(&upload, ByteStream::from_static(b"helloworld"))
+        .await
+        .expect("the failed part is retried");
+    assert_eq!(Some("etag-2"), output.e_tag());
+
+    let requests = conn.requests();
+    let parts: Vec<_> = requests[1..4]
+        .iter()
+        .map(|request| {
+            let query = request.actual.uri().query().unwrap();
+            assert!(query.contains("uploadId=upload-id"), "{}", query);
+            request.actual.body().bytes().unwrap()
+        })
+        .collect();
+    assert_eq!(vec![&b"hello"[..], b"hello", b"world"], parts);
+    let complete = std::str::from_utf8(requests[4].actual.body().bytes().unwrap()).unwrap();
+    assert!(
+        complete.contains("<ETag>etag-1</ETag><PartNumber>1</PartNumber>"),
+        "{}",
+        complete
+    );
+}
+
+/// Every range is requested from the version of the object that was current when the download started
+#[tokio::test]
+async fn ranges_are_pinned_to_the_object_version() {
+    let request = || http::Request::new(SdkBody::empty());
+    let conn = TestConnection::new(vec![
+        (
+            request(),
+            http::Response::builder()
+                .status(200)
+                .header("Content-Length", "10")
+                .header("ETag", "etag-1")
+                .header("x-amz-version-id", "version-1")
+                .body("")
+                .unwrap(),
+        ),
+        (request(), ok("hello")),
+        (request(), ok("world")),
+    ]);
+
+    let download = ObjectDownload::new(client(conn.clone()), "test-bucket", "key");
+    let mut output = Vec::new();
+    Downloader::new()
+        .with_part_size(5)
+        .with_concurrency(1)
+        .download(download, &mut output)
+        .await
+        .expect("success");
+    assert_eq!(b"helloworld"[..], output[..]);
+
+    let requests = conn.requests();
+    for (request, range) in requests[1..].iter().zip(["bytes=0-4", "bytes=5-9"]) {
+        let request = &request.actual;
+        assert_eq!(range, request.headers()["Range"]);
+        assert_eq!("etag-1", request.headers()["If-Match"]);
+        let query = request.uri().query().unwrap();
+        assert!(query.contains("versionId=version-1"), "{}", query);
+    }
+}
@@ -0,0 +1,36 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use aws_sdk_sts::{Region, StsRegionalEndpoints};
+use aws_smithy_client::test_connection::capture_request;
+
+async fn request_uri(region: &'static str, setting: Option<StsRegionalEndpoints>) -> String {
+    let mut conf = aws_sdk_sts::Config::builder().region(Region::new(region));
+    conf.set_sts_regional_endpoints(setting);
+    let (server, request) = capture_request(None);
+    let client = aws_sdk_sts::Client::from_conf_conn(conf.build(), server);
+    let _ = client.assume_role_with_web_identity().send().await;
+    request.expect_request().uri().to_string()
+}
+
+#[tokio::test]
+async fn regional_endpoints_by_default() {
+    assert_eq!(
+        request_uri("us-west-2", None).await,
+        "https://sts.us-west-2.amazonaws.com/"
+    );
+}
+
+#[tokio::test]
+async fn legacy_regions_use_the_global_endpoint() {
+    assert_eq!(
+        request_uri("us-west-2", Some(StsRegionalEndpoints::Legacy)).await,
+        "https://sts.amazonaws.com/"
+    );
+    assert_eq!(
+        request_uri("af-south-1", Some(StsRegionalEndpoints::Legacy)).await,
+        "https://sts.af-south-1.amazonaws.com/"
+    );
+}
@@ -16,6 +16,8 @@ object CrateSet {
 
     private val SMITHY_RUNTIME_COMMON = listOf(
         "aws-smithy-async",
+        "aws-smithy-cbor",
+        "aws-smithy-checksums",
         "aws-smithy-client",
         "aws-smithy-eventstream",
         "aws-smithy-http",
@@ -28,7 +30,7 @@ object CrateSet {
         "aws-smithy-xml"
     )
 
-    val AWS_SDK_SMITHY_RUNTIME = SMITHY_RUNTIME_COMMON
+    val AWS_SDK_SMITHY_RUNTIME = SMITHY_RUNTIME_COMMON + listOf("aws-smithy-transfer")
 
     val SERVER_SMITHY_RUNTIME = SMITHY_RUNTIME_COMMON + listOf("aws-smithy-http-server")
 
@@ -167,7 +167,7 @@ class ServerCodegenVisitor(context: PluginContext, private val codegenDecorator:
         rustCrate.useShapeWriter(shape) { writer ->
             StructureGenerator(model, symbolProvider, writer, shape).render(CodegenTarget.SERVER)
             val builderGenerator =
-                BuilderGenerator(codegenContext.model, codegenContext.symbolProvider, shape)
+                BuilderGenerator(codegenContext.model, codegenContext.symbolProvider, shape, CodegenTarget.SERVER)
             builderGenerator.render(writer)
             writer.implBlock(shape, symbolProvider) {
                 builderGenerator.renderConvenienceMethod(this)
@@ -101,8 +101,17 @@ class InlineDependency(
         fun jsonErrors(runtimeConfig: RuntimeConfig) =
             forRustFile("json_errors", CargoDependency.Http, CargoDependency.SmithyTypes(runtimeConfig))
 
-        fun idempotencyToken() =
-            forRustFile("idempotency_token", CargoDependency.FastRand)
+        fun awsQueryCompatibleErrors(runtimeConfig: RuntimeConfig) =
+            forRustFile(
+                "aws_query_compatible_errors",
+                "inlineable",
+                public = true,
+                CargoDependency.Http,
+                CargoDependency.SmithyTypes(runtimeConfig)
+            )
+
+        fun idempotencyToken(runtimeConfig: RuntimeConfig) =
+            forRustFile("idempotency_token", CargoDependency.FastRand, CargoDependency.SmithyTypes(runtimeConfig))
 
         fun ec2QueryErrors(runtimeConfig: RuntimeConfig): InlineDependency =
             forRustFile("ec2_query_errors", CargoDependency.smithyXml(runtimeConfig))
@@ -204,6 +213,7 @@ data class CargoDependency(
         val PrettyAssertions: CargoDependency = CargoDependency("pretty_assertions", CratesIo("1"), scope = DependencyScope.Dev)
         val Regex: CargoDependency = CargoDependency("regex", CratesIo("1"))
         val Ring: CargoDependency = CargoDependency("ring", CratesIo("0.16"))
+        val Serde: CargoDependency = CargoDependency("serde", CratesIo("1"), features = setOf("derive"))
         val TempFile: CargoDependency = CargoDependency("temp-file", CratesIo("0.1.6"), scope = DependencyScope.Dev)
         val TokioStream: CargoDependency = CargoDependency("tokio-stream", CratesIo("0.1.7"))
         val Tower: CargoDependency = CargoDependency("tower", CratesIo("0.4"))
@@ -212,11 +222,13 @@ data class CargoDependency(
         fun SmithyTypes(runtimeConfig: RuntimeConfig) = runtimeConfig.runtimeCrate("types")
         fun SmithyClient(runtimeConfig: RuntimeConfig) = runtimeConfig.runtimeCrate("client")
         fun SmithyAsync(runtimeConfig: RuntimeConfig) = runtimeConfig.runtimeCrate("async")
+        fun SmithyChecksums(runtimeConfig: RuntimeConfig) = runtimeConfig.runtimeCrate("checksums")
         fun SmithyEventStream(runtimeConfig: RuntimeConfig) = runtimeConfig.runtimeCrate("eventstream")
         fun SmithyHttp(runtimeConfig: RuntimeConfig) = runtimeConfig.runtimeCrate("http")
         fun SmithyHttpTower(runtimeConfig: RuntimeConfig) = runtimeConfig.runtimeCrate("http-tower")
         fun SmithyProtocolTestHelpers(runtimeConfig: RuntimeConfig) =
             runtimeConfig.runtimeCrate("protocol-test").copy(scope = DependencyScope.Dev)
+        fun smithyCbor(runtimeConfig: RuntimeConfig): CargoDependency = runtimeConfig.runtimeCrate("cbor")
         fun smithyJson(runtimeConfig: RuntimeConfig): CargoDependency = runtimeConfig.runtimeCrate("json")
         fun smithyQuery(runtimeConfig: RuntimeConfig): CargoDependency = runtimeConfig.runtimeCrate("query")
         fun smithyXml(runtimeConfig: RuntimeConfig): CargoDependency = runtimeConfig.runtimeCrate("xml")
@@ -187,6 +187,18 @@ data class RuntimeType(val name: String?, val dependency: RustDependency?, val n
         fun Document(runtimeConfig: RuntimeConfig): RuntimeType =
             RuntimeType("Document", CargoDependency.SmithyTypes(runtimeConfig), "${runtimeConfig.crateSrcPrefix}_types")
 
+        fun UnknownVariantValue(runtimeConfig: RuntimeConfig): RuntimeType =
+            RuntimeType("UnknownVariantValue", CargoDependency.SmithyTypes(runtimeConfig), "${runtimeConfig.crateSrcPrefix}_types")
+
+        fun UnknownMemberName(runtimeConfig: RuntimeConfig): RuntimeType =
+            RuntimeType("UnknownMemberName", CargoDependency.SmithyTypes(runtimeConfig), "${runtimeConfig.crateSrcPrefix}_types")
+
+        fun Sensitive(runtimeConfig: RuntimeConfig) =
+            RuntimeType("Sensitive", CargoDependency.SmithyTypes(runtimeConfig), "${runtimeConfig.crateSrcPrefix}_types::sensitive")
+
+        fun Validation(runtimeConfig: RuntimeConfig, func: String) =
+            RuntimeType(func, CargoDependency.SmithyTypes(runtimeConfig), "${runtimeConfig.crateSrcPrefix}_types::validation")
+
         fun LabelFormat(runtimeConfig: RuntimeConfig, func: String) =
             RuntimeType(func, CargoDependency.SmithyHttp(runtimeConfig), "${runtimeConfig.crateSrcPrefix}_http::label")
 
@@ -245,7 +257,11 @@ data class RuntimeType(val name: String?, val dependency: RustDependency?, val n
         fun jsonErrors(runtimeConfig: RuntimeConfig) =
             forInlineDependency(InlineDependency.jsonErrors(runtimeConfig))
 
-        val IdempotencyToken by lazy { forInlineDependency(InlineDependency.idempotencyToken()) }
+        fun awsQueryCompatibleErrors(runtimeConfig: RuntimeConfig) =
+            forInlineDependency(InlineDependency.awsQueryCompatibleErrors(runtimeConfig))
+
+        fun idempotencyToken(runtimeConfig: RuntimeConfig) =
+            forInlineDependency(InlineDependency.idempotencyToken(runtimeConfig))
 
         val Config = RuntimeType("config", null, "crate")
 
@@ -270,6 +286,18 @@ data class RuntimeType(val name: String?, val dependency: RustDependency?, val n
             namespace = "aws_smithy_http::response"
         )
 
+        fun requestId(runtimeConfig: RuntimeConfig) = RuntimeType(
+            "RequestId",
+            dependency = CargoDependency.SmithyHttp(runtimeConfig),
+            namespace = "aws_smithy_http::request_id"
+        )
+
+        fun responseParseError(runtimeConfig: RuntimeConfig) = RuntimeType(
+            "ResponseParseError",
+            dependency = CargoDependency.SmithyHttp(runtimeConfig),
+            namespace = "aws_smithy_http::result"
+        )
+
         val Bytes = RuntimeType("Bytes", dependency = CargoDependency.Bytes, namespace = "bytes")
 
         fun forInlineDependency(inlineDependency: InlineDependency) =
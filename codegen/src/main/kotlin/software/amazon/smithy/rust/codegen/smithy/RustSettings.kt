@@ -48,6 +48,8 @@ data class CodegenConfig(
     val debugMode: Boolean = false,
     // TODO(EventStream): [CLEANUP] Remove this property when turning on Event Stream for all services
     val eventStreamAllowList: Set<String> = emptySet(),
+    /** Check the `@length` and `@range` traits of members in the `build()` method of fallible builders */
+    val validateConstraints: Boolean = false,
 ) {
     companion object {
         fun fromNode(node: Optional<ObjectNode>): CodegenConfig {
@@ -60,7 +62,8 @@ data class CodegenConfig(
                     node.get().getBooleanMemberOrDefault("debugMode", false),
                     node.get().getArrayMember("eventStreamAllowList")
                         .map { array -> array.toList().mapNotNull { node -> node.asStringNode().orNull()?.value } }
-                        .orNull()?.toSet() ?: emptySet()
+                        .orNull()?.toSet() ?: emptySet(),
+                    node.get().getBooleanMemberOrDefault("validateConstraints", false)
                 )
             } else {
                 CodegenConfig()
@@ -0,0 +1,45 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+package software.amazon.smithy.rust.codegen.smithy.customizations
+
+import software.amazon.smithy.model.shapes.OperationShape
+import software.amazon.smithy.model.traits.IdempotentTrait
+import software.amazon.smithy.model.traits.ReadonlyTrait
+import software.amazon.smithy.rust.codegen.rustlang.CargoDependency
+import software.amazon.smithy.rust.codegen.rustlang.Writable
+import software.amazon.smithy.rust.codegen.rustlang.asType
+import software.amazon.smithy.rust.codegen.rustlang.rust
+import software.amazon.smithy.rust.codegen.rustlang.writable
+import software.amazon.smithy.rust.codegen.smithy.CodegenContext
+import software.amazon.smithy.rust.codegen.smithy.customize.OperationCustomization
+import software.amazon.smithy.rust.codegen.smithy.customize.OperationSection
+import software.amazon.smithy.rust.codegen.util.hasTrait
+
+/**
+ * Marks `@readonly` and `@idempotent` operations as idempotent so that retry classifiers can retry failures that
+ * happen after the request was sent (e.g. a connection reset while reading the response body)
+ */
+class IdempotentOperationGenerator(
+    private val codegenContext: CodegenContext,
+    private val operationShape: OperationShape
+) : OperationCustomization() {
+    private val isIdempotent = operationShape.hasTrait<ReadonlyTrait>() || operationShape.hasTrait<IdempotentTrait>()
+
+    override fun section(section: OperationSection): Writable {
+        if (!isIdempotent) {
+            return emptySection
+        }
+        return when (section) {
+            is OperationSection.MutateRequest -> writable {
+                rust(
+                    "${section.request}.properties_mut().insert(#T);",
+                    CargoDependency.SmithyHttp(codegenContext.runtimeConfig).asType().member("retry::Idempotent")
+                )
+            }
+            else -> emptySection
+        }
+    }
+}
@@ -0,0 +1,139 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+package software.amazon.smithy.rust.codegen.smithy.customizations
+
+import software.amazon.smithy.codegen.core.Symbol
+import software.amazon.smithy.model.neighbor.Walker
+import software.amazon.smithy.model.shapes.MemberShape
+import software.amazon.smithy.model.shapes.StringShape
+import software.amazon.smithy.model.shapes.StructureShape
+import software.amazon.smithy.model.shapes.UnionShape
+import software.amazon.smithy.model.traits.EnumTrait
+import software.amazon.smithy.rust.codegen.rustlang.Attribute
+import software.amazon.smithy.rust.codegen.rustlang.CargoDependency
+import software.amazon.smithy.rust.codegen.rustlang.Feature
+import software.amazon.smithy.rust.codegen.rustlang.RustMetadata
+import software.amazon.smithy.rust.codegen.rustlang.RustWriter
+import software.amazon.smithy.rust.codegen.rustlang.asType
+import software.amazon.smithy.rust.codegen.rustlang.rustTemplate
+import software.amazon.smithy.rust.codegen.smithy.CodegenContext
+import software.amazon.smithy.rust.codegen.smithy.RuntimeType
+import software.amazon.smithy.rust.codegen.smithy.RustCrate
+import software.amazon.smithy.rust.codegen.smithy.RustSymbolProvider
+import software.amazon.smithy.rust.codegen.smithy.SymbolMetadataProvider
+import software.amazon.smithy.rust.codegen.smithy.customize.RustCodegenDecorator
+import software.amazon.smithy.rust.codegen.smithy.expectRustMetadata
+import software.amazon.smithy.rust.codegen.util.hasTrait
+
+/**
+ * Derive serde's `Serialize` and `Deserialize` for generated shapes behind crate features
+ *
+ * The generated crate gets a `serde-serialize` and a `serde-deserialize` feature. They enable the features of the same
+ * name in `aws-smithy-types`, so that shapes containing a `Blob`, `DateTime`, or `Document` can derive serde's traits too.
+ *
+ * Enums aren't derived, since that would use the names of their Rust variants (e.g. `T2Micro`). They're serialized as
+ * their value in the model (e.g. `t2.micro`) instead, see [renderEnumSerde].
+ *
+ * # Notes
+ * This decorator is not part of the generic client codegen. The AWS SDK includes it in its list of decorators, other
+ * code generators must include it themselves.
+ */
+class SerdeDecorator : RustCodegenDecorator {
+    override val name: String = "Serde"
+    override val order: Byte = 0
+
+    override fun symbolProvider(baseProvider: RustSymbolProvider): RustSymbolProvider =
+        SerdeMetadataProvider(baseProvider)
+
+    override fun extras(codegenContext: CodegenContext, rustCrate: RustCrate) {
+        val smithyTypes = CargoDependency.SmithyTypes(codegenContext.runtimeConfig).name
+        for (feature in listOf(SerializeFeature, DeserializeFeature)) {
+            rustCrate.mergeFeature(Feature(feature, default = false, listOf("serde", "$smithyTypes/$feature")))
+        }
+        Walker(codegenContext.model).walkShapes(codegenContext.serviceShape)
+            .filterIsInstance<StringShape>()
+            .filter { it.hasTrait<EnumTrait>() }
+            .forEach { shape ->
+                rustCrate.useShapeWriter(shape) { writer ->
+                    writer.renderEnumSerde(codegenContext.symbolProvider.toSymbol(shape))
+                }
+            }
+    }
+
+    companion object {
+        const val SerializeFeature = "serde-serialize"
+        const val DeserializeFeature = "serde-deserialize"
+    }
+}
+
+/**
+ * Implement serde's `Serialize` and `Deserialize` for the generated enum [enumSymbol] through its `as_str()` and
+ * `From<&str>` implementations, so that it's serialized as its value in the model
+ */
+fun RustWriter.renderEnumSerde(enumSymbol: Symbol) {
+    val serde = CargoDependency.Serde.copy(optional = true).asType()
+    rustTemplate(
+        """
+        ##[cfg(feature = "${SerdeDecorator.SerializeFeature}")]
+        impl #{Serialize} for #{Enum} {
+            fn serialize<S: #{Serializer}>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+                serializer.serialize_str(self.as_str())
+            }
+        }
+
+        ##[cfg(feature = "${SerdeDecorator.DeserializeFeature}")]
+        impl<'de> #{Deserialize}<'de> for #{Enum} {
+            fn deserialize<D: #{Deserializer}<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+                let value = <String as #{Deserialize}<'de>>::deserialize(deserializer)?;
+                Ok(#{Enum}::from(value.as_str()))
+            }
+        }
+        """,
+        "Enum" to enumSymbol,
+        "Serialize" to serde.member("Serialize"),
+        "Serializer" to serde.member("Serializer"),
+        "Deserialize" to serde.member("Deserialize"),
+        "Deserializer" to serde.member("Deserializer"),
+    )
+}
+
+/**
+ * Add `#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]` (and the equivalent for `Deserialize`) to
+ * generated structures and unions
+ *
+ * Streaming shapes are skipped: byte streams and event streams can't be serialized, which is also why they don't derive
+ * `Clone` (see [software.amazon.smithy.rust.codegen.smithy.StreamingShapeMetadataProvider]).
+ */
+class SerdeMetadataProvider(private val base: RustSymbolProvider) : SymbolMetadataProvider(base) {
+    private val serde = CargoDependency.Serde.copy(optional = true).asType()
+    private val serdeAttributes = listOf(
+        Attribute.Custom(
+            "cfg_attr(feature = \"${SerdeDecorator.SerializeFeature}\", derive(serde::Serialize))",
+            listOf(serde)
+        ),
+        Attribute.Custom(
+            "cfg_attr(feature = \"${SerdeDecorator.DeserializeFeature}\", derive(serde::Deserialize))",
+            listOf(serde)
+        ),
+    )
+
+    private fun RustMetadata.withSerde(): RustMetadata = if (derives.derives.contains(RuntimeType.Clone)) {
+        copy(additionalAttributes = additionalAttributes + serdeAttributes)
+    } else this
+
+    override fun memberMeta(memberShape: MemberShape): RustMetadata =
+        base.toSymbol(memberShape).expectRustMetadata()
+
+    override fun structureMeta(structureShape: StructureShape): RustMetadata =
+        base.toSymbol(structureShape).expectRustMetadata().withSerde()
+
+    override fun unionMeta(unionShape: UnionShape): RustMetadata =
+        base.toSymbol(unionShape).expectRustMetadata().withSerde()
+
+    // enums implement serde's traits by hand, see [renderEnumSerde]
+    override fun enumMeta(stringShape: StringShape): RustMetadata =
+        base.toSymbol(stringShape).expectRustMetadata()
+}
@@ -62,6 +62,7 @@ internal fun pubUseTypes(runtimeConfig: RuntimeConfig, model: Model): List<Runti
         ) + CargoDependency.SmithyHttp(runtimeConfig).asType().let { http ->
             listOf(
                 PubUseType(http.member("result::SdkError")) { true },
+                PubUseType(http.member("request_id::RequestId")) { true },
                 PubUseType(http.member("byte_stream::ByteStream"), ::hasStreamingOperations),
                 PubUseType(http.member("byte_stream::AggregatedBytes"), ::hasStreamingOperations),
             )
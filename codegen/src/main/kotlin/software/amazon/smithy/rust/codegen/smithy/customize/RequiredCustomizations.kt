@@ -15,6 +15,7 @@ import software.amazon.smithy.rust.codegen.smithy.customizations.EndpointPrefixG
 import software.amazon.smithy.rust.codegen.smithy.customizations.HttpChecksumRequiredGenerator
 import software.amazon.smithy.rust.codegen.smithy.customizations.HttpVersionListCustomization
 import software.amazon.smithy.rust.codegen.smithy.customizations.IdempotencyTokenGenerator
+import software.amazon.smithy.rust.codegen.smithy.customizations.IdempotentOperationGenerator
 import software.amazon.smithy.rust.codegen.smithy.customizations.SmithyTypesPubUseGenerator
 import software.amazon.smithy.rust.codegen.smithy.generators.LibRsCustomization
 
@@ -35,6 +36,7 @@ class RequiredCustomizations : RustCodegenDecorator {
             IdempotencyTokenGenerator(codegenContext, operation) +
             EndpointPrefixGenerator(codegenContext, operation) +
             HttpChecksumRequiredGenerator(codegenContext, operation) +
+            IdempotentOperationGenerator(codegenContext, operation) +
             HttpVersionListCustomization(codegenContext, operation)
     }
 
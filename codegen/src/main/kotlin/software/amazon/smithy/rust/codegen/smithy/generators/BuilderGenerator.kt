@@ -9,6 +9,9 @@ import software.amazon.smithy.codegen.core.Symbol
 import software.amazon.smithy.model.Model
 import software.amazon.smithy.model.shapes.MemberShape
 import software.amazon.smithy.model.shapes.StructureShape
+import software.amazon.smithy.model.traits.EnumTrait
+import software.amazon.smithy.model.traits.LengthTrait
+import software.amazon.smithy.model.traits.RangeTrait
 import software.amazon.smithy.rust.codegen.rustlang.Attribute
 import software.amazon.smithy.rust.codegen.rustlang.RustReservedWords
 import software.amazon.smithy.rust.codegen.rustlang.RustType
@@ -21,6 +24,7 @@ import software.amazon.smithy.rust.codegen.rustlang.documentShape
 import software.amazon.smithy.rust.codegen.rustlang.render
 import software.amazon.smithy.rust.codegen.rustlang.rust
 import software.amazon.smithy.rust.codegen.rustlang.rustBlock
+import software.amazon.smithy.rust.codegen.rustlang.rustTemplate
 import software.amazon.smithy.rust.codegen.rustlang.stripOuter
 import software.amazon.smithy.rust.codegen.rustlang.withBlock
 import software.amazon.smithy.rust.codegen.smithy.Default
@@ -33,6 +37,8 @@ import software.amazon.smithy.rust.codegen.smithy.isOptional
 import software.amazon.smithy.rust.codegen.smithy.makeOptional
 import software.amazon.smithy.rust.codegen.smithy.rustType
 import software.amazon.smithy.rust.codegen.util.dq
+import software.amazon.smithy.rust.codegen.util.isStreaming
+import software.amazon.smithy.rust.codegen.util.orNull
 import software.amazon.smithy.rust.codegen.util.toSnakeCase
 
 fun StructureShape.builderSymbol(symbolProvider: RustSymbolProvider): Symbol {
@@ -53,6 +59,7 @@ fun RuntimeConfig.serializationError() = RuntimeType.operationModule(this).membe
 class OperationBuildError(private val runtimeConfig: RuntimeConfig) {
     fun missingField(w: RustWriter, field: String, details: String) = "${w.format(runtimeConfig.operationBuildError())}::MissingField { field: ${field.dq()}, details: ${details.dq()} }"
     fun invalidField(w: RustWriter, field: String, details: String) = "${w.format(runtimeConfig.operationBuildError())}::InvalidField { field: ${field.dq()}, details: ${details.dq()}.to_string() }"
+    fun invalidLabel(w: RustWriter, field: String, details: String) = "${w.format(runtimeConfig.operationBuildError())}::InvalidLabel { field: ${field.dq()}, details: ${details.dq()} }"
     fun serializationError(w: RustWriter, error: String) = "${w.format(runtimeConfig.operationBuildError())}::SerializationError($error.into())"
 }
 
@@ -62,11 +69,14 @@ fun MemberShape.setterName(): String = "set_${this.memberName.toSnakeCase()}"
 class BuilderGenerator(
     private val model: Model,
     private val symbolProvider: RustSymbolProvider,
-    private val shape: StructureShape
+    private val shape: StructureShape,
+    forWhom: CodegenTarget = CodegenTarget.CLIENT,
 ) {
     private val runtimeConfig = symbolProvider.config().runtimeConfig
     private val members: List<MemberShape> = shape.allMembers.values.toList()
     private val structureSymbol = symbolProvider.toSymbol(shape)
+    private val withRequestIds = StructureGenerator.hasRequestIds(shape, forWhom)
+    private val validateConstraints = symbolProvider.config().codegenConfig.validateConstraints
 
     fun render(writer: RustWriter) {
         val symbol = symbolProvider.toSymbol(shape)
@@ -86,6 +96,9 @@ class BuilderGenerator(
         }
         implBlockWriter.docs("Consumes the builder and constructs a #D", outputSymbol)
         implBlockWriter.rustBlock("pub fn build(self) -> $returnType") {
+            if (fallibleBuilder && validateConstraints) {
+                members.forEach { member -> renderConstraintChecks(member) }
+            }
             conditionalBlock("Ok(", ")", conditional = fallibleBuilder) {
                 // If a wrapper is specified, use the `::new` associated function to construct the wrapper
                 coreBuilder(this)
@@ -105,6 +118,57 @@ class BuilderGenerator(
         )
     }
 
+    /**
+     * Checks the `@length` and `@range` traits of [member] with the shared `validation` module when it is set,
+     * returning a constraint violation from `build()` when it doesn't satisfy them. Only fallible builders perform
+     * these checks, and only when the `validateConstraints` setting is enabled, since services may accept values
+     * that their models don't.
+     */
+    private fun RustWriter.renderConstraintChecks(member: MemberShape) {
+        val memberName = symbolProvider.toMemberName(member)
+        val target = model.expectShape(member.target)
+        val length = member.getMemberTrait(model, LengthTrait::class.java).orNull()
+        val range = member.getMemberTrait(model, RangeTrait::class.java).orNull()
+        val hasLength = when {
+            target.isStringShape -> !target.hasTrait(EnumTrait::class.java)
+            target.isBlobShape -> !member.isStreaming(model)
+            else -> target.isListShape || target.isSetShape || target.isMapShape
+        }
+        if (length != null && hasLength && (length.min.isPresent || length.max.isPresent)) {
+            val bound = { bound: Long? -> bound?.let { "Some($it)" } ?: "None" }
+            rustTemplate(
+                """
+                if let Some(value) = &self.$memberName {
+                    #{check_length}(${memberName.dq()}, value, ${bound(length.min.orNull())}, ${bound(length.max.orNull())})?;
+                }
+                """,
+                "check_length" to RuntimeType.Validation(runtimeConfig, "check_length")
+            )
+        }
+        val numeric = when {
+            target.isFloatShape || target.isDoubleShape -> "f64"
+            target.isByteShape || target.isShortShape || target.isIntegerShape || target.isLongShape -> "i64"
+            else -> null
+        }
+        if (range != null && numeric != null && (range.min.isPresent || range.max.isPresent)) {
+            val bound = { bound: java.math.BigDecimal? ->
+                when {
+                    bound == null -> "None"
+                    numeric == "f64" -> "Some(${bound.toDouble()}_f64)"
+                    else -> "Some(${bound.toBigInteger()}_i64)"
+                }
+            }
+            rustTemplate(
+                """
+                if let Some(value) = &self.$memberName {
+                    #{check_range}(${memberName.dq()}, $numeric::from(*value), ${bound(range.min.orNull())}, ${bound(range.max.orNull())})?;
+                }
+                """,
+                "check_range" to RuntimeType.Validation(runtimeConfig, "check_range")
+            )
+        }
+    }
+
     fun renderConvenienceMethod(implBlock: RustWriter) {
         val builderSymbol = shape.builderSymbol(symbolProvider)
         implBlock.docs("Creates a new builder-style object to manufacture #D", structureSymbol)
@@ -168,6 +232,10 @@ class BuilderGenerator(
                 val memberSymbol = symbolProvider.toSymbol(member).makeOptional()
                 renderBuilderMember(this, memberName, memberSymbol)
             }
+            if (withRequestIds) {
+                rust("_request_id: Option<String>,")
+                rust("_extended_request_id: Option<String>,")
+            }
         }
 
         writer.rustBlock("impl $builderName") {
@@ -187,10 +255,32 @@ class BuilderGenerator(
 
                 renderBuilderMemberSetterFn(this, outerType, member, memberName)
             }
+            if (withRequestIds) {
+                renderRequestIdSetters()
+            }
             buildFn(this)
         }
     }
 
+    private fun RustWriter.renderRequestIdSetters() {
+        // set by the response parser from the response headers
+        rust(
+            """
+            ##[doc(hidden)]
+            pub fn _set_request_id(mut self, request_id: Option<String>) -> Self {
+                self._request_id = request_id;
+                self
+            }
+
+            ##[doc(hidden)]
+            pub fn _set_extended_request_id(mut self, extended_request_id: Option<String>) -> Self {
+                self._extended_request_id = extended_request_id;
+                self
+            }
+            """
+        )
+    }
+
     private fun RustWriter.renderVecHelper(member: MemberShape, memberName: String, coreType: RustType.Vec) {
         docs("Appends an item to `$memberName`.")
         rust("///")
@@ -263,6 +353,10 @@ class BuilderGenerator(
                     }
                 }
             }
+            if (withRequestIds) {
+                rust("_request_id: self._request_id,")
+                rust("_extended_request_id: self._extended_request_id,")
+            }
         }
     }
 }
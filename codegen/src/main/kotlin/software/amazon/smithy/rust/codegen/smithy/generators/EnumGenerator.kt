@@ -82,6 +82,7 @@ class EnumGenerator(
     private val meta = symbol.expectRustMetadata()
     private val sortedMembers: List<EnumMemberModel> =
         enumTrait.values.sortedBy { it.value }.map { EnumMemberModel(it, symbolProvider) }
+    private val unknownVariantValue = RuntimeType.UnknownVariantValue(symbolProvider.config().runtimeConfig)
 
     companion object {
         /** Name of the generated unknown enum member name for enums with named members. */
@@ -152,7 +153,7 @@ class EnumGenerator(
         writer.rustBlock("enum $enumName") {
             sortedMembers.forEach { member -> member.render(writer) }
             docs("$UnknownVariant contains new variants that have been added since this code was generated.")
-            write("$UnknownVariant(String)")
+            write("$UnknownVariant(#T)", unknownVariantValue)
         }
     }
 
@@ -164,10 +165,15 @@ class EnumGenerator(
                     sortedMembers.forEach { member ->
                         write("""$enumName::${member.derivedName()} => ${member.value.dq()},""")
                     }
-                    write("$enumName::$UnknownVariant(s) => s.as_ref()")
+                    write("$enumName::$UnknownVariant(value) => value.as_str()")
                 }
             }
 
+            rust("/// Returns true if this value was not known when this code was generated.")
+            writer.rustBlock("pub fn is_unknown(&self) -> bool") {
+                write("matches!(self, $enumName::$UnknownVariant(_))")
+            }
+
             rust("/// Returns all the `&str` values of the enum members.")
             writer.rustBlock("pub fn $Values() -> &'static [&'static str]") {
                 withBlock("&[", "]") {
@@ -185,7 +191,7 @@ class EnumGenerator(
                     sortedMembers.forEach { member ->
                         write("""${member.value.dq()} => $enumName::${member.derivedName()},""")
                     }
-                    write("other => $enumName::$UnknownVariant(other.to_owned())")
+                    write("other => $enumName::$UnknownVariant(#T::from(other))", unknownVariantValue)
                 }
             }
         }
@@ -32,6 +32,7 @@ import software.amazon.smithy.rust.codegen.smithy.isOptional
 import software.amazon.smithy.rust.codegen.smithy.renamedFrom
 import software.amazon.smithy.rust.codegen.smithy.rustType
 import software.amazon.smithy.rust.codegen.smithy.traits.SyntheticInputTrait
+import software.amazon.smithy.rust.codegen.smithy.traits.SyntheticOutputTrait
 import software.amazon.smithy.rust.codegen.util.dq
 import software.amazon.smithy.rust.codegen.util.getTrait
 import software.amazon.smithy.rust.codegen.util.hasTrait
@@ -64,9 +65,10 @@ class StructureGenerator(
         else -> members.filter { "message" != symbolProvider.toMemberName(it) }
     }
     private val name = symbolProvider.toSymbol(shape).name
+    private val runtimeConfig = symbolProvider.config().runtimeConfig
 
     fun render(forWhom: CodegenTarget = CodegenTarget.CLIENT) {
-        renderStructure()
+        renderStructure(hasRequestIds(shape, forWhom))
         errorTrait?.also { errorTrait ->
             ErrorGenerator(symbolProvider, writer, shape, errorTrait).render(forWhom)
         }
@@ -84,6 +86,14 @@ class StructureGenerator(
                         // generate a fallible builder
                         !it.isOptional() && !it.canUseDefault()
                     }
+
+        /**
+         * Returns whether a structure shape carries the IDs of the request it was parsed from.
+         *
+         * Client operation outputs record them from the response headers so that they're available on success.
+         */
+        fun hasRequestIds(structureShape: StructureShape, forWhom: CodegenTarget): Boolean =
+            forWhom == CodegenTarget.CLIENT && structureShape.hasTrait<SyntheticOutputTrait>()
     }
 
     /**
@@ -105,19 +115,30 @@ class StructureGenerator(
     }
 
     /** Render a custom debug implementation
-     * When [SensitiveTrait] support is required, render a custom debug implementation to redact sensitive data
+     * When [SensitiveTrait] support is required, render a custom debug implementation that wraps sensitive members
+     * in `aws_smithy_types::sensitive::Sensitive` to redact them
      */
-    private fun renderDebugImpl() {
+    private fun renderDebugImpl(withRequestIds: Boolean) {
         writer.rustBlock("impl ${lifetimeDeclaration()} #T for $name ${lifetimeDeclaration()}", RuntimeType.Debug) {
             writer.rustBlock("fn fmt(&self, f: &mut #1T::Formatter<'_>) -> #1T::Result", RuntimeType.stdfmt) {
                 rust("""let mut formatter = f.debug_struct(${name.dq()});""")
                 members.forEach { member ->
                     val memberName = symbolProvider.toMemberName(member)
-                    val fieldValue = redactIfNecessary(
-                        member, model, "self.$memberName"
-                    )
+                    if (member.getMemberTrait(model, SensitiveTrait::class.java).isPresent) {
+                        rust(
+                            "formatter.field(${memberName.dq()}, &#T(&self.$memberName));",
+                            RuntimeType.Sensitive(runtimeConfig),
+                        )
+                    } else {
+                        rust("formatter.field(${memberName.dq()}, &self.$memberName);")
+                    }
+                }
+                if (withRequestIds) {
                     rust(
-                        "formatter.field(${memberName.dq()}, &$fieldValue);",
+                        """
+                        formatter.field("_request_id", &self._request_id);
+                        formatter.field("_extended_request_id", &self._extended_request_id);
+                        """
                     )
                 }
                 rust("formatter.finish()")
@@ -125,6 +146,19 @@ class StructureGenerator(
         }
     }
 
+    /** Render a `PartialEq` implementation that compares the modeled members only */
+    private fun renderPartialEqImpl() {
+        writer.rustBlock("impl ${lifetimeDeclaration()} #T for $name ${lifetimeDeclaration()}", RuntimeType.PartialEq) {
+            rustBlock("fn eq(&self, other: &Self) -> bool") {
+                if (members.isEmpty()) {
+                    rust("let _ = other; true")
+                } else {
+                    rust(members.joinToString(" && ") { "self.${symbolProvider.toMemberName(it)} == other.${symbolProvider.toMemberName(it)}" })
+                }
+            }
+        }
+    }
+
     private fun renderStructureImpl() {
         if (accessorMembers.isEmpty()) {
             return
@@ -153,11 +187,30 @@ class StructureGenerator(
         }
     }
 
-    private fun renderStructure() {
+    private fun renderRequestIdImpl() {
+        writer.rustBlock("impl #T for $name", RuntimeType.requestId(runtimeConfig)) {
+            rust(
+                """
+                fn request_id(&self) -> Option<&str> {
+                    self._request_id.as_deref()
+                }
+
+                fn extended_request_id(&self) -> Option<&str> {
+                    self._extended_request_id.as_deref()
+                }
+                """
+            )
+        }
+    }
+
+    private fun renderStructure(withRequestIds: Boolean) {
         val symbol = symbolProvider.toSymbol(shape)
         val containerMeta = symbol.expectRustMetadata()
         writer.documentShape(shape, model)
-        val withoutDebug = containerMeta.derives.copy(derives = containerMeta.derives.derives - RuntimeType.Debug)
+        // Request IDs differ between otherwise identical responses, so they are left out of `PartialEq`
+        val manualPartialEq = withRequestIds && containerMeta.derives.derives.contains(RuntimeType.PartialEq)
+        val manualDerives = setOfNotNull(RuntimeType.Debug, RuntimeType.PartialEq.takeIf { manualPartialEq })
+        val withoutDebug = containerMeta.derives.copy(derives = containerMeta.derives.derives - manualDerives)
         containerMeta.copy(derives = withoutDebug).render(writer)
 
         writer.rustBlock("struct $name ${lifetimeDeclaration()}") {
@@ -166,10 +219,20 @@ class StructureGenerator(
                 memberSymbol.expectRustMetadata().render(this)
                 write("$memberName: #T,", symbolProvider.toSymbol(member))
             }
+            if (withRequestIds) {
+                rust("_request_id: Option<String>,")
+                rust("_extended_request_id: Option<String>,")
+            }
         }
 
         renderStructureImpl()
-        renderDebugImpl()
+        renderDebugImpl(withRequestIds)
+        if (manualPartialEq) {
+            renderPartialEqImpl()
+        }
+        if (withRequestIds) {
+            renderRequestIdImpl()
+        }
     }
 
     private fun RustWriter.forEachMember(
@@ -5,7 +5,6 @@
 
 package software.amazon.smithy.rust.codegen.smithy.generators
 
-import software.amazon.smithy.codegen.core.SymbolProvider
 import software.amazon.smithy.model.Model
 import software.amazon.smithy.model.shapes.MemberShape
 import software.amazon.smithy.model.shapes.UnionShape
@@ -16,6 +15,8 @@ import software.amazon.smithy.rust.codegen.rustlang.documentShape
 import software.amazon.smithy.rust.codegen.rustlang.rust
 import software.amazon.smithy.rust.codegen.rustlang.rustBlock
 import software.amazon.smithy.rust.codegen.smithy.CodegenMode
+import software.amazon.smithy.rust.codegen.smithy.RuntimeType
+import software.amazon.smithy.rust.codegen.smithy.RustSymbolProvider
 import software.amazon.smithy.rust.codegen.smithy.expectRustMetadata
 import software.amazon.smithy.rust.codegen.smithy.renamedFrom
 import software.amazon.smithy.rust.codegen.util.toSnakeCase
@@ -37,15 +38,17 @@ fun CodegenMode.renderUnknownVariant() = when (this) {
  *
  * Finally, if `[renderUnknownVariant]` is true (the default), it will render an `Unknown` variant. This is used by
  * clients to allow response parsing to succeed, even if the server has added a new variant since the client was generated.
+ * The `Unknown` variant holds the name of the member that was received, which is returned by `unknown_member_name()`.
  */
 class UnionGenerator(
     val model: Model,
-    private val symbolProvider: SymbolProvider,
+    private val symbolProvider: RustSymbolProvider,
     private val writer: RustWriter,
     private val shape: UnionShape,
     private val renderUnknownVariant: Boolean = true,
 ) {
     private val sortedMembers: List<MemberShape> = shape.allMembers.values.sortedBy { symbolProvider.toMemberName(it) }
+    private val unknownMemberName = RuntimeType.UnknownMemberName(symbolProvider.config().runtimeConfig)
 
     fun render() {
         renderUnion()
@@ -74,9 +77,10 @@ class UnionGenerator(
                 rust("/// The `Unknown` variant represents cases where the server sent a value that wasn't recognized")
                 rust("/// by the client. This can happen when the server adds new functionality, but the client has not been updated.")
                 rust("/// To investigate this, consider turning on debug logging to print the raw HTTP response.")
+                rust("/// The name of the unrecognized member is available from [`unknown_member_name`](Self::unknown_member_name).")
                 // at some point in the future, we may start actually putting things like the raw data in here.
                 Attribute.NonExhaustive.render(this)
-                rust("Unknown,")
+                rust("Unknown(#T),", unknownMemberName)
             }
         }
         writer.rustBlock("impl ${unionSymbol.name}") {
@@ -101,7 +105,11 @@ class UnionGenerator(
             if (renderUnknownVariant) {
                 rust("/// Returns true if the enum instance is the `Unknown` variant.")
                 rustBlock("pub fn is_unknown(&self) -> bool") {
-                    rust("matches!(self, Self::Unknown)")
+                    rust("matches!(self, Self::Unknown(_))")
+                }
+                rust("/// Returns the name of the member that was received if the enum instance is the `Unknown` variant.")
+                rustBlock("pub fn unknown_member_name(&self) -> Option<&str>") {
+                    rust("if let Self::Unknown(member) = self { Some(member.as_str()) } else { None }")
                 }
             }
         }
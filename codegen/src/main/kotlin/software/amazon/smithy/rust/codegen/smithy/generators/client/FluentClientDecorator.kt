@@ -53,6 +53,7 @@ import software.amazon.smithy.rust.codegen.smithy.generators.error.errorSymbol
 import software.amazon.smithy.rust.codegen.smithy.generators.isPaginated
 import software.amazon.smithy.rust.codegen.smithy.generators.setterName
 import software.amazon.smithy.rust.codegen.smithy.rustType
+import software.amazon.smithy.rust.codegen.util.eventStreamInitialResponseMembers
 import software.amazon.smithy.rust.codegen.util.inputShape
 import software.amazon.smithy.rust.codegen.util.orNull
 import software.amazon.smithy.rust.codegen.util.outputShape
@@ -76,6 +77,7 @@ class FluentClientDecorator : RustCodegenDecorator {
         ).render(rustCrate)
         rustCrate.mergeFeature(Feature("rustls", default = true, listOf("aws-smithy-client/rustls")))
         rustCrate.mergeFeature(Feature("native-tls", default = false, listOf("aws-smithy-client/native-tls")))
+        rustCrate.mergeFeature(Feature("client-fetch", default = false, listOf("aws-smithy-client/client-fetch")))
     }
 
     override fun libRsCustomizations(
@@ -178,7 +180,8 @@ class GenericFluentClient(codegenContext: CodegenContext) : FluentClientCustomiz
                     ///
                     /// For the middleware, you'll want to use whatever matches the
                     /// routing, authentication and authorization required by the target
-                    /// service. For example, for the standard AWS SDK which uses
+                    /// service. Services that only need a fixed endpoint and a user agent can use
+                    /// [`#{client}::middleware::DefaultSmithyMiddleware`]. For the standard AWS SDK, which uses
                     /// [SigV4-signed requests], the middleware looks like this:
                     ///
                     // Ignored as otherwise we'd need to pull in all these dev-dependencies.
@@ -311,6 +314,64 @@ class FluentClientGenerator(
     private val runtimeConfig = codegenContext.runtimeConfig
     private val core = FluentClientCore(model)
 
+    /** The generic parameters of `Handle`, e.g. `["C", "M", "R"]` */
+    private val handleGenerics = generics.inst.removeSurrounding("<", ">").split(",").map { it.trim() }.filter { it.isNotEmpty() }
+
+    private fun renderCustomizableOperation(writer: RustWriter) {
+        val smithyHttp = CargoDependency.SmithyHttp(runtimeConfig).asType()
+        val operationGenerics = (listOf("O", "Retry") + handleGenerics).joinToString(", ")
+        writer.docs("Operation customization and supporting types", newlinePrefix = "//! ")
+        writer.rustTemplate(
+            """
+            /// A wrapper type for [`Operation`](#{Operation})s that allows for customization of the operation before it
+            /// is sent. A `CustomizableOperation` may be sent by calling its [`.send()`](CustomizableOperation::send)
+            /// method.
+            ##[derive(Debug)]
+            pub struct CustomizableOperation<$operationGenerics> {
+                pub(crate) handle: std::sync::Arc<crate::client::Handle${generics.inst}>,
+                pub(crate) operation: #{Operation}<O, Retry>,
+            }
+
+            impl<$operationGenerics> CustomizableOperation<$operationGenerics> #{bounds:W} {
+                /// Allows for customizing the operation's property bag, e.g. to override the region or
+                /// credentials provider set on the service config for this request only.
+                pub fn map_properties(mut self, f: impl FnOnce(&mut #{PropertyBag})) -> Self {
+                    f(&mut self.operation.properties_mut());
+                    self
+                }
+
+                /// Allows for customizing the entire operation, including its inner [http::Request].
+                pub fn map_operation<E>(
+                    mut self,
+                    f: impl FnOnce(#{Operation}<O, Retry>) -> Result<#{Operation}<O, Retry>, E>,
+                ) -> Result<Self, E> {
+                    self.operation = f(self.operation)?;
+                    Ok(self)
+                }
+
+                /// Sends this operation's request
+                pub async fn send<T, E>(self) -> Result<T, #{SdkError}<E>>
+                where
+                    E: std::error::Error,
+                    O: #{ParseHttpResponse}<Output = Result<T, E>> + Send + Sync + Clone + 'static,
+                    Retry: #{ClassifyResponse}<#{SdkSuccess}<T>, #{SdkError}<E>> + Send + Sync + Clone,
+                    #{customizable_send_bounds:W}
+                {
+                    self.handle.client.call(self.operation).await
+                }
+            }
+            """,
+            "Operation" to smithyHttp.member("operation::Operation"),
+            "PropertyBag" to smithyHttp.member("property_bag::PropertyBag"),
+            "ParseHttpResponse" to smithyHttp.member("response::ParseHttpResponse"),
+            "ClassifyResponse" to smithyHttp.member("retry::ClassifyResponse"),
+            "SdkError" to smithyHttp.member("result::SdkError"),
+            "SdkSuccess" to smithyHttp.member("result::SdkSuccess"),
+            "bounds" to generics.bounds,
+            "customizable_send_bounds" to generics.customizableSendBounds,
+        )
+    }
+
     fun render(crate: RustCrate) {
         crate.withModule(clientModule) { writer ->
             renderFluentClient(writer)
@@ -439,6 +500,9 @@ class FluentClientGenerator(
                 )
             }
         }
+        writer.withModule("customize") {
+            renderCustomizableOperation(this)
+        }
         writer.withModule("fluent_builders") {
             docs(
                 """
@@ -506,14 +570,49 @@ class FluentClientGenerator(
                                 .make_operation(&self.handle.conf)
                                 .await
                                 .map_err(|err|#{sdk_err}::ConstructionFailure(err.into()))?;
-                            self.handle.client.call(op).await
+                            #{call:W}
+                        }
+
+                        /// Consumes this builder, creating a customizable operation that can be modified before being
+                        /// sent. The operation's inner [http::Request] can be modified as well.
+                        ///
+                        /// This can be used to override configuration, such as the region or credentials, for
+                        /// a single request.
+                        pub async fn customize(self) -> std::result::Result<
+                            crate::client::customize::CustomizableOperation#{customizable_op_type_params:W},
+                            #{sdk_err}<#{operation_err}>
+                        > {
+                            let operation = self.inner.build().map_err(|err|#{sdk_err}::ConstructionFailure(err.into()))?
+                                .make_operation(&self.handle.conf)
+                                .await
+                                .map_err(|err|#{sdk_err}::ConstructionFailure(err.into()))?;
+                            Ok(crate::client::customize::CustomizableOperation { handle: self.handle, operation })
                         }
                         """,
                         "ok" to outputType,
                         "operation_err" to errorType,
+                        "call" to writable {
+                            if (operation.eventStreamInitialResponseMembers(model).isEmpty()) {
+                                rust("self.handle.client.call(op).await")
+                            } else {
+                                rustTemplate(
+                                    """
+                                    let output = self.handle.client.call(op).await?;
+                                    #{operation}::receive_initial_response(output).await
+                                    """,
+                                    "operation" to operationSymbol
+                                )
+                            }
+                        },
                         "sdk_err" to CargoDependency.SmithyHttp(runtimeConfig).asType()
                             .copy(name = "result::SdkError"),
-                        "send_bounds" to generics.sendBounds(inputType, outputType, errorType)
+                        "send_bounds" to generics.sendBounds(inputType, outputType, errorType),
+                        "customizable_op_type_params" to writable {
+                            rustTemplate(
+                                "<#{Input}OperationOutputAlias, #{Input}OperationRetryAlias${handleGenerics.joinToString("") { ", $it" }}>",
+                                "Input" to inputType
+                            )
+                        }
                     )
                     PaginatorGenerator.paginatorType(codegenContext, generics, operation)?.also { paginatorType ->
                         rustTemplate(
@@ -27,6 +27,9 @@ interface FluentClientGenerics {
 
     /** Bounds for generated `send()` functions */
     fun sendBounds(input: Symbol, output: Symbol, error: RuntimeType): Writable
+
+    /** Additional bounds for `send()` on a `CustomizableOperation<O, Retry, ...>` */
+    val customizableSendBounds: Writable
 }
 
 data class FlexibleClientGenerics(
@@ -83,6 +86,14 @@ data class FlexibleClientGenerics(
         )
     }
 
+    /** Additional bounds for `send()` on a `CustomizableOperation<O, Retry, ...>` */
+    override val customizableSendBounds = writable {
+        rustTemplate(
+            "R::Policy: #{client}::bounds::SmithyRetryPolicy<O, T, E, Retry>,",
+            "client" to client
+        )
+    }
+
     private fun defaultType(default: RuntimeType?) = writable {
         default?.also { rust("= #T", default) }
     }
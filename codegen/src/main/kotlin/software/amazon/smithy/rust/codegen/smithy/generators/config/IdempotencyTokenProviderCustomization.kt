@@ -5,39 +5,56 @@
 
 package software.amazon.smithy.rust.codegen.smithy.generators.config
 
+import software.amazon.smithy.rust.codegen.rustlang.CargoDependency
 import software.amazon.smithy.rust.codegen.rustlang.Writable
+import software.amazon.smithy.rust.codegen.rustlang.asType
 import software.amazon.smithy.rust.codegen.rustlang.rust
+import software.amazon.smithy.rust.codegen.rustlang.rustTemplate
 import software.amazon.smithy.rust.codegen.rustlang.writable
+import software.amazon.smithy.rust.codegen.smithy.RuntimeConfig
 import software.amazon.smithy.rust.codegen.smithy.RuntimeType
 import software.amazon.smithy.rust.codegen.smithy.customize.NamedSectionGenerator
 
 /**
  * Add a `make_token` field to Service config. See below for the resulting generated code.
  */
-class IdempotencyTokenProviderCustomization : NamedSectionGenerator<ServiceConfig>() {
+class IdempotencyTokenProviderCustomization(runtimeConfig: RuntimeConfig) : NamedSectionGenerator<ServiceConfig>() {
+    private val idempotencyToken = RuntimeType.idempotencyToken(runtimeConfig)
+    private val provideIdempotencyToken =
+        CargoDependency.SmithyTypes(runtimeConfig).asType().member("idempotency_token::ProvideIdempotencyToken")
+
     override fun section(section: ServiceConfig): Writable {
         return when (section) {
             is ServiceConfig.ConfigStruct -> writable {
-                rust("pub (crate) make_token: #T::IdempotencyTokenProvider,", RuntimeType.IdempotencyToken)
+                rust("pub (crate) make_token: #T::IdempotencyTokenProvider,", idempotencyToken)
             }
             ServiceConfig.ConfigImpl -> emptySection
             ServiceConfig.BuilderStruct -> writable {
-                rust("make_token: Option<#T::IdempotencyTokenProvider>,", RuntimeType.IdempotencyToken)
+                rust("make_token: Option<#T::IdempotencyTokenProvider>,", idempotencyToken)
             }
             ServiceConfig.BuilderImpl -> writable {
-                rust(
+                rustTemplate(
                     """
                     /// Sets the idempotency token provider to use for service calls that require tokens.
-                    pub fn make_token(mut self, make_token: impl Into<#T::IdempotencyTokenProvider>) -> Self {
+                    pub fn make_token(mut self, make_token: impl Into<#{IdempotencyToken}::IdempotencyTokenProvider>) -> Self {
                         self.make_token = Some(make_token.into());
                         self
                     }
+
+                    /// Sets a custom idempotency token provider to use for service calls that require tokens.
+                    ///
+                    /// Tokens are only generated for requests that don't already have one set.
+                    pub fn idempotency_token_provider(mut self, provider: impl #{ProvideIdempotencyToken} + 'static) -> Self {
+                        self.make_token = Some(#{IdempotencyToken}::IdempotencyTokenProvider::custom(provider));
+                        self
+                    }
                     """,
-                    RuntimeType.IdempotencyToken
+                    "IdempotencyToken" to idempotencyToken,
+                    "ProvideIdempotencyToken" to provideIdempotencyToken
                 )
             }
             ServiceConfig.BuilderBuild -> writable {
-                rust("make_token: self.make_token.unwrap_or_else(#T::default_provider),", RuntimeType.IdempotencyToken)
+                rust("make_token: self.make_token.unwrap_or_else(#T::default_provider),", idempotencyToken)
             }
             else -> writable { }
         }
@@ -114,7 +114,7 @@ class ServiceConfigGenerator(private val customizations: List<ConfigCustomizatio
         fun withBaseBehavior(codegenContext: CodegenContext, extraCustomizations: List<ConfigCustomization>): ServiceConfigGenerator {
             val baseFeatures = mutableListOf<ConfigCustomization>()
             if (codegenContext.serviceShape.needsIdempotencyToken(codegenContext.model)) {
-                baseFeatures.add(IdempotencyTokenProviderCustomization())
+                baseFeatures.add(IdempotencyTokenProviderCustomization(codegenContext.runtimeConfig))
             }
             return ServiceConfigGenerator(baseFeatures + extraCustomizations)
         }
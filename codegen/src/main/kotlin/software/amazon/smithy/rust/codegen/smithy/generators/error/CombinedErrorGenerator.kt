@@ -127,6 +127,21 @@ class CombinedErrorGenerator(
             }
         }
 
+        writer.rustTemplate(
+            """
+            impl #{RequestId} for ${symbol.name} {
+                fn request_id(&self) -> Option<&str> {
+                    self.meta.request_id()
+                }
+
+                fn extended_request_id(&self) -> Option<&str> {
+                    #{RequestId}::extended_request_id(&self.meta)
+                }
+            }
+            """,
+            "RequestId" to RuntimeType.requestId(symbolProvider.config().runtimeConfig)
+        )
+
         writer.rustBlock("impl ${symbol.name}") {
             writer.rustTemplate(
                 """
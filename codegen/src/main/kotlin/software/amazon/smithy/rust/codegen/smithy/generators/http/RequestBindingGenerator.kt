@@ -246,17 +246,24 @@ class RequestBindingGenerator(
     private fun RustWriter.serializeLabel(member: MemberShape, label: SmithyPattern.Segment, outputVar: String) {
         val target = model.expectShape(member.target)
         val symbol = symbolProvider.toSymbol(member)
-        val buildError = {
+        val missingError = {
             OperationBuildError(runtimeConfig).missingField(
                 this,
                 symbolProvider.toMemberName(member),
-                "cannot be empty or unset"
+                "cannot be unset"
+            )
+        }
+        val emptyError = {
+            OperationBuildError(runtimeConfig).invalidLabel(
+                this,
+                symbolProvider.toMemberName(member),
+                "cannot be empty"
             )
         }
         val input = safeName("input")
         rust("let $input = &_input.${symbolProvider.toMemberName(member)};")
         if (symbol.isOptional()) {
-            rust("let $input = $input.as_ref().ok_or(${buildError()})?;")
+            rust("let $input = $input.as_ref().ok_or(${missingError()})?;")
         }
         when {
             target.isStringShape -> {
@@ -280,7 +287,7 @@ class RequestBindingGenerator(
         rust(
             """
             if $outputVar.is_empty() {
-                return Err(${buildError()})
+                return Err(${emptyError()})
             }
             """
         )
@@ -9,6 +9,7 @@ import software.amazon.smithy.model.Model
 import software.amazon.smithy.model.pattern.UriPattern
 import software.amazon.smithy.model.shapes.MemberShape
 import software.amazon.smithy.model.shapes.OperationShape
+import software.amazon.smithy.model.shapes.ShapeId
 import software.amazon.smithy.model.shapes.ToShapeId
 import software.amazon.smithy.model.traits.HttpTrait
 import software.amazon.smithy.model.traits.TimestampFormatTrait
@@ -17,6 +18,7 @@ import software.amazon.smithy.rust.codegen.rustlang.RustModule
 import software.amazon.smithy.rust.codegen.rustlang.asType
 import software.amazon.smithy.rust.codegen.rustlang.rustBlockTemplate
 import software.amazon.smithy.rust.codegen.rustlang.rustTemplate
+import software.amazon.smithy.rust.codegen.rustlang.writable
 import software.amazon.smithy.rust.codegen.smithy.CodegenContext
 import software.amazon.smithy.rust.codegen.smithy.RuntimeType
 import software.amazon.smithy.rust.codegen.smithy.generators.protocol.ProtocolSupport
@@ -26,6 +28,7 @@ import software.amazon.smithy.rust.codegen.smithy.protocols.parse.StructuredData
 import software.amazon.smithy.rust.codegen.smithy.protocols.serialize.JsonSerializerGenerator
 import software.amazon.smithy.rust.codegen.smithy.protocols.serialize.StructuredDataSerializerGenerator
 import software.amazon.smithy.rust.codegen.util.inputShape
+import software.amazon.smithy.rust.codegen.util.isEventStream
 
 sealed class AwsJsonVersion {
     abstract val value: String
@@ -72,9 +75,16 @@ class AwsJsonHttpBindingResolver(
         .uri(UriPattern.parse("/"))
         .build()
 
+    // An event stream is the whole body of the request or response. The other members are sent in its
+    // `initial-request` or `initial-response` message.
     private fun bindings(shape: ToShapeId) =
         shape.let { model.expectShape(it.toShapeId()) }.members()
-            .map { HttpBindingDescriptor(it, HttpLocation.DOCUMENT, "document") }
+            .map { member ->
+                when (member.isEventStream(model)) {
+                    true -> HttpBindingDescriptor(member, HttpLocation.PAYLOAD, "payload")
+                    false -> HttpBindingDescriptor(member, HttpLocation.DOCUMENT, "document")
+                }
+            }
             .toList()
 
     override fun httpTrait(operationShape: OperationShape): HttpTrait = httpTrait
@@ -162,12 +172,28 @@ open class AwsJson(
             writer.rustTemplate(
                 """
                 pub fn parse_http_generic_error(response: &#{Response}<#{Bytes}>) -> Result<#{Error}, #{JsonError}> {
-                    #{json_errors}::parse_generic_error(response.body(), response.headers())
+                    #{parse_generic_error:W}
                 }
                 """,
-                *errorScope
+                *errorScope,
+                "parse_generic_error" to parseGenericError()
+            )
+        }
+
+    private fun parseGenericError() = writable {
+        if (codegenContext.serviceShape.hasTrait(AwsQueryCompatibleTraitId)) {
+            rustTemplate(
+                """
+                let generic = #{json_errors}::parse_generic_error(response.body(), response.headers())?;
+                Ok(#{aws_query_compatible_errors}::apply_aws_query_compatible_error(generic, response.headers()))
+                """,
+                *errorScope,
+                "aws_query_compatible_errors" to RuntimeType.awsQueryCompatibleErrors(runtimeConfig)
             )
+        } else {
+            rustTemplate("#{json_errors}::parse_generic_error(response.body(), response.headers())", *errorScope)
         }
+    }
 
     override fun parseEventStreamGenericError(operationShape: OperationShape): RuntimeType =
         RuntimeType.forInlineFun("parse_event_stream_generic_error", jsonDeserModule) { writer ->
@@ -184,3 +210,9 @@ open class AwsJson(
 }
 
 fun awsJsonFieldName(member: MemberShape): String = member.memberName
+
+/**
+ * Services migrated from AWS Query to AWS JSON send their legacy error code in the `x-amzn-query-error` header.
+ * The trait isn't available in the version of Smithy we build against, so it is referenced by its shape ID.
+ */
+val AwsQueryCompatibleTraitId: ShapeId = ShapeId.from("aws.protocols#awsQueryCompatible")
@@ -5,14 +5,17 @@
 
 package software.amazon.smithy.rust.codegen.smithy.protocols
 
+import software.amazon.smithy.codegen.core.CodegenException
 import software.amazon.smithy.codegen.core.Symbol
 import software.amazon.smithy.model.shapes.OperationShape
 import software.amazon.smithy.model.shapes.StructureShape
 import software.amazon.smithy.model.traits.ErrorTrait
 import software.amazon.smithy.rust.codegen.rustlang.Attribute
+import software.amazon.smithy.rust.codegen.rustlang.CargoDependency
 import software.amazon.smithy.rust.codegen.rustlang.RustModule
 import software.amazon.smithy.rust.codegen.rustlang.RustWriter
 import software.amazon.smithy.rust.codegen.rustlang.Writable
+import software.amazon.smithy.rust.codegen.rustlang.asType
 import software.amazon.smithy.rust.codegen.rustlang.assignment
 import software.amazon.smithy.rust.codegen.rustlang.rust
 import software.amazon.smithy.rust.codegen.rustlang.rustBlock
@@ -22,6 +25,7 @@ import software.amazon.smithy.rust.codegen.rustlang.withBlock
 import software.amazon.smithy.rust.codegen.rustlang.writable
 import software.amazon.smithy.rust.codegen.smithy.CodegenContext
 import software.amazon.smithy.rust.codegen.smithy.RuntimeType
+import software.amazon.smithy.rust.codegen.smithy.generators.CodegenTarget
 import software.amazon.smithy.rust.codegen.smithy.generators.StructureGenerator
 import software.amazon.smithy.rust.codegen.smithy.generators.builderSymbol
 import software.amazon.smithy.rust.codegen.smithy.generators.error.errorSymbol
@@ -34,8 +38,11 @@ import software.amazon.smithy.rust.codegen.smithy.protocols.parse.StructuredData
 import software.amazon.smithy.rust.codegen.smithy.transformers.errorMessageMember
 import software.amazon.smithy.rust.codegen.util.UNREACHABLE
 import software.amazon.smithy.rust.codegen.util.dq
+import software.amazon.smithy.rust.codegen.util.eventStreamInitialResponseMembers
 import software.amazon.smithy.rust.codegen.util.hasStreamingMember
 import software.amazon.smithy.rust.codegen.util.hasTrait
+import software.amazon.smithy.rust.codegen.util.isEventStream
+import software.amazon.smithy.rust.codegen.util.isOutputEventStream
 import software.amazon.smithy.rust.codegen.util.isStreaming
 import software.amazon.smithy.rust.codegen.util.outputShape
 import software.amazon.smithy.rust.codegen.util.toSnakeCase
@@ -98,12 +105,16 @@ class HttpBoundProtocolTraitImplGenerator(
         operationShape: OperationShape
     ) {
         val successCode = httpBindingResolver.httpTrait(operationShape).code
+        val successResponseIsError = protocol.successResponseIsError(operationShape)
+        val isError = "!response.status().is_success() && response.status().as_u16() != $successCode".let {
+            if (successResponseIsError != null) "($it) || #{success_response_is_error}(response)" else it
+        }
         rustTemplate(
             """
             impl #{ParseStrict} for $operationName {
                 type Output = std::result::Result<#{O}, #{E}>;
                 fn parse(&self, response: &#{http}::Response<#{Bytes}>) -> Self::Output {
-                     if !response.status().is_success() && response.status().as_u16() != $successCode {
+                     if $isError {
                         #{parse_error}(response)
                      } else {
                         #{parse_response}(response)
@@ -114,7 +125,8 @@ class HttpBoundProtocolTraitImplGenerator(
             "O" to outputSymbol,
             "E" to operationShape.errorSymbol(symbolProvider),
             "parse_error" to parseError(operationShape),
-            "parse_response" to parseResponse(operationShape)
+            "parse_response" to parseResponse(operationShape),
+            *listOfNotNull(successResponseIsError?.let { "success_response_is_error" to it }).toTypedArray()
         )
     }
 
@@ -147,6 +159,84 @@ class HttpBoundProtocolTraitImplGenerator(
             "parse_error" to parseError(operationShape),
             *codegenScope
         )
+        if (operationShape.eventStreamInitialResponseMembers(model).isNotEmpty()) {
+            renderReceiveInitialResponse(operationName, outputSymbol, operationShape)
+        }
+    }
+
+    /**
+     * RPC protocols send the output members that aren't the event stream in the stream's `initial-response`
+     * message. The fluent client waits for it with the generated `receive_initial_response` after sending the
+     * operation, since receiving it is asynchronous.
+     */
+    private fun RustWriter.renderReceiveInitialResponse(
+        operationName: String,
+        outputSymbol: Symbol,
+        operationShape: OperationShape
+    ) {
+        val outputShape = operationShape.outputShape(model)
+        val streamMember = outputShape.members().first { it.isEventStream(model) }
+        val streamMemberName = symbolProvider.toMemberName(streamMember)
+        val parser = protocol.structuredDataParser(operationShape).operationParser(operationShape)
+            ?: throw CodegenException("the initial response of $operationShape must have a parser")
+        val withRequestIds = StructureGenerator.hasRequestIds(outputShape, CodegenTarget.CLIENT)
+        rustTemplate(
+            """
+            impl $operationName {
+                /// Receives the `initial-response` message of the output's event stream, and sets the
+                /// output members that it carries.
+                ##[doc(hidden)]
+                pub async fn receive_initial_response(
+                    mut output: #{O},
+                ) -> std::result::Result<#{O}, #{SdkError}<#{E}>> {
+                    // The HTTP response was consumed when `output` was parsed, so errors are reported with an empty one
+                    fn response_error(
+                        err: impl Into<Box<dyn std::error::Error + Send + Sync>>,
+                    ) -> #{SdkError}<#{E}> {
+                        #{SdkError}::ResponseError {
+                            err: err.into(),
+                            raw: #{operation}::Response::new(#{http}::Response::new(#{SdkBody}::taken())),
+                        }
+                    }
+                    let message = match output.$streamMemberName.try_recv_initial().await {
+                        Ok(Some(message)) => message,
+                        Ok(None) => return Ok(output),
+                        Err(#{SdkError}::DispatchFailure(err)) => return Err(#{SdkError}::DispatchFailure(err)),
+                        Err(err) => return Err(response_error(err)),
+                    };
+                    #{request_ids:W}
+                    #{parser}(message.payload(), #{O}::builder())
+                        .map_err(response_error)?
+                        .${streamMember.setterName()}(Some(output.$streamMemberName))
+                        #{set_request_ids:W}
+                        .build()
+                        .map_err(response_error)
+                }
+            }
+            """,
+            *codegenScope,
+            "O" to outputSymbol,
+            "E" to operationShape.errorSymbol(symbolProvider),
+            "SdkError" to CargoDependency.SmithyHttp(runtimeConfig).asType().member("result::SdkError"),
+            "SdkBody" to RuntimeType.sdkBody(runtimeConfig),
+            "parser" to parser,
+            "request_ids" to writable {
+                if (withRequestIds) {
+                    rustTemplate(
+                        """
+                        let request_id = #{RequestId}::request_id(&output).map(str::to_string);
+                        let extended_request_id = #{RequestId}::extended_request_id(&output).map(str::to_string);
+                        """,
+                        "RequestId" to RuntimeType.requestId(runtimeConfig)
+                    )
+                }
+            },
+            "set_request_ids" to writable {
+                if (withRequestIds) {
+                    rust("._set_request_id(request_id)._set_extended_request_id(extended_request_id)")
+                }
+            },
+        )
     }
 
     private fun parseError(operationShape: OperationShape): RuntimeType {
@@ -167,6 +257,10 @@ class HttpBoundProtocolTraitImplGenerator(
                     protocol.parseHttpGenericError(operationShape),
                     errorSymbol
                 )
+                rust(
+                    "let generic = #T(generic, response.headers());",
+                    CargoDependency.SmithyHttp(runtimeConfig).asType().member("request_id::apply_request_id")
+                )
                 if (operationShape.errors.isNotEmpty()) {
                     rustTemplate(
                         """
@@ -285,11 +379,14 @@ class HttpBoundProtocolTraitImplGenerator(
         // avoid non-usage warnings for response
         rust("let _ = response;")
         if (outputShape.id == operationShape.output.get()) {
-            structuredDataParser.operationParser(operationShape)?.also { parser ->
-                rust(
-                    "output = #T(response.body().as_ref(), output).map_err(#T::unhandled)?;",
-                    parser,
-                    errorSymbol
+            // The body of an event stream output is the stream itself. RPC protocols send the other members in its
+            // `initial-response` message, which `receive_initial_response` parses.
+            structuredDataParser.operationParser(operationShape)?.takeIf { !operationShape.isOutputEventStream(model) }?.also { parser ->
+                rustTemplate(
+                    "output = #{parser}(response.body().as_ref(), output).map_err(|err| #{error}::unhandled(#{ResponseParseError}::new(err)))?;",
+                    "parser" to parser,
+                    "error" to errorSymbol,
+                    "ResponseParseError" to RuntimeType.responseParseError(runtimeConfig),
                 )
             }
         } else {
@@ -310,6 +407,15 @@ class HttpBoundProtocolTraitImplGenerator(
                 }
             }
         }
+        if (StructureGenerator.hasRequestIds(outputShape, CodegenTarget.CLIENT)) {
+            rustTemplate(
+                """
+                output = output._set_request_id(#{RequestId}::request_id(response.headers()).map(str::to_string));
+                output = output._set_extended_request_id(#{RequestId}::extended_request_id(response.headers()).map(str::to_string));
+                """,
+                "RequestId" to RuntimeType.requestId(runtimeConfig)
+            )
+        }
 
         val err = if (StructureGenerator.fallibleBuilder(outputShape, symbolProvider)) {
             ".map_err(${format(errorSymbol)}::unhandled)?"
@@ -347,7 +453,12 @@ class HttpBoundProtocolTraitImplGenerator(
             }
             HttpLocation.PAYLOAD -> {
                 val payloadParser: RustWriter.(String) -> Unit = { body ->
-                    rust("#T($body).map_err(#T::unhandled)", structuredDataParser.payloadParser(member), errorSymbol)
+                    rustTemplate(
+                        "#{parser}($body).map_err(|err| #{error}::unhandled(#{ResponseParseError}::new(err)))",
+                        "parser" to structuredDataParser.payloadParser(member),
+                        "error" to errorSymbol,
+                        "ResponseParseError" to RuntimeType.responseParseError(runtimeConfig),
+                    )
                 }
                 val deserializer = httpBindingGenerator.generateDeserializePayloadFn(
                     binding,
@@ -23,6 +23,7 @@ import software.amazon.smithy.rust.codegen.rustlang.rustBlockTemplate
 import software.amazon.smithy.rust.codegen.rustlang.rustTemplate
 import software.amazon.smithy.rust.codegen.rustlang.withBlock
 import software.amazon.smithy.rust.codegen.rustlang.withBlockTemplate
+import software.amazon.smithy.rust.codegen.rustlang.writable
 import software.amazon.smithy.rust.codegen.smithy.CodegenContext
 import software.amazon.smithy.rust.codegen.smithy.RuntimeType
 import software.amazon.smithy.rust.codegen.smithy.generators.error.errorSymbol
@@ -34,6 +35,7 @@ import software.amazon.smithy.rust.codegen.smithy.protocols.serialize.EventStrea
 import software.amazon.smithy.rust.codegen.smithy.protocols.serialize.StructuredDataSerializerGenerator
 import software.amazon.smithy.rust.codegen.util.PANIC
 import software.amazon.smithy.rust.codegen.util.UNREACHABLE
+import software.amazon.smithy.rust.codegen.util.dq
 import software.amazon.smithy.rust.codegen.util.expectMember
 import software.amazon.smithy.rust.codegen.util.hasTrait
 import software.amazon.smithy.rust.codegen.util.inputShape
@@ -161,6 +163,8 @@ class HttpBoundProtocolPayloadGenerator(
         val memberName = symbolProvider.toMemberName(memberShape)
         val unionShape = model.expectShape(memberShape.target, UnionShape::class.java)
 
+        val contentType = httpBindingResolver.requestContentType(operationShape)
+            ?: throw CodegenException("event streams must set a content type")
         val marshallerConstructorFn = EventStreamMarshallerGenerator(
             model,
             mode,
@@ -168,26 +172,46 @@ class HttpBoundProtocolPayloadGenerator(
             symbolProvider,
             unionShape,
             serializerGenerator,
-            httpBindingResolver.requestContentType(operationShape)
-                ?: throw CodegenException("event streams must set a content type"),
+            contentType,
         ).render()
 
-        // TODO(EventStream): [RPC] RPC protocols need to send an initial message with the
-        // parameters that are not `@eventHeader` or `@eventPayload`.
+        // RPC protocols send the members that aren't the event stream in an `initial-request` message
+        val initialRequestSerializer = serializerGenerator.operationInputSerializer(operationShape)
         rustTemplate(
             """
             {
                 let marshaller = #{marshallerConstructorFn}();
                 let signer = _config.new_event_stream_signer(properties.clone());
                 let adapter: #{SmithyHttp}::event_stream::MessageStreamAdapter<_, #{OperationError}> =
-                    self.$memberName.into_body_stream(marshaller, signer);
+                    #{into_body_stream:W};
                 let body: #{SdkBody} = #{hyper}::Body::wrap_stream(adapter).into();
                 body
             }
             """,
             *codegenScope,
             "marshallerConstructorFn" to marshallerConstructorFn,
-            "OperationError" to operationShape.errorSymbol(symbolProvider)
+            "OperationError" to operationShape.errorSymbol(symbolProvider),
+            "into_body_stream" to writable {
+                if (initialRequestSerializer == null) {
+                    rust("self.$memberName.into_body_stream(marshaller, signer)")
+                } else {
+                    rustTemplate(
+                        """
+                        {
+                            let initial_request = #{serializer}(&self)?;
+                            let initial_request = #{initial_request_message}(
+                                ${contentType.dq()},
+                                initial_request.bytes().expect("serialized input is in memory").to_vec(),
+                            );
+                            self.$memberName.into_body_stream_with_initial_request(marshaller, signer, initial_request)
+                        }
+                        """,
+                        "serializer" to initialRequestSerializer,
+                        "initial_request_message" to CargoDependency.SmithyEventStream(runtimeConfig).asType()
+                            .member("smithy::initial_request_message"),
+                    )
+                }
+            },
         )
     }
 
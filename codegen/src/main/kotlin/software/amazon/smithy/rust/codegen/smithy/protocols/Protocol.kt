@@ -64,6 +64,18 @@ interface Protocol {
      */
     fun parseHttpGenericError(operationShape: OperationShape): RuntimeType
 
+    /**
+     * Generates a function signature like the following:
+     * ```rust
+     * fn success_response_is_error(response: &Response<Bytes>) -> bool
+     * ```
+     *
+     * Some services (e.g. S3) can return an error with a successful status code. When this returns a function,
+     * successful responses are passed to it, and are parsed as errors if it returns `true`. Returns `null` for
+     * protocols where a successful status code always means success.
+     */
+    fun successResponseIsError(operationShape: OperationShape): RuntimeType? = null
+
     /**
      * Generates a function signature like the following:
      * ```rust
@@ -67,6 +67,7 @@ class EventStreamUnmarshallerGenerator(
         "Message" to RuntimeType("Message", smithyEventStream, "aws_smithy_eventstream::frame"),
         "OpError" to operationErrorSymbol,
         "SmithyError" to RuntimeType("Error", CargoDependency.SmithyTypes(runtimeConfig), "aws_smithy_types"),
+        "UnknownMemberName" to RuntimeType.UnknownMemberName(runtimeConfig),
         "tracing" to CargoDependency.Tracing.asType(),
         "UnmarshalledMessage" to RuntimeType("UnmarshalledMessage", smithyEventStream, "aws_smithy_eventstream::frame"),
         "UnmarshallMessage" to RuntimeType("UnmarshallMessage", smithyEventStream, "aws_smithy_eventstream::frame"),
@@ -146,7 +147,7 @@ class EventStreamUnmarshallerGenerator(
             rustBlock("_unknown_variant => ") {
                 when (mode.renderUnknownVariant()) {
                     true -> rustTemplate(
-                        "Ok(#{UnmarshalledMessage}::Event(#{Output}::${UnionGenerator.UnknownVariantName}))",
+                        "Ok(#{UnmarshalledMessage}::Event(#{Output}::${UnionGenerator.UnknownVariantName}(#{UnknownMemberName}::from(_unknown_variant))))",
                         "Output" to unionSymbol,
                         *codegenScope
                     )
@@ -237,8 +238,6 @@ class EventStreamUnmarshallerGenerator(
     }
 
     private fun RustWriter.renderUnmarshallEventPayload(member: MemberShape) {
-        // TODO(EventStream): [RPC] Don't blow up on an initial-message that's not part of the union (:event-type will be "initial-request" or "initial-response")
-        // TODO(EventStream): [RPC] Incorporate initial-message into original output (:event-type will be "initial-request" or "initial-response")
         val target = model.expectShape(member.target)
         expectedContentType(target)?.also { contentType ->
             rustTemplate(
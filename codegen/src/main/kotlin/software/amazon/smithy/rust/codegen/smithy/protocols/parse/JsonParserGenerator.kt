@@ -9,6 +9,8 @@ import software.amazon.smithy.model.shapes.BlobShape
 import software.amazon.smithy.model.shapes.BooleanShape
 import software.amazon.smithy.model.shapes.CollectionShape
 import software.amazon.smithy.model.shapes.DocumentShape
+import software.amazon.smithy.model.shapes.DoubleShape
+import software.amazon.smithy.model.shapes.FloatShape
 import software.amazon.smithy.model.shapes.MapShape
 import software.amazon.smithy.model.shapes.MemberShape
 import software.amazon.smithy.model.shapes.NumberShape
@@ -75,7 +77,7 @@ class JsonParserGenerator(
         "expect_start_object" to smithyJson.member("deserialize::token::expect_start_object"),
         "expect_string_or_null" to smithyJson.member("deserialize::token::expect_string_or_null"),
         "expect_timestamp_or_null" to smithyJson.member("deserialize::token::expect_timestamp_or_null"),
-        "json_token_iter" to smithyJson.member("deserialize::json_token_iter"),
+        "parse_document" to smithyJson.member("deserialize::parse_document"),
         "Peekable" to RuntimeType.std.member("iter::Peekable"),
         "skip_value" to smithyJson.member("deserialize::token::skip_value"),
         "skip_to_end" to smithyJson.member("deserialize::token::skip_to_end"),
@@ -101,17 +103,12 @@ class JsonParserGenerator(
                 "Builder" to structureShape.builderSymbol(symbolProvider),
                 *codegenScope
             ) {
-                rustTemplate(
-                    """
-                    let mut tokens_owned = #{json_token_iter}(#{or_empty}(value)).peekable();
-                    let tokens = &mut tokens_owned;
-                    #{expect_start_object}(tokens.next())?;
-                    """,
-                    *codegenScope
-                )
-                deserializeStructInner(includedMembers)
-                expectEndOfTokenStream()
-                rust("Ok(builder)")
+                withBlockTemplate("#{parse_document}(#{or_empty}(value), |tokens| {", "})", *codegenScope) {
+                    rustTemplate("#{expect_start_object}(tokens.next())?;", *codegenScope)
+                    deserializeStructInner(includedMembers)
+                    expectEndOfTokenStream()
+                    rust("Ok(builder)")
+                }
             }
         }
     }
@@ -132,18 +129,13 @@ class JsonParserGenerator(
                     "#{or_empty}(input)"
                 }
 
-                rustTemplate(
-                    """
-                    let mut tokens_owned = #{json_token_iter}($input).peekable();
-                    let tokens = &mut tokens_owned;
-                    """,
-                    *codegenScope
-                )
-                rust("let result =")
-                deserializeMember(member)
-                rustTemplate(".ok_or_else(|| #{Error}::custom(\"expected payload member value\"));", *codegenScope)
-                expectEndOfTokenStream()
-                rust("result")
+                withBlockTemplate("#{parse_document}($input, |tokens| {", "})", *codegenScope) {
+                    rust("let result =")
+                    deserializeMember(member)
+                    rustTemplate(".ok_or_else(|| #{Error}::custom(\"expected payload member value\"));", *codegenScope)
+                    expectEndOfTokenStream()
+                    rust("result")
+                }
             }
         }
     }
@@ -252,7 +244,18 @@ class JsonParserGenerator(
 
     private fun RustWriter.deserializeNumber(target: NumberShape) {
         val symbol = symbolProvider.toSymbol(target)
-        rustTemplate("#{expect_number_or_null}(tokens.next())?.map(|v| v.to_#{T}())", "T" to symbol, *codegenScope)
+        when (target) {
+            // Floating-point numbers are expected to be rounded to the nearest value of the target type
+            is FloatShape, is DoubleShape -> rustTemplate(
+                "#{expect_number_or_null}(tokens.next())?.map(|v| v.to_#{T}())",
+                "T" to symbol, *codegenScope
+            )
+            // Integers fail to parse rather than silently wrap or truncate when they're out of range
+            else -> rustTemplate(
+                "#{expect_number_or_null}(tokens.next())?.map(<#{T} as std::convert::TryFrom<_>>::try_from).transpose()?",
+                "T" to symbol, *codegenScope
+            )
+        }
     }
 
     private fun RustWriter.deserializeTimestamp(member: MemberShape) {
@@ -426,12 +429,14 @@ class JsonParserGenerator(
                                     // in client mode, resolve an unknown union variant to the unknown variant
                                     true -> rustTemplate(
                                         """
-                                        _ => {
+                                        member => {
                                           #{skip_value}(tokens)?;
-                                          Some(#{Union}::${UnionGenerator.UnknownVariantName})
+                                          Some(#{Union}::${UnionGenerator.UnknownVariantName}(#{UnknownMemberName}::from(member)))
                                         }
                                         """,
-                                        "Union" to symbol, *codegenScope
+                                        "Union" to symbol,
+                                        "UnknownMemberName" to RuntimeType.UnknownMemberName(runtimeConfig),
+                                        *codegenScope
                                     )
                                     // in server mode, use strict parsing
                                     false -> rustTemplate(
@@ -435,7 +435,10 @@ class XmlBindingTraitParserGenerator(
                         }
                     }
                     when (mode.renderUnknownVariant()) {
-                        true -> rust("_unknown => base = Some(#T::${UnionGenerator.UnknownVariantName}),", symbol)
+                        true -> rust(
+                            "unknown => base = Some(#T::${UnionGenerator.UnknownVariantName}(#T::from(unknown.local()))),",
+                            symbol, RuntimeType.UnknownMemberName(runtimeConfig)
+                        )
                         false -> rustTemplate("""variant => return Err(#{XmlError}::custom(format!("unexpected union variant: {:?}", variant)))""", *codegenScope)
                     }
                 }
@@ -110,7 +110,7 @@ class EventStreamMarshallerGenerator(
                     if (mode.renderUnknownVariant()) {
                         rustTemplate(
                             """
-                            Self::Input::${UnionGenerator.UnknownVariantName} => return Err(
+                            Self::Input::${UnionGenerator.UnknownVariantName}(_) => return Err(
                                 #{Error}::Marshalling(${unknownVariantError(unionSymbol.rustType().name).dq()}.to_owned())
                             )
                             """,
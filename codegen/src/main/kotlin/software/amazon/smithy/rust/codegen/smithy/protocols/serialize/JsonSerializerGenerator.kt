@@ -444,7 +444,7 @@ class JsonSerializerGenerator(
                     }
                     if (mode.renderUnknownVariant()) {
                         rustTemplate(
-                            "#{Union}::${UnionGenerator.UnknownVariantName} => return Err(#{Error}::unknown_variant(${unionSymbol.name.dq()}))",
+                            "#{Union}::${UnionGenerator.UnknownVariantName}(_) => return Err(#{Error}::unknown_variant(${unionSymbol.name.dq()}))",
                             "Union" to unionSymbol,
                             *codegenScope
                         )
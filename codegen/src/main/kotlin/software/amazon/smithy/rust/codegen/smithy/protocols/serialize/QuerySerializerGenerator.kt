@@ -325,7 +325,7 @@ abstract class QuerySerializerGenerator(codegenContext: CodegenContext) : Struct
                     }
                     if (mode.renderUnknownVariant()) {
                         rustTemplate(
-                            "#{Union}::${UnionGenerator.UnknownVariantName} => return Err(#{Error}::unknown_variant(${unionSymbol.name.dq()}))",
+                            "#{Union}::${UnionGenerator.UnknownVariantName}(_) => return Err(#{Error}::unknown_variant(${unionSymbol.name.dq()}))",
                             "Union" to unionSymbol,
                             *codegenScope
                         )
@@ -97,7 +97,7 @@ fun String.asSmithyModel(sourceLocation: String? = null): Model {
  */
 fun StructureShape.renderWithModelBuilder(model: Model, symbolProvider: RustSymbolProvider, writer: RustWriter, forWhom: CodegenTarget = CodegenTarget.CLIENT) {
     StructureGenerator(model, symbolProvider, writer, this).render(forWhom)
-    val modelBuilder = BuilderGenerator(model, symbolProvider, this)
+    val modelBuilder = BuilderGenerator(model, symbolProvider, this, forWhom)
     modelBuilder.render(writer)
     writer.implBlock(this, symbolProvider) {
         modelBuilder.renderConvenienceMethod(this)
@@ -16,6 +16,10 @@ import software.amazon.smithy.model.shapes.Shape
 import software.amazon.smithy.model.shapes.ShapeId
 import software.amazon.smithy.model.shapes.StructureShape
 import software.amazon.smithy.model.shapes.UnionShape
+import software.amazon.smithy.model.traits.HttpHeaderTrait
+import software.amazon.smithy.model.traits.HttpPayloadTrait
+import software.amazon.smithy.model.traits.HttpPrefixHeadersTrait
+import software.amazon.smithy.model.traits.HttpResponseCodeTrait
 import software.amazon.smithy.model.traits.StreamingTrait
 import software.amazon.smithy.model.traits.Trait
 import software.amazon.smithy.rust.codegen.smithy.traits.SyntheticInputTrait
@@ -76,6 +80,25 @@ fun OperationShape.isEventStream(model: Model): Boolean {
     return isInputEventStream(model) || isOutputEventStream(model)
 }
 
+/**
+ * Returns the members of the operation's output that arrive in the `initial-response` message of its event stream,
+ * i.e. the members that are neither the event stream nor bound to the HTTP response. Only RPC protocols have these.
+ */
+fun OperationShape.eventStreamInitialResponseMembers(model: Model): List<MemberShape> {
+    if (!isOutputEventStream(model)) {
+        return listOf()
+    }
+    val httpBindings = listOf(
+        HttpHeaderTrait::class.java,
+        HttpPrefixHeadersTrait::class.java,
+        HttpResponseCodeTrait::class.java,
+        HttpPayloadTrait::class.java,
+    )
+    return outputShape(model).members().filter { member ->
+        !member.isEventStream(model) && httpBindings.none { member.hasTrait(it) }
+    }
+}
+
 fun ServiceShape.hasEventStreamOperations(model: Model): Boolean = operations.any { id ->
     model.expectShape(id, OperationShape::class.java).isEventStream(model)
 }
@@ -0,0 +1,85 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+package software.amazon.smithy.rust.codegen.customizations
+
+import io.kotest.matchers.shouldBe
+import io.kotest.matchers.string.shouldContain
+import org.junit.jupiter.api.Test
+import software.amazon.smithy.model.shapes.Shape
+import software.amazon.smithy.model.shapes.StringShape
+import software.amazon.smithy.model.shapes.StructureShape
+import software.amazon.smithy.model.shapes.UnionShape
+import software.amazon.smithy.rust.codegen.rustlang.Attribute
+import software.amazon.smithy.rust.codegen.rustlang.RustWriter
+import software.amazon.smithy.rust.codegen.smithy.customizations.SerdeDecorator
+import software.amazon.smithy.rust.codegen.smithy.customizations.renderEnumSerde
+import software.amazon.smithy.rust.codegen.smithy.expectRustMetadata
+import software.amazon.smithy.rust.codegen.smithy.transformers.OperationNormalizer
+import software.amazon.smithy.rust.codegen.testutil.asSmithyModel
+import software.amazon.smithy.rust.codegen.testutil.testSymbolProvider
+import software.amazon.smithy.rust.codegen.util.lookup
+
+internal class SerdeDecoratorTest {
+    private val model = OperationNormalizer.transform(
+        """
+        namespace test
+        operation GetObject {
+            input: GetObjectInput,
+            output: GetObjectOutput
+        }
+
+        structure GetObjectInput {
+            key: String,
+            choice: Choice,
+            instanceType: InstanceType
+        }
+
+        structure GetObjectOutput {
+            data: BlobStream
+        }
+
+        union Choice {
+            a: String,
+            b: Timestamp
+        }
+
+        @streaming
+        blob BlobStream
+
+        @enum([{ value: "t2.micro", name: "T2_MICRO" }])
+        string InstanceType
+        """.asSmithyModel()
+    )
+    private val symbolProvider = SerdeDecorator().symbolProvider(testSymbolProvider(model))
+
+    private fun derivesSerde(shape: Shape): Boolean =
+        symbolProvider.toSymbol(shape).expectRustMetadata().additionalAttributes.any {
+            it is Attribute.Custom && it.annotation.contains("serde::Serialize")
+        }
+
+    @Test
+    fun `derives serde traits for generated shapes`() {
+        derivesSerde(model.lookup<StructureShape>("test.synthetic#GetObjectInput")) shouldBe true
+        derivesSerde(model.lookup<UnionShape>("test#Choice")) shouldBe true
+    }
+
+    @Test
+    fun `does not derive serde traits for streaming shapes`() {
+        derivesSerde(model.lookup<StructureShape>("test.synthetic#GetObjectOutput")) shouldBe false
+    }
+
+    @Test
+    fun `serializes enums as their model values`() {
+        val instanceType = model.lookup<StringShape>("test#InstanceType")
+        derivesSerde(instanceType) shouldBe false
+
+        val writer = RustWriter.forModule("model")
+        writer.renderEnumSerde(symbolProvider.toSymbol(instanceType))
+        val rendered = writer.toString()
+        rendered shouldContain "serializer.serialize_str(self.as_str())"
+        rendered shouldContain "InstanceType::from(value.as_str())"
+    }
+}
@@ -78,6 +78,11 @@ class SmithyTypesPubUseGeneratorTest {
         assertHasType(typesWithEmptyModel(), "aws_smithy_http::result::SdkError")
     }
 
+    @Test
+    fun `it always re-exports RequestId`() {
+        assertHasType(typesWithEmptyModel(), "aws_smithy_http::request_id::RequestId")
+    }
+
     @Test
     fun `it re-exports Blob when a model uses blobs`() {
         assertDoesntHaveType(typesWithEmptyModel(), "aws_smithy_types::Blob")
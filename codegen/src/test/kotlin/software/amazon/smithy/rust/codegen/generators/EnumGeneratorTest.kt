@@ -115,7 +115,9 @@ class EnumGeneratorTest {
                 let instance = InstanceType::T2Micro;
                 assert_eq!(instance.as_str(), "t2.micro");
                 assert_eq!(InstanceType::from("t2.nano"), InstanceType::T2Nano);
-                assert_eq!(InstanceType::from("other"), InstanceType::Unknown("other".to_owned()));
+                assert_eq!(InstanceType::from("other"), InstanceType::Unknown("other".into()));
+                assert!(InstanceType::from("other").is_unknown());
+                assert!(!instance.is_unknown());
                 // round trip unknown variants:
                 assert_eq!(InstanceType::from("other").as_str(), "other");
                 """
@@ -16,10 +16,12 @@ import software.amazon.smithy.rust.codegen.rustlang.raw
 import software.amazon.smithy.rust.codegen.rustlang.rust
 import software.amazon.smithy.rust.codegen.rustlang.rustBlock
 import software.amazon.smithy.rust.codegen.smithy.generators.StructureGenerator
+import software.amazon.smithy.rust.codegen.smithy.transformers.OperationNormalizer
 import software.amazon.smithy.rust.codegen.smithy.transformers.RecursiveShapeBoxer
 import software.amazon.smithy.rust.codegen.testutil.TestWorkspace
 import software.amazon.smithy.rust.codegen.testutil.asSmithyModel
 import software.amazon.smithy.rust.codegen.testutil.compileAndTest
+import software.amazon.smithy.rust.codegen.testutil.renderWithModelBuilder
 import software.amazon.smithy.rust.codegen.testutil.testSymbolProvider
 import software.amazon.smithy.rust.codegen.testutil.unitTest
 import software.amazon.smithy.rust.codegen.util.lookup
@@ -122,6 +124,54 @@ class StructureGeneratorTest {
         writer.compileAndTest()
     }
 
+    @Test
+    fun `operation outputs carry request ids`() {
+        val model = OperationNormalizer.transform(
+            """
+            namespace com.test
+            operation SomeOperation {
+                output: SomeOutput
+            }
+            structure SomeOutput {
+                foo: String
+            }
+            """.asSmithyModel()
+        )
+        val output = model.lookup<StructureShape>("com.test.synthetic#SomeOperationOutput")
+        val provider = testSymbolProvider(model)
+        val project = TestWorkspace.testProject(provider)
+        project.useShapeWriter(output) { writer ->
+            output.renderWithModelBuilder(model, provider, writer)
+            writer.unitTest(
+                "output_request_ids",
+                """
+                use aws_smithy_http::request_id::RequestId;
+                let output = SomeOperationOutput::builder().foo("bar").build();
+                assert_eq!(None, output.request_id());
+                let output = SomeOperationOutput::builder()
+                    ._set_request_id(Some("request-id".to_string()))
+                    ._set_extended_request_id(Some("extended-request-id".to_string()))
+                    .build();
+                assert_eq!(Some("request-id"), output.request_id());
+                assert_eq!(Some("extended-request-id"), output.extended_request_id());
+                """
+            )
+            writer.unitTest(
+                "output_equality_ignores_request_ids",
+                """
+                let output = SomeOperationOutput::builder().foo("bar").build();
+                let with_request_id = SomeOperationOutput::builder()
+                    .foo("bar")
+                    ._set_request_id(Some("request-id".to_string()))
+                    .build();
+                assert_eq!(output, with_request_id);
+                assert_ne!(output, SomeOperationOutput::builder().foo("baz").build());
+                """
+            )
+        }
+        project.compileAndTest()
+    }
+
     @Test
     fun `generate error structures`() {
         val provider = testSymbolProvider(model)
@@ -7,7 +7,6 @@ package software.amazon.smithy.rust.codegen.generators
 
 import io.kotest.matchers.string.shouldContain
 import org.junit.jupiter.api.Test
-import software.amazon.smithy.codegen.core.SymbolProvider
 import software.amazon.smithy.rust.codegen.rustlang.RustWriter
 import software.amazon.smithy.rust.codegen.smithy.generators.UnionGenerator
 import software.amazon.smithy.rust.codegen.testutil.asSmithyModel
@@ -88,8 +87,10 @@ class UnionGeneratorTest {
         val writer = generateUnion("union MyUnion { a: String, b: String }", unknownVariant = true)
         writer.compileAndTest(
             """
-            let union = MyUnion::Unknown;
+            let union = MyUnion::Unknown("c".into());
             assert!(union.is_unknown());
+            assert_eq!(Some("c"), union.unknown_member_name());
+            assert_eq!(None, MyUnion::A("a".to_string()).unknown_member_name());
 
             """
         )
@@ -97,7 +98,7 @@ class UnionGeneratorTest {
 
     private fun generateUnion(modelSmithy: String, unionName: String = "MyUnion", unknownVariant: Boolean = true): RustWriter {
         val model = "namespace test\n$modelSmithy".asSmithyModel()
-        val provider: SymbolProvider = testSymbolProvider(model)
+        val provider = testSymbolProvider(model)
         val writer = RustWriter.forModule("model")
         UnionGenerator(model, provider, writer, model.lookup("test#$unionName"), renderUnknownVariant = unknownVariant).render()
         return writer
@@ -316,8 +316,9 @@ class RequestBindingGeneratorTest {
                         .bucket_name("")
                         .key(ts.clone())
                         .build().unwrap();
-                    let err = inp.test_request_builder_base().expect_err("can't build request with bucket unset");
-                    assert!(matches!(err, ${writer.format(TestRuntimeConfig.operationBuildError())}::MissingField { .. }))
+                    let err = inp.test_request_builder_base().expect_err("can't build request with bucket empty");
+                    assert!(matches!(err, ${writer.format(TestRuntimeConfig.operationBuildError())}::InvalidLabel { .. }));
+                    assert_eq!(Some("bucket_name"), err.field());
                 """
             )
         }
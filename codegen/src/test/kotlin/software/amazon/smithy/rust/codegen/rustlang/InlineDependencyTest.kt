@@ -8,6 +8,7 @@ package software.amazon.smithy.rust.codegen.rustlang
 import io.kotest.matchers.shouldBe
 import io.kotest.matchers.shouldNotBe
 import org.junit.jupiter.api.Test
+import software.amazon.smithy.rust.codegen.testutil.TestRuntimeConfig
 import software.amazon.smithy.rust.codegen.testutil.compileAndTest
 
 internal class InlineDependencyTest {
@@ -27,9 +28,10 @@ internal class InlineDependencyTest {
 
     @Test
     fun `locate dependencies from the inlineable module`() {
-        val dep = InlineDependency.idempotencyToken()
+        val dep = InlineDependency.idempotencyToken(TestRuntimeConfig)
         val testWriter = RustWriter.root()
         testWriter.addDependency(CargoDependency.FastRand)
+        testWriter.addDependency(CargoDependency.SmithyTypes(TestRuntimeConfig))
         testWriter.withModule(dep.module.name) {
             dep.renderer(this)
         }
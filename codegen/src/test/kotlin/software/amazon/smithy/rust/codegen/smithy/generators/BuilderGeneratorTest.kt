@@ -7,24 +7,51 @@ package software.amazon.smithy.rust.codegen.smithy.generators
 
 import org.junit.jupiter.api.Test
 import software.amazon.smithy.codegen.core.Symbol
+import software.amazon.smithy.model.Model
 import software.amazon.smithy.model.shapes.MemberShape
 import software.amazon.smithy.model.shapes.Shape
+import software.amazon.smithy.model.shapes.StructureShape
 import software.amazon.smithy.model.traits.EnumDefinition
 import software.amazon.smithy.rust.codegen.generators.StructureGeneratorTest
 import software.amazon.smithy.rust.codegen.rustlang.RustWriter
+import software.amazon.smithy.rust.codegen.smithy.CodegenConfig
 import software.amazon.smithy.rust.codegen.smithy.Default
 import software.amazon.smithy.rust.codegen.smithy.MaybeRenamed
 import software.amazon.smithy.rust.codegen.smithy.RustSymbolProvider
 import software.amazon.smithy.rust.codegen.smithy.SymbolVisitorConfig
 import software.amazon.smithy.rust.codegen.smithy.setDefault
+import software.amazon.smithy.rust.codegen.testutil.TestRuntimeConfig
+import software.amazon.smithy.rust.codegen.testutil.asSmithyModel
 import software.amazon.smithy.rust.codegen.testutil.compileAndTest
 import software.amazon.smithy.rust.codegen.testutil.testSymbolProvider
+import software.amazon.smithy.rust.codegen.util.lookup
 
 internal class BuilderGeneratorTest {
     private val model = StructureGeneratorTest.model
     private val inner = StructureGeneratorTest.inner
     private val struct = StructureGeneratorTest.struct
 
+    private fun noDefaultProvider(model: Model, codegenConfig: CodegenConfig = CodegenConfig()): RustSymbolProvider {
+        val baseProvider: RustSymbolProvider = testSymbolProvider(model)
+        return object : RustSymbolProvider {
+            override fun config(): SymbolVisitorConfig {
+                return baseProvider.config().copy(codegenConfig = codegenConfig)
+            }
+
+            override fun toEnumVariantName(definition: EnumDefinition): MaybeRenamed? {
+                return baseProvider.toEnumVariantName(definition)
+            }
+
+            override fun toSymbol(shape: Shape?): Symbol {
+                return baseProvider.toSymbol(shape).toBuilder().setDefault(Default.NoDefault).build()
+            }
+
+            override fun toMemberName(shape: MemberShape?): String {
+                return baseProvider.toMemberName(shape)
+            }
+        }
+    }
+
     @Test
     fun `generate builders`() {
         val provider = testSymbolProvider(model)
@@ -49,25 +76,7 @@ internal class BuilderGeneratorTest {
 
     @Test
     fun `generate fallible builders`() {
-        val baseProvider: RustSymbolProvider = testSymbolProvider(StructureGeneratorTest.model)
-        val provider =
-            object : RustSymbolProvider {
-                override fun config(): SymbolVisitorConfig {
-                    return baseProvider.config()
-                }
-
-                override fun toEnumVariantName(definition: EnumDefinition): MaybeRenamed? {
-                    return baseProvider.toEnumVariantName(definition)
-                }
-
-                override fun toSymbol(shape: Shape?): Symbol {
-                    return baseProvider.toSymbol(shape).toBuilder().setDefault(Default.NoDefault).build()
-                }
-
-                override fun toMemberName(shape: MemberShape?): String {
-                    return baseProvider.toMemberName(shape)
-                }
-            }
+        val provider = noDefaultProvider(StructureGeneratorTest.model)
         val writer = RustWriter.forModule("model")
         val innerGenerator = StructureGenerator(
             StructureGeneratorTest.model, provider, writer,
@@ -92,4 +101,63 @@ internal class BuilderGeneratorTest {
             """
         )
     }
+
+    @Test
+    fun `fallible builders check constraints when enabled`() {
+        val model = """
+            namespace com.test
+            structure Constrained {
+                @range(min: 1, max: 1000)
+                maxKeys: PrimitiveInteger,
+                @length(min: 3, max: 5)
+                name: String,
+            }
+        """.asSmithyModel()
+        val shape = model.lookup<StructureShape>("com.test#Constrained")
+        val provider = noDefaultProvider(model, CodegenConfig(validateConstraints = true))
+        val writer = RustWriter.forModule("model")
+        StructureGenerator(model, provider, writer, shape).render()
+        val builderGenerator = BuilderGenerator(model, provider, shape)
+        builderGenerator.render(writer)
+        writer.implBlock(shape, provider) {
+            builderGenerator.renderConvenienceMethod(this)
+        }
+        writer.compileAndTest(
+            """
+            let constrained = Constrained::builder().max_keys(1000).name("abc").build().expect("valid");
+            assert_eq!(constrained.max_keys, 1000);
+            let err = Constrained::builder().max_keys(1001).build().expect_err("out of range");
+            assert!(matches!(err, ${writer.format(TestRuntimeConfig.operationBuildError())}::ConstraintViolation(_)));
+            assert_eq!(Some("max_keys"), err.field());
+            let err = Constrained::builder().max_keys(1).name("ab").build().expect_err("too short");
+            assert_eq!(Some("name"), err.field());
+            """
+        )
+    }
+
+    @Test
+    fun `fallible builders skip constraint checks by default`() {
+        val model = """
+            namespace com.test
+            structure Constrained {
+                @range(min: 1, max: 1000)
+                maxKeys: PrimitiveInteger,
+            }
+        """.asSmithyModel()
+        val shape = model.lookup<StructureShape>("com.test#Constrained")
+        val provider = noDefaultProvider(model)
+        val writer = RustWriter.forModule("model")
+        StructureGenerator(model, provider, writer, shape).render()
+        val builderGenerator = BuilderGenerator(model, provider, shape)
+        builderGenerator.render(writer)
+        writer.implBlock(shape, provider) {
+            builderGenerator.renderConvenienceMethod(this)
+        }
+        writer.compileAndTest(
+            """
+            let constrained = Constrained::builder().max_keys(1001).build().expect("left for the service to validate");
+            assert_eq!(constrained.max_keys, 1001);
+            """
+        )
+    }
 }
@@ -6,11 +6,12 @@
 package software.amazon.smithy.rust.codegen.smithy.generators.config
 
 import org.junit.jupiter.api.Test
+import software.amazon.smithy.rust.codegen.testutil.TestRuntimeConfig
 import software.amazon.smithy.rust.codegen.testutil.validateConfigCustomizations
 
 class IdempotencyTokenProviderCustomizationTest {
     @Test
     fun `generates a valid config`() {
-        validateConfigCustomizations(IdempotencyTokenProviderCustomization())
+        validateConfigCustomizations(IdempotencyTokenProviderCustomization(TestRuntimeConfig))
     }
 }
@@ -0,0 +1,128 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+package software.amazon.smithy.rust.codegen.smithy.protocols
+
+import io.kotest.matchers.string.shouldContain
+import io.kotest.matchers.string.shouldNotContain
+import org.junit.jupiter.api.Test
+import software.amazon.smithy.model.Model
+import software.amazon.smithy.rust.codegen.smithy.RustCodegenPlugin
+import software.amazon.smithy.rust.codegen.testutil.asSmithyModel
+import software.amazon.smithy.rust.codegen.testutil.generatePluginContext
+import software.amazon.smithy.rust.codegen.util.runCommand
+import kotlin.io.path.readText
+
+class AwsJsonTest {
+    // The Smithy version this is built against doesn't define `awsQueryCompatible` yet
+    private val queryCompatibleTrait = """
+        ${'$'}version: "1.0"
+        namespace aws.protocols
+
+        @trait(selector: "service")
+        structure awsQueryCompatible {}
+    """
+
+    private fun model(serviceTraits: String) = """
+        ${'$'}version: "1.0"
+        namespace test
+        use aws.protocols#awsJson1_0
+        use aws.protocols#awsQueryCompatible
+
+        @awsJson1_0
+        $serviceTraits
+        service TestService {
+            version: "2019-12-16",
+            operations: [SomeOperation]
+        }
+
+        operation SomeOperation {
+            input: SomeOperationInputOutput,
+            output: SomeOperationInputOutput,
+            errors: [QueueDoesNotExist]
+        }
+
+        structure SomeOperationInputOutput {
+            payload: String,
+            a: String,
+            b: Integer
+        }
+
+        @error("client")
+        structure QueueDoesNotExist {
+            message: String
+        }
+    """
+
+    private fun generate(serviceTraits: String): String {
+        val model = Model.assembler().discoverModels()
+            .addUnparsedModel("aws-query-compatible.smithy", queryCompatibleTrait)
+            .addUnparsedModel("test.smithy", model(serviceTraits))
+            .assemble()
+            .unwrap()
+        val (pluginContext, testDir) = generatePluginContext(model)
+        RustCodegenPlugin().execute(pluginContext)
+        "cargo check".runCommand(testDir)
+        return testDir.resolve("src/json_deser.rs").readText()
+    }
+
+    @Test
+    fun `generate an aws json service that compiles`() {
+        generate("") shouldNotContain "aws_query_compatible_errors"
+    }
+
+    @Test
+    fun `aws query compatible services record the query error code`() {
+        generate("@awsQueryCompatible") shouldContain "apply_aws_query_compatible_error"
+    }
+
+    @Test
+    fun `event streams send and receive the other members in initial messages`() {
+        val model = """
+            namespace test
+            use aws.protocols#awsJson1_1
+
+            @awsJson1_1
+            service TestService {
+                version: "2019-12-16",
+                operations: [Subscribe]
+            }
+
+            operation Subscribe {
+                input: SubscribeInput,
+                output: SubscribeOutput
+            }
+
+            structure SubscribeInput {
+                channel: String,
+                events: InputEvents
+            }
+
+            structure SubscribeOutput {
+                sessionId: String,
+                events: OutputEvents
+            }
+
+            @streaming
+            union InputEvents {
+                message: Message
+            }
+
+            @streaming
+            union OutputEvents {
+                message: Message
+            }
+
+            structure Message {
+                text: String
+            }
+        """.asSmithyModel()
+        val (pluginContext, testDir) = generatePluginContext(model)
+        RustCodegenPlugin().execute(pluginContext)
+        "cargo check".runCommand(testDir)
+        testDir.resolve("src/input.rs").readText() shouldContain "into_body_stream_with_initial_request"
+        testDir.resolve("src/operation.rs").readText() shouldContain "pub async fn receive_initial_response"
+    }
+}
@@ -105,7 +105,7 @@ class EventStreamUnmarshallerGeneratorTest {
                     let result = ${writer.format(generator.render())}().unmarshall(&message);
                     assert!(result.is_ok(), "expected ok, got: {:?}", result);
                     assert_eq!(
-                        TestStream::Unknown,
+                        TestStream::Unknown("NewUnmodeledMessageType".into()),
                         expect_event(result.unwrap())
                     );
                     """,
@@ -164,7 +164,32 @@ class JsonParserGeneratorTest {
                 // unknown variant
                 let input = br#"{ "top": { "choice": { "somenewvariant": "data" } } }"#;
                 let output = ${writer.format(operationGenerator)}(input, output::op_output::Builder::default()).unwrap().build();
-                assert!(output.top.unwrap().choice.unwrap().is_unknown());
+                let choice = output.top.unwrap().choice.unwrap();
+                assert!(choice.is_unknown());
+                assert_eq!(Some("somenewvariant"), choice.unknown_member_name());
+                """
+            )
+            writer.unitTest(
+                "out_of_range_integer",
+                """
+                use model::Choice;
+
+                // `extra` is an `i32`, so it must not be truncated
+                let input = br#"{ "top": { "extra": 4294967296 } }"#;
+                ${writer.format(operationGenerator)}(input, output::op_output::Builder::default()).expect_err("out of range");
+                let input = br#"{ "top": { "choice": { "long": 9007199254740993 } } }"#;
+                let output = ${writer.format(operationGenerator)}(input, output::op_output::Builder::default()).unwrap().build();
+                assert_eq!(Some(Choice::Long(9007199254740993)), output.top.unwrap().choice);
+                """
+            )
+
+            writer.unitTest(
+                "error_position",
+                """
+                let input = b"{\n  \"top\": {\n    \"extra\": true\n  }\n}";
+                let err = ${writer.format(operationGenerator)}(input, output::op_output::Builder::default()).expect_err("not a number");
+                let position = err.position().expect("position");
+                assert_eq!((3, 14), (position.line(), position.column()));
                 """
             )
 
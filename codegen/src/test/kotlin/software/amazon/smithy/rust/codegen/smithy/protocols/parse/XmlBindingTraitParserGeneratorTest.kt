@@ -187,7 +187,9 @@ internal class XmlBindingTraitParserGeneratorTest {
                     </Top>
                     "#;
                     let output = ${writer.format(operationParser)}(xml, output::op_output::Builder::default()).unwrap().build();
-                    assert!(output.choice.unwrap().is_unknown());
+                    let choice = output.choice.unwrap();
+                    assert!(choice.is_unknown());
+                    assert_eq!(Some("NewVariantName"), choice.unknown_member_name());
                 """
             )
         }
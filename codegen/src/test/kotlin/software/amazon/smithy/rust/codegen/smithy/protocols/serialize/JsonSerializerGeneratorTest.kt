@@ -133,7 +133,7 @@ class JsonSerializerGeneratorTest {
 
                 let input = crate::input::OpInput::builder().top(
                     Top::builder()
-                        .choice(Choice::Unknown)
+                        .choice(Choice::Unknown("NewVariant".into()))
                         .build()
                 ).build().unwrap();
                 let serialized = ${writer.format(operationGenerator)}(&input).expect_err("cannot serialize unknown variant");
@@ -136,7 +136,7 @@ internal class XmlBindingTraitSerializerGeneratorTest {
                 use model::{Top, Choice};
                 let input = crate::input::OpInput::builder().payload(
                     Top::builder()
-                        .choice(Choice::Unknown)
+                        .choice(Choice::Unknown("NewVariant".into()))
                         .build()
                 ).build().unwrap();
                 ${writer.format(operationSerializer)}(&input.payload.unwrap()).expect_err("cannot serialize unknown variant");
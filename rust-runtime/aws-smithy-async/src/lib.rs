@@ -13,10 +13,18 @@
 //! Future utilities and runtime-agnostic abstractions for smithy-rs.
 //!
 //! Async runtime specific code is abstracted behind async traits, and implementations are
-//! provided via feature flag. For now, only Tokio runtime implementations are provided.
+//! provided via feature flag:
+//!
+//! | Feature    | Description |
+//! |------------|-------------|
+//! | `rt-tokio` | Sleep with the Tokio runtime |
+//! | `rt-wasm`  | On `wasm32` targets, sleep with JavaScript timers and read the time from `Date.now()`, for use in browsers and JavaScript runtimes such as Cloudflare Workers |
 
 pub mod future;
 pub mod rt;
+#[cfg(feature = "test-util")]
+pub mod test_util;
+pub mod time;
 
 /// Given an `Instant` and a `Duration`, assert time elapsed since `Instant` is equal to `Duration`.
 /// This macro allows for a 5ms margin of error.
@@ -45,7 +45,13 @@ pub fn default_async_sleep() -> Option<Arc<dyn AsyncSleep>> {
     Some(sleep_tokio())
 }
 
-#[cfg(not(feature = "rt-tokio"))]
+#[cfg(all(not(feature = "rt-tokio"), feature = "rt-wasm", target_arch = "wasm32"))]
+/// Returns a default sleep implementation based on the features enabled
+pub fn default_async_sleep() -> Option<Arc<dyn AsyncSleep>> {
+    Some(Arc::new(WasmSleep::new()))
+}
+
+#[cfg(not(any(feature = "rt-tokio", all(feature = "rt-wasm", target_arch = "wasm32"))))]
 /// Returns a default sleep implementation based on the features enabled
 pub fn default_async_sleep() -> Option<Arc<dyn AsyncSleep>> {
     None
@@ -103,3 +109,33 @@ impl AsyncSleep for TokioSleep {
 fn sleep_tokio() -> Arc<dyn AsyncSleep> {
     Arc::new(TokioSleep::new())
 }
+
+/// Implementation of [`AsyncSleep`] for `wasm32` targets that run in a JavaScript host.
+///
+/// This sleeps with `setTimeout`, which is available in browsers, web workers, and runtimes such
+/// as Cloudflare Workers. JavaScript hosts are single threaded, so the returned [`Sleep`] must be
+/// polled on the thread that created it.
+#[non_exhaustive]
+#[cfg(all(feature = "rt-wasm", target_arch = "wasm32"))]
+#[derive(Debug, Default)]
+pub struct WasmSleep;
+
+#[cfg(all(feature = "rt-wasm", target_arch = "wasm32"))]
+impl WasmSleep {
+    /// Create a new [`AsyncSleep`] implementation using JavaScript timers
+    pub fn new() -> WasmSleep {
+        Default::default()
+    }
+}
+
+#[cfg(all(feature = "rt-wasm", target_arch = "wasm32"))]
+impl AsyncSleep for WasmSleep {
+    fn sleep(&self, duration: Duration) -> Sleep {
+        // Round up so that the sleep is never shorter than requested. `setTimeout` fires
+        // immediately for delays that don't fit in a signed 32-bit integer, so clamp to that.
+        let millis = ((duration.as_micros() + 999) / 1000).min(i32::MAX as u128) as u32;
+        Sleep::new(send_wrapper::SendWrapper::new(
+            gloo_timers::future::TimeoutFuture::new(millis),
+        ))
+    }
+}
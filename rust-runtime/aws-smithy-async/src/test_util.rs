@@ -0,0 +1,146 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Test implementations of [`AsyncSleep`] and [`TimeSource`].
+
+use crate::rt::sleep::{AsyncSleep, Sleep};
+use crate::time::TimeSource;
+use std::ops::Deref;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+/// Time source that always returns the same time
+#[derive(Clone, Debug)]
+pub struct StaticTimeSource {
+    time: SystemTime,
+}
+
+impl StaticTimeSource {
+    /// Creates a new `StaticTimeSource` that always returns `time`
+    pub fn new(time: SystemTime) -> Self {
+        Self { time }
+    }
+}
+
+impl TimeSource for StaticTimeSource {
+    fn now(&self) -> SystemTime {
+        self.time
+    }
+}
+
+/// Time source that only moves when it is advanced, either manually or by an [`InstantSleep`]
+///
+/// Every reading of the time is recorded, and can be inspected with [`queries`](Self::queries).
+#[derive(Clone, Debug)]
+pub struct ManualTimeSource {
+    queries: Arc<Mutex<Vec<SystemTime>>>,
+    now: Arc<Mutex<SystemTime>>,
+}
+
+impl ManualTimeSource {
+    /// Creates a new `ManualTimeSource` starting at `start_time`
+    pub fn new(start_time: SystemTime) -> Self {
+        Self {
+            queries: Default::default(),
+            now: Arc::new(Mutex::new(start_time)),
+        }
+    }
+
+    /// Sets the current time to `time`
+    pub fn set_time(&mut self, time: SystemTime) {
+        *self.now.lock().unwrap() = time;
+    }
+
+    /// Moves the time forward by `delta`
+    pub fn advance(&mut self, delta: Duration) {
+        *self.now.lock().unwrap() += delta;
+    }
+
+    /// Returns every time that has been read from this time source so far
+    pub fn queries(&self) -> impl Deref<Target = Vec<SystemTime>> + '_ {
+        self.queries.lock().unwrap()
+    }
+
+    /// Returns the current time, and records it in [`queries`](Self::queries)
+    pub fn now(&self) -> SystemTime {
+        let now = *self.now.lock().unwrap();
+        self.queries.lock().unwrap().push(now);
+        now
+    }
+}
+
+impl TimeSource for ManualTimeSource {
+    fn now(&self) -> SystemTime {
+        ManualTimeSource::now(self)
+    }
+}
+
+/// Sleep implementation that completes immediately and records every requested duration
+///
+/// When created with [`instant_time_and_sleep`], sleeping also advances the paired
+/// [`ManualTimeSource`], so code under test observes time passing without actually waiting.
+#[derive(Clone, Debug, Default)]
+pub struct InstantSleep {
+    log: Arc<Mutex<Vec<Duration>>>,
+    time_source: Option<ManualTimeSource>,
+}
+
+impl InstantSleep {
+    /// Creates a new `InstantSleep` that isn't paired with a time source
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the durations of all sleeps so far
+    pub fn logs(&self) -> Vec<Duration> {
+        self.log.lock().unwrap().clone()
+    }
+
+    /// Returns the sum of all sleeps so far
+    pub fn total_duration(&self) -> Duration {
+        self.log.lock().unwrap().iter().sum()
+    }
+}
+
+impl AsyncSleep for InstantSleep {
+    fn sleep(&self, duration: Duration) -> Sleep {
+        self.log.lock().unwrap().push(duration);
+        if let Some(time_source) = &self.time_source {
+            *time_source.now.lock().unwrap() += duration;
+        }
+        Sleep::new(std::future::ready(()))
+    }
+}
+
+/// Returns a [`ManualTimeSource`] starting at `start_time`, and an [`InstantSleep`] that
+/// advances it
+pub fn instant_time_and_sleep(start_time: SystemTime) -> (ManualTimeSource, InstantSleep) {
+    let time_source = ManualTimeSource::new(start_time);
+    let sleep = InstantSleep {
+        log: Default::default(),
+        time_source: Some(time_source.clone()),
+    };
+    (time_source, sleep)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rt::sleep::AsyncSleep;
+    use crate::test_util::instant_time_and_sleep;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    #[tokio::test]
+    async fn sleeping_advances_time() {
+        let (time_source, sleep) = instant_time_and_sleep(UNIX_EPOCH);
+        sleep.sleep(Duration::from_secs(5)).await;
+        sleep.sleep(Duration::from_secs(2)).await;
+        assert_eq!(time_source.now(), UNIX_EPOCH + Duration::from_secs(7));
+        assert_eq!(
+            sleep.logs(),
+            vec![Duration::from_secs(5), Duration::from_secs(2)]
+        );
+        assert_eq!(sleep.total_duration(), Duration::from_secs(7));
+    }
+}
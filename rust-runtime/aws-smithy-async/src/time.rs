@@ -0,0 +1,169 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Provides a [`TimeSource`] trait that returns the current time, so that code that depends on
+//! the wall clock (e.g. request signing and credentials expiry) can be tested deterministically.
+
+use std::fmt::Debug;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+/// A monotonic clock reading, used to measure how long something took
+///
+/// This is [`std::time::Instant`], except with the `rt-wasm` feature on `wasm32` targets, where
+/// `std::time::Instant::now` panics. There, the clock is read from JavaScript's `Date.now()`
+/// instead.
+#[cfg(not(all(feature = "rt-wasm", target_arch = "wasm32")))]
+pub use std::time::Instant;
+
+#[cfg(all(feature = "rt-wasm", target_arch = "wasm32"))]
+pub use wasm::Instant;
+
+#[cfg(all(feature = "rt-wasm", target_arch = "wasm32"))]
+mod wasm {
+    use std::ops::{Add, AddAssign, Sub, SubAssign};
+    use std::time::Duration;
+
+    /// A clock reading, used to measure how long something took
+    ///
+    /// `std::time::Instant::now` panics on `wasm32` targets, so the clock is read from JavaScript's
+    /// `Date.now()` instead. That clock has millisecond precision and can go backwards, so
+    /// durations between readings saturate at zero.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    pub struct Instant(Duration);
+
+    impl Instant {
+        /// Returns the current time
+        pub fn now() -> Self {
+            Instant(Duration::from_secs_f64(js_sys::Date::now() / 1000.0))
+        }
+
+        /// Returns the time elapsed from `earlier` to this instant, or zero if `earlier` is later
+        pub fn duration_since(&self, earlier: Instant) -> Duration {
+            self.saturating_duration_since(earlier)
+        }
+
+        /// Returns the time elapsed from `earlier` to this instant, or `None` if `earlier` is later
+        pub fn checked_duration_since(&self, earlier: Instant) -> Option<Duration> {
+            self.0.checked_sub(earlier.0)
+        }
+
+        /// Returns the time elapsed from `earlier` to this instant, or zero if `earlier` is later
+        pub fn saturating_duration_since(&self, earlier: Instant) -> Duration {
+            self.checked_duration_since(earlier).unwrap_or_default()
+        }
+
+        /// Returns the time elapsed since this instant
+        pub fn elapsed(&self) -> Duration {
+            Instant::now().duration_since(*self)
+        }
+
+        /// Returns this instant plus `duration`, or `None` on overflow
+        pub fn checked_add(&self, duration: Duration) -> Option<Instant> {
+            self.0.checked_add(duration).map(Instant)
+        }
+
+        /// Returns this instant minus `duration`, or `None` if it would be before the epoch
+        pub fn checked_sub(&self, duration: Duration) -> Option<Instant> {
+            self.0.checked_sub(duration).map(Instant)
+        }
+    }
+
+    impl Add<Duration> for Instant {
+        type Output = Instant;
+
+        fn add(self, rhs: Duration) -> Instant {
+            self.checked_add(rhs)
+                .expect("overflow when adding duration to instant")
+        }
+    }
+
+    impl AddAssign<Duration> for Instant {
+        fn add_assign(&mut self, rhs: Duration) {
+            *self = *self + rhs;
+        }
+    }
+
+    impl Sub<Duration> for Instant {
+        type Output = Instant;
+
+        fn sub(self, rhs: Duration) -> Instant {
+            self.checked_sub(rhs)
+                .expect("overflow when subtracting duration from instant")
+        }
+    }
+
+    impl SubAssign<Duration> for Instant {
+        fn sub_assign(&mut self, rhs: Duration) {
+            *self = *self - rhs;
+        }
+    }
+
+    impl Sub<Instant> for Instant {
+        type Output = Duration;
+
+        fn sub(self, rhs: Instant) -> Duration {
+            self.duration_since(rhs)
+        }
+    }
+}
+
+/// Trait with a `now()` function returning the current time
+pub trait TimeSource: Debug + Send + Sync {
+    /// Returns the current time
+    fn now(&self) -> SystemTime;
+}
+
+/// Time source that delegates to [`SystemTime::now`]
+///
+/// With the `rt-wasm` feature on `wasm32` targets, the time comes from JavaScript's `Date.now()`
+/// instead.
+#[non_exhaustive]
+#[derive(Debug, Default)]
+pub struct SystemTimeSource;
+
+impl SystemTimeSource {
+    /// Creates a new `SystemTimeSource`
+    pub fn new() -> Self {
+        SystemTimeSource
+    }
+}
+
+impl TimeSource for SystemTimeSource {
+    #[cfg(not(all(feature = "rt-wasm", target_arch = "wasm32")))]
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+
+    // `SystemTime::now` panics on `wasm32-unknown-unknown`, so ask the JavaScript host instead
+    #[cfg(all(feature = "rt-wasm", target_arch = "wasm32"))]
+    fn now(&self) -> SystemTime {
+        let millis = js_sys::Date::now();
+        std::time::UNIX_EPOCH + std::time::Duration::from_secs_f64(millis / 1000.0)
+    }
+}
+
+/// A cheaply cloneable [`TimeSource`] that can be stored in a property bag or config
+#[derive(Clone, Debug)]
+pub struct SharedTimeSource(Arc<dyn TimeSource>);
+
+impl SharedTimeSource {
+    /// Creates a new `SharedTimeSource` from `time_source`
+    pub fn new(time_source: impl TimeSource + 'static) -> Self {
+        Self(Arc::new(time_source))
+    }
+}
+
+impl Default for SharedTimeSource {
+    fn default() -> Self {
+        Self::new(SystemTimeSource::new())
+    }
+}
+
+impl TimeSource for SharedTimeSource {
+    fn now(&self) -> SystemTime {
+        self.0.now()
+    }
+}
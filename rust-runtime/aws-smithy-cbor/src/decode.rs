@@ -0,0 +1,837 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! CBOR decoding
+//!
+//! ```
+//! use aws_smithy_cbor::decode::Decoder;
+//!
+//! // {_ "name": "smithy", "tags": ["rpc", "cbor"]}
+//! let bytes = b"\xBF\x64name\x66smithy\x64tags\x82\x63rpc\x64cbor\xFF";
+//! let mut decoder = Decoder::new(bytes);
+//! let mut entries = decoder.map().unwrap();
+//! while decoder.has_next(&mut entries).unwrap() {
+//!     match decoder.str().unwrap().as_ref() {
+//!         "name" => assert_eq!("smithy", decoder.str().unwrap()),
+//!         _ => decoder.skip().unwrap(),
+//!     }
+//! }
+//! ```
+
+use crate::additional_info::{
+    EIGHT_BYTES, FALSE, FOUR_BYTES, INDEFINITE_LENGTH, NULL, ONE_BYTE, TRUE, TWO_BYTES, UNDEFINED,
+};
+use crate::major_type::{
+    ARRAY, BYTE_STRING, MAP, NEGATIVE_INTEGER, SIMPLE, TAG, TEXT_STRING, UNSIGNED_INTEGER,
+};
+use crate::{BREAK, EPOCH_TIMESTAMP_TAG};
+use aws_smithy_types::{Blob, DateTime};
+use std::borrow::Cow;
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+
+/// The deepest nesting of arrays, maps, and tags that [`Decoder::skip`] will descend into
+const MAX_NESTING_DEPTH: usize = 128;
+
+/// The type of a CBOR data item
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Type {
+    /// An integer from 0 to 2^64 - 1
+    UnsignedInteger,
+    /// An integer from -2^64 to -1
+    NegativeInteger,
+    /// A byte string, with a definite or indefinite length
+    Bytes,
+    /// A UTF-8 text string, with a definite or indefinite length
+    String,
+    /// An array, with a definite or indefinite length
+    Array,
+    /// A map, with a definite or indefinite length
+    Map,
+    /// A tag around another data item
+    Tag,
+    /// `false` or `true`
+    Bool,
+    /// `null`
+    Null,
+    /// `undefined`
+    Undefined,
+    /// A half, single, or double-precision float
+    Float,
+    /// The "break" stop code that terminates an indefinite-length data item
+    Break,
+    /// Another simple value
+    Simple,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum DeserializeErrorKind {
+    Custom(Cow<'static, str>),
+    UnexpectedEof,
+    UnexpectedType { expected: &'static str, found: Type },
+    UnexpectedTag(u64),
+    IntegerOutOfRange,
+    TimestampOutOfRange,
+    InvalidUtf8,
+    InvalidIndefiniteLengthChunk,
+    ReservedAdditionalInformation(u8),
+    NestingTooDeep,
+}
+
+/// An error encountered while decoding CBOR
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeserializeError {
+    kind: DeserializeErrorKind,
+    offset: usize,
+}
+
+impl DeserializeError {
+    fn new(kind: DeserializeErrorKind, offset: usize) -> Self {
+        Self { kind, offset }
+    }
+
+    /// Creates a custom error for the data item at `offset`
+    pub fn custom(message: impl Into<Cow<'static, str>>, offset: usize) -> Self {
+        Self::new(DeserializeErrorKind::Custom(message.into()), offset)
+    }
+
+    /// The byte offset of the data item that couldn't be decoded
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+impl fmt::Display for DeserializeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use DeserializeErrorKind::*;
+        write!(f, "Error at offset {}: ", self.offset)?;
+        match &self.kind {
+            Custom(message) => write!(f, "failed to decode CBOR: {}", message),
+            UnexpectedEof => write!(f, "unexpected end of input"),
+            UnexpectedType { expected, found } => {
+                write!(f, "expected {}, but found {:?}", expected, found)
+            }
+            UnexpectedTag(tag) => write!(f, "unexpected tag {}", tag),
+            IntegerOutOfRange => write!(f, "integer is out of range"),
+            TimestampOutOfRange => write!(f, "timestamp is not finite or is out of range"),
+            InvalidUtf8 => write!(f, "invalid UTF-8 in text string"),
+            InvalidIndefiniteLengthChunk => write!(
+                f,
+                "indefinite-length strings may only contain definite-length strings of the same type"
+            ),
+            ReservedAdditionalInformation(info) => {
+                write!(f, "reserved additional information value {}", info)
+            }
+            NestingTooDeep => write!(f, "exceeded the maximum nesting depth"),
+        }
+    }
+}
+
+impl Error for DeserializeError {}
+
+/// Reads CBOR data items from a byte slice
+///
+/// Each method reads one data item of the expected type, and fails without consuming anything if
+/// the next data item has a different type. Use [`datatype`](Decoder::datatype) to look at the next
+/// data item before reading it, e.g. to check for `null`.
+#[derive(Debug)]
+pub struct Decoder<'b> {
+    input: &'b [u8],
+    position: usize,
+}
+
+impl<'b> Decoder<'b> {
+    /// Creates a decoder that reads from the start of `input`
+    pub fn new(input: &'b [u8]) -> Self {
+        Self { input, position: 0 }
+    }
+
+    /// The byte offset of the next data item
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    fn error(&self, kind: DeserializeErrorKind) -> DeserializeError {
+        DeserializeError::new(kind, self.position)
+    }
+
+    fn peek_byte(&self) -> Result<u8, DeserializeError> {
+        self.input
+            .get(self.position)
+            .copied()
+            .ok_or_else(|| self.error(DeserializeErrorKind::UnexpectedEof))
+    }
+
+    fn read_bytes(&mut self, len: u64) -> Result<&'b [u8], DeserializeError> {
+        let end = usize::try_from(len)
+            .ok()
+            .and_then(|len| self.position.checked_add(len))
+            .filter(|end| *end <= self.input.len())
+            .ok_or_else(|| self.error(DeserializeErrorKind::UnexpectedEof))?;
+        let bytes = &self.input[self.position..end];
+        self.position = end;
+        Ok(bytes)
+    }
+
+    /// Reads the argument that follows the initial byte for the given additional information
+    fn read_argument(&mut self, additional_info: u8) -> Result<u64, DeserializeError> {
+        fn be_bytes<const N: usize>(bytes: &[u8]) -> [u8; N] {
+            <[u8; N]>::try_from(bytes).expect("read exactly N bytes")
+        }
+        Ok(match additional_info {
+            0..=23 => additional_info.into(),
+            ONE_BYTE => self.read_bytes(1)?[0].into(),
+            TWO_BYTES => u16::from_be_bytes(be_bytes(self.read_bytes(2)?)).into(),
+            FOUR_BYTES => u32::from_be_bytes(be_bytes(self.read_bytes(4)?)).into(),
+            EIGHT_BYTES => u64::from_be_bytes(be_bytes(self.read_bytes(8)?)),
+            _ => {
+                return Err(DeserializeError::new(
+                    DeserializeErrorKind::ReservedAdditionalInformation(additional_info),
+                    self.position - 1,
+                ))
+            }
+        })
+    }
+
+    /// Returns the type of the next data item without consuming it
+    pub fn datatype(&self) -> Result<Type, DeserializeError> {
+        let initial = self.peek_byte()?;
+        Ok(match (initial >> 5, initial & 0x1F) {
+            (UNSIGNED_INTEGER, _) => Type::UnsignedInteger,
+            (NEGATIVE_INTEGER, _) => Type::NegativeInteger,
+            (BYTE_STRING, _) => Type::Bytes,
+            (TEXT_STRING, _) => Type::String,
+            (ARRAY, _) => Type::Array,
+            (MAP, _) => Type::Map,
+            (TAG, _) => Type::Tag,
+            (_, FALSE) | (_, TRUE) => Type::Bool,
+            (_, NULL) => Type::Null,
+            (_, UNDEFINED) => Type::Undefined,
+            (_, TWO_BYTES) | (_, FOUR_BYTES) | (_, EIGHT_BYTES) => Type::Float,
+            (_, INDEFINITE_LENGTH) => Type::Break,
+            _ => Type::Simple,
+        })
+    }
+
+    /// Reads the initial byte of a data item of `major_type`, and returns its additional information
+    fn expect_initial_byte(
+        &mut self,
+        major_type: u8,
+        expected: &'static str,
+    ) -> Result<u8, DeserializeError> {
+        let initial = self.peek_byte()?;
+        if initial >> 5 != major_type {
+            return Err(self.unexpected_type(expected));
+        }
+        self.position += 1;
+        Ok(initial & 0x1F)
+    }
+
+    fn unexpected_type(&self, expected: &'static str) -> DeserializeError {
+        match self.datatype() {
+            Ok(found) => self.error(DeserializeErrorKind::UnexpectedType { expected, found }),
+            Err(err) => err,
+        }
+    }
+
+    /// Reads the head of a string, array, or map, returning its length if it's not indefinite
+    fn read_length(
+        &mut self,
+        major_type: u8,
+        expected: &'static str,
+    ) -> Result<Option<u64>, DeserializeError> {
+        match self.expect_initial_byte(major_type, expected)? {
+            INDEFINITE_LENGTH => Ok(None),
+            additional_info => self.read_argument(additional_info).map(Some),
+        }
+    }
+
+    /// Reads a definite or indefinite-length string of `major_type`
+    ///
+    /// Each chunk of an indefinite-length string is passed to `chunk` separately.
+    fn read_string(
+        &mut self,
+        major_type: u8,
+        expected: &'static str,
+        mut chunk: impl FnMut(&'b [u8], usize) -> Result<(), DeserializeError>,
+    ) -> Result<(), DeserializeError> {
+        if let Some(len) = self.read_length(major_type, expected)? {
+            let start = self.position;
+            return chunk(self.read_bytes(len)?, start);
+        }
+        while self.peek_byte()? != BREAK {
+            let initial = self.peek_byte()?;
+            if initial >> 5 != major_type || initial & 0x1F == INDEFINITE_LENGTH {
+                return Err(self.error(DeserializeErrorKind::InvalidIndefiniteLengthChunk));
+            }
+            self.position += 1;
+            let len = self.read_argument(initial & 0x1F)?;
+            let start = self.position;
+            chunk(self.read_bytes(len)?, start)?;
+        }
+        self.position += 1;
+        Ok(())
+    }
+
+    /// Reads an integer as an `i128`, which fits every CBOR integer
+    fn read_integer(&mut self) -> Result<i128, DeserializeError> {
+        let initial = self.peek_byte()?;
+        let (major_type, additional_info) = (initial >> 5, initial & 0x1F);
+        if major_type != UNSIGNED_INTEGER && major_type != NEGATIVE_INTEGER {
+            return Err(self.unexpected_type("an integer"));
+        }
+        self.position += 1;
+        let argument = i128::from(self.read_argument(additional_info)?);
+        Ok(if major_type == NEGATIVE_INTEGER {
+            -1 - argument
+        } else {
+            argument
+        })
+    }
+
+    fn read_integer_as<T: TryFrom<i128>>(&mut self) -> Result<T, DeserializeError> {
+        let start = self.position;
+        let value = self.read_integer()?;
+        T::try_from(value)
+            .map_err(|_| DeserializeError::new(DeserializeErrorKind::IntegerOutOfRange, start))
+    }
+
+    /// Reads a half, single, or double-precision float as an `f64`
+    fn read_float(&mut self) -> Result<f64, DeserializeError> {
+        let initial = self.peek_byte()?;
+        if initial >> 5 != SIMPLE {
+            return Err(self.unexpected_type("a float"));
+        }
+        let bits = match initial & 0x1F {
+            TWO_BYTES | FOUR_BYTES | EIGHT_BYTES => {
+                self.position += 1;
+                self.read_argument(initial & 0x1F)?
+            }
+            _ => return Err(self.unexpected_type("a float")),
+        };
+        Ok(match initial & 0x1F {
+            TWO_BYTES => half_to_f64(bits as u16),
+            FOUR_BYTES => f32::from_bits(bits as u32).into(),
+            _ => f64::from_bits(bits),
+        })
+    }
+
+    /// Reads `null`
+    pub fn null(&mut self) -> Result<(), DeserializeError> {
+        if self.peek_byte()? != (SIMPLE << 5) | NULL {
+            return Err(self.unexpected_type("null"));
+        }
+        self.position += 1;
+        Ok(())
+    }
+
+    /// Reads a boolean
+    pub fn boolean(&mut self) -> Result<bool, DeserializeError> {
+        let value = match self.peek_byte()? {
+            initial if initial == (SIMPLE << 5) | FALSE => false,
+            initial if initial == (SIMPLE << 5) | TRUE => true,
+            _ => return Err(self.unexpected_type("a boolean")),
+        };
+        self.position += 1;
+        Ok(value)
+    }
+
+    /// Reads a Smithy `byte`, failing if the integer is out of range
+    pub fn byte(&mut self) -> Result<i8, DeserializeError> {
+        self.read_integer_as()
+    }
+
+    /// Reads a Smithy `short`, failing if the integer is out of range
+    pub fn short(&mut self) -> Result<i16, DeserializeError> {
+        self.read_integer_as()
+    }
+
+    /// Reads a Smithy `integer`, failing if the integer is out of range
+    pub fn integer(&mut self) -> Result<i32, DeserializeError> {
+        self.read_integer_as()
+    }
+
+    /// Reads a Smithy `long`, failing if the integer is out of range
+    pub fn long(&mut self) -> Result<i64, DeserializeError> {
+        self.read_integer_as()
+    }
+
+    /// Reads a float of any precision as an `f32`, rounding doubles to the nearest `f32`
+    pub fn float(&mut self) -> Result<f32, DeserializeError> {
+        self.read_float().map(|value| value as f32)
+    }
+
+    /// Reads a float of any precision as an `f64`
+    pub fn double(&mut self) -> Result<f64, DeserializeError> {
+        self.read_float()
+    }
+
+    /// Reads a text string
+    ///
+    /// Definite-length strings are borrowed from the input, while the chunks of
+    /// indefinite-length strings are copied into a new string.
+    pub fn str(&mut self) -> Result<Cow<'b, str>, DeserializeError> {
+        let mut value: Option<Cow<'b, str>> = None;
+        self.read_string(TEXT_STRING, "a text string", |chunk, start| {
+            // every chunk must be valid UTF-8 by itself
+            let chunk = std::str::from_utf8(chunk)
+                .map_err(|_| DeserializeError::new(DeserializeErrorKind::InvalidUtf8, start))?;
+            value = Some(match value.take() {
+                None => Cow::Borrowed(chunk),
+                Some(mut value) => {
+                    value.to_mut().push_str(chunk);
+                    value
+                }
+            });
+            Ok(())
+        })?;
+        Ok(value.unwrap_or(Cow::Borrowed("")))
+    }
+
+    /// Reads a text string into a `String`
+    pub fn string(&mut self) -> Result<String, DeserializeError> {
+        self.str().map(Cow::into_owned)
+    }
+
+    /// Reads a byte string
+    pub fn blob(&mut self) -> Result<Blob, DeserializeError> {
+        let mut value = Vec::new();
+        self.read_string(BYTE_STRING, "a byte string", |chunk, _| {
+            value.extend_from_slice(chunk);
+            Ok(())
+        })?;
+        Ok(Blob::new(value))
+    }
+
+    /// Reads a timestamp: a tag 1 (epoch-based date/time) around an integer or float number of
+    /// seconds since the Unix epoch
+    pub fn timestamp(&mut self) -> Result<DateTime, DeserializeError> {
+        let start = self.position;
+        let additional_info = self.expect_initial_byte(TAG, "a timestamp")?;
+        let tag = self.read_argument(additional_info)?;
+        if tag != EPOCH_TIMESTAMP_TAG {
+            return Err(DeserializeError::new(
+                DeserializeErrorKind::UnexpectedTag(tag),
+                start,
+            ));
+        }
+        match self.datatype()? {
+            Type::UnsignedInteger | Type::NegativeInteger => self.long().map(DateTime::from_secs),
+            Type::Float => {
+                let secs = self.double()?;
+                timestamp_from_secs_f64(secs).ok_or_else(|| {
+                    DeserializeError::new(DeserializeErrorKind::TimestampOutOfRange, start)
+                })
+            }
+            _ => Err(self.unexpected_type("an integer or float in a timestamp")),
+        }
+    }
+
+    /// Starts reading an array, returning its number of items if it has a definite length
+    ///
+    /// Use [`has_next`](Decoder::has_next) to iterate over the items of both definite and
+    /// indefinite-length arrays. The length comes from the input, so it shouldn't be trusted to
+    /// preallocate memory.
+    pub fn list(&mut self) -> Result<Option<u64>, DeserializeError> {
+        self.read_length(ARRAY, "an array")
+    }
+
+    /// Starts reading a map, returning its number of entries if it has a definite length
+    ///
+    /// Use [`has_next`](Decoder::has_next) to iterate over the entries of both definite and
+    /// indefinite-length maps. The length comes from the input, so it shouldn't be trusted to
+    /// preallocate memory.
+    pub fn map(&mut self) -> Result<Option<u64>, DeserializeError> {
+        self.read_length(MAP, "a map")
+    }
+
+    /// Returns true if the array or map being read has another item or entry
+    ///
+    /// `remaining` is the value returned by [`list`](Decoder::list) or [`map`](Decoder::map), and
+    /// it's updated as items are read. At the end of an indefinite-length array or map, the break
+    /// that terminates it is consumed.
+    pub fn has_next(&mut self, remaining: &mut Option<u64>) -> Result<bool, DeserializeError> {
+        match remaining {
+            Some(0) => Ok(false),
+            Some(len) => {
+                *len -= 1;
+                Ok(true)
+            }
+            None if self.peek_byte()? == BREAK => {
+                self.position += 1;
+                Ok(false)
+            }
+            None => Ok(true),
+        }
+    }
+
+    /// Skips the next data item, including all of the items it contains
+    pub fn skip(&mut self) -> Result<(), DeserializeError> {
+        self.skip_nested(0)
+    }
+
+    fn skip_nested(&mut self, depth: usize) -> Result<(), DeserializeError> {
+        if depth > MAX_NESTING_DEPTH {
+            return Err(self.error(DeserializeErrorKind::NestingTooDeep));
+        }
+        let initial = self.peek_byte()?;
+        let (major_type, additional_info) = (initial >> 5, initial & 0x1F);
+        match major_type {
+            BYTE_STRING => self.read_string(BYTE_STRING, "a byte string", |_, _| Ok(())),
+            // the contents of skipped strings aren't validated as UTF-8
+            TEXT_STRING => self.read_string(TEXT_STRING, "a text string", |_, _| Ok(())),
+            ARRAY | MAP => {
+                let mut remaining = self.read_length(major_type, "an array or map")?;
+                while self.has_next(&mut remaining)? {
+                    self.skip_nested(depth + 1)?;
+                    if major_type == MAP {
+                        self.skip_nested(depth + 1)?;
+                    }
+                }
+                Ok(())
+            }
+            TAG => {
+                self.position += 1;
+                self.read_argument(additional_info)?;
+                self.skip_nested(depth + 1)
+            }
+            _ if initial == BREAK => Err(self.unexpected_type("a data item")),
+            _ => {
+                // integers, simple values, and floats are just a head
+                self.position += 1;
+                self.read_argument(additional_info).map(|_| ())
+            }
+        }
+    }
+}
+
+/// Converts the bits of a half-precision float to an `f64` (see Appendix D of RFC 8949)
+fn half_to_f64(half: u16) -> f64 {
+    let exponent = i32::from((half >> 10) & 0x1F);
+    let mantissa = f64::from(half & 0x3FF);
+    let value = match exponent {
+        0 => mantissa * 2f64.powi(-24),
+        31 if mantissa == 0.0 => f64::INFINITY,
+        31 => f64::NAN,
+        _ => (mantissa + 1024.0) * 2f64.powi(exponent - 25),
+    };
+    if half & 0x8000 != 0 {
+        -value
+    } else {
+        value
+    }
+}
+
+/// Converts a float number of seconds since the Unix epoch into a `DateTime`, returning `None`
+/// if it isn't finite or doesn't fit
+///
+/// Unlike [`DateTime::from_secs_f64`], this never saturates or panics on input from the wire.
+fn timestamp_from_secs_f64(secs: f64) -> Option<DateTime> {
+    // i64::MIN as f64 is exactly -2^63, and i64::MAX as f64 rounds up to 2^63
+    if !secs.is_finite() || secs < i64::MIN as f64 || secs >= i64::MAX as f64 {
+        return None;
+    }
+    let whole = secs.floor();
+    let mut seconds = whole as i64;
+    // The fraction can round up to 1.0 (e.g. for -1e-17), so carry it into the seconds
+    let mut nanos = ((secs - whole) * 1_000_000_000_f64) as u32;
+    if nanos >= 1_000_000_000 {
+        seconds = seconds.checked_add(1)?;
+        nanos = 0;
+    }
+    Some(DateTime::from_secs_and_nanos(seconds, nanos))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Decoder, DeserializeErrorKind, Type};
+    use crate::encode::Encoder;
+    use aws_smithy_types::{Blob, DateTime};
+    use proptest::prelude::*;
+
+    // Examples from Appendix A of RFC 8949
+    #[test]
+    fn integers() {
+        assert_eq!(Ok(0), Decoder::new(&[0x00]).long());
+        assert_eq!(Ok(24), Decoder::new(&[0x18, 0x18]).byte());
+        assert_eq!(Ok(1000), Decoder::new(&[0x19, 0x03, 0xE8]).short());
+        assert_eq!(
+            Ok(1_000_000),
+            Decoder::new(&[0x1A, 0x00, 0x0F, 0x42, 0x40]).integer()
+        );
+        assert_eq!(Ok(-1), Decoder::new(&[0x20]).long());
+        assert_eq!(Ok(-1000), Decoder::new(&[0x39, 0x03, 0xE7]).integer());
+
+        // 18446744073709551615 and -18446744073709551616
+        let max = [0x1B, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF];
+        let err = Decoder::new(&max).long().unwrap_err();
+        assert_eq!(DeserializeErrorKind::IntegerOutOfRange, err.kind);
+        let min = [0x3B, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF];
+        Decoder::new(&min).long().expect_err("out of range");
+        Decoder::new(&[0x19, 0x01, 0x00])
+            .byte()
+            .expect_err("256 is out of range");
+    }
+
+    #[test]
+    fn floats() {
+        let decode = |bytes: &[u8]| Decoder::new(bytes).double().unwrap();
+        assert_eq!(1.0, decode(&[0xF9, 0x3C, 0x00]));
+        assert_eq!(65504.0, decode(&[0xF9, 0x7B, 0xFF]));
+        assert_eq!(2f64.powi(-24), decode(&[0xF9, 0x00, 0x01]));
+        assert_eq!(2f64.powi(-14), decode(&[0xF9, 0x04, 0x00]));
+        assert_eq!(-4.0, decode(&[0xF9, 0xC4, 0x00]));
+        assert_eq!(f64::INFINITY, decode(&[0xF9, 0x7C, 0x00]));
+        assert!(decode(&[0xF9, 0x7E, 0x00]).is_nan());
+        assert_eq!(100000.0, decode(&[0xFA, 0x47, 0xC3, 0x50, 0x00]));
+        assert_eq!(
+            1.1,
+            decode(&[0xFB, 0x3F, 0xF1, 0x99, 0x99, 0x99, 0x99, 0x99, 0x9A])
+        );
+        assert_eq!(Ok(1.5), Decoder::new(&[0xF9, 0x3E, 0x00]).float());
+        Decoder::new(&[0x01])
+            .double()
+            .expect_err("integers aren't floats");
+    }
+
+    #[test]
+    fn half_precision_floats() {
+        use super::half_to_f64;
+        // positive halves are ordered like their bits, from zero up to infinity
+        for bits in 0..0x7C00 {
+            assert!(half_to_f64(bits) < half_to_f64(bits + 1), "{:04X}", bits);
+            assert_eq!(-half_to_f64(bits), half_to_f64(bits | 0x8000));
+        }
+        assert_eq!(f64::INFINITY, half_to_f64(0x7C00));
+        assert!((0x7C01..0x8000).all(|bits| half_to_f64(bits).is_nan()));
+    }
+
+    #[test]
+    fn simple_values() {
+        assert_eq!(Ok(false), Decoder::new(&[0xF4]).boolean());
+        assert_eq!(Ok(true), Decoder::new(&[0xF5]).boolean());
+        assert_eq!(Ok(()), Decoder::new(&[0xF6]).null());
+        let mut decoder = Decoder::new(&[0xF6]);
+        let err = decoder.boolean().unwrap_err();
+        assert_eq!(
+            DeserializeErrorKind::UnexpectedType {
+                expected: "a boolean",
+                found: Type::Null
+            },
+            err.kind
+        );
+        // the data item wasn't consumed
+        assert_eq!(Ok(()), decoder.null());
+    }
+
+    #[test]
+    fn strings() {
+        assert_eq!("", Decoder::new(&[0x60]).str().unwrap());
+        assert_eq!("IETF", Decoder::new(b"\x64IETF").str().unwrap());
+        // (_ "strea", "ming")
+        assert_eq!(
+            "streaming",
+            Decoder::new(b"\x7F\x65strea\x64ming\xFF").str().unwrap()
+        );
+        // (_ h'0102', h'030405')
+        assert_eq!(
+            Ok(Blob::new(vec![1, 2, 3, 4, 5])),
+            Decoder::new(&[0x5F, 0x42, 0x01, 0x02, 0x43, 0x03, 0x04, 0x05, 0xFF]).blob()
+        );
+        assert_eq!(
+            DeserializeErrorKind::InvalidUtf8,
+            Decoder::new(&[0x62, 0xC3, 0x28]).str().unwrap_err().kind
+        );
+        // a byte string chunk in a text string
+        assert_eq!(
+            DeserializeErrorKind::InvalidIndefiniteLengthChunk,
+            Decoder::new(&[0x7F, 0x41, 0x61, 0xFF])
+                .str()
+                .unwrap_err()
+                .kind
+        );
+        // the length is larger than the input
+        assert_eq!(
+            DeserializeErrorKind::UnexpectedEof,
+            Decoder::new(&[0x7B, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x61])
+                .str()
+                .unwrap_err()
+                .kind
+        );
+    }
+
+    #[test]
+    fn collections() {
+        // {_ "a": 1, "b": [_ 2, 3]}
+        let bytes = b"\xBF\x61a\x01\x61b\x9F\x02\x03\xFF\xFF";
+        let mut decoder = Decoder::new(bytes);
+        let mut entries = decoder.map().unwrap();
+        assert_eq!(None, entries);
+        let mut b = Vec::new();
+        while decoder.has_next(&mut entries).unwrap() {
+            match decoder.str().unwrap().as_ref() {
+                "a" => assert_eq!(Ok(1), decoder.integer()),
+                "b" => {
+                    let mut items = decoder.list().unwrap();
+                    while decoder.has_next(&mut items).unwrap() {
+                        b.push(decoder.integer().unwrap());
+                    }
+                }
+                other => panic!("unexpected key {}", other),
+            }
+        }
+        assert_eq!(vec![2, 3], b);
+        assert_eq!(bytes.len(), decoder.position());
+
+        // [1, [2, 3], [4, 5]]
+        let mut decoder = Decoder::new(&[0x83, 0x01, 0x82, 0x02, 0x03, 0x82, 0x04, 0x05]);
+        assert_eq!(Ok(Some(3)), decoder.list());
+    }
+
+    #[test]
+    fn timestamps() {
+        // 1(1363896240) and 1(1363896240.5)
+        assert_eq!(
+            Ok(DateTime::from_secs(1363896240)),
+            Decoder::new(&[0xC1, 0x1A, 0x51, 0x4B, 0x67, 0xB0]).timestamp()
+        );
+        assert_eq!(
+            Ok(DateTime::from_secs_f64(1363896240.5)),
+            Decoder::new(&[0xC1, 0xFB, 0x41, 0xD4, 0x52, 0xD9, 0xEC, 0x20, 0x00, 0x00]).timestamp()
+        );
+        // 0("2013-03-21T20:04:00Z") is a date/time string, which isn't used by Smithy
+        assert_eq!(
+            DeserializeErrorKind::UnexpectedTag(0),
+            Decoder::new(b"\xC0\x742013-03-21T20:04:00Z")
+                .timestamp()
+                .unwrap_err()
+                .kind
+        );
+    }
+
+    #[test]
+    fn float_timestamps_out_of_range() {
+        let decode = |secs: f64| {
+            let mut encoder = Encoder::new(vec![0xC1]);
+            encoder.double(secs);
+            Decoder::new(&encoder.into_writer()).timestamp()
+        };
+        // the fraction of -1e-17 rounds up to a whole second
+        assert_eq!(Ok(DateTime::from_secs(0)), decode(-1e-17));
+        for secs in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY, 1e19, -1e19] {
+            assert_eq!(
+                DeserializeErrorKind::TimestampOutOfRange,
+                decode(secs).unwrap_err().kind
+            );
+        }
+    }
+
+    #[test]
+    fn skip() {
+        // [_ {"a": 1(2)}, (_ "b"), h'01', -1, 1.5, null], then true
+        let bytes = b"\x9F\xA1\x61a\xC1\x02\x7F\x61b\xFF\x41\x01\x20\xF9\x3E\x00\xF6\xFF\xF5";
+        let mut decoder = Decoder::new(bytes);
+        decoder.skip().unwrap();
+        assert_eq!(Ok(true), decoder.boolean());
+
+        let mut nested = vec![0x81; 1000];
+        nested.push(0x00);
+        assert_eq!(
+            DeserializeErrorKind::NestingTooDeep,
+            Decoder::new(&nested).skip().unwrap_err().kind
+        );
+        Decoder::new(&[0xFF]).skip().expect_err("unexpected break");
+        Decoder::new(&[0x9B, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF])
+            .skip()
+            .expect_err("missing items");
+        Decoder::new(&[0x1C]).skip().expect_err("reserved");
+    }
+
+    proptest! {
+        #[test]
+        fn integer_round_trip(value: i64) {
+            let mut encoder = Encoder::default();
+            encoder.long(value);
+            let bytes = encoder.into_writer();
+            let mut decoder = Decoder::new(&bytes);
+            prop_assert_eq!(Ok(value), decoder.long());
+            prop_assert_eq!(bytes.len(), decoder.position());
+        }
+
+        #[test]
+        fn float_round_trip(value: f64) {
+            let mut encoder = Encoder::default();
+            encoder.double(value).float(value as f32);
+            let bytes = encoder.into_writer();
+            let mut decoder = Decoder::new(&bytes);
+            let (double, float) = (decoder.double().unwrap(), decoder.float().unwrap());
+            if value.is_nan() {
+                prop_assert!(double.is_nan() && float.is_nan());
+            } else {
+                prop_assert_eq!(value.to_bits(), double.to_bits());
+                prop_assert_eq!((value as f32).to_bits(), float.to_bits());
+            }
+        }
+
+        #[test]
+        fn string_round_trip(value: String, chunk in 1usize..8) {
+            let mut encoder = Encoder::default();
+            encoder.str(&value);
+            let bytes = encoder.into_writer();
+            let mut decoder = Decoder::new(&bytes);
+            prop_assert_eq!(value.as_str(), decoder.str().unwrap());
+
+            // the same bytes as an indefinite-length byte string
+            let mut bytes = vec![0x5F];
+            for part in value.as_bytes().chunks(chunk) {
+                let mut encoder = Encoder::new(bytes);
+                encoder.blob(&Blob::new(part));
+                bytes = encoder.into_writer();
+            }
+            bytes.push(0xFF);
+            prop_assert_eq!(Ok(Blob::new(value.as_bytes())), Decoder::new(&bytes).blob());
+        }
+
+        #[test]
+        fn timestamp_round_trip(secs in -1_000_000_000_000i64..1_000_000_000_000, millis in 0u32..1000) {
+            let value = DateTime::from_secs_and_nanos(secs, millis * 1_000_000);
+            let mut encoder = Encoder::default();
+            encoder.timestamp(&value);
+            let bytes = encoder.into_writer();
+            let decoded = Decoder::new(&bytes).timestamp().unwrap();
+            // doubles can't represent every millisecond this far from the epoch
+            prop_assert!((decoded.as_secs_f64() - value.as_secs_f64()).abs() < 0.001);
+        }
+
+        #[test]
+        fn float_timestamps_never_panic(secs: f64) {
+            let mut encoder = Encoder::new(vec![0xC1]);
+            encoder.double(secs);
+            let bytes = encoder.into_writer();
+            let decoded = Decoder::new(&bytes).timestamp();
+            if secs.is_finite() && secs.abs() < 9.2e18 {
+                let decoded = decoded.unwrap();
+                prop_assert!(decoded.subsec_nanos() < 1_000_000_000);
+                prop_assert!((decoded.as_secs_f64() - secs).abs() <= 1.0 + secs.abs() * f64::EPSILON);
+            } else if !secs.is_finite() || secs.abs() >= 9.3e18 {
+                prop_assert_eq!(
+                    DeserializeErrorKind::TimestampOutOfRange,
+                    decoded.unwrap_err().kind
+                );
+            }
+        }
+
+        #[test]
+        fn float_timestamps_near_whole_seconds(secs in -1_000_000i64..1_000_000, offset in -1e-9f64..1e-9) {
+            let mut encoder = Encoder::new(vec![0xC1]);
+            encoder.double(secs as f64 + offset);
+            let bytes = encoder.into_writer();
+            let decoded = Decoder::new(&bytes).timestamp().unwrap();
+            prop_assert!(decoded.subsec_nanos() < 1_000_000_000);
+        }
+    }
+}
@@ -0,0 +1,291 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! CBOR encoding
+//!
+//! ```
+//! use aws_smithy_cbor::encode::Encoder;
+//!
+//! let mut encoder = Encoder::new(Vec::new());
+//! encoder.map(2).str("name").str("smithy").str("tags").begin_array();
+//! encoder.str("rpc").str("cbor").end();
+//! let bytes = encoder.into_writer();
+//! assert_eq!(0xA2, bytes[0]); // a map with 2 entries
+//! ```
+
+use crate::additional_info::{
+    EIGHT_BYTES, FALSE, FOUR_BYTES, INDEFINITE_LENGTH, NULL, ONE_BYTE, TRUE, TWO_BYTES,
+};
+use crate::major_type::{
+    ARRAY, BYTE_STRING, MAP, NEGATIVE_INTEGER, SIMPLE, TAG, TEXT_STRING, UNSIGNED_INTEGER,
+};
+use crate::{BREAK, EPOCH_TIMESTAMP_TAG};
+use aws_smithy_types::{Blob, DateTime};
+
+/// Writes CBOR data items into a buffer
+///
+/// Integers and lengths are always written in their shortest form. Every method returns the
+/// encoder so that calls can be chained.
+#[derive(Debug, Default)]
+pub struct Encoder {
+    writer: Vec<u8>,
+}
+
+impl Encoder {
+    /// Creates an encoder that appends to `writer`
+    pub fn new(writer: Vec<u8>) -> Self {
+        Self { writer }
+    }
+
+    /// Writes the initial byte of a data item, followed by its argument if it doesn't fit
+    fn head(&mut self, major_type: u8, argument: u64) -> &mut Self {
+        let major_type = major_type << 5;
+        match argument {
+            0..=23 => self.writer.push(major_type | argument as u8),
+            24..=0xFF => {
+                self.writer.push(major_type | ONE_BYTE);
+                self.writer.push(argument as u8);
+            }
+            0x100..=0xFFFF => {
+                self.writer.push(major_type | TWO_BYTES);
+                self.writer.extend(&(argument as u16).to_be_bytes());
+            }
+            0x1_0000..=0xFFFF_FFFF => {
+                self.writer.push(major_type | FOUR_BYTES);
+                self.writer.extend(&(argument as u32).to_be_bytes());
+            }
+            _ => {
+                self.writer.push(major_type | EIGHT_BYTES);
+                self.writer.extend(&argument.to_be_bytes());
+            }
+        }
+        self
+    }
+
+    fn signed(&mut self, value: i64) -> &mut Self {
+        if value < 0 {
+            // negative integers are encoded as `-1 - n`, which is the bitwise complement
+            self.head(NEGATIVE_INTEGER, !value as u64)
+        } else {
+            self.head(UNSIGNED_INTEGER, value as u64)
+        }
+    }
+
+    /// Writes `null`
+    pub fn null(&mut self) -> &mut Self {
+        self.writer.push((SIMPLE << 5) | NULL);
+        self
+    }
+
+    /// Writes a boolean
+    pub fn boolean(&mut self, value: bool) -> &mut Self {
+        self.writer
+            .push((SIMPLE << 5) | if value { TRUE } else { FALSE });
+        self
+    }
+
+    /// Writes a Smithy `byte`
+    pub fn byte(&mut self, value: i8) -> &mut Self {
+        self.signed(value.into())
+    }
+
+    /// Writes a Smithy `short`
+    pub fn short(&mut self, value: i16) -> &mut Self {
+        self.signed(value.into())
+    }
+
+    /// Writes a Smithy `integer`
+    pub fn integer(&mut self, value: i32) -> &mut Self {
+        self.signed(value.into())
+    }
+
+    /// Writes a Smithy `long`
+    pub fn long(&mut self, value: i64) -> &mut Self {
+        self.signed(value)
+    }
+
+    /// Writes a single-precision float
+    pub fn float(&mut self, value: f32) -> &mut Self {
+        self.writer.push((SIMPLE << 5) | FOUR_BYTES);
+        self.writer.extend(&value.to_be_bytes());
+        self
+    }
+
+    /// Writes a double-precision float
+    pub fn double(&mut self, value: f64) -> &mut Self {
+        self.writer.push((SIMPLE << 5) | EIGHT_BYTES);
+        self.writer.extend(&value.to_be_bytes());
+        self
+    }
+
+    /// Writes a text string
+    pub fn str(&mut self, value: &str) -> &mut Self {
+        self.head(TEXT_STRING, value.len() as u64);
+        self.writer.extend(value.as_bytes());
+        self
+    }
+
+    /// Writes a byte string
+    pub fn blob(&mut self, value: &Blob) -> &mut Self {
+        self.head(BYTE_STRING, value.as_ref().len() as u64);
+        self.writer.extend(value.as_ref());
+        self
+    }
+
+    /// Writes a timestamp as a tag 1 (epoch-based date/time) around the number of seconds since
+    /// the Unix epoch, as a double-precision float
+    pub fn timestamp(&mut self, value: &DateTime) -> &mut Self {
+        self.head(TAG, EPOCH_TIMESTAMP_TAG)
+            .double(value.as_secs_f64())
+    }
+
+    /// Starts an array of `len` items
+    ///
+    /// The next `len` data items written are the items of the array.
+    pub fn array(&mut self, len: usize) -> &mut Self {
+        self.head(ARRAY, len as u64)
+    }
+
+    /// Starts a map of `len` entries
+    ///
+    /// The next `2 * len` data items written are the keys and values of the map, alternating.
+    pub fn map(&mut self, len: usize) -> &mut Self {
+        self.head(MAP, len as u64)
+    }
+
+    /// Starts an indefinite-length array, which must be closed with [`end`](Encoder::end)
+    pub fn begin_array(&mut self) -> &mut Self {
+        self.writer.push((ARRAY << 5) | INDEFINITE_LENGTH);
+        self
+    }
+
+    /// Starts an indefinite-length map, which must be closed with [`end`](Encoder::end)
+    pub fn begin_map(&mut self) -> &mut Self {
+        self.writer.push((MAP << 5) | INDEFINITE_LENGTH);
+        self
+    }
+
+    /// Closes the innermost indefinite-length array or map
+    pub fn end(&mut self) -> &mut Self {
+        self.writer.push(BREAK);
+        self
+    }
+
+    /// Returns the encoded bytes
+    pub fn into_writer(self) -> Vec<u8> {
+        self.writer
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Encoder;
+    use aws_smithy_types::{Blob, DateTime};
+
+    fn encode(f: impl FnOnce(&mut Encoder) -> &mut Encoder) -> Vec<u8> {
+        let mut encoder = Encoder::default();
+        f(&mut encoder);
+        encoder.into_writer()
+    }
+
+    // Examples from Appendix A of RFC 8949
+    #[test]
+    fn integers() {
+        assert_eq!(vec![0x00], encode(|e| e.long(0)));
+        assert_eq!(vec![0x17], encode(|e| e.long(23)));
+        assert_eq!(vec![0x18, 0x18], encode(|e| e.long(24)));
+        assert_eq!(vec![0x18, 0x64], encode(|e| e.integer(100)));
+        assert_eq!(vec![0x19, 0x03, 0xE8], encode(|e| e.short(1000)));
+        assert_eq!(
+            vec![0x1A, 0x00, 0x0F, 0x42, 0x40],
+            encode(|e| e.integer(1_000_000))
+        );
+        assert_eq!(
+            vec![0x1B, 0x00, 0x00, 0x00, 0xE8, 0xD4, 0xA5, 0x10, 0x00],
+            encode(|e| e.long(1_000_000_000_000))
+        );
+        assert_eq!(vec![0x20], encode(|e| e.byte(-1)));
+        assert_eq!(vec![0x29], encode(|e| e.byte(-10)));
+        assert_eq!(vec![0x38, 0x63], encode(|e| e.byte(-100)));
+        assert_eq!(vec![0x39, 0x03, 0xE7], encode(|e| e.short(-1000)));
+        assert_eq!(
+            vec![0x3B, 0x7F, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF],
+            encode(|e| e.long(i64::MIN))
+        );
+    }
+
+    #[test]
+    fn simple_values_and_floats() {
+        assert_eq!(
+            vec![0xF4, 0xF5, 0xF6],
+            encode(|e| e.boolean(false).boolean(true).null())
+        );
+        assert_eq!(
+            vec![0xFA, 0x47, 0xC3, 0x50, 0x00],
+            encode(|e| e.float(100000.0))
+        );
+        assert_eq!(
+            vec![0xFB, 0x3F, 0xF1, 0x99, 0x99, 0x99, 0x99, 0x99, 0x9A],
+            encode(|e| e.double(1.1))
+        );
+    }
+
+    #[test]
+    fn strings() {
+        assert_eq!(vec![0x60], encode(|e| e.str("")));
+        assert_eq!(
+            vec![0x64, 0x49, 0x45, 0x54, 0x46],
+            encode(|e| e.str("IETF"))
+        );
+        assert_eq!(
+            vec![0x44, 0x01, 0x02, 0x03, 0x04],
+            encode(|e| e.blob(&Blob::new(vec![1, 2, 3, 4])))
+        );
+        let long = "a".repeat(300);
+        let encoded = encode(|e| e.str(&long));
+        assert_eq!(&[0x79, 0x01, 0x2C], &encoded[..3]);
+        assert_eq!(long.as_bytes(), &encoded[3..]);
+    }
+
+    #[test]
+    fn collections() {
+        // [1, [2, 3], [4, 5]]
+        assert_eq!(
+            vec![0x83, 0x01, 0x82, 0x02, 0x03, 0x82, 0x04, 0x05],
+            encode(|e| e
+                .array(3)
+                .long(1)
+                .array(2)
+                .long(2)
+                .long(3)
+                .array(2)
+                .long(4)
+                .long(5))
+        );
+        // {_ "a": 1, "b": [_ 2, 3]}
+        assert_eq!(
+            vec![0xBF, 0x61, 0x61, 0x01, 0x61, 0x62, 0x9F, 0x02, 0x03, 0xFF, 0xFF],
+            encode(|e| e
+                .begin_map()
+                .str("a")
+                .long(1)
+                .str("b")
+                .begin_array()
+                .long(2)
+                .long(3)
+                .end()
+                .end())
+        );
+    }
+
+    #[test]
+    fn timestamps() {
+        // 1(1363896240.5)
+        assert_eq!(
+            vec![0xC1, 0xFB, 0x41, 0xD4, 0x52, 0xD9, 0xEC, 0x20, 0x00, 0x00],
+            encode(|e| e.timestamp(&DateTime::from_secs_f64(1363896240.5)))
+        );
+    }
+}
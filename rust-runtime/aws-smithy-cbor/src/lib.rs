@@ -0,0 +1,51 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! CBOR abstractions for the Smithy RPC v2 CBOR protocol
+//!
+//! [`encode::Encoder`] writes CBOR data items ([RFC 8949](https://www.rfc-editor.org/rfc/rfc8949))
+//! and [`decode::Decoder`] reads them. Timestamps are written as a tag 1 (epoch-based date/time)
+//! around a double-precision float. When decoding, integers and floats of every width are
+//! accepted, as are indefinite-length strings, byte strings, arrays, and maps.
+
+pub mod decode;
+pub mod encode;
+
+/// CBOR major types, stored in the high 3 bits of the initial byte of a data item
+mod major_type {
+    pub(crate) const UNSIGNED_INTEGER: u8 = 0;
+    pub(crate) const NEGATIVE_INTEGER: u8 = 1;
+    pub(crate) const BYTE_STRING: u8 = 2;
+    pub(crate) const TEXT_STRING: u8 = 3;
+    pub(crate) const ARRAY: u8 = 4;
+    pub(crate) const MAP: u8 = 5;
+    pub(crate) const TAG: u8 = 6;
+    pub(crate) const SIMPLE: u8 = 7;
+}
+
+/// Values of the low 5 bits of the initial byte of a data item that have a special meaning
+mod additional_info {
+    /// The argument is in the following byte
+    pub(crate) const ONE_BYTE: u8 = 24;
+    /// The argument is in the following 2 bytes
+    pub(crate) const TWO_BYTES: u8 = 25;
+    /// The argument is in the following 4 bytes
+    pub(crate) const FOUR_BYTES: u8 = 26;
+    /// The argument is in the following 8 bytes
+    pub(crate) const EIGHT_BYTES: u8 = 27;
+    /// The string, array, or map has an indefinite length, and is terminated by a break
+    pub(crate) const INDEFINITE_LENGTH: u8 = 31;
+
+    pub(crate) const FALSE: u8 = 20;
+    pub(crate) const TRUE: u8 = 21;
+    pub(crate) const NULL: u8 = 22;
+    pub(crate) const UNDEFINED: u8 = 23;
+}
+
+/// The "break" stop code that terminates indefinite-length items
+const BREAK: u8 = 0xFF;
+
+/// The tag of an epoch-based date/time, the number of seconds since the Unix epoch
+const EPOCH_TIMESTAMP_TAG: u64 = 1;
@@ -5,6 +5,8 @@
 
 //! Checksum calculation and verification callbacks
 
+pub mod payload;
+
 use aws_smithy_http::callback::BodyCallback;
 use aws_smithy_types::base64;
 
@@ -0,0 +1,253 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Middleware stage that adds checksums of the full request payload as headers.
+//!
+//! Some operations (e.g. S3's `DeleteObjects`) require a `Content-MD5` header. To request it,
+//! insert [`PayloadChecksums`] into the property bag of the request. The AWS SDK's default
+//! middleware stack includes [`PayloadChecksumStage`], and the S3 client requests `Content-MD5`
+//! for every operation that requires it.
+
+use aws_smithy_http::body::SdkBody;
+use aws_smithy_http::byte_stream::ByteStream;
+use aws_smithy_http::middleware::AsyncMapRequest;
+use aws_smithy_http::operation::Request;
+use aws_smithy_types::base64;
+use http::header::{HeaderName, HeaderValue};
+use http_body::Body;
+use sha2::Digest;
+use std::future::Future;
+use std::pin::Pin;
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+const CONTENT_MD5: &str = "content-md5";
+const X_AMZ_CONTENT_SHA256: &str = "x-amz-content-sha256";
+
+/// Payload checksums that [`PayloadChecksumStage`] should add to a request.
+///
+/// Headers that are already set on the request are left untouched.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PayloadChecksums {
+    content_md5: bool,
+    content_sha256: bool,
+}
+
+impl PayloadChecksums {
+    /// Request no payload checksums.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a base64-encoded `Content-MD5` header.
+    pub fn with_content_md5(mut self) -> Self {
+        self.content_md5 = true;
+        self
+    }
+
+    /// Add a hex-encoded `x-amz-content-sha256` header.
+    ///
+    /// This is only needed when the request is not going to be signed, since the SigV4 signer
+    /// computes this header itself.
+    pub fn with_content_sha256(mut self) -> Self {
+        self.content_sha256 = true;
+        self
+    }
+
+    fn is_empty(&self) -> bool {
+        !self.content_md5 && !self.content_sha256
+    }
+
+    /// Returns the checksums that aren't already set in `headers`
+    fn missing_from(&self, headers: &http::HeaderMap) -> Self {
+        Self {
+            content_md5: self.content_md5 && !headers.contains_key(CONTENT_MD5),
+            content_sha256: self.content_sha256 && !headers.contains_key(X_AMZ_CONTENT_SHA256),
+        }
+    }
+}
+
+/// Middleware stage that computes the checksums requested by [`PayloadChecksums`].
+///
+/// Retryable bodies are hashed by streaming a clone of the body, so they are never held in
+/// memory in full. Bodies that can't be cloned are buffered into memory first so that they can
+/// still be sent after being hashed. The body isn't read at all when every requested checksum
+/// header is already set.
+#[non_exhaustive]
+#[derive(Clone, Debug, Default)]
+pub struct PayloadChecksumStage;
+
+impl PayloadChecksumStage {
+    /// Creates a new payload checksum stage.
+    pub fn new() -> Self {
+        PayloadChecksumStage
+    }
+
+    async fn add_checksums(mut request: Request) -> Result<Request, BoxError> {
+        let checksums = request.properties().get::<PayloadChecksums>().copied();
+        let checksums = match checksums {
+            Some(checksums) => checksums.missing_from(request.http().headers()),
+            None => return Ok(request),
+        };
+        if checksums.is_empty() {
+            return Ok(request);
+        }
+        let http_request = request.http_mut();
+        let body = match http_request.body().try_clone() {
+            Some(cloned_body) => cloned_body,
+            None => {
+                let body = std::mem::replace(http_request.body_mut(), SdkBody::taken());
+                let loaded_body = ByteStream::new(body).collect().await?.into_bytes();
+                *http_request.body_mut() = SdkBody::from(loaded_body.clone());
+                SdkBody::from(loaded_body)
+            }
+        };
+
+        let mut md5 = md5::Context::new();
+        let mut sha256 = sha2::Sha256::new();
+        let mut body = body;
+        while let Some(data) = body.data().await {
+            let data = data?;
+            if checksums.content_md5 {
+                md5.consume(&data);
+            }
+            if checksums.content_sha256 {
+                sha256.update(&data);
+            }
+        }
+
+        let headers = http_request.headers_mut();
+        if checksums.content_md5 {
+            headers.insert(
+                HeaderName::from_static(CONTENT_MD5),
+                HeaderValue::from_str(&base64::encode(&md5.compute()[..]))
+                    .expect("base64 will always produce valid header values from checksums"),
+            );
+        }
+        if checksums.content_sha256 {
+            headers.insert(
+                HeaderName::from_static(X_AMZ_CONTENT_SHA256),
+                HeaderValue::from_str(&hex::encode(sha256.finalize()))
+                    .expect("hex will always produce valid header values from checksums"),
+            );
+        }
+        Ok(request)
+    }
+}
+
+type BoxFuture<T> = Pin<Box<dyn Future<Output = T> + Send>>;
+
+impl AsyncMapRequest for PayloadChecksumStage {
+    type Error = BoxError;
+    type Future = BoxFuture<Result<Request, Self::Error>>;
+
+    fn apply(&self, request: Request) -> Self::Future {
+        Box::pin(Self::add_checksums(request))
+    }
+
+    fn name(&self) -> &'static str {
+        "payload_checksum"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PayloadChecksumStage, PayloadChecksums};
+    use aws_smithy_http::body::SdkBody;
+    use aws_smithy_http::middleware::AsyncMapRequest;
+    use aws_smithy_http::operation;
+    use bytes::Bytes;
+    use http_body::combinators::BoxBody;
+    use http_body::{Body, Full};
+    use pretty_assertions::assert_eq;
+
+    fn request(body: SdkBody, checksums: Option<PayloadChecksums>) -> operation::Request {
+        let mut request = operation::Request::new(http::Request::new(body));
+        if let Some(checksums) = checksums {
+            request.properties_mut().insert(checksums);
+        }
+        request
+    }
+
+    #[tokio::test]
+    async fn no_checksums_requested() {
+        let request = PayloadChecksumStage::new()
+            .apply(request(SdkBody::from("hello world"), None))
+            .await
+            .unwrap();
+        assert!(request.http().headers().is_empty());
+    }
+
+    #[tokio::test]
+    async fn content_md5_and_sha256() {
+        let checksums = PayloadChecksums::new()
+            .with_content_md5()
+            .with_content_sha256();
+        let request = PayloadChecksumStage::new()
+            .apply(request(SdkBody::from("hello world"), Some(checksums)))
+            .await
+            .unwrap();
+        let headers = request.http().headers();
+        assert_eq!(headers["content-md5"], "XrY7u+Ae7tCTyyK7j1rNww==");
+        assert_eq!(
+            headers["x-amz-content-sha256"],
+            "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9"
+        );
+    }
+
+    #[tokio::test]
+    async fn existing_header_is_preserved() {
+        let mut request = request(
+            SdkBody::from("hello world"),
+            Some(PayloadChecksums::new().with_content_md5()),
+        );
+        request
+            .http_mut()
+            .headers_mut()
+            .insert("content-md5", "precomputed".parse().unwrap());
+        let request = PayloadChecksumStage::new().apply(request).await.unwrap();
+        assert_eq!(request.http().headers()["content-md5"], "precomputed");
+    }
+
+    #[tokio::test]
+    async fn body_is_not_read_when_headers_are_set() {
+        let body = SdkBody::from_dyn(BoxBody::new(
+            Full::new(Bytes::from("hello world")).map_err(|never| match never {}),
+        ));
+        let mut request = request(body, Some(PayloadChecksums::new().with_content_md5()));
+        request
+            .http_mut()
+            .headers_mut()
+            .insert("content-md5", "precomputed".parse().unwrap());
+        let request = PayloadChecksumStage::new().apply(request).await.unwrap();
+        // the non-retryable body would have been buffered if it had been hashed
+        assert_eq!(request.http().body().bytes(), None);
+        assert_eq!(request.http().headers()["content-md5"], "precomputed");
+    }
+
+    #[tokio::test]
+    async fn non_retryable_bodies_are_buffered() {
+        let body = SdkBody::from_dyn(BoxBody::new(
+            Full::new(Bytes::from("hello world")).map_err(|never| match never {}),
+        ));
+        assert!(body.try_clone().is_none());
+        let request = PayloadChecksumStage::new()
+            .apply(request(
+                body,
+                Some(PayloadChecksums::new().with_content_md5()),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(
+            request.http().headers()["content-md5"],
+            "XrY7u+Ae7tCTyyK7j1rNww=="
+        );
+        assert_eq!(
+            request.http().body().bytes(),
+            Some("hello world".as_bytes())
+        );
+    }
+}
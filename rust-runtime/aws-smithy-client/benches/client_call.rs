@@ -0,0 +1,95 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Measures the per-call overhead of the client's service stack, using a connector that responds
+//! immediately.
+
+use aws_smithy_client::erase::DynConnector;
+use aws_smithy_client::Client;
+use aws_smithy_http::body::SdkBody;
+use aws_smithy_http::operation::{self, Operation};
+use aws_smithy_http::response::ParseHttpResponse;
+use aws_smithy_types::retry::{ErrorKind, ProvideErrorKind};
+use bytes::Bytes;
+use criterion::{criterion_group, criterion_main, Criterion};
+use std::fmt;
+use tower::layer::util::Identity;
+
+#[derive(Debug)]
+enum NeverError {}
+
+impl fmt::Display for NeverError {
+    fn fmt(&self, _f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {}
+    }
+}
+
+impl std::error::Error for NeverError {}
+
+impl ProvideErrorKind for NeverError {
+    fn retryable_error_kind(&self) -> Option<ErrorKind> {
+        match *self {}
+    }
+
+    fn code(&self) -> Option<&str> {
+        match *self {}
+    }
+}
+
+#[derive(Clone)]
+struct EmptyParser;
+
+impl ParseHttpResponse for EmptyParser {
+    type Output = Result<(), NeverError>;
+
+    fn parse_unloaded(&self, _response: &mut operation::Response) -> Option<Self::Output> {
+        None
+    }
+
+    fn parse_loaded(&self, _response: &http::Response<Bytes>) -> Self::Output {
+        Ok(())
+    }
+}
+
+fn operation() -> Operation<EmptyParser, ()> {
+    let request = http::Request::builder()
+        .uri("https://test-service.test-region.amazonaws.com/")
+        .body(SdkBody::from("request body"))
+        .unwrap();
+    Operation::new(operation::Request::new(request), EmptyParser)
+}
+
+fn client() -> Client<DynConnector, Identity> {
+    aws_smithy_client::Builder::new()
+        .connector_fn(|_| async {
+            Ok(http::Response::builder()
+                .status(200)
+                .body(SdkBody::empty())
+                .unwrap())
+        })
+        .middleware(Identity::new())
+        .default_async_sleep()
+        .build()
+        .into_dyn_connector()
+}
+
+fn bench_group(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+
+    let static_client = client();
+    c.bench_function("call", |b| {
+        b.iter(|| runtime.block_on(static_client.call(operation())).unwrap())
+    });
+
+    let dyn_client = client().into_dyn();
+    c.bench_function("call (dyn middleware)", |b| {
+        b.iter(|| runtime.block_on(dyn_client.call(operation())).unwrap())
+    });
+
+    c.bench_function("clone", |b| b.iter(|| dyn_client.clone()));
+}
+
+criterion_group!(benches, bench_group);
+criterion_main!(benches);
@@ -0,0 +1,120 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Sending many operations at once with [`Client::call_all`](crate::Client::call_all).
+
+use futures_util::stream::Stream;
+use pin_project_lite::pin_project;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// Counts of the results yielded so far by a [`CallAll`] stream
+#[non_exhaustive]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BatchSummary {
+    /// The number of operations that succeeded
+    pub succeeded: usize,
+    /// The positions, in input order, of the operations that failed
+    pub failed: Vec<usize>,
+}
+
+impl BatchSummary {
+    /// Returns the number of results yielded so far
+    pub fn total(&self) -> usize {
+        self.succeeded + self.failed.len()
+    }
+
+    /// Returns true if no operation has failed so far
+    pub fn all_succeeded(&self) -> bool {
+        self.failed.is_empty()
+    }
+}
+
+pin_project! {
+    /// Stream of results returned by [`Client::call_all`](crate::Client::call_all)
+    ///
+    /// Results are yielded in the same order as the operations were given, regardless of the
+    /// order in which they complete. [`CallAll::summary`] tallies the results yielded so far, so
+    /// it covers the whole batch once the stream has ended.
+    #[must_use = "streams do nothing unless polled"]
+    pub struct CallAll<S> {
+        #[pin]
+        inner: S,
+        summary: BatchSummary,
+    }
+}
+
+impl<S> CallAll<S> {
+    pub(crate) fn new(inner: S) -> Self {
+        Self {
+            inner,
+            summary: BatchSummary::default(),
+        }
+    }
+
+    /// Returns the summary of the results yielded so far
+    pub fn summary(&self) -> &BatchSummary {
+        &self.summary
+    }
+}
+
+impl<S> std::fmt::Debug for CallAll<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CallAll")
+            .field("summary", &self.summary)
+            .finish()
+    }
+}
+
+impl<S, T, E> Stream for CallAll<S>
+where
+    S: Stream<Item = Result<T, E>>,
+{
+    type Item = Result<T, E>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let me = self.project();
+        let result = match me.inner.poll_next(cx) {
+            Poll::Ready(Some(result)) => result,
+            other => return other,
+        };
+        match &result {
+            Ok(_) => me.summary.succeeded += 1,
+            Err(_) => {
+                let position = me.summary.total();
+                me.summary.failed.push(position)
+            }
+        }
+        Poll::Ready(Some(result))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::batch::{BatchSummary, CallAll};
+    use futures_util::StreamExt;
+
+    #[tokio::test]
+    async fn summary_tracks_failures_by_position() {
+        let results: Vec<Result<u8, &str>> = vec![Ok(1), Err("first"), Ok(2), Err("second")];
+        let mut stream = CallAll::new(futures_util::stream::iter(results));
+        assert_eq!(stream.summary(), &BatchSummary::default());
+
+        let mut yielded = vec![];
+        while let Some(result) = stream.next().await {
+            yielded.push(result);
+        }
+        assert_eq!(yielded, vec![Ok(1), Err("first"), Ok(2), Err("second")]);
+        let summary = stream.summary();
+        assert_eq!(summary.succeeded, 2);
+        assert_eq!(summary.failed, vec![1, 3]);
+        assert_eq!(summary.total(), 4);
+        assert!(!summary.all_succeeded());
+    }
+}
@@ -0,0 +1,184 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Send operations from synchronous code.
+//!
+//! [`Client::call_blocking`] runs an operation to completion on a Tokio runtime that is created
+//! the first time it's needed and then shared by every client in the process. This is intended
+//! for scripts and applications that don't otherwise use async Rust.
+//!
+//! Blocking a thread that is driving an async runtime can stall every other task on that runtime,
+//! or deadlock it entirely. `call_blocking` can be called from threads started with
+//! `tokio::task::spawn_blocking`, and from the worker threads of a multi-threaded Tokio runtime,
+//! where it uses [`tokio::task::block_in_place`] to hand the worker's other tasks to another
+//! thread first. A current-thread runtime has no other thread to hand its tasks to, so there
+//! `call_blocking` fails with [`BlockingInAsyncContext`] instead of blocking. Because Tokio can't
+//! tell a current-thread runtime apart from the `spawn_blocking` threads it starts, this includes
+//! calls from those threads. Async code should `.await` [`Client::call`] instead.
+
+use crate::{bounds, retry, Client};
+use aws_smithy_http::operation::Operation;
+use aws_smithy_http::result::{SdkError, SdkSuccess};
+use once_cell::sync::OnceCell;
+use std::error::Error;
+use std::fmt;
+use tokio::runtime::{Handle, Runtime, RuntimeFlavor};
+use tower::Service;
+
+/// Error returned when a blocking call is made from within a current-thread async runtime.
+///
+/// This is returned as the `err` of an [`SdkError::ConstructionFailure`]; no request was sent.
+#[non_exhaustive]
+#[derive(Debug)]
+pub struct BlockingInAsyncContext;
+
+impl fmt::Display for BlockingInAsyncContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "a blocking call was made from within an async runtime. Use `Client::call` and \
+             `.await` the result instead"
+        )
+    }
+}
+
+impl Error for BlockingInAsyncContext {}
+
+/// The runtime that blocking calls are run on, shared by every client.
+///
+/// A single worker thread is enough: the calling thread blocks until its operation completes, so
+/// the worker only has to drive I/O and timers.
+fn runtime() -> Result<&'static Runtime, std::io::Error> {
+    static RUNTIME: OnceCell<Runtime> = OnceCell::new();
+    RUNTIME.get_or_try_init(|| {
+        tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .thread_name("aws-smithy-client-blocking")
+            .enable_all()
+            .build()
+    })
+}
+
+impl<C, M, R> Client<C, M, R>
+where
+    C: bounds::SmithyConnector,
+    M: bounds::SmithyMiddleware<C>,
+    R: retry::NewRequestPolicy,
+{
+    /// Dispatch this request to the network, blocking the current thread until it completes.
+    ///
+    /// This fails with [`BlockingInAsyncContext`] if it's called from within a current-thread
+    /// Tokio runtime. See the [`blocking`](crate::blocking) module for details.
+    ///
+    /// The error is boxed to keep the size of the result small for synchronous callers.
+    pub fn call_blocking<O, T, E, Retry>(
+        &self,
+        input: Operation<O, Retry>,
+    ) -> Result<T, Box<SdkError<E>>>
+    where
+        O: Send + Sync,
+        Retry: Send + Sync,
+        R::Policy: bounds::SmithyRetryPolicy<O, T, E, Retry>,
+        bounds::Parsed<<M as bounds::SmithyMiddleware<C>>::Service, O, Retry>:
+            Service<Operation<O, Retry>, Response = SdkSuccess<T>, Error = SdkError<E>> + Clone,
+    {
+        let in_multi_thread_runtime = match Handle::try_current() {
+            Ok(handle) => match handle.runtime_flavor() {
+                RuntimeFlavor::MultiThread => true,
+                _ => {
+                    return Err(Box::new(SdkError::ConstructionFailure(Box::new(
+                        BlockingInAsyncContext,
+                    ))))
+                }
+            },
+            Err(_) => false,
+        };
+        let runtime = runtime().map_err(|err| SdkError::ConstructionFailure(err.into()))?;
+        if in_multi_thread_runtime {
+            // `block_in_place` leaves the runtime context, so the shared runtime can be entered
+            // from here. On a `spawn_blocking` thread it just runs the closure.
+            tokio::task::block_in_place(|| runtime.block_on(self.call(input)).map_err(Box::new))
+        } else {
+            runtime.block_on(self.call(input)).map_err(Box::new)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::blocking::BlockingInAsyncContext;
+    use crate::static_tests::TestOperationError;
+    use crate::Builder;
+    use aws_smithy_http::body::SdkBody;
+    use aws_smithy_http::operation::{self, Operation};
+    use aws_smithy_http::response::ParseStrictResponse;
+    use aws_smithy_http::result::SdkError;
+    use bytes::Bytes;
+
+    #[derive(Clone)]
+    struct Status;
+
+    impl ParseStrictResponse for Status {
+        type Output = Result<u16, TestOperationError>;
+
+        fn parse(&self, response: &http::Response<Bytes>) -> Self::Output {
+            Ok(response.status().as_u16())
+        }
+    }
+
+    fn operation() -> Operation<Status, ()> {
+        Operation::new(
+            operation::Request::new(http::Request::new(SdkBody::empty())),
+            Status,
+        )
+    }
+
+    fn client() -> crate::Client<crate::erase::DynConnector, tower::layer::util::Identity> {
+        Builder::new()
+            .middleware(tower::layer::util::Identity::new())
+            .connector_fn(|_| async {
+                Ok(http::Response::builder()
+                    .status(204)
+                    .body(SdkBody::empty())
+                    .unwrap())
+            })
+            .build()
+            .into_dyn_connector()
+    }
+
+    #[test]
+    fn blocking_calls_complete_without_a_runtime() {
+        let client = client();
+        assert_eq!(204, client.call_blocking(operation()).expect("success"));
+        // The shared runtime is reused for later calls
+        assert_eq!(204, client.call_blocking(operation()).expect("success"));
+    }
+
+    #[tokio::test]
+    async fn blocking_calls_fail_inside_a_runtime() {
+        match client().call_blocking(operation()) {
+            Err(err) => match *err {
+                SdkError::ConstructionFailure(err) => {
+                    assert!(err.downcast_ref::<BlockingInAsyncContext>().is_some())
+                }
+                other => panic!("expected a construction failure, got {:?}", other),
+            },
+            Ok(status) => panic!("expected a construction failure, got {}", status),
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn blocking_calls_complete_on_spawn_blocking_threads() {
+        let status = tokio::task::spawn_blocking(|| client().call_blocking(operation()))
+            .await
+            .expect("task completes");
+        assert_eq!(204, status.expect("success"));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn blocking_calls_complete_on_multi_thread_workers() {
+        assert_eq!(204, client().call_blocking(operation()).expect("success"));
+    }
+}
@@ -5,11 +5,16 @@
 
 use std::sync::Arc;
 
+use crate::concurrency_limit::ConcurrencyLimit;
+use crate::rate_limit::RateLimiter;
 use crate::{bounds, erase, retry, Client, TriState, MISSING_SLEEP_IMPL_RECOMMENDATION};
 use aws_smithy_async::rt::sleep::{default_async_sleep, AsyncSleep};
 use aws_smithy_http::body::SdkBody;
+use aws_smithy_http::metrics::{EmitMetrics, SharedMetricsEmitter};
+use aws_smithy_http::response::{ParseOffload, ResponseLimits};
 use aws_smithy_http::result::ConnectorError;
 use aws_smithy_types::timeout;
+use tower::layer::util::Stack;
 
 /// A builder that provides more customization options when constructing a [`Client`].
 ///
@@ -23,6 +28,11 @@ pub struct Builder<C = (), M = (), R = retry::Standard> {
     retry_policy: R,
     timeout_config: timeout::Config,
     sleep_impl: TriState<Arc<dyn AsyncSleep>>,
+    metrics_emitter: Option<SharedMetricsEmitter>,
+    response_limits: Option<ResponseLimits>,
+    parse_offload: Option<ParseOffload>,
+    rate_limiter: Option<RateLimiter>,
+    concurrency_limit: Option<ConcurrencyLimit>,
 }
 
 // It'd be nice to include R where R: Default here, but then the caller ends up always having to
@@ -62,6 +72,11 @@ impl<M, R> Builder<(), M, R> {
             middleware: self.middleware,
             timeout_config: self.timeout_config,
             sleep_impl: self.sleep_impl,
+            metrics_emitter: self.metrics_emitter,
+            response_limits: self.response_limits,
+            parse_offload: self.parse_offload,
+            rate_limiter: self.rate_limiter,
+            concurrency_limit: self.concurrency_limit,
         }
     }
 
@@ -118,6 +133,11 @@ impl<C, R> Builder<C, (), R> {
             timeout_config: self.timeout_config,
             middleware,
             sleep_impl: self.sleep_impl,
+            metrics_emitter: self.metrics_emitter,
+            response_limits: self.response_limits,
+            parse_offload: self.parse_offload,
+            rate_limiter: self.rate_limiter,
+            concurrency_limit: self.concurrency_limit,
         }
     }
 
@@ -169,6 +189,11 @@ impl<C, M> Builder<C, M, retry::Standard> {
             timeout_config: self.timeout_config,
             middleware: self.middleware,
             sleep_impl: self.sleep_impl,
+            metrics_emitter: self.metrics_emitter,
+            response_limits: self.response_limits,
+            parse_offload: self.parse_offload,
+            rate_limiter: self.rate_limiter,
+            concurrency_limit: self.concurrency_limit,
         }
     }
 }
@@ -179,6 +204,13 @@ impl<C, M> Builder<C, M> {
         self.retry_policy.with_config(config);
     }
 
+    /// Draw retry quota from `partitions`, which may be shared with other clients.
+    ///
+    /// See [`RetryPartitions`](retry::RetryPartitions).
+    pub fn set_retry_partitions(&mut self, partitions: retry::RetryPartitions) {
+        self.retry_policy.with_retry_partitions(partitions);
+    }
+
     /// Set a timeout config for the builder
     pub fn set_timeout_config(&mut self, timeout_config: timeout::Config) {
         self.timeout_config = timeout_config;
@@ -203,6 +235,78 @@ impl<C, M> Builder<C, M> {
 }
 
 impl<C, M, R> Builder<C, M, R> {
+    /// Set the [`EmitMetrics`] implementation that the [`Client`] will report per-attempt
+    /// metrics to.
+    ///
+    /// By default, no metrics are emitted. [`TracingMetrics`](aws_smithy_http::metrics::TracingMetrics)
+    /// can be used to emit metrics as `tracing` events.
+    pub fn set_metrics_emitter(&mut self, emitter: Option<SharedMetricsEmitter>) {
+        self.metrics_emitter = emitter;
+    }
+
+    /// Set the [`EmitMetrics`] implementation that the [`Client`] will report per-attempt
+    /// metrics to.
+    pub fn metrics_emitter(mut self, emitter: impl EmitMetrics + 'static) -> Self {
+        self.set_metrics_emitter(Some(SharedMetricsEmitter::new(emitter)));
+        self
+    }
+
+    /// Set the [`ResponseLimits`] that the [`Client`] applies when reading response bodies.
+    ///
+    /// By default, response bodies of any size are read into memory. Limits set on an individual
+    /// operation take precedence over the limits set here.
+    pub fn set_response_limits(&mut self, limits: Option<ResponseLimits>) {
+        self.response_limits = limits;
+    }
+
+    /// Set the [`ResponseLimits`] that the [`Client`] applies when reading response bodies.
+    pub fn response_limits(mut self, limits: ResponseLimits) -> Self {
+        self.set_response_limits(Some(limits));
+        self
+    }
+
+    /// Set the [`ParseOffload`] that the [`Client`] uses to parse large responses without
+    /// stalling the async runtime.
+    ///
+    /// By default, responses are parsed inline. A `ParseOffload` set on an individual operation
+    /// takes precedence over the one set here.
+    pub fn set_parse_offload(&mut self, offload: Option<ParseOffload>) {
+        self.parse_offload = offload;
+    }
+
+    /// Set the [`ParseOffload`] that the [`Client`] uses to parse large responses without
+    /// stalling the async runtime.
+    pub fn parse_offload(mut self, offload: ParseOffload) -> Self {
+        self.set_parse_offload(Some(offload));
+        self
+    }
+
+    /// Set the [`RateLimiter`] that limits the rate of requests sent by the [`Client`].
+    ///
+    /// By default, requests are not rate limited.
+    pub fn set_rate_limiter(&mut self, rate_limiter: Option<RateLimiter>) {
+        self.rate_limiter = rate_limiter;
+    }
+
+    /// Set the [`RateLimiter`] that limits the rate of requests sent by the [`Client`].
+    pub fn rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        self.set_rate_limiter(Some(rate_limiter));
+        self
+    }
+
+    /// Set the [`ConcurrencyLimit`] on the number of requests the [`Client`] has in flight.
+    ///
+    /// By default, the number of requests in flight is not limited.
+    pub fn set_concurrency_limit(&mut self, limit: Option<ConcurrencyLimit>) {
+        self.concurrency_limit = limit;
+    }
+
+    /// Set the [`ConcurrencyLimit`] on the number of requests the [`Client`] has in flight.
+    pub fn concurrency_limit(mut self, limit: ConcurrencyLimit) -> Self {
+        self.set_concurrency_limit(Some(limit));
+        self
+    }
+
     /// Use a connector that wraps the current connector.
     pub fn map_connector<F, C2>(self, map: F) -> Builder<C2, M, R>
     where
@@ -214,6 +318,11 @@ impl<C, M, R> Builder<C, M, R> {
             retry_policy: self.retry_policy,
             timeout_config: self.timeout_config,
             sleep_impl: self.sleep_impl,
+            metrics_emitter: self.metrics_emitter,
+            response_limits: self.response_limits,
+            parse_offload: self.parse_offload,
+            rate_limiter: self.rate_limiter,
+            concurrency_limit: self.concurrency_limit,
         }
     }
 
@@ -228,9 +337,49 @@ impl<C, M, R> Builder<C, M, R> {
             retry_policy: self.retry_policy,
             timeout_config: self.timeout_config,
             sleep_impl: self.sleep_impl,
+            metrics_emitter: self.metrics_emitter,
+            response_limits: self.response_limits,
+            parse_offload: self.parse_offload,
+            rate_limiter: self.rate_limiter,
+            concurrency_limit: self.concurrency_limit,
         }
     }
 
+    /// Add a layer that runs _before_ the current middleware.
+    ///
+    /// Requests pass through `layer` first and are then handed to the existing middleware. This
+    /// is the right place for layers that should see the request as the operation produced it,
+    /// such as request mirroring.
+    ///
+    /// ```no_run
+    /// use aws_smithy_client::Builder;
+    /// use aws_smithy_client::never::NeverConnector;
+    /// let client = Builder::new()
+    ///   .connector(NeverConnector::new())
+    ///   .middleware(tower::layer::util::Identity::new())
+    ///   .layer(tower::util::MapRequestLayer::new(|req: aws_smithy_http::operation::Request| {
+    ///     req
+    ///   }))
+    ///   .build();
+    /// # client.check();
+    /// ```
+    pub fn layer<L>(self, layer: L) -> Builder<C, Stack<M, L>, R> {
+        self.map_middleware(|middleware| Stack::new(middleware, layer))
+    }
+
+    /// Add a layer that runs _after_ the current middleware, right before dispatch.
+    ///
+    /// Requests pass through the existing middleware first, so `layer` sees the request once it
+    /// has been fully prepared (e.g. after the endpoint has been resolved and the request has been
+    /// signed). This is the right place for layers like auth header injection.
+    ///
+    /// Note that this requires the current middleware to be able to wrap the service produced by
+    /// `layer`, so it can't be used once the middleware has been type-erased with
+    /// [`DynMiddleware`](erase::DynMiddleware).
+    pub fn inner_layer<L>(self, layer: L) -> Builder<C, Stack<L, M>, R> {
+        self.map_middleware(|middleware| Stack::new(layer, middleware))
+    }
+
     /// Build a Smithy service [`Client`].
     pub fn build(self) -> Client<C, M, R> {
         if matches!(self.sleep_impl, TriState::Unset) {
@@ -256,6 +405,12 @@ impl<C, M, R> Builder<C, M, R> {
             middleware: self.middleware,
             timeout_config: self.timeout_config,
             sleep_impl: self.sleep_impl,
+            metrics_emitter: self.metrics_emitter,
+            response_limits: self.response_limits,
+            parse_offload: self.parse_offload,
+            rate_limiter: self.rate_limiter,
+            concurrency_limit: self.concurrency_limit,
+            stack: Default::default(),
         }
     }
 }
@@ -0,0 +1,650 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Per-endpoint circuit breaking
+//!
+//! [`CircuitBreaker`] wraps a connector and tracks the failure rate of requests to each endpoint
+//! (host and port). When the failure rate of an endpoint crosses a threshold, the circuit for that
+//! endpoint _opens_, and requests to it fail immediately with an
+//! [`SdkError::DispatchFailure`](crate::SdkError::DispatchFailure) instead of being sent. After a
+//! cool-down period, the circuit becomes _half-open_ and a single probe request is let through:
+//! if it succeeds the circuit closes again, otherwise it re-opens.
+//!
+//! Since it operates on the connector, endpoints are tracked after endpoint resolution, so
+//! requests to different hosts (e.g. S3 access points or regional endpoints) are tracked
+//! separately. Add it to a client with [`Builder::map_connector`](crate::Builder::map_connector):
+//! ```no_run
+//! # fn example<M>(builder: aws_smithy_client::Builder<aws_smithy_client::erase::DynConnector, M>) {
+//! use aws_smithy_client::circuit_breaker::{CircuitBreakerLayer, Config};
+//! use tower::Layer;
+//!
+//! let builder = builder.map_connector(|conn| CircuitBreakerLayer::new(Config::default()).layer(conn));
+//! # }
+//! ```
+//!
+//! Connection errors and responses with a 5xx status code count as failures. Short-circuited
+//! requests fail with an unclassified [`ConnectorError`], which the standard retry policies don't
+//! retry.
+//!
+//! At most [`Config::with_max_endpoints`] endpoints are tracked at once. When a request is made to
+//! a new endpoint and the limit has been reached, the least recently used endpoint is forgotten.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, SystemTime};
+
+use aws_smithy_async::time::{SharedTimeSource, TimeSource};
+use aws_smithy_http::body::SdkBody;
+use aws_smithy_http::metrics::{
+    CircuitBreakerMetrics, CircuitState, EmitMetrics, SharedMetricsEmitter,
+};
+use aws_smithy_http::result::ConnectorError;
+use pin_project_lite::pin_project;
+use tower::Layer;
+
+/// Circuit breaker configuration
+#[derive(Clone, Debug)]
+pub struct Config {
+    failure_rate_threshold: f64,
+    minimum_requests: u32,
+    window: Duration,
+    open_duration: Duration,
+    max_endpoints: usize,
+    time_source: SharedTimeSource,
+    metrics_emitter: Option<SharedMetricsEmitter>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            failure_rate_threshold: 0.5,
+            minimum_requests: 10,
+            window: Duration::from_secs(10),
+            open_duration: Duration::from_secs(5),
+            max_endpoints: 1024,
+            time_source: SharedTimeSource::default(),
+            metrics_emitter: None,
+        }
+    }
+}
+
+impl Config {
+    /// Open the circuit when at least this fraction of requests in a window fail (default: `0.5`)
+    pub fn with_failure_rate_threshold(mut self, threshold: f64) -> Self {
+        self.failure_rate_threshold = threshold;
+        self
+    }
+
+    /// Only open the circuit once a window has seen at least this many requests (default: `10`)
+    pub fn with_minimum_requests(mut self, minimum_requests: u32) -> Self {
+        self.minimum_requests = minimum_requests;
+        self
+    }
+
+    /// The length of the window that the failure rate is measured over (default: 10 seconds)
+    pub fn with_window(mut self, window: Duration) -> Self {
+        self.window = window;
+        self
+    }
+
+    /// How long the circuit stays open before a probe request is allowed (default: 5 seconds)
+    pub fn with_open_duration(mut self, open_duration: Duration) -> Self {
+        self.open_duration = open_duration;
+        self
+    }
+
+    /// The maximum number of endpoints whose health is tracked at once (default: `1024`)
+    pub fn with_max_endpoints(mut self, max_endpoints: usize) -> Self {
+        self.max_endpoints = max_endpoints;
+        self
+    }
+
+    /// Use `time_source` to measure failure rate windows and open durations
+    pub fn with_time_source(mut self, time_source: impl TimeSource + 'static) -> Self {
+        self.time_source = SharedTimeSource::new(time_source);
+        self
+    }
+
+    /// Report circuit state changes to `emitter`
+    pub fn with_metrics_emitter(mut self, emitter: impl EmitMetrics + 'static) -> Self {
+        self.metrics_emitter = Some(SharedMetricsEmitter::new(emitter));
+        self
+    }
+}
+
+#[derive(Debug)]
+enum EndpointState {
+    Closed {
+        window_start: SystemTime,
+        requests: u32,
+        failures: u32,
+    },
+    Open {
+        until: SystemTime,
+    },
+    HalfOpen {
+        probe_in_flight: bool,
+    },
+}
+
+impl EndpointState {
+    fn closed(now: SystemTime) -> Self {
+        EndpointState::Closed {
+            window_start: now,
+            requests: 0,
+            failures: 0,
+        }
+    }
+}
+
+/// The health of an endpoint, and when a request was last made to it
+#[derive(Debug)]
+struct Tracked {
+    state: EndpointState,
+    last_used: SystemTime,
+}
+
+/// Health of all endpoints, shared between clones of a [`CircuitBreaker`]
+#[derive(Debug)]
+struct Endpoints {
+    config: Config,
+    states: Mutex<HashMap<String, Tracked>>,
+}
+
+impl Endpoints {
+    /// Returns `Some(is_probe)` if a request to `endpoint` may be sent
+    fn try_acquire(&self, endpoint: &str) -> Option<bool> {
+        let now = self.config.time_source.now();
+        let mut states = self.states.lock().unwrap();
+        if !states.contains_key(endpoint) && states.len() >= self.config.max_endpoints {
+            Self::evict_least_recently_used(&mut states);
+        }
+        let tracked = states
+            .entry(endpoint.to_string())
+            .or_insert_with(|| Tracked {
+                state: EndpointState::closed(now),
+                last_used: now,
+            });
+        tracked.last_used = now;
+        let state = &mut tracked.state;
+        match state {
+            EndpointState::Closed { .. } => Some(false),
+            EndpointState::Open { until } if now >= *until => {
+                *state = EndpointState::HalfOpen {
+                    probe_in_flight: true,
+                };
+                self.emit(endpoint, CircuitState::HalfOpen);
+                Some(true)
+            }
+            EndpointState::HalfOpen { probe_in_flight } if !*probe_in_flight => {
+                *probe_in_flight = true;
+                Some(true)
+            }
+            _ => None,
+        }
+    }
+
+    fn evict_least_recently_used(states: &mut HashMap<String, Tracked>) {
+        let oldest = states
+            .iter()
+            .min_by_key(|(_, tracked)| tracked.last_used)
+            .map(|(endpoint, _)| endpoint.clone());
+        if let Some(endpoint) = oldest {
+            states.remove(&endpoint);
+        }
+    }
+
+    fn record(&self, endpoint: &str, success: bool) {
+        let now = self.config.time_source.now();
+        let mut states = self.states.lock().unwrap();
+        let state = match states.get_mut(endpoint) {
+            Some(tracked) => &mut tracked.state,
+            None => return,
+        };
+        match state {
+            EndpointState::Closed {
+                window_start,
+                requests,
+                failures,
+            } => {
+                if now.duration_since(*window_start).unwrap_or_default() > self.config.window {
+                    *window_start = now;
+                    *requests = 0;
+                    *failures = 0;
+                }
+                *requests += 1;
+                if !success {
+                    *failures += 1;
+                }
+                let failure_rate = *failures as f64 / *requests as f64;
+                if *requests >= self.config.minimum_requests
+                    && failure_rate >= self.config.failure_rate_threshold
+                {
+                    tracing::debug!(endpoint, failure_rate, "opening circuit");
+                    self.open(state, endpoint, now);
+                }
+            }
+            EndpointState::HalfOpen { .. } if success => {
+                *state = EndpointState::closed(now);
+                self.emit(endpoint, CircuitState::Closed);
+            }
+            EndpointState::HalfOpen { .. } => self.open(state, endpoint, now),
+            // A request that was sent before the circuit opened
+            EndpointState::Open { .. } => {}
+        }
+    }
+
+    /// Allows another probe when a probe request was cancelled before it completed
+    fn cancel(&self, endpoint: &str) {
+        let mut states = self.states.lock().unwrap();
+        if let Some(Tracked {
+            state: EndpointState::HalfOpen { probe_in_flight },
+            ..
+        }) = states.get_mut(endpoint)
+        {
+            *probe_in_flight = false;
+        }
+    }
+
+    fn open(&self, state: &mut EndpointState, endpoint: &str, now: SystemTime) {
+        *state = EndpointState::Open {
+            until: now + self.config.open_duration,
+        };
+        self.emit(endpoint, CircuitState::Open);
+    }
+
+    fn emit(&self, endpoint: &str, state: CircuitState) {
+        if let Some(emitter) = &self.config.metrics_emitter {
+            emitter.emit_circuit_breaker(&CircuitBreakerMetrics::new(endpoint, state));
+        }
+    }
+}
+
+#[derive(Debug)]
+struct CircuitOpenError {
+    endpoint: String,
+}
+
+impl std::fmt::Display for CircuitOpenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "request was not sent because the circuit for {} is open",
+            self.endpoint
+        )
+    }
+}
+
+impl std::error::Error for CircuitOpenError {}
+
+/// A layer that wraps connectors in a [`CircuitBreaker`]
+#[derive(Clone, Debug)]
+pub struct CircuitBreakerLayer {
+    config: Config,
+}
+
+impl CircuitBreakerLayer {
+    /// Create a new `CircuitBreakerLayer`
+    pub fn new(config: Config) -> Self {
+        Self { config }
+    }
+}
+
+impl<C> Layer<C> for CircuitBreakerLayer {
+    type Service = CircuitBreaker<C>;
+
+    fn layer(&self, inner: C) -> Self::Service {
+        CircuitBreaker::new(inner, self.config.clone())
+    }
+}
+
+/// A connector that stops sending requests to endpoints that are failing
+///
+/// Clones share endpoint health. See the [module documentation](crate::circuit_breaker) for
+/// details.
+#[derive(Clone, Debug)]
+pub struct CircuitBreaker<C> {
+    inner: C,
+    endpoints: Arc<Endpoints>,
+}
+
+impl<C> CircuitBreaker<C> {
+    /// Wrap `inner` in a circuit breaker configured with `config`
+    pub fn new(inner: C, config: Config) -> Self {
+        Self {
+            inner,
+            endpoints: Arc::new(Endpoints {
+                config,
+                states: Mutex::new(HashMap::new()),
+            }),
+        }
+    }
+}
+
+impl<C> tower::Service<http::Request<SdkBody>> for CircuitBreaker<C>
+where
+    C: tower::Service<http::Request<SdkBody>, Response = http::Response<SdkBody>>,
+    C::Error: Into<ConnectorError>,
+{
+    type Response = http::Response<SdkBody>;
+    type Error = ConnectorError;
+    type Future = CircuitBreakerFuture<C::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, req: http::Request<SdkBody>) -> Self::Future {
+        let endpoint = req
+            .uri()
+            .authority()
+            .map(|authority| authority.to_string())
+            .unwrap_or_default();
+        let probe = match self.endpoints.try_acquire(&endpoint) {
+            Some(probe) => probe,
+            None => {
+                return CircuitBreakerFuture {
+                    inner: Inner::Open {
+                        endpoint: Some(endpoint),
+                    },
+                }
+            }
+        };
+        CircuitBreakerFuture {
+            inner: Inner::Sent {
+                future: self.inner.call(req),
+                outcome: Outcome {
+                    endpoint,
+                    probe,
+                    endpoints: Some(self.endpoints.clone()),
+                },
+            },
+        }
+    }
+}
+
+/// Records the outcome of a request, or its cancellation if it's dropped first
+#[derive(Debug)]
+struct Outcome {
+    endpoint: String,
+    probe: bool,
+    endpoints: Option<Arc<Endpoints>>,
+}
+
+impl Outcome {
+    fn record(&mut self, success: bool) {
+        if let Some(endpoints) = self.endpoints.take() {
+            endpoints.record(&self.endpoint, success);
+        }
+    }
+}
+
+impl Drop for Outcome {
+    fn drop(&mut self) {
+        match self.endpoints.take() {
+            Some(endpoints) if self.probe => endpoints.cancel(&self.endpoint),
+            _ => {}
+        }
+    }
+}
+
+pin_project! {
+    #[project = InnerProj]
+    enum Inner<F> {
+        Sent {
+            #[pin]
+            future: F,
+            outcome: Outcome,
+        },
+        Open {
+            endpoint: Option<String>,
+        },
+    }
+}
+
+pin_project! {
+    /// A future generated by a [`CircuitBreaker`]
+    #[must_use = "futures do nothing unless you `.await` or poll them"]
+    pub struct CircuitBreakerFuture<F> {
+        #[pin]
+        inner: Inner<F>,
+    }
+}
+
+impl<F> std::fmt::Debug for CircuitBreakerFuture<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("CircuitBreakerFuture")
+    }
+}
+
+impl<F, E> Future for CircuitBreakerFuture<F>
+where
+    F: Future<Output = Result<http::Response<SdkBody>, E>>,
+    E: Into<ConnectorError>,
+{
+    type Output = Result<http::Response<SdkBody>, ConnectorError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match self.project().inner.project() {
+            InnerProj::Sent { future, outcome } => {
+                let result = match future.poll(cx) {
+                    Poll::Ready(result) => result.map_err(Into::into),
+                    Poll::Pending => return Poll::Pending,
+                };
+                let success =
+                    matches!(&result, Ok(response) if !response.status().is_server_error());
+                outcome.record(success);
+                Poll::Ready(result)
+            }
+            InnerProj::Open { endpoint } => {
+                let endpoint = endpoint.take().expect("polled after completion");
+                Poll::Ready(Err(ConnectorError::other(
+                    Box::new(CircuitOpenError { endpoint }),
+                    None,
+                )))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::atomic::{AtomicU16, AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use crate::circuit_breaker::{CircuitBreakerLayer, Config};
+    use aws_smithy_async::test_util::ManualTimeSource;
+    use aws_smithy_http::body::SdkBody;
+    use aws_smithy_http::metrics::{CircuitBreakerMetrics, CircuitState, EmitMetrics};
+    use aws_smithy_http::result::ConnectorError;
+    use tower::{service_fn, Layer, Service, ServiceExt};
+
+    #[derive(Clone, Debug, Default)]
+    struct RecordStates(Arc<Mutex<Vec<(String, CircuitState)>>>);
+
+    impl EmitMetrics for RecordStates {
+        fn emit_circuit_breaker(&self, metrics: &CircuitBreakerMetrics) {
+            self.0
+                .lock()
+                .unwrap()
+                .push((metrics.endpoint.clone(), metrics.state));
+        }
+    }
+
+    /// A connector that responds with `status` and counts the requests it receives
+    fn connector(
+        status: Arc<AtomicU16>,
+        sent: Arc<AtomicUsize>,
+    ) -> impl Service<
+        http::Request<SdkBody>,
+        Response = http::Response<SdkBody>,
+        Error = ConnectorError,
+    > + Clone {
+        service_fn(move |_req: http::Request<SdkBody>| {
+            sent.fetch_add(1, Ordering::SeqCst);
+            let status = status.load(Ordering::SeqCst);
+            async move {
+                Ok::<_, ConnectorError>(
+                    http::Response::builder()
+                        .status(status)
+                        .body(SdkBody::empty())
+                        .unwrap(),
+                )
+            }
+        })
+    }
+
+    fn request(host: &str) -> http::Request<SdkBody> {
+        http::Request::builder()
+            .uri(format!("https://{}/", host))
+            .body(SdkBody::empty())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn circuit_opens_per_endpoint() {
+        let status = Arc::new(AtomicU16::new(500));
+        let sent = Arc::new(AtomicUsize::new(0));
+        let states = RecordStates::default();
+        let config = Config::default()
+            .with_minimum_requests(2)
+            .with_open_duration(Duration::from_secs(60))
+            .with_metrics_emitter(states.clone());
+        let svc = CircuitBreakerLayer::new(config).layer(connector(status, sent.clone()));
+
+        for _ in 0..2 {
+            let response = svc.clone().oneshot(request("a.example.com")).await;
+            assert_eq!(500, response.unwrap().status());
+        }
+        let err = svc
+            .clone()
+            .oneshot(request("a.example.com"))
+            .await
+            .expect_err("circuit is open");
+        assert!(err
+            .to_string()
+            .contains("circuit for a.example.com is open"));
+        assert_eq!(None, err.is_other());
+        assert_eq!(2, sent.load(Ordering::SeqCst));
+
+        // Other endpoints are unaffected
+        svc.oneshot(request("b.example.com"))
+            .await
+            .expect("circuit for b is closed");
+        assert_eq!(3, sent.load(Ordering::SeqCst));
+        assert_eq!(
+            vec![("a.example.com".to_string(), CircuitState::Open)],
+            *states.0.lock().unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn half_open_probe_closes_circuit() {
+        let status = Arc::new(AtomicU16::new(500));
+        let sent = Arc::new(AtomicUsize::new(0));
+        let states = RecordStates::default();
+        let config = Config::default()
+            .with_minimum_requests(1)
+            .with_open_duration(Duration::ZERO)
+            .with_metrics_emitter(states.clone());
+        let svc = CircuitBreakerLayer::new(config).layer(connector(status.clone(), sent.clone()));
+
+        svc.clone().oneshot(request("example.com")).await.unwrap();
+        // The first probe fails, re-opening the circuit
+        svc.clone().oneshot(request("example.com")).await.unwrap();
+        status.store(200, Ordering::SeqCst);
+        // The second probe succeeds, closing the circuit
+        svc.clone().oneshot(request("example.com")).await.unwrap();
+        svc.oneshot(request("example.com")).await.unwrap();
+        assert_eq!(4, sent.load(Ordering::SeqCst));
+
+        let states: Vec<_> = states.0.lock().unwrap().iter().map(|s| s.1).collect();
+        assert_eq!(
+            vec![
+                CircuitState::Open,
+                CircuitState::HalfOpen,
+                CircuitState::Open,
+                CircuitState::HalfOpen,
+                CircuitState::Closed
+            ],
+            states
+        );
+    }
+
+    #[tokio::test]
+    async fn cancelled_probe_allows_another_probe() {
+        let status = Arc::new(AtomicU16::new(500));
+        let sent = Arc::new(AtomicUsize::new(0));
+        let config = Config::default()
+            .with_minimum_requests(1)
+            .with_open_duration(Duration::ZERO);
+        let mut svc = CircuitBreakerLayer::new(config).layer(connector(status, sent.clone()));
+
+        svc.call(request("example.com")).await.unwrap();
+        drop(svc.call(request("example.com")));
+        svc.call(request("example.com"))
+            .await
+            .expect("a new probe is allowed");
+        assert_eq!(3, sent.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn circuit_stays_open_until_time_source_advances() {
+        let status = Arc::new(AtomicU16::new(500));
+        let sent = Arc::new(AtomicUsize::new(0));
+        let mut time_source = ManualTimeSource::new(UNIX_EPOCH);
+        let config = Config::default()
+            .with_minimum_requests(1)
+            .with_open_duration(Duration::from_secs(5))
+            .with_time_source(time_source.clone());
+        let svc = CircuitBreakerLayer::new(config).layer(connector(status, sent.clone()));
+
+        svc.clone().oneshot(request("example.com")).await.unwrap();
+        time_source.advance(Duration::from_secs(4));
+        svc.clone()
+            .oneshot(request("example.com"))
+            .await
+            .expect_err("circuit is still open");
+        time_source.advance(Duration::from_secs(1));
+        svc.oneshot(request("example.com"))
+            .await
+            .expect("a probe is allowed once the open duration has passed");
+        assert_eq!(2, sent.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn least_recently_used_endpoint_is_evicted() {
+        let status = Arc::new(AtomicU16::new(500));
+        let sent = Arc::new(AtomicUsize::new(0));
+        let mut time_source = ManualTimeSource::new(UNIX_EPOCH);
+        let config = Config::default()
+            .with_minimum_requests(1)
+            .with_open_duration(Duration::from_secs(60))
+            .with_max_endpoints(2)
+            .with_time_source(time_source.clone());
+        let svc = CircuitBreakerLayer::new(config).layer(connector(status, sent.clone()));
+
+        // Opens the circuit for a
+        svc.clone().oneshot(request("a.example.com")).await.unwrap();
+        time_source.advance(Duration::from_secs(1));
+        svc.clone().oneshot(request("b.example.com")).await.unwrap();
+        time_source.advance(Duration::from_secs(1));
+        // Tracking c forgets a, the least recently used endpoint
+        svc.clone().oneshot(request("c.example.com")).await.unwrap();
+        assert_eq!(2, svc.endpoints.states.lock().unwrap().len());
+        svc.clone()
+            .oneshot(request("a.example.com"))
+            .await
+            .expect("a is no longer tracked, so its circuit is closed");
+        svc.oneshot(request("c.example.com"))
+            .await
+            .expect_err("c is still tracked");
+        assert_eq!(4, sent.load(Ordering::SeqCst));
+    }
+}
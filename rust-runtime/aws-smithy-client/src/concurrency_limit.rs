@@ -0,0 +1,373 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Limits on the number of in-flight requests
+//!
+//! A [`ConcurrencyLimit`] caps how many requests a [`Client`](crate::Client) has in flight at
+//! once. Requests over the limit wait in a queue until an in-flight request completes. If the
+//! queue is bounded with [`ConcurrencyLimit::with_max_queued`], requests that would make the
+//! queue longer than the bound fail immediately with a [`ConcurrencyLimitExceeded`] error, so that
+//! an overloaded workload fails fast instead of hanging.
+//!
+//! Each attempt, including retries, occupies a slot for as long as it is in flight. Clones of a
+//! `ConcurrencyLimit` share their slots and queue, so one limit can be shared between clients.
+//!
+//! Slots are handed out by [`tower::limit::ConcurrencyLimit`]. When a metrics emitter is
+//! configured, the queue depth is reported through
+//! [`EmitMetrics::emit_concurrency_limit`] whenever a request joins or leaves the queue.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use crate::SdkError;
+use aws_smithy_http::metrics::{ConcurrencyLimitMetrics, EmitMetrics, SharedMetricsEmitter};
+use aws_smithy_http::operation::Operation;
+use futures_util::future::Either;
+use tokio::sync::Semaphore;
+use tower::limit::concurrency::future::ResponseFuture;
+use tower::Layer;
+
+/// Error returned when a request can't be queued because the queue is full
+#[non_exhaustive]
+#[derive(Debug)]
+pub struct ConcurrencyLimitExceeded {
+    max_queued: usize,
+}
+
+impl std::fmt::Display for ConcurrencyLimitExceeded {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "request was not sent because {} requests are already waiting for the client concurrency limit",
+            self.max_queued
+        )
+    }
+}
+
+impl std::error::Error for ConcurrencyLimitExceeded {}
+
+/// Limits the number of requests a client has in flight
+///
+/// See the [module documentation](crate::concurrency_limit) for details.
+#[derive(Clone, Debug)]
+pub struct ConcurrencyLimit {
+    max_in_flight: usize,
+    max_queued: Option<usize>,
+    semaphore: Arc<Semaphore>,
+    queued: Arc<AtomicUsize>,
+}
+
+impl ConcurrencyLimit {
+    /// Allow up to `max_in_flight` requests in flight at once
+    pub fn new(max_in_flight: usize) -> Self {
+        Self {
+            max_in_flight,
+            max_queued: None,
+            semaphore: Arc::new(Semaphore::new(max_in_flight)),
+            queued: Default::default(),
+        }
+    }
+
+    /// Fail requests that would make more than `max_queued` requests wait for a slot
+    pub fn with_max_queued(mut self, max_queued: usize) -> Self {
+        self.max_queued = Some(max_queued);
+        self
+    }
+
+    /// Returns the number of requests currently in flight
+    pub fn in_flight(&self) -> usize {
+        self.max_in_flight - self.semaphore.available_permits()
+    }
+
+    /// Returns the number of requests currently waiting for a slot
+    pub fn queued(&self) -> usize {
+        self.queued.load(Ordering::SeqCst)
+    }
+
+    fn emit(&self, metrics_emitter: Option<&SharedMetricsEmitter>) {
+        if let Some(emitter) = metrics_emitter {
+            emitter.emit_concurrency_limit(&ConcurrencyLimitMetrics::new(
+                self.in_flight(),
+                self.queued(),
+            ));
+        }
+    }
+}
+
+/// A layer that wraps services in a [`ConcurrencyLimitService`]
+#[derive(Clone, Debug)]
+pub struct ConcurrencyLimitLayer {
+    limit: Option<ConcurrencyLimit>,
+    metrics_emitter: Option<SharedMetricsEmitter>,
+}
+
+impl ConcurrencyLimitLayer {
+    /// Create a new `ConcurrencyLimitLayer`
+    ///
+    /// If `limit` is `None`, the number of requests in flight is not limited.
+    pub fn new(limit: Option<ConcurrencyLimit>) -> Self {
+        Self {
+            limit,
+            metrics_emitter: None,
+        }
+    }
+
+    /// Report the queue depth to `emitter`
+    pub fn with_metrics_emitter(mut self, emitter: Option<SharedMetricsEmitter>) -> Self {
+        self.metrics_emitter = emitter;
+        self
+    }
+}
+
+impl<S> Layer<S> for ConcurrencyLimitLayer {
+    type Service = ConcurrencyLimitService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        let inner = match &self.limit {
+            Some(limit) => Inner::Limited {
+                service: tower::limit::ConcurrencyLimit::with_semaphore(
+                    inner,
+                    limit.semaphore.clone(),
+                ),
+                limit: limit.clone(),
+                queued: None,
+            },
+            None => Inner::Unlimited(inner),
+        };
+        ConcurrencyLimitService {
+            inner,
+            metrics_emitter: self.metrics_emitter.clone(),
+        }
+    }
+}
+
+/// A service that limits the number of requests in flight with a [`ConcurrencyLimit`]
+#[derive(Debug)]
+pub struct ConcurrencyLimitService<S> {
+    inner: Inner<S>,
+    metrics_emitter: Option<SharedMetricsEmitter>,
+}
+
+#[derive(Debug)]
+enum Inner<S> {
+    Unlimited(S),
+    Limited {
+        service: tower::limit::ConcurrencyLimit<S>,
+        limit: ConcurrencyLimit,
+        /// Set while this service is waiting for a slot
+        queued: Option<QueuedGuard>,
+    },
+}
+
+impl<S: Clone> Clone for ConcurrencyLimitService<S> {
+    fn clone(&self) -> Self {
+        // A clone starts without a slot and outside of the queue, like a newly layered service
+        let inner = match &self.inner {
+            Inner::Unlimited(service) => Inner::Unlimited(service.clone()),
+            Inner::Limited { service, limit, .. } => Inner::Limited {
+                service: tower::limit::ConcurrencyLimit::with_semaphore(
+                    service.get_ref().clone(),
+                    limit.semaphore.clone(),
+                ),
+                limit: limit.clone(),
+                queued: None,
+            },
+        };
+        Self {
+            inner,
+            metrics_emitter: self.metrics_emitter.clone(),
+        }
+    }
+}
+
+/// Counts a request as queued until it is dropped
+#[derive(Debug)]
+struct QueuedGuard {
+    limit: ConcurrencyLimit,
+    metrics_emitter: Option<SharedMetricsEmitter>,
+}
+
+impl Drop for QueuedGuard {
+    fn drop(&mut self) {
+        self.limit.queued.fetch_sub(1, Ordering::SeqCst);
+        self.limit.emit(self.metrics_emitter.as_ref());
+    }
+}
+
+impl<H, R, S, E> tower::Service<Operation<H, R>> for ConcurrencyLimitService<S>
+where
+    S: tower::Service<Operation<H, R>, Error = SdkError<E>>,
+{
+    type Response = S::Response;
+    type Error = SdkError<E>;
+    type Future = Either<ResponseFuture<S::Future>, S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let (service, limit, queued) = match &mut self.inner {
+            Inner::Unlimited(service) => return service.poll_ready(cx),
+            Inner::Limited {
+                service,
+                limit,
+                queued,
+            } => (service, limit, queued),
+        };
+        let poll = service.poll_ready(cx);
+        if poll.is_ready() {
+            // Leave the queue, if this service was waiting in it
+            *queued = None;
+        } else if queued.is_none() {
+            let depth = limit.queued.fetch_add(1, Ordering::SeqCst) + 1;
+            let guard = QueuedGuard {
+                limit: limit.clone(),
+                metrics_emitter: self.metrics_emitter.clone(),
+            };
+            if let Some(max_queued) = limit.max_queued {
+                if depth > max_queued {
+                    drop(guard);
+                    return Poll::Ready(Err(SdkError::ConstructionFailure(Box::new(
+                        ConcurrencyLimitExceeded { max_queued },
+                    ))));
+                }
+            }
+            limit.emit(self.metrics_emitter.as_ref());
+            *queued = Some(guard);
+        }
+        poll
+    }
+
+    fn call(&mut self, req: Operation<H, R>) -> Self::Future {
+        match &mut self.inner {
+            Inner::Unlimited(service) => Either::Right(service.call(req)),
+            Inner::Limited { service, .. } => Either::Left(service.call(req)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::convert::Infallible;
+    use std::sync::Arc;
+
+    use crate::concurrency_limit::{
+        ConcurrencyLimit, ConcurrencyLimitExceeded, ConcurrencyLimitLayer,
+    };
+    use crate::SdkError;
+    use aws_smithy_http::body::SdkBody;
+    use aws_smithy_http::metrics::{ConcurrencyLimitMetrics, EmitMetrics, SharedMetricsEmitter};
+    use aws_smithy_http::operation::{Operation, Request};
+    use std::sync::Mutex;
+    use tokio::sync::Notify;
+    use tower::{service_fn, Layer, ServiceExt};
+
+    fn operation() -> Operation<(), ()> {
+        Operation::new(Request::new(http::Request::new(SdkBody::empty())), ())
+    }
+
+    #[tokio::test]
+    async fn requests_queue_until_a_slot_is_free() {
+        let limit = ConcurrencyLimit::new(1).with_max_queued(1);
+        let release = Arc::new(Notify::new());
+        let svc = ConcurrencyLimitLayer::new(Some(limit.clone())).layer(service_fn({
+            let release = release.clone();
+            move |_req: Operation<(), ()>| {
+                let release = release.clone();
+                async move {
+                    release.notified().await;
+                    Ok::<_, SdkError<Infallible>>(())
+                }
+            }
+        }));
+
+        let first = tokio::spawn(svc.clone().oneshot(operation()));
+        let second = tokio::spawn(svc.clone().oneshot(operation()));
+        while limit.queued() < 1 || limit.in_flight() < 1 {
+            tokio::task::yield_now().await;
+        }
+
+        // The queue is full, so a third request fails immediately
+        match svc.oneshot(operation()).await {
+            Err(SdkError::ConstructionFailure(err)) => {
+                assert!(err.downcast_ref::<ConcurrencyLimitExceeded>().is_some())
+            }
+            other => panic!("expected a concurrency limit error, got {:?}", other),
+        }
+        assert_eq!(1, limit.queued());
+
+        release.notify_one();
+        first.await.unwrap().unwrap();
+        while limit.queued() > 0 {
+            tokio::task::yield_now().await;
+        }
+        assert_eq!(1, limit.in_flight());
+        release.notify_one();
+        second.await.unwrap().unwrap();
+        assert_eq!(0, limit.in_flight());
+    }
+
+    #[tokio::test]
+    async fn cancelled_requests_leave_the_queue() {
+        let limit = ConcurrencyLimit::new(1);
+        let svc = ConcurrencyLimitLayer::new(Some(limit.clone())).layer(service_fn(
+            |_req: Operation<(), ()>| std::future::pending::<Result<(), SdkError<Infallible>>>(),
+        ));
+        let first = tokio::spawn(svc.clone().oneshot(operation()));
+        let second = tokio::spawn(svc.oneshot(operation()));
+        while limit.queued() < 1 || limit.in_flight() < 1 {
+            tokio::task::yield_now().await;
+        }
+
+        second.abort();
+        assert!(second.await.unwrap_err().is_cancelled());
+        assert_eq!((1, 0), (limit.in_flight(), limit.queued()));
+        first.abort();
+        assert!(first.await.unwrap_err().is_cancelled());
+        assert_eq!((0, 0), (limit.in_flight(), limit.queued()));
+    }
+
+    #[derive(Clone, Debug, Default)]
+    struct QueueDepths(Arc<Mutex<Vec<(usize, usize)>>>);
+
+    impl EmitMetrics for QueueDepths {
+        fn emit_concurrency_limit(&self, metrics: &ConcurrencyLimitMetrics) {
+            self.0
+                .lock()
+                .unwrap()
+                .push((metrics.in_flight, metrics.queued));
+        }
+    }
+
+    #[tokio::test]
+    async fn queue_depth_is_reported() {
+        let limit = ConcurrencyLimit::new(1);
+        let depths = QueueDepths::default();
+        let release = Arc::new(Notify::new());
+        let svc = ConcurrencyLimitLayer::new(Some(limit.clone()))
+            .with_metrics_emitter(Some(SharedMetricsEmitter::new(depths.clone())))
+            .layer(service_fn({
+                let release = release.clone();
+                move |_req: Operation<(), ()>| {
+                    let release = release.clone();
+                    async move {
+                        release.notified().await;
+                        Ok::<_, SdkError<Infallible>>(())
+                    }
+                }
+            }));
+
+        let first = tokio::spawn(svc.clone().oneshot(operation()));
+        let second = tokio::spawn(svc.oneshot(operation()));
+        while limit.queued() < 1 || limit.in_flight() < 1 {
+            tokio::task::yield_now().await;
+        }
+        release.notify_one();
+        first.await.unwrap().unwrap();
+        release.notify_one();
+        second.await.unwrap().unwrap();
+
+        // The second request joins the queue, then leaves it once the first request completes
+        assert_eq!(vec![(1, 1), (1, 0)], *depths.0.lock().unwrap());
+    }
+}
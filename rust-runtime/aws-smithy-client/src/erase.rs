@@ -11,16 +11,25 @@
 pub mod boxclone;
 use boxclone::*;
 
+#[cfg(feature = "client-hyper")]
+use crate::hyper_ext::{ConnectionCounter, PoolStats};
 use crate::{bounds, retry, Client};
 use aws_smithy_http::body::SdkBody;
 use aws_smithy_http::result::ConnectorError;
+use std::any::Any;
 use std::fmt;
+#[cfg(feature = "client-hyper")]
+use std::sync::Arc;
 use tower::{Layer, Service, ServiceExt};
 
 /// A [`Client`] whose connector and middleware types have been erased.
 ///
 /// Mainly useful if you need to name `R` in a type-erased client. If you do not, you can instead
 /// just use `Client` with no type parameters, which ends up being the same type.
+///
+/// A `DynClient` is `Send`, `Sync`, and cheap to clone, so it can be stored in application state
+/// without naming the connector or middleware it was built from. Use [`Client::into_dyn`] to
+/// produce one.
 pub type DynClient<R = retry::Standard> = Client<DynConnector, DynMiddleware<DynConnector>, R>;
 
 impl<C, M, R> Client<C, M, R>
@@ -58,6 +67,12 @@ where
             retry_policy: self.retry_policy,
             timeout_config: self.timeout_config,
             sleep_impl: self.sleep_impl,
+            metrics_emitter: self.metrics_emitter,
+            response_limits: self.response_limits,
+            parse_offload: self.parse_offload,
+            rate_limiter: self.rate_limiter,
+            concurrency_limit: self.concurrency_limit,
+            stack: Default::default(),
         }
     }
 }
@@ -98,6 +113,12 @@ where
             retry_policy: self.retry_policy,
             timeout_config: self.timeout_config,
             sleep_impl: self.sleep_impl,
+            metrics_emitter: self.metrics_emitter,
+            response_limits: self.response_limits,
+            parse_offload: self.parse_offload,
+            rate_limiter: self.rate_limiter,
+            concurrency_limit: self.concurrency_limit,
+            stack: Default::default(),
         }
     }
 
@@ -138,11 +159,17 @@ where
 /// enable dynamic dispatch for every request that goes through the connector, which increases
 /// memory pressure and suffers an additional vtable indirection for each request, but is unlikely
 /// to matter in all but the highest-performance settings.
+///
+/// A `DynConnector` created from a [Hyper adapter](crate::hyper_ext::Adapter) with
+/// [`DynConnector::from`] keeps access to the adapter's [connection pool
+/// statistics](DynConnector::pool_stats).
 #[non_exhaustive]
 #[derive(Clone)]
-pub struct DynConnector(
-    BoxCloneService<http::Request<SdkBody>, http::Response<SdkBody>, ConnectorError>,
-);
+pub struct DynConnector {
+    inner: BoxCloneService<http::Request<SdkBody>, http::Response<SdkBody>, ConnectorError>,
+    #[cfg(feature = "client-hyper")]
+    connection_counter: Option<Arc<ConnectionCounter>>,
+}
 
 impl fmt::Debug for DynConnector {
     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -151,13 +178,43 @@ impl fmt::Debug for DynConnector {
 }
 
 impl DynConnector {
-    /// Construct a new dynamically-dispatched Smithy middleware.
+    /// Construct a new dynamically-dispatched Smithy connector.
+    ///
+    /// If `connector` is already a `DynConnector`, it's returned as is rather than boxed again.
     pub fn new<E, C>(connector: C) -> Self
     where
         C: bounds::SmithyConnector<Error = E> + Send + 'static,
         E: Into<ConnectorError>,
     {
-        Self(BoxCloneService::new(connector.map_err(|e| e.into())))
+        let mut connector = Some(connector);
+        if let Some(dyn_connector) =
+            (&mut connector as &mut dyn Any).downcast_mut::<Option<DynConnector>>()
+        {
+            return dyn_connector.take().expect("set above");
+        }
+        let connector = connector.expect("set above");
+        Self {
+            inner: BoxCloneService::new(connector.map_err(|e| e.into())),
+            #[cfg(feature = "client-hyper")]
+            connection_counter: None,
+        }
+    }
+
+    /// Statistics about the connections opened by this connector
+    ///
+    /// Returns `None` unless the connector was created from a [Hyper
+    /// adapter](crate::hyper_ext::Adapter) with [`DynConnector::from`].
+    #[cfg(feature = "client-hyper")]
+    pub fn pool_stats(&self) -> Option<PoolStats> {
+        self.connection_counter
+            .as_ref()
+            .map(|counter| counter.stats())
+    }
+
+    #[cfg(feature = "client-hyper")]
+    pub(crate) fn with_connection_counter(mut self, counter: Arc<ConnectionCounter>) -> Self {
+        self.connection_counter = Some(counter);
+        self
     }
 }
 
@@ -170,11 +227,11 @@ impl Service<http::Request<SdkBody>> for DynConnector {
         &mut self,
         cx: &mut std::task::Context<'_>,
     ) -> std::task::Poll<Result<(), Self::Error>> {
-        self.0.poll_ready(cx)
+        self.inner.poll_ready(cx)
     }
 
     fn call(&mut self, req: http::Request<SdkBody>) -> Self::Future {
-        self.0.call(req)
+        self.inner.call(req)
     }
 }
 
@@ -0,0 +1,171 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! A connector for `wasm32` targets that sends requests with the JavaScript [Fetch API].
+//!
+//! This allows clients to run in browsers, web workers, and JavaScript runtimes such as
+//! Cloudflare Workers, where the hyper-based connectors don't compile. Disable the default
+//! `rustls` and `rt-tokio` features, enable `client-fetch`, and pair the connector with the
+//! `WasmSleep` implementation from `aws-smithy-async`'s `rt-wasm` feature so that retries and
+//! timeouts work:
+//!
+//! ```no_run
+//! use aws_smithy_async::rt::sleep::WasmSleep;
+//! use aws_smithy_client::fetch::FetchConnector;
+//! use std::sync::Arc;
+//!
+//! let client = aws_smithy_client::Builder::new()
+//!     .connector(FetchConnector::new())
+//!     .middleware(tower::layer::util::Identity::new())
+//!     .sleep_impl(Some(Arc::new(WasmSleep::new())))
+//!     .build();
+//! ```
+//!
+//! Request and response bodies are buffered in memory: streaming request bodies are read to the
+//! end before the request is sent, and the response body is read before it's returned.
+//!
+//! JavaScript hosts are single threaded, so the futures returned by this connector must be polled
+//! on the thread that created them.
+//!
+//! [Fetch API]: https://developer.mozilla.org/en-US/docs/Web/API/Fetch_API
+
+use crate::erase::boxclone::BoxFuture;
+use aws_smithy_http::body::SdkBody;
+use aws_smithy_http::result::ConnectorError;
+use bytes::Bytes;
+use http_body::Body;
+use js_sys::{Array, Uint8Array};
+use send_wrapper::SendWrapper;
+use std::error::Error;
+use std::fmt;
+use std::task::{Context, Poll};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+
+/// A connector that sends requests with the JavaScript Fetch API.
+///
+/// See the [module documentation](crate::fetch) for details.
+#[non_exhaustive]
+#[derive(Clone, Debug, Default)]
+pub struct FetchConnector;
+
+impl FetchConnector {
+    /// Create a connector that sends requests with the global `fetch` function.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl tower::Service<http::Request<SdkBody>> for FetchConnector {
+    type Response = http::Response<SdkBody>;
+    type Error = ConnectorError;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: http::Request<SdkBody>) -> Self::Future {
+        // JavaScript values can't leave the thread they were created on, so the future isn't
+        // `Send`. JavaScript hosts only have the one thread, so this is safe to assert.
+        Box::pin(SendWrapper::new(fetch(request)))
+    }
+}
+
+async fn fetch(request: http::Request<SdkBody>) -> Result<http::Response<SdkBody>, ConnectorError> {
+    let (parts, body) = request.into_parts();
+    let body = read_body(body).await.map_err(ConnectorError::user)?;
+
+    let headers = web_sys::Headers::new().map_err(|err| ConnectorError::user(js_error(err)))?;
+    for (name, value) in &parts.headers {
+        let value = value
+            .to_str()
+            .map_err(|err| ConnectorError::user(err.into()))?;
+        headers
+            .append(name.as_str(), value)
+            .map_err(|err| ConnectorError::user(js_error(err)))?;
+    }
+    let mut init = web_sys::RequestInit::new();
+    init.method(parts.method.as_str());
+    init.headers(&headers);
+    if !body.is_empty() {
+        init.body(Some(&Uint8Array::from(body.as_ref()).into()));
+    }
+    let request = web_sys::Request::new_with_str_and_init(&parts.uri.to_string(), &init)
+        .map_err(|err| ConnectorError::user(js_error(err)))?;
+
+    // `fetch` only rejects when the request couldn't be sent, e.g. due to a network error
+    let response: web_sys::Response = JsFuture::from(global_fetch(&request))
+        .await
+        .map_err(|err| ConnectorError::io(js_error(err)))?
+        .dyn_into()
+        .map_err(|err| ConnectorError::other(js_error(err), None))?;
+
+    let mut builder = http::Response::builder().status(response.status());
+    let entries = js_sys::try_iter(&response.headers())
+        .map_err(|err| ConnectorError::other(js_error(err), None))?
+        .ok_or_else(|| ConnectorError::other("response headers are not iterable".into(), None))?;
+    for entry in entries {
+        let entry: Array = entry
+            .map_err(|err| ConnectorError::other(js_error(err), None))?
+            .unchecked_into();
+        if let (Some(name), Some(value)) = (entry.get(0).as_string(), entry.get(1).as_string()) {
+            builder = builder.header(name, value);
+        }
+    }
+    let body = response
+        .array_buffer()
+        .map_err(|err| ConnectorError::io(js_error(err)))?;
+    let body = JsFuture::from(body)
+        .await
+        .map_err(|err| ConnectorError::io(js_error(err)))?;
+    builder
+        .body(SdkBody::from(Uint8Array::new(&body).to_vec()))
+        .map_err(|err| ConnectorError::other(err.into(), None))
+}
+
+/// Calls `fetch` on the global object, which is a `Window` in browsers, and a
+/// `WorkerGlobalScope` (or something that behaves like one) elsewhere.
+fn global_fetch(request: &web_sys::Request) -> js_sys::Promise {
+    let global = js_sys::global();
+    match global.dyn_ref::<web_sys::Window>() {
+        Some(window) => window.fetch_with_request(request),
+        None => global
+            .unchecked_into::<web_sys::WorkerGlobalScope>()
+            .fetch_with_request(request),
+    }
+}
+
+async fn read_body(mut body: SdkBody) -> Result<Bytes, Box<dyn Error + Send + Sync>> {
+    if let Some(bytes) = body.bytes() {
+        return Ok(Bytes::copy_from_slice(bytes));
+    }
+    let mut buffer = Vec::new();
+    while let Some(chunk) = body.data().await {
+        buffer.extend_from_slice(&chunk?);
+    }
+    Ok(buffer.into())
+}
+
+/// A JavaScript exception, converted to a string so that it can cross threads
+#[derive(Debug)]
+struct JsError(String);
+
+impl fmt::Display for JsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Error for JsError {}
+
+fn js_error(value: JsValue) -> Box<dyn Error + Send + Sync> {
+    let message = value
+        .dyn_ref::<js_sys::Error>()
+        .map(|err| String::from(err.message()))
+        .or_else(|| value.as_string())
+        .unwrap_or_else(|| format!("{:?}", value));
+    Box::new(JsError(message))
+}
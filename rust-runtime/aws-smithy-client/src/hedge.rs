@@ -0,0 +1,746 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Hedged requests
+//!
+//! A hedged request sends a second, identical attempt when the first attempt hasn't completed
+//! after a delay, and uses the result of whichever attempt completes first. This trades a small
+//! amount of extra load for lower tail latency.
+//!
+//! Since both attempts may be processed by the service, hedging must only be enabled for
+//! idempotent operations, and so it is enabled per operation by inserting a [`HedgeConfig`] into
+//! the operation's property bag:
+//! ```no_run
+//! # fn example(mut operation: aws_smithy_http::operation::Operation<(), ()>) {
+//! use aws_smithy_client::hedge::HedgeConfig;
+//! use std::time::Duration;
+//!
+//! operation
+//!     .properties_mut()
+//!     .insert(HedgeConfig::new(Duration::from_millis(200)));
+//! # }
+//! ```
+//!
+//! Instead of a fixed delay, [`HedgeConfig::latency_percentile`] hedges attempts that take longer
+//! than a percentile of the operation's recently observed latencies.
+//!
+//! Hedged attempts are only sent when:
+//! - the client has a sleep implementation,
+//! - the request can be cloned (see [`SdkBody::try_clone`](aws_smithy_http::body::SdkBody::try_clone)), and
+//! - retry quota is available. A hedged attempt costs as much quota as a retry, which is returned
+//!   if the operation succeeds. This prevents hedging from adding load to a service that is
+//!   already failing.
+
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use crate::retry::RetryQuota;
+use aws_smithy_async::rt::sleep::{AsyncSleep, Sleep};
+use aws_smithy_async::time::Instant;
+use aws_smithy_http::operation::Operation;
+use pin_project_lite::pin_project;
+use tower::Layer;
+
+/// Number of recent latencies kept to compute a [latency percentile](HedgeConfig::latency_percentile)
+const LATENCY_SAMPLES: usize = 128;
+
+/// Number of latencies that must be recorded before a latency percentile is used
+const MIN_LATENCY_SAMPLES: usize = 16;
+
+/// Enables hedging for an operation when inserted into its property bag.
+///
+/// See the [module documentation](crate::hedge) for details.
+#[non_exhaustive]
+#[derive(Clone, Debug)]
+pub struct HedgeConfig {
+    delay: HedgeDelay,
+}
+
+#[derive(Clone, Debug)]
+enum HedgeDelay {
+    Fixed(Duration),
+    Percentile(Arc<LatencyTracker>),
+}
+
+impl HedgeConfig {
+    /// Send a hedged attempt if the first attempt hasn't completed after `delay`.
+    pub fn new(delay: Duration) -> Self {
+        Self {
+            delay: HedgeDelay::Fixed(delay),
+        }
+    }
+
+    /// Send a hedged attempt if the first attempt hasn't completed after the `percentile`th
+    /// percentile (e.g. `95.0`) of recently observed latencies.
+    ///
+    /// The latencies of successful attempts are recorded, and `initial_delay` is used until
+    /// enough have been recorded. Clones share the recorded latencies, so the same config (or
+    /// clones of it) should be inserted into every request for an operation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `percentile` is not greater than zero and at most 100.
+    pub fn latency_percentile(percentile: f64, initial_delay: Duration) -> Self {
+        assert!(
+            percentile > 0.0 && percentile <= 100.0,
+            "the latency percentile must be greater than zero and at most 100, but was {}",
+            percentile
+        );
+        Self {
+            delay: HedgeDelay::Percentile(Arc::new(LatencyTracker {
+                percentile,
+                initial_delay,
+                samples: Mutex::new(VecDeque::with_capacity(LATENCY_SAMPLES)),
+            })),
+        }
+    }
+
+    /// The delay after which a hedged attempt is sent.
+    ///
+    /// For a [latency percentile](HedgeConfig::latency_percentile), this is the percentile of
+    /// the latencies recorded so far.
+    pub fn delay(&self) -> Duration {
+        match &self.delay {
+            HedgeDelay::Fixed(delay) => *delay,
+            HedgeDelay::Percentile(tracker) => tracker.delay(),
+        }
+    }
+
+    fn record(&self, latency: Duration) {
+        if let HedgeDelay::Percentile(tracker) = &self.delay {
+            tracker.record(latency);
+        }
+    }
+}
+
+impl PartialEq for HedgeConfig {
+    fn eq(&self, other: &Self) -> bool {
+        match (&self.delay, &other.delay) {
+            (HedgeDelay::Fixed(a), HedgeDelay::Fixed(b)) => a == b,
+            (HedgeDelay::Percentile(a), HedgeDelay::Percentile(b)) => Arc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
+}
+
+impl Eq for HedgeConfig {}
+
+/// Recent latencies of an operation, used to pick a hedging delay
+#[derive(Debug)]
+struct LatencyTracker {
+    percentile: f64,
+    initial_delay: Duration,
+    samples: Mutex<VecDeque<Duration>>,
+}
+
+impl LatencyTracker {
+    fn record(&self, latency: Duration) {
+        let mut samples = self.samples.lock().unwrap();
+        if samples.len() == LATENCY_SAMPLES {
+            samples.pop_front();
+        }
+        samples.push_back(latency);
+    }
+
+    fn delay(&self) -> Duration {
+        let mut samples: Vec<_> = self.samples.lock().unwrap().iter().copied().collect();
+        if samples.len() < MIN_LATENCY_SAMPLES {
+            return self.initial_delay;
+        }
+        samples.sort_unstable();
+        // nearest-rank percentile
+        let rank = (self.percentile / 100.0 * samples.len() as f64).ceil() as usize;
+        samples[rank.clamp(1, samples.len()) - 1]
+    }
+}
+
+/// A layer that wraps services in a [`HedgeService`]
+///
+/// Requests are cloned for the hedged attempt with the retry policy `P`, the same way they are
+/// cloned for retries.
+#[derive(Clone, Debug)]
+pub struct HedgeLayer<P> {
+    policy: P,
+    sleep_impl: Option<Arc<dyn AsyncSleep>>,
+    quota: Option<RetryQuota>,
+}
+
+impl<P> HedgeLayer<P> {
+    /// Create a new `HedgeLayer`
+    ///
+    /// Hedged attempts are never sent without a `sleep_impl`. If `quota` is `None`, hedged
+    /// attempts are not limited by retry quota.
+    pub fn new(
+        policy: P,
+        sleep_impl: Option<Arc<dyn AsyncSleep>>,
+        quota: Option<RetryQuota>,
+    ) -> Self {
+        Self {
+            policy,
+            sleep_impl,
+            quota,
+        }
+    }
+}
+
+impl<P: Clone, S> Layer<S> for HedgeLayer<P> {
+    type Service = HedgeService<S, P>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        HedgeService {
+            inner,
+            policy: self.policy.clone(),
+            sleep_impl: self.sleep_impl.clone(),
+            quota: self.quota.clone(),
+        }
+    }
+}
+
+/// A service that sends a hedged attempt for operations configured with a [`HedgeConfig`]
+#[derive(Clone, Debug)]
+pub struct HedgeService<S, P> {
+    inner: S,
+    policy: P,
+    sleep_impl: Option<Arc<dyn AsyncSleep>>,
+    quota: Option<RetryQuota>,
+}
+
+impl<H, R, S, P> tower::Service<Operation<H, R>> for HedgeService<S, P>
+where
+    S: tower::Service<Operation<H, R>> + Clone,
+    P: tower::retry::Policy<Operation<H, R>, S::Response, S::Error>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = HedgeFuture<S, Operation<H, R>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Operation<H, R>) -> Self::Future {
+        let config = req.properties().get::<HedgeConfig>().cloned();
+        let state = match (config.clone(), &self.sleep_impl) {
+            (Some(config), Some(sleep_impl)) => match self.policy.clone_request(&req) {
+                Some(hedge_request) => HedgeState::Waiting {
+                    delay: sleep_impl.sleep(config.delay()),
+                    service: self.inner.clone(),
+                    request: hedge_request,
+                    quota: self.quota.clone(),
+                },
+                None => {
+                    tracing::debug!("not hedging because the request can't be cloned");
+                    HedgeState::Disabled
+                }
+            },
+            _ => HedgeState::Disabled,
+        };
+        HedgeFuture {
+            primary: Some(self.inner.call(req)),
+            hedge: None,
+            primary_error: None,
+            config,
+            started: Instant::now(),
+            hedge_started: None,
+            state,
+        }
+    }
+}
+
+#[derive(Debug)]
+enum HedgeState<S, Req> {
+    /// Waiting for the delay to elapse before sending the hedged attempt
+    Waiting {
+        delay: Sleep,
+        service: S,
+        request: Req,
+        quota: Option<RetryQuota>,
+    },
+    /// Waiting for the service to be ready to send the hedged attempt
+    Starting {
+        service: S,
+        request: Req,
+        acquired: Option<(RetryQuota, usize)>,
+    },
+    /// The hedged attempt has been sent
+    Sent {
+        acquired: Option<(RetryQuota, usize)>,
+    },
+    /// No hedged attempt will be sent
+    Disabled,
+}
+
+pin_project! {
+    /// A future generated by a [`HedgeService`] that completes with the result of whichever
+    /// attempt succeeds first.
+    ///
+    /// If one attempt fails while the other is still in flight, the future keeps waiting for the
+    /// other one. If both attempts fail, the first attempt's error is returned.
+    #[must_use = "futures do nothing unless you `.await` or poll them"]
+    pub struct HedgeFuture<S, Req>
+    where
+        S: tower::Service<Req>,
+    {
+        #[pin]
+        primary: Option<S::Future>,
+        #[pin]
+        hedge: Option<S::Future>,
+        primary_error: Option<S::Error>,
+        config: Option<HedgeConfig>,
+        started: Instant,
+        hedge_started: Option<Instant>,
+        state: HedgeState<S, Req>,
+    }
+}
+
+impl<S, Req> std::fmt::Debug for HedgeFuture<S, Req>
+where
+    S: tower::Service<Req>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("HedgeFuture")
+    }
+}
+
+impl<S, Req> HedgeFuture<S, Req>
+where
+    S: tower::Service<Req>,
+{
+    /// Returns quota acquired for a hedged attempt that was never sent, or that was sent for an
+    /// operation that succeeded
+    fn finish<T, E>(state: &mut HedgeState<S, Req>, result: Result<T, E>) -> Poll<Result<T, E>> {
+        match std::mem::replace(state, HedgeState::Disabled) {
+            HedgeState::Starting {
+                acquired: Some((quota, acquired)),
+                ..
+            } => quota.release(acquired),
+            HedgeState::Sent {
+                acquired: Some((quota, acquired)),
+            } if result.is_ok() => quota.release(acquired),
+            _ => {}
+        }
+        Poll::Ready(result)
+    }
+}
+
+impl<S, Req> Future for HedgeFuture<S, Req>
+where
+    S: tower::Service<Req>,
+{
+    type Output = Result<S::Response, S::Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut this = self.project();
+        if let Some(primary) = this.primary.as_mut().as_pin_mut() {
+            if let Poll::Ready(result) = primary.poll(cx) {
+                this.primary.set(None);
+                match result {
+                    Ok(response) => {
+                        if let Some(config) = this.config {
+                            config.record(this.started.elapsed());
+                        }
+                        return Self::finish(this.state, Ok(response));
+                    }
+                    Err(error) if this.hedge.is_some() => {
+                        tracing::debug!("first attempt failed, waiting for the hedged attempt");
+                        *this.primary_error = Some(error);
+                    }
+                    Err(error) => return Self::finish(this.state, Err(error)),
+                }
+            }
+        }
+        loop {
+            match std::mem::replace(this.state, HedgeState::Disabled) {
+                HedgeState::Waiting {
+                    mut delay,
+                    service,
+                    request,
+                    quota,
+                } => {
+                    if Pin::new(&mut delay).poll(cx).is_pending() {
+                        *this.state = HedgeState::Waiting {
+                            delay,
+                            service,
+                            request,
+                            quota,
+                        };
+                        return Poll::Pending;
+                    }
+                    let acquired = match quota {
+                        Some(quota) => match quota.acquire() {
+                            Some(acquired) => Some((quota, acquired)),
+                            None => {
+                                tracing::debug!("not hedging because no retry quota is available");
+                                return Poll::Pending;
+                            }
+                        },
+                        None => None,
+                    };
+                    *this.state = HedgeState::Starting {
+                        service,
+                        request,
+                        acquired,
+                    };
+                }
+                HedgeState::Starting {
+                    mut service,
+                    request,
+                    acquired,
+                } => match service.poll_ready(cx) {
+                    Poll::Pending => {
+                        *this.state = HedgeState::Starting {
+                            service,
+                            request,
+                            acquired,
+                        };
+                        return Poll::Pending;
+                    }
+                    Poll::Ready(Ok(())) => {
+                        tracing::debug!("sending hedged attempt");
+                        *this.hedge_started = Some(Instant::now());
+                        this.hedge.set(Some(service.call(request)));
+                        *this.state = HedgeState::Sent { acquired };
+                    }
+                    // The primary attempt is still in flight, so keep waiting on it
+                    Poll::Ready(Err(_)) => {
+                        if let Some((quota, acquired)) = acquired {
+                            quota.release(acquired);
+                        }
+                        return Poll::Pending;
+                    }
+                },
+                state @ HedgeState::Sent { .. } => {
+                    *this.state = state;
+                    let hedge = match this.hedge.as_mut().as_pin_mut() {
+                        Some(hedge) => hedge,
+                        // The hedged attempt failed, so only the primary attempt is left
+                        None => return Poll::Pending,
+                    };
+                    return match hedge.poll(cx) {
+                        Poll::Ready(Ok(response)) => {
+                            if let (Some(config), Some(hedge_started)) =
+                                (this.config, this.hedge_started)
+                            {
+                                config.record(hedge_started.elapsed());
+                            }
+                            Self::finish(this.state, Ok(response))
+                        }
+                        Poll::Ready(Err(_)) => {
+                            this.hedge.set(None);
+                            match this.primary_error.take() {
+                                // Both attempts failed
+                                Some(primary_error) => Self::finish(this.state, Err(primary_error)),
+                                None => {
+                                    tracing::debug!(
+                                        "hedged attempt failed, waiting for the first attempt"
+                                    );
+                                    Poll::Pending
+                                }
+                            }
+                        }
+                        Poll::Pending => Poll::Pending,
+                    };
+                }
+                HedgeState::Disabled => return Poll::Pending,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use crate::hedge::{HedgeConfig, HedgeLayer};
+    use crate::retry::{Config, NewRequestPolicy, Standard};
+    use aws_smithy_async::rt::sleep::{AsyncSleep, TokioSleep};
+    use aws_smithy_http::body::SdkBody;
+    use aws_smithy_http::operation::{Operation, Request};
+    use bytes::Bytes;
+    use http_body::combinators::BoxBody;
+    use http_body::{Body, Full};
+    use std::future::Ready;
+    use tower::{service_fn, ServiceBuilder, ServiceExt};
+
+    /// Clones requests for hedging, but never retries
+    #[derive(Clone)]
+    struct CloneOnly;
+
+    impl<T, E> tower::retry::Policy<Operation<(), ()>, T, E> for CloneOnly {
+        type Future = Ready<Self>;
+
+        fn retry(&self, _req: &Operation<(), ()>, _result: Result<&T, &E>) -> Option<Self::Future> {
+            None
+        }
+
+        fn clone_request(&self, req: &Operation<(), ()>) -> Option<Operation<(), ()>> {
+            req.try_clone()
+        }
+    }
+
+    /// Responds to the first attempt after `first_latency`, and to later attempts immediately
+    fn service(
+        attempts: Arc<AtomicUsize>,
+        first_latency: Duration,
+    ) -> impl tower::Service<Operation<(), ()>, Response = usize, Error = &'static str> + Clone
+    {
+        service_fn(move |_req: Operation<(), ()>| {
+            let attempt = attempts.fetch_add(1, Ordering::SeqCst) + 1;
+            async move {
+                if attempt == 1 {
+                    tokio::time::sleep(first_latency).await;
+                }
+                Ok::<_, &'static str>(attempt)
+            }
+        })
+    }
+
+    fn operation(body: SdkBody, hedge: Option<HedgeConfig>) -> Operation<(), ()> {
+        let mut operation = Operation::new(Request::new(http::Request::new(body)), ());
+        if let Some(hedge) = hedge {
+            operation.properties_mut().insert(hedge);
+        }
+        operation
+    }
+
+    fn sleep_impl() -> Option<Arc<dyn AsyncSleep>> {
+        Some(Arc::new(TokioSleep::new()))
+    }
+
+    #[tokio::test]
+    async fn slow_attempt_is_hedged() {
+        tokio::time::pause();
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let retry = Standard::new(Config::default());
+        let svc = ServiceBuilder::new()
+            .layer(HedgeLayer::new(
+                CloneOnly,
+                sleep_impl(),
+                retry.retry_quota(None),
+            ))
+            .service(service(attempts.clone(), Duration::from_secs(10)));
+        let hedge = Some(HedgeConfig::new(Duration::from_millis(100)));
+        let winner = svc.oneshot(operation(SdkBody::from("hi"), hedge)).await;
+        assert_eq!(Ok(2), winner);
+        assert_eq!(2, attempts.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn fast_attempt_is_not_hedged() {
+        tokio::time::pause();
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let svc = ServiceBuilder::new()
+            .layer(HedgeLayer::new(CloneOnly, sleep_impl(), None))
+            .service(service(attempts.clone(), Duration::from_millis(10)));
+        let hedge = Some(HedgeConfig::new(Duration::from_millis(100)));
+        let winner = svc.oneshot(operation(SdkBody::from("hi"), hedge)).await;
+        assert_eq!(Ok(1), winner);
+        assert_eq!(1, attempts.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn hedging_requires_opt_in_and_a_cloneable_request() {
+        tokio::time::pause();
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let svc = ServiceBuilder::new()
+            .layer(HedgeLayer::new(CloneOnly, sleep_impl(), None))
+            .service(service(attempts.clone(), Duration::from_secs(1)));
+        let winner = svc
+            .clone()
+            .oneshot(operation(SdkBody::from("hi"), None))
+            .await;
+        assert_eq!(Ok(1), winner);
+
+        attempts.store(0, Ordering::SeqCst);
+        let streaming = SdkBody::from_dyn(BoxBody::new(
+            Full::new(Bytes::from("hi")).map_err(|never| match never {}),
+        ));
+        assert!(streaming.try_clone().is_none());
+        let hedge = Some(HedgeConfig::new(Duration::from_millis(100)));
+        let winner = svc.oneshot(operation(streaming, hedge)).await;
+        assert_eq!(Ok(1), winner);
+        assert_eq!(1, attempts.load(Ordering::SeqCst));
+    }
+
+    /// The first attempt responds after `first_latency`, later attempts fail immediately
+    fn failing_hedge(
+        attempts: Arc<AtomicUsize>,
+        first_latency: Duration,
+        first_result: Result<usize, &'static str>,
+    ) -> impl tower::Service<Operation<(), ()>, Response = usize, Error = &'static str> + Clone
+    {
+        service_fn(move |_req: Operation<(), ()>| {
+            let attempt = attempts.fetch_add(1, Ordering::SeqCst) + 1;
+            async move {
+                if attempt == 1 {
+                    tokio::time::sleep(first_latency).await;
+                    first_result
+                } else {
+                    Err("hedge failed")
+                }
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn failed_hedge_does_not_cancel_the_first_attempt() {
+        tokio::time::pause();
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let svc = ServiceBuilder::new()
+            .layer(HedgeLayer::new(CloneOnly, sleep_impl(), None))
+            .service(failing_hedge(
+                attempts.clone(),
+                Duration::from_secs(1),
+                Ok(1),
+            ));
+        let hedge = Some(HedgeConfig::new(Duration::from_millis(100)));
+        let winner = svc.oneshot(operation(SdkBody::from("hi"), hedge)).await;
+        assert_eq!(Ok(1), winner);
+        assert_eq!(2, attempts.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn first_error_is_returned_when_both_attempts_fail() {
+        tokio::time::pause();
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let svc = ServiceBuilder::new()
+            .layer(HedgeLayer::new(CloneOnly, sleep_impl(), None))
+            .service(failing_hedge(
+                attempts.clone(),
+                Duration::from_secs(1),
+                Err("first failed"),
+            ));
+        let hedge = Some(HedgeConfig::new(Duration::from_millis(100)));
+        let result = svc.oneshot(operation(SdkBody::from("hi"), hedge)).await;
+        assert_eq!(Err("first failed"), result);
+        assert_eq!(2, attempts.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn failed_first_attempt_does_not_cancel_the_hedge() {
+        tokio::time::pause();
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let svc = ServiceBuilder::new()
+            .layer(HedgeLayer::new(CloneOnly, sleep_impl(), None))
+            .service(service_fn({
+                let attempts = attempts.clone();
+                move |_req: Operation<(), ()>| {
+                    let attempt = attempts.fetch_add(1, Ordering::SeqCst) + 1;
+                    async move {
+                        // The first attempt fails while the hedge is still in flight
+                        if attempt == 1 {
+                            tokio::time::sleep(Duration::from_millis(200)).await;
+                            Err("first failed")
+                        } else {
+                            tokio::time::sleep(Duration::from_millis(500)).await;
+                            Ok(attempt)
+                        }
+                    }
+                }
+            }));
+        let hedge = Some(HedgeConfig::new(Duration::from_millis(100)));
+        let winner = svc.oneshot(operation(SdkBody::from("hi"), hedge)).await;
+        assert_eq!(Ok(2), winner);
+        assert_eq!(2, attempts.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn latency_percentile_delay() {
+        let config = HedgeConfig::latency_percentile(90.0, Duration::from_secs(1));
+        // Clones share the recorded latencies
+        let clone = config.clone();
+        assert_eq!(config, clone);
+        for millis in 1..=15 {
+            clone.record(Duration::from_millis(millis * 10));
+        }
+        assert_eq!(Duration::from_secs(1), config.delay());
+        for millis in 16..=20 {
+            clone.record(Duration::from_millis(millis * 10));
+        }
+        assert_eq!(Duration::from_millis(180), config.delay());
+        assert_ne!(
+            config,
+            HedgeConfig::latency_percentile(90.0, Duration::from_secs(1))
+        );
+    }
+
+    #[tokio::test]
+    async fn latency_percentile_is_learned_from_successful_attempts() {
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let svc = ServiceBuilder::new()
+            .layer(HedgeLayer::new(CloneOnly, sleep_impl(), None))
+            .service(service_fn({
+                let attempts = attempts.clone();
+                move |_req: Operation<(), ()>| {
+                    attempts.fetch_add(1, Ordering::SeqCst);
+                    async move {
+                        tokio::time::sleep(Duration::from_millis(1)).await;
+                        Ok::<_, &'static str>(())
+                    }
+                }
+            }));
+        let config = HedgeConfig::latency_percentile(50.0, Duration::from_secs(10));
+        for _ in 0..super::MIN_LATENCY_SAMPLES {
+            svc.clone()
+                .oneshot(operation(SdkBody::from("hi"), Some(config.clone())))
+                .await
+                .unwrap();
+        }
+        assert_eq!(super::MIN_LATENCY_SAMPLES, attempts.load(Ordering::SeqCst));
+        let delay = config.delay();
+        assert!(delay >= Duration::from_millis(1) && delay < Duration::from_secs(10));
+    }
+
+    #[test]
+    #[should_panic(expected = "latency percentile")]
+    fn latency_percentile_must_be_in_range() {
+        HedgeConfig::latency_percentile(0.0, Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn hedging_spends_retry_quota() {
+        tokio::time::pause();
+        let attempts = Arc::new(AtomicUsize::new(0));
+        // Leave only enough quota for a single retry
+        let retry = Standard::new(Config::default());
+        let quota = retry.retry_quota(None).expect("standard retry has quota");
+        while quota.acquire().is_some() {}
+        quota.release(5);
+        let svc = ServiceBuilder::new()
+            .layer(HedgeLayer::new(
+                CloneOnly,
+                sleep_impl(),
+                retry.retry_quota(None),
+            ))
+            .service(service_fn({
+                let attempts = attempts.clone();
+                move |_req: Operation<(), ()>| {
+                    attempts.fetch_add(1, Ordering::SeqCst);
+                    async move {
+                        tokio::time::sleep(Duration::from_secs(1)).await;
+                        Err::<(), _>("failed")
+                    }
+                }
+            }));
+        let hedge = Some(HedgeConfig::new(Duration::from_millis(100)));
+        let result = svc
+            .clone()
+            .oneshot(operation(SdkBody::from("hi"), hedge.clone()))
+            .await;
+        assert_eq!(Err("failed"), result);
+        assert_eq!(2, attempts.load(Ordering::SeqCst));
+
+        // The failed hedge used up all of the quota, so this request isn't hedged
+        let result = svc.oneshot(operation(SdkBody::from("hi"), hedge)).await;
+        assert_eq!(Err("failed"), result);
+        assert_eq!(3, attempts.load(Ordering::SeqCst));
+    }
+}
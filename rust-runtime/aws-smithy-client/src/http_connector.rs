@@ -7,8 +7,12 @@
 //! that enable passing HTTP connectors around.
 
 use crate::erase::DynConnector;
+use crate::tls::TlsContext;
 use aws_smithy_async::rt::sleep::AsyncSleep;
 use aws_smithy_types::timeout;
+use aws_smithy_types::tristate::TriState;
+use std::net::IpAddr;
+use std::time::Duration;
 use std::{fmt::Debug, sync::Arc};
 
 /// Type alias for a Connector factory function.
@@ -63,6 +67,10 @@ pub struct HttpSettings {
     pub http_timeout_config: timeout::Http,
     /// Timeout configuration used when creating TCP connections
     pub tcp_timeout_config: timeout::Tcp,
+    /// Socket options used when creating TCP connections
+    pub tcp_settings: TcpSettings,
+    /// TLS settings used when establishing HTTPS connections
+    pub tls_context: TlsContext,
 }
 
 impl HttpSettings {
@@ -77,4 +85,106 @@ impl HttpSettings {
         self.tcp_timeout_config = tcp_timeout_config;
         self
     }
+
+    /// Set the socket options to be used when creating TCP connections
+    pub fn with_tcp_settings(mut self, tcp_settings: TcpSettings) -> Self {
+        self.tcp_settings = tcp_settings;
+        self
+    }
+
+    /// Set the TLS settings to be used when establishing HTTPS connections
+    ///
+    /// Settings that the TLS backend rejects are reported as a [`TlsError`](crate::tls::TlsError)
+    /// when the connector is created.
+    pub fn with_tls_context(mut self, tls_context: TlsContext) -> Self {
+        self.tls_context = tls_context;
+        self
+    }
+}
+
+/// Socket options for the TCP connections made by the default connectors
+///
+/// Options that are left unset keep the connector's default behavior.
+///
+/// # Examples
+/// ```
+/// use aws_smithy_client::http_connector::{HttpSettings, TcpSettings};
+/// use aws_smithy_types::tristate::TriState;
+/// use std::time::Duration;
+///
+/// // Disable Nagle's algorithm and send keepalive probes on connections idle for a minute
+/// let tcp_settings = TcpSettings::new()
+///     .with_nodelay(true)
+///     .with_keepalive(TriState::Set(Duration::from_secs(60)));
+/// let settings = HttpSettings::default().with_tcp_settings(tcp_settings);
+/// ```
+#[non_exhaustive]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TcpSettings {
+    nodelay: Option<bool>,
+    keepalive: TriState<Duration>,
+    happy_eyeballs_timeout: TriState<Duration>,
+    local_address: Option<IpAddr>,
+}
+
+impl TcpSettings {
+    /// Create new TCP settings with no options set
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Whether `TCP_NODELAY` is set, disabling Nagle's algorithm
+    pub fn nodelay(&self) -> Option<bool> {
+        self.nodelay
+    }
+
+    /// Set whether `TCP_NODELAY` is set, disabling Nagle's algorithm
+    ///
+    /// By default, Nagle's algorithm is enabled.
+    pub fn with_nodelay(mut self, nodelay: bool) -> Self {
+        self.nodelay = Some(nodelay);
+        self
+    }
+
+    /// How long a connection must be idle before `SO_KEEPALIVE` probes are sent
+    pub fn keepalive(&self) -> TriState<Duration> {
+        self.keepalive.clone()
+    }
+
+    /// Set how long a connection must be idle before `SO_KEEPALIVE` probes are sent
+    ///
+    /// By default, keepalive probes are not sent.
+    pub fn with_keepalive(mut self, keepalive: TriState<Duration>) -> Self {
+        self.keepalive = keepalive;
+        self
+    }
+
+    /// How long to wait for a connection to the preferred address family before racing a
+    /// connection to the other family ("happy eyeballs", [RFC 6555](https://tools.ietf.org/html/rfc6555))
+    pub fn happy_eyeballs_timeout(&self) -> TriState<Duration> {
+        self.happy_eyeballs_timeout.clone()
+    }
+
+    /// Set how long to wait for a connection to the preferred address family before racing a
+    /// connection to the other family
+    ///
+    /// Set this to [`TriState::Disabled`] to only ever try one address at a time. By default,
+    /// the connectors wait 300 milliseconds.
+    pub fn with_happy_eyeballs_timeout(mut self, timeout: TriState<Duration>) -> Self {
+        self.happy_eyeballs_timeout = timeout;
+        self
+    }
+
+    /// The local address connections are bound to
+    pub fn local_address(&self) -> Option<IpAddr> {
+        self.local_address
+    }
+
+    /// Bind connections to a local address, e.g. to send requests through a specific network interface
+    ///
+    /// By default, the operating system picks the local address.
+    pub fn with_local_address(mut self, local_address: impl Into<Option<IpAddr>>) -> Self {
+        self.local_address = local_address.into();
+        self
+    }
 }
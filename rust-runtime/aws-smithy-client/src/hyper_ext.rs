@@ -38,6 +38,9 @@
 //! // once you have a connector, use it to construct a Smithy client:
 //! let client = Client::<DynConnector, MyMiddleware>::new(DynConnector::new(connector));
 //! ```
+//!
+//! ### Use a transport other than TCP
+//! To send requests over a Unix domain socket or another custom transport, see [`transport`].
 
 use std::error::Error;
 use std::sync::Arc;
@@ -51,6 +54,7 @@ use aws_smithy_async::future::timeout::TimedOutError;
 use aws_smithy_async::rt::sleep::{default_async_sleep, AsyncSleep};
 use aws_smithy_http::body::SdkBody;
 use aws_smithy_http::result::ConnectorError;
+use aws_smithy_http_tower::dispatch::ContinueSignal;
 use aws_smithy_types::retry::ErrorKind;
 use aws_smithy_types::timeout;
 use aws_smithy_types::tristate::TriState;
@@ -59,15 +63,62 @@ use crate::erase::DynConnector;
 use crate::never::stream::EmptyStream;
 use crate::Builder as ClientBuilder;
 
+pub(crate) use self::connect_timing::ConnectionCounter;
+use self::connect_timing::TimedConnector;
 use self::timeout_middleware::{ConnectTimeout, HttpReadTimeout, HttpTimeoutError};
 
+pub mod transport;
+
 /// Adapter from a [`hyper::Client`](hyper::Client) to a connector usable by a Smithy [`Client`](crate::Client).
 ///
 /// This adapter also enables TCP `CONNECT` and HTTP `READ` timeouts via [`Adapter::builder`]. For examples
 /// see [the module documentation](crate::hyper_ext).
 #[derive(Clone, Debug)]
 #[non_exhaustive]
-pub struct Adapter<C>(HttpReadTimeout<hyper::Client<ConnectTimeout<C>, SdkBody>>);
+pub struct Adapter<C>(
+    HttpReadTimeout<hyper::Client<ConnectTimeout<TimedConnector<C>>, SdkBody>>,
+    Arc<ConnectionCounter>,
+);
+
+impl<C> Adapter<C> {
+    /// Statistics about the connections opened by this adapter
+    ///
+    /// Clones of an adapter share their connection pool, and so their statistics.
+    pub fn pool_stats(&self) -> PoolStats {
+        self.1.stats()
+    }
+}
+
+impl<C> From<Adapter<C>> for DynConnector
+where
+    Adapter<C>: crate::bounds::SmithyConnector,
+{
+    /// Type-erase `adapter`, keeping access to its [`pool_stats`](DynConnector::pool_stats)
+    fn from(adapter: Adapter<C>) -> Self {
+        let counter = adapter.1.clone();
+        DynConnector::new(adapter).with_connection_counter(counter)
+    }
+}
+
+/// Statistics about the connections of an [`Adapter`]
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PoolStats {
+    open_connections: usize,
+    connections_opened: u64,
+}
+
+impl PoolStats {
+    /// The number of connections that are currently open, whether in use or idle in the pool
+    pub fn open_connections(&self) -> usize {
+        self.open_connections
+    }
+
+    /// The total number of connections that have been opened
+    pub fn connections_opened(&self) -> u64 {
+        self.connections_opened
+    }
+}
 
 impl<C> Service<http::Request<SdkBody>> for Adapter<C>
 where
@@ -93,6 +144,12 @@ where
     }
 
     fn call(&mut self, req: http::Request<SdkBody>) -> Self::Future {
+        // hyper doesn't surface `100 Continue`, but it does return a final response (e.g.
+        // `403 Forbidden`) that arrives while the body is held back, so the body is held back
+        // until the timeout elapses
+        if let Some(signal) = req.extensions().get::<ContinueSignal>() {
+            signal.hold();
+        }
         let fut = self.0.call(req);
         Box::pin(async move { Ok(fut.await.map_err(downcast_error)?.map(SdkBody::from)) })
     }
@@ -199,7 +256,21 @@ impl Builder {
     {
         // if we are using Hyper, Tokio must already be enabled so we can fallback to Tokio.
         let sleep = self.sleep.or_else(default_async_sleep);
-        let connector = match self.http_timeout_config.connect_timeout() {
+        // Time each connection so that it's reported in the `TimingInfo` of the first request
+        // sent on it
+        let counter = Arc::new(ConnectionCounter::default());
+        let connector = TimedConnector::new(connector, counter.clone());
+        // the connector establishes the connection and negotiates TLS in a single step, so both
+        // timeouts bound it together
+        let connect_timeout = match (
+            self.http_timeout_config.connect_timeout(),
+            self.http_timeout_config.tls_negotiation_timeout(),
+        ) {
+            (TriState::Set(connect), TriState::Set(tls)) => TriState::Set(connect + tls),
+            (TriState::Unset, TriState::Set(tls)) => TriState::Set(tls),
+            (connect, _) => connect,
+        };
+        let connector = match connect_timeout {
             TriState::Set(duration) => ConnectTimeout::new(
                 connector,
                 sleep
@@ -222,7 +293,7 @@ impl Builder {
             // Some day, we could provide a default timeout if none is set. Today is not that day.
             TriState::Unset | TriState::Disabled => HttpReadTimeout::no_timeout(base),
         };
-        Adapter(http_timeout)
+        Adapter(http_timeout, counter)
     }
 
     /// Set the async sleep implementation used for timeouts
@@ -257,6 +328,35 @@ impl Builder {
     }
 }
 
+/// Create a [`hyper::client::HttpConnector`] that applies the given [`TcpSettings`](crate::http_connector::TcpSettings)
+///
+/// The connector accepts `https` URIs so that it can be wrapped with a TLS connector, as
+/// [`conns::https_with_tcp_settings`](crate::conns::https_with_tcp_settings) does.
+#[cfg(any(feature = "rustls", feature = "native-tls"))]
+pub fn http_connector(
+    tcp_settings: &crate::http_connector::TcpSettings,
+) -> hyper::client::HttpConnector {
+    let mut http = hyper::client::HttpConnector::new();
+    http.enforce_http(false);
+    if let Some(nodelay) = tcp_settings.nodelay() {
+        http.set_nodelay(nodelay);
+    }
+    match tcp_settings.keepalive() {
+        TriState::Set(keepalive) => http.set_keepalive(Some(keepalive)),
+        TriState::Disabled => http.set_keepalive(None),
+        TriState::Unset => {}
+    }
+    match tcp_settings.happy_eyeballs_timeout() {
+        TriState::Set(timeout) => http.set_happy_eyeballs_timeout(Some(timeout)),
+        TriState::Disabled => http.set_happy_eyeballs_timeout(None),
+        TriState::Unset => {}
+    }
+    if let Some(local_address) = tcp_settings.local_address() {
+        http.set_local_address(Some(local_address));
+    }
+    http
+}
+
 #[cfg(any(feature = "rustls", feature = "native-tls"))]
 impl<M> crate::Builder<crate::erase::DynConnector, M>
 where
@@ -309,7 +409,7 @@ where
 impl<M, R> ClientBuilder<(), M, R> {
     /// Connect to the service over HTTPS using Rustls using dynamic dispatch.
     pub fn rustls(self) -> ClientBuilder<DynConnector, M, R> {
-        self.connector(DynConnector::new(
+        self.connector(DynConnector::from(
             Adapter::builder().build(crate::conns::https()),
         ))
     }
@@ -320,12 +420,185 @@ impl<M, R> ClientBuilder<(), M, R> {
     /// Connect to the service over HTTPS using the native TLS library on your
     /// platform using dynamic dispatch.
     pub fn native_tls(self) -> ClientBuilder<DynConnector, M, R> {
-        self.connector(DynConnector::new(
+        self.connector(DynConnector::from(
             Adapter::builder().build(crate::conns::native_tls()),
         ))
     }
 }
 
+mod connect_timing {
+    use aws_smithy_async::time::Instant;
+    use std::future::Future;
+    use std::io::IoSlice;
+    use std::pin::Pin;
+    use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::task::{Context, Poll};
+
+    use http::Uri;
+    use hyper::client::connect::{Connected, Connection};
+    use pin_project_lite::pin_project;
+    use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+    use aws_smithy_http::timing::ConnectTiming;
+
+    use super::PoolStats;
+
+    /// Counts the connections opened by a connector
+    #[derive(Debug, Default)]
+    pub(crate) struct ConnectionCounter {
+        open: AtomicUsize,
+        opened: AtomicU64,
+    }
+
+    impl ConnectionCounter {
+        pub(crate) fn stats(&self) -> PoolStats {
+            PoolStats {
+                open_connections: self.open.load(Ordering::Relaxed),
+                connections_opened: self.opened.load(Ordering::Relaxed),
+            }
+        }
+
+        fn open(self: &Arc<Self>) -> OpenConnection {
+            self.open.fetch_add(1, Ordering::Relaxed);
+            self.opened.fetch_add(1, Ordering::Relaxed);
+            OpenConnection(self.clone())
+        }
+    }
+
+    /// Marks a connection as open until it's dropped
+    #[derive(Debug)]
+    struct OpenConnection(Arc<ConnectionCounter>);
+
+    impl Drop for OpenConnection {
+        fn drop(&mut self) {
+            self.0.open.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Connector that records how long each connection took to establish, and counts the
+    /// connections that are open
+    ///
+    /// Hyper copies the [`ConnectTiming`] into the extensions of every response received on the
+    /// connection, where it's picked up by the dispatch layer.
+    #[derive(Clone, Debug)]
+    pub(super) struct TimedConnector<C> {
+        inner: C,
+        counter: Arc<ConnectionCounter>,
+    }
+
+    impl<C> TimedConnector<C> {
+        pub(super) fn new(inner: C, counter: Arc<ConnectionCounter>) -> Self {
+            Self { inner, counter }
+        }
+    }
+
+    impl<C> tower::Service<Uri> for TimedConnector<C>
+    where
+        C: tower::Service<Uri>,
+    {
+        type Response = TimedConnection<C::Response>;
+        type Error = C::Error;
+        type Future = TimedConnectFuture<C::Future>;
+
+        fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            self.inner.poll_ready(cx)
+        }
+
+        fn call(&mut self, uri: Uri) -> Self::Future {
+            TimedConnectFuture {
+                inner: self.inner.call(uri),
+                start: Instant::now(),
+                counter: self.counter.clone(),
+            }
+        }
+    }
+
+    pin_project! {
+        pub(super) struct TimedConnectFuture<F> {
+            #[pin]
+            inner: F,
+            start: Instant,
+            counter: Arc<ConnectionCounter>,
+        }
+    }
+
+    impl<F, T, E> Future for TimedConnectFuture<F>
+    where
+        F: Future<Output = Result<T, E>>,
+    {
+        type Output = Result<TimedConnection<T>, E>;
+
+        fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+            let this = self.project();
+            this.inner.poll(cx).map_ok(|inner| {
+                let now = Instant::now();
+                TimedConnection {
+                    inner,
+                    timing: ConnectTiming::new(now, now - *this.start),
+                    _open: this.counter.open(),
+                }
+            })
+        }
+    }
+
+    pin_project! {
+        /// A connection that reports how long it took to establish
+        pub(super) struct TimedConnection<I> {
+            #[pin]
+            inner: I,
+            timing: ConnectTiming,
+            _open: OpenConnection,
+        }
+    }
+
+    impl<I: Connection> Connection for TimedConnection<I> {
+        fn connected(&self) -> Connected {
+            self.inner.connected().extra(self.timing)
+        }
+    }
+
+    impl<I: AsyncRead> AsyncRead for TimedConnection<I> {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<std::io::Result<()>> {
+            self.project().inner.poll_read(cx, buf)
+        }
+    }
+
+    impl<I: AsyncWrite> AsyncWrite for TimedConnection<I> {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<std::io::Result<usize>> {
+            self.project().inner.poll_write(cx, buf)
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            self.project().inner.poll_flush(cx)
+        }
+
+        fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            self.project().inner.poll_shutdown(cx)
+        }
+
+        fn poll_write_vectored(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            bufs: &[IoSlice<'_>],
+        ) -> Poll<std::io::Result<usize>> {
+            self.project().inner.poll_write_vectored(cx, bufs)
+        }
+
+        fn is_write_vectored(&self) -> bool {
+            self.inner.is_write_vectored()
+        }
+    }
+}
+
 mod timeout_middleware {
     use std::error::Error;
     use std::fmt::Formatter;
@@ -585,6 +858,31 @@ mod timeout_middleware {
             assert_elapsed!(now, Duration::from_secs(1));
         }
 
+        #[tokio::test]
+        async fn tls_negotiation_timeout_extends_connect_timeout() {
+            let inner = NeverConnected::new();
+            let timeout = timeout::Http::new()
+                .with_connect_timeout(TriState::Set(Duration::from_secs(1)))
+                .with_tls_negotiation_timeout(TriState::Set(Duration::from_secs(2)));
+            let mut hyper = Adapter::builder()
+                .timeout(&timeout)
+                .sleep_impl(TokioSleep::new())
+                .build(inner);
+            let now = tokio::time::Instant::now();
+            tokio::time::pause();
+            let resp = hyper
+                .call(
+                    http::Request::builder()
+                        .uri("http://foo.com")
+                        .body(SdkBody::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap_err();
+            assert!(resp.is_timeout(), "{:?}", resp);
+            assert_elapsed!(now, Duration::from_secs(3));
+        }
+
         #[tokio::test]
         async fn http_read_timeout_works() {
             let inner = NeverReplies::new();
@@ -652,6 +950,114 @@ mod test {
         let _builder: ClientBuilder<DynConnector, (), _> = ClientBuilder::new().native_tls();
     }
 
+    #[cfg(any(feature = "rustls", feature = "native-tls"))]
+    #[tokio::test]
+    async fn prewarm_opens_pooled_connections() {
+        use crate::http_connector::TcpSettings;
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        use tower::layer::util::Identity;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let uri: Uri = format!("http://{}", listener.local_addr().unwrap())
+            .parse()
+            .unwrap();
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                tokio::spawn(async move {
+                    let mut buf = [0; 1024];
+                    // reply to every request, keeping the connection open
+                    while stream.read(&mut buf).await.unwrap_or(0) > 0 {
+                        let response = b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n";
+                        if stream.write_all(response).await.is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+        });
+
+        let adapter = Adapter::builder().build(super::http_connector(&TcpSettings::default()));
+        let client =
+            crate::Client::<DynConnector, Identity>::new(DynConnector::new(adapter.clone()));
+        assert_eq!(0, adapter.pool_stats().connections_opened());
+
+        client.prewarm(&uri, 3).await.expect("connections open");
+        assert_eq!(3, adapter.pool_stats().connections_opened());
+        assert_eq!(3, adapter.pool_stats().open_connections());
+    }
+
+    #[cfg(any(feature = "rustls", feature = "native-tls"))]
+    #[tokio::test]
+    async fn pool_stats_are_reachable_through_dyn_connector() {
+        use crate::http_connector::TcpSettings;
+        use tower::layer::util::Identity;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let uri: Uri = format!("http://{}", listener.local_addr().unwrap())
+            .parse()
+            .unwrap();
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                tokio::spawn(async move {
+                    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+                    let mut buf = [0; 1024];
+                    while stream.read(&mut buf).await.unwrap_or(0) > 0 {
+                        let response = b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n";
+                        if stream.write_all(response).await.is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+        });
+
+        let adapter = Adapter::builder().build(super::http_connector(&TcpSettings::default()));
+        assert_eq!(None, DynConnector::new(adapter.clone()).pool_stats());
+        let connector = DynConnector::from(adapter);
+        // wrapping a `DynConnector` again keeps its statistics
+        let client =
+            crate::Client::<DynConnector, Identity>::new(DynConnector::new(connector.clone()));
+        client.prewarm(&uri, 2).await.expect("connections open");
+        let stats = connector.pool_stats().expect("created from an adapter");
+        assert_eq!(2, stats.connections_opened());
+        assert_eq!(2, stats.open_connections());
+    }
+
+    #[cfg(any(feature = "rustls", feature = "native-tls"))]
+    #[tokio::test]
+    async fn http_connector_applies_tcp_settings() {
+        use crate::http_connector::TcpSettings;
+        use std::net::{IpAddr, Ipv4Addr};
+        use tower::Service;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let uri: Uri = format!("http://{}", listener.local_addr().unwrap())
+            .parse()
+            .unwrap();
+        let local_address = IpAddr::V4(Ipv4Addr::LOCALHOST);
+
+        let mut connector = super::http_connector(
+            &TcpSettings::new()
+                .with_nodelay(true)
+                .with_local_address(local_address),
+        );
+        let (stream, (_, peer)) = tokio::join!(
+            async { connector.call(uri.clone()).await.expect("connects") },
+            async { listener.accept().await.expect("accepts") }
+        );
+        assert!(stream.nodelay().unwrap());
+        assert_eq!(local_address, peer.ip());
+
+        let mut connector = super::http_connector(&TcpSettings::new().with_nodelay(false));
+        let (stream, _) = tokio::join!(
+            async { connector.call(uri).await.expect("connects") },
+            async { listener.accept().await.expect("accepts") }
+        );
+        assert!(!stream.nodelay().unwrap());
+    }
+
     #[tokio::test]
     async fn hyper_io_error() {
         let connector = TestConnection {
@@ -671,6 +1077,108 @@ mod test {
         assert!(err.is_io(), "{:?}", err);
     }
 
+    #[tokio::test]
+    async fn connect_time_is_reported_for_new_connections() {
+        use aws_smithy_async::time::Instant;
+        use aws_smithy_http::timing::ConnectTiming;
+        use tower::Service;
+
+        let mut adapter = Adapter::builder().build(TestConnection {
+            inner: OkStream::default(),
+        });
+        let mut send = || {
+            adapter.call(
+                http::Request::builder()
+                    .uri("http://amazon.com")
+                    .body(SdkBody::empty())
+                    .unwrap(),
+            )
+        };
+
+        let dispatched_at = Instant::now();
+        let response = send().await.expect("success");
+        let timing = *response
+            .extensions()
+            .get::<ConnectTiming>()
+            .expect("connect timing is set");
+        assert!(timing.established_at >= dispatched_at);
+        drop(response);
+
+        // The second request reuses the pooled connection
+        let dispatched_at = Instant::now();
+        let response = send().await.expect("success");
+        let reused = response.extensions().get::<ConnectTiming>().unwrap();
+        assert_eq!(reused.established_at, timing.established_at);
+        assert_eq!(
+            reused.connect_time_since(dispatched_at),
+            std::time::Duration::ZERO
+        );
+    }
+
+    // ---- machinery to make a Hyper connector that responds with an empty `200 OK` to every
+    // request
+    #[derive(Clone, Default)]
+    struct OkStream {
+        state: std::sync::Arc<std::sync::Mutex<OkStreamState>>,
+    }
+
+    #[derive(Default)]
+    struct OkStreamState {
+        pending_responses: usize,
+        reader: Option<std::task::Waker>,
+    }
+
+    impl Connection for OkStream {
+        fn connected(&self) -> Connected {
+            Connected::new()
+        }
+    }
+
+    const OK_RESPONSE: &[u8] = b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n";
+
+    impl AsyncRead for OkStream {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<std::io::Result<()>> {
+            let mut state = self.state.lock().unwrap();
+            if state.pending_responses == 0 {
+                state.reader = Some(cx.waker().clone());
+                return Poll::Pending;
+            }
+            state.pending_responses -= 1;
+            buf.put_slice(OK_RESPONSE);
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    impl AsyncWrite for OkStream {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<Result<usize, Error>> {
+            // Requests without a body are written in one go
+            if buf.ends_with(b"\r\n\r\n") {
+                let mut state = self.state.lock().unwrap();
+                state.pending_responses += 1;
+                if let Some(reader) = state.reader.take() {
+                    reader.wake();
+                }
+            }
+            Poll::Ready(Ok(buf.len()))
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+
     // ---- machinery to make a Hyper connector that responds with an IO Error
     #[derive(Clone)]
     struct HangupStream;
@@ -0,0 +1,284 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Connectors that send requests over transports other than TCP
+//!
+//! The connectors in this module can be passed to [`Adapter::builder`](super::Adapter::builder)
+//! like any other Hyper connector. Since they replace the transport, every request is sent over it
+//! regardless of the host in its URI. The transports are not encrypted, so requests should use
+//! `http` URIs.
+//!
+//! # Examples
+//! ### Send requests to a local emulator listening on a Unix domain socket
+//! ```no_run
+//! # #[cfg(unix)]
+//! # fn example() {
+//! use aws_smithy_client::erase::DynConnector;
+//! use aws_smithy_client::hyper_ext::{self, transport::UnixConnector};
+//!
+//! let connector = hyper_ext::Adapter::builder().build(UnixConnector::new("/var/run/emulator.sock"));
+//! let connector = DynConnector::new(connector);
+//! # }
+//! ```
+//!
+//! ### Send requests over a custom transport
+//! Any `AsyncRead + AsyncWrite` stream can be used as the transport, e.g. a vsock stream to reach
+//! a proxy outside of a Firecracker microVM:
+//! ```no_run
+//! use aws_smithy_client::hyper_ext::{self, transport::CustomConnector};
+//!
+//! let connector = hyper_ext::Adapter::builder().build(CustomConnector::new(|_uri| async {
+//!     // connect to the proxy, for example with `tokio-vsock`
+//! #   Ok::<_, std::io::Error>(tokio::io::duplex(1024).0)
+//! }));
+//! ```
+
+use std::fmt;
+use std::future::Future;
+use std::io;
+use std::io::IoSlice;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use http::Uri;
+use hyper::client::connect::{Connected, Connection};
+use pin_project_lite::pin_project;
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+type BoxFuture<T> = Pin<Box<dyn Future<Output = T> + Send>>;
+
+/// Connector that opens a new transport stream for each connection with a user-provided function
+///
+/// The function is called with the URI of the request that needs a new connection.
+pub struct CustomConnector<F> {
+    make_transport: Arc<F>,
+}
+
+impl<F> Clone for CustomConnector<F> {
+    fn clone(&self) -> Self {
+        Self {
+            make_transport: self.make_transport.clone(),
+        }
+    }
+}
+
+impl<F> fmt::Debug for CustomConnector<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CustomConnector").finish()
+    }
+}
+
+impl<F> CustomConnector<F> {
+    /// Create a connector that opens transport streams with `make_transport`
+    pub fn new<Fut, S>(make_transport: F) -> Self
+    where
+        F: Fn(Uri) -> Fut,
+        Fut: Future<Output = io::Result<S>>,
+        S: AsyncRead + AsyncWrite,
+    {
+        Self {
+            make_transport: Arc::new(make_transport),
+        }
+    }
+}
+
+impl<F, Fut, S> tower::Service<Uri> for CustomConnector<F>
+where
+    F: Fn(Uri) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = io::Result<S>> + Send + 'static,
+    S: AsyncRead + AsyncWrite + Send + Unpin + 'static,
+{
+    type Response = Transport<S>;
+    type Error = io::Error;
+    type Future = BoxFuture<io::Result<Transport<S>>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, uri: Uri) -> Self::Future {
+        let connect = (self.make_transport)(uri);
+        Box::pin(async move { Ok(Transport::new(connect.await?)) })
+    }
+}
+
+/// Connector that sends requests over a Unix domain socket
+#[cfg(unix)]
+#[derive(Clone, Debug)]
+pub struct UnixConnector {
+    path: Arc<std::path::Path>,
+}
+
+#[cfg(unix)]
+impl UnixConnector {
+    /// Create a connector that connects to the Unix domain socket at `path`
+    pub fn new(path: impl AsRef<std::path::Path>) -> Self {
+        Self {
+            path: path.as_ref().into(),
+        }
+    }
+}
+
+#[cfg(unix)]
+impl tower::Service<Uri> for UnixConnector {
+    type Response = Transport<tokio::net::UnixStream>;
+    type Error = io::Error;
+    type Future = BoxFuture<io::Result<Self::Response>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, _uri: Uri) -> Self::Future {
+        let path = self.path.clone();
+        Box::pin(async move { Ok(Transport::new(tokio::net::UnixStream::connect(path).await?)) })
+    }
+}
+
+pin_project! {
+    /// A connection over a transport stream, as returned by the connectors in this module
+    #[derive(Debug)]
+    pub struct Transport<S> {
+        #[pin]
+        inner: S,
+    }
+}
+
+impl<S> Transport<S> {
+    fn new(inner: S) -> Self {
+        Self { inner }
+    }
+
+    /// Returns the underlying transport stream
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S> Connection for Transport<S> {
+    fn connected(&self) -> Connected {
+        Connected::new()
+    }
+}
+
+impl<S: AsyncRead> AsyncRead for Transport<S> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        self.project().inner.poll_read(cx, buf)
+    }
+}
+
+impl<S: AsyncWrite> AsyncWrite for Transport<S> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.project().inner.poll_write(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.project().inner.poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.project().inner.poll_shutdown(cx)
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        self.project().inner.poll_write_vectored(cx, bufs)
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        self.inner.is_write_vectored()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::CustomConnector;
+    use crate::hyper_ext::Adapter;
+    use aws_smithy_http::body::SdkBody;
+    use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+    use tower::Service;
+
+    const RESPONSE: &[u8] = b"HTTP/1.1 200 OK\r\ncontent-length: 5\r\n\r\nhello";
+
+    /// Reads a request without a body and replies with [`RESPONSE`]
+    async fn serve_one(mut stream: impl AsyncRead + AsyncWrite + Unpin) {
+        let mut request = Vec::new();
+        while !request.ends_with(b"\r\n\r\n") {
+            let mut buf = [0; 1024];
+            let read = stream.read(&mut buf).await.unwrap();
+            assert_ne!(0, read, "connection closed before the request was received");
+            request.extend_from_slice(&buf[..read]);
+        }
+        stream.write_all(RESPONSE).await.unwrap();
+    }
+
+    async fn send_request<C>(mut adapter: Adapter<C>) -> String
+    where
+        Adapter<C>: Service<
+            http::Request<SdkBody>,
+            Response = http::Response<SdkBody>,
+            Error = aws_smithy_http::result::ConnectorError,
+        >,
+    {
+        let request = http::Request::builder()
+            .uri("http://localhost/path")
+            .body(SdkBody::empty())
+            .unwrap();
+        let response = adapter.call(request).await.expect("request succeeds");
+        assert_eq!(200, response.status().as_u16());
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        String::from_utf8(body.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn custom_transport() {
+        let connector = CustomConnector::new(|uri: http::Uri| async move {
+            assert_eq!("localhost", uri.host().unwrap());
+            let (client, server) = tokio::io::duplex(1024);
+            tokio::spawn(serve_one(server));
+            Ok(client)
+        });
+        let adapter = Adapter::builder().build(connector);
+        assert_eq!("hello", send_request(adapter).await);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn unix_domain_socket() {
+        use super::UnixConnector;
+        use std::time::{SystemTime, UNIX_EPOCH};
+
+        let path = std::env::temp_dir().join(format!(
+            "smithy-client-{}-{}.sock",
+            std::process::id(),
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        let listener = tokio::net::UnixListener::bind(&path).unwrap();
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            serve_one(stream).await;
+        });
+
+        let adapter = Adapter::builder().build(UnixConnector::new(&path));
+        let body = send_request(adapter).await;
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!("hello", body);
+    }
+}
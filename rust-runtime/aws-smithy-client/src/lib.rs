@@ -13,6 +13,18 @@
 //! | `native-tls`      | Use `native-tls` as the HTTP client's TLS implementation |
 //! | `rustls`          | Use `rustls` as the HTTP client's TLS implementation |
 //! | `client-hyper`    | Use `hyper` to handle HTTP requests |
+//! | `client-fetch`    | On `wasm32` targets, use the JavaScript Fetch API to handle HTTP requests |
+//! | `blocking`        | Provides [`Client::call_blocking`] for synchronous code |
+//!
+//! # Async runtimes
+//!
+//! The client itself is runtime agnostic: it only sleeps (for retry backoff and timeouts) through
+//! the [`AsyncSleep`](aws_smithy_async::rt::sleep::AsyncSleep) provided with
+//! [`Builder::sleep_impl`], and only performs I/O through its connector. The `rt-tokio`,
+//! `rustls`, and `native-tls` features enable the Tokio-based defaults. To run under another
+//! executor (e.g. `async-std`), disable the default features, supply a connector that works with
+//! that executor, and provide a matching sleep implementation. Without a sleep implementation,
+//! requests are still sent, but retries and timeouts are disabled.
 
 #![warn(
     missing_debug_implementations,
@@ -21,9 +33,18 @@
     rust_2018_idioms
 )]
 
+pub mod batch;
+#[cfg(feature = "blocking")]
+pub mod blocking;
 pub mod bounds;
+pub mod circuit_breaker;
+pub mod concurrency_limit;
 pub mod erase;
+pub mod hedge;
+pub mod middleware;
+pub mod rate_limit;
 pub mod retry;
+pub mod service;
 
 // https://github.com/rust-lang/rust/issues/72081
 #[allow(rustdoc::private_doc_tests)]
@@ -40,6 +61,9 @@ pub mod http_connector;
 #[cfg(feature = "client-hyper")]
 pub mod hyper_ext;
 
+#[cfg(all(feature = "client-fetch", target_arch = "wasm32"))]
+pub mod fetch;
+
 // The types in this module are only used to write the bounds in [`Client::check`]. Customers will
 // not need them. But the module and its types must be public so that we can call `check` from
 // doc-tests.
@@ -49,6 +73,7 @@ pub mod static_tests;
 pub mod never;
 pub mod timeout;
 pub use timeout::TimeoutLayer;
+pub mod tls;
 
 /// Type aliases for standard connection types.
 #[cfg(feature = "client-hyper")]
@@ -57,18 +82,64 @@ pub mod conns {
     #[cfg(feature = "rustls")]
     pub type Https = hyper_rustls::HttpsConnector<hyper::client::HttpConnector>;
 
-    // Creating a `with_native_roots` HTTP client takes 300ms on OS X. Cache this so that we
-    // don't need to repeatedly incur that cost.
+    // Loading the native root certificates takes 300ms on OS X. Cache the TLS configuration so that
+    // we don't need to repeatedly incur that cost.
     #[cfg(feature = "rustls")]
     lazy_static::lazy_static! {
-        static ref HTTPS_NATIVE_ROOTS: Https = {
-            hyper_rustls::HttpsConnector::with_native_roots()
+        static ref NATIVE_ROOTS_TLS_CONFIG: std::sync::Arc<tokio_rustls::rustls::ClientConfig> = {
+            let mut config = tokio_rustls::rustls::ClientConfig::new();
+            config.root_store = match rustls_native_certs::load_native_certs() {
+                Ok(store) => store,
+                Err((Some(store), err)) => {
+                    tracing::warn!(err = %err, "could not load all native root certificates");
+                    store
+                }
+                Err((None, err)) => panic!("cannot access the native certificate store: {}", err),
+            };
+            config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+            std::sync::Arc::new(config)
         };
     }
 
     #[cfg(feature = "rustls")]
     pub fn https() -> Https {
-        HTTPS_NATIVE_ROOTS.clone()
+        https_with_tcp_settings(&Default::default())
+    }
+
+    /// An HTTPS connector using Rustls whose TCP connections use `tcp_settings`
+    #[cfg(feature = "rustls")]
+    pub fn https_with_tcp_settings(tcp_settings: &crate::http_connector::TcpSettings) -> Https {
+        hyper_rustls::HttpsConnector::from((
+            crate::hyper_ext::http_connector(tcp_settings),
+            NATIVE_ROOTS_TLS_CONFIG.clone(),
+        ))
+    }
+
+    /// An HTTPS connector using Rustls whose TCP connections use `tcp_settings` and whose TLS
+    /// sessions use `tls_context`
+    #[cfg(feature = "rustls")]
+    pub fn https_with_tls_context(
+        tcp_settings: &crate::http_connector::TcpSettings,
+        tls_context: &crate::tls::TlsContext,
+    ) -> Result<Https, crate::tls::TlsError> {
+        if tls_context == &Default::default() {
+            return Ok(https_with_tcp_settings(tcp_settings));
+        }
+        // only load the native roots if they are trusted
+        let native_roots = if tls_context.trust_store().native_roots() {
+            NATIVE_ROOTS_TLS_CONFIG.root_store.clone()
+        } else {
+            tokio_rustls::rustls::RootCertStore::empty()
+        };
+        let config = tls_context.rustls_config(&native_roots)?;
+        // every connection made without a trusted root certificate would fail to verify the server
+        if config.root_store.is_empty() {
+            return Err(crate::tls::TlsError::no_trusted_certificates());
+        }
+        Ok(hyper_rustls::HttpsConnector::from((
+            crate::hyper_ext::http_connector(tcp_settings),
+            std::sync::Arc::new(config),
+        )))
     }
 
     #[cfg(feature = "native-tls")]
@@ -76,6 +147,30 @@ pub mod conns {
         hyper_tls::HttpsConnector::new()
     }
 
+    /// An HTTPS connector using the platform's native TLS library whose TCP connections use `tcp_settings`
+    #[cfg(feature = "native-tls")]
+    pub fn native_tls_with_tcp_settings(
+        tcp_settings: &crate::http_connector::TcpSettings,
+    ) -> NativeTls {
+        hyper_tls::HttpsConnector::new_with_connector(crate::hyper_ext::http_connector(
+            tcp_settings,
+        ))
+    }
+
+    /// An HTTPS connector using the platform's native TLS library whose TCP connections use
+    /// `tcp_settings` and whose TLS sessions use `tls_context`
+    #[cfg(feature = "native-tls")]
+    pub fn native_tls_with_tls_context(
+        tcp_settings: &crate::http_connector::TcpSettings,
+        tls_context: &crate::tls::TlsContext,
+    ) -> Result<NativeTls, crate::tls::TlsError> {
+        let tls = tls_context.native_tls_connector()?;
+        Ok(hyper_tls::HttpsConnector::from((
+            crate::hyper_ext::http_connector(tcp_settings),
+            tokio_native_tls::TlsConnector::from(tls),
+        )))
+    }
+
     #[cfg(feature = "native-tls")]
     pub type NativeTls = hyper_tls::HttpsConnector<hyper::client::HttpConnector>;
 
@@ -84,20 +179,31 @@ pub mod conns {
         crate::hyper_ext::Adapter<hyper_rustls::HttpsConnector<hyper::client::HttpConnector>>;
 }
 
+use aws_smithy_async::time::{Instant, SystemTimeSource, TimeSource};
+use once_cell::sync::OnceCell;
+use std::any::Any;
 use std::error::Error;
+use std::fmt;
+use std::future::Future;
 use std::sync::Arc;
+use tower::layer::util::Stack;
 use tower::{Layer, Service, ServiceBuilder, ServiceExt};
 
-use crate::timeout::generate_timeout_service_params_from_timeout_config;
+use crate::concurrency_limit::ConcurrencyLimitLayer;
+use crate::hedge::HedgeLayer;
+use crate::rate_limit::RateLimitLayer;
+use crate::timeout::{generate_timeout_service_params_from_timeout_config, TimeoutServiceParams};
 use aws_smithy_async::rt::sleep::AsyncSleep;
 use aws_smithy_http::body::SdkBody;
-use aws_smithy_http::operation::Operation;
+use aws_smithy_http::metrics::{EmitMetrics, SharedMetricsEmitter};
+use aws_smithy_http::operation::{ExpectContinue, InvocationId, Operation};
 use aws_smithy_http::response::ParseHttpResponse;
+use aws_smithy_http::response::{ParseOffload, ResponseLimits};
 pub use aws_smithy_http::result::{SdkError, SdkSuccess};
-use aws_smithy_http::retry::ClassifyResponse;
-use aws_smithy_http_tower::dispatch::DispatchLayer;
+use aws_smithy_http::retry::{AttemptLimits, ClassifyResponse};
+use aws_smithy_http_tower::dispatch::{DispatchLayer, ExpectContinueSleep};
 use aws_smithy_http_tower::parse_response::ParseResponseLayer;
-use aws_smithy_types::retry::ProvideErrorKind;
+use aws_smithy_types::retry::{ProvideErrorKind, RetryConfig};
 use aws_smithy_types::tristate::TriState;
 
 /// Smithy service client.
@@ -114,12 +220,18 @@ use aws_smithy_types::tristate::TriState;
 /// [`aws_smithy_http::operation::Request`] and return responses of the type
 /// [`http::Response<SdkBody>`], most likely by modifying the provided request in place, passing it
 /// to the inner service, and then ultimately returning the inner service's response.
+/// Services that don't need AWS endpoint resolution or SigV4 signing can use
+/// [`middleware::DefaultSmithyMiddleware`], which only applies a fixed endpoint and adds a user agent.
 ///
 /// With the `hyper` feature enabled, you can construct a `Client` directly from a
 /// [`hyper::Client`] using [`hyper_ext::Adapter::builder`]. You can also enable the `rustls` or `native-tls`
 /// features to construct a Client against a standard HTTPS endpoint using [`Builder::rustls`] and
 /// `Builder::native_tls` respectively.
-#[derive(Debug)]
+///
+/// Cloning a `Client` is cheap as long as its connector and middleware are: the service that the
+/// middleware builds around the connector is constructed once, on first use, and is shared by all
+/// clones of the client.
+#[derive(Debug, Clone)]
 pub struct Client<
     Connector = erase::DynConnector,
     Middleware = erase::DynMiddleware<Connector>,
@@ -130,6 +242,44 @@ pub struct Client<
     retry_policy: RetryPolicy,
     timeout_config: aws_smithy_types::timeout::Config,
     sleep_impl: TriState<Arc<dyn AsyncSleep>>,
+    metrics_emitter: Option<SharedMetricsEmitter>,
+    response_limits: Option<ResponseLimits>,
+    parse_offload: Option<ParseOffload>,
+    rate_limiter: Option<rate_limit::RateLimiter>,
+    concurrency_limit: Option<concurrency_limit::ConcurrencyLimit>,
+    stack: SharedStack,
+}
+
+/// The service that a client's middleware builds around its connector.
+///
+/// Building it can allocate (for example, when the middleware is an [`erase::DynMiddleware`]), so
+/// it is built on first use and then cloned for each request. The stack's type depends only on
+/// the client's connector and middleware, so it is stored type-erased to keep those bounds off
+/// of [`Client`] itself.
+#[derive(Clone, Default)]
+struct SharedStack(Arc<OnceCell<Box<dyn Any + Send + Sync>>>);
+
+impl SharedStack {
+    fn get_or_init<S>(&self, init: impl FnOnce() -> S) -> S
+    where
+        S: Clone + Send + Sync + 'static,
+    {
+        if let Some(stack) = self.0.get().and_then(|stack| stack.downcast_ref::<S>()) {
+            return stack.clone();
+        }
+        let stack = init();
+        // If another request won the race to initialize the stack, ours is used just this once
+        let _ = self.0.set(Box::new(stack.clone()));
+        stack
+    }
+}
+
+impl fmt::Debug for SharedStack {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SharedStack")
+            .field("initialized", &self.0.get().is_some())
+            .finish()
+    }
 }
 
 // Quick-create for people who just want "the default".
@@ -189,12 +339,140 @@ impl<C, M, R> Client<C, M, R> {
         self.set_sleep_impl(Some(sleep_impl));
         self
     }
+
+    /// Set the [`EmitMetrics`] implementation that the client will report per-attempt metrics to.
+    ///
+    /// *Note: If `None` is passed, no metrics will be emitted.*
+    pub fn set_metrics_emitter(&mut self, emitter: Option<SharedMetricsEmitter>) {
+        self.metrics_emitter = emitter;
+    }
+
+    /// Set the [`EmitMetrics`] implementation that the client will report per-attempt metrics to.
+    pub fn with_metrics_emitter(mut self, emitter: impl EmitMetrics + 'static) -> Self {
+        self.set_metrics_emitter(Some(SharedMetricsEmitter::new(emitter)));
+        self
+    }
+
+    /// Set the [`ResponseLimits`] the client applies when reading response bodies into memory.
+    ///
+    /// *Note: Limits set on an individual operation take precedence over these.*
+    pub fn set_response_limits(&mut self, limits: Option<ResponseLimits>) {
+        self.response_limits = limits;
+    }
+
+    /// Set the [`ResponseLimits`] the client applies when reading response bodies into memory.
+    pub fn with_response_limits(mut self, limits: ResponseLimits) -> Self {
+        self.set_response_limits(Some(limits));
+        self
+    }
+
+    /// Set the [`ParseOffload`] the client uses to parse large responses without stalling
+    /// the async runtime.
+    ///
+    /// *Note: A `ParseOffload` set on an individual operation takes precedence over this.*
+    pub fn set_parse_offload(&mut self, offload: Option<ParseOffload>) {
+        self.parse_offload = offload;
+    }
+
+    /// Set the [`ParseOffload`] the client uses to parse large responses without stalling
+    /// the async runtime.
+    pub fn with_parse_offload(mut self, offload: ParseOffload) -> Self {
+        self.set_parse_offload(Some(offload));
+        self
+    }
+
+    /// Set the [`RateLimiter`](rate_limit::RateLimiter) that limits the rate of requests the
+    /// client sends.
+    ///
+    /// *Note: If `None` is passed, requests will not be rate limited.*
+    pub fn set_rate_limiter(&mut self, rate_limiter: Option<rate_limit::RateLimiter>) {
+        self.rate_limiter = rate_limiter;
+    }
+
+    /// Set the [`RateLimiter`](rate_limit::RateLimiter) that limits the rate of requests the
+    /// client sends.
+    pub fn with_rate_limiter(mut self, rate_limiter: rate_limit::RateLimiter) -> Self {
+        self.set_rate_limiter(Some(rate_limiter));
+        self
+    }
+
+    /// Set the [`ConcurrencyLimit`](concurrency_limit::ConcurrencyLimit) on the number of
+    /// requests the client has in flight.
+    ///
+    /// *Note: If `None` is passed, the number of requests in flight will not be limited.*
+    pub fn set_concurrency_limit(&mut self, limit: Option<concurrency_limit::ConcurrencyLimit>) {
+        self.concurrency_limit = limit;
+    }
+
+    /// Set the [`ConcurrencyLimit`](concurrency_limit::ConcurrencyLimit) on the number of
+    /// requests the client has in flight.
+    pub fn with_concurrency_limit(mut self, limit: concurrency_limit::ConcurrencyLimit) -> Self {
+        self.set_concurrency_limit(Some(limit));
+        self
+    }
+
+    /// Use a middleware that wraps the current middleware.
+    pub fn map_middleware<F, M2>(self, map: F) -> Client<C, M2, R>
+    where
+        F: FnOnce(M) -> M2,
+    {
+        Client {
+            connector: self.connector,
+            middleware: map(self.middleware),
+            retry_policy: self.retry_policy,
+            timeout_config: self.timeout_config,
+            sleep_impl: self.sleep_impl,
+            metrics_emitter: self.metrics_emitter,
+            response_limits: self.response_limits,
+            parse_offload: self.parse_offload,
+            rate_limiter: self.rate_limiter,
+            concurrency_limit: self.concurrency_limit,
+            stack: Default::default(),
+        }
+    }
+
+    /// Add a layer that runs _before_ the client's current middleware.
+    ///
+    /// See [`Builder::layer`] for details.
+    pub fn layer<L>(self, layer: L) -> Client<C, Stack<M, L>, R> {
+        self.map_middleware(|middleware| Stack::new(middleware, layer))
+    }
+
+    /// Add a layer that runs _after_ the client's current middleware, right before dispatch.
+    ///
+    /// See [`Builder::inner_layer`] for details.
+    pub fn inner_layer<L>(self, layer: L) -> Client<C, Stack<L, M>, R> {
+        self.map_middleware(|middleware| Stack::new(layer, middleware))
+    }
+
+    /// Convert this client into a [`tower::Service`] that sends [`Operation`]s.
+    ///
+    /// See the [`service`] module for details.
+    pub fn into_service(self) -> service::ClientService<C, M, R> {
+        service::ClientService::new(self)
+    }
 }
 
 fn check_send_sync<T: Send + Sync>(t: T) -> T {
     t
 }
 
+/// Generate a random (version 4) UUID to identify an invocation of an operation
+fn new_invocation_id() -> InvocationId {
+    let mut uuid = fastrand::u128(..);
+    // Set the version (4) and variant (RFC 4122) bits
+    uuid = (uuid & !(0xf << 76)) | (0x4 << 76);
+    uuid = (uuid & !(0x3 << 62)) | (0x2 << 62);
+    InvocationId::new(format!(
+        "{:08x}-{:04x}-{:04x}-{:04x}-{:012x}",
+        uuid >> 96,
+        (uuid >> 80) & 0xffff,
+        (uuid >> 64) & 0xffff,
+        (uuid >> 48) & 0xffff,
+        uuid & 0xffff_ffff_ffff
+    ))
+}
+
 impl<C, M, R> Client<C, M, R>
 where
     C: bounds::SmithyConnector,
@@ -205,7 +483,12 @@ where
     ///
     /// For ergonomics, this does not include the raw response for successful responses. To
     /// access the raw response use `call_raw`.
-    pub async fn call<O, T, E, Retry>(&self, input: Operation<O, Retry>) -> Result<T, SdkError<E>>
+    ///
+    /// The returned future does not borrow the client, so it can be moved onto any executor.
+    pub fn call<O, T, E, Retry>(
+        &self,
+        input: Operation<O, Retry>,
+    ) -> impl Future<Output = Result<T, SdkError<E>>>
     where
         O: Send + Sync,
         Retry: Send + Sync,
@@ -213,17 +496,92 @@ where
         bounds::Parsed<<M as bounds::SmithyMiddleware<C>>::Service, O, Retry>:
             Service<Operation<O, Retry>, Response = SdkSuccess<T>, Error = SdkError<E>> + Clone,
     {
-        self.call_raw(input).await.map(|res| res.parsed)
+        let response = self.call_raw(input);
+        async move { response.await.map(|res| res.parsed) }
+    }
+
+    /// Dispatch many requests to the network, with at most `concurrency` of them in flight
+    ///
+    /// Results are yielded in the same order as `inputs`, and a failed request doesn't stop the
+    /// rest from being sent. Operations are only turned into requests as capacity frees up, so
+    /// `inputs` can be a long (or lazy) iterator. Once the stream ends,
+    /// [`CallAll::summary`](batch::CallAll::summary) reports which operations failed.
+    ///
+    /// A `concurrency` of zero is treated as one.
+    pub fn call_all<'a, I, O, T, E, Retry>(
+        &'a self,
+        inputs: I,
+        concurrency: usize,
+    ) -> batch::CallAll<impl futures_util::stream::Stream<Item = Result<T, SdkError<E>>> + 'a>
+    where
+        I: IntoIterator<Item = Operation<O, Retry>>,
+        I::IntoIter: 'a,
+        O: Send + Sync + 'a,
+        Retry: Send + Sync + 'a,
+        T: 'a,
+        E: 'a,
+        R::Policy: bounds::SmithyRetryPolicy<O, T, E, Retry>,
+        bounds::Parsed<<M as bounds::SmithyMiddleware<C>>::Service, O, Retry>:
+            Service<Operation<O, Retry>, Response = SdkSuccess<T>, Error = SdkError<E>> + Clone,
+    {
+        use futures_util::StreamExt;
+        let requests = futures_util::stream::iter(inputs).map(move |input| self.call(input));
+        batch::CallAll::new(requests.buffered(concurrency.max(1)))
+    }
+
+    /// Dispatch this request to the network, failing with [`SdkError::TimeoutError`] if it has
+    /// not completed by `deadline`.
+    ///
+    /// The deadline covers all attempts, including the time spent sleeping between retries. When
+    /// it passes, or when the returned future is dropped, the in-flight request and any pending
+    /// retry sleep are dropped with it; no work continues in the background.
+    ///
+    /// Enforcing the deadline requires a sleep implementation (see [`Builder::sleep_impl`]).
+    /// Without one, the call fails with [`SdkError::ConstructionFailure`] before anything is sent.
+    pub fn call_with_deadline<O, T, E, Retry>(
+        &self,
+        input: Operation<O, Retry>,
+        deadline: Instant,
+    ) -> impl Future<Output = Result<T, SdkError<E>>>
+    where
+        O: Send + Sync,
+        Retry: Send + Sync,
+        R::Policy: bounds::SmithyRetryPolicy<O, T, E, Retry>,
+        bounds::Parsed<<M as bounds::SmithyMiddleware<C>>::Service, O, Retry>:
+            Service<Operation<O, Retry>, Response = SdkSuccess<T>, Error = SdkError<E>> + Clone,
+    {
+        let response = self.dispatch(input, Some(deadline));
+        async move { response.await.map(|res| res.parsed) }
     }
 
     /// Dispatch this request to the network
     ///
     /// The returned result contains the raw HTTP response which can be useful for debugging or
     /// implementing unsupported features.
-    pub async fn call_raw<O, T, E, Retry>(
+    ///
+    /// The service stack is assembled up front, so the returned future does not borrow the
+    /// client. All sleeping (for retry backoff and timeouts) goes through the client's
+    /// [`AsyncSleep`] implementation, which keeps the future independent of any particular
+    /// async runtime.
+    pub fn call_raw<O, T, E, Retry>(
         &self,
         input: Operation<O, Retry>,
-    ) -> Result<SdkSuccess<T>, SdkError<E>>
+    ) -> impl Future<Output = Result<SdkSuccess<T>, SdkError<E>>>
+    where
+        O: Send + Sync,
+        Retry: Send + Sync,
+        R::Policy: bounds::SmithyRetryPolicy<O, T, E, Retry>,
+        bounds::Parsed<<M as bounds::SmithyMiddleware<C>>::Service, O, Retry>:
+            Service<Operation<O, Retry>, Response = SdkSuccess<T>, Error = SdkError<E>> + Clone,
+    {
+        self.dispatch(input, None)
+    }
+
+    fn dispatch<O, T, E, Retry>(
+        &self,
+        mut input: Operation<O, Retry>,
+        deadline: Option<Instant>,
+    ) -> impl Future<Output = Result<SdkSuccess<T>, SdkError<E>>>
     where
         O: Send + Sync,
         Retry: Send + Sync,
@@ -245,28 +603,114 @@ where
                 MISSING_SLEEP_IMPL_RECOMMENDATION
             );
         }
-        let connector = self.connector.clone();
-
+        if let Some(metrics_emitter) = &self.metrics_emitter {
+            input.properties_mut().insert(metrics_emitter.clone());
+        }
+        {
+            let mut properties = input.properties_mut();
+            if properties.get::<InvocationId>().is_none() {
+                properties.insert(new_invocation_id());
+            }
+            let max_attempts = properties
+                .get::<RetryConfig>()
+                .map(|config| config.max_attempts())
+                .or_else(|| self.retry_policy.max_attempts());
+            let mut limits = AttemptLimits::new();
+            if let Some(max_attempts) = max_attempts {
+                limits = limits.with_max_attempts(max_attempts);
+            }
+            if let Some(deadline) = deadline {
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                limits = limits.with_deadline(SystemTimeSource::new().now() + remaining);
+            }
+            properties.insert(limits);
+        }
+        if let Some(limits) = self.response_limits {
+            let mut properties = input.properties_mut();
+            if properties.get::<ResponseLimits>().is_none() {
+                properties.insert(limits);
+            }
+        }
+        if let Some(offload) = &self.parse_offload {
+            let mut properties = input.properties_mut();
+            if properties.get::<ParseOffload>().is_none() {
+                properties.insert(offload.clone());
+            }
+        }
+        if let Some(sleep_impl) = Option::<Arc<dyn AsyncSleep>>::from(self.sleep_impl.clone()) {
+            let mut properties = input.properties_mut();
+            if properties.get::<ExpectContinue>().is_some()
+                && properties.get::<ExpectContinueSleep>().is_none()
+            {
+                properties.insert(ExpectContinueSleep::new(sleep_impl));
+            }
+        }
+        let api_timeouts = match input.properties().get::<aws_smithy_types::timeout::Api>() {
+            Some(timeouts) => timeouts
+                .clone()
+                .take_unset_from(self.timeout_config.api.clone()),
+            None => self.timeout_config.api.clone(),
+        };
         let timeout_service_params = generate_timeout_service_params_from_timeout_config(
-            &self.timeout_config.api,
+            &api_timeouts,
             self.sleep_impl.clone().into(),
         );
 
+        let sleep_impl: Option<Arc<dyn AsyncSleep>> = self.sleep_impl.clone().into();
+        let (deadline, deadline_error) = match (deadline, sleep_impl) {
+            (None, _) => (None, None),
+            (Some(deadline), Some(sleep_impl)) => (
+                Some(TimeoutServiceParams::deadline(deadline, sleep_impl)),
+                None,
+            ),
+            (Some(_), None) => (
+                None,
+                Some(SdkError::ConstructionFailure(
+                    "a sleep implementation is required to enforce a deadline".into(),
+                )),
+            ),
+        };
+
+        let retry_policy = self
+            .retry_policy
+            .new_request_policy(self.sleep_impl.clone().into());
+        let hedge = HedgeLayer::new(
+            retry_policy.clone(),
+            self.sleep_impl.clone().into(),
+            self.retry_policy
+                .retry_quota(retry::RetryPartition::for_operation(&input).as_ref()),
+        );
         let svc = ServiceBuilder::new()
+            .layer(TimeoutLayer::new(deadline))
             .layer(TimeoutLayer::new(timeout_service_params.api_call))
-            .retry(
-                self.retry_policy
-                    .new_request_policy(self.sleep_impl.clone().into()),
+            .retry(retry_policy)
+            .layer(hedge)
+            .layer(RateLimitLayer::new(
+                self.rate_limiter.clone(),
+                self.sleep_impl.clone().into(),
+            ))
+            .layer(
+                ConcurrencyLimitLayer::new(self.concurrency_limit.clone())
+                    .with_metrics_emitter(self.metrics_emitter.clone()),
             )
             .layer(TimeoutLayer::new(timeout_service_params.api_call_attempt))
             .layer(ParseResponseLayer::<O, Retry>::new())
-            // These layers can be considered as occurring in order. That is, first invoke the
-            // customer-provided middleware, then dispatch dispatch over the wire.
-            .layer(&self.middleware)
-            .layer(DispatchLayer::new())
-            .service(connector);
-
-        check_send_sync(svc).ready().await?.call(input).await
+            .service(self.stack.get_or_init(|| {
+                // These layers can be considered as occurring in order. That is, first invoke the
+                // customer-provided middleware, then dispatch dispatch over the wire.
+                ServiceBuilder::new()
+                    .layer(&self.middleware)
+                    .layer(DispatchLayer::new())
+                    .service(self.connector.clone())
+            }));
+
+        let mut svc = check_send_sync(svc);
+        async move {
+            if let Some(err) = deadline_error {
+                return Err(err);
+            }
+            svc.ready().await?.call(input).await
+        }
     }
 
     /// Statically check the validity of a `Client` without a request to send.
@@ -289,6 +733,51 @@ where
     }
 }
 
+impl<C, M, R> Client<C, M, R>
+where
+    C: bounds::SmithyConnector,
+{
+    /// Open `connections` connections to `endpoint` before traffic starts, so that the first
+    /// requests don't pay for connection setup and the TLS handshake
+    ///
+    /// Connectors only open connections to send requests, so this sends `connections` concurrent
+    /// `HEAD` requests to `endpoint` directly through the connector, bypassing the middleware and
+    /// retries. Connectors that pool connections, like the
+    /// [Hyper adapter](crate::hyper_ext::Adapter), then keep them open for the following requests.
+    ///
+    /// # Side effects
+    ///
+    /// The `HEAD` requests are real HTTP requests to the service:
+    /// - they are not signed, so most AWS services reject them, typically with a `403`. These
+    ///   responses appear in the service's access logs and in its error metrics.
+    /// - a connection is only pooled if the server keeps it open after responding. Servers that
+    ///   close the connection after an error response (`Connection: close`) leave nothing in the
+    ///   pool, and the following requests still pay for connection setup.
+    /// - requests over HTTP/2 share a single connection.
+    ///
+    /// Use an endpoint that answers unauthenticated requests without closing the connection, such
+    /// as a health check path, when one is available.
+    ///
+    /// Returns the first error that prevented a connection from being established. An error
+    /// status is not an error: any response means a connection was established.
+    pub async fn prewarm(
+        &self,
+        endpoint: &http::Uri,
+        connections: usize,
+    ) -> Result<(), aws_smithy_http::result::ConnectorError> {
+        let requests = (0..connections).map(|_| {
+            let request = http::Request::head(endpoint.clone())
+                .body(SdkBody::empty())
+                .expect("valid request");
+            self.connector.clone().oneshot(request)
+        });
+        for result in futures_util::future::join_all(requests).await {
+            result.map_err(Into::into)?;
+        }
+        Ok(())
+    }
+}
+
 pub(crate) const MISSING_SLEEP_IMPL_RECOMMENDATION: &str =
     "If this was intentional, you can suppress this message with `Client::set_sleep_impl(None). \
      Otherwise, unless you have a good reason to use the low-level service \
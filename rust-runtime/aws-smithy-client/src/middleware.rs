@@ -0,0 +1,252 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Middleware for clients of Smithy services that aren't AWS services.
+//!
+//! AWS clients use a middleware stack that resolves AWS endpoints and signs requests with SigV4.
+//! Other Smithy services need neither, so [`DefaultSmithyMiddleware`] only applies a fixed
+//! endpoint and adds a user agent. Retries and timeouts are provided by the
+//! [`Client`](crate::Client) itself, so a client built with `DefaultSmithyMiddleware` retries and
+//! times out requests like any other:
+//!
+//! ```no_run
+//! use aws_smithy_client::erase::DynConnector;
+//! use aws_smithy_client::middleware::DefaultSmithyMiddleware;
+//! use aws_smithy_client::Client;
+//!
+//! # fn connector() -> DynConnector { unimplemented!() }
+//! let client = Client::<DynConnector, DefaultSmithyMiddleware>::new(connector());
+//! # client.check();
+//! ```
+//!
+//! Without an endpoint, requests must already have an absolute URI.
+
+use aws_smithy_http::endpoint::{Endpoint, EndpointPrefix};
+use aws_smithy_http::middleware::MapRequest;
+use aws_smithy_http::operation::Request;
+use aws_smithy_http::pipeline::{PipelineConfig, Stage};
+use aws_smithy_http_tower::map_request::MapRequestLayer;
+use http::header::USER_AGENT;
+use http::HeaderValue;
+use std::convert::Infallible;
+use tower::layer::util::{Identity, Stack};
+use tower::ServiceBuilder;
+
+/// The user agent sent by [`DefaultSmithyMiddleware`] unless another is configured
+pub const DEFAULT_USER_AGENT: &str = concat!("smithy-rs/", env!("CARGO_PKG_VERSION"), " lang/rust");
+
+type DefaultSmithyMiddlewareStack = Stack<
+    MapRequestLayer<SmithyUserAgentStage>,
+    Stack<MapRequestLayer<SmithyEndpointStage>, Identity>,
+>;
+
+/// Middleware stack for non-AWS Smithy services
+///
+/// This applies the endpoint to each request and adds a `User-Agent` header. Unlike the AWS
+/// middleware, it doesn't resolve the endpoint from a region or sign requests.
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+pub struct DefaultSmithyMiddleware {
+    endpoint: SmithyEndpointStage,
+    user_agent: SmithyUserAgentStage,
+}
+
+impl DefaultSmithyMiddleware {
+    /// Creates a new `DefaultSmithyMiddleware` stack that sends [`DEFAULT_USER_AGENT`] and no endpoint
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sends requests to `endpoint`
+    pub fn with_endpoint(mut self, endpoint: Endpoint) -> Self {
+        self.endpoint = SmithyEndpointStage::new(Some(endpoint));
+        self
+    }
+
+    /// Sends `user_agent` as the `User-Agent` header instead of [`DEFAULT_USER_AGENT`]
+    pub fn with_user_agent(mut self, user_agent: HeaderValue) -> Self {
+        self.user_agent = SmithyUserAgentStage::new(user_agent);
+        self
+    }
+}
+
+// define the middleware stack in a non-generic location to reduce code bloat.
+fn base(
+    endpoint: SmithyEndpointStage,
+    user_agent: SmithyUserAgentStage,
+) -> ServiceBuilder<DefaultSmithyMiddlewareStack> {
+    ServiceBuilder::new()
+        .layer(MapRequestLayer::for_mapper(endpoint))
+        .layer(MapRequestLayer::for_mapper(user_agent))
+}
+
+impl<S> tower::Layer<S> for DefaultSmithyMiddleware {
+    type Service = <DefaultSmithyMiddlewareStack as tower::Layer<S>>::Service;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        base(self.endpoint.clone(), self.user_agent.clone()).service(inner)
+    }
+}
+
+/// Middleware stage that applies an [`Endpoint`] to the request URI
+///
+/// An `Endpoint` in the operation's property bag takes precedence over the one the stage was
+/// created with. The operation's [`EndpointPrefix`] (from the `@endpoint` trait) is added to the
+/// host. Without an endpoint, or if the operation's [`PipelineConfig`] skips [`Stage::Endpoint`],
+/// the URI is left as-is.
+#[derive(Clone, Debug, Default)]
+pub struct SmithyEndpointStage {
+    endpoint: Option<Endpoint>,
+}
+
+impl SmithyEndpointStage {
+    /// Creates a stage that sends requests to `endpoint`
+    pub fn new(endpoint: Option<Endpoint>) -> Self {
+        Self { endpoint }
+    }
+}
+
+impl MapRequest for SmithyEndpointStage {
+    type Error = Infallible;
+
+    fn apply(&self, request: Request) -> Result<Request, Self::Error> {
+        request.augment(|mut req, properties| {
+            if PipelineConfig::skips(properties, Stage::Endpoint) {
+                return Ok(req);
+            }
+            if let Some(endpoint) = properties.get::<Endpoint>().or(self.endpoint.as_ref()) {
+                endpoint.set_endpoint(req.uri_mut(), properties.get::<EndpointPrefix>());
+            }
+            Ok(req)
+        })
+    }
+
+    fn name(&self) -> &'static str {
+        "endpoint"
+    }
+}
+
+/// Middleware stage that sets the `User-Agent` header
+///
+/// A `User-Agent` header already on the request is left as-is. The stage does nothing if the
+/// operation's [`PipelineConfig`] skips [`Stage::UserAgent`].
+#[derive(Clone, Debug)]
+pub struct SmithyUserAgentStage {
+    user_agent: HeaderValue,
+}
+
+impl SmithyUserAgentStage {
+    /// Creates a stage that sends `user_agent` as the `User-Agent` header
+    pub fn new(user_agent: HeaderValue) -> Self {
+        Self { user_agent }
+    }
+}
+
+impl Default for SmithyUserAgentStage {
+    fn default() -> Self {
+        Self::new(HeaderValue::from_static(DEFAULT_USER_AGENT))
+    }
+}
+
+impl MapRequest for SmithyUserAgentStage {
+    type Error = Infallible;
+
+    fn apply(&self, request: Request) -> Result<Request, Self::Error> {
+        request.augment(|mut req, properties| {
+            if !PipelineConfig::skips(properties, Stage::UserAgent) {
+                req.headers_mut()
+                    .entry(USER_AGENT)
+                    .or_insert_with(|| self.user_agent.clone());
+            }
+            Ok(req)
+        })
+    }
+
+    fn name(&self) -> &'static str {
+        "user_agent"
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::middleware::{SmithyEndpointStage, SmithyUserAgentStage, DEFAULT_USER_AGENT};
+    use aws_smithy_http::body::SdkBody;
+    use aws_smithy_http::endpoint::{Endpoint, EndpointPrefix};
+    use aws_smithy_http::middleware::MapRequest;
+    use aws_smithy_http::operation;
+    use aws_smithy_http::pipeline::{PipelineConfig, Stage};
+    use http::header::USER_AGENT;
+    use http::HeaderValue;
+
+    fn request() -> operation::Request {
+        operation::Request::new(http::Request::new(SdkBody::empty()))
+    }
+
+    #[test]
+    fn default_user_agent_is_added() {
+        let req = SmithyUserAgentStage::default().apply(request()).unwrap();
+        assert_eq!(req.http().headers()[USER_AGENT], DEFAULT_USER_AGENT);
+    }
+
+    #[test]
+    fn existing_user_agent_is_kept() {
+        let mut req = request();
+        req.http_mut()
+            .headers_mut()
+            .insert(USER_AGENT, HeaderValue::from_static("my-app/1.0"));
+        let req = SmithyUserAgentStage::new(HeaderValue::from_static("other"))
+            .apply(req)
+            .unwrap();
+        assert_eq!(req.http().headers()[USER_AGENT], "my-app/1.0");
+    }
+
+    #[test]
+    fn skipped_stage_adds_no_user_agent() {
+        let mut req = request();
+        PipelineConfig::new()
+            .skip(Stage::UserAgent)
+            .apply(&mut req.properties_mut());
+        let req = SmithyUserAgentStage::default().apply(req).unwrap();
+        assert!(req.http().headers().get(USER_AGENT).is_none());
+    }
+
+    #[test]
+    fn endpoint_is_applied_with_prefix() {
+        let stage = SmithyEndpointStage::new(Some(Endpoint::mutable(
+            "https://example.com/base".parse().unwrap(),
+        )));
+        let mut req = request();
+        *req.http_mut().uri_mut() = "/operation?x=1".parse().unwrap();
+        let req = stage.apply(req).unwrap();
+        assert_eq!(req.http().uri(), "https://example.com/base/operation?x=1");
+
+        let mut req = request();
+        req.properties_mut()
+            .insert(EndpointPrefix::new("data.").unwrap());
+        let req = stage.apply(req).unwrap();
+        assert_eq!(req.http().uri(), "https://data.example.com/base/");
+    }
+
+    #[test]
+    fn operation_endpoint_takes_precedence() {
+        let stage = SmithyEndpointStage::new(Some(Endpoint::mutable(
+            "https://example.com".parse().unwrap(),
+        )));
+        let mut req = request();
+        req.properties_mut().insert(Endpoint::immutable(
+            "http://localhost:8080".parse().unwrap(),
+        ));
+        let req = stage.apply(req).unwrap();
+        assert_eq!(req.http().uri(), "http://localhost:8080/");
+    }
+
+    #[test]
+    fn no_endpoint_keeps_the_uri() {
+        let mut req = request();
+        *req.http_mut().uri_mut() = "https://example.com/operation".parse().unwrap();
+        let req = SmithyEndpointStage::default().apply(req).unwrap();
+        assert_eq!(req.http().uri(), "https://example.com/operation");
+    }
+}
@@ -0,0 +1,624 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Client-side rate limiting
+//!
+//! A [`RateLimiter`] caps the rate at which a [`Client`](crate::Client) sends requests, which
+//! can be used to stay below a service quota. Limits can be set for all requests made by the
+//! client, and for individual operations by name. Every attempt, including retries, counts
+//! towards the limits.
+//!
+//! ```no_run
+//! use aws_smithy_client::rate_limit::RateLimiter;
+//!
+//! let rate_limiter = RateLimiter::new()
+//!     .with_requests_per_second(100.0)
+//!     .with_operation_requests_per_second("PutObject", 10.0);
+//! ```
+//!
+//! Limits are enforced with token buckets that allow bursts of up to one second's worth of
+//! requests. By default, requests over the limit wait until they are permitted, which requires
+//! the client to have a sleep implementation. With [`RateLimiter::fail_fast`], they fail
+//! immediately with a [`RateLimitExceeded`] error instead. Requests that are cancelled while
+//! waiting don't count towards the limits.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, SystemTime};
+
+use crate::SdkError;
+use aws_smithy_async::rt::sleep::{AsyncSleep, Sleep};
+use aws_smithy_async::time::{SharedTimeSource, TimeSource};
+use aws_smithy_http::operation::Operation;
+use pin_project_lite::pin_project;
+use tower::Layer;
+
+/// Error returned when a [fail-fast](RateLimiter::fail_fast) rate limit is exceeded
+#[non_exhaustive]
+#[derive(Debug)]
+pub struct RateLimitExceeded;
+
+impl std::fmt::Display for RateLimitExceeded {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "request was not sent because the client rate limit was exceeded"
+        )
+    }
+}
+
+impl std::error::Error for RateLimitExceeded {}
+
+#[derive(Debug)]
+struct TokenBucket {
+    rate: f64,
+    capacity: f64,
+    tokens: f64,
+    last_refill: Option<SystemTime>,
+}
+
+impl TokenBucket {
+    fn new(rate: f64) -> Self {
+        assert!(
+            rate.is_finite() && rate > 0.0,
+            "the rate limit must be a positive number of requests per second, but was {}",
+            rate
+        );
+        let capacity = rate.max(1.0);
+        Self {
+            rate,
+            capacity,
+            tokens: capacity,
+            last_refill: None,
+        }
+    }
+
+    fn refill(&mut self, now: SystemTime) {
+        if let Some(last_refill) = self.last_refill {
+            let elapsed = now.duration_since(last_refill).unwrap_or_default();
+            self.tokens = (self.tokens + elapsed.as_secs_f64() * self.rate).min(self.capacity);
+        }
+        self.last_refill = Some(now);
+    }
+
+    /// Takes a token, returning how long to wait before the token may be used
+    ///
+    /// Tokens may be taken before they are available, so that waiting requests are permitted in
+    /// the order they arrived.
+    fn reserve(&mut self) -> Duration {
+        self.tokens -= 1.0;
+        if self.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-self.tokens / self.rate)
+        }
+    }
+
+    /// Returns a token taken for a request that wasn't sent
+    fn refund(&mut self) {
+        self.tokens = (self.tokens + 1.0).min(self.capacity);
+    }
+}
+
+/// The tokens taken for a request that hasn't been sent yet
+///
+/// Dropping the reservation returns the tokens to their buckets, so that a request that is
+/// cancelled while waiting for its permit doesn't use up the limit.
+#[derive(Debug)]
+struct Reservation(Vec<Arc<Mutex<TokenBucket>>>);
+
+impl Reservation {
+    /// Keeps the tokens, as the request has been sent
+    fn sent(mut self) {
+        self.0.clear();
+    }
+}
+
+impl Drop for Reservation {
+    fn drop(&mut self) {
+        for bucket in &self.0 {
+            bucket.lock().unwrap().refund();
+        }
+    }
+}
+
+/// Limits the rate of requests sent by a client
+///
+/// Clones share the same limits. See the [module documentation](crate::rate_limit) for details.
+#[derive(Clone, Debug)]
+pub struct RateLimiter {
+    fail_fast: bool,
+    time_source: SharedTimeSource,
+    client: Option<Arc<Mutex<TokenBucket>>>,
+    operations: HashMap<String, Arc<Mutex<TokenBucket>>>,
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RateLimiter {
+    /// Creates a rate limiter with no limits
+    pub fn new() -> Self {
+        Self {
+            fail_fast: false,
+            time_source: SharedTimeSource::default(),
+            client: None,
+            operations: HashMap::new(),
+        }
+    }
+
+    /// Limit the rate of all requests sent by the client
+    ///
+    /// # Panics
+    ///
+    /// Panics if `rate` is not a finite number greater than zero.
+    pub fn with_requests_per_second(mut self, rate: f64) -> Self {
+        self.client = Some(Arc::new(Mutex::new(TokenBucket::new(rate))));
+        self
+    }
+
+    /// Limit the rate of requests for the operation named `operation`
+    ///
+    /// This applies in addition to the limit set with
+    /// [`with_requests_per_second`](RateLimiter::with_requests_per_second).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `rate` is not a finite number greater than zero.
+    pub fn with_operation_requests_per_second(
+        mut self,
+        operation: impl Into<String>,
+        rate: f64,
+    ) -> Self {
+        self.operations.insert(
+            operation.into(),
+            Arc::new(Mutex::new(TokenBucket::new(rate))),
+        );
+        self
+    }
+
+    /// Fail requests over the limit with [`RateLimitExceeded`] rather than waiting
+    pub fn fail_fast(mut self) -> Self {
+        self.fail_fast = true;
+        self
+    }
+
+    /// Use `time_source` to measure the rate of requests
+    pub fn with_time_source(mut self, time_source: impl TimeSource + 'static) -> Self {
+        self.time_source = SharedTimeSource::new(time_source);
+        self
+    }
+
+    /// Returns how long a request for `operation` must wait before being sent, along with the
+    /// tokens it took, or `None` if it can't be sent because the limiter is fail-fast.
+    fn acquire(&self, operation: Option<&str>) -> Option<(Duration, Reservation)> {
+        let operation = operation.and_then(|name| self.operations.get(name));
+        // The client bucket is always locked first so that concurrent requests can't deadlock
+        let reserved: Vec<_> = self.client.iter().chain(operation).cloned().collect();
+        let wait = {
+            let mut buckets: Vec<_> = reserved
+                .iter()
+                .map(|bucket| bucket.lock().unwrap())
+                .collect();
+            let now = self.time_source.now();
+            for bucket in buckets.iter_mut() {
+                bucket.refill(now);
+            }
+            if self.fail_fast && buckets.iter().any(|bucket| bucket.tokens < 1.0) {
+                return None;
+            }
+            buckets
+                .iter_mut()
+                .map(|bucket| bucket.reserve())
+                .max()
+                .unwrap_or_default()
+        };
+        Some((wait, Reservation(reserved)))
+    }
+}
+
+/// A layer that wraps services in a [`RateLimitService`]
+#[derive(Clone, Debug)]
+pub struct RateLimitLayer {
+    rate_limiter: Option<RateLimiter>,
+    sleep_impl: Option<Arc<dyn AsyncSleep>>,
+}
+
+impl RateLimitLayer {
+    /// Create a new `RateLimitLayer`
+    ///
+    /// If `rate_limiter` is `None`, requests are not limited.
+    pub fn new(rate_limiter: Option<RateLimiter>, sleep_impl: Option<Arc<dyn AsyncSleep>>) -> Self {
+        Self {
+            rate_limiter,
+            sleep_impl,
+        }
+    }
+}
+
+impl<S> Layer<S> for RateLimitLayer {
+    type Service = RateLimitService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RateLimitService {
+            inner,
+            rate_limiter: self.rate_limiter.clone(),
+            sleep_impl: self.sleep_impl.clone(),
+        }
+    }
+}
+
+/// A service that delays or fails requests that exceed the limits of a [`RateLimiter`]
+#[derive(Clone, Debug)]
+pub struct RateLimitService<S> {
+    inner: S,
+    rate_limiter: Option<RateLimiter>,
+    sleep_impl: Option<Arc<dyn AsyncSleep>>,
+}
+
+impl<H, R, S, E> tower::Service<Operation<H, R>> for RateLimitService<S>
+where
+    S: tower::Service<Operation<H, R>, Error = SdkError<E>> + Clone,
+{
+    type Response = S::Response;
+    type Error = SdkError<E>;
+    type Future = RateLimitFuture<S, Operation<H, R>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Operation<H, R>) -> Self::Future {
+        let rate_limiter = match &self.rate_limiter {
+            Some(rate_limiter) => rate_limiter,
+            None => return RateLimitFuture::ready(self.inner.call(req)),
+        };
+        let operation = req.metadata().map(|metadata| metadata.name());
+        let (wait, reservation) = match rate_limiter.acquire(operation) {
+            Some(acquired) => acquired,
+            None => {
+                return RateLimitFuture::failed(SdkError::ConstructionFailure(Box::new(
+                    RateLimitExceeded,
+                )))
+            }
+        };
+        if wait.is_zero() {
+            reservation.sent();
+            return RateLimitFuture::ready(self.inner.call(req));
+        }
+        match &self.sleep_impl {
+            Some(sleep_impl) => {
+                tracing::debug!("waiting {:?} to satisfy the client rate limit", wait);
+                // The service was made ready for this request, so a clone must be made ready
+                // again once the wait is over
+                let clone = self.inner.clone();
+                let service = std::mem::replace(&mut self.inner, clone);
+                RateLimitFuture {
+                    future: None,
+                    state: RateLimitState::Waiting {
+                        sleep: Some(sleep_impl.sleep(wait)),
+                        service,
+                        request: req,
+                        reservation,
+                    },
+                }
+            }
+            None => RateLimitFuture::failed(SdkError::ConstructionFailure(
+                "a sleep implementation is required to wait for the client rate limit".into(),
+            )),
+        }
+    }
+}
+
+#[derive(Debug)]
+enum RateLimitState<S, Req, E> {
+    /// Waiting until the request is permitted to be sent, and then for the service to be ready
+    ///
+    /// `sleep` is taken once it completes so that it isn't polled again. The `reservation` is
+    /// returned if the future is dropped before the request is sent.
+    Waiting {
+        sleep: Option<Sleep>,
+        service: S,
+        request: Req,
+        reservation: Reservation,
+    },
+    /// The request was not permitted
+    Failed(E),
+    /// The request has been sent
+    Sent,
+}
+
+pin_project! {
+    /// A future generated by a [`RateLimitService`]
+    #[must_use = "futures do nothing unless you `.await` or poll them"]
+    pub struct RateLimitFuture<S, Req>
+    where
+        S: tower::Service<Req>,
+    {
+        #[pin]
+        future: Option<S::Future>,
+        state: RateLimitState<S, Req, S::Error>,
+    }
+}
+
+impl<S, Req> std::fmt::Debug for RateLimitFuture<S, Req>
+where
+    S: tower::Service<Req>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("RateLimitFuture")
+    }
+}
+
+impl<S, Req> RateLimitFuture<S, Req>
+where
+    S: tower::Service<Req>,
+{
+    fn ready(future: S::Future) -> Self {
+        Self {
+            future: Some(future),
+            state: RateLimitState::Sent,
+        }
+    }
+
+    fn failed(err: S::Error) -> Self {
+        Self {
+            future: None,
+            state: RateLimitState::Failed(err),
+        }
+    }
+}
+
+impl<S, Req> Future for RateLimitFuture<S, Req>
+where
+    S: tower::Service<Req>,
+{
+    type Output = Result<S::Response, S::Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut this = self.project();
+        loop {
+            if let Some(future) = this.future.as_mut().as_pin_mut() {
+                return future.poll(cx);
+            }
+            match std::mem::replace(this.state, RateLimitState::Sent) {
+                RateLimitState::Waiting {
+                    mut sleep,
+                    mut service,
+                    request,
+                    reservation,
+                } => {
+                    if let Some(pending) = sleep.as_mut() {
+                        if Pin::new(pending).poll(cx).is_ready() {
+                            sleep = None;
+                        }
+                    }
+                    let ready = match sleep {
+                        None => service.poll_ready(cx),
+                        Some(_) => Poll::Pending,
+                    };
+                    match ready {
+                        Poll::Ready(Ok(())) => {
+                            reservation.sent();
+                            this.future.set(Some(service.call(request)))
+                        }
+                        Poll::Ready(Err(err)) => return Poll::Ready(Err(err)),
+                        Poll::Pending => {
+                            *this.state = RateLimitState::Waiting {
+                                sleep,
+                                service,
+                                request,
+                                reservation,
+                            };
+                            return Poll::Pending;
+                        }
+                    }
+                }
+                RateLimitState::Failed(err) => return Poll::Ready(Err(err)),
+                RateLimitState::Sent => panic!("polled after completion"),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use crate::rate_limit::{RateLimitExceeded, RateLimitLayer, RateLimiter};
+    use crate::SdkError;
+    use aws_smithy_async::rt::sleep::AsyncSleep;
+    use aws_smithy_async::test_util::instant_time_and_sleep;
+    use aws_smithy_http::body::SdkBody;
+    use aws_smithy_http::operation::{Metadata, Operation, Request};
+    use std::convert::Infallible;
+    use tower::{service_fn, Layer, ServiceExt};
+
+    fn operation(name: &'static str) -> Operation<(), ()> {
+        Operation::new(Request::new(http::Request::new(SdkBody::empty())), ())
+            .with_metadata(Metadata::new(name, "test-service"))
+    }
+
+    fn counting_service(
+        sent: Arc<AtomicUsize>,
+    ) -> impl tower::Service<Operation<(), ()>, Response = (), Error = SdkError<Infallible>> + Clone
+    {
+        service_fn(move |_req: Operation<(), ()>| {
+            sent.fetch_add(1, Ordering::SeqCst);
+            async { Ok::<_, SdkError<Infallible>>(()) }
+        })
+    }
+
+    #[tokio::test]
+    async fn requests_wait_for_permits() {
+        let (time_source, sleep) = instant_time_and_sleep(UNIX_EPOCH);
+        let sent = Arc::new(AtomicUsize::new(0));
+        let rate_limiter = RateLimiter::new()
+            .with_requests_per_second(2.0)
+            .with_time_source(time_source);
+        let sleep_impl: Arc<dyn AsyncSleep> = Arc::new(sleep.clone());
+        let svc = RateLimitLayer::new(Some(rate_limiter), Some(sleep_impl))
+            .layer(counting_service(sent.clone()));
+
+        // Two requests are permitted immediately, and then one every half second
+        for _ in 0..4 {
+            svc.clone().oneshot(operation("Any")).await.unwrap();
+        }
+        assert_eq!(
+            vec![Duration::from_millis(500), Duration::from_millis(500)],
+            sleep.logs()
+        );
+        assert_eq!(4, sent.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn operation_limits() {
+        let (time_source, sleep) = instant_time_and_sleep(UNIX_EPOCH);
+        let sent = Arc::new(AtomicUsize::new(0));
+        let rate_limiter = RateLimiter::new()
+            .with_requests_per_second(10.0)
+            .with_operation_requests_per_second("Slow", 1.0)
+            .with_time_source(time_source);
+        let sleep_impl: Arc<dyn AsyncSleep> = Arc::new(sleep.clone());
+        let svc = RateLimitLayer::new(Some(rate_limiter), Some(sleep_impl))
+            .layer(counting_service(sent.clone()));
+
+        for _ in 0..3 {
+            svc.clone().oneshot(operation("Slow")).await.unwrap();
+        }
+        assert_eq!(vec![Duration::from_secs(1); 2], sleep.logs());
+        // Other operations are only subject to the client limit
+        svc.oneshot(operation("Fast")).await.unwrap();
+        assert_eq!(vec![Duration::from_secs(1); 2], sleep.logs());
+        assert_eq!(4, sent.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn fail_fast() {
+        let (mut time_source, sleep) = instant_time_and_sleep(UNIX_EPOCH);
+        let sent = Arc::new(AtomicUsize::new(0));
+        let rate_limiter = RateLimiter::new()
+            .with_operation_requests_per_second("Limited", 1.0)
+            .with_time_source(time_source.clone())
+            .fail_fast();
+        let sleep_impl: Arc<dyn AsyncSleep> = Arc::new(sleep);
+        let svc = RateLimitLayer::new(Some(rate_limiter), Some(sleep_impl))
+            .layer(counting_service(sent.clone()));
+
+        svc.clone().oneshot(operation("Limited")).await.unwrap();
+        match svc.clone().oneshot(operation("Limited")).await {
+            Err(SdkError::ConstructionFailure(err)) => {
+                assert!(err.downcast_ref::<RateLimitExceeded>().is_some())
+            }
+            other => panic!("expected a rate limit error, got {:?}", other),
+        }
+        // Other operations aren't limited
+        svc.clone().oneshot(operation("Unlimited")).await.unwrap();
+
+        time_source.advance(Duration::from_secs(1));
+        svc.oneshot(operation("Limited")).await.unwrap();
+        assert_eq!(3, sent.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn cancelled_requests_refund_their_tokens() {
+        let (time_source, sleep) = instant_time_and_sleep(UNIX_EPOCH);
+        let sent = Arc::new(AtomicUsize::new(0));
+        let rate_limiter = RateLimiter::new()
+            .with_requests_per_second(1.0)
+            .with_operation_requests_per_second("Any", 1.0)
+            .with_time_source(time_source);
+        let sleep_impl: Arc<dyn AsyncSleep> = Arc::new(sleep.clone());
+        let mut svc = RateLimitLayer::new(Some(rate_limiter), Some(sleep_impl))
+            .layer(counting_service(sent.clone()));
+
+        svc.ready().await.unwrap();
+        tower::Service::call(&mut svc, operation("Any"))
+            .await
+            .unwrap();
+        // The second request waits for a second, and is cancelled during that time
+        svc.ready().await.unwrap();
+        drop(tower::Service::call(&mut svc, operation("Any")));
+        assert_eq!(vec![Duration::from_secs(1)], sleep.logs());
+
+        // After a second, a token is available again for the next request
+        svc.oneshot(operation("Any")).await.unwrap();
+        assert_eq!(vec![Duration::from_secs(1)], sleep.logs());
+        assert_eq!(2, sent.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    #[should_panic(expected = "positive number of requests per second")]
+    fn zero_rate_is_rejected() {
+        RateLimiter::new().with_requests_per_second(0.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "positive number of requests per second")]
+    fn non_finite_rate_is_rejected() {
+        RateLimiter::new().with_operation_requests_per_second("Any", f64::NAN);
+    }
+
+    #[tokio::test]
+    async fn sleep_is_not_polled_after_completion() {
+        use std::future::Future;
+        use std::pin::Pin;
+        use std::sync::atomic::AtomicBool;
+        use std::task::{Context, Poll};
+
+        let (time_source, sleep) = instant_time_and_sleep(UNIX_EPOCH);
+        let rate_limiter = RateLimiter::new()
+            .with_requests_per_second(1.0)
+            .with_time_source(time_source);
+        let sleep_impl: Arc<dyn AsyncSleep> = Arc::new(sleep);
+
+        // A service that is not ready the first time it's polled after the wait
+        #[derive(Clone)]
+        struct SlowToReady(Arc<AtomicBool>);
+        impl tower::Service<Operation<(), ()>> for SlowToReady {
+            type Response = ();
+            type Error = SdkError<Infallible>;
+            type Future = std::future::Ready<Result<(), SdkError<Infallible>>>;
+
+            fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+                if self.0.swap(true, Ordering::SeqCst) {
+                    Poll::Ready(Ok(()))
+                } else {
+                    cx.waker().wake_by_ref();
+                    Poll::Pending
+                }
+            }
+
+            fn call(&mut self, _req: Operation<(), ()>) -> Self::Future {
+                std::future::ready(Ok(()))
+            }
+        }
+
+        let ready = Arc::new(AtomicBool::new(true));
+        let mut svc = RateLimitLayer::new(Some(rate_limiter), Some(sleep_impl))
+            .layer(SlowToReady(ready.clone()));
+        svc.ready().await.unwrap();
+        tower::Service::call(&mut svc, operation("Any"))
+            .await
+            .unwrap();
+
+        svc.ready().await.unwrap();
+        ready.store(false, Ordering::SeqCst);
+        let mut future = tower::Service::call(&mut svc, operation("Any"));
+        // The sleep completes (time is instant), then the service isn't ready yet
+        let waker = futures_util::task::noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        assert!(Pin::new(&mut future).poll(&mut cx).is_pending());
+        assert!(Pin::new(&mut future).poll(&mut cx).is_ready());
+    }
+}
@@ -15,18 +15,35 @@
 //! - [`RetryHandler`]: A request-scoped retry policy, backed by request-local state and shared
 //!   state contained within [`Standard`].
 //! - [`Config`]: Static configuration (max attempts, max backoff etc.)
-
+//!
+//! Each [`RetryHandler`] tracks how many attempts its request has made and when the request
+//! started, so that retries can be limited by both attempt count ([`Config::with_max_attempts`])
+//! and total elapsed time ([`Config::with_max_elapsed_time`]).
+//!
+//! The maximum number of attempts can be overridden for a single operation by inserting a
+//! [`RetryConfig`](aws_smithy_types::retry::RetryConfig) into the operation's property bag.
+//!
+//! Retry quota is tracked separately for each [`RetryPartition`]. By default, operations are
+//! partitioned by the service in their [`Metadata`](aws_smithy_http::operation::Metadata).
+//! The quotas of each partition are held by [`RetryPartitions`], which can be shared between
+//! clients (see [`Standard::with_retry_partitions`]) so that clients of the same service draw from
+//! the same quota.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::{Arc, Mutex};
-use std::time::Duration;
+use std::time::{Duration, SystemTime};
 
 use crate::{SdkError, SdkSuccess};
 use aws_smithy_async::rt::sleep::AsyncSleep;
+use aws_smithy_async::time::{SharedTimeSource, TimeSource};
+use aws_smithy_http::metrics::{EmitMetrics, RetryMetrics, SharedMetricsEmitter};
 use aws_smithy_http::operation;
 use aws_smithy_http::operation::Operation;
 use aws_smithy_http::retry::ClassifyResponse;
-use aws_smithy_types::retry::{ErrorKind, RetryKind};
+use aws_smithy_types::retry::{ErrorKind, RetryConfig, RetryKind};
 use tracing::Instrument;
 
 /// A policy instantiator.
@@ -43,6 +60,21 @@ where
 
     /// Create a new policy mechanism instance.
     fn new_request_policy(&self, sleep_impl: Option<Arc<dyn AsyncSleep>>) -> Self::Policy;
+
+    /// Returns the quota that attempts made outside of the retry policy (such as
+    /// [hedged requests](crate::hedge)) should spend from, if the policy has one.
+    ///
+    /// `partition` is the [`RetryPartition`] of the request making those attempts, so that they
+    /// spend from the same quota as its retries.
+    fn retry_quota(&self, _partition: Option<&RetryPartition>) -> Option<RetryQuota> {
+        None
+    }
+
+    /// Returns the maximum number of attempts, including the initial attempt, that policies
+    /// created by this instantiator will make, if it is known.
+    fn max_attempts(&self) -> Option<u32> {
+        None
+    }
 }
 
 /// Retry Policy Configuration
@@ -58,7 +90,9 @@ pub struct Config {
     timeout_retry_cost: usize,
     max_attempts: u32,
     max_backoff: Duration,
+    max_elapsed_time: Option<Duration>,
     base: fn() -> f64,
+    time_source: SharedTimeSource,
 }
 
 impl Config {
@@ -82,6 +116,21 @@ impl Config {
         self.max_attempts = max_attempts;
         self
     }
+
+    /// Override the maximum amount of time a request may spend retrying
+    ///
+    /// A retry will not be attempted if the time elapsed since the request started, plus the
+    /// backoff before the retry, would exceed `max_elapsed_time`. By default, there is no limit.
+    pub fn with_max_elapsed_time(mut self, max_elapsed_time: Duration) -> Self {
+        self.max_elapsed_time = Some(max_elapsed_time);
+        self
+    }
+
+    /// Use `time_source` to measure how long a request has been running
+    pub fn with_time_source(mut self, time_source: impl TimeSource + 'static) -> Self {
+        self.time_source = SharedTimeSource::new(time_source);
+        self
+    }
 }
 
 impl Default for Config {
@@ -93,8 +142,10 @@ impl Default for Config {
             timeout_retry_cost: 10,
             max_attempts: MAX_ATTEMPTS,
             max_backoff: Duration::from_secs(20),
+            max_elapsed_time: None,
             // by default, use a random base for exponential backoff
             base: fastrand::f64,
+            time_source: SharedTimeSource::default(),
         }
     }
 }
@@ -122,6 +173,7 @@ const RETRY_COST: usize = 5;
 pub struct Standard {
     config: Config,
     shared_state: CrossRequestRetryState,
+    partitions: RetryPartitions,
 }
 
 impl Standard {
@@ -129,6 +181,7 @@ impl Standard {
     pub fn new(config: Config) -> Self {
         Self {
             shared_state: CrossRequestRetryState::new(config.initial_retry_tokens),
+            partitions: RetryPartitions::with_initial_quota(config.initial_retry_tokens),
             config,
         }
     }
@@ -138,6 +191,20 @@ impl Standard {
         self.config = config;
         self
     }
+
+    /// Draw retry quota from `partitions`, which may be shared with other retry policies.
+    pub fn with_retry_partitions(&mut self, partitions: RetryPartitions) -> &mut Self {
+        self.partitions = partitions;
+        self
+    }
+
+    /// Returns the retry quotas of this policy's partitions.
+    ///
+    /// Pass this to [`with_retry_partitions`](Standard::with_retry_partitions) to share quotas
+    /// with another policy.
+    pub fn retry_partitions(&self) -> RetryPartitions {
+        self.partitions.clone()
+    }
 }
 
 impl NewRequestPolicy for Standard {
@@ -145,12 +212,51 @@ impl NewRequestPolicy for Standard {
 
     fn new_request_policy(&self, sleep_impl: Option<Arc<dyn AsyncSleep>>) -> Self::Policy {
         RetryHandler {
-            local: RequestLocalRetryState::new(),
+            local: RequestLocalRetryState::new(self.config.time_source.now()),
             shared: self.shared_state.clone(),
+            partitions: self.partitions.clone(),
             config: self.config.clone(),
             sleep_impl,
         }
     }
+
+    fn max_attempts(&self) -> Option<u32> {
+        Some(self.config.max_attempts)
+    }
+
+    fn retry_quota(&self, partition: Option<&RetryPartition>) -> Option<RetryQuota> {
+        let shared = match partition {
+            Some(partition) => self.partitions.state(partition.clone()),
+            None => self.shared_state.clone(),
+        };
+        Some(RetryQuota {
+            shared,
+            config: self.config.clone(),
+        })
+    }
+}
+
+/// A handle to the retry quota shared by the requests of a [`RetryPartition`] made with a
+/// [`Standard`] retry policy.
+///
+/// Additional attempts that aren't retries spend from this quota at the cost of a retry, so that
+/// they back off along with retries while a service is unhealthy.
+#[derive(Clone, Debug)]
+pub struct RetryQuota {
+    shared: CrossRequestRetryState,
+    config: Config,
+}
+
+impl RetryQuota {
+    /// Acquire quota for one additional attempt, returning the amount acquired.
+    pub(crate) fn acquire(&self) -> Option<usize> {
+        self.shared.quota_acquire_cost(self.config.retry_cost)
+    }
+
+    /// Return quota acquired by [`acquire`](RetryQuota::acquire).
+    pub(crate) fn release(&self, acquired: usize) {
+        self.shared.quota_release(Some(acquired), &self.config)
+    }
 }
 
 impl Default for Standard {
@@ -163,29 +269,94 @@ impl Default for Standard {
 struct RequestLocalRetryState {
     attempts: u32,
     last_quota_usage: Option<usize>,
+    started_at: SystemTime,
 }
 
-impl Default for RequestLocalRetryState {
-    fn default() -> Self {
+impl RequestLocalRetryState {
+    pub fn new(started_at: SystemTime) -> Self {
         Self {
             // Starts at one to account for the initial request that failed and warranted a retry
             attempts: 1,
             last_quota_usage: None,
+            started_at,
         }
     }
 }
 
-impl RequestLocalRetryState {
-    pub fn new() -> Self {
-        Self::default()
+/// A scope for cross-request retry state
+///
+/// Requests in different partitions draw from separate retry quotas, so that one unhealthy service
+/// can't exhaust the retry budget of every other service sharing a client. Unless a `RetryPartition`
+/// is inserted into an operation's property bag, the partition is the service named by the
+/// operation's [`Metadata`](operation::Metadata).
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RetryPartition(Cow<'static, str>);
+
+impl RetryPartition {
+    /// Creates a new retry partition with the given name
+    pub fn new(name: impl Into<Cow<'static, str>>) -> Self {
+        Self(name.into())
+    }
+
+    /// Returns the retry partition for `operation`, if it has one
+    pub(crate) fn for_operation<H, R>(operation: &Operation<H, R>) -> Option<Self> {
+        let properties = operation.properties();
+        properties.get::<RetryPartition>().cloned().or_else(|| {
+            properties
+                .get::<operation::Metadata>()
+                .or_else(|| operation.metadata())
+                .map(|metadata| RetryPartition::new(metadata.service().to_string()))
+        })
     }
 }
 
-/* TODO(retries)
-/// RetryPartition represents a scope for cross request retry state
+/// The retry quota of each [`RetryPartition`], created on demand
+///
+/// Cloning `RetryPartitions` produces a handle to the same quotas, so that several clients can
+/// share them:
+/// ```
+/// use aws_smithy_client::retry::{RetryPartitions, Standard};
 ///
-/// For example, a retry partition could be the id of a service. This would give each service a separate retry budget.
-struct RetryPartition(Cow<'static, str>); */
+/// let partitions = RetryPartitions::new();
+/// let mut first = Standard::default();
+/// first.with_retry_partitions(partitions.clone());
+/// let mut second = Standard::default();
+/// second.with_retry_partitions(partitions);
+/// ```
+#[derive(Clone, Debug)]
+pub struct RetryPartitions {
+    initial_quota: usize,
+    partitions: Arc<Mutex<HashMap<RetryPartition, CrossRequestRetryState>>>,
+}
+
+impl Default for RetryPartitions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RetryPartitions {
+    /// Creates a new set of partitions, each starting with the default retry quota
+    pub fn new() -> Self {
+        Self::with_initial_quota(Config::default().initial_retry_tokens)
+    }
+
+    fn with_initial_quota(initial_quota: usize) -> Self {
+        Self {
+            initial_quota,
+            partitions: Default::default(),
+        }
+    }
+
+    fn state(&self, partition: RetryPartition) -> CrossRequestRetryState {
+        self.partitions
+            .lock()
+            .unwrap()
+            .entry(partition)
+            .or_insert_with(|| CrossRequestRetryState::new(self.initial_quota))
+            .clone()
+    }
+}
 
 /// Shared state between multiple requests to the same client.
 #[derive(Clone, Debug)]
@@ -213,12 +384,16 @@ impl CrossRequestRetryState {
     /// If quota is available, the amount of quota consumed is returned
     /// If no quota is available, `None` is returned.
     fn quota_acquire(&self, err: &ErrorKind, config: &Config) -> Option<usize> {
-        let mut quota = self.quota_available.lock().unwrap();
         let retry_cost = if err == &ErrorKind::TransientError {
             config.timeout_retry_cost
         } else {
             config.retry_cost
         };
+        self.quota_acquire_cost(retry_cost)
+    }
+
+    fn quota_acquire_cost(&self, retry_cost: usize) -> Option<usize> {
+        let mut quota = self.quota_available.lock().unwrap();
         if retry_cost > *quota {
             None
         } else {
@@ -239,6 +414,7 @@ type BoxFuture<T> = Pin<Box<dyn Future<Output = T> + Send>>;
 pub struct RetryHandler {
     local: RequestLocalRetryState,
     shared: CrossRequestRetryState,
+    partitions: RetryPartitions,
     config: Config,
     sleep_impl: Option<Arc<dyn AsyncSleep>>,
 }
@@ -251,17 +427,36 @@ impl RetryHandler {
 }
 
 impl RetryHandler {
+    /// The number of attempts made so far for this request, including the initial attempt
+    pub fn attempts(&self) -> u32 {
+        self.local.attempts
+    }
+
+    /// The time elapsed since this request started
+    pub fn elapsed(&self) -> Duration {
+        self.config
+            .time_source
+            .now()
+            .duration_since(self.local.started_at)
+            .unwrap_or_default()
+    }
+
+    /// Returns true if retrying after `backoff` would exceed the maximum elapsed time
+    fn exceeds_max_elapsed_time(&self, backoff: Duration) -> bool {
+        match self.config.max_elapsed_time {
+            Some(max_elapsed_time) => self.elapsed() + backoff > max_elapsed_time,
+            None => false,
+        }
+    }
+
     /// Determine the correct response given `retry_kind`
     ///
     /// If a retry is specified, this function returns `(next, backoff_duration)`
     /// If no retry is specified, this function returns None
     fn should_retry_error(&self, error_kind: &ErrorKind) -> Option<(Self, Duration)> {
-        let quota_used = {
-            if self.local.attempts == self.config.max_attempts {
-                return None;
-            }
-            self.shared.quota_acquire(error_kind, &self.config)?
-        };
+        if self.local.attempts == self.config.max_attempts {
+            return None;
+        }
         /*
         From the retry spec:
             b = random number within the range of: 0 <= b <= 1
@@ -274,12 +469,18 @@ impl RetryHandler {
         // The initial attempt shouldn't count towards backoff calculations so we subtract it
         let backoff = b * (r.pow(self.local.attempts - 1) as f64);
         let backoff = Duration::from_secs_f64(backoff).min(self.config.max_backoff);
+        if self.exceeds_max_elapsed_time(backoff) {
+            return None;
+        }
+        let quota_used = self.shared.quota_acquire(error_kind, &self.config)?;
         let next = RetryHandler {
             local: RequestLocalRetryState {
                 attempts: self.local.attempts + 1,
                 last_quota_usage: Some(quota_used),
+                started_at: self.local.started_at,
             },
             shared: self.shared.clone(),
+            partitions: self.partitions.clone(),
             config: self.config.clone(),
             sleep_impl: self.sleep_impl.clone(),
         };
@@ -289,6 +490,7 @@ impl RetryHandler {
 
     fn should_retry(&self, retry_kind: &RetryKind) -> Option<(Self, Duration)> {
         match retry_kind {
+            RetryKind::Explicit(dur) if self.exceeds_max_elapsed_time(*dur) => None,
             RetryKind::Explicit(dur) => Some((self.clone(), *dur)),
             RetryKind::UnretryableFailure => None,
             RetryKind::Unnecessary => {
@@ -301,8 +503,24 @@ impl RetryHandler {
         }
     }
 
-    fn retry_for(&self, retry_kind: RetryKind) -> Option<BoxFuture<Self>> {
-        let (next, dur) = self.should_retry(&retry_kind)?;
+    fn retry_for(
+        &self,
+        retry_kind: RetryKind,
+        metrics: Option<&SharedMetricsEmitter>,
+        metadata: Option<&operation::Metadata>,
+    ) -> Option<BoxFuture<Self>> {
+        let retry = self.should_retry(&retry_kind);
+        if let Some(metrics) = metrics {
+            metrics.emit_retry(
+                &RetryMetrics::new(
+                    self.local.attempts,
+                    retry_kind.clone(),
+                    retry.as_ref().map(|(_, dur)| *dur),
+                )
+                .with_metadata(metadata.cloned()),
+            );
+        }
+        let (next, dur) = retry?;
 
         let sleep = match &self.sleep_impl {
             Some(sleep) => sleep,
@@ -346,7 +564,29 @@ where
     ) -> Option<Self::Future> {
         let policy = req.retry_policy();
         let retry_kind = policy.classify(result);
-        self.retry_for(retry_kind)
+        let (metrics, retry_config, metadata) = {
+            let properties = req.properties();
+            (
+                properties.get::<SharedMetricsEmitter>().cloned(),
+                properties.get::<RetryConfig>().cloned(),
+                properties
+                    .get::<operation::Metadata>()
+                    .or_else(|| req.metadata())
+                    .cloned(),
+            )
+        };
+        let partition = RetryPartition::for_operation(req);
+        if retry_config.is_none() && partition.is_none() {
+            return self.retry_for(retry_kind, metrics.as_ref(), metadata.as_ref());
+        }
+        let mut handler = self.clone();
+        if let Some(retry_config) = retry_config {
+            handler.config.max_attempts = retry_config.max_attempts();
+        }
+        if let Some(partition) = partition {
+            handler.shared = self.partitions.state(partition);
+        }
+        handler.retry_for(retry_kind, metrics.as_ref(), metadata.as_ref())
     }
 
     fn clone_request(&self, req: &Operation<Handler, R>) -> Option<Operation<Handler, R>> {
@@ -361,11 +601,15 @@ fn check_send<T: Send>(t: T) -> T {
 #[cfg(test)]
 mod test {
 
-    use crate::retry::{Config, NewRequestPolicy, RetryHandler, Standard};
+    use crate::retry::{Config, NewRequestPolicy, RetryHandler, RetryPartition, Standard};
+
+    use aws_smithy_http::body::SdkBody;
+    use aws_smithy_http::operation::{self, Operation};
 
+    use aws_smithy_async::test_util::ManualTimeSource;
     use aws_smithy_types::retry::{ErrorKind, RetryKind};
 
-    use std::time::Duration;
+    use std::time::{Duration, UNIX_EPOCH};
 
     fn test_config() -> Config {
         Config::default().with_base(|| 1_f64)
@@ -378,6 +622,112 @@ mod test {
         must_be_send_sync::<RetryHandler>()
     }
 
+    #[test]
+    fn partitions_have_separate_quotas() {
+        let mut conf = test_config();
+        conf.initial_retry_tokens = 5;
+        let standard = Standard::new(conf);
+        let s3 = standard.partitions.state(RetryPartition::new("s3"));
+        let dynamodb = standard.partitions.state(RetryPartition::new("dynamodb"));
+        assert_eq!(s3.quota_acquire_cost(5), Some(5));
+        assert_eq!(s3.quota_acquire_cost(5), None);
+        assert_eq!(dynamodb.quota_acquire_cost(5), Some(5));
+        assert_eq!(
+            standard
+                .partitions
+                .state(RetryPartition::new("s3"))
+                .quota_acquire_cost(5),
+            None,
+            "requests in the same partition share quota"
+        );
+    }
+
+    #[test]
+    fn retry_quota_is_drawn_from_the_partition() {
+        let standard = Standard::new(test_config());
+        let s3 = RetryPartition::new("s3");
+        let quota = standard.retry_quota(Some(&s3)).unwrap();
+        while quota.acquire().is_some() {}
+        assert_eq!(
+            standard.partitions.state(s3).quota_acquire_cost(1),
+            None,
+            "the partition's quota was spent"
+        );
+        assert!(standard
+            .partitions
+            .state(RetryPartition::new("dynamodb"))
+            .quota_acquire_cost(1)
+            .is_some());
+        assert!(standard.shared_state.quota_acquire_cost(1).is_some());
+    }
+
+    #[test]
+    fn partitions_can_be_shared_between_policies() {
+        let first = Standard::default();
+        let mut second = Standard::default();
+        second.with_retry_partitions(first.retry_partitions());
+        let mut unshared = Standard::default();
+        unshared.with_config(test_config());
+
+        let initial = Config::default().initial_retry_tokens;
+        let s3 = first.partitions.state(RetryPartition::new("s3"));
+        assert_eq!(s3.quota_acquire_cost(initial), Some(initial));
+        assert_eq!(
+            second
+                .partitions
+                .state(RetryPartition::new("s3"))
+                .quota_acquire_cost(1),
+            None
+        );
+        assert_eq!(
+            unshared
+                .partitions
+                .state(RetryPartition::new("s3"))
+                .quota_acquire_cost(1),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn operations_are_partitioned_by_service() {
+        let operation = || {
+            Operation::new(
+                operation::Request::new(http::Request::new(SdkBody::from(""))),
+                (),
+            )
+        };
+        assert_eq!(RetryPartition::for_operation(&operation()), None);
+        assert_eq!(
+            RetryPartition::for_operation(
+                &operation().with_metadata(operation::Metadata::new("ListBuckets", "s3"))
+            ),
+            Some(RetryPartition::new("s3"))
+        );
+        let mut custom = operation().with_metadata(operation::Metadata::new("ListBuckets", "s3"));
+        custom
+            .properties_mut()
+            .insert(RetryPartition::new("custom"));
+        assert_eq!(
+            RetryPartition::for_operation(&custom),
+            Some(RetryPartition::new("custom"))
+        );
+    }
+
+    #[test]
+    fn partition_uses_metadata_from_the_property_bag() {
+        let mut operation = Operation::new(
+            operation::Request::new(http::Request::new(SdkBody::from(""))),
+            (),
+        );
+        operation
+            .properties_mut()
+            .insert(operation::Metadata::new("GetItem", "dynamodb"));
+        assert_eq!(
+            RetryPartition::for_operation(&operation),
+            Some(RetryPartition::new("dynamodb"))
+        );
+    }
+
     #[test]
     fn eventual_success() {
         let policy = Standard::new(test_config()).new_request_policy(None);
@@ -532,4 +882,39 @@ mod test {
         assert!(no_retry.is_none());
         assert_eq!(policy.retry_quota(), 480);
     }
+
+    #[test]
+    fn max_elapsed_time() {
+        let mut time_source = ManualTimeSource::new(UNIX_EPOCH);
+        let conf = test_config()
+            .with_max_attempts(5)
+            .with_max_elapsed_time(Duration::from_secs(10))
+            .with_time_source(time_source.clone());
+        let policy = Standard::new(conf).new_request_policy(None);
+        let (policy, dur) = policy
+            .should_retry(&RetryKind::Error(ErrorKind::ServerError))
+            .expect("should retry");
+        assert_eq!(dur, Duration::from_secs(1));
+        assert_eq!(policy.attempts(), 2);
+
+        time_source.advance(Duration::from_secs(7));
+        assert_eq!(policy.elapsed(), Duration::from_secs(7));
+        let (policy, dur) = policy
+            .should_retry(&RetryKind::Error(ErrorKind::ServerError))
+            .expect("should retry");
+        assert_eq!(dur, Duration::from_secs(2));
+        assert_eq!(policy.retry_quota(), 490);
+
+        // 9 seconds have elapsed, so a 4 second backoff would run past the limit
+        time_source.advance(Duration::from_secs(2));
+        let no_retry = policy.should_retry(&RetryKind::Error(ErrorKind::ServerError));
+        assert!(no_retry.is_none());
+        assert_eq!(policy.retry_quota(), 490, "no quota should be spent");
+
+        let no_retry = policy.should_retry(&RetryKind::Explicit(Duration::from_secs(2)));
+        assert!(no_retry.is_none());
+        assert!(policy
+            .should_retry(&RetryKind::Explicit(Duration::from_secs(1)))
+            .is_some());
+    }
 }
@@ -0,0 +1,89 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Use a [`Client`] as a [`tower::Service`].
+//!
+//! [`Client::into_service`] returns a service that sends each [`Operation`] through the client's
+//! full stack, exactly like [`Client::call_raw`]. This makes it possible to compose additional
+//! tower layers (e.g. buffering, load shedding, or metrics) around the client in your own stacks:
+//!
+//! ```no_run
+//! # fn wrap(client: aws_smithy_client::erase::DynClient) {
+//! use tower::ServiceBuilder;
+//!
+//! let service = ServiceBuilder::new()
+//!     // Your own layers go here
+//!     .service(client.into_service());
+//! # }
+//! ```
+//!
+//! Layers composed this way see one request per operation. Retries, hedged requests, and the
+//! client's timeouts all happen inside the service, so they aren't visible to outer layers.
+
+use crate::erase::boxclone::BoxFuture;
+use crate::{bounds, retry, Client};
+use aws_smithy_http::operation::Operation;
+use aws_smithy_http::result::{SdkError, SdkSuccess};
+use std::task::{Context, Poll};
+use tower::Service;
+
+/// A [`Client`] that is a [`tower::Service`] for [`Operation`]s.
+///
+/// Created with [`Client::into_service`]. See the [module documentation](crate::service) for
+/// details.
+///
+/// The service is always ready. Backpressure from the client's
+/// [concurrency limit](crate::concurrency_limit) is applied to each call instead.
+#[derive(Clone, Debug)]
+pub struct ClientService<C, M, R = retry::Standard> {
+    client: Client<C, M, R>,
+}
+
+impl<C, M, R> ClientService<C, M, R> {
+    pub(crate) fn new(client: Client<C, M, R>) -> Self {
+        Self { client }
+    }
+
+    /// Returns the client that this service sends operations with.
+    pub fn client(&self) -> &Client<C, M, R> {
+        &self.client
+    }
+
+    /// Returns the client that this service sends operations with.
+    pub fn into_inner(self) -> Client<C, M, R> {
+        self.client
+    }
+}
+
+impl<C, M, R, O, T, E, Retry> Service<Operation<O, Retry>> for ClientService<C, M, R>
+where
+    C: bounds::SmithyConnector,
+    M: bounds::SmithyMiddleware<C> + 'static,
+    R: retry::NewRequestPolicy + 'static,
+    O: Send + Sync + 'static,
+    T: Send + 'static,
+    E: Send + 'static,
+    Retry: Send + Sync + 'static,
+    R::Policy: bounds::SmithyRetryPolicy<O, T, E, Retry>,
+    <R::Policy as tower::retry::Policy<Operation<O, Retry>, SdkSuccess<T>, SdkError<E>>>::Future:
+        Send,
+    bounds::Parsed<<M as bounds::SmithyMiddleware<C>>::Service, O, Retry>:
+        Service<Operation<O, Retry>, Response = SdkSuccess<T>, Error = SdkError<E>> + Clone,
+    <bounds::Parsed<<M as bounds::SmithyMiddleware<C>>::Service, O, Retry> as Service<
+        Operation<O, Retry>,
+    >>::Future: Send,
+{
+    type Response = SdkSuccess<T>;
+    type Error = SdkError<E>;
+    type Future = BoxFuture<SdkSuccess<T>, SdkError<E>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, operation: Operation<O, Retry>) -> Self::Future {
+        Box::pin(self.client.call_raw(operation))
+    }
+}
@@ -113,3 +113,62 @@ fn erased_is_send_sync() {
             .into_dyn(),
     );
 }
+
+// Statically check that a fully type-erased client can be stored and shared without naming the
+// connector or middleware it was built from.
+#[allow(dead_code)]
+fn erased_is_storable() {
+    fn is_clone_static<T: Clone + Send + Sync + 'static>(_: &T) {}
+    struct App {
+        client: crate::erase::DynClient,
+    }
+    let app = App {
+        client: Builder::new()
+            .middleware(tower::layer::util::Identity::new())
+            .connector_fn(|_| async { unreachable!() })
+            .build()
+            .into_dyn(),
+    };
+    is_clone_static(&app.client);
+    let _: crate::Client = app.client;
+}
+
+// Statically check that the future returned by `call` is Send, even if the middleware isn't Sync.
+#[allow(dead_code)]
+fn call_future_is_send() {
+    struct NotSyncLayer(std::marker::PhantomData<std::cell::Cell<()>>);
+    impl<S> tower::Layer<S> for NotSyncLayer {
+        type Service = S;
+
+        fn layer(&self, inner: S) -> S {
+            inner
+        }
+    }
+
+    fn is_send<T: Send>(_: T) {}
+    let client = Builder::new()
+        .middleware(NotSyncLayer(Default::default()))
+        .connector_fn(|_| async { unreachable!() })
+        .build();
+    let _ = |o: ValidTestOperation| {
+        is_send(client.call_raw(o));
+    };
+}
+
+// Statically check that a client can be used as a `tower::Service` in other stacks.
+#[allow(dead_code)]
+fn client_is_a_service() {
+    fn is_service<S>(_: S)
+    where
+        S: tower::Service<ValidTestOperation> + Clone + Send + Sync + 'static,
+        S::Future: Send + 'static,
+    {
+    }
+    is_service(
+        Builder::new()
+            .middleware(tower::layer::util::Identity::new())
+            .connector_fn(|_| async { unreachable!() })
+            .build()
+            .into_service(),
+    );
+}
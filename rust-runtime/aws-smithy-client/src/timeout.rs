@@ -9,6 +9,7 @@
 //!
 //! As timeout and HTTP configuration stabilizes, this will move to aws-types and become a part of
 //! HttpSettings.
+use aws_smithy_async::time::Instant;
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
@@ -18,34 +19,14 @@ use std::time::Duration;
 use crate::SdkError;
 use aws_smithy_async::future::timeout::Timeout;
 use aws_smithy_async::rt::sleep::{AsyncSleep, Sleep};
-use aws_smithy_http::operation::Operation;
+use aws_smithy_http::diagnostic::DiagnosticContext;
+use aws_smithy_http::operation::{InvocationId, Operation, Request};
+use aws_smithy_http::property_bag::SharedPropertyBag;
+use aws_smithy_http::result::RequestTimeoutError;
+use aws_smithy_http::retry::RequestAttempts;
 use pin_project_lite::pin_project;
 use tower::Layer;
 
-#[derive(Debug)]
-struct RequestTimeoutError {
-    kind: &'static str,
-    duration: Duration,
-}
-
-impl RequestTimeoutError {
-    pub fn new_boxed(kind: &'static str, duration: Duration) -> Box<Self> {
-        Box::new(Self { kind, duration })
-    }
-}
-
-impl std::fmt::Display for RequestTimeoutError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{} timeout occurred after {:?}",
-            self.kind, self.duration
-        )
-    }
-}
-
-impl std::error::Error for RequestTimeoutError {}
-
 #[derive(Clone, Debug)]
 /// A struct containing everything needed to create a new [`TimeoutService`]
 pub struct TimeoutServiceParams {
@@ -57,6 +38,17 @@ pub struct TimeoutServiceParams {
     async_sleep: Arc<dyn AsyncSleep>,
 }
 
+impl TimeoutServiceParams {
+    /// Params for a timeout that elapses at `deadline`, measured from when they are created
+    pub(crate) fn deadline(deadline: Instant, async_sleep: Arc<dyn AsyncSleep>) -> Self {
+        Self {
+            duration: deadline.saturating_duration_since(Instant::now()),
+            kind: "API call deadline",
+            async_sleep,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Default)]
 /// A struct of structs containing everything needed to create new [`TimeoutService`]s
 pub struct ClientTimeoutParams {
@@ -161,6 +153,7 @@ pin_project! {
             future: Timeout<F, Sleep>,
             kind: &'static str,
             duration: Duration,
+            properties: Option<SharedPropertyBag>,
         },
         /// A thin wrapper around an inner future that will never time out
         NoTimeout {
@@ -179,9 +172,18 @@ impl<F> TimeoutServiceFuture<F> {
             future: Timeout::new(future, params.async_sleep.sleep(params.duration)),
             kind: params.kind,
             duration: params.duration,
+            properties: None,
         }
     }
 
+    /// Sets the property bag of the operation, to report what it did if the timeout elapses
+    fn with_properties(mut self, bag: SharedPropertyBag) -> Self {
+        if let Self::Timeout { properties, .. } = &mut self {
+            *properties = Some(bag);
+        }
+        self
+    }
+
     /// Create a [`TimeoutServiceFuture`] that will never time out.
     pub fn no_timeout(future: F) -> Self {
         Self::NoTimeout { future }
@@ -195,24 +197,47 @@ where
     type Output = Result<T, SdkError<E>>;
 
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        let (future, kind, duration) = match self.project() {
+        let (future, kind, duration, properties) = match self.project() {
             TimeoutServiceFutureProj::NoTimeout { future } => return future.poll(cx),
             TimeoutServiceFutureProj::Timeout {
                 future,
                 kind,
                 duration,
-            } => (future, kind, duration),
+                properties,
+            } => (future, kind, duration, properties),
         };
         match future.poll(cx) {
             Poll::Ready(Ok(response)) => Poll::Ready(response),
-            Poll::Ready(Err(_timeout)) => Poll::Ready(Err(SdkError::TimeoutError(
-                RequestTimeoutError::new_boxed(kind, *duration),
-            ))),
+            Poll::Ready(Err(_timeout)) => Poll::Ready(Err(SdkError::TimeoutError(Box::new(
+                timeout_error(kind, *duration, properties.take()),
+            )))),
             Poll::Pending => Poll::Pending,
         }
     }
 }
 
+/// Creates the error for a timeout, recording the attempt it interrupted, if any
+fn timeout_error(
+    kind: &'static str,
+    duration: Duration,
+    properties: Option<SharedPropertyBag>,
+) -> RequestTimeoutError {
+    let err = RequestTimeoutError::new(kind, duration);
+    let properties = match properties {
+        Some(properties) => properties,
+        None => return err,
+    };
+    let mut properties = properties.acquire_mut();
+    if let Some(attempts) = properties
+        .get::<RequestAttempts>()
+        .map(RequestAttempts::attempts)
+    {
+        DiagnosticContext::record(&mut properties).attempt_timed_out(attempts);
+    }
+    err.with_invocation_id(properties.get::<InvocationId>().cloned())
+        .with_diagnostic_context(properties.get::<DiagnosticContext>().cloned())
+}
+
 impl<H, R, InnerService, E> tower::Service<Operation<H, R>> for TimeoutService<InnerService>
 where
     InnerService: tower::Service<Operation<H, R>, Error = SdkError<E>>,
@@ -226,10 +251,17 @@ where
     }
 
     fn call(&mut self, req: Operation<H, R>) -> Self::Future {
-        let future = self.inner.call(req);
+        // Keep a handle to the property bag, so that the timeout error can report what the
+        // operation did before the timeout elapsed
+        let (request, parts) = req.into_request_response();
+        let (request, properties) = request.into_parts();
+        let future = self.inner.call(Operation::from_parts(
+            Request::from_parts(request, properties.clone()),
+            parts,
+        ));
 
         if let Some(params) = &self.params {
-            Self::Future::new(future, params)
+            Self::Future::new(future, params).with_properties(properties)
         } else {
             Self::Future::no_timeout(future)
         }
@@ -248,7 +280,11 @@ mod test {
     use aws_smithy_async::assert_elapsed;
     use aws_smithy_async::rt::sleep::{AsyncSleep, TokioSleep};
     use aws_smithy_http::body::SdkBody;
-    use aws_smithy_http::operation::{Operation, Request};
+    use aws_smithy_http::diagnostic::{AttemptRecord, DiagnosticContext};
+    use aws_smithy_http::metrics::AttemptOutcome;
+    use aws_smithy_http::operation::{InvocationId, Operation, Request};
+    use aws_smithy_http::result::RequestTimeoutError;
+    use aws_smithy_http::retry::RequestAttempts;
     use aws_smithy_types::tristate::TriState;
 
     use tower::{Service, ServiceBuilder, ServiceExt};
@@ -273,7 +309,77 @@ mod test {
         let err: SdkError<Box<dyn std::error::Error + 'static>> =
             svc.ready().await.unwrap().call(op).await.unwrap_err();
 
-        assert_eq!(format!("{:?}", err), "TimeoutError(RequestTimeoutError { kind: \"API call (all attempts including retries)\", duration: 250ms })");
+        assert_eq!(format!("{:?}", err), "TimeoutError(RequestTimeoutError { kind: \"API call (all attempts including retries)\", duration: 250ms, invocation_id: None, diagnostic_context: None })");
         assert_elapsed!(now, Duration::from_secs_f32(0.25));
     }
+
+    #[tokio::test]
+    async fn timeout_errors_carry_the_invocation_id() {
+        let mut req = Request::new(http::Request::new(SdkBody::empty()));
+        req.properties_mut()
+            .insert(InvocationId::new("invocation-id"));
+        let op = Operation::new(req, ());
+        let never_service: NeverService<_, (), _> = NeverService::new();
+        let timeout_config = aws_smithy_types::timeout::Api::new()
+            .with_call_attempt_timeout(TriState::Set(Duration::from_secs_f32(0.25)));
+        let sleep_impl: Option<Arc<dyn AsyncSleep>> = Some(Arc::new(TokioSleep::new()));
+        let timeout_service_params =
+            generate_timeout_service_params_from_timeout_config(&timeout_config, sleep_impl);
+        let svc = ServiceBuilder::new()
+            .layer(TimeoutLayer::new(timeout_service_params.api_call_attempt))
+            .service(never_service);
+
+        tokio::time::pause();
+        let err: SdkError<Box<dyn std::error::Error + 'static>> =
+            svc.oneshot(op).await.unwrap_err();
+        assert_eq!(
+            Some(InvocationId::new("invocation-id")),
+            err.invocation_id()
+        );
+    }
+
+    #[tokio::test]
+    async fn timeout_errors_record_the_interrupted_attempt() {
+        let mut req = Request::new(http::Request::new(SdkBody::empty()));
+        {
+            let mut properties = req.properties_mut();
+            properties.insert(RequestAttempts::new(2));
+            DiagnosticContext::record(&mut properties)
+                .attempts
+                .push(AttemptRecord::new(1, AttemptOutcome::DispatchFailure));
+        }
+        let op = Operation::new(req, ());
+        let never_service: NeverService<_, (), _> = NeverService::new();
+        let timeout_config = aws_smithy_types::timeout::Api::new()
+            .with_call_attempt_timeout(TriState::Set(Duration::from_secs_f32(0.25)));
+        let sleep_impl: Option<Arc<dyn AsyncSleep>> = Some(Arc::new(TokioSleep::new()));
+        let timeout_service_params =
+            generate_timeout_service_params_from_timeout_config(&timeout_config, sleep_impl);
+        let svc = ServiceBuilder::new()
+            .layer(TimeoutLayer::new(timeout_service_params.api_call_attempt))
+            .service(never_service);
+
+        tokio::time::pause();
+        let err: SdkError<Box<dyn std::error::Error + 'static>> =
+            svc.oneshot(op).await.unwrap_err();
+        let context = match &err {
+            SdkError::TimeoutError(err) => err
+                .downcast_ref::<RequestTimeoutError>()
+                .and_then(RequestTimeoutError::diagnostic_context)
+                .expect("the attempt history is attached"),
+            err => panic!("expected a timeout, got {:?}", err),
+        };
+        let outcomes: Vec<_> = context
+            .attempts
+            .iter()
+            .map(|record| (record.attempt, record.outcome))
+            .collect();
+        assert_eq!(
+            vec![
+                (1, AttemptOutcome::DispatchFailure),
+                (2, AttemptOutcome::Timeout)
+            ],
+            outcomes
+        );
+    }
 }
@@ -171,6 +171,273 @@ async fn end_to_end_retry_test() {
     assert_time_passed(initial, Duration::from_secs(7));
 }
 
+#[tokio::test]
+async fn retry_config_can_be_overridden_per_operation() {
+    use aws_smithy_types::retry::RetryConfig;
+
+    fn req() -> http::Request<SdkBody> {
+        http::Request::builder()
+            .body(SdkBody::from("request body"))
+            .unwrap()
+    }
+
+    fn err() -> http::Response<&'static str> {
+        http::Response::builder()
+            .status(500)
+            .body("response body")
+            .unwrap()
+    }
+    let conn = TestConnection::new(vec![(req(), err()), (req(), err()), (req(), err())]);
+    let client = Client::<TestConnection<_>, Identity>::new(conn.clone())
+        .with_retry_config(aws_smithy_client::retry::Config::default().with_base(|| 1_f64))
+        .with_sleep_impl(Arc::new(TokioSleep::new()));
+    tokio::time::pause();
+    let operation = test_operation().with_retry_config(RetryConfig::new().with_max_attempts(2));
+    client
+        .call(operation)
+        .await
+        .expect_err("all responses failed");
+    assert_eq!(conn.requests().len(), 2);
+}
+
+#[tokio::test]
+async fn retries_sleep_with_the_configured_sleep_impl() {
+    use aws_smithy_async::test_util::InstantSleep;
+
+    fn req() -> http::Request<SdkBody> {
+        http::Request::builder()
+            .body(SdkBody::from("request body"))
+            .unwrap()
+    }
+
+    fn err() -> http::Response<&'static str> {
+        http::Response::builder()
+            .status(500)
+            .body("response body")
+            .unwrap()
+    }
+    let conn = TestConnection::new(vec![(req(), err()), (req(), err()), (req(), err())]);
+    let sleep = InstantSleep::new();
+    let client = Client::<TestConnection<_>, Identity>::new(conn.clone())
+        .with_retry_config(aws_smithy_client::retry::Config::default().with_base(|| 1_f64))
+        .with_sleep_impl(Arc::new(sleep.clone()));
+    client
+        .call(test_operation())
+        .await
+        .expect_err("all responses failed");
+    assert_eq!(conn.requests().len(), 3);
+    assert_eq!(
+        sleep.logs(),
+        vec![Duration::from_secs(1), Duration::from_secs(2)]
+    );
+}
+
+#[test]
+fn client_runs_without_a_tokio_runtime() {
+    use aws_smithy_async::test_util::InstantSleep;
+
+    fn req() -> http::Request<SdkBody> {
+        http::Request::builder()
+            .body(SdkBody::from("request body"))
+            .unwrap()
+    }
+
+    fn response(status: u16) -> http::Response<&'static str> {
+        http::Response::builder()
+            .status(status)
+            .body("response body")
+            .unwrap()
+    }
+    let conn = TestConnection::new(vec![(req(), response(500)), (req(), response(200))]);
+    let sleep = InstantSleep::new();
+    let client = Client::<TestConnection<_>, Identity>::new(conn.clone())
+        .with_retry_config(aws_smithy_client::retry::Config::default().with_base(|| 1_f64))
+        .with_sleep_impl(Arc::new(sleep.clone()));
+    let resp =
+        futures_executor::block_on(client.call(test_operation())).expect("successful operation");
+    assert_eq!(resp, "Hello!");
+    assert_eq!(conn.requests().len(), 2);
+    assert_eq!(sleep.logs(), vec![Duration::from_secs(1)]);
+}
+
+/// Connector that counts attempts and tracks whether the in-flight request is still alive
+#[derive(Clone, Default)]
+struct TrackingConnector {
+    attempts: Arc<std::sync::atomic::AtomicUsize>,
+    in_flight: Arc<std::sync::atomic::AtomicUsize>,
+}
+
+struct InFlightGuard(Arc<std::sync::atomic::AtomicUsize>);
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, std::sync::atomic::Ordering::SeqCst);
+    }
+}
+
+impl TrackingConnector {
+    fn attempts(&self) -> usize {
+        self.attempts.load(std::sync::atomic::Ordering::SeqCst)
+    }
+
+    fn in_flight(&self) -> usize {
+        self.in_flight.load(std::sync::atomic::Ordering::SeqCst)
+    }
+
+    /// Build a client whose first attempt fails and whose later attempts never complete
+    fn client(&self) -> Client<aws_smithy_client::erase::DynConnector, Identity> {
+        let conn = self.clone();
+        aws_smithy_client::Builder::new()
+            .connector_fn(move |_req| {
+                let attempt = conn
+                    .attempts
+                    .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                conn.in_flight
+                    .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                let guard = InFlightGuard(conn.in_flight.clone());
+                async move {
+                    let _guard = guard;
+                    if attempt > 0 {
+                        aws_smithy_async::future::never::Never::new().await;
+                    }
+                    Ok(http::Response::builder()
+                        .status(500)
+                        .body(SdkBody::from("response body"))
+                        .unwrap())
+                }
+            })
+            .middleware(Identity::new())
+            .sleep_impl(Some(Arc::new(TokioSleep::new())))
+            .build()
+            .with_retry_config(aws_smithy_client::retry::Config::default().with_base(|| 1_f64))
+            .into_dyn_connector()
+    }
+}
+
+#[tokio::test]
+async fn deadline_aborts_in_flight_request() {
+    let conn = TrackingConnector::default();
+    let client = conn.client();
+    tokio::time::pause();
+    let initial = tokio::time::Instant::now();
+    let err = client
+        .call_with_deadline(
+            test_operation(),
+            std::time::Instant::now() + Duration::from_secs(5),
+        )
+        .await
+        .expect_err("the second attempt never completes");
+    assert!(matches!(err, SdkError::TimeoutError(_)), "{:?}", err);
+    assert_time_passed(initial, Duration::from_secs(5));
+    assert_eq!(conn.attempts(), 2);
+    assert_eq!(conn.in_flight(), 0);
+
+    // nothing keeps running after the deadline
+    tokio::time::sleep(Duration::from_secs(60)).await;
+    assert_eq!(conn.attempts(), 2);
+}
+
+#[tokio::test]
+async fn dropping_the_call_cancels_retry_sleeps() {
+    let conn = TrackingConnector::default();
+    let client = conn.client();
+    tokio::time::pause();
+    // the first attempt fails immediately, so this times out during the 1s retry backoff
+    tokio::time::timeout(Duration::from_millis(500), client.call(test_operation()))
+        .await
+        .expect_err("the call is still sleeping");
+    assert_eq!(conn.attempts(), 1);
+    assert_eq!(conn.in_flight(), 0);
+
+    tokio::time::sleep(Duration::from_secs(60)).await;
+    assert_eq!(conn.attempts(), 1);
+}
+
+#[tokio::test]
+async fn timeouts_can_be_overridden_per_operation() {
+    use aws_smithy_types::timeout;
+    use aws_smithy_types::tristate::TriState;
+
+    let conn = TrackingConnector::default();
+    let client = conn.client();
+    tokio::time::pause();
+    let initial = tokio::time::Instant::now();
+    let operation = test_operation()
+        .with_timeout(timeout::Api::new().with_call_timeout(TriState::Set(Duration::from_secs(3))));
+    let err = client
+        .call(operation)
+        .await
+        .expect_err("the second attempt never completes");
+    assert!(matches!(err, SdkError::TimeoutError(_)), "{:?}", err);
+    assert_time_passed(initial, Duration::from_secs(3));
+    assert_eq!(conn.attempts(), 2);
+}
+
+#[tokio::test]
+async fn interceptors_run_for_every_attempt() {
+    use aws_smithy_http::interceptor::Interceptor;
+    use aws_smithy_http::property_bag::PropertyBag;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[derive(Debug, Default)]
+    struct CountAttempts(AtomicUsize);
+
+    impl Interceptor for CountAttempts {
+        fn modify_before_transmit(
+            &self,
+            request: &mut http::Request<SdkBody>,
+            _properties: &PropertyBag,
+        ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+            let attempt = self.0.fetch_add(1, Ordering::SeqCst) + 1;
+            request
+                .headers_mut()
+                .insert("x-attempt", attempt.to_string().parse().unwrap());
+            Ok(())
+        }
+    }
+
+    fn req(attempt: &'static str) -> http::Request<SdkBody> {
+        http::Request::builder()
+            .uri("https://test-service.test-region.amazonaws.com/")
+            .header("x-attempt", attempt)
+            .body(SdkBody::from("request body"))
+            .unwrap()
+    }
+
+    fn response(status: u16) -> http::Response<&'static str> {
+        http::Response::builder()
+            .status(status)
+            .body("Hello!")
+            .unwrap()
+    }
+    let conn = TestConnection::new(vec![(req("1"), response(500)), (req("2"), response(200))]);
+    let client = Client::<TestConnection<_>, Identity>::new(conn.clone())
+        .with_retry_config(aws_smithy_client::retry::Config::default().with_base(|| 1_f64))
+        .with_sleep_impl(Arc::new(TokioSleep::new()));
+    tokio::time::pause();
+    client
+        .call(test_operation().with_interceptor(CountAttempts::default()))
+        .await
+        .expect("the second attempt succeeds");
+    conn.assert_requests_match(&[]);
+}
+
+#[tokio::test]
+async fn deadline_requires_a_sleep_impl() {
+    let conn = TestConnection::<&'static str>::new(vec![]);
+    let mut client = Client::<TestConnection<_>, Identity>::new(conn.clone());
+    client.set_sleep_impl(None);
+    let err = client
+        .call_with_deadline(
+            test_operation(),
+            std::time::Instant::now() + Duration::from_secs(5),
+        )
+        .await
+        .expect_err("no sleep impl");
+    assert!(matches!(err, SdkError::ConstructionFailure(_)), "{:?}", err);
+    assert!(conn.requests().is_empty());
+}
+
 /// Validate that time has passed with a 5ms tolerance
 ///
 /// This is to account for some non-determinism in the Tokio timer
@@ -181,3 +448,611 @@ fn assert_time_passed(initial: Instant, passed: Duration) {
         assert_eq!(delta, passed)
     }
 }
+
+#[tokio::test]
+async fn custom_layers_run_around_middleware() {
+    use tower::util::MapRequestLayer;
+
+    let conn = TestConnection::new(vec![(
+        http::Request::builder()
+            .body(SdkBody::from("request body"))
+            .unwrap(),
+        http::Response::builder()
+            .status(200)
+            .body("response body")
+            .unwrap(),
+    )]);
+    let client = Client::<TestConnection<_>, Identity>::new(conn.clone())
+        .with_sleep_impl(Arc::new(TokioSleep::new()))
+        .inner_layer(MapRequestLayer::new(|mut req: operation::Request| {
+            let outer = req
+                .http()
+                .headers()
+                .get("x-outer")
+                .expect("outer layer runs first")
+                .clone();
+            req.http_mut().headers_mut().insert("x-inner", outer);
+            req
+        }))
+        .layer(MapRequestLayer::new(|mut req: operation::Request| {
+            req.http_mut()
+                .headers_mut()
+                .insert("x-outer", http::HeaderValue::from_static("outer"));
+            req
+        }));
+    client
+        .call(test_operation())
+        .await
+        .expect("successful operation");
+    let requests = conn.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(
+        requests[0].actual.headers().get("x-inner").unwrap(),
+        "outer"
+    );
+}
+
+#[tokio::test]
+async fn metrics_are_emitted_per_attempt() {
+    use aws_smithy_http::metrics::{AttemptMetrics, AttemptOutcome, EmitMetrics, RetryMetrics};
+    use aws_smithy_types::retry::{ErrorKind, RetryKind};
+    use std::sync::Mutex;
+
+    #[derive(Clone, Debug, Default)]
+    struct RecordingMetrics {
+        attempts: Arc<Mutex<Vec<AttemptMetrics>>>,
+        retries: Arc<Mutex<Vec<RetryMetrics>>>,
+    }
+
+    impl EmitMetrics for RecordingMetrics {
+        fn emit_attempt(&self, metrics: &AttemptMetrics) {
+            self.attempts.lock().unwrap().push(metrics.clone());
+        }
+
+        fn emit_retry(&self, metrics: &RetryMetrics) {
+            self.retries.lock().unwrap().push(metrics.clone());
+        }
+    }
+
+    fn req() -> http::Request<SdkBody> {
+        http::Request::builder()
+            .body(SdkBody::from("request body"))
+            .unwrap()
+    }
+    let conn = TestConnection::new(vec![
+        (
+            req(),
+            http::Response::builder()
+                .status(500)
+                .body("response body")
+                .unwrap(),
+        ),
+        (
+            req(),
+            http::Response::builder()
+                .status(200)
+                .body("response body")
+                .unwrap(),
+        ),
+    ]);
+    let metrics = RecordingMetrics::default();
+    let client = Client::<TestConnection<_>, Identity>::new(conn.clone())
+        .with_retry_config(aws_smithy_client::retry::Config::default().with_base(|| 1_f64))
+        .with_sleep_impl(Arc::new(TokioSleep::new()))
+        .with_metrics_emitter(metrics.clone());
+    tokio::time::pause();
+    client
+        .call(test_operation())
+        .await
+        .expect("successful operation");
+
+    let attempts = metrics.attempts.lock().unwrap();
+    assert_eq!(attempts.len(), 2);
+    assert_eq!(attempts[0].attempt, 1);
+    assert_eq!(attempts[0].outcome, AttemptOutcome::ServiceError);
+    assert_eq!(attempts[0].request_body_size, Some(12));
+    assert_eq!(
+        attempts[0].response_status,
+        Some(http::StatusCode::INTERNAL_SERVER_ERROR)
+    );
+    assert_eq!(attempts[1].attempt, 2);
+    assert_eq!(attempts[1].outcome, AttemptOutcome::Success);
+
+    let retries = metrics.retries.lock().unwrap();
+    assert_eq!(retries.len(), 2);
+    assert_eq!(
+        retries[0].retry_kind,
+        RetryKind::Error(ErrorKind::ThrottlingError)
+    );
+    assert_eq!(retries[0].backoff, Some(Duration::from_secs(1)));
+    assert_eq!(retries[1].retry_kind, RetryKind::Unnecessary);
+    assert_eq!(retries[1].backoff, None);
+}
+
+#[tokio::test]
+async fn metrics_carry_operation_metadata() {
+    use aws_smithy_http::metrics::{AttemptMetrics, EmitMetrics, RetryMetrics};
+    use std::sync::Mutex;
+
+    #[derive(Clone, Debug, Default)]
+    struct RecordingMetrics {
+        attempts: Arc<Mutex<Vec<AttemptMetrics>>>,
+        retries: Arc<Mutex<Vec<RetryMetrics>>>,
+    }
+
+    impl EmitMetrics for RecordingMetrics {
+        fn emit_attempt(&self, metrics: &AttemptMetrics) {
+            self.attempts.lock().unwrap().push(metrics.clone());
+        }
+
+        fn emit_retry(&self, metrics: &RetryMetrics) {
+            self.retries.lock().unwrap().push(metrics.clone());
+        }
+    }
+
+    let conn = TestConnection::new(vec![(
+        http::Request::builder()
+            .body(SdkBody::from("request body"))
+            .unwrap(),
+        http::Response::builder()
+            .status(200)
+            .body("response body")
+            .unwrap(),
+    )]);
+    let metrics = RecordingMetrics::default();
+    let client = Client::<TestConnection<_>, Identity>::new(conn)
+        .with_sleep_impl(Arc::new(TokioSleep::new()))
+        .with_metrics_emitter(metrics.clone());
+    client
+        .call(test_operation().with_metadata(operation::Metadata::new("TestOperation", "test")))
+        .await
+        .expect("successful operation");
+
+    let expected = Some(operation::Metadata::new("TestOperation", "test"));
+    assert_eq!(metrics.attempts.lock().unwrap()[0].metadata, expected);
+    assert_eq!(metrics.retries.lock().unwrap()[0].metadata, expected);
+}
+
+#[tokio::test]
+async fn response_limits_can_be_overridden_per_operation() {
+    use aws_smithy_http::response::{ResponseBodyTooLarge, ResponseLimits};
+    use aws_smithy_http::test_util::ParseBody;
+    use bytes::Bytes;
+
+    fn req() -> http::Request<SdkBody> {
+        http::Request::builder()
+            .body(SdkBody::from("request body"))
+            .unwrap()
+    }
+
+    fn ok() -> http::Response<&'static str> {
+        http::Response::builder()
+            .status(200)
+            .body("response body")
+            .unwrap()
+    }
+
+    fn operation() -> Operation<ParseBody, ()> {
+        let req = operation::Request::new(
+            http::Request::builder()
+                .uri("https://test-service.test-region.amazonaws.com/")
+                .body(SdkBody::from("request body"))
+                .unwrap(),
+        );
+        Operation::new(req, ParseBody)
+    }
+
+    let conn = TestConnection::new(vec![(req(), ok()), (req(), ok())]);
+    let client = Client::<TestConnection<_>, Identity>::new(conn.clone())
+        .with_sleep_impl(Arc::new(TokioSleep::new()))
+        .with_response_limits(ResponseLimits::new().with_max_body_size(4));
+
+    match client.call(operation()).await {
+        Err(SdkError::ResponseError { err, .. }) => {
+            assert!(err.downcast_ref::<ResponseBodyTooLarge>().is_some())
+        }
+        other => panic!("expected a response error, got {:?}", other),
+    }
+
+    let mut operation = operation();
+    operation.properties_mut().insert(ResponseLimits::new());
+    let body = client
+        .call(operation)
+        .await
+        .expect("no limit on this operation");
+    assert_eq!(Bytes::from_static(b"response body"), body);
+}
+
+/// Connector that is only ready after being polled `pending_polls` times for each request, and
+/// that fails any request it is called with before it is ready
+#[derive(Clone)]
+struct SlowToReadyConnector {
+    pending_polls: usize,
+    state: Arc<std::sync::Mutex<SlowToReadyState>>,
+}
+
+#[derive(Default)]
+struct SlowToReadyState {
+    polls: usize,
+    ready: bool,
+    statuses: Vec<u16>,
+}
+
+impl tower::Service<http::Request<SdkBody>> for SlowToReadyConnector {
+    type Response = http::Response<SdkBody>;
+    type Error = aws_smithy_http::result::ConnectorError;
+    type Future = std::future::Ready<Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(
+        &mut self,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        let mut state = self.state.lock().unwrap();
+        if state.polls < self.pending_polls {
+            state.polls += 1;
+            cx.waker().wake_by_ref();
+            return std::task::Poll::Pending;
+        }
+        state.ready = true;
+        std::task::Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, _req: http::Request<SdkBody>) -> Self::Future {
+        let mut state = self.state.lock().unwrap();
+        assert!(state.ready, "connector was called before it was ready");
+        state.ready = false;
+        state.polls = 0;
+        let status = state.statuses.remove(0);
+        std::future::ready(Ok(http::Response::builder()
+            .status(status)
+            .body(SdkBody::empty())
+            .unwrap()))
+    }
+}
+
+#[tokio::test]
+async fn connector_readiness_is_respected_for_every_attempt() {
+    let state = Arc::new(std::sync::Mutex::new(SlowToReadyState {
+        statuses: vec![500, 200],
+        ..Default::default()
+    }));
+    let conn = SlowToReadyConnector {
+        pending_polls: 3,
+        state: state.clone(),
+    };
+    let client = Client::<SlowToReadyConnector, Identity>::new(conn)
+        .with_retry_config(aws_smithy_client::retry::Config::default().with_base(|| 1_f64))
+        .with_sleep_impl(Arc::new(TokioSleep::new()));
+    tokio::time::pause();
+    let resp = client
+        .call(test_operation())
+        .await
+        .expect("successful operation");
+    assert_eq!(resp, "Hello!");
+    // Both the initial attempt and the retry were sent
+    assert!(state.lock().unwrap().statuses.is_empty());
+}
+
+/// Identity middleware that counts how many times it has been used to build a service
+#[derive(Clone, Default)]
+struct CountingLayer(Arc<std::sync::atomic::AtomicUsize>);
+
+impl<S> tower::Layer<S> for CountingLayer {
+    type Service = S;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        self.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        inner
+    }
+}
+
+#[tokio::test]
+async fn middleware_stack_is_shared_between_clones() {
+    let conn = TestConnection::new(
+        (0..3)
+            .map(|_| {
+                (
+                    http::Request::builder()
+                        .uri("https://www.example.com")
+                        .body(SdkBody::empty())
+                        .unwrap(),
+                    http::Response::builder()
+                        .status(200)
+                        .body("response body")
+                        .unwrap(),
+                )
+            })
+            .collect(),
+    );
+    let layer = CountingLayer::default();
+    let client = aws_smithy_client::Builder::new()
+        .connector(conn.clone())
+        .middleware(layer.clone())
+        .sleep_impl(Some(Arc::new(TokioSleep::new())))
+        .build();
+    let cloned = client.clone();
+    client.call(test_operation()).await.expect("success");
+    cloned.call(test_operation()).await.expect("success");
+    client.call(test_operation()).await.expect("success");
+    assert_eq!(3, conn.requests().len());
+    assert_eq!(1, layer.0.load(std::sync::atomic::Ordering::SeqCst));
+}
+
+/// Middleware that records the invocation ID of every attempt
+#[derive(Clone, Default)]
+struct RecordInvocationIds(Arc<std::sync::Mutex<Vec<Option<operation::InvocationId>>>>);
+
+impl aws_smithy_http::middleware::MapRequest for RecordInvocationIds {
+    type Error = std::convert::Infallible;
+
+    fn apply(&self, request: operation::Request) -> Result<operation::Request, Self::Error> {
+        let id = request
+            .properties()
+            .get::<operation::InvocationId>()
+            .cloned();
+        self.0.lock().unwrap().push(id);
+        Ok(request)
+    }
+}
+
+#[tokio::test]
+async fn invocation_id_is_stable_across_retries() {
+    let req = || {
+        http::Request::builder()
+            .body(SdkBody::from("request body"))
+            .unwrap()
+    };
+    let conn = TestConnection::new(vec![
+        (
+            req(),
+            http::Response::builder()
+                .status(500)
+                .body("response body")
+                .unwrap(),
+        ),
+        (
+            req(),
+            http::Response::builder()
+                .status(200)
+                .body("response body")
+                .unwrap(),
+        ),
+    ]);
+    let recorder = RecordInvocationIds::default();
+    let client = aws_smithy_client::Builder::new()
+        .connector(conn)
+        .middleware(
+            aws_smithy_http_tower::map_request::MapRequestLayer::for_mapper(recorder.clone()),
+        )
+        .sleep_impl(Some(Arc::new(TokioSleep::new())))
+        .build()
+        .with_retry_config(aws_smithy_client::retry::Config::default().with_base(|| 1_f64));
+    tokio::time::pause();
+    let resp = client
+        .call_raw(test_operation())
+        .await
+        .expect("successful operation");
+    let invocation_id = resp.invocation_id().expect("invocation ID is set");
+    assert_eq!(invocation_id.as_str().len(), 36);
+    assert_eq!(
+        recorder.0.lock().unwrap().as_slice(),
+        &[Some(invocation_id.clone()), Some(invocation_id)]
+    );
+}
+
+#[tokio::test]
+async fn client_can_be_composed_as_a_tower_service() {
+    use tower::ServiceExt;
+
+    fn req() -> http::Request<SdkBody> {
+        http::Request::builder()
+            .body(SdkBody::from("request body"))
+            .unwrap()
+    }
+
+    fn response(status: u16) -> http::Response<&'static str> {
+        http::Response::builder()
+            .status(status)
+            .body("response body")
+            .unwrap()
+    }
+    let conn = TestConnection::new(vec![(req(), response(500)), (req(), response(200))]);
+    let client = Client::<TestConnection<_>, Identity>::new(conn.clone())
+        .with_retry_config(aws_smithy_client::retry::Config::default().with_base(|| 1_f64))
+        .with_sleep_impl(Arc::new(TokioSleep::new()));
+    tokio::time::pause();
+
+    let calls = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let svc = tower::ServiceBuilder::new()
+        .map_response({
+            let calls = calls.clone();
+            move |response: aws_smithy_http::result::SdkSuccess<String>| {
+                calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                response.parsed
+            }
+        })
+        .service(client.into_service());
+    let parsed = svc
+        .oneshot(test_operation())
+        .await
+        .expect("successful operation");
+    assert_eq!("Hello!", parsed);
+    // Retries happen inside the service, so outer layers see a single response
+    assert_eq!(2, conn.requests().len());
+    assert_eq!(1, calls.load(std::sync::atomic::Ordering::SeqCst));
+}
+
+#[tokio::test]
+async fn timing_info_is_recorded_for_each_attempt() {
+    let req = || {
+        http::Request::builder()
+            .body(SdkBody::from("request body"))
+            .unwrap()
+    };
+    let conn = TestConnection::new(vec![(
+        req(),
+        http::Response::builder()
+            .status(200)
+            .body("response body")
+            .unwrap(),
+    )]);
+    let client = Client::<TestConnection<_>, Identity>::new(conn)
+        .with_sleep_impl(Arc::new(TokioSleep::new()));
+    let resp = client
+        .call_raw(test_operation())
+        .await
+        .expect("successful operation");
+    let timing = resp.timing_info().expect("timing info is recorded");
+    assert!(timing.time_to_first_byte.is_some());
+    assert!(timing.body_read.is_some());
+    // This client has no credentials, signing, or connection timing
+    assert_eq!(timing.credentials, None);
+    assert_eq!(timing.signing, None);
+    assert_eq!(timing.connect, None);
+}
+
+#[tokio::test]
+async fn diagnostic_includes_every_attempt() {
+    let req = || {
+        http::Request::builder()
+            .body(SdkBody::from("request body"))
+            .unwrap()
+    };
+    let response = || {
+        http::Response::builder()
+            .status(503)
+            .body("response body")
+            .unwrap()
+    };
+    let conn = TestConnection::new(vec![
+        (req(), response()),
+        (req(), response()),
+        (req(), response()),
+    ]);
+    let client = Client::<TestConnection<_>, Identity>::new(conn)
+        .with_retry_config(aws_smithy_client::retry::Config::default().with_base(|| 1_f64))
+        .with_sleep_impl(Arc::new(TokioSleep::new()));
+    tokio::time::pause();
+    let err = client
+        .call(test_operation())
+        .await
+        .expect_err("all attempts fail");
+    let diagnostic = err.diagnostic();
+    assert!(
+        diagnostic.starts_with("Service error: OperationError\n"),
+        "{}",
+        diagnostic
+    );
+    assert!(
+        diagnostic.contains("Endpoint: https://test-service.test-region.amazonaws.com/\n"),
+        "{}",
+        diagnostic
+    );
+    let attempts: Vec<_> = diagnostic
+        .lines()
+        .skip_while(|line| *line != "Attempts:")
+        .skip(1)
+        .collect();
+    assert_eq!(attempts.len(), 3, "{}", diagnostic);
+    for (i, attempt) in attempts.iter().enumerate() {
+        assert!(
+            attempt.starts_with(&format!(
+                "  {}: 503 Service Unavailable, service error: OperationError",
+                i + 1
+            )),
+            "{}",
+            diagnostic
+        );
+        assert_eq!(attempt.contains("retried after"), i < 2, "{}", diagnostic);
+    }
+}
+
+#[tokio::test]
+async fn smithy_middleware_adds_user_agent_and_retries() {
+    use aws_smithy_client::middleware::{DefaultSmithyMiddleware, DEFAULT_USER_AGENT};
+
+    fn req() -> http::Request<SdkBody> {
+        http::Request::builder()
+            .body(SdkBody::from("request body"))
+            .unwrap()
+    }
+    fn response(status: u16) -> http::Response<&'static str> {
+        http::Response::builder()
+            .status(status)
+            .body("response body")
+            .unwrap()
+    }
+    let conn = TestConnection::new(vec![(req(), response(500)), (req(), response(200))]);
+    let client = Client::<TestConnection<_>, DefaultSmithyMiddleware>::new(conn.clone())
+        .with_retry_config(aws_smithy_client::retry::Config::default().with_base(|| 1_f64))
+        .with_sleep_impl(Arc::new(TokioSleep::new()));
+    tokio::time::pause();
+    let resp = client
+        .call(test_operation())
+        .await
+        .expect("the second attempt succeeds");
+    assert_eq!(resp, "Hello!");
+    let requests = conn.requests();
+    assert_eq!(requests.len(), 2);
+    for request in requests.iter() {
+        assert_eq!(
+            request.actual.headers()[http::header::USER_AGENT],
+            DEFAULT_USER_AGENT
+        );
+    }
+}
+
+#[tokio::test]
+async fn call_all_yields_results_in_input_order_with_bounded_concurrency() {
+    use aws_smithy_types::retry::RetryConfig;
+    use futures_util::StreamExt;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    let in_flight = Arc::new(AtomicUsize::new(0));
+    let max_in_flight = Arc::new(AtomicUsize::new(0));
+    let client: Client<aws_smithy_client::erase::DynConnector, Identity> = {
+        let (in_flight, max_in_flight) = (in_flight.clone(), max_in_flight.clone());
+        aws_smithy_client::Builder::new()
+            .connector_fn(move |req| {
+                let (in_flight, max_in_flight) = (in_flight.clone(), max_in_flight.clone());
+                let current = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                max_in_flight.fetch_max(current, Ordering::SeqCst);
+                let index: u64 = req.headers()["x-index"].to_str().unwrap().parse().unwrap();
+                async move {
+                    // later operations complete first
+                    tokio::time::sleep(Duration::from_secs(10 - index)).await;
+                    in_flight.fetch_sub(1, Ordering::SeqCst);
+                    // `u64::is_multiple_of` requires Rust 1.87, which is newer than the MSRV
+                    #[allow(unknown_lints, clippy::manual_is_multiple_of)]
+                    let status = if index % 2 == 0 { 200 } else { 500 };
+                    Ok(http::Response::builder()
+                        .status(status)
+                        .body(SdkBody::from(index.to_string()))
+                        .unwrap())
+                }
+            })
+            .middleware(Identity::new())
+            .sleep_impl(Some(Arc::new(TokioSleep::new())))
+            .build()
+            .into_dyn_connector()
+    };
+    let operations = (0..5).map(|index| {
+        let operation = test_operation().with_retry_config(RetryConfig::new().with_max_attempts(1));
+        let (mut request, parts) = operation.into_request_response();
+        request
+            .http_mut()
+            .headers_mut()
+            .insert("x-index", index.to_string().parse().unwrap());
+        Operation::from_parts(request, parts)
+    });
+    tokio::time::pause();
+    let mut results = client.call_all(operations, 2);
+    let mut succeeded = vec![];
+    while let Some(result) = results.next().await {
+        succeeded.push(result.is_ok());
+    }
+    assert_eq!(succeeded, vec![true, false, true, false, true]);
+    assert_eq!(max_in_flight.load(Ordering::SeqCst), 2);
+    assert_eq!(results.summary().succeeded, 3);
+    assert_eq!(results.summary().failed, vec![1, 3]);
+}
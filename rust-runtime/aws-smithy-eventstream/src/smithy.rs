@@ -7,6 +7,13 @@ use crate::error::Error;
 use crate::frame::{Header, HeaderValue, Message};
 use crate::str_bytes::StrBytes;
 use aws_smithy_types::{Blob, DateTime};
+use bytes::Bytes;
+
+/// `:event-type` of the message that carries the operation input of an RPC-bound event stream
+pub const INITIAL_REQUEST_EVENT_TYPE: &str = "initial-request";
+
+/// `:event-type` of the message that carries the operation output of an RPC-bound event stream
+pub const INITIAL_RESPONSE_EVENT_TYPE: &str = "initial-response";
 
 macro_rules! expect_shape_fn {
     (fn $fn_name:ident[$val_typ:ident] -> $result_typ:ident { $val_name:ident -> $val_expr:expr }) => {
@@ -119,9 +126,43 @@ pub fn parse_response_headers(message: &Message) -> Result<ResponseHeaders<'_>,
     })
 }
 
+/// Creates an `initial-request` [`Message`] with the given content type and serialized payload.
+///
+/// For protocols that bind the operation input to the event stream itself (rather than to the
+/// HTTP request), this message must be sent before any of the input stream's events.
+pub fn initial_request_message(content_type: &'static str, payload: impl Into<Bytes>) -> Message {
+    Message::new(payload)
+        .add_header(Header::new(
+            ":message-type",
+            HeaderValue::String("event".into()),
+        ))
+        .add_header(Header::new(
+            ":event-type",
+            HeaderValue::String(INITIAL_REQUEST_EVENT_TYPE.into()),
+        ))
+        .add_header(Header::new(
+            ":content-type",
+            HeaderValue::String(content_type.into()),
+        ))
+}
+
+/// Returns true if the given [`Message`] has an `:event-type` of `initial-response`.
+pub fn is_initial_response(message: &Message) -> bool {
+    message
+        .headers()
+        .iter()
+        .find(|h| h.name().as_str() == ":event-type")
+        .and_then(|h| h.value().as_string().ok())
+        .map(|event_type| event_type.as_str() == INITIAL_RESPONSE_EVENT_TYPE)
+        .unwrap_or(false)
+}
+
 #[cfg(test)]
 mod tests {
-    use super::parse_response_headers;
+    use super::{
+        initial_request_message, is_initial_response, parse_response_headers,
+        INITIAL_REQUEST_EVENT_TYPE,
+    };
     use crate::frame::{Header, HeaderValue, Message};
 
     #[test]
@@ -240,4 +281,36 @@ mod tests {
             error
         );
     }
+
+    #[test]
+    fn initial_request() {
+        let message = initial_request_message("application/json", &b"{}"[..]);
+        let parsed = parse_response_headers(&message).unwrap();
+        assert_eq!(INITIAL_REQUEST_EVENT_TYPE, parsed.smithy_type.as_str());
+        assert_eq!(Some("application/json"), parsed.content_type());
+        assert_eq!("event", parsed.message_type.as_str());
+        assert_eq!(&b"{}"[..], &message.payload()[..]);
+        assert!(!is_initial_response(&message));
+    }
+
+    #[test]
+    fn initial_response() {
+        let message = Message::new(&b"{}"[..])
+            .add_header(Header::new(
+                ":event-type",
+                HeaderValue::String("initial-response".into()),
+            ))
+            .add_header(Header::new(
+                ":message-type",
+                HeaderValue::String("event".into()),
+            ));
+        assert!(is_initial_response(&message));
+
+        let message = Message::new(&b"{}"[..]).add_header(Header::new(
+            ":event-type",
+            HeaderValue::String("Foo".into()),
+        ));
+        assert!(!is_initial_response(&message));
+        assert!(!is_initial_response(&Message::new(&b"{}"[..])));
+    }
 }
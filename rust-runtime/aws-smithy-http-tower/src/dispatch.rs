@@ -3,13 +3,28 @@
  * SPDX-License-Identifier: Apache-2.0.
  */
 
+use crate::trace::RedactedRequest;
 use crate::SendOperationError;
+use aws_smithy_async::rt::sleep::{AsyncSleep, Sleep};
+use aws_smithy_async::time::Instant;
 use aws_smithy_http::body::SdkBody;
+use aws_smithy_http::diagnostic::DiagnosticContext;
+use aws_smithy_http::interceptor::{Interceptor, Interceptors};
 use aws_smithy_http::operation;
+use aws_smithy_http::operation::{ExpectContinue, InvocationId};
 use aws_smithy_http::result::ConnectorError;
+use aws_smithy_http::timing::{ConnectTiming, TimingInfo};
+use bytes::Bytes;
+use http::header::{HeaderValue, CONTENT_LENGTH, EXPECT, TRANSFER_ENCODING};
+use http::{HeaderMap, Method};
+use http_body::combinators::BoxBody;
+use http_body::{Body, SizeHint};
+use pin_project::pin_project;
+use std::fmt::{Debug, Formatter};
 use std::future::Future;
 use std::pin::Pin;
-use std::task::{Context, Poll};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
 use tower::{Layer, Service};
 use tracing::trace;
 
@@ -41,20 +56,279 @@ where
     }
 
     fn call(&mut self, req: operation::Request) -> Self::Future {
-        let (req, property_bag) = req.into_parts();
+        let (mut req, property_bag) = req.into_parts();
+        set_framing_headers(&mut req);
+        let hold_back = {
+            let properties = property_bag.acquire();
+            match properties.get::<ExpectContinue>() {
+                Some(expect_continue) => set_expect_continue(
+                    &mut req,
+                    expect_continue,
+                    properties.get::<ExpectContinueSleep>(),
+                ),
+                None => None,
+            }
+        };
+        let interceptors = property_bag.acquire().get::<Interceptors>().cloned();
+        let invocation_id = property_bag.acquire().get::<InvocationId>().cloned();
         let mut inner = self.inner.clone();
         let future = async move {
-            trace!(request = ?req);
-            inner
-                .call(req)
-                .await
-                .map(|resp| operation::Response::from_parts(resp, property_bag))
-                .map_err(|e| SendOperationError::RequestDispatchError(e.into()))
+            if let Some(interceptors) = &interceptors {
+                let properties = property_bag.acquire();
+                for interceptor in interceptors.iter() {
+                    interceptor
+                        .modify_before_transmit(&mut req, &properties)
+                        .map_err(SendOperationError::RequestConstructionError)?;
+                }
+            }
+            trace!(request = ?RedactedRequest(&req), "dispatching request");
+            DiagnosticContext::record(&mut property_bag.acquire_mut()).endpoint =
+                Some(endpoint(req.uri()));
+            let dispatched_at = Instant::now();
+            let resp = HoldBack {
+                response: inner.call(req),
+                hold_back,
+            }
+            .await
+            .map_err(|e| {
+                SendOperationError::RequestDispatchError(e.into().with_invocation_id(invocation_id))
+            })?;
+            if let Some(interceptors) = &interceptors {
+                let properties = property_bag.acquire();
+                for interceptor in interceptors.iter() {
+                    interceptor.read_after_transmit(&resp, &properties);
+                }
+            }
+            {
+                let mut properties = property_bag.acquire_mut();
+                let timing = TimingInfo::record(&mut properties);
+                timing.time_to_first_byte = Some(dispatched_at.elapsed());
+                timing.connect = resp
+                    .extensions()
+                    .get::<ConnectTiming>()
+                    .map(|connect| connect.connect_time_since(dispatched_at));
+            }
+            Ok::<_, SendOperationError>(operation::Response::from_parts(resp, property_bag))
         };
         Box::pin(future)
     }
 }
 
+/// Returns `uri` without its query string, which may contain credentials (e.g. for presigned
+/// requests)
+fn endpoint(uri: &http::Uri) -> String {
+    match (uri.scheme_str(), uri.authority()) {
+        (Some(scheme), Some(authority)) => format!("{}://{}{}", scheme, authority, uri.path()),
+        _ => uri.path().to_string(),
+    }
+}
+
+/// Sets `Content-Length` for bodies of a known size, and `Transfer-Encoding: chunked` otherwise.
+///
+/// Nothing is changed if either header was already set (e.g. by the serializer, so that it's
+/// included in the signature). Empty bodies and bodies of unknown length only get framing headers
+/// for methods that are expected to have a body.
+fn set_framing_headers(request: &mut http::Request<SdkBody>) {
+    let headers = request.headers();
+    if headers.contains_key(CONTENT_LENGTH) || headers.contains_key(TRANSFER_ENCODING) {
+        return;
+    }
+    let expects_body = [Method::POST, Method::PUT, Method::PATCH].contains(request.method());
+    match request.body().content_length() {
+        Some(0) | None if !expects_body => {}
+        Some(length) => {
+            request
+                .headers_mut()
+                .insert(CONTENT_LENGTH, HeaderValue::from(length));
+        }
+        None => {
+            request
+                .headers_mut()
+                .insert(TRANSFER_ENCODING, HeaderValue::from_static("chunked"));
+        }
+    }
+}
+
+/// Sleep implementation used to hold back bodies sent with `Expect: 100-continue`
+///
+/// Insert into the property bag of an operation along with an [`ExpectContinue`] that has a
+/// [timeout](ExpectContinue::with_timeout). Without it, only the header is sent.
+#[derive(Clone, Debug)]
+pub struct ExpectContinueSleep(Arc<dyn AsyncSleep>);
+
+impl ExpectContinueSleep {
+    /// Creates timers for holding back bodies with `sleep`
+    pub fn new(sleep: Arc<dyn AsyncSleep>) -> Self {
+        Self(sleep)
+    }
+}
+
+/// Sends `Expect: 100-continue` for bodies of at least [`ExpectContinue::threshold`] bytes (or of
+/// unknown length).
+///
+/// If the [`ExpectContinue`] has a timeout and `sleep` is set, connectors that support it can hold
+/// the body back with the [`ContinueSignal`] in the request extensions. The body is then sent once
+/// the connector signals that `100 Continue` arrived, or the returned timer elapses, whichever
+/// comes first. A final response (e.g.
+/// `403 Forbidden`) that arrives earlier completes the request without sending the body.
+fn set_expect_continue(
+    request: &mut http::Request<SdkBody>,
+    expect_continue: &ExpectContinue,
+    sleep: Option<&ExpectContinueSleep>,
+) -> Option<(Sleep, ContinueSignal)> {
+    if request.headers().contains_key(EXPECT) {
+        return None;
+    }
+    match request.body().content_length() {
+        Some(length) if length == 0 || length < expect_continue.threshold() => return None,
+        _ => {}
+    }
+    request
+        .headers_mut()
+        .insert(EXPECT, HeaderValue::from_static("100-continue"));
+    let timer = match (expect_continue.timeout(), sleep) {
+        (Some(timeout), Some(sleep)) => sleep.0.sleep(timeout),
+        _ => return None,
+    };
+    let release = ContinueSignal::default();
+    let body = std::mem::replace(request.body_mut(), SdkBody::taken());
+    *request.body_mut() = SdkBody::from_dyn(BoxBody::new(ExpectContinueBody {
+        release: Some(release.clone()),
+        inner: body,
+    }));
+    request.extensions_mut().insert(release.clone());
+    Some((timer, release))
+}
+
+/// Releases a body held back by `Expect: 100-continue`
+///
+/// When a request is sent with `Expect: 100-continue` and a timeout, this is inserted into the
+/// extensions of the `http::Request` passed to the connector. The body is only held back if the
+/// connector calls [`hold`](ContinueSignal::hold) before sending the request, so connectors that
+/// don't support `Expect: 100-continue` send it right away. Connectors that hold the body back and
+/// observe the interim `100 Continue` response should call [`proceed`](ContinueSignal::proceed)
+/// when it arrives so that the body is sent right away rather than when the timeout elapses.
+#[derive(Clone, Default)]
+pub struct ContinueSignal(Arc<Mutex<ReleaseState>>);
+
+impl Debug for ContinueSignal {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ContinueSignal").finish()
+    }
+}
+
+#[derive(Default)]
+struct ReleaseState {
+    held: bool,
+    released: bool,
+    waker: Option<Waker>,
+}
+
+impl ContinueSignal {
+    /// Holds the body back until [`proceed`](ContinueSignal::proceed) is called or the timeout
+    /// elapses
+    ///
+    /// Call this before sending the request, from connectors that either observe the interim
+    /// `100 Continue` response or return a final response that arrives before the body is sent.
+    pub fn hold(&self) {
+        self.0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .held = true;
+    }
+
+    /// Sends the held back body
+    pub fn proceed(&self) {
+        let mut state = self
+            .0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        state.released = true;
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    }
+
+    fn poll_released(&self, cx: &mut Context<'_>) -> Poll<()> {
+        let mut state = self
+            .0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if state.released || !state.held {
+            Poll::Ready(())
+        } else {
+            state.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+/// Future that drives the timer of a held back body alongside the `response` future
+#[pin_project]
+struct HoldBack<F> {
+    #[pin]
+    response: F,
+    hold_back: Option<(Sleep, ContinueSignal)>,
+}
+
+impl<F: Future> Future for HoldBack<F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        if let Some((timer, release)) = this.hold_back {
+            if Pin::new(timer).poll(cx).is_ready() {
+                release.proceed();
+                *this.hold_back = None;
+            }
+        }
+        this.response.poll(cx)
+    }
+}
+
+/// Body that waits for `release` before yielding any data from `inner`
+#[pin_project]
+struct ExpectContinueBody {
+    release: Option<ContinueSignal>,
+    #[pin]
+    inner: SdkBody,
+}
+
+impl Body for ExpectContinueBody {
+    type Data = Bytes;
+    type Error = aws_smithy_http::body::Error;
+
+    fn poll_data(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Self::Data, Self::Error>>> {
+        let this = self.project();
+        if let Some(release) = this.release {
+            if release.poll_released(cx).is_pending() {
+                return Poll::Pending;
+            }
+            trace!("sending body after waiting for 100-continue");
+            *this.release = None;
+        }
+        this.inner.poll_data(cx)
+    }
+
+    fn poll_trailers(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Option<HeaderMap>, Self::Error>> {
+        self.project().inner.poll_trailers(cx)
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.inner.size_hint()
+    }
+}
+
 #[derive(Clone, Default)]
 #[non_exhaustive]
 pub struct DispatchLayer;
@@ -75,3 +349,408 @@ where
         DispatchService { inner }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::dispatch::{ContinueSignal, DispatchLayer, ExpectContinueSleep};
+    use crate::SendOperationError;
+    use aws_smithy_async::rt::sleep::{AsyncSleep, Sleep, TokioSleep};
+    use aws_smithy_http::body::SdkBody;
+    use aws_smithy_http::interceptor::{Interceptor, Interceptors, SharedInterceptor};
+    use aws_smithy_http::operation;
+    use aws_smithy_http::operation::{ExpectContinue, InvocationId};
+    use aws_smithy_http::property_bag::PropertyBag;
+    use aws_smithy_http::result::ConnectorError;
+    use bytes::Bytes;
+    use http::{HeaderMap, Method};
+    use http_body::combinators::BoxBody;
+    use http_body::Body;
+    use std::error::Error;
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::task::{Context, Poll};
+    use std::time::Duration;
+    use tower::{Layer, Service, ServiceExt};
+
+    /// Dispatches `request` to a connector that echoes the request headers back in the response
+    async fn dispatched_headers(request: http::Request<SdkBody>) -> HeaderMap {
+        let connector = tower::service_fn(|request: http::Request<SdkBody>| async move {
+            let mut response = http::Response::new(SdkBody::empty());
+            *response.headers_mut() = request.headers().clone();
+            Ok::<_, ConnectorError>(response)
+        });
+        let mut svc = DispatchLayer::new().layer(connector);
+        let response = svc
+            .ready()
+            .await
+            .unwrap()
+            .call(operation::Request::new(request))
+            .await
+            .unwrap();
+        response.http().headers().clone()
+    }
+
+    fn request(method: Method, body: SdkBody) -> http::Request<SdkBody> {
+        http::Request::builder()
+            .method(method)
+            .uri("https://www.example.com")
+            .body(body)
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn known_length_sets_content_length() {
+        let headers = dispatched_headers(request(Method::PUT, SdkBody::from("hello"))).await;
+        assert_eq!(headers["content-length"], "5");
+        assert!(!headers.contains_key("transfer-encoding"));
+    }
+
+    #[tokio::test]
+    async fn empty_body() {
+        let headers = dispatched_headers(request(Method::GET, SdkBody::empty())).await;
+        assert!(headers.is_empty());
+
+        let headers = dispatched_headers(request(Method::POST, SdkBody::empty())).await;
+        assert_eq!(headers["content-length"], "0");
+    }
+
+    /// A body that doesn't know its length ahead of time
+    struct UnknownLength(Option<Bytes>);
+
+    impl Body for UnknownLength {
+        type Data = Bytes;
+        type Error = aws_smithy_http::body::Error;
+
+        fn poll_data(
+            mut self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+        ) -> Poll<Option<Result<Self::Data, Self::Error>>> {
+            Poll::Ready(self.0.take().map(Ok))
+        }
+
+        fn poll_trailers(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+        ) -> Poll<Result<Option<HeaderMap>, Self::Error>> {
+            Poll::Ready(Ok(None))
+        }
+    }
+
+    #[tokio::test]
+    async fn unknown_length_is_chunked() {
+        let body = SdkBody::from_dyn(BoxBody::new(UnknownLength(Some(Bytes::from("hello")))));
+        let headers = dispatched_headers(request(Method::PUT, body)).await;
+        assert_eq!(headers["transfer-encoding"], "chunked");
+        assert!(!headers.contains_key("content-length"));
+    }
+
+    #[tokio::test]
+    async fn unknown_length_without_a_body_method_is_not_chunked() {
+        for method in [Method::GET, Method::HEAD] {
+            let body = SdkBody::from_dyn(BoxBody::new(UnknownLength(None)));
+            let headers = dispatched_headers(request(method, body)).await;
+            assert!(headers.is_empty());
+        }
+    }
+
+    #[tokio::test]
+    async fn existing_headers_are_preserved() {
+        let mut request = request(Method::PUT, SdkBody::from("hello"));
+        request
+            .headers_mut()
+            .insert("transfer-encoding", "chunked".parse().unwrap());
+        let headers = dispatched_headers(request).await;
+        assert_eq!(headers["transfer-encoding"], "chunked");
+        assert!(!headers.contains_key("content-length"));
+    }
+
+    /// Dispatches `request` with `expect_continue` to `connector`
+    async fn dispatch_expecting_continue<C, F>(
+        request: http::Request<SdkBody>,
+        expect_continue: ExpectContinue,
+        sleep_impl: Arc<dyn AsyncSleep>,
+        connector: C,
+    ) -> http::Response<SdkBody>
+    where
+        C: Fn(http::Request<SdkBody>) -> F + Clone + Send + 'static,
+        F: Future<Output = Result<http::Response<SdkBody>, ConnectorError>> + Send + 'static,
+    {
+        let mut svc = DispatchLayer::new().layer(tower::service_fn(connector));
+        let mut request = operation::Request::new(request);
+        request.properties_mut().insert(expect_continue);
+        request
+            .properties_mut()
+            .insert(ExpectContinueSleep::new(sleep_impl));
+        svc.ready()
+            .await
+            .unwrap()
+            .call(request)
+            .await
+            .unwrap()
+            .into_parts()
+            .0
+    }
+
+    #[derive(Debug)]
+    struct NeverSleep;
+
+    impl AsyncSleep for NeverSleep {
+        fn sleep(&self, _duration: Duration) -> Sleep {
+            Sleep::new(std::future::pending())
+        }
+    }
+
+    #[tokio::test]
+    async fn expect_continue_only_for_large_bodies() {
+        let echo_headers = |request: http::Request<SdkBody>| async move {
+            let mut response = http::Response::new(SdkBody::empty());
+            *response.headers_mut() = request.headers().clone();
+            Ok::<_, ConnectorError>(response)
+        };
+        let expect_continue = ExpectContinue::new().with_threshold(5);
+
+        let response = dispatch_expecting_continue(
+            request(Method::PUT, SdkBody::from("hello")),
+            expect_continue,
+            Arc::new(NeverSleep),
+            echo_headers,
+        )
+        .await;
+        assert_eq!(response.headers()["expect"], "100-continue");
+        assert_eq!(response.headers()["content-length"], "5");
+
+        let response = dispatch_expecting_continue(
+            request(Method::PUT, SdkBody::from("hi")),
+            expect_continue,
+            Arc::new(NeverSleep),
+            echo_headers,
+        )
+        .await;
+        assert!(!response.headers().contains_key("expect"));
+
+        let headers = dispatched_headers(request(Method::PUT, SdkBody::from("hello"))).await;
+        assert!(!headers.contains_key("expect"));
+    }
+
+    #[tokio::test]
+    async fn body_is_not_held_back_by_default() {
+        let response = dispatch_expecting_continue(
+            request(Method::PUT, SdkBody::from("hello")),
+            ExpectContinue::new().with_threshold(1),
+            Arc::new(NeverSleep),
+            |request: http::Request<SdkBody>| async move {
+                let expect = request.headers()["expect"].clone();
+                let body = read_body(request.into_body()).await;
+                let mut response = http::Response::new(SdkBody::from(body));
+                response.headers_mut().insert("expect", expect);
+                Ok::<_, ConnectorError>(response)
+            },
+        )
+        .await;
+        assert_eq!(response.headers()["expect"], "100-continue");
+        assert_eq!(response.body().bytes(), Some(&b"hello"[..]));
+    }
+
+    #[tokio::test]
+    async fn early_response_skips_the_body() {
+        let response = dispatch_expecting_continue(
+            request(Method::PUT, SdkBody::from("hello")),
+            ExpectContinue::new()
+                .with_threshold(1)
+                .with_timeout(Duration::from_secs(1)),
+            Arc::new(NeverSleep),
+            |mut request: http::Request<SdkBody>| async move {
+                hold(&request);
+                let data =
+                    tokio::time::timeout(Duration::from_millis(10), request.body_mut().data())
+                        .await;
+                assert!(data.is_err(), "the body should be held back");
+                Ok::<_, ConnectorError>(
+                    http::Response::builder()
+                        .status(403)
+                        .body(SdkBody::empty())
+                        .unwrap(),
+                )
+            },
+        )
+        .await;
+        assert_eq!(response.status(), 403);
+    }
+
+    #[tokio::test]
+    async fn body_is_sent_once_continue_arrives() {
+        let response = dispatch_expecting_continue(
+            request(Method::PUT, SdkBody::from("hello")),
+            ExpectContinue::new()
+                .with_threshold(1)
+                .with_timeout(Duration::from_secs(1)),
+            Arc::new(NeverSleep),
+            |request: http::Request<SdkBody>| async move {
+                hold(&request);
+                request
+                    .extensions()
+                    .get::<ContinueSignal>()
+                    .unwrap()
+                    .proceed();
+                let body = read_body(request.into_body()).await;
+                Ok::<_, ConnectorError>(http::Response::new(SdkBody::from(body)))
+            },
+        )
+        .await;
+        assert_eq!(response.body().bytes(), Some(&b"hello"[..]));
+    }
+
+    #[tokio::test]
+    async fn body_is_sent_after_timeout() {
+        let response = dispatch_expecting_continue(
+            request(Method::PUT, SdkBody::from("hello")),
+            ExpectContinue::new()
+                .with_threshold(1)
+                .with_timeout(Duration::from_millis(1)),
+            Arc::new(TokioSleep::new()),
+            |request: http::Request<SdkBody>| async move {
+                hold(&request);
+                let body = read_body(request.into_body()).await;
+                Ok::<_, ConnectorError>(http::Response::new(SdkBody::from(body)))
+            },
+        )
+        .await;
+        assert_eq!(response.body().bytes(), Some(&b"hello"[..]));
+    }
+
+    #[tokio::test]
+    async fn body_is_not_held_back_without_connector_support() {
+        let response = dispatch_expecting_continue(
+            request(Method::PUT, SdkBody::from("hello")),
+            ExpectContinue::new()
+                .with_threshold(1)
+                .with_timeout(Duration::from_secs(1)),
+            Arc::new(NeverSleep),
+            |request: http::Request<SdkBody>| async move {
+                assert!(request.extensions().get::<ContinueSignal>().is_some());
+                let body = read_body(request.into_body()).await;
+                Ok::<_, ConnectorError>(http::Response::new(SdkBody::from(body)))
+            },
+        )
+        .await;
+        assert_eq!(response.body().bytes(), Some(&b"hello"[..]));
+    }
+
+    /// Holds the body of `request` back, like a connector supporting `Expect: 100-continue`
+    fn hold(request: &http::Request<SdkBody>) {
+        request
+            .extensions()
+            .get::<ContinueSignal>()
+            .expect("the body can be held back")
+            .hold();
+    }
+
+    async fn read_body(mut body: SdkBody) -> Vec<u8> {
+        let mut out = Vec::new();
+        while let Some(data) = body.data().await {
+            out.extend_from_slice(&data.unwrap());
+        }
+        out
+    }
+
+    #[derive(Debug)]
+    struct AddHeader;
+
+    impl Interceptor for AddHeader {
+        fn modify_before_transmit(
+            &self,
+            request: &mut http::Request<SdkBody>,
+            _properties: &PropertyBag,
+        ) -> Result<(), Box<dyn Error + Send + Sync>> {
+            request
+                .headers_mut()
+                .insert("x-intercepted", "true".parse().unwrap());
+            Ok(())
+        }
+    }
+
+    #[derive(Debug)]
+    struct Reject;
+
+    impl Interceptor for Reject {
+        fn modify_before_transmit(
+            &self,
+            _request: &mut http::Request<SdkBody>,
+            _properties: &PropertyBag,
+        ) -> Result<(), Box<dyn Error + Send + Sync>> {
+            Err("rejected".into())
+        }
+    }
+
+    fn intercepted(interceptor: impl Interceptor + 'static) -> operation::Request {
+        let mut interceptors = Interceptors::new();
+        interceptors.push(SharedInterceptor::new(interceptor));
+        let mut request = operation::Request::new(request(Method::GET, SdkBody::empty()));
+        request.properties_mut().insert(interceptors);
+        request
+    }
+
+    #[tokio::test]
+    async fn interceptors_run_before_transmit() {
+        let sent = Arc::new(AtomicUsize::new(0));
+        let connector = {
+            let sent = sent.clone();
+            tower::service_fn(move |request: http::Request<SdkBody>| {
+                sent.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    let mut response = http::Response::new(SdkBody::empty());
+                    *response.headers_mut() = request.headers().clone();
+                    Ok::<_, ConnectorError>(response)
+                }
+            })
+        };
+        let mut svc = DispatchLayer::new().layer(connector);
+
+        let response = svc
+            .ready()
+            .await
+            .unwrap()
+            .call(intercepted(AddHeader))
+            .await
+            .unwrap();
+        assert_eq!(response.http().headers()["x-intercepted"], "true");
+
+        let err = svc
+            .ready()
+            .await
+            .unwrap()
+            .call(intercepted(Reject))
+            .await
+            .expect_err("the interceptor rejected the request");
+        assert!(matches!(
+            err,
+            SendOperationError::RequestConstructionError(_)
+        ));
+        assert_eq!(sent.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn dispatch_failures_carry_the_invocation_id() {
+        let connector = tower::service_fn(|_request: http::Request<SdkBody>| async move {
+            Err::<http::Response<SdkBody>, _>(ConnectorError::io("connection reset".into()))
+        });
+        let mut request = operation::Request::new(request(Method::GET, SdkBody::empty()));
+        request
+            .properties_mut()
+            .insert(InvocationId::new("invocation-id"));
+        let err = DispatchLayer::new()
+            .layer(connector)
+            .oneshot(request)
+            .await
+            .expect_err("the connector fails");
+        match err {
+            SendOperationError::RequestDispatchError(err) => assert_eq!(
+                Some(&InvocationId::new("invocation-id")),
+                err.invocation_id()
+            ),
+            other => panic!("expected a dispatch error, got {:?}", other),
+        }
+    }
+}
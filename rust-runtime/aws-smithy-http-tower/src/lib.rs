@@ -6,6 +6,8 @@
 pub mod dispatch;
 pub mod map_request;
 pub mod parse_response;
+pub mod trace;
+pub mod wire_trace;
 
 use aws_smithy_http::result::{ConnectorError, SdkError};
 use tower::BoxError;
@@ -53,19 +55,70 @@ impl<E> From<SendOperationError> for SdkError<E> {
 
 #[cfg(test)]
 mod tests {
-    use crate::dispatch::DispatchLayer;
-    use crate::map_request::MapRequestLayer;
-    use crate::parse_response::ParseResponseLayer;
+    use crate::dispatch::{DispatchLayer, DispatchService};
+    use crate::map_request::{
+        AsyncMapRequestLayer, MapRequestLayer, StageError, StageTimeout, StageTimeoutError,
+    };
+    use crate::parse_response::{ParseResponseLayer, ParseResponseService};
+    use aws_smithy_async::test_util::InstantSleep;
     use aws_smithy_http::body::SdkBody;
-    use aws_smithy_http::middleware::MapRequest;
+    use aws_smithy_http::metrics::AttemptOutcome;
+    use aws_smithy_http::middleware::{AsyncMapRequest, MapRequest};
     use aws_smithy_http::operation;
     use aws_smithy_http::operation::{Operation, Request};
     use aws_smithy_http::response::ParseStrictResponse;
-    use aws_smithy_http::result::ConnectorError;
+    use aws_smithy_http::result::{ConnectorError, SdkError};
+    use aws_smithy_http::retry::RequestAttempts;
     use bytes::Bytes;
     use http::Response;
     use std::convert::{Infallible, TryInto};
-    use tower::{service_fn, Service, ServiceBuilder};
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tower::layer::util::Identity;
+    use tower::util::ServiceFn;
+    use tower::{service_fn, Layer, Service, ServiceBuilder};
+
+    #[derive(Clone)]
+    struct TestParseResponse;
+    impl ParseStrictResponse for TestParseResponse {
+        type Output = Result<String, Infallible>;
+
+        fn parse(&self, _response: &Response<Bytes>) -> Self::Output {
+            Ok("OK".to_string())
+        }
+    }
+
+    fn test_operation() -> Operation<TestParseResponse, ()> {
+        let req = operation::Request::new(http::Request::new(SdkBody::from("hello")));
+        Operation::new(req, TestParseResponse)
+    }
+
+    async fn ok(
+        _request: http::Request<SdkBody>,
+    ) -> Result<http::Response<SdkBody>, ConnectorError> {
+        Ok(http::Response::new(SdkBody::from("ok")))
+    }
+
+    /// Builds a parse response / `stage` / dispatch stack that sends requests to `connector`
+    fn test_stack<L, F, Fut>(
+        stage: L,
+        connector: F,
+    ) -> ParseResponseService<L::Service, TestParseResponse, ()>
+    where
+        L: Layer<DispatchService<ServiceFn<F>>>,
+        L::Service: Service<Request, Response = operation::Response>,
+        F: FnMut(http::Request<SdkBody>) -> Fut,
+        Fut: Future<Output = Result<http::Response<SdkBody>, ConnectorError>>,
+    {
+        ServiceBuilder::new()
+            .layer(ParseResponseLayer::<TestParseResponse, ()>::new())
+            .layer(stage)
+            .layer(DispatchLayer::new())
+            .service(service_fn(connector))
+    }
 
     /// Creates a stubbed service stack and runs it to validate that all the types line up &
     /// everything is properly wired
@@ -84,32 +137,169 @@ mod tests {
             }
         }
 
-        struct TestParseResponse;
-        impl ParseStrictResponse for TestParseResponse {
-            type Output = Result<String, Infallible>;
-
-            fn parse(&self, _response: &Response<Bytes>) -> Self::Output {
-                Ok("OK".to_string())
-            }
-        }
-
-        let http_layer = service_fn(|_request: http::Request<SdkBody>| async move {
-            if _request.headers().contains_key("X-Test") {
+        async fn expect_header(
+            request: http::Request<SdkBody>,
+        ) -> Result<http::Response<SdkBody>, ConnectorError> {
+            if request.headers().contains_key("X-Test") {
                 Ok(http::Response::new(SdkBody::from("ok")))
             } else {
                 Err(ConnectorError::user("header not set".into()))
             }
-        });
+        }
 
-        let mut svc = ServiceBuilder::new()
-            .layer(ParseResponseLayer::<TestParseResponse, ()>::new())
-            .layer(MapRequestLayer::for_mapper(AddHeader))
-            .layer(DispatchLayer)
-            .service(http_layer);
-        let req = http::Request::new(SdkBody::from("hello"));
-        let req = operation::Request::new(req);
-        let req = Operation::new(req, TestParseResponse);
-        let resp = svc.call(req).await.expect("Response should succeed");
+        let mut svc = test_stack(MapRequestLayer::for_mapper(AddHeader), expect_header);
+        let resp = svc
+            .call(test_operation())
+            .await
+            .expect("Response should succeed");
         assert_eq!(resp.parsed, "OK".to_string())
     }
+
+    #[tokio::test]
+    async fn attempts_are_recorded_in_the_property_bag() {
+        #[derive(Clone)]
+        struct ExpectAttempt(u32);
+        impl MapRequest for ExpectAttempt {
+            type Error = Infallible;
+            fn apply(&self, request: Request) -> Result<Request, Self::Error> {
+                assert_eq!(
+                    request.properties().get::<RequestAttempts>(),
+                    Some(&RequestAttempts::new(self.0))
+                );
+                Ok(request)
+            }
+        }
+
+        let stack = |attempt| test_stack(MapRequestLayer::for_mapper(ExpectAttempt(attempt)), ok);
+        let req = test_operation();
+        let retry = req.try_clone().expect("body is cloneable");
+        stack(1).call(req).await.expect("first attempt succeeds");
+        stack(2).call(retry).await.expect("second attempt succeeds");
+    }
+
+    #[tokio::test]
+    async fn dispatch_failures_carry_the_attempt_history() {
+        async fn connection_reset(
+            _request: http::Request<SdkBody>,
+        ) -> Result<http::Response<SdkBody>, ConnectorError> {
+            Err(ConnectorError::io("connection reset".into()))
+        }
+
+        let mut svc = test_stack(Identity::new(), connection_reset);
+        let err = svc
+            .call(test_operation())
+            .await
+            .expect_err("dispatch fails");
+        let context = err
+            .dispatch_failure()
+            .and_then(ConnectorError::diagnostic_context)
+            .expect("the attempt history is attached");
+        assert_eq!(1, context.attempts.len());
+        assert_eq!(AttemptOutcome::DispatchFailure, context.attempts[0].outcome);
+        assert!(
+            err.diagnostic()
+                .ends_with("Attempts:\n  1: dispatch failure: io error: connection reset"),
+            "{}",
+            err.diagnostic()
+        );
+    }
+
+    #[tokio::test]
+    async fn stage_errors_identify_the_stage() {
+        #[derive(Clone)]
+        struct ResolveEndpoint;
+        impl MapRequest for ResolveEndpoint {
+            type Error = &'static str;
+            fn apply(&self, _request: Request) -> Result<Request, Self::Error> {
+                Err("no endpoint")
+            }
+
+            fn name(&self) -> &'static str {
+                "endpoint"
+            }
+        }
+
+        let mut svc = test_stack(MapRequestLayer::for_mapper(ResolveEndpoint), ok);
+        let err = svc
+            .call(test_operation())
+            .await
+            .expect_err("the endpoint stage fails");
+        let err = match err {
+            SdkError::ConstructionFailure(err) => err,
+            other => panic!("expected a construction failure, got {:?}", other),
+        };
+        let stage_error = err
+            .downcast_ref::<StageError>()
+            .expect("construction failures are stage errors");
+        assert_eq!("endpoint", stage_error.stage_name());
+        assert_eq!(
+            "the `endpoint` middleware stage failed: no endpoint",
+            err.to_string()
+        );
+        assert_eq!(
+            "no endpoint",
+            std::error::Error::source(stage_error).unwrap().to_string()
+        );
+    }
+
+    #[tokio::test]
+    async fn stage_timeouts_drop_the_stage_future() {
+        struct SetOnDrop(Arc<AtomicBool>);
+        impl Drop for SetOnDrop {
+            fn drop(&mut self) {
+                self.0.store(true, Ordering::SeqCst);
+            }
+        }
+
+        /// A credentials stage whose provider never resolves, like a hung IMDS call
+        #[derive(Clone)]
+        struct NeverLoadsCredentials(Arc<AtomicBool>);
+        impl AsyncMapRequest for NeverLoadsCredentials {
+            type Error = Infallible;
+            type Future = Pin<Box<dyn Future<Output = Result<Request, Self::Error>> + Send>>;
+
+            fn apply(&self, _request: Request) -> Self::Future {
+                let set_on_drop = SetOnDrop(self.0.clone());
+                Box::pin(async move {
+                    let _set_on_drop = set_on_drop;
+                    std::future::pending().await
+                })
+            }
+
+            fn name(&self) -> &'static str {
+                "credentials"
+            }
+        }
+
+        let dropped = Arc::new(AtomicBool::new(false));
+        let sleep = InstantSleep::new();
+        let timeout = StageTimeout::new(Duration::from_secs(5), Arc::new(sleep.clone()));
+        let mut svc = test_stack(
+            AsyncMapRequestLayer::for_mapper(NeverLoadsCredentials(dropped.clone()))
+                .with_timeout(timeout),
+            ok,
+        );
+        let err = svc
+            .call(test_operation())
+            .await
+            .expect_err("the credentials stage times out");
+
+        assert_eq!(vec![Duration::from_secs(5)], sleep.logs());
+        assert!(
+            dropped.load(Ordering::SeqCst),
+            "the stage future was dropped"
+        );
+        let err = match err {
+            SdkError::ConstructionFailure(err) => err,
+            other => panic!("expected a construction failure, got {:?}", other),
+        };
+        let stage_error = err
+            .downcast_ref::<StageError>()
+            .expect("construction failures are stage errors");
+        assert_eq!("credentials", stage_error.stage_name());
+        let timeout_error = std::error::Error::source(stage_error)
+            .and_then(|source| source.downcast_ref::<StageTimeoutError>())
+            .expect("the source is a timeout");
+        assert_eq!(Duration::from_secs(5), timeout_error.duration());
+    }
 }
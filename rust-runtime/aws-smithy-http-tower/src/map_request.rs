@@ -4,22 +4,134 @@
  */
 
 use crate::SendOperationError;
+use aws_smithy_async::future::timeout::Timeout;
+use aws_smithy_async::rt::sleep::AsyncSleep;
 use aws_smithy_http::middleware::{AsyncMapRequest, MapRequest};
 use aws_smithy_http::operation;
 use pin_project::pin_project;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
 use std::future::Future;
 use std::pin::Pin;
+use std::sync::Arc;
 use std::task::{Context, Poll};
-use tower::{Layer, Service};
+use std::time::Duration;
+use tower::{BoxError, Layer, Service};
+use tracing::trace;
+
+/// A middleware stage failed to apply to the request
+///
+/// [`MapRequestService`] and [`AsyncMapRequestService`] wrap the errors returned by their stages
+/// in a `StageError` so that a [`ConstructionFailure`](aws_smithy_http::result::SdkError::ConstructionFailure)
+/// identifies which stage failed, e.g. endpoint resolution, credentials, or signing.
+#[derive(Debug)]
+pub struct StageError {
+    stage_name: &'static str,
+    source: BoxError,
+}
+
+impl StageError {
+    /// Creates a `StageError` for a failure in the stage named `stage_name`
+    pub fn new(stage_name: &'static str, source: impl Into<BoxError>) -> Self {
+        Self {
+            stage_name,
+            source: source.into(),
+        }
+    }
+
+    /// Returns the [name](aws_smithy_http::middleware::MapRequest::name) of the stage that failed
+    pub fn stage_name(&self) -> &'static str {
+        self.stage_name
+    }
+
+    /// Returns the error returned by the stage
+    pub fn into_source(self) -> BoxError {
+        self.source
+    }
+}
+
+impl Display for StageError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "the `{}` middleware stage failed: {}",
+            self.stage_name, self.source
+        )
+    }
+}
+
+impl Error for StageError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(self.source.as_ref())
+    }
+}
+
+/// Time limit for an [`AsyncMapRequest`] stage
+///
+/// Stages like credentials loading may call out to other services (e.g. IMDS). Without a time
+/// limit, a call that never completes would hang the request forever.
+#[derive(Clone, Debug)]
+pub struct StageTimeout {
+    duration: Duration,
+    sleep: Arc<dyn AsyncSleep>,
+}
+
+impl StageTimeout {
+    /// Limits a stage to `duration`, using `sleep` to create the timer
+    pub fn new(duration: Duration, sleep: Arc<dyn AsyncSleep>) -> Self {
+        Self { duration, sleep }
+    }
+
+    /// Returns the time limit for the stage
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+}
+
+/// An [`AsyncMapRequest`] stage didn't complete within its [`StageTimeout`]
+///
+/// This is returned as the source of a [`StageError`].
+#[derive(Debug)]
+pub struct StageTimeoutError {
+    duration: Duration,
+}
+
+impl StageTimeoutError {
+    /// Returns the time limit the stage exceeded
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+}
+
+impl Display for StageTimeoutError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "timed out after {:?}", self.duration)
+    }
+}
+
+impl Error for StageTimeoutError {}
 
 #[derive(Debug)]
 pub struct AsyncMapRequestLayer<M> {
     mapper: M,
+    timeout: Option<StageTimeout>,
 }
 
 impl<M: AsyncMapRequest + Clone> AsyncMapRequestLayer<M> {
     pub fn for_mapper(mapper: M) -> Self {
-        AsyncMapRequestLayer { mapper }
+        AsyncMapRequestLayer {
+            mapper,
+            timeout: None,
+        }
+    }
+
+    /// Fails the request if the stage doesn't complete within `timeout`
+    ///
+    /// When the timeout elapses, the stage's future is dropped and the request fails with a
+    /// [`StageError`] whose source is a [`StageTimeoutError`].
+    pub fn with_timeout(mut self, timeout: StageTimeout) -> Self {
+        self.timeout = Some(timeout);
+        self
     }
 }
 
@@ -33,6 +145,7 @@ where
         AsyncMapRequestService {
             inner,
             mapper: self.mapper.clone(),
+            timeout: self.timeout.clone(),
         }
     }
 }
@@ -42,6 +155,7 @@ where
 pub struct AsyncMapRequestService<S, M> {
     inner: S,
     mapper: M,
+    timeout: Option<StageTimeout>,
 }
 
 type BoxFuture<T> = Pin<Box<dyn Future<Output = T> + Send>>;
@@ -61,12 +175,31 @@ where
     }
 
     fn call(&mut self, req: operation::Request) -> Self::Future {
-        let mut inner = self.inner.clone();
+        // `poll_ready` was called on `self.inner`, so that's the service that must handle this
+        // request. Dropping the returned future releases it along with the stage's future.
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let stage_name = self.mapper.name();
+        trace!(stage = stage_name, "applying async middleware stage");
         let future = self.mapper.apply(req);
+        let timeout = self.timeout.clone();
         Box::pin(async move {
-            let mapped_request = future
-                .await
-                .map_err(|e| SendOperationError::RequestConstructionError(e.into()))?;
+            let mapped_request: Result<operation::Request, BoxError> = match timeout {
+                Some(timeout) => {
+                    match Timeout::new(future, timeout.sleep.sleep(timeout.duration)).await {
+                        Ok(result) => result.map_err(Into::into),
+                        Err(_timed_out) => Err(StageTimeoutError {
+                            duration: timeout.duration,
+                        }
+                        .into()),
+                    }
+                }
+                None => future.await.map_err(Into::into),
+            };
+            let mapped_request = mapped_request.map_err(|e| {
+                SendOperationError::RequestConstructionError(StageError::new(stage_name, e).into())
+            })?;
+            trace!(stage = stage_name, "async middleware stage complete");
             inner.call(mapped_request).await
         })
     }
@@ -138,11 +271,11 @@ where
     }
 
     fn call(&mut self, req: operation::Request) -> Self::Future {
-        match self
-            .mapper
-            .apply(req)
-            .map_err(|e| SendOperationError::RequestConstructionError(e.into()))
-        {
+        let stage_name = self.mapper.name();
+        trace!(stage = stage_name, "applying middleware stage");
+        match self.mapper.apply(req).map_err(|e| {
+            SendOperationError::RequestConstructionError(StageError::new(stage_name, e).into())
+        }) {
             Err(e) => MapRequestFuture::Ready(Some(e)),
             Ok(req) => MapRequestFuture::Inner(self.inner.call(req)),
         }
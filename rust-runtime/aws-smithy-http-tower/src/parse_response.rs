@@ -4,18 +4,26 @@
  */
 
 use crate::SendOperationError;
-use aws_smithy_http::middleware::load_response;
+use aws_smithy_async::time::Instant;
+use aws_smithy_http::diagnostic::{AttemptRecord, DiagnosticContext};
+use aws_smithy_http::metrics::{AttemptMetrics, AttemptOutcome, EmitMetrics, SharedMetricsEmitter};
+use aws_smithy_http::middleware::load_response_offloaded;
 use aws_smithy_http::operation;
-use aws_smithy_http::operation::Operation;
+use aws_smithy_http::operation::{Metadata, Operation};
+use aws_smithy_http::property_bag::SharedPropertyBag;
 use aws_smithy_http::response::ParseHttpResponse;
-use aws_smithy_http::result::SdkError;
+use aws_smithy_http::result::{SdkError, SdkSuccess};
+use aws_smithy_http::retry::RequestAttempts;
+use aws_smithy_http::timing::TimingInfo;
 use std::future::Future;
 use std::marker::PhantomData;
 use std::pin::Pin;
+use std::sync::Arc;
 use std::task::{Context, Poll};
+use std::time::Duration;
 use tower::{Layer, Service};
 use tracing::field::display;
-use tracing::{debug_span, field, info_span, Instrument};
+use tracing::{debug, debug_span, field, info_span, Instrument};
 
 /// `ParseResponseService` dispatches [`Operation`](aws_smithy_http::operation::Operation)s and parses them.
 ///
@@ -77,7 +85,8 @@ where
         + Send
         + Sync
         + 'static,
-    FailureResponse: std::error::Error,
+    SuccessResponse: Send + 'static,
+    FailureResponse: std::error::Error + Send + 'static,
 {
     type Response = aws_smithy_http::result::SdkSuccess<SuccessResponse>;
     type Error = aws_smithy_http::result::SdkError<FailureResponse>;
@@ -88,32 +97,68 @@ where
     }
 
     fn call(&mut self, req: Operation<ResponseHandler, RetryPolicy>) -> Self::Future {
-        let (req, parts) = req.into_request_response();
+        let (mut req, parts) = req.into_request_response();
         let handler = parts.response_handler;
-        // send_operation records the full request-response lifecycle.
+        // The property bag is shared between all attempts of an operation, so the attempt
+        // count recorded by the previous attempt (if any) is visible here.
+        let attempt = {
+            let mut properties = req.properties_mut();
+            let attempt = properties
+                .get::<RequestAttempts>()
+                .map(|attempts| attempts.attempts() + 1)
+                .unwrap_or(1);
+            properties.insert(RequestAttempts::new(attempt));
+            // Stages record into the same `TimingInfo`, so start a new one for each attempt
+            properties.insert(TimingInfo::new());
+            DiagnosticContext::record(&mut properties).start_attempt();
+            attempt
+        };
+        let metrics = req.properties().get::<SharedMetricsEmitter>().cloned();
+        let request_body_size = http_body::Body::size_hint(req.http().body()).exact();
+        // send_operation records the full request-response lifecycle of a single attempt.
         // NOTE: For operations that stream output, only the setup is captured in this span.
         let span = info_span!(
             "send_operation",
             operation = field::Empty,
             service = field::Empty,
+            attempt = attempt,
+            request_id = field::Empty,
             status = field::Empty,
             message = field::Empty
         );
         let inner_span = span.clone();
-        if let Some(metadata) = parts.metadata {
+        let metadata = req
+            .properties()
+            .get::<Metadata>()
+            .cloned()
+            .or(parts.metadata);
+        if let Some(metadata) = &metadata {
             span.record("operation", &metadata.name());
             span.record("service", &metadata.service());
         }
-        let resp = self.inner.call(req);
+        let start = Instant::now();
+        // Keep a handle to the property bag, so that the attempt can be recorded even if no
+        // response is received
+        let (req, properties) = req.into_parts();
+        let resp = self
+            .inner
+            .call(operation::Request::from_parts(req, properties.clone()));
         let fut = async move {
             let resp = match resp.await {
                 Err(e) => Err(e.into()),
                 Ok(resp) => {
+                    if let Some(request_id) = resp.request_id() {
+                        inner_span.record("request_id", request_id);
+                    }
+                    debug!(status = %resp.http().status(), "response received");
                     // load_response contains reading the body as far as is required & parsing the response
                     let response_span = debug_span!("load_response");
-                    load_response(resp, &handler)
+                    let load_start = Instant::now();
+                    let mut resp = load_response_offloaded(resp, Arc::new(handler))
                         .instrument(response_span)
-                        .await
+                        .await;
+                    record_body_read(&mut resp, load_start.elapsed());
+                    resp
                 }
             };
             match &resp {
@@ -134,9 +179,89 @@ where
                     .record("status", &"timeout_error")
                     .record("message", &display(err)),
             };
+            record_attempt(&properties, &resp, attempt);
+            let resp = resp.map_err(|err| attach_diagnostic_context(&properties, err));
+            if let Some(metrics) = metrics {
+                metrics.emit_attempt(&attempt_metrics(
+                    &resp,
+                    metadata,
+                    attempt,
+                    start,
+                    request_body_size,
+                ));
+            }
             resp
         }
         .instrument(span);
         Box::pin(fut)
     }
 }
+
+fn record_body_read<T, E>(result: &mut Result<SdkSuccess<T>, SdkError<E>>, body_read: Duration) {
+    let raw = match result {
+        Ok(success) => &mut success.raw,
+        Err(SdkError::ServiceError { raw, .. }) | Err(SdkError::ResponseError { raw, .. }) => raw,
+        Err(_) => return,
+    };
+    TimingInfo::record(&mut raw.properties_mut()).body_read = Some(body_read);
+}
+
+fn attempt_outcome<T, E>(
+    result: &Result<SdkSuccess<T>, SdkError<E>>,
+) -> (AttemptOutcome, Option<&operation::Response>) {
+    match result {
+        Ok(success) => (AttemptOutcome::Success, Some(&success.raw)),
+        Err(SdkError::ServiceError { raw, .. }) => (AttemptOutcome::ServiceError, Some(raw)),
+        Err(SdkError::ResponseError { raw, .. }) => (AttemptOutcome::ResponseError, Some(raw)),
+        Err(SdkError::DispatchFailure(_)) => (AttemptOutcome::DispatchFailure, None),
+        Err(SdkError::ConstructionFailure(_)) => (AttemptOutcome::ConstructionFailure, None),
+        Err(SdkError::TimeoutError(_)) => (AttemptOutcome::Timeout, None),
+    }
+}
+
+fn record_attempt<T, E: std::error::Error>(
+    properties: &SharedPropertyBag,
+    result: &Result<SdkSuccess<T>, SdkError<E>>,
+    attempt: u32,
+) {
+    let (outcome, raw) = attempt_outcome(result);
+    let record = AttemptRecord::new(attempt, outcome)
+        .with_status(raw.map(|raw| raw.http().status()))
+        .with_error(result.as_ref().err().map(|err| err.to_string()));
+    DiagnosticContext::record(&mut properties.acquire_mut())
+        .attempts
+        .push(record);
+}
+
+/// Attaches the attempts recorded so far to dispatch failures, since they have no response to
+/// carry them
+fn attach_diagnostic_context<E>(properties: &SharedPropertyBag, err: SdkError<E>) -> SdkError<E> {
+    match err {
+        SdkError::DispatchFailure(err) => {
+            let context = properties.acquire().get::<DiagnosticContext>().cloned();
+            SdkError::DispatchFailure(err.with_diagnostic_context(context))
+        }
+        err => err,
+    }
+}
+
+fn attempt_metrics<T, E>(
+    result: &Result<SdkSuccess<T>, SdkError<E>>,
+    metadata: Option<Metadata>,
+    attempt: u32,
+    start: Instant,
+    request_body_size: Option<u64>,
+) -> AttemptMetrics {
+    let (outcome, raw) = attempt_outcome(result);
+    let metrics = AttemptMetrics::new(metadata, attempt, start.elapsed(), outcome)
+        .with_request_body_size(request_body_size);
+    match raw {
+        Some(raw) => metrics
+            .with_response(
+                raw.http().status(),
+                http_body::Body::size_hint(raw.http().body()).exact(),
+            )
+            .with_timing(raw.timing_info()),
+        None => metrics,
+    }
+}
@@ -0,0 +1,88 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Helpers for emitting requests and responses into `tracing` without leaking secrets.
+
+use aws_smithy_types::sensitive::Sensitive;
+use http::header::{HeaderName, AUTHORIZATION};
+use http::HeaderMap;
+use std::fmt;
+
+pub use aws_smithy_http::header::request_id;
+
+/// Headers whose values are never written to logs.
+const SENSITIVE_HEADERS: &[&str] = &["x-amz-security-token", "proxy-authorization"];
+
+/// Returns true if the value of `name` should be redacted from logs.
+pub fn is_sensitive_header(name: &HeaderName) -> bool {
+    name == AUTHORIZATION || SENSITIVE_HEADERS.contains(&name.as_str())
+}
+
+/// `Debug` view of a [`HeaderMap`] with the values of sensitive headers redacted.
+pub struct RedactedHeaders<'a>(pub &'a HeaderMap);
+
+impl fmt::Debug for RedactedHeaders<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut map = f.debug_map();
+        for (name, value) in self.0 {
+            if is_sensitive_header(name) {
+                map.entry(name, &Sensitive(value));
+            } else {
+                map.entry(name, value);
+            }
+        }
+        map.finish()
+    }
+}
+
+/// `Debug` view of an [`http::Request`] with the values of sensitive headers redacted.
+pub struct RedactedRequest<'a, B>(pub &'a http::Request<B>);
+
+impl<B: fmt::Debug> fmt::Debug for RedactedRequest<'_, B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Request")
+            .field("method", self.0.method())
+            .field("uri", self.0.uri())
+            .field("version", &self.0.version())
+            .field("headers", &RedactedHeaders(self.0.headers()))
+            .field("body", self.0.body())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::trace::RedactedRequest;
+
+    #[test]
+    fn sensitive_headers_are_redacted() {
+        let request = http::Request::builder()
+            .uri("https://example.com")
+            .header("authorization", "AWS4-HMAC-SHA256 Credential=secret")
+            .header("x-amz-security-token", "session-token")
+            .header("x-amz-date", "20210618T170728Z")
+            .body(())
+            .unwrap();
+        let debug = format!("{:?}", RedactedRequest(&request));
+        assert!(!debug.contains("secret"), "{}", debug);
+        assert!(!debug.contains("session-token"), "{}", debug);
+        assert!(debug.contains("20210618T170728Z"), "{}", debug);
+    }
+
+    #[test]
+    fn redacted_headers_use_the_sensitive_placeholder() {
+        let request = http::Request::builder()
+            .uri("https://example.com")
+            .header("authorization", "AWS4-HMAC-SHA256 Credential=secret")
+            .body(())
+            .unwrap();
+        let debug = format!("{:?}", RedactedRequest(&request));
+        assert!(
+            debug.contains(r#""authorization": "*** Sensitive Data Redacted ***""#),
+            "{}",
+            debug
+        );
+    }
+}
@@ -0,0 +1,759 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Wire-level logging of HTTP requests and responses.
+//!
+//! [`WireTraceLayer`] wraps a connector and logs every request it sends and every response it
+//! receives:
+//! - The method, URI, status, and headers are logged at `DEBUG`. Sensitive headers are redacted
+//!   (see [`is_sensitive_header`](crate::trace::is_sensitive_header)).
+//! - Bodies are logged at `TRACE`, up to [`with_body_limit`](WireTraceLayer::with_body_limit)
+//!   bytes. JSON and XML bodies are pretty-printed.
+//!
+//! Bodies are logged as they are sent, so they may contain sensitive data even when headers are
+//! redacted. Only enable `TRACE` logging for `aws_smithy_http_tower::wire_trace` when that's
+//! acceptable.
+//!
+//! Logging can be switched on and off while the connector is in use with a [`WireTraceHandle`]:
+//!
+//! ```no_run
+//! # fn wrap<C>(connector: C) {
+//! use aws_smithy_http_tower::wire_trace::WireTraceLayer;
+//! use tower::Layer;
+//!
+//! let layer = WireTraceLayer::new().with_body_limit(16 * 1024);
+//! let handle = layer.handle();
+//! // Build the client with this connector
+//! let connector = layer.layer(connector);
+//!
+//! // Later, e.g. once the problem being debugged has been reproduced:
+//! handle.disable();
+//! # }
+//! ```
+
+use crate::trace::RedactedHeaders;
+use aws_smithy_http::body::SdkBody;
+use bytes::{Bytes, BytesMut};
+use http::header::CONTENT_TYPE;
+use http::HeaderMap;
+use http_body::combinators::BoxBody;
+use http_body::{Body, SizeHint};
+use pin_project::pin_project;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tower::{Layer, Service};
+use tracing::{debug, level_enabled, trace, Level};
+
+const DEFAULT_BODY_LIMIT: usize = 4096;
+
+/// Layer that logs requests and responses at the wire level.
+///
+/// See the [module documentation](crate::wire_trace) for details.
+#[derive(Clone, Debug)]
+pub struct WireTraceLayer {
+    enabled: Arc<AtomicBool>,
+    body_limit: usize,
+}
+
+impl Default for WireTraceLayer {
+    fn default() -> Self {
+        WireTraceLayer {
+            enabled: Arc::new(AtomicBool::new(true)),
+            body_limit: DEFAULT_BODY_LIMIT,
+        }
+    }
+}
+
+impl WireTraceLayer {
+    /// Creates a layer that logs bodies of up to 4 KiB
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the maximum number of bytes of each body to log
+    ///
+    /// Longer bodies are truncated. A limit of zero disables body logging.
+    pub fn with_body_limit(mut self, body_limit: usize) -> Self {
+        self.body_limit = body_limit;
+        self
+    }
+
+    /// Sets whether logging starts out enabled
+    ///
+    /// Logging is enabled by default.
+    pub fn with_enabled(self, enabled: bool) -> Self {
+        self.enabled.store(enabled, Ordering::Relaxed);
+        self
+    }
+
+    /// Returns a handle that enables or disables logging for every service created by this layer
+    pub fn handle(&self) -> WireTraceHandle {
+        WireTraceHandle {
+            enabled: self.enabled.clone(),
+        }
+    }
+}
+
+impl<S> Layer<S> for WireTraceLayer {
+    type Service = WireTraceService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        WireTraceService {
+            inner,
+            enabled: self.enabled.clone(),
+            body_limit: self.body_limit,
+        }
+    }
+}
+
+/// Enables or disables logging for the services created by a [`WireTraceLayer`]
+#[derive(Clone, Debug)]
+pub struct WireTraceHandle {
+    enabled: Arc<AtomicBool>,
+}
+
+impl WireTraceHandle {
+    /// Enables logging
+    pub fn enable(&self) {
+        self.set_enabled(true);
+    }
+
+    /// Disables logging
+    pub fn disable(&self) {
+        self.set_enabled(false);
+    }
+
+    /// Enables or disables logging
+    ///
+    /// Requests that have already been sent are unaffected.
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Returns true if logging is enabled
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+}
+
+/// Service that logs requests and responses at the wire level
+///
+/// Created by [`WireTraceLayer`].
+#[derive(Clone, Debug)]
+pub struct WireTraceService<S> {
+    inner: S,
+    enabled: Arc<AtomicBool>,
+    body_limit: usize,
+}
+
+impl<S> Service<http::Request<SdkBody>> for WireTraceService<S>
+where
+    S: Service<http::Request<SdkBody>, Response = http::Response<SdkBody>>,
+{
+    type Response = http::Response<SdkBody>;
+    type Error = S::Error;
+    type Future = WireTraceFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: http::Request<SdkBody>) -> Self::Future {
+        let body_limit = if self.enabled.load(Ordering::Relaxed) {
+            log_request(&request, self.body_limit);
+            Some(self.body_limit)
+        } else {
+            None
+        };
+        WireTraceFuture {
+            inner: self.inner.call(request),
+            body_limit,
+        }
+    }
+}
+
+/// Response future for [`WireTraceService`]
+#[pin_project]
+#[derive(Debug)]
+pub struct WireTraceFuture<F> {
+    #[pin]
+    inner: F,
+    /// `None` if logging was disabled when the request was sent
+    body_limit: Option<usize>,
+}
+
+impl<F, E> Future for WireTraceFuture<F>
+where
+    F: Future<Output = Result<http::Response<SdkBody>, E>>,
+{
+    type Output = Result<http::Response<SdkBody>, E>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let response = match this.inner.poll(cx) {
+            Poll::Ready(response) => response,
+            Poll::Pending => return Poll::Pending,
+        };
+        Poll::Ready(match (response, *this.body_limit) {
+            (Ok(response), Some(body_limit)) => Ok(log_response(response, body_limit)),
+            (response, _) => response,
+        })
+    }
+}
+
+fn log_request(request: &http::Request<SdkBody>, body_limit: usize) {
+    debug!(
+        method = %request.method(),
+        uri = %request.uri(),
+        headers = ?RedactedHeaders(request.headers()),
+        "sending request"
+    );
+    if !logs_bodies(body_limit) {
+        return;
+    }
+    match request.body().bytes() {
+        Some(bytes) => trace!(
+            body = %BodySample::new(request.headers(), bytes, body_limit),
+            "request body"
+        ),
+        None => trace!("request body is streamed and won't be logged"),
+    }
+}
+
+fn log_response(response: http::Response<SdkBody>, body_limit: usize) -> http::Response<SdkBody> {
+    debug!(
+        status = response.status().as_u16(),
+        headers = ?RedactedHeaders(response.headers()),
+        "received response"
+    );
+    if !logs_bodies(body_limit) {
+        return response;
+    }
+    if let Some(bytes) = response.body().bytes() {
+        trace!(
+            body = %BodySample::new(response.headers(), bytes, body_limit),
+            "response body"
+        );
+        return response;
+    }
+    // Streaming bodies are logged once the limit is reached or the body ends, so that reading
+    // them isn't delayed
+    let content_type = response.headers().get(CONTENT_TYPE).cloned();
+    response.map(|body| {
+        SdkBody::from_dyn(BoxBody::new(SampledBody {
+            inner: body,
+            content_type,
+            sample: BytesMut::new(),
+            received: 0,
+            body_limit,
+            logged: false,
+        }))
+    })
+}
+
+fn logs_bodies(body_limit: usize) -> bool {
+    body_limit > 0 && level_enabled!(Level::TRACE)
+}
+
+/// Response body that logs the first `body_limit` bytes of its inner body
+#[pin_project]
+struct SampledBody {
+    #[pin]
+    inner: SdkBody,
+    content_type: Option<http::HeaderValue>,
+    sample: BytesMut,
+    received: usize,
+    body_limit: usize,
+    logged: bool,
+}
+
+impl SampledBody {
+    fn log(
+        sample: &[u8],
+        total: Option<usize>,
+        content_type: Option<&http::HeaderValue>,
+        body_limit: usize,
+    ) {
+        let sample = BodySample {
+            format: content_type.map(Format::of).unwrap_or(Format::Raw),
+            bytes: sample,
+            total,
+            body_limit,
+        };
+        trace!(body = %sample, "response body");
+    }
+}
+
+impl Body for SampledBody {
+    type Data = Bytes;
+    type Error = aws_smithy_http::body::Error;
+
+    fn poll_data(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Self::Data, Self::Error>>> {
+        let this = self.project();
+        let data = this.inner.poll_data(cx);
+        if *this.logged {
+            return data;
+        }
+        match &data {
+            Poll::Ready(Some(Ok(chunk))) => {
+                *this.received += chunk.len();
+                let wanted = *this.body_limit - this.sample.len();
+                this.sample
+                    .extend_from_slice(&chunk[..chunk.len().min(wanted)]);
+                if *this.received > *this.body_limit {
+                    // The total is unknown until the end, so only report that there's more
+                    Self::log(
+                        this.sample,
+                        None,
+                        this.content_type.as_ref(),
+                        *this.body_limit,
+                    );
+                    *this.logged = true;
+                }
+            }
+            Poll::Ready(None) => {
+                Self::log(
+                    this.sample,
+                    Some(*this.received),
+                    this.content_type.as_ref(),
+                    *this.body_limit,
+                );
+                *this.logged = true;
+            }
+            Poll::Ready(Some(Err(_))) | Poll::Pending => {}
+        }
+        data
+    }
+
+    fn poll_trailers(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Option<HeaderMap>, Self::Error>> {
+        self.project().inner.poll_trailers(cx)
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.inner.size_hint()
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Format {
+    Json,
+    Xml,
+    Raw,
+}
+
+impl Format {
+    fn of(content_type: &http::HeaderValue) -> Self {
+        let content_type = match content_type.to_str() {
+            Ok(content_type) => content_type,
+            Err(_) => return Format::Raw,
+        };
+        let media_type = content_type
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase();
+        // e.g. `application/json`, `application/x-amz-json-1.1`, `application/vnd.api+json`
+        if media_type.ends_with("/json")
+            || media_type.ends_with("+json")
+            || media_type.starts_with("application/x-amz-json")
+        {
+            Format::Json
+        } else if media_type.ends_with("/xml") || media_type.ends_with("+xml") {
+            Format::Xml
+        } else {
+            Format::Raw
+        }
+    }
+}
+
+/// `Display` view of the first `body_limit` bytes of a body
+struct BodySample<'a> {
+    format: Format,
+    bytes: &'a [u8],
+    /// The length of the body, if it's known
+    total: Option<usize>,
+    body_limit: usize,
+}
+
+impl<'a> BodySample<'a> {
+    fn new(headers: &HeaderMap, bytes: &'a [u8], body_limit: usize) -> Self {
+        BodySample {
+            format: headers
+                .get(CONTENT_TYPE)
+                .map(Format::of)
+                .unwrap_or(Format::Raw),
+            bytes: &bytes[..bytes.len().min(body_limit)],
+            total: Some(bytes.len()),
+            body_limit,
+        }
+    }
+}
+
+impl fmt::Display for BodySample<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = String::from_utf8_lossy(self.bytes);
+        match self.format {
+            Format::Json => write_indented_json(f, &text)?,
+            Format::Xml => write_indented_xml(f, &text)?,
+            Format::Raw => f.write_str(&text)?,
+        }
+        match self.total {
+            Some(total) if total > self.body_limit => write!(
+                f,
+                "... (truncated to {} of {} bytes)",
+                self.body_limit, total
+            ),
+            Some(_) => Ok(()),
+            None => write!(f, "... (truncated to {} bytes)", self.body_limit),
+        }
+    }
+}
+
+const INDENT: &str = "  ";
+
+fn write_newline(f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+    f.write_str("\n")?;
+    for _ in 0..depth {
+        f.write_str(INDENT)?;
+    }
+    Ok(())
+}
+
+/// Writes `json` with one member per line
+///
+/// This only tracks nesting and strings rather than fully parsing the document, so that
+/// truncated and invalid documents are still written out in full.
+fn write_indented_json(f: &mut fmt::Formatter<'_>, json: &str) -> fmt::Result {
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut empty = false;
+    let mut chars = json.chars().peekable();
+    while let Some(c) = chars.next() {
+        if in_string {
+            write!(f, "{}", c)?;
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => {
+                in_string = true;
+                f.write_str("\"")?;
+            }
+            '{' | '[' => {
+                write!(f, "{}", c)?;
+                // Keep empty objects and arrays on one line
+                while matches!(chars.peek(), Some(c) if c.is_whitespace()) {
+                    chars.next();
+                }
+                if matches!(chars.peek(), Some('}') | Some(']')) {
+                    empty = true;
+                    continue;
+                }
+                depth += 1;
+                write_newline(f, depth)?;
+            }
+            '}' | ']' => {
+                if empty {
+                    empty = false;
+                } else {
+                    depth = depth.saturating_sub(1);
+                    write_newline(f, depth)?;
+                }
+                write!(f, "{}", c)?;
+            }
+            ',' => {
+                f.write_str(",")?;
+                write_newline(f, depth)?;
+            }
+            ':' => f.write_str(": ")?,
+            c if c.is_whitespace() => {}
+            c => write!(f, "{}", c)?,
+        }
+    }
+    Ok(())
+}
+
+/// Writes `xml` with one element per line, keeping elements that only contain text on one line
+///
+/// Like [`write_indented_json`], this is lenient so that truncated documents are written in full.
+fn write_indented_xml(f: &mut fmt::Formatter<'_>, xml: &str) -> fmt::Result {
+    #[derive(PartialEq)]
+    enum Last {
+        Nothing,
+        Open,
+        Close,
+        Text,
+    }
+    let mut depth = 0usize;
+    let mut last = Last::Nothing;
+    let mut rest = xml;
+    while !rest.is_empty() {
+        if !rest.starts_with('<') {
+            let end = rest.find('<').unwrap_or(rest.len());
+            let text = rest[..end].trim();
+            if !text.is_empty() {
+                f.write_str(text)?;
+                last = Last::Text;
+            }
+            rest = &rest[end..];
+            continue;
+        }
+        let terminator = if rest.starts_with("<!--") {
+            "-->"
+        } else if rest.starts_with("<![CDATA[") {
+            "]]>"
+        } else {
+            ">"
+        };
+        let tag = match rest.find(terminator) {
+            Some(end) => &rest[..end + terminator.len()],
+            // Truncated in the middle of a tag
+            None => rest,
+        };
+        rest = &rest[tag.len()..];
+        if tag.starts_with("<![CDATA[") {
+            f.write_str(tag)?;
+            last = Last::Text;
+        } else if tag.starts_with("</") {
+            depth = depth.saturating_sub(1);
+            if last == Last::Close {
+                write_newline(f, depth)?;
+            }
+            f.write_str(tag)?;
+            last = Last::Close;
+        } else {
+            if last != Last::Nothing {
+                write_newline(f, depth)?;
+            }
+            f.write_str(tag)?;
+            let opens_element = tag.ends_with('>')
+                && !tag.ends_with("/>")
+                && !tag.starts_with("<?")
+                && !tag.starts_with("<!");
+            if opens_element {
+                depth += 1;
+                last = Last::Open;
+            } else {
+                last = Last::Close;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::wire_trace::{BodySample, Format, WireTraceLayer};
+    use aws_smithy_http::body::SdkBody;
+    use aws_smithy_http::result::ConnectorError;
+    use bytes::Bytes;
+    use http::HeaderValue;
+    use http_body::Body;
+    use tower::{Layer, ServiceExt};
+
+    fn sample(format: Format, body: &str, body_limit: usize) -> String {
+        BodySample {
+            format,
+            bytes: &body.as_bytes()[..body.len().min(body_limit)],
+            total: Some(body.len()),
+            body_limit,
+        }
+        .to_string()
+    }
+
+    #[test]
+    fn content_types_are_detected() {
+        for (content_type, format) in [
+            ("application/json", Format::Json),
+            ("application/x-amz-json-1.1", Format::Json),
+            ("application/vnd.api+json; charset=utf-8", Format::Json),
+            ("application/xml", Format::Xml),
+            ("Text/XML", Format::Xml),
+            ("application/atom+xml", Format::Xml),
+            ("application/octet-stream", Format::Raw),
+            ("text/plain", Format::Raw),
+        ] {
+            assert_eq!(
+                Format::of(&HeaderValue::from_static(content_type)),
+                format,
+                "{}",
+                content_type
+            );
+        }
+    }
+
+    #[test]
+    fn json_is_indented() {
+        assert_eq!(
+            sample(
+                Format::Json,
+                r#"{"Name":"a, {b}","Tags":[],"Limits":{"Max":1,"Escaped":"\"}"}}"#,
+                1024
+            ),
+            r#"{
+  "Name": "a, {b}",
+  "Tags": [],
+  "Limits": {
+    "Max": 1,
+    "Escaped": "\"}"
+  }
+}"#
+        );
+    }
+
+    #[test]
+    fn xml_is_indented() {
+        assert_eq!(
+            sample(
+                Format::Xml,
+                r#"<?xml version="1.0"?><Result><Name>a &lt; b</Name><Empty/><Items><Item>1</Item><Item></Item></Items></Result>"#,
+                1024
+            ),
+            r#"<?xml version="1.0"?>
+<Result>
+  <Name>a &lt; b</Name>
+  <Empty/>
+  <Items>
+    <Item>1</Item>
+    <Item></Item>
+  </Items>
+</Result>"#
+        );
+    }
+
+    #[test]
+    fn bodies_are_truncated() {
+        assert_eq!(
+            sample(Format::Raw, "hello world", 5),
+            "hello... (truncated to 5 of 11 bytes)"
+        );
+        assert_eq!(
+            sample(Format::Xml, "<a><b>text</b></a>", 8),
+            "<a>\n  <b>te... (truncated to 8 of 18 bytes)"
+        );
+    }
+
+    async fn send(
+        layer: &WireTraceLayer,
+        request_body: SdkBody,
+        response_body: SdkBody,
+    ) -> Vec<u8> {
+        let response_body = std::sync::Mutex::new(Some(response_body));
+        let connector = tower::service_fn(move |_request: http::Request<SdkBody>| {
+            let body = response_body.lock().unwrap().take().unwrap();
+            async move {
+                Ok::<_, ConnectorError>(
+                    http::Response::builder()
+                        // Pretty-printed bodies span several lines, and `logs_contain` only
+                        // matches lines that include the test's span
+                        .header("content-type", "text/plain")
+                        .header("x-amz-security-token", "session-token")
+                        .body(body)
+                        .unwrap(),
+                )
+            }
+        });
+        let request = http::Request::builder()
+            .uri("https://example.com/")
+            .header("authorization", "AWS4-HMAC-SHA256 Credential=secret")
+            .body(request_body)
+            .unwrap();
+        let mut body = layer
+            .layer(connector)
+            .oneshot(request)
+            .await
+            .unwrap()
+            .into_body();
+        let mut out = Vec::new();
+        while let Some(data) = body.data().await {
+            out.extend_from_slice(&data.unwrap());
+        }
+        out
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn requests_and_responses_are_logged() {
+        let layer = WireTraceLayer::new();
+        let body = send(
+            &layer,
+            SdkBody::from(r#"{"Request":1}"#),
+            SdkBody::from(r#"{"Response":2}"#),
+        )
+        .await;
+        assert_eq!(body, br#"{"Response":2}"#);
+        assert!(logs_contain("sending request"));
+        assert!(logs_contain(r#"{"Request":1}"#));
+        assert!(logs_contain("received response"));
+        assert!(logs_contain(r#"{"Response":2}"#));
+        assert!(!logs_contain("secret"));
+        assert!(!logs_contain("session-token"));
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn streaming_response_bodies_are_sampled() {
+        let (mut sender, response_body) = hyper::Body::channel();
+        tokio::spawn(async move {
+            sender.send_data(Bytes::from(r#"{"Stream"#)).await.unwrap();
+            sender.send_data(Bytes::from(r#"ed":3}"#)).await.unwrap();
+        });
+        let body = send(
+            &WireTraceLayer::new().with_body_limit(13),
+            SdkBody::empty(),
+            SdkBody::from(response_body),
+        )
+        .await;
+        assert_eq!(body, br#"{"Streamed":3}"#);
+        assert!(logs_contain(r#"{"Streamed":3... (truncated to 13 bytes)"#));
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn logging_can_be_disabled_at_runtime() {
+        let layer = WireTraceLayer::new().with_enabled(false);
+        let handle = layer.handle();
+        assert!(!handle.is_enabled());
+        send(&layer, SdkBody::from("first"), SdkBody::empty()).await;
+        assert!(!logs_contain("sending request"));
+
+        handle.enable();
+        send(&layer, SdkBody::from("second"), SdkBody::empty()).await;
+        assert!(logs_contain("sending request"));
+        assert!(logs_contain("second"));
+        assert!(!logs_contain("first"));
+    }
+
+    #[test]
+    fn services_are_clone() {
+        fn is_clone<T: Clone>(_: &T) {}
+        let service =
+            WireTraceLayer::new().layer(tower::service_fn(|_: http::Request<SdkBody>| async {
+                Ok::<_, ConnectorError>(http::Response::new(SdkBody::empty()))
+            }));
+        is_clone(&service);
+    }
+}
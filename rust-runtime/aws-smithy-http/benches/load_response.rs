@@ -0,0 +1,116 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Measures the cost of reading response bodies into memory with `load_response`.
+//!
+//! Before the timings, the number of allocations (and bytes allocated) made while loading each
+//! body is printed. Bodies already in memory shouldn't be copied at all, and streamed bodies should
+//! be copied exactly once.
+
+use aws_smithy_http::body::SdkBody;
+use aws_smithy_http::middleware::load_response;
+use aws_smithy_http::operation;
+use aws_smithy_http::response::ParseHttpResponse;
+use bytes::Bytes;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::convert::Infallible;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATED_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+struct ParseBody;
+
+impl ParseHttpResponse for ParseBody {
+    type Output = Result<Bytes, Infallible>;
+
+    fn parse_unloaded(&self, _response: &mut operation::Response) -> Option<Self::Output> {
+        None
+    }
+
+    fn parse_loaded(&self, response: &http::Response<Bytes>) -> Self::Output {
+        Ok(response.body().clone())
+    }
+}
+
+const BODY_SIZE: usize = 8 * 1024 * 1024;
+const CHUNK_SIZE: usize = 16 * 1024;
+
+/// Builds the response a benchmark case loads from the body data
+type MakeResponse = fn(&Bytes) -> operation::Response;
+
+fn in_memory(data: &Bytes) -> operation::Response {
+    operation::Response::new(http::Response::new(SdkBody::from(data.clone())))
+}
+
+fn streamed(data: &Bytes) -> operation::Response {
+    let chunks: Vec<Result<Bytes, Infallible>> = (0..data.len())
+        .step_by(CHUNK_SIZE)
+        .map(|start| Ok(data.slice(start..(start + CHUNK_SIZE).min(data.len()))))
+        .collect();
+    let body = hyper::Body::wrap_stream(futures_util::stream::iter(chunks));
+    operation::Response::new(http::Response::new(SdkBody::from(body)))
+}
+
+fn bench_group(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let data = Bytes::from(vec![b'a'; BODY_SIZE]);
+    let cases: [(&str, MakeResponse); 2] = [("in memory", in_memory), ("streamed", streamed)];
+
+    for (name, response) in cases {
+        let response = response(&data);
+        let (allocations, bytes) = (
+            ALLOCATIONS.load(Ordering::Relaxed),
+            ALLOCATED_BYTES.load(Ordering::Relaxed),
+        );
+        runtime
+            .block_on(load_response(response, &ParseBody))
+            .unwrap();
+        println!(
+            "load_response ({}): {} allocations, {} bytes allocated",
+            name,
+            ALLOCATIONS.load(Ordering::Relaxed) - allocations,
+            ALLOCATED_BYTES.load(Ordering::Relaxed) - bytes
+        );
+    }
+
+    let mut group = c.benchmark_group("load_response");
+    group.throughput(Throughput::Bytes(BODY_SIZE as u64));
+    for (name, response) in cases {
+        group.bench_with_input(BenchmarkId::from_parameter(name), &data, |b, data| {
+            b.iter_batched(
+                || response(data),
+                |response| {
+                    runtime
+                        .block_on(load_response(response, &ParseBody))
+                        .unwrap()
+                },
+                criterion::BatchSize::SmallInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_group);
+criterion_main!(benches);
@@ -0,0 +1,677 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! HTTP auth schemes
+//!
+//! Operations that don't use SigV4 authenticate with one of the HTTP auth schemes defined by
+//! Smithy, e.g. [`httpBearerAuth`](https://awslabs.github.io/smithy/1.0/spec/core/auth-traits.html#httpbearerauth-trait)
+//! or [`httpApiKeyAuth`](https://awslabs.github.io/smithy/1.0/spec/core/auth-traits.html#httpapikeyauth-trait).
+//!
+//! The scheme an operation uses is placed into its property bag as a [`SharedHttpAuthScheme`].
+//! [`HttpAuthStage`] then applies that scheme to the request, using the identity (e.g. a
+//! [`BearerToken`] or an [`ApiKey`]) found in the same property bag. Custom schemes can be
+//! plugged in by implementing [`HttpAuthScheme`].
+//!
+//! Operations that support several schemes (e.g. SigV4, falling back to anonymous access) instead
+//! place their [`AuthSchemeOptions`] into the property bag, in priority order. [`HttpAuthStage`]
+//! applies the first of those schemes that has an identity available.
+
+use crate::body::SdkBody;
+use crate::middleware::MapRequest;
+use crate::operation::Request;
+use crate::pipeline::{PipelineConfig, Stage};
+use crate::property_bag::PropertyBag;
+use crate::query::fmt_string;
+use crate::timing::TimingInfo;
+use aws_smithy_async::time::Instant;
+use http::header::{HeaderName, HeaderValue, AUTHORIZATION};
+use http::Uri;
+use std::error::Error;
+use std::fmt;
+use std::fmt::{Debug, Display, Formatter};
+use std::sync::Arc;
+
+type BoxError = Box<dyn Error + Send + Sync>;
+
+/// Identifier for an auth scheme, e.g. `"http-bearer-auth"`
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct AuthSchemeId {
+    scheme_id: &'static str,
+}
+
+impl AuthSchemeId {
+    /// Creates a new auth scheme ID
+    pub const fn new(scheme_id: &'static str) -> Self {
+        Self { scheme_id }
+    }
+
+    /// Returns the string form of this ID
+    pub const fn as_str(&self) -> &'static str {
+        self.scheme_id
+    }
+}
+
+impl Display for AuthSchemeId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.scheme_id)
+    }
+}
+
+/// ID of the [`HttpBearerAuth`] scheme
+pub const HTTP_BEARER_AUTH_SCHEME_ID: AuthSchemeId = AuthSchemeId::new("http-bearer-auth");
+
+/// ID of the [`HttpApiKeyAuth`] scheme
+pub const HTTP_API_KEY_AUTH_SCHEME_ID: AuthSchemeId = AuthSchemeId::new("http-api-key-auth");
+
+/// ID of the [`NoAuth`] scheme
+pub const NO_AUTH_SCHEME_ID: AuthSchemeId = AuthSchemeId::new("no-auth");
+
+/// A bearer token, sent by [`HttpBearerAuth`]
+///
+/// The token is redacted from the `Debug` output.
+#[derive(Clone, Eq, PartialEq)]
+pub struct BearerToken(Arc<str>);
+
+impl BearerToken {
+    /// Creates a new bearer token
+    pub fn new(token: impl Into<String>) -> Self {
+        Self(token.into().into())
+    }
+
+    /// Returns the token
+    pub fn token(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Debug for BearerToken {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("BearerToken")
+            .field(&"** redacted **")
+            .finish()
+    }
+}
+
+/// An API key, sent by [`HttpApiKeyAuth`]
+///
+/// The key is redacted from the `Debug` output.
+#[derive(Clone, Eq, PartialEq)]
+pub struct ApiKey(Arc<str>);
+
+impl ApiKey {
+    /// Creates a new API key
+    pub fn new(api_key: impl Into<String>) -> Self {
+        Self(api_key.into().into())
+    }
+
+    /// Returns the API key
+    pub fn api_key(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Debug for ApiKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ApiKey").field(&"** redacted **").finish()
+    }
+}
+
+/// Failure to authenticate a request with an [`HttpAuthScheme`]
+#[non_exhaustive]
+#[derive(Debug)]
+pub enum HttpAuthError {
+    /// The identity required by the auth scheme was not in the property bag
+    MissingIdentity { scheme_id: AuthSchemeId },
+
+    /// The identity could not be placed into the request
+    InvalidIdentity {
+        scheme_id: AuthSchemeId,
+        source: BoxError,
+    },
+
+    /// None of the operation's auth scheme options had an identity available
+    NoMatchingAuthScheme { candidates: Vec<AuthSchemeId> },
+
+    /// The auth scheme failed to authenticate the request
+    AuthSchemeFailed {
+        scheme_id: AuthSchemeId,
+        source: BoxError,
+    },
+}
+
+impl Display for HttpAuthError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            HttpAuthError::MissingIdentity { scheme_id } => write!(
+                f,
+                "no identity for the `{}` auth scheme in the property bag",
+                scheme_id
+            ),
+            HttpAuthError::InvalidIdentity { scheme_id, .. } => write!(
+                f,
+                "the identity for the `{}` auth scheme could not be added to the request",
+                scheme_id
+            ),
+            HttpAuthError::NoMatchingAuthScheme { candidates } => {
+                write!(f, "no identity was available for any of the auth schemes [")?;
+                for (idx, scheme_id) in candidates.iter().enumerate() {
+                    if idx > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "`{}`", scheme_id)?;
+                }
+                write!(f, "]")
+            }
+            HttpAuthError::AuthSchemeFailed { scheme_id, .. } => {
+                write!(f, "the `{}` auth scheme failed", scheme_id)
+            }
+        }
+    }
+}
+
+impl Error for HttpAuthError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            HttpAuthError::MissingIdentity { .. } | HttpAuthError::NoMatchingAuthScheme { .. } => {
+                None
+            }
+            HttpAuthError::InvalidIdentity { source, .. }
+            | HttpAuthError::AuthSchemeFailed { source, .. } => Some(source.as_ref() as _),
+        }
+    }
+}
+
+/// An auth scheme that authenticates a request by modifying its headers or URI
+pub trait HttpAuthScheme: Send + Sync + Debug {
+    /// The ID of this auth scheme
+    fn scheme_id(&self) -> AuthSchemeId;
+
+    /// Returns true if the identity this scheme needs is available in `properties`
+    ///
+    /// When choosing between several [`AuthSchemeOptions`], schemes without an identity are skipped.
+    fn has_identity(&self, properties: &PropertyBag) -> bool;
+
+    /// Authenticates `request` with the identity found in `properties`
+    fn apply(
+        &self,
+        request: &mut http::Request<SdkBody>,
+        properties: &mut PropertyBag,
+    ) -> Result<(), HttpAuthError>;
+}
+
+/// An [`HttpAuthScheme`] that can be cloned and placed into a property bag
+#[derive(Clone, Debug)]
+pub struct SharedHttpAuthScheme(Arc<dyn HttpAuthScheme>);
+
+impl SharedHttpAuthScheme {
+    /// Creates a new shared auth scheme
+    pub fn new(scheme: impl HttpAuthScheme + 'static) -> Self {
+        Self(Arc::new(scheme))
+    }
+}
+
+impl HttpAuthScheme for SharedHttpAuthScheme {
+    fn scheme_id(&self) -> AuthSchemeId {
+        self.0.scheme_id()
+    }
+
+    fn has_identity(&self, properties: &PropertyBag) -> bool {
+        self.0.has_identity(properties)
+    }
+
+    fn apply(
+        &self,
+        request: &mut http::Request<SdkBody>,
+        properties: &mut PropertyBag,
+    ) -> Result<(), HttpAuthError> {
+        self.0.apply(request, properties)
+    }
+}
+
+/// The `httpBearerAuth` scheme: sends the [`BearerToken`] in the `Authorization` header
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+pub struct HttpBearerAuth;
+
+impl HttpBearerAuth {
+    /// Creates a new bearer auth scheme
+    pub fn new() -> Self {
+        HttpBearerAuth
+    }
+}
+
+impl HttpAuthScheme for HttpBearerAuth {
+    fn scheme_id(&self) -> AuthSchemeId {
+        HTTP_BEARER_AUTH_SCHEME_ID
+    }
+
+    fn has_identity(&self, properties: &PropertyBag) -> bool {
+        properties.get::<BearerToken>().is_some()
+    }
+
+    fn apply(
+        &self,
+        request: &mut http::Request<SdkBody>,
+        properties: &mut PropertyBag,
+    ) -> Result<(), HttpAuthError> {
+        let token = properties
+            .get::<BearerToken>()
+            .ok_or(HttpAuthError::MissingIdentity {
+                scheme_id: self.scheme_id(),
+            })?;
+        let value = sensitive_header_value(format!("Bearer {}", token.token()), self)?;
+        request.headers_mut().insert(AUTHORIZATION, value);
+        Ok(())
+    }
+}
+
+/// Where [`HttpApiKeyAuth`] places the API key
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ApiKeyLocation {
+    /// Send the API key in a header
+    Header,
+    /// Send the API key in a query string parameter
+    Query,
+}
+
+/// The `httpApiKeyAuth` scheme: sends the [`ApiKey`] in a header or query string parameter
+#[derive(Clone, Debug)]
+pub struct HttpApiKeyAuth {
+    location: ApiKeyLocation,
+    name: String,
+    scheme: Option<String>,
+}
+
+impl HttpApiKeyAuth {
+    /// Creates a new API key auth scheme that places the key in the header or query parameter `name`
+    pub fn new(location: ApiKeyLocation, name: impl Into<String>) -> Self {
+        Self {
+            location,
+            name: name.into(),
+            scheme: None,
+        }
+    }
+
+    /// Prefixes the API key with `scheme` (e.g. `ApiKey`) when it is sent in a header
+    pub fn with_scheme(mut self, scheme: impl Into<String>) -> Self {
+        self.scheme = Some(scheme.into());
+        self
+    }
+}
+
+impl HttpAuthScheme for HttpApiKeyAuth {
+    fn scheme_id(&self) -> AuthSchemeId {
+        HTTP_API_KEY_AUTH_SCHEME_ID
+    }
+
+    fn has_identity(&self, properties: &PropertyBag) -> bool {
+        properties.get::<ApiKey>().is_some()
+    }
+
+    fn apply(
+        &self,
+        request: &mut http::Request<SdkBody>,
+        properties: &mut PropertyBag,
+    ) -> Result<(), HttpAuthError> {
+        let api_key = properties
+            .get::<ApiKey>()
+            .ok_or(HttpAuthError::MissingIdentity {
+                scheme_id: self.scheme_id(),
+            })?;
+        match self.location {
+            ApiKeyLocation::Header => {
+                let name = HeaderName::from_bytes(self.name.as_bytes())
+                    .map_err(|err| invalid_identity(self, err))?;
+                let value = match &self.scheme {
+                    Some(scheme) => format!("{} {}", scheme, api_key.api_key()),
+                    None => api_key.api_key().to_string(),
+                };
+                let value = sensitive_header_value(value, self)?;
+                request.headers_mut().insert(name, value);
+            }
+            ApiKeyLocation::Query => {
+                let param = format!(
+                    "{}={}",
+                    fmt_string(&self.name),
+                    fmt_string(api_key.api_key())
+                );
+                let mut parts = request.uri().clone().into_parts();
+                let path_and_query = match parts.path_and_query.as_ref() {
+                    Some(pq) => match pq.query() {
+                        Some(query) if !query.is_empty() => {
+                            format!("{}?{}&{}", pq.path(), query, param)
+                        }
+                        _ => format!("{}?{}", pq.path(), param),
+                    },
+                    None => format!("/?{}", param),
+                };
+                parts.path_and_query = Some(
+                    path_and_query
+                        .parse()
+                        .map_err(|err| invalid_identity(self, err))?,
+                );
+                *request.uri_mut() =
+                    Uri::from_parts(parts).map_err(|err| invalid_identity(self, err))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An auth scheme that leaves the request unauthenticated
+///
+/// This is usually the last of an operation's [`AuthSchemeOptions`], so that the operation can
+/// still be called anonymously when no other identity is available.
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+pub struct NoAuth;
+
+impl NoAuth {
+    /// Creates a new anonymous auth scheme
+    pub fn new() -> Self {
+        NoAuth
+    }
+}
+
+impl HttpAuthScheme for NoAuth {
+    fn scheme_id(&self) -> AuthSchemeId {
+        NO_AUTH_SCHEME_ID
+    }
+
+    fn has_identity(&self, _properties: &PropertyBag) -> bool {
+        true
+    }
+
+    fn apply(
+        &self,
+        _request: &mut http::Request<SdkBody>,
+        _properties: &mut PropertyBag,
+    ) -> Result<(), HttpAuthError> {
+        Ok(())
+    }
+}
+
+/// The auth schemes an operation supports, in priority order
+///
+/// [`HttpAuthStage`] authenticates the request with the first scheme that
+/// [has an identity](HttpAuthScheme::has_identity) available, e.g. trying SigV4 first and falling
+/// back to [`NoAuth`] when no credentials are configured.
+#[derive(Clone, Debug, Default)]
+pub struct AuthSchemeOptions {
+    schemes: Vec<SharedHttpAuthScheme>,
+}
+
+impl AuthSchemeOptions {
+    /// Creates an empty set of auth scheme options
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `scheme` as the lowest priority option
+    pub fn with_scheme(mut self, scheme: impl HttpAuthScheme + 'static) -> Self {
+        self.schemes.push(SharedHttpAuthScheme::new(scheme));
+        self
+    }
+
+    /// Returns the options, highest priority first
+    pub fn schemes(&self) -> &[SharedHttpAuthScheme] {
+        &self.schemes
+    }
+
+    /// Returns the highest priority scheme that has an identity available in `properties`
+    pub fn resolve(&self, properties: &PropertyBag) -> Result<SharedHttpAuthScheme, HttpAuthError> {
+        self.schemes
+            .iter()
+            .find(|scheme| scheme.has_identity(properties))
+            .cloned()
+            .ok_or_else(|| HttpAuthError::NoMatchingAuthScheme {
+                candidates: self.schemes.iter().map(|s| s.scheme_id()).collect(),
+            })
+    }
+}
+
+fn invalid_identity(scheme: &dyn HttpAuthScheme, err: impl Into<BoxError>) -> HttpAuthError {
+    HttpAuthError::InvalidIdentity {
+        scheme_id: scheme.scheme_id(),
+        source: err.into(),
+    }
+}
+
+fn sensitive_header_value(
+    value: String,
+    scheme: &dyn HttpAuthScheme,
+) -> Result<HeaderValue, HttpAuthError> {
+    let mut value = HeaderValue::try_from(value).map_err(|err| invalid_identity(scheme, err))?;
+    value.set_sensitive(true);
+    Ok(value)
+}
+
+/// Middleware stage that applies the operation's [`HttpAuthScheme`]
+///
+/// The scheme is read from the request's property bag: either resolved from its
+/// [`AuthSchemeOptions`], or given directly as a [`SharedHttpAuthScheme`]. The options take
+/// precedence if both are present. Requests without either, e.g. operations signed by the SigV4
+/// signing stage, are passed through unchanged.
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+pub struct HttpAuthStage;
+
+impl HttpAuthStage {
+    /// Creates a new HTTP auth stage
+    pub fn new() -> Self {
+        HttpAuthStage
+    }
+}
+
+impl MapRequest for HttpAuthStage {
+    type Error = HttpAuthError;
+
+    fn apply(&self, request: Request) -> Result<Request, Self::Error> {
+        request.augment(|mut request, properties| {
+            if PipelineConfig::skips(properties, Stage::Signing) {
+                tracing::debug!("signing is skipped for this operation");
+                return Ok(request);
+            }
+            let scheme = match properties.get::<AuthSchemeOptions>() {
+                Some(options) => Some(options.resolve(properties)?),
+                None => properties.get::<SharedHttpAuthScheme>().cloned(),
+            };
+            if let Some(scheme) = scheme {
+                tracing::trace!(scheme_id = %scheme.scheme_id(), "authenticating request");
+                let start = Instant::now();
+                scheme.apply(&mut request, properties)?;
+                TimingInfo::record(properties).signing = Some(start.elapsed());
+            }
+            Ok(request)
+        })
+    }
+
+    fn name(&self) -> &'static str {
+        "http_auth"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        ApiKey, ApiKeyLocation, AuthSchemeOptions, BearerToken, HttpApiKeyAuth, HttpAuthError,
+        HttpAuthStage, HttpBearerAuth, NoAuth, SharedHttpAuthScheme, HTTP_API_KEY_AUTH_SCHEME_ID,
+        HTTP_BEARER_AUTH_SCHEME_ID,
+    };
+    use crate::body::SdkBody;
+    use crate::middleware::MapRequest;
+    use crate::operation;
+    use crate::pipeline::{PipelineConfig, Stage};
+    use http::header::AUTHORIZATION;
+
+    fn request(uri: &str) -> operation::Request {
+        operation::Request::new(
+            http::Request::builder()
+                .uri(uri)
+                .body(SdkBody::empty())
+                .unwrap(),
+        )
+    }
+
+    #[test]
+    fn requests_without_a_scheme_are_unchanged() {
+        let req = HttpAuthStage::new()
+            .apply(request("https://example.com/"))
+            .unwrap();
+        assert!(req.http().headers().is_empty());
+        assert_eq!("https://example.com/", req.http().uri().to_string());
+    }
+
+    #[test]
+    fn bearer_token_is_sent_in_authorization_header() {
+        let mut req = request("https://example.com/");
+        req.properties_mut()
+            .insert(SharedHttpAuthScheme::new(HttpBearerAuth::new()));
+        req.properties_mut().insert(BearerToken::new("some-token"));
+        let req = HttpAuthStage::new().apply(req).unwrap();
+        let header = req.http().headers().get(AUTHORIZATION).unwrap();
+        assert_eq!("Bearer some-token", header);
+        assert!(header.is_sensitive());
+        assert_eq!(
+            "BearerToken(\"** redacted **\")",
+            format!("{:?}", BearerToken::new("some-token"))
+        );
+    }
+
+    #[test]
+    fn skipped_signing_leaves_the_request_unchanged() {
+        let mut req = request("https://example.com/");
+        req.properties_mut()
+            .insert(SharedHttpAuthScheme::new(HttpBearerAuth::new()));
+        PipelineConfig::new()
+            .skip(Stage::Signing)
+            .apply(&mut req.properties_mut());
+        let req = HttpAuthStage::new()
+            .apply(req)
+            .expect("the missing token is never needed");
+        assert!(req.http().headers().get(AUTHORIZATION).is_none());
+    }
+
+    #[test]
+    fn missing_identity_is_an_error() {
+        let mut req = request("https://example.com/");
+        req.properties_mut()
+            .insert(SharedHttpAuthScheme::new(HttpBearerAuth::new()));
+        let err = HttpAuthStage::new().apply(req).expect_err("no token");
+        assert!(matches!(err, HttpAuthError::MissingIdentity { .. }));
+    }
+
+    #[test]
+    fn api_key_in_header() {
+        let mut req = request("https://example.com/");
+        req.properties_mut().insert(SharedHttpAuthScheme::new(
+            HttpApiKeyAuth::new(ApiKeyLocation::Header, "Authorization").with_scheme("ApiKey"),
+        ));
+        req.properties_mut().insert(ApiKey::new("some-key"));
+        let req = HttpAuthStage::new().apply(req).unwrap();
+        assert_eq!(
+            "ApiKey some-key",
+            req.http().headers().get(AUTHORIZATION).unwrap()
+        );
+
+        let mut req = request("https://example.com/");
+        req.properties_mut()
+            .insert(SharedHttpAuthScheme::new(HttpApiKeyAuth::new(
+                ApiKeyLocation::Header,
+                "x-api-key",
+            )));
+        req.properties_mut().insert(ApiKey::new("some-key"));
+        let req = HttpAuthStage::new().apply(req).unwrap();
+        assert_eq!("some-key", req.http().headers().get("x-api-key").unwrap());
+    }
+
+    #[test]
+    fn api_key_in_query() {
+        let mut req = request("https://example.com/path?foo=bar");
+        req.properties_mut()
+            .insert(SharedHttpAuthScheme::new(HttpApiKeyAuth::new(
+                ApiKeyLocation::Query,
+                "api_key",
+            )));
+        req.properties_mut().insert(ApiKey::new("some key"));
+        let req = HttpAuthStage::new().apply(req).unwrap();
+        assert_eq!(
+            "https://example.com/path?foo=bar&api_key=some%20key",
+            req.http().uri().to_string()
+        );
+
+        let mut req = request("https://example.com/path");
+        req.properties_mut()
+            .insert(SharedHttpAuthScheme::new(HttpApiKeyAuth::new(
+                ApiKeyLocation::Query,
+                "api_key",
+            )));
+        req.properties_mut().insert(ApiKey::new("some-key"));
+        let req = HttpAuthStage::new().apply(req).unwrap();
+        assert_eq!(
+            "https://example.com/path?api_key=some-key",
+            req.http().uri().to_string()
+        );
+    }
+
+    fn fallback_options() -> AuthSchemeOptions {
+        AuthSchemeOptions::new()
+            .with_scheme(HttpBearerAuth::new())
+            .with_scheme(HttpApiKeyAuth::new(ApiKeyLocation::Header, "x-api-key"))
+            .with_scheme(NoAuth::new())
+    }
+
+    #[test]
+    fn highest_priority_scheme_with_identity_is_used() {
+        let mut req = request("https://example.com/");
+        req.properties_mut().insert(fallback_options());
+        req.properties_mut().insert(BearerToken::new("some-token"));
+        req.properties_mut().insert(ApiKey::new("some-key"));
+        let req = HttpAuthStage::new().apply(req).unwrap();
+        assert_eq!(
+            "Bearer some-token",
+            req.http().headers().get(AUTHORIZATION).unwrap()
+        );
+        assert!(req.http().headers().get("x-api-key").is_none());
+    }
+
+    #[test]
+    fn schemes_without_identity_are_skipped() {
+        let mut req = request("https://example.com/");
+        req.properties_mut().insert(fallback_options());
+        req.properties_mut().insert(ApiKey::new("some-key"));
+        let req = HttpAuthStage::new().apply(req).unwrap();
+        assert!(req.http().headers().get(AUTHORIZATION).is_none());
+        assert_eq!("some-key", req.http().headers().get("x-api-key").unwrap());
+
+        let mut req = request("https://example.com/");
+        req.properties_mut().insert(fallback_options());
+        let req = HttpAuthStage::new().apply(req).unwrap();
+        assert!(req.http().headers().is_empty());
+    }
+
+    #[test]
+    fn no_scheme_with_identity_is_an_error() {
+        let mut req = request("https://example.com/");
+        req.properties_mut().insert(
+            AuthSchemeOptions::new()
+                .with_scheme(HttpBearerAuth::new())
+                .with_scheme(HttpApiKeyAuth::new(ApiKeyLocation::Query, "api_key")),
+        );
+        let err = HttpAuthStage::new().apply(req).expect_err("no identity");
+        match &err {
+            HttpAuthError::NoMatchingAuthScheme { candidates } => assert_eq!(
+                &vec![HTTP_BEARER_AUTH_SCHEME_ID, HTTP_API_KEY_AUTH_SCHEME_ID],
+                candidates
+            ),
+            other => panic!("unexpected error: {}", other),
+        }
+        assert_eq!(
+            "no identity was available for any of the auth schemes [`http-bearer-auth`, `http-api-key-auth`]",
+            err.to_string()
+        );
+    }
+}
@@ -339,6 +339,30 @@ mod test {
         assert!(data.is_none());
     }
 
+    #[tokio::test]
+    async fn retryable_body_is_rebuilt_for_each_attempt() {
+        use crate::byte_stream::ByteStream;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        let streaming = || SdkBody::from(hyper::Body::from("streaming data"));
+        assert!(streaming().try_clone().is_none());
+
+        let rebuilds = Arc::new(AtomicUsize::new(0));
+        let counter = rebuilds.clone();
+        let body = SdkBody::retryable(move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+            streaming()
+        });
+        let retry = body.try_clone().expect("retryable bodies can be cloned");
+        assert_eq!(rebuilds.load(Ordering::SeqCst), 2);
+
+        for body in [body, retry] {
+            let data = ByteStream::new(body).collect().await.unwrap().into_bytes();
+            assert_eq!(data.as_ref(), b"streaming data");
+        }
+    }
+
     #[test]
     fn sdkbody_debug_once() {
         let body = SdkBody::from("123");
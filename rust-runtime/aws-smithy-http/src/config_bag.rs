@@ -0,0 +1,307 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Layered, typed configuration storage
+//!
+//! A [`ConfigBag`] is a stack of named [`Layer`]s. Configuration is stored into the topmost layer and
+//! loaded from the first layer (searching from the top down) that contains a value of the
+//! requested type. For example, an operation may add an `"operation"` layer on top of a frozen
+//! `"client"` layer: values set for the operation shadow those of the client without modifying them.
+//!
+//! Unlike [`PropertyBag`], the `Debug` implementation lists every layer along with the types it
+//! contains, and [`ConfigBag::require`] produces an error naming the missing type and the layers
+//! that were searched.
+
+use crate::property_bag::PropertyBag;
+use std::borrow::Cow;
+use std::error::Error;
+use std::fmt;
+use std::fmt::{Debug, Display, Formatter};
+use std::ops::Deref;
+use std::sync::Arc;
+
+/// Marker stored in a layer when a type has been explicitly unset
+struct ExplicitlyUnset<T>(std::marker::PhantomData<fn() -> T>);
+
+/// A named layer of configuration
+pub struct Layer {
+    name: Cow<'static, str>,
+    props: PropertyBag,
+}
+
+impl Debug for Layer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Layer")
+            .field("name", &self.name)
+            .field("contents", &self.props.type_names())
+            .finish()
+    }
+}
+
+impl Layer {
+    /// Creates a new, empty layer
+    pub fn new(name: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            name: name.into(),
+            props: PropertyBag::new(),
+        }
+    }
+
+    /// Returns the name of this layer
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Stores `value` in this layer, replacing any existing value of the same type
+    pub fn put<T: Send + Sync + Debug + 'static>(&mut self, value: T) -> &mut Self {
+        self.props.remove::<ExplicitlyUnset<T>>();
+        self.props.insert(value);
+        self
+    }
+
+    /// Stores `value` in this layer, consuming and returning the layer
+    pub fn with<T: Send + Sync + Debug + 'static>(mut self, value: T) -> Self {
+        self.put(value);
+        self
+    }
+
+    /// Marks `T` as unset in this layer so that values of `T` in lower layers are ignored
+    pub fn unset<T: Send + Sync + Debug + 'static>(&mut self) -> &mut Self {
+        self.props.remove::<T>();
+        self.props
+            .insert(ExplicitlyUnset::<T>(std::marker::PhantomData));
+        self
+    }
+
+    fn lookup<T: Send + Sync + 'static>(&self) -> Lookup<'_, T> {
+        if let Some(value) = self.props.get::<T>() {
+            Lookup::Set(value)
+        } else if self.props.get::<ExplicitlyUnset<T>>().is_some() {
+            Lookup::Unset
+        } else {
+            Lookup::Absent
+        }
+    }
+}
+
+enum Lookup<'a, T> {
+    Set(&'a T),
+    Unset,
+    Absent,
+}
+
+/// A [`ConfigBag`] that can no longer be modified and can be cheaply cloned
+#[derive(Clone, Debug)]
+pub struct FrozenConfigBag(Arc<ConfigBag>);
+
+impl Deref for FrozenConfigBag {
+    type Target = ConfigBag;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl FrozenConfigBag {
+    /// Adds a new, empty layer named `name` on top of this bag
+    pub fn add_layer(&self, name: impl Into<Cow<'static, str>>) -> ConfigBag {
+        ConfigBag {
+            head: Layer::new(name),
+            tail: Some(self.clone()),
+        }
+    }
+
+    /// Adds a new layer on top of this bag, populated by `f`
+    pub fn with_fn(
+        &self,
+        name: impl Into<Cow<'static, str>>,
+        f: impl FnOnce(&mut Layer),
+    ) -> ConfigBag {
+        let mut bag = self.add_layer(name);
+        f(&mut bag.head);
+        bag
+    }
+}
+
+/// Layered, typed configuration
+///
+/// # Examples
+///
+/// ```
+/// use aws_smithy_http::config_bag::ConfigBag;
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Region(&'static str);
+///
+/// let mut client = ConfigBag::base();
+/// client.store_put(Region("us-east-1"));
+/// let client = client.freeze();
+///
+/// let mut operation = client.add_layer("operation");
+/// operation.store_put(Region("us-west-2"));
+/// assert_eq!(operation.load::<Region>(), Some(&Region("us-west-2")));
+/// assert_eq!(client.load::<Region>(), Some(&Region("us-east-1")));
+/// ```
+pub struct ConfigBag {
+    head: Layer,
+    tail: Option<FrozenConfigBag>,
+}
+
+impl Debug for ConfigBag {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConfigBag")
+            .field("layers", &self.layers().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl ConfigBag {
+    /// Creates a new bag with a single, empty `"base"` layer
+    pub fn base() -> Self {
+        Self {
+            head: Layer::new("base"),
+            tail: None,
+        }
+    }
+
+    /// Returns the layers of this bag, from the top down
+    pub fn layers(&self) -> impl Iterator<Item = &Layer> {
+        let mut next = Some(self);
+        std::iter::from_fn(move || {
+            let bag = next?;
+            next = bag.tail.as_deref();
+            Some(&bag.head)
+        })
+    }
+
+    /// Loads the value of type `T` from the topmost layer that contains one
+    ///
+    /// Returns `None` if no layer contains a `T`, or if `T` was explicitly unset in a layer above
+    /// the first layer that contains one.
+    pub fn load<T: Send + Sync + Debug + 'static>(&self) -> Option<&T> {
+        for layer in self.layers() {
+            match layer.lookup::<T>() {
+                Lookup::Set(value) => return Some(value),
+                Lookup::Unset => return None,
+                Lookup::Absent => {}
+            }
+        }
+        None
+    }
+
+    /// Loads the value of type `T`, returning an error describing the bag when it is missing
+    pub fn require<T: Send + Sync + Debug + 'static>(&self) -> Result<&T, MissingConfig> {
+        self.load::<T>().ok_or_else(|| MissingConfig {
+            type_name: std::any::type_name::<T>(),
+            layers: self
+                .layers()
+                .map(|layer| layer.name().to_string())
+                .collect(),
+        })
+    }
+
+    /// Stores `value` in the topmost layer of this bag
+    pub fn store_put<T: Send + Sync + Debug + 'static>(&mut self, value: T) -> &mut Self {
+        self.head.put(value);
+        self
+    }
+
+    /// Marks `T` as unset in the topmost layer so that values of `T` in lower layers are ignored
+    pub fn unset<T: Send + Sync + Debug + 'static>(&mut self) -> &mut Self {
+        self.head.unset::<T>();
+        self
+    }
+
+    /// Freezes this bag so that new layers can be added on top of it
+    pub fn freeze(self) -> FrozenConfigBag {
+        FrozenConfigBag(Arc::new(self))
+    }
+}
+
+/// Error returned by [`ConfigBag::require`] when a required type isn't present
+#[derive(Debug)]
+pub struct MissingConfig {
+    type_name: &'static str,
+    layers: Vec<String>,
+}
+
+impl Display for MissingConfig {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "no `{}` was found in the config bag (searched layers: {})",
+            self.type_name,
+            self.layers.join(", ")
+        )
+    }
+}
+
+impl Error for MissingConfig {}
+
+#[cfg(test)]
+mod test {
+    use crate::config_bag::ConfigBag;
+
+    #[derive(Debug, PartialEq)]
+    struct Region(&'static str);
+
+    #[derive(Debug, PartialEq)]
+    struct Retries(u32);
+
+    #[test]
+    fn layers_shadow_lower_layers() {
+        let mut base = ConfigBag::base();
+        base.store_put(Region("us-east-1")).store_put(Retries(3));
+        let base = base.freeze();
+
+        let client = base
+            .with_fn("client", |layer| {
+                layer.put(Retries(5));
+            })
+            .freeze();
+        let mut operation = client.add_layer("operation");
+        operation.store_put(Region("us-west-2"));
+
+        assert_eq!(operation.load::<Region>(), Some(&Region("us-west-2")));
+        assert_eq!(operation.load::<Retries>(), Some(&Retries(5)));
+        assert_eq!(client.load::<Region>(), Some(&Region("us-east-1")));
+        assert_eq!(base.load::<Retries>(), Some(&Retries(3)));
+        assert_eq!(
+            operation
+                .layers()
+                .map(|layer| layer.name())
+                .collect::<Vec<_>>(),
+            vec!["operation", "client", "base"]
+        );
+    }
+
+    #[test]
+    fn unset_hides_lower_layers() {
+        let mut base = ConfigBag::base();
+        base.store_put(Retries(3));
+        let mut operation = base.freeze().add_layer("operation");
+        operation.unset::<Retries>();
+        assert_eq!(operation.load::<Retries>(), None);
+
+        operation.store_put(Retries(1));
+        assert_eq!(operation.load::<Retries>(), Some(&Retries(1)));
+    }
+
+    #[test]
+    fn missing_config_is_actionable() {
+        let mut base = ConfigBag::base();
+        base.store_put(Retries(3));
+        let operation = base.freeze().add_layer("operation");
+
+        let err = operation.require::<Region>().expect_err("no region");
+        let message = format!("{}", err);
+        assert!(message.contains("Region"), "{}", message);
+        assert!(message.contains("operation, base"), "{}", message);
+
+        let debug = format!("{:?}", operation);
+        assert!(debug.contains("\"operation\""), "{}", debug);
+        assert!(debug.contains("Retries"), "{}", debug);
+    }
+}
@@ -0,0 +1,385 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Context for explaining why an operation failed.
+//!
+//! While an operation is sent, middleware records what it did in a [`DiagnosticContext`] in the
+//! operation's property bag: the endpoint the request was sent to, how it was signed, where the
+//! credentials came from, and the outcome of each attempt.
+//! [`SdkError::diagnostic`](crate::result::SdkError::diagnostic) renders this into a report.
+
+use crate::metrics::AttemptOutcome;
+use crate::operation;
+use crate::property_bag::PropertyBag;
+use aws_smithy_async::time::Instant;
+use std::error::Error;
+use std::fmt::Write;
+use std::time::Duration;
+
+/// What was done while sending an operation
+#[non_exhaustive]
+#[derive(Clone, Debug, Default)]
+pub struct DiagnosticContext {
+    /// Every attempt made so far, in order
+    pub attempts: Vec<AttemptRecord>,
+    /// The endpoint the last attempt was sent to, without its query string
+    pub endpoint: Option<String>,
+    /// The region the request was signed for
+    pub signing_region: Option<String>,
+    /// The service the request was signed for
+    pub signing_service: Option<String>,
+    /// The name of the credentials provider that supplied the credentials
+    pub credentials_source: Option<String>,
+}
+
+impl DiagnosticContext {
+    /// Creates an empty `DiagnosticContext`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the `DiagnosticContext` from `properties`, adding it if necessary
+    pub fn record(properties: &mut PropertyBag) -> &mut DiagnosticContext {
+        if properties.get::<DiagnosticContext>().is_none() {
+            properties.insert(DiagnosticContext::new());
+        }
+        properties
+            .get_mut::<DiagnosticContext>()
+            .expect("inserted above")
+    }
+
+    /// Records that a new attempt is starting
+    ///
+    /// The time since the previous attempt completed is recorded as that attempt's
+    /// [`delay`](AttemptRecord::delay).
+    pub fn start_attempt(&mut self) {
+        if let Some(previous) = self.attempts.last_mut() {
+            previous.delay = Some(previous.completed_at.elapsed());
+        }
+    }
+
+    /// Records that a timeout interrupted `attempt`
+    ///
+    /// Nothing is recorded if `attempt` had already completed, e.g. when an API call timeout
+    /// elapses while the client waits to retry.
+    pub fn attempt_timed_out(&mut self, attempt: u32) {
+        if self.attempts.last().map(|record| record.attempt) < Some(attempt) {
+            self.attempts
+                .push(AttemptRecord::new(attempt, AttemptOutcome::Timeout));
+        }
+    }
+}
+
+/// The outcome of a single attempt
+#[non_exhaustive]
+#[derive(Clone, Debug)]
+pub struct AttemptRecord {
+    /// The attempt number, starting at `1` for the initial attempt
+    pub attempt: u32,
+    /// The outcome of the attempt
+    pub outcome: AttemptOutcome,
+    /// The HTTP status code of the response, if a response was received
+    pub status: Option<http::StatusCode>,
+    /// The error the attempt failed with, if any
+    pub error: Option<String>,
+    /// How long the client waited before making the next attempt, if one was made
+    pub delay: Option<Duration>,
+    completed_at: Instant,
+}
+
+impl AttemptRecord {
+    /// Creates a record of an attempt that has just completed
+    pub fn new(attempt: u32, outcome: AttemptOutcome) -> Self {
+        Self {
+            attempt,
+            outcome,
+            status: None,
+            error: None,
+            delay: None,
+            completed_at: Instant::now(),
+        }
+    }
+
+    /// Sets the HTTP status code of the response
+    pub fn with_status(mut self, status: Option<http::StatusCode>) -> Self {
+        self.status = status;
+        self
+    }
+
+    /// Sets the error the attempt failed with
+    pub fn with_error(mut self, error: Option<String>) -> Self {
+        self.error = error;
+        self
+    }
+}
+
+/// What's known about the request an error is reported for
+pub(crate) enum Report<'a> {
+    /// A response was received, and the context is in its property bag
+    Response(&'a operation::Response),
+    /// No response was received, but the client recorded what it did
+    NoResponse(&'a DiagnosticContext),
+    /// Nothing is known beyond the error itself
+    Unavailable,
+}
+
+/// Renders the report returned by [`SdkError::diagnostic`](crate::result::SdkError::diagnostic)
+pub(crate) fn render(summary: &str, error: &(dyn Error + 'static), report: Report<'_>) -> String {
+    let mut out = String::new();
+    // Writing to a `String` can't fail
+    let _ = write_report(&mut out, summary, error, report);
+    out
+}
+
+fn write_report(
+    out: &mut String,
+    summary: &str,
+    error: &(dyn Error + 'static),
+    report: Report<'_>,
+) -> std::fmt::Result {
+    writeln!(out, "{}: {}", summary, error)?;
+    let mut source = error.source();
+    while let Some(err) = source {
+        writeln!(out, "  caused by: {}", err)?;
+        source = err.source();
+    }
+
+    let context = match report {
+        Report::Response(response) => {
+            writeln!(out, "Status: {}", response.status())?;
+            if let Some(request_id) = response.request_id() {
+                writeln!(out, "Request ID: {}", request_id)?;
+            }
+            let properties = response.properties();
+            properties
+                .get::<DiagnosticContext>()
+                .cloned()
+                .unwrap_or_default()
+        }
+        Report::NoResponse(context) => {
+            writeln!(out, "Status: no response was received")?;
+            context.clone()
+        }
+        Report::Unavailable => {
+            return write!(
+                out,
+                "No response was received, so no further details are available."
+            )
+        }
+    };
+
+    let or_unknown = |value: &Option<String>| value.clone().unwrap_or_else(|| "unknown".into());
+    writeln!(out, "Endpoint: {}", or_unknown(&context.endpoint))?;
+    match (&context.signing_region, &context.signing_service) {
+        (None, None) => writeln!(out, "Signing: not signed with SigV4")?,
+        (region, service) => writeln!(
+            out,
+            "Signing: region `{}`, service `{}`",
+            or_unknown(region),
+            or_unknown(service)
+        )?,
+    }
+    match &context.credentials_source {
+        Some(source) => writeln!(out, "Credentials: provided by `{}`", source)?,
+        None => writeln!(out, "Credentials: none were loaded")?,
+    }
+    write!(out, "Attempts:")?;
+    if context.attempts.is_empty() {
+        write!(out, " unknown")?;
+    }
+    for attempt in &context.attempts {
+        write!(out, "\n  {}: ", attempt.attempt)?;
+        if let Some(status) = attempt.status {
+            write!(out, "{}, ", status)?;
+        }
+        write!(out, "{}", outcome_description(attempt.outcome))?;
+        if let Some(error) = &attempt.error {
+            write!(out, ": {}", error)?;
+        }
+        if let Some(delay) = attempt.delay {
+            write!(out, "; retried after {:?}", delay)?;
+        }
+    }
+    Ok(())
+}
+
+fn outcome_description(outcome: AttemptOutcome) -> &'static str {
+    match outcome {
+        AttemptOutcome::Success => "success",
+        AttemptOutcome::ServiceError => "service error",
+        AttemptOutcome::ResponseError => "response error",
+        AttemptOutcome::DispatchFailure => "dispatch failure",
+        AttemptOutcome::ConstructionFailure => "construction failure",
+        AttemptOutcome::Timeout => "timeout",
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::body::SdkBody;
+    use crate::diagnostic::{AttemptRecord, DiagnosticContext};
+    use crate::metrics::AttemptOutcome;
+    use crate::operation;
+    use crate::property_bag::SharedPropertyBag;
+    use crate::result::{ConnectorError, RequestTimeoutError, SdkError};
+    use http::StatusCode;
+    use std::fmt;
+
+    #[derive(Debug)]
+    struct AccessDenied;
+
+    impl fmt::Display for AccessDenied {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "AccessDenied: Access Denied")
+        }
+    }
+
+    impl std::error::Error for AccessDenied {}
+
+    fn response(status: u16) -> operation::Response {
+        operation::Response::from_parts(
+            http::Response::builder()
+                .status(status)
+                .header("x-amzn-requestid", "request-1234")
+                .body(SdkBody::empty())
+                .unwrap(),
+            SharedPropertyBag::new(),
+        )
+    }
+
+    #[test]
+    fn service_errors_include_the_attempt_history() {
+        let mut raw = response(403);
+        {
+            let mut properties = raw.properties_mut();
+            let context = DiagnosticContext::record(&mut properties);
+            context.endpoint = Some("https://bucket.s3.us-east-1.amazonaws.com/key".into());
+            context.signing_region = Some("us-east-1".into());
+            context.signing_service = Some("s3".into());
+            context.credentials_source = Some("Environment".into());
+            context.attempts.push(
+                AttemptRecord::new(1, AttemptOutcome::ServiceError)
+                    .with_status(Some(StatusCode::SERVICE_UNAVAILABLE))
+                    .with_error(Some("SlowDown".into())),
+            );
+            context.start_attempt();
+            context.attempts[0].delay = Some(std::time::Duration::from_millis(250));
+            context.attempts.push(
+                AttemptRecord::new(2, AttemptOutcome::ServiceError)
+                    .with_status(Some(StatusCode::FORBIDDEN))
+                    .with_error(Some("AccessDenied: Access Denied".into())),
+            );
+        }
+        let err = SdkError::ServiceError {
+            err: AccessDenied,
+            raw,
+        };
+        assert_eq!(
+            err.diagnostic(),
+            "Service error: AccessDenied: Access Denied
+Status: 403 Forbidden
+Request ID: request-1234
+Endpoint: https://bucket.s3.us-east-1.amazonaws.com/key
+Signing: region `us-east-1`, service `s3`
+Credentials: provided by `Environment`
+Attempts:
+  1: 503 Service Unavailable, service error: SlowDown; retried after 250ms
+  2: 403 Forbidden, service error: AccessDenied: Access Denied"
+        );
+    }
+
+    #[test]
+    fn missing_context_is_reported_as_unknown() {
+        let err = SdkError::ServiceError {
+            err: AccessDenied,
+            raw: response(403),
+        };
+        let diagnostic = err.diagnostic();
+        assert!(diagnostic.contains("Endpoint: unknown"), "{}", diagnostic);
+        assert!(
+            diagnostic.contains("Signing: not signed with SigV4"),
+            "{}",
+            diagnostic
+        );
+        assert!(diagnostic.ends_with("Attempts: unknown"), "{}", diagnostic);
+    }
+
+    #[test]
+    fn errors_without_a_response_include_the_cause() {
+        let err = SdkError::<AccessDenied>::DispatchFailure(ConnectorError::io(
+            std::io::Error::new(std::io::ErrorKind::ConnectionReset, "connection reset").into(),
+        ));
+        assert_eq!(
+            err.diagnostic(),
+            "Dispatch failure: io error: connection reset
+  caused by: connection reset
+No response was received, so no further details are available."
+        );
+    }
+
+    #[test]
+    fn dispatch_failures_include_the_attempt_history() {
+        let mut context = DiagnosticContext::new();
+        context.endpoint = Some("https://sts.us-east-1.amazonaws.com/".into());
+        context.attempts.push(
+            AttemptRecord::new(1, AttemptOutcome::DispatchFailure)
+                .with_error(Some("io error: connection reset".into())),
+        );
+        let err = SdkError::<AccessDenied>::DispatchFailure(
+            ConnectorError::io(
+                std::io::Error::new(std::io::ErrorKind::ConnectionReset, "connection reset").into(),
+            )
+            .with_diagnostic_context(Some(context)),
+        );
+        assert_eq!(
+            err.diagnostic(),
+            "Dispatch failure: io error: connection reset
+  caused by: connection reset
+Status: no response was received
+Endpoint: https://sts.us-east-1.amazonaws.com/
+Signing: not signed with SigV4
+Credentials: none were loaded
+Attempts:
+  1: dispatch failure: io error: connection reset"
+        );
+    }
+
+    #[test]
+    fn timeouts_record_the_interrupted_attempt_once() {
+        let mut context = DiagnosticContext::new();
+        context
+            .attempts
+            .push(AttemptRecord::new(1, AttemptOutcome::ServiceError));
+        context.attempt_timed_out(1);
+        context.attempt_timed_out(2);
+        context.attempt_timed_out(2);
+        let outcomes: Vec<_> = context
+            .attempts
+            .iter()
+            .map(|record| (record.attempt, record.outcome))
+            .collect();
+        assert_eq!(
+            vec![
+                (1, AttemptOutcome::ServiceError),
+                (2, AttemptOutcome::Timeout)
+            ],
+            outcomes
+        );
+
+        let err = SdkError::<AccessDenied>::TimeoutError(Box::new(
+            RequestTimeoutError::new("API call", std::time::Duration::from_secs(1))
+                .with_diagnostic_context(Some(context)),
+        ));
+        assert!(
+            err.diagnostic().ends_with(
+                "Attempts:
+  1: service error
+  2: timeout"
+            ),
+            "{}",
+            err.diagnostic()
+        );
+    }
+}
@@ -4,12 +4,104 @@
  */
 
 use std::borrow::Cow;
+use std::error::Error;
+use std::fmt;
+use std::fmt::{Display, Formatter};
 use std::str::FromStr;
+use std::sync::Arc;
 
 use http::uri::{Authority, Uri};
 
 use crate::operation::BuildError;
 
+pub use aws_smithy_types::endpoint::Endpoint as SmithyEndpoint;
+
+/// The result of resolving an endpoint
+pub type ResolveEndpointResult = Result<SmithyEndpoint, ResolveEndpointError>;
+
+/// Resolves an endpoint from a set of typed endpoint parameters
+///
+/// Unlike a static [`Endpoint`], which can only produce a URI, a resolver receives the parameters
+/// relevant to a request (e.g. the region, a bucket, or feature flags such as FIPS) and returns an
+/// endpoint with the headers and properties (e.g. auth schemes) needed to send the request.
+pub trait ResolveEndpoint<Params>: Send + Sync {
+    /// Resolves an endpoint for the given `params`
+    fn resolve_endpoint(&self, params: &Params) -> ResolveEndpointResult;
+}
+
+impl<T, Params> ResolveEndpoint<Params> for Arc<T>
+where
+    T: ResolveEndpoint<Params> + ?Sized,
+{
+    fn resolve_endpoint(&self, params: &Params) -> ResolveEndpointResult {
+        self.as_ref().resolve_endpoint(params)
+    }
+}
+
+/// A static endpoint resolves to the same URI regardless of the parameters
+impl<Params> ResolveEndpoint<Params> for Endpoint {
+    fn resolve_endpoint(&self, _params: &Params) -> ResolveEndpointResult {
+        Ok(SmithyEndpoint::builder().url(self.uri.to_string()).build())
+    }
+}
+
+/// Error indicating that an endpoint could not be resolved
+#[derive(Debug)]
+pub struct ResolveEndpointError {
+    message: Cow<'static, str>,
+    source: Option<Box<dyn Error + Send + Sync>>,
+}
+
+impl ResolveEndpointError {
+    /// Creates a new error with the given message
+    pub fn message(message: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            message: message.into(),
+            source: None,
+        }
+    }
+
+    /// Adds the underlying cause of this error
+    pub fn with_source(self, source: Option<Box<dyn Error + Send + Sync>>) -> Self {
+        Self { source, ..self }
+    }
+}
+
+impl Display for ResolveEndpointError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl Error for ResolveEndpointError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.source.as_ref().map(|err| err.as_ref() as _)
+    }
+}
+
+/// Applies a resolved endpoint to `uri`, potentially applying the specified `prefix` in the process.
+pub fn apply_endpoint(
+    uri: &mut Uri,
+    endpoint: &SmithyEndpoint,
+    prefix: Option<&EndpointPrefix>,
+) -> Result<(), ResolveEndpointError> {
+    let endpoint_uri = Uri::from_str(endpoint.url()).map_err(|err| {
+        ResolveEndpointError::message(format!(
+            "resolved endpoint `{}` is not a valid URI",
+            endpoint.url()
+        ))
+        .with_source(Some(err.into()))
+    })?;
+    if endpoint_uri.scheme().is_none() || endpoint_uri.authority().is_none() {
+        return Err(ResolveEndpointError::message(format!(
+            "resolved endpoint `{}` must include a scheme and authority",
+            endpoint.url()
+        )));
+    }
+    Endpoint::mutable(endpoint_uri).set_endpoint(uri, prefix);
+    Ok(())
+}
+
 /// API Endpoint
 ///
 /// This implements an API endpoint as specified in the
@@ -80,7 +172,23 @@ impl Endpoint {
         }
     }
 
+    /// Returns the URI of this endpoint
+    pub fn uri(&self) -> &Uri {
+        &self.uri
+    }
+
+    /// Returns true if this endpoint ignores mutations such as endpoint prefixes
+    pub fn is_immutable(&self) -> bool {
+        self.immutable
+    }
+
     /// Sets the endpoint on `uri`, potentially applying the specified `prefix` in the process.
+    ///
+    /// The scheme and authority of `uri` are replaced by the endpoint's, while their paths and
+    /// queries are merged: the path of `uri` is appended to the endpoint's base path (e.g.
+    /// `http://localhost:4566/base`), and the endpoint's query parameters are placed before those
+    /// of `uri`. Both are taken verbatim, so percent-encoded segments are never decoded or
+    /// re-encoded.
     pub fn set_endpoint(&self, uri: &mut http::Uri, prefix: Option<&EndpointPrefix>) {
         let prefix = prefix.map(|p| p.0.as_str()).unwrap_or("");
         let authority = self
@@ -98,27 +206,41 @@ impl Endpoint {
         let new_uri = Uri::builder()
             .authority(authority)
             .scheme(scheme.clone())
-            .path_and_query(Self::merge_paths(&self.uri, uri).as_ref())
+            .path_and_query(Self::merge_path_and_query(&self.uri, uri).as_ref())
             .build()
             .expect("valid uri");
         *uri = new_uri;
     }
 
-    fn merge_paths<'a>(endpoint: &'a Uri, uri: &'a Uri) -> Cow<'a, str> {
-        if let Some(query) = endpoint.path_and_query().and_then(|pq| pq.query()) {
-            tracing::warn!(query = %query, "query specified in endpoint will be ignored during endpoint resolution");
-        }
+    fn merge_path_and_query<'a>(endpoint: &'a Uri, uri: &'a Uri) -> Cow<'a, str> {
         let endpoint_path = endpoint.path();
+        let base_path = endpoint_path.strip_suffix('/').unwrap_or(endpoint_path);
+        let endpoint_query = endpoint.query().filter(|query| !query.is_empty());
         let uri_path_and_query = uri.path_and_query().map(|pq| pq.as_str()).unwrap_or("");
-        if endpoint_path.is_empty() {
-            Cow::Borrowed(uri_path_and_query)
-        } else {
-            let ep_no_slash = endpoint_path.strip_suffix('/').unwrap_or(endpoint_path);
-            let uri_path_no_slash = uri_path_and_query
-                .strip_prefix('/')
-                .unwrap_or(uri_path_and_query);
-            Cow::Owned(format!("{}/{}", ep_no_slash, uri_path_no_slash))
+        if base_path.is_empty() && endpoint_query.is_none() {
+            return Cow::Borrowed(uri_path_and_query);
         }
+
+        let uri_path = uri.path();
+        let mut merged = String::with_capacity(endpoint_path.len() + uri_path_and_query.len() + 1);
+        merged.push_str(base_path);
+        merged.push('/');
+        merged.push_str(uri_path.strip_prefix('/').unwrap_or(uri_path));
+        let uri_query = uri.query().filter(|query| !query.is_empty());
+        match (endpoint_query, uri_query) {
+            (Some(endpoint_query), Some(uri_query)) => {
+                merged.push('?');
+                merged.push_str(endpoint_query);
+                merged.push('&');
+                merged.push_str(uri_query);
+            }
+            (Some(query), None) | (None, Some(query)) => {
+                merged.push('?');
+                merged.push_str(query);
+            }
+            (None, None) => {}
+        }
+        Cow::Owned(merged)
     }
 }
 
@@ -126,7 +248,9 @@ impl Endpoint {
 mod test {
     use http::Uri;
 
-    use crate::endpoint::{Endpoint, EndpointPrefix};
+    use crate::endpoint::{
+        apply_endpoint, Endpoint, EndpointPrefix, ResolveEndpoint, SmithyEndpoint,
+    };
 
     #[test]
     fn prefix_endpoint() {
@@ -201,4 +325,137 @@ mod test {
         ep.set_endpoint(&mut uri, None);
         assert_eq!(uri, Uri::from_static("http://localhost:8000/"))
     }
+
+    #[test]
+    fn static_endpoints_resolve_to_their_uri() {
+        let ep = Endpoint::immutable(Uri::from_static("http://localhost:8000"));
+        let resolved = ep
+            .resolve_endpoint(&())
+            .expect("static endpoints always resolve");
+        assert_eq!(resolved.url(), "http://localhost:8000/");
+    }
+
+    #[test]
+    fn apply_resolved_endpoint() {
+        let ep = SmithyEndpoint::builder()
+            .url("https://us-east-1.dynamo.amazonaws.com")
+            .build();
+        let mut uri = Uri::from_static("/list_tables?k=v");
+        apply_endpoint(
+            &mut uri,
+            &ep,
+            Some(&EndpointPrefix::new("subregion.").expect("valid prefix")),
+        )
+        .expect("valid endpoint");
+        assert_eq!(
+            uri,
+            Uri::from_static("https://subregion.us-east-1.dynamo.amazonaws.com/list_tables?k=v")
+        );
+
+        let invalid = SmithyEndpoint::builder().url("/no-authority").build();
+        apply_endpoint(&mut uri, &invalid, None).expect_err("endpoints must have an authority");
+    }
+
+    #[test]
+    fn endpoint_paths_and_queries_are_merged() {
+        let cases = [
+            // (endpoint, request, expected)
+            ("http://localhost:4566", "/", "http://localhost:4566/"),
+            (
+                "http://localhost:4566/",
+                "/bucket",
+                "http://localhost:4566/bucket",
+            ),
+            (
+                "http://localhost:4566",
+                "/bucket?list-type=2",
+                "http://localhost:4566/bucket?list-type=2",
+            ),
+            // base paths are preserved, with or without a trailing slash
+            (
+                "http://localhost:4566/base",
+                "/",
+                "http://localhost:4566/base/",
+            ),
+            (
+                "http://localhost:4566/base",
+                "/bucket/key?x-id=GetObject",
+                "http://localhost:4566/base/bucket/key?x-id=GetObject",
+            ),
+            (
+                "http://localhost:4566/base/",
+                "/bucket/key?x-id=GetObject",
+                "http://localhost:4566/base/bucket/key?x-id=GetObject",
+            ),
+            (
+                "http://localhost:4566/a/b/c",
+                "/d",
+                "http://localhost:4566/a/b/c/d",
+            ),
+            // percent-encoded segments are neither decoded nor re-encoded
+            (
+                "http://localhost:4566/my%20base",
+                "/key%2Fwith%2Fslashes",
+                "http://localhost:4566/my%20base/key%2Fwith%2Fslashes",
+            ),
+            (
+                "http://localhost:4566/base",
+                "/a%3Db?prefix=a%26b&delimiter=%2F",
+                "http://localhost:4566/base/a%3Db?prefix=a%26b&delimiter=%2F",
+            ),
+            // empty segments in the request path are significant (e.g. S3 keys that start with `/`)
+            (
+                "http://localhost:4566/base",
+                "/bucket//key",
+                "http://localhost:4566/base/bucket//key",
+            ),
+            // query parameters on the endpoint come before those of the request
+            (
+                "https://example.com/base?sig=abc",
+                "/op?x=1",
+                "https://example.com/base/op?sig=abc&x=1",
+            ),
+            (
+                "https://example.com?sig=abc",
+                "/op",
+                "https://example.com/op?sig=abc",
+            ),
+            (
+                "https://example.com/?sig=abc",
+                "/op?",
+                "https://example.com/op?sig=abc",
+            ),
+            (
+                "https://example.com/base?",
+                "/op?x=1",
+                "https://example.com/base/op?x=1",
+            ),
+        ];
+        for (endpoint, request, expected) in cases {
+            let ep = Endpoint::immutable(Uri::from_static(endpoint));
+            let mut uri = Uri::from_static(request);
+            ep.set_endpoint(&mut uri, None);
+            assert_eq!(
+                uri,
+                Uri::from_static(expected),
+                "endpoint: {}, request: {}",
+                endpoint,
+                request
+            );
+        }
+    }
+
+    #[test]
+    fn prefixes_apply_to_endpoints_with_base_paths() {
+        let ep = Endpoint::mutable(Uri::from_static("http://localhost:4566/base?sig=abc"));
+        let mut uri = Uri::from_static("/list_tables?k=v");
+        ep.set_endpoint(
+            &mut uri,
+            Some(&EndpointPrefix::new("subregion.").expect("valid prefix")),
+        );
+        assert_eq!(
+            uri,
+            Uri::from_static("http://subregion.localhost:4566/base/list_tables?sig=abc&k=v")
+        );
+    }
 }
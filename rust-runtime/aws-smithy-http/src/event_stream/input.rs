@@ -5,7 +5,7 @@
 
 use super::BoxError;
 use crate::result::SdkError;
-use aws_smithy_eventstream::frame::{MarshallMessage, SignMessage};
+use aws_smithy_eventstream::frame::{MarshallMessage, Message, SignMessage};
 use bytes::Bytes;
 use futures_core::Stream;
 use pin_project::pin_project;
@@ -35,6 +35,19 @@ impl<T> EventStreamInput<T> {
     ) -> MessageStreamAdapter<T, E> {
         MessageStreamAdapter::new(marshaller, signer, self.input_stream)
     }
+
+    /// Like [`into_body_stream`](EventStreamInput::into_body_stream), but sends the given
+    /// `initial-request` message ahead of the input stream's events.
+    #[doc(hidden)]
+    pub fn into_body_stream_with_initial_request<E: StdError + Send + Sync + 'static>(
+        self,
+        marshaller: impl MarshallMessage<Input = T> + Send + Sync + 'static,
+        signer: impl SignMessage + Send + Sync + 'static,
+        initial_request: Message,
+    ) -> MessageStreamAdapter<T, E> {
+        MessageStreamAdapter::new(marshaller, signer, self.input_stream)
+            .with_initial_message(initial_request)
+    }
 }
 
 impl<T, S> From<S> for EventStreamInput<T>
@@ -53,12 +66,15 @@ where
 ///
 /// This will yield an `Err(SdkError::ConstructionFailure)` if a message can't be
 /// marshalled into an Event Stream frame, (e.g., if the message payload was too large).
+///
+/// If an initial message is set, it is signed and sent before any messages from the stream.
 #[pin_project]
 pub struct MessageStreamAdapter<T, E> {
     marshaller: Box<dyn MarshallMessage<Input = T> + Send + Sync>,
     signer: Box<dyn SignMessage + Send + Sync>,
     #[pin]
     stream: Pin<Box<dyn Stream<Item = Result<T, BoxError>> + Send>>,
+    initial_message: Option<Message>,
     end_signal_sent: bool,
     _phantom: PhantomData<E>,
 }
@@ -76,10 +92,27 @@ where
             marshaller: Box::new(marshaller),
             signer: Box::new(signer),
             stream,
+            initial_message: None,
             end_signal_sent: false,
             _phantom: Default::default(),
         }
     }
+
+    /// Sets a message to send ahead of the stream's messages, such as an `initial-request`.
+    pub fn with_initial_message(mut self, message: Message) -> Self {
+        self.initial_message = Some(message);
+        self
+    }
+}
+
+fn sign_and_write(
+    signer: &mut (dyn SignMessage + Send + Sync),
+    message: Message,
+) -> Result<Bytes, BoxError> {
+    let message = signer.sign(message)?;
+    let mut buffer = Vec::new();
+    message.write_to(&mut buffer)?;
+    Ok(Bytes::from(buffer))
 }
 
 impl<T, E> Stream for MessageStreamAdapter<T, E>
@@ -90,6 +123,12 @@ where
 
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
         let this = self.project();
+        if let Some(initial_message) = this.initial_message.take() {
+            return Poll::Ready(Some(
+                sign_and_write(this.signer.as_mut(), initial_message)
+                    .map_err(SdkError::ConstructionFailure),
+            ));
+        }
         match this.stream.poll_next(cx) {
             Poll::Ready(message_option) => {
                 if let Some(message_result) = message_option {
@@ -99,15 +138,10 @@ where
                         .marshaller
                         .marshall(message_result?)
                         .map_err(|err| SdkError::ConstructionFailure(Box::new(err)))?;
-                    let message = this
-                        .signer
-                        .sign(message)
-                        .map_err(|err| SdkError::ConstructionFailure(err))?;
-                    let mut buffer = Vec::new();
-                    message
-                        .write_to(&mut buffer)
-                        .map_err(|err| SdkError::ConstructionFailure(Box::new(err)))?;
-                    Poll::Ready(Some(Ok(Bytes::from(buffer))))
+                    Poll::Ready(Some(
+                        sign_and_write(this.signer.as_mut(), message)
+                            .map_err(SdkError::ConstructionFailure),
+                    ))
                 } else if !*this.end_signal_sent {
                     *this.end_signal_sent = true;
                     let mut buffer = Vec::new();
@@ -243,6 +277,37 @@ mod tests {
         ));
     }
 
+    #[tokio::test]
+    async fn message_stream_adapter_initial_message() {
+        let stream = stream! {
+            yield Ok(TestMessage("test".into()));
+        };
+        let mut adapter = check_compatible_with_hyper_wrap_stream(
+            EventStreamInput::from(stream)
+                .into_body_stream_with_initial_request::<TestServiceError>(
+                    Marshaller,
+                    TestSigner,
+                    Message::new(&b"initial"[..]),
+                ),
+        );
+
+        let mut sent_bytes = adapter.next().await.unwrap().unwrap();
+        let sent = Message::read_from(&mut sent_bytes).unwrap();
+        assert_eq!("signed", sent.headers()[0].name().as_str());
+        let inner = Message::read_from(&mut (&sent.payload()[..])).unwrap();
+        assert_eq!(&b"initial"[..], &inner.payload()[..]);
+
+        let mut sent_bytes = adapter.next().await.unwrap().unwrap();
+        let sent = Message::read_from(&mut sent_bytes).unwrap();
+        let inner = Message::read_from(&mut (&sent.payload()[..])).unwrap();
+        assert_eq!(&b"test"[..], &inner.payload()[..]);
+
+        let mut end_signal_bytes = adapter.next().await.unwrap().unwrap();
+        let end_signal = Message::read_from(&mut end_signal_bytes).unwrap();
+        assert_eq!(0, end_signal.payload().len());
+        assert!(adapter.next().await.is_none());
+    }
+
     // Verify the developer experience for this compiles
     #[allow(unused)]
     fn event_stream_input_ergonomics() {
@@ -8,6 +8,7 @@ use crate::result::{ConnectorError, SdkError};
 use aws_smithy_eventstream::frame::{
     DecodedFrame, Message, MessageFrameDecoder, UnmarshallMessage, UnmarshalledMessage,
 };
+use aws_smithy_eventstream::smithy::is_initial_response;
 use bytes::Buf;
 use bytes::Bytes;
 use bytes_utils::SegmentedBuf;
@@ -148,19 +149,7 @@ impl<T, E> Receiver<T, E> {
     }
 
     fn unmarshall(&self, message: Message) -> Result<Option<T>, SdkError<E, RawMessage>> {
-        match self.unmarshaller.unmarshall(&message) {
-            Ok(unmarshalled) => match unmarshalled {
-                UnmarshalledMessage::Event(event) => Ok(Some(event)),
-                UnmarshalledMessage::Error(err) => Err(SdkError::ServiceError {
-                    err,
-                    raw: RawMessage::Decoded(message),
-                }),
-            },
-            Err(err) => Err(SdkError::ResponseError {
-                err: Box::new(err),
-                raw: RawMessage::Decoded(message),
-            }),
-        }
+        unmarshall_with(self.unmarshaller.as_ref(), message)
     }
 
     async fn buffer_next_chunk(&mut self) -> Result<(), SdkError<E, RawMessage>> {
@@ -212,40 +201,68 @@ impl<T, E> Receiver<T, E> {
     #[doc(hidden)]
     pub async fn try_recv_initial(&mut self) -> Result<Option<Message>, SdkError<E, RawMessage>> {
         if let Some(message) = self.next_message().await? {
-            if let Some(event_type) = message
-                .headers()
-                .iter()
-                .find(|h| h.name().as_str() == ":event-type")
-            {
-                if event_type
-                    .value()
-                    .as_string()
-                    .map(|s| s.as_str() == "initial-response")
-                    .unwrap_or(false)
-                {
-                    return Ok(Some(message));
-                }
-            } else {
-                // Buffer the message so that it can be returned by the next call to `recv()`
-                self.buffered_message = Some(message);
+            if is_initial_response(&message) {
+                return Ok(Some(message));
             }
+            // Buffer the message so that it can be returned by the next call to `recv()`
+            self.buffered_message = Some(message);
         }
         Ok(None)
     }
 
+    /// Tries to receive the initial response message, and unmarshalls it with the given
+    /// `unmarshaller` into the operation output it carries.
+    ///
+    /// Like [`try_recv_initial`](Receiver::try_recv_initial), any other message received is
+    /// buffered for the next call to `recv()` and `Ok(None)` is returned.
+    #[doc(hidden)]
+    pub async fn try_recv_initial_response<O>(
+        &mut self,
+        unmarshaller: impl UnmarshallMessage<Output = O, Error = E>,
+    ) -> Result<Option<O>, SdkError<E, RawMessage>> {
+        match self.try_recv_initial().await? {
+            Some(message) => unmarshall_with(&unmarshaller, message),
+            None => Ok(None),
+        }
+    }
+
     /// Asynchronously tries to receive a message from the stream. If the stream has ended,
     /// it returns an `Ok(None)`. If there is a transport layer error, it will return
     /// `Err(SdkError::DispatchFailure)`. Service-modeled errors will be a part of the returned
     /// messages.
+    ///
+    /// An initial response that wasn't received with
+    /// [`try_recv_initial`](Receiver::try_recv_initial) isn't one of the stream's messages, so it
+    /// is skipped.
     pub async fn recv(&mut self) -> Result<Option<T>, SdkError<E, RawMessage>> {
         if let Some(buffered) = self.buffered_message.take() {
             return self.unmarshall(buffered);
         }
-        if let Some(message) = self.next_message().await? {
-            self.unmarshall(message)
-        } else {
-            Ok(None)
+        while let Some(message) = self.next_message().await? {
+            if !is_initial_response(&message) {
+                return self.unmarshall(message);
+            }
         }
+        Ok(None)
+    }
+}
+
+fn unmarshall_with<O, E>(
+    unmarshaller: &dyn UnmarshallMessage<Output = O, Error = E>,
+    message: Message,
+) -> Result<Option<O>, SdkError<E, RawMessage>> {
+    match unmarshaller.unmarshall(&message) {
+        Ok(unmarshalled) => match unmarshalled {
+            UnmarshalledMessage::Event(event) => Ok(Some(event)),
+            UnmarshalledMessage::Error(err) => Err(SdkError::ServiceError {
+                err,
+                raw: RawMessage::Decoded(message),
+            }),
+        },
+        Err(err) => Err(SdkError::ResponseError {
+            err: Box::new(err),
+            raw: RawMessage::Decoded(message),
+        }),
     }
 }
 
@@ -262,8 +279,12 @@ mod tests {
     use std::io::{Error as IOError, ErrorKind};
 
     fn encode_initial_response() -> Bytes {
+        encode_initial_response_with_payload("")
+    }
+
+    fn encode_initial_response_with_payload(payload: &str) -> Bytes {
         let mut buffer = Vec::new();
-        Message::new(Bytes::new())
+        Message::new(Bytes::copy_from_slice(payload.as_bytes()))
             .add_header(Header::new(
                 ":message-type",
                 HeaderValue::String("event".into()),
@@ -285,6 +306,22 @@ mod tests {
         buffer.into()
     }
 
+    fn encode_event(event_type: &'static str, message: &str) -> Bytes {
+        let mut buffer = Vec::new();
+        Message::new(Bytes::copy_from_slice(message.as_bytes()))
+            .add_header(Header::new(
+                ":message-type",
+                HeaderValue::String("event".into()),
+            ))
+            .add_header(Header::new(
+                ":event-type",
+                HeaderValue::String(event_type.into()),
+            ))
+            .write_to(&mut buffer)
+            .unwrap();
+        buffer.into()
+    }
+
     #[derive(Debug)]
     struct FakeError;
     impl std::fmt::Display for FakeError {
@@ -506,6 +543,20 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn unrequested_initial_response_is_skipped() {
+        let chunks: Vec<Result<_, IOError>> =
+            vec![Ok(encode_initial_response()), Ok(encode_message("one"))];
+        let chunk_stream = futures_util::stream::iter(chunks);
+        let body = SdkBody::from(Body::wrap_stream(chunk_stream));
+        let mut receiver = Receiver::<TestMessage, EventStreamError>::new(Unmarshaller, body);
+        assert_eq!(
+            TestMessage("one".into()),
+            receiver.recv().await.unwrap().unwrap()
+        );
+        assert_eq!(None, receiver.recv().await.unwrap());
+    }
+
     #[tokio::test]
     async fn receive_no_initial_response() {
         let chunks: Vec<Result<_, IOError>> =
@@ -524,6 +575,99 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn receive_no_initial_response_with_event_type() {
+        let chunks: Vec<Result<_, IOError>> = vec![
+            Ok(encode_event("Foo", "one")),
+            Ok(encode_event("Foo", "two")),
+        ];
+        let chunk_stream = futures_util::stream::iter(chunks);
+        let body = SdkBody::from(Body::wrap_stream(chunk_stream));
+        let mut receiver = Receiver::<TestMessage, EventStreamError>::new(Unmarshaller, body);
+        assert!(receiver.try_recv_initial().await.unwrap().is_none());
+        assert_eq!(
+            TestMessage("one".into()),
+            receiver.recv().await.unwrap().unwrap()
+        );
+        assert_eq!(
+            TestMessage("two".into()),
+            receiver.recv().await.unwrap().unwrap()
+        );
+    }
+
+    #[derive(Debug, Eq, PartialEq)]
+    struct TestOutput(String);
+
+    #[derive(Debug)]
+    struct OutputUnmarshaller;
+    impl UnmarshallMessage for OutputUnmarshaller {
+        type Output = TestOutput;
+        type Error = EventStreamError;
+
+        fn unmarshall(
+            &self,
+            message: &Message,
+        ) -> Result<UnmarshalledMessage<Self::Output, Self::Error>, EventStreamError> {
+            match std::str::from_utf8(&message.payload()[..]).unwrap() {
+                "" => Err(EventStreamError::Unmarshalling("empty output".into())),
+                output => Ok(UnmarshalledMessage::Event(TestOutput(output.into()))),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn receive_typed_initial_response() {
+        let chunks: Vec<Result<_, IOError>> = vec![
+            Ok(encode_initial_response_with_payload("output")),
+            Ok(encode_message("one")),
+        ];
+        let chunk_stream = futures_util::stream::iter(chunks);
+        let body = SdkBody::from(Body::wrap_stream(chunk_stream));
+        let mut receiver = Receiver::<TestMessage, EventStreamError>::new(Unmarshaller, body);
+        assert_eq!(
+            Some(TestOutput("output".into())),
+            receiver
+                .try_recv_initial_response(OutputUnmarshaller)
+                .await
+                .unwrap()
+        );
+        assert_eq!(
+            TestMessage("one".into()),
+            receiver.recv().await.unwrap().unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn receive_typed_initial_response_failure() {
+        let chunks: Vec<Result<_, IOError>> = vec![Ok(encode_initial_response())];
+        let chunk_stream = futures_util::stream::iter(chunks);
+        let body = SdkBody::from(Body::wrap_stream(chunk_stream));
+        let mut receiver = Receiver::<TestMessage, EventStreamError>::new(Unmarshaller, body);
+        assert!(matches!(
+            receiver.try_recv_initial_response(OutputUnmarshaller).await,
+            Err(SdkError::ResponseError { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn receive_typed_no_initial_response() {
+        let chunks: Vec<Result<_, IOError>> = vec![Ok(encode_message("one"))];
+        let chunk_stream = futures_util::stream::iter(chunks);
+        let body = SdkBody::from(Body::wrap_stream(chunk_stream));
+        let mut receiver = Receiver::<TestMessage, EventStreamError>::new(Unmarshaller, body);
+        assert_eq!(
+            None,
+            receiver
+                .try_recv_initial_response(OutputUnmarshaller)
+                .await
+                .unwrap()
+        );
+        assert_eq!(
+            TestMessage("one".into()),
+            receiver.recv().await.unwrap().unwrap()
+        );
+    }
+
     fn assert_send<T: Send>() {}
 
     #[tokio::test]
@@ -49,6 +49,18 @@ impl Display for ParseError {
 
 impl Error for ParseError {}
 
+/// Response headers that may carry the request ID assigned by the service, in order of preference.
+const REQUEST_ID_HEADERS: &[&str] = &["x-amzn-requestid", "x-amz-request-id"];
+
+/// Returns the request ID from a response's headers, if the service returned one.
+pub fn request_id(headers: &HeaderMap) -> Option<&str> {
+    REQUEST_ID_HEADERS
+        .iter()
+        .flat_map(|name| headers.get(*name))
+        .flat_map(|value| value.to_str().ok())
+        .next()
+}
+
 /// Read all the dates from the header map at `key` according the `format`
 ///
 /// This is separate from `read_many` below because we need to invoke `DateTime::read` to take advantage
@@ -197,14 +209,6 @@ mod parse_multi_header {
         }
     }
 
-    fn replace<'a>(value: Cow<'a, str>, pattern: &str, replacement: &str) -> Cow<'a, str> {
-        if value.contains(pattern) {
-            Cow::Owned(value.replace(pattern, replacement))
-        } else {
-            value
-        }
-    }
-
     /// Reads a single value out of the given input, and returns a tuple containing
     /// the parsed value and the remainder of the slice that can be used to parse
     /// more values.
@@ -234,17 +238,25 @@ mod parse_multi_header {
     }
 
     /// Reads a header value that is surrounded by quotation marks and may have escaped
-    /// quotes inside of it.
+    /// characters (e.g. `\"` or `\\`) inside of it.
     fn read_quoted_value(input: &[u8]) -> Result<(Cow<'_, str>, &[u8]), ParseError> {
-        for index in 0..input.len() {
-            match input[index] {
-                b'"' if index == 0 || input[index - 1] != b'\\' => {
-                    let mut inner =
-                        Cow::Borrowed(std::str::from_utf8(&input[0..index]).map_err(|_| {
-                            ParseError::new_with_message("header was not valid utf8")
-                        })?);
-                    inner = replace(inner, "\\\"", "\"");
-                    inner = replace(inner, "\\\\", "\\");
+        let mut escaped = false;
+        let mut has_escapes = false;
+        for (index, &byte) in input.iter().enumerate() {
+            match byte {
+                _ if escaped => escaped = false,
+                b'\\' => {
+                    escaped = true;
+                    has_escapes = true;
+                }
+                b'"' => {
+                    let inner = std::str::from_utf8(&input[0..index])
+                        .map_err(|_| ParseError::new_with_message("header was not valid utf8"))?;
+                    let inner = if has_escapes {
+                        Cow::Owned(unescape(inner))
+                    } else {
+                        Cow::Borrowed(inner)
+                    };
                     let rest = then_comma(&input[(index + 1)..])?;
                     return Ok((inner, rest));
                 }
@@ -256,6 +268,19 @@ mod parse_multi_header {
         ))
     }
 
+    /// Removes the backslashes from escaped characters in the contents of a quoted value
+    fn unescape(value: &str) -> String {
+        let mut out = String::with_capacity(value.len());
+        let mut chars = value.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => out.extend(chars.next()),
+                c => out.push(c),
+            }
+        }
+        out
+    }
+
     fn then_comma(s: &[u8]) -> Result<&[u8], ParseError> {
         if s.is_empty() {
             Ok(s)
@@ -330,11 +355,22 @@ mod test {
 
     use crate::header::{
         append_merge_header_maps, headers_for_prefix, many_dates, read_many_from_str,
-        read_many_primitive, set_request_header_if_absent, set_response_header_if_absent,
-        ParseError,
+        read_many_primitive, request_id, set_request_header_if_absent,
+        set_response_header_if_absent, ParseError,
     };
 
     use super::quote_header_value;
+    use proptest::proptest;
+
+    #[test]
+    fn request_id_header_precedence() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(None, request_id(&headers));
+        headers.insert("x-amz-request-id", "s3-style".parse().unwrap());
+        assert_eq!(Some("s3-style"), request_id(&headers));
+        headers.insert("x-amzn-requestid", "json-style".parse().unwrap());
+        assert_eq!(Some("json-style"), request_id(&headers));
+    }
 
     #[test]
     fn put_on_request_if_absent() {
@@ -588,6 +624,37 @@ mod test {
         assert_eq!("\")\"", &quote_header_value(")"));
     }
 
+    #[test]
+    fn quoted_value_ending_in_backslash() {
+        let test_request = http::Request::builder()
+            .header("TrailingBackslash", "\"foo,\\\\\", bar")
+            .body(())
+            .unwrap();
+        assert_eq!(
+            read_many_from_str::<String>(
+                test_request.headers().get_all("TrailingBackslash").iter()
+            )
+            .expect("valid"),
+            vec!["foo,\\", "bar"]
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn quoted_values_round_trip(values in proptest::collection::vec("[ -~]+", 1..5)) {
+            let joined = values
+                .iter()
+                .map(|value| quote_header_value(value.as_str()))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let mut headers = HeaderMap::new();
+            headers.insert("X-List", HeaderValue::from_str(&joined).unwrap());
+            let read = read_many_from_str::<String>(headers.get_all("X-List").iter())
+                .expect("quoted values should always be readable");
+            assert_eq!(read, values);
+        }
+    }
+
     #[test]
     fn test_append_merge_header_maps_with_shared_key() {
         let header_name = HeaderName::from_static("some_key");
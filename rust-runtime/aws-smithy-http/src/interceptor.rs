@@ -0,0 +1,86 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Hooks for observing and modifying requests right before they're sent.
+//!
+//! Interceptors run for every attempt, after all middleware (e.g. signing) has run, so anything
+//! they change is sent as-is. Attach one to a single operation with
+//! [`Operation::with_interceptor`](crate::operation::Operation::with_interceptor).
+
+use crate::body::SdkBody;
+use crate::property_bag::PropertyBag;
+use std::error::Error;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+type BoxError = Box<dyn Error + Send + Sync>;
+
+/// Hook that runs around the transmission of each request attempt.
+pub trait Interceptor: Send + Sync + Debug {
+    /// Called before a request attempt is sent.
+    ///
+    /// Returning an error fails the attempt with a construction failure, without sending it.
+    fn modify_before_transmit(
+        &self,
+        request: &mut http::Request<SdkBody>,
+        properties: &PropertyBag,
+    ) -> Result<(), BoxError> {
+        let _ = (request, properties);
+        Ok(())
+    }
+
+    /// Called when a response is received, before it is parsed.
+    fn read_after_transmit(&self, response: &http::Response<SdkBody>, properties: &PropertyBag) {
+        let _ = (response, properties);
+    }
+}
+
+/// A cheaply cloneable [`Interceptor`].
+#[derive(Clone, Debug)]
+pub struct SharedInterceptor(Arc<dyn Interceptor>);
+
+impl SharedInterceptor {
+    /// Create a new `SharedInterceptor` from `interceptor`.
+    pub fn new(interceptor: impl Interceptor + 'static) -> Self {
+        Self(Arc::new(interceptor))
+    }
+}
+
+impl Interceptor for SharedInterceptor {
+    fn modify_before_transmit(
+        &self,
+        request: &mut http::Request<SdkBody>,
+        properties: &PropertyBag,
+    ) -> Result<(), BoxError> {
+        self.0.modify_before_transmit(request, properties)
+    }
+
+    fn read_after_transmit(&self, response: &http::Response<SdkBody>, properties: &PropertyBag) {
+        self.0.read_after_transmit(response, properties)
+    }
+}
+
+/// The interceptors attached to an operation, stored in its property bag.
+///
+/// Interceptors run in the order they were added.
+#[derive(Clone, Debug, Default)]
+pub struct Interceptors(Vec<SharedInterceptor>);
+
+impl Interceptors {
+    /// Create an empty list of interceptors.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add `interceptor` to the end of the list.
+    pub fn push(&mut self, interceptor: SharedInterceptor) {
+        self.0.push(interceptor);
+    }
+
+    /// Returns the interceptors in the order they run.
+    pub fn iter(&self) -> impl Iterator<Item = &SharedInterceptor> {
+        self.0.iter()
+    }
+}
@@ -6,6 +6,7 @@
 //! Core HTTP primitives for service clients generated by [smithy-rs](https://github.com/awslabs/smithy-rs) including:
 //! - HTTP Body implementation
 //! - Endpoint support
+//! - HTTP auth schemes, such as bearer tokens and API keys
 //! - HTTP header deserialization
 //! - Event streams
 //! - [`ByteStream`](byte_stream::ByteStream): a misuse-resistant abstraction for streaming binary data
@@ -14,27 +15,40 @@
 //! |----------------|-------------|
 //! | `rt-tokio`     | Provides features that are dependent on `tokio` including the `ByteStream::from_path` util |
 //! | `event-stream` | Provides Sender/Receiver implementations for Event Stream codegen. |
+//! | `test-util`    | Include various testing utils |
 
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
+pub mod auth;
 pub mod body;
 pub mod callback;
+pub mod config_bag;
+pub mod diagnostic;
 pub mod endpoint;
 pub mod header;
 pub mod http_versions;
+pub mod interceptor;
 pub mod label;
+pub mod metrics;
 pub mod middleware;
 pub mod operation;
+pub mod pipeline;
 pub mod property_bag;
 pub mod query;
+pub mod request_id;
+pub mod request_validation;
 pub mod response;
 pub mod result;
 pub mod retry;
+pub mod timing;
 
 #[cfg(feature = "event-stream")]
 pub mod event_stream;
 
 pub mod byte_stream;
 
+#[cfg(any(test, feature = "test-util"))]
+pub mod test_util;
+
 mod pin_util;
 mod urlencode;
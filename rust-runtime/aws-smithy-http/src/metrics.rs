@@ -0,0 +1,332 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Hooks for emitting client-side metrics.
+//!
+//! To collect metrics, implement [`EmitMetrics`] and insert it into an operation's property bag
+//! as a [`SharedMetricsEmitter`]. Most users will do this once by configuring the emitter on the
+//! client rather than for each operation.
+
+use crate::operation::Metadata;
+use crate::timing::TimingInfo;
+use aws_smithy_types::retry::RetryKind;
+use std::fmt::Debug;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// The outcome of a single request attempt.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AttemptOutcome {
+    /// A response was received and successfully parsed.
+    Success,
+    /// A response was received and parsed into a modeled service error.
+    ServiceError,
+    /// A response was received but could not be read or parsed.
+    ResponseError,
+    /// The request could not be dispatched.
+    DispatchFailure,
+    /// The request could not be constructed.
+    ConstructionFailure,
+    /// The attempt timed out.
+    Timeout,
+}
+
+/// Metrics recorded for a single request attempt.
+#[non_exhaustive]
+#[derive(Clone, Debug)]
+pub struct AttemptMetrics {
+    /// The service and operation the attempt was made for, if known.
+    pub metadata: Option<Metadata>,
+    /// The attempt number, starting at `1` for the initial attempt.
+    pub attempt: u32,
+    /// Time taken from dispatching the request until the response was parsed.
+    pub latency: Duration,
+    /// The size of the request body, if it was known up front.
+    pub request_body_size: Option<u64>,
+    /// The HTTP status code of the response, if a response was received.
+    pub response_status: Option<http::StatusCode>,
+    /// The size of the response body, if it is known.
+    pub response_body_size: Option<u64>,
+    /// The outcome of the attempt.
+    pub outcome: AttemptOutcome,
+    /// Time spent in each stage of the attempt, if a response was received.
+    ///
+    /// Record these in histograms to find which stage latency comes from.
+    pub timing: Option<TimingInfo>,
+}
+
+impl AttemptMetrics {
+    /// Create a new set of attempt metrics.
+    pub fn new(
+        metadata: Option<Metadata>,
+        attempt: u32,
+        latency: Duration,
+        outcome: AttemptOutcome,
+    ) -> Self {
+        Self {
+            metadata,
+            attempt,
+            latency,
+            request_body_size: None,
+            response_status: None,
+            response_body_size: None,
+            outcome,
+            timing: None,
+        }
+    }
+
+    /// Set the size of the request body.
+    pub fn with_request_body_size(mut self, size: Option<u64>) -> Self {
+        self.request_body_size = size;
+        self
+    }
+
+    /// Set the response status and body size.
+    pub fn with_response(mut self, status: http::StatusCode, body_size: Option<u64>) -> Self {
+        self.response_status = Some(status);
+        self.response_body_size = body_size;
+        self
+    }
+
+    /// Set the time spent in each stage of the attempt.
+    pub fn with_timing(mut self, timing: Option<TimingInfo>) -> Self {
+        self.timing = timing;
+        self
+    }
+}
+
+/// Metrics recorded when the retry policy classifies the result of an attempt.
+#[non_exhaustive]
+#[derive(Clone, Debug)]
+pub struct RetryMetrics {
+    /// The service and operation the attempt was made for, if known.
+    pub metadata: Option<Metadata>,
+    /// The attempt number that was classified, starting at `1` for the initial attempt.
+    pub attempt: u32,
+    /// How the result was classified. Throttling errors are reported as
+    /// [`ErrorKind::ThrottlingError`](aws_smithy_types::retry::ErrorKind::ThrottlingError).
+    pub retry_kind: RetryKind,
+    /// The backoff before the next attempt, or `None` if no retry will be made.
+    pub backoff: Option<Duration>,
+}
+
+impl RetryMetrics {
+    /// Create a new set of retry metrics.
+    pub fn new(attempt: u32, retry_kind: RetryKind, backoff: Option<Duration>) -> Self {
+        Self {
+            metadata: None,
+            attempt,
+            retry_kind,
+            backoff,
+        }
+    }
+
+    /// Set the service and operation the attempt was made for.
+    pub fn with_metadata(mut self, metadata: Option<Metadata>) -> Self {
+        self.metadata = metadata;
+        self
+    }
+}
+
+/// The state of a circuit breaker for a single endpoint.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Requests are sent to the endpoint.
+    Closed,
+    /// Requests to the endpoint fail without being sent.
+    Open,
+    /// A single probe request is allowed through to check if the endpoint has recovered.
+    HalfOpen,
+}
+
+/// Metrics recorded when a circuit breaker changes state.
+#[non_exhaustive]
+#[derive(Clone, Debug)]
+pub struct CircuitBreakerMetrics {
+    /// The endpoint (host and port) that the circuit breaker tracks.
+    pub endpoint: String,
+    /// The new state of the circuit breaker.
+    pub state: CircuitState,
+}
+
+impl CircuitBreakerMetrics {
+    /// Create a new set of circuit breaker metrics.
+    pub fn new(endpoint: impl Into<String>, state: CircuitState) -> Self {
+        Self {
+            endpoint: endpoint.into(),
+            state,
+        }
+    }
+}
+
+/// Metrics recorded when a caching credentials provider loads new credentials.
+#[non_exhaustive]
+#[derive(Clone, Debug)]
+pub struct CredentialsLoadMetrics {
+    /// Time taken to load the credentials, including time spent waiting on the load timeout.
+    pub latency: Duration,
+    /// Whether credentials were loaded successfully.
+    pub success: bool,
+}
+
+impl CredentialsLoadMetrics {
+    /// Create a new set of credentials load metrics.
+    pub fn new(latency: Duration, success: bool) -> Self {
+        Self { latency, success }
+    }
+}
+
+/// Metrics recorded when a request joins or leaves the queue of a client concurrency limit.
+#[non_exhaustive]
+#[derive(Clone, Debug)]
+pub struct ConcurrencyLimitMetrics {
+    /// The number of requests in flight.
+    pub in_flight: usize,
+    /// The number of requests waiting for a slot.
+    pub queued: usize,
+}
+
+impl ConcurrencyLimitMetrics {
+    /// Create a new set of concurrency limit metrics.
+    pub fn new(in_flight: usize, queued: usize) -> Self {
+        Self { in_flight, queued }
+    }
+}
+
+/// Receiver for client-side metrics.
+///
+/// Implementations are invoked inline on the request path, so they should be cheap. Any
+/// expensive work (e.g. publishing to a remote metrics service) should be handed off.
+pub trait EmitMetrics: Send + Sync + Debug {
+    /// Called when a request attempt completes.
+    fn emit_attempt(&self, metrics: &AttemptMetrics) {
+        let _ = metrics;
+    }
+
+    /// Called when the retry policy classifies the result of an attempt.
+    fn emit_retry(&self, metrics: &RetryMetrics) {
+        let _ = metrics;
+    }
+
+    /// Called when a circuit breaker changes state.
+    fn emit_circuit_breaker(&self, metrics: &CircuitBreakerMetrics) {
+        let _ = metrics;
+    }
+
+    /// Called when a caching credentials provider finishes loading credentials.
+    fn emit_credentials_load(&self, metrics: &CredentialsLoadMetrics) {
+        let _ = metrics;
+    }
+
+    /// Called when a request joins or leaves the queue of a client concurrency limit.
+    fn emit_concurrency_limit(&self, metrics: &ConcurrencyLimitMetrics) {
+        let _ = metrics;
+    }
+}
+
+/// Metrics emitter that discards all metrics.
+#[non_exhaustive]
+#[derive(Clone, Debug, Default)]
+pub struct NoMetrics;
+
+impl EmitMetrics for NoMetrics {}
+
+/// Metrics emitter that writes metrics as `tracing` events at `INFO` level.
+#[non_exhaustive]
+#[derive(Clone, Debug, Default)]
+pub struct TracingMetrics;
+
+impl EmitMetrics for TracingMetrics {
+    fn emit_attempt(&self, metrics: &AttemptMetrics) {
+        let timing = metrics.timing.clone().unwrap_or_default();
+        let ms = |duration: Option<Duration>| duration.map(|d| d.as_millis() as u64);
+        tracing::info!(
+            service = metrics.metadata.as_ref().map(|m| m.service()),
+            operation = metrics.metadata.as_ref().map(|m| m.name()),
+            attempt = metrics.attempt,
+            latency_ms = metrics.latency.as_millis() as u64,
+            request_body_size = metrics.request_body_size,
+            response_status = metrics.response_status.map(|s| s.as_u16()),
+            response_body_size = metrics.response_body_size,
+            outcome = ?metrics.outcome,
+            credentials_ms = ms(timing.credentials),
+            signing_ms = ms(timing.signing),
+            connect_ms = ms(timing.connect),
+            time_to_first_byte_ms = ms(timing.time_to_first_byte),
+            body_read_ms = ms(timing.body_read),
+            "request attempt complete"
+        );
+    }
+
+    fn emit_retry(&self, metrics: &RetryMetrics) {
+        tracing::info!(
+            service = metrics.metadata.as_ref().map(|m| m.service()),
+            operation = metrics.metadata.as_ref().map(|m| m.name()),
+            attempt = metrics.attempt,
+            retry_kind = ?metrics.retry_kind,
+            backoff_ms = metrics.backoff.map(|b| b.as_millis() as u64),
+            "request attempt classified"
+        );
+    }
+
+    fn emit_circuit_breaker(&self, metrics: &CircuitBreakerMetrics) {
+        tracing::info!(
+            endpoint = %metrics.endpoint,
+            state = ?metrics.state,
+            "circuit breaker state changed"
+        );
+    }
+
+    fn emit_credentials_load(&self, metrics: &CredentialsLoadMetrics) {
+        tracing::info!(
+            latency_ms = metrics.latency.as_millis() as u64,
+            success = metrics.success,
+            "credentials load complete"
+        );
+    }
+
+    fn emit_concurrency_limit(&self, metrics: &ConcurrencyLimitMetrics) {
+        tracing::info!(
+            in_flight = metrics.in_flight,
+            queued = metrics.queued,
+            "concurrency limit queue changed"
+        );
+    }
+}
+
+/// A cheaply cloneable [`EmitMetrics`] that can be stored in the property bag.
+#[derive(Clone, Debug)]
+pub struct SharedMetricsEmitter(Arc<dyn EmitMetrics>);
+
+impl SharedMetricsEmitter {
+    /// Create a new `SharedMetricsEmitter` from `emitter`.
+    pub fn new(emitter: impl EmitMetrics + 'static) -> Self {
+        Self(Arc::new(emitter))
+    }
+}
+
+impl EmitMetrics for SharedMetricsEmitter {
+    fn emit_attempt(&self, metrics: &AttemptMetrics) {
+        self.0.emit_attempt(metrics)
+    }
+
+    fn emit_retry(&self, metrics: &RetryMetrics) {
+        self.0.emit_retry(metrics)
+    }
+
+    fn emit_circuit_breaker(&self, metrics: &CircuitBreakerMetrics) {
+        self.0.emit_circuit_breaker(metrics)
+    }
+
+    fn emit_credentials_load(&self, metrics: &CredentialsLoadMetrics) {
+        self.0.emit_credentials_load(metrics)
+    }
+
+    fn emit_concurrency_limit(&self, metrics: &ConcurrencyLimitMetrics) {
+        self.0.emit_concurrency_limit(metrics)
+    }
+}
@@ -11,12 +11,18 @@
 use crate::body::SdkBody;
 use crate::operation;
 use crate::pin_mut;
-use crate::response::ParseHttpResponse;
-use crate::result::{SdkError, SdkSuccess};
-use bytes::{Buf, Bytes};
+use crate::property_bag::SharedPropertyBag;
+use crate::response::{
+    IncompleteBody, ParseHttpResponse, ParseOffload, ParsedResponseHook, ResponseBodyTooLarge,
+    ResponseLimits,
+};
+use crate::result::{ResponseParseError, SdkError, SdkSuccess};
+use bytes::{Buf, Bytes, BytesMut};
 use http_body::Body;
 use std::error::Error;
 use std::future::Future;
+use std::sync::Arc;
+use tokio::sync::oneshot;
 use tracing::trace;
 
 type BoxError = Box<dyn Error + Send + Sync>;
@@ -34,6 +40,13 @@ pub trait AsyncMapRequest {
     type Future: Future<Output = Result<operation::Request, Self::Error>> + Send + 'static;
 
     fn apply(&self, request: operation::Request) -> Self::Future;
+
+    /// The name of this middleware stage, used to identify it when it fails
+    ///
+    /// Defaults to the type name of the stage.
+    fn name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
 }
 
 /// [`MapRequest`] defines a synchronous middleware that transforms an [`operation::Request`].
@@ -73,6 +86,13 @@ pub trait MapRequest {
     /// Typically, implementations will use [`request.augment`](crate::operation::Request::augment)
     /// to be able to transform an owned `http::Request`.
     fn apply(&self, request: operation::Request) -> Result<operation::Request, Self::Error>;
+
+    /// The name of this middleware stage, used to identify it when it fails
+    ///
+    /// Defaults to the type name of the stage.
+    fn name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
 }
 
 /// Load a response using `handler` to parse the results.
@@ -84,61 +104,819 @@ pub trait MapRequest {
 /// - `O`: The Http response handler that returns `Result<T, E>`
 /// - `T`/`E`: `Result<T, E>` returned by `handler`.
 pub async fn load_response<T, E, O>(
-    mut response: operation::Response,
+    response: operation::Response,
     handler: &O,
 ) -> Result<SdkSuccess<T>, SdkError<E>>
 where
     O: ParseHttpResponse<Output = Result<T, E>>,
+    T: 'static,
+    E: Error + 'static,
+{
+    let (http_response, properties) = match read_response(response, handler).await {
+        Ok(loaded) => loaded,
+        Err(result) => return result,
+    };
+    let parsed = handler.parse_loaded(&http_response);
+    sdk_result(
+        parsed,
+        operation::Response::from_parts(http_response.map(SdkBody::from), properties),
+    )
+}
+
+/// Load a response like [`load_response`], parsing large bodies without stalling the async runtime.
+///
+/// When the operation's property bag contains a [`ParseOffload`] that applies to the size of the
+/// body that was read, the body is parsed with its executor, and this waits for the result without
+/// blocking. Otherwise, it's parsed inline.
+pub async fn load_response_offloaded<T, E, O>(
+    response: operation::Response,
+    handler: Arc<O>,
+) -> Result<SdkSuccess<T>, SdkError<E>>
+where
+    O: ParseHttpResponse<Output = Result<T, E>> + Send + Sync + 'static,
+    T: Send + 'static,
+    E: Error + Send + 'static,
+{
+    let (http_response, properties) = match read_response(response, handler.as_ref()).await {
+        Ok(loaded) => loaded,
+        Err(result) => return result,
+    };
+    let offload = properties
+        .acquire()
+        .get::<ParseOffload>()
+        .filter(|offload| offload.applies_to(http_response.body().len() as u64))
+        .cloned();
+    let (http_response, parsed) = match offload {
+        Some(offload) => {
+            trace!(
+                len = http_response.body().len(),
+                threshold = offload.threshold(),
+                "parsing response with the offload executor"
+            );
+            let http_response = Arc::new(http_response);
+            let (tx, rx) = oneshot::channel();
+            let task = {
+                let http_response = http_response.clone();
+                move || {
+                    let parsed = handler.parse_loaded(&http_response);
+                    // Release the response before reporting back, so that it can be reclaimed
+                    drop(http_response);
+                    let _ = tx.send(parsed);
+                }
+            };
+            offload.run_blocking(Box::new(task));
+            let parsed = rx.await.ok();
+            (reclaim_response(http_response), parsed)
+        }
+        None => {
+            let parsed = handler.parse_loaded(&http_response);
+            (http_response, Some(parsed))
+        }
+    };
+    let raw = operation::Response::from_parts(http_response.map(SdkBody::from), properties);
+    match parsed {
+        Some(parsed) => sdk_result(parsed, raw),
+        None => Err(SdkError::ResponseError {
+            raw,
+            err: "the offload executor didn't parse the response".into(),
+        }),
+    }
+}
+
+/// Takes back a response that was shared with an offloaded parse
+///
+/// The parse releases its reference before reporting its result, and a parse that never ran
+/// releases it when its task is dropped. If the executor still holds on to the task, the response
+/// is copied, without its extensions.
+fn reclaim_response(response: Arc<http::Response<Bytes>>) -> http::Response<Bytes> {
+    Arc::try_unwrap(response).unwrap_or_else(|response| {
+        let mut copy = http::Response::new(response.body().clone());
+        *copy.status_mut() = response.status();
+        *copy.version_mut() = response.version();
+        *copy.headers_mut() = response.headers().clone();
+        copy
+    })
+}
+
+/// Reads the body of `response` into memory unless `handler` can parse it without doing so
+///
+/// When the response doesn't need to be loaded, or can't be, the final result is returned as the
+/// error.
+async fn read_response<T, E, O>(
+    mut response: operation::Response,
+    handler: &O,
+) -> Result<(http::Response<Bytes>, SharedPropertyBag), Result<SdkSuccess<T>, SdkError<E>>>
+where
+    O: ParseHttpResponse<Output = Result<T, E>>,
+    T: 'static,
+    E: Error + 'static,
 {
     if let Some(parsed_response) = handler.parse_unloaded(&mut response) {
         trace!(response = ?response);
-        return sdk_result(parsed_response, response);
+        return Err(sdk_result(parsed_response, response));
     }
 
+    let limits = response
+        .properties()
+        .get::<ResponseLimits>()
+        .copied()
+        .unwrap_or_default();
     let (http_response, properties) = response.into_parts();
-    let (parts, body) = http_response.into_parts();
-    let body = match read_body(body).await {
+    let (mut parts, body) = http_response.into_parts();
+    let content_length = parts
+        .headers
+        .get(http::header::CONTENT_LENGTH)
+        .and_then(|len| len.to_str().ok())
+        .and_then(|len| len.parse::<u64>().ok());
+    let body = read_body(body, content_length, &limits)
+        .await
+        .and_then(|body| decode_body(&mut parts, body, &limits));
+    let body = match body {
         Ok(body) => body,
         Err(err) => {
-            return Err(SdkError::ResponseError {
+            return Err(Err(SdkError::ResponseError {
                 raw: operation::Response::from_parts(
                     http::Response::from_parts(parts, SdkBody::taken()),
                     properties,
                 ),
                 err,
-            });
+            }));
         }
     };
 
-    let http_response = http::Response::from_parts(parts, Bytes::from(body));
+    let http_response = http::Response::from_parts(parts, body);
     trace!(http_response = ?http_response);
-    let parsed = handler.parse_loaded(&http_response);
-    sdk_result(
-        parsed,
-        operation::Response::from_parts(http_response.map(SdkBody::from), properties),
-    )
+    Ok((http_response, properties))
 }
 
-async fn read_body<B: http_body::Body>(body: B) -> Result<Vec<u8>, B::Error> {
-    let mut output = Vec::new();
+/// Reads `body` into memory, failing if it is larger than the limits allow
+///
+/// A body made of a single chunk (e.g. one that was already in memory) is returned without being
+/// copied, and bodies made of several chunks are copied exactly once. When the response declares a
+/// `Content-Length` of at most [`ResponseLimits::max_preallocation`], the chunks are copied into a
+/// buffer of that size as they arrive. Otherwise, they're collected and copied once the whole
+/// body has been read.
+async fn read_body<B>(
+    body: B,
+    content_length: Option<u64>,
+    limits: &ResponseLimits,
+) -> Result<Bytes, BoxError>
+where
+    B: http_body::Body,
+    B::Error: Into<BoxError>,
+{
+    let max_size = limits.max_body_size();
+    let too_large = |size: u64| matches!(max_size, Some(max_size) if size > max_size);
+    // Fail early when the response declares a length that's already too large
+    if too_large(body.size_hint().lower()) || content_length.map(too_large).unwrap_or(false) {
+        return Err(ResponseBodyTooLarge::new(max_size.unwrap()).into());
+    }
+    let preallocate = preallocation(content_length, limits);
+    let mut output = BodyBuffer::Segments(Vec::new());
+    let mut len = 0;
     pin_mut!(body);
     while let Some(buf) = body.data().await {
-        let mut buf = buf?;
-        while buf.has_remaining() {
-            output.extend_from_slice(buf.chunk());
-            buf.advance(buf.chunk().len())
+        let mut buf = buf.map_err(|err| IncompleteBody::new(err.into()))?;
+        if too_large((len + buf.remaining()) as u64) {
+            return Err(ResponseBodyTooLarge::new(max_size.unwrap()).into());
+        }
+        len += buf.remaining();
+        // `Bytes` (the data type of `SdkBody`) implements this without copying
+        output.push(buf.copy_to_bytes(buf.remaining()), len, preallocate);
+    }
+    Ok(output.into_bytes(len))
+}
+
+/// The size of the buffer to copy a body of `content_length` into, if it's within the limits
+fn preallocation(content_length: Option<u64>, limits: &ResponseLimits) -> Option<usize> {
+    content_length
+        .filter(|len| *len <= limits.max_preallocation())
+        .map(|len| len as usize)
+}
+
+/// Body chunks read so far
+enum BodyBuffer {
+    /// Chunks that haven't been copied
+    Segments(Vec<Bytes>),
+    /// A buffer sized from the `Content-Length` of the response
+    Contiguous(BytesMut),
+}
+
+impl BodyBuffer {
+    /// Adds `segment`, where `len` is the length of the body read so far (including `segment`)
+    fn push(&mut self, segment: Bytes, len: usize, preallocate: Option<usize>) {
+        if segment.is_empty() {
+            return;
+        }
+        match self {
+            BodyBuffer::Segments(segments) => match preallocate {
+                // Only copy once there's more than one chunk, so that single-chunk bodies aren't copied
+                Some(capacity) if !segments.is_empty() => {
+                    let mut buffer = BytesMut::with_capacity(capacity.max(len));
+                    for segment in segments.drain(..) {
+                        buffer.extend_from_slice(&segment);
+                    }
+                    buffer.extend_from_slice(&segment);
+                    *self = BodyBuffer::Contiguous(buffer);
+                }
+                _ => segments.push(segment),
+            },
+            BodyBuffer::Contiguous(buffer) => buffer.extend_from_slice(&segment),
+        }
+    }
+
+    /// Returns the body, where `len` is its total length
+    fn into_bytes(self, len: usize) -> Bytes {
+        match self {
+            BodyBuffer::Segments(mut segments) => match segments.len() {
+                0 => Bytes::new(),
+                1 => segments.pop().unwrap(),
+                _ => {
+                    let mut output = BytesMut::with_capacity(len);
+                    for segment in segments {
+                        output.extend_from_slice(&segment);
+                    }
+                    output.freeze()
+                }
+            },
+            BodyBuffer::Contiguous(buffer) => buffer.freeze(),
+        }
+    }
+}
+
+/// Decompresses the body if it has a `Content-Encoding` that `limits` enables decompression for
+#[cfg(feature = "gzip")]
+fn decode_body(
+    parts: &mut http::response::Parts,
+    body: Bytes,
+    limits: &ResponseLimits,
+) -> Result<Bytes, BoxError> {
+    use std::io::Read;
+
+    let is_gzip = parts
+        .headers
+        .get(http::header::CONTENT_ENCODING)
+        .map(|encoding| encoding.as_bytes().eq_ignore_ascii_case(b"gzip"))
+        .unwrap_or(false);
+    if !limits.decompress_gzip() || !is_gzip {
+        return Ok(body);
+    }
+    let mut output = Vec::new();
+    let decoder = flate2::read::GzDecoder::new(&body[..]);
+    match limits.max_body_size() {
+        // Read one byte past the limit to detect bodies that expand beyond it
+        Some(max_size) => {
+            decoder.take(max_size + 1).read_to_end(&mut output)?;
+            if output.len() as u64 > max_size {
+                return Err(ResponseBodyTooLarge::new(max_size).into());
+            }
+        }
+        None => {
+            let mut decoder = decoder;
+            decoder.read_to_end(&mut output)?;
         }
     }
-    Ok(output)
+    parts.headers.remove(http::header::CONTENT_ENCODING);
+    parts.headers.remove(http::header::CONTENT_LENGTH);
+    Ok(output.into())
+}
+
+#[cfg(not(feature = "gzip"))]
+fn decode_body(
+    _parts: &mut http::response::Parts,
+    body: Bytes,
+    _limits: &ResponseLimits,
+) -> Result<Bytes, BoxError> {
+    Ok(body)
 }
 
 /// Convert a `Result<T, E>` into an `SdkResult` that includes the operation response
+///
+/// The operation's [`ParsedResponseHook`], if it has one, runs first. Errors caused by a
+/// [`ResponseParseError`] are response errors rather than service errors.
+// `SdkError` is the public error type returned by `load_response`; boxing it here would only move the allocation
+#[allow(clippy::result_large_err)]
 fn sdk_result<T, E>(
     parsed: Result<T, E>,
-    raw: operation::Response,
-) -> Result<SdkSuccess<T>, SdkError<E>> {
+    mut raw: operation::Response,
+) -> Result<SdkSuccess<T>, SdkError<E>>
+where
+    T: 'static,
+    E: Error + 'static,
+{
+    let hook = raw.properties().get::<ParsedResponseHook<T, E>>().cloned();
+    let parsed = match hook {
+        Some(hook) => hook.apply(parsed, &mut raw),
+        None => parsed,
+    };
     match parsed {
         Ok(parsed) => Ok(SdkSuccess { raw, parsed }),
-        Err(err) => Err(SdkError::ServiceError { raw, err }),
+        Err(err) => match response_parse_error(&err) {
+            Some(parse_err) => Err(SdkError::ResponseError {
+                err: Box::new(parse_err.clone()),
+                raw,
+            }),
+            None => Err(SdkError::ServiceError { raw, err }),
+        },
+    }
+}
+
+/// Finds a [`ResponseParseError`] in the source chain of `err`
+///
+/// Operation errors wrap parse errors, so `err` itself is never a `ResponseParseError`.
+fn response_parse_error(err: &dyn Error) -> Option<&ResponseParseError> {
+    let mut next = err.source();
+    while let Some(err) = next {
+        if let Some(parse_err) = err.downcast_ref::<ResponseParseError>() {
+            return Some(parse_err);
+        }
+        next = err.source();
+    }
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use crate::body::SdkBody;
+    use crate::middleware::load_response;
+    use crate::middleware::load_response_offloaded;
+    use crate::middleware::{preallocation, BodyBuffer};
+    use crate::operation;
+    use crate::response::{
+        IncompleteBody, ParseHttpResponse, ParseOffload, ParsedResponseHook, ResponseBodyTooLarge,
+        ResponseLimits, RunBlocking,
+    };
+    use crate::result::{ResponseParseError, SdkError, SdkSuccess};
+    use crate::retry::RetryClassification;
+    use crate::test_util::ParseBody;
+    use aws_smithy_types::retry::{ErrorKind, RetryKind};
+    use bytes::Bytes;
+    use std::convert::Infallible;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    fn response(body: SdkBody, limits: Option<ResponseLimits>) -> operation::Response {
+        let mut response = operation::Response::new(http::Response::new(body));
+        if let Some(limits) = limits {
+            response.properties_mut().insert(limits);
+        }
+        response
+    }
+
+    fn assert_too_large(result: Result<SdkSuccess<Bytes>, SdkError<Infallible>>) {
+        match result {
+            Err(SdkError::ResponseError { err, .. }) => {
+                let err = err
+                    .downcast_ref::<ResponseBodyTooLarge>()
+                    .expect("body too large error");
+                assert_eq!(4, err.limit());
+            }
+            other => panic!(
+                "expected a response error, got {:?}",
+                other.map(|o| o.parsed)
+            ),
+        }
+    }
+
+    #[tokio::test]
+    async fn body_within_limit() {
+        let limits = ResponseLimits::new().with_max_body_size(4);
+        let result = load_response(response(SdkBody::from("data"), Some(limits)), &ParseBody)
+            .await
+            .expect("success");
+        assert_eq!(Bytes::from_static(b"data"), result.parsed);
+    }
+
+    #[tokio::test]
+    async fn body_exceeds_limit() {
+        let limits = ResponseLimits::new().with_max_body_size(4);
+        // `SdkBody::from` reports an exact size hint, so this is rejected before reading
+        assert_too_large(
+            load_response(
+                response(SdkBody::from("too long"), Some(limits)),
+                &ParseBody,
+            )
+            .await,
+        );
+
+        let chunks: Vec<Result<_, Infallible>> = vec![Ok("to"), Ok("o l"), Ok("ong")];
+        let streaming = SdkBody::from(hyper::Body::wrap_stream(futures_util::stream::iter(chunks)));
+        assert_too_large(load_response(response(streaming, Some(limits)), &ParseBody).await);
+    }
+
+    #[tokio::test]
+    async fn body_stream_errors_are_incomplete_bodies() {
+        let chunks: Vec<Result<_, std::io::Error>> = vec![
+            Ok("partial"),
+            Err(std::io::Error::new(
+                std::io::ErrorKind::ConnectionReset,
+                "connection reset",
+            )),
+        ];
+        let streaming = SdkBody::from(hyper::Body::wrap_stream(futures_util::stream::iter(chunks)));
+        match load_response(response(streaming, None), &ParseBody).await {
+            Err(SdkError::ResponseError { err, .. }) => {
+                assert!(err.downcast_ref::<IncompleteBody>().is_some(), "{:?}", err)
+            }
+            other => panic!(
+                "expected a response error, got {:?}",
+                other.map(|o| o.parsed)
+            ),
+        }
+    }
+
+    #[tokio::test]
+    async fn single_chunk_bodies_are_not_copied() {
+        let data = Bytes::from(vec![b'a'; 1024]);
+        let result = load_response(response(SdkBody::from(data.clone()), None), &ParseBody)
+            .await
+            .expect("success");
+        assert_eq!(data.as_ptr(), result.parsed.as_ptr());
+
+        let chunks: Vec<Result<_, Infallible>> = vec![Ok("multi"), Ok(""), Ok("ple chunks")];
+        let streaming = SdkBody::from(hyper::Body::wrap_stream(futures_util::stream::iter(chunks)));
+        let result = load_response(response(streaming, None), &ParseBody)
+            .await
+            .expect("success");
+        assert_eq!(Bytes::from_static(b"multiple chunks"), result.parsed);
+    }
+
+    #[tokio::test]
+    async fn buffer_is_presized_from_content_length() {
+        let streamed = |content_length: &str, limits| {
+            let chunks: Vec<Result<_, Infallible>> = vec![Ok("multi"), Ok("ple"), Ok(" chunks")];
+            let body = SdkBody::from(hyper::Body::wrap_stream(futures_util::stream::iter(chunks)));
+            let mut response = response(body, Some(limits));
+            response
+                .http_mut()
+                .headers_mut()
+                .insert("content-length", content_length.parse().unwrap());
+            response
+        };
+        let expected = Bytes::from_static(b"multiple chunks");
+
+        for (content_length, limits) in [
+            ("15", ResponseLimits::new()),
+            // a declared length beyond the cap isn't used to size the buffer
+            ("15", ResponseLimits::new().with_max_preallocation(4)),
+            // a length that doesn't match the body doesn't affect the result
+            ("4", ResponseLimits::new()),
+            ("1000", ResponseLimits::new()),
+        ] {
+            let result = load_response(streamed(content_length, limits), &ParseBody)
+                .await
+                .expect("success");
+            assert_eq!(
+                expected, result.parsed,
+                "content-length: {}",
+                content_length
+            );
+        }
+
+        assert_too_large(
+            load_response(
+                streamed("15", ResponseLimits::new().with_max_body_size(4)),
+                &ParseBody,
+            )
+            .await,
+        );
+    }
+
+    #[test]
+    fn body_buffer_is_allocated_once_from_content_length() {
+        let limits = ResponseLimits::new();
+        let preallocate = preallocation(Some(15), &limits);
+        assert_eq!(Some(15), preallocate);
+        // a declared length beyond the cap isn't used to size the buffer
+        assert_eq!(
+            None,
+            preallocation(Some(15), &limits.with_max_preallocation(4))
+        );
+
+        let mut output = BodyBuffer::Segments(Vec::new());
+        output.push(Bytes::from_static(b"multi"), 5, preallocate);
+        assert!(matches!(output, BodyBuffer::Segments(_)));
+        output.push(Bytes::from_static(b"ple"), 8, preallocate);
+        let (capacity, ptr) = match &output {
+            BodyBuffer::Contiguous(buffer) => (buffer.capacity(), buffer.as_ptr()),
+            BodyBuffer::Segments(_) => panic!("the chunks should be copied into one buffer"),
+        };
+        assert!(capacity >= 15, "capacity: {}", capacity);
+        output.push(Bytes::from_static(b" chunks"), 15, preallocate);
+        match &output {
+            // the last chunk fits without growing the buffer
+            BodyBuffer::Contiguous(buffer) => {
+                assert_eq!(capacity, buffer.capacity());
+                assert_eq!(ptr, buffer.as_ptr());
+            }
+            BodyBuffer::Segments(_) => panic!("the chunks should be copied into one buffer"),
+        }
+        assert_eq!(
+            Bytes::from_static(b"multiple chunks"),
+            output.into_bytes(15)
+        );
+    }
+
+    #[tokio::test]
+    async fn no_limit_by_default() {
+        let result = load_response(response(SdkBody::from("too long"), None), &ParseBody)
+            .await
+            .expect("success");
+        assert_eq!(Bytes::from_static(b"too long"), result.parsed);
+    }
+
+    #[cfg(feature = "gzip")]
+    #[tokio::test]
+    async fn gzip_bodies_are_decompressed_within_limit() {
+        use std::io::Write;
+
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::best());
+        encoder.write_all(&[b'a'; 1024]).unwrap();
+        let compressed = encoder.finish().unwrap();
+        let gzip_response = |limits| {
+            let mut response = response(SdkBody::from(compressed.clone()), Some(limits));
+            response
+                .http_mut()
+                .headers_mut()
+                .insert("content-encoding", "gzip".parse().unwrap());
+            response
+        };
+
+        let limits = ResponseLimits::new().with_gzip_decompression();
+        let result = load_response(gzip_response(limits), &ParseBody)
+            .await
+            .expect("success");
+        assert_eq!(&[b'a'; 1024][..], &result.parsed[..]);
+        assert!(result
+            .raw
+            .http()
+            .headers()
+            .get("content-encoding")
+            .is_none());
+
+        // The compressed body fits within the limit, but expands beyond it
+        assert!(compressed.len() < 64);
+        let limits = limits.with_max_body_size(64);
+        match load_response(gzip_response(limits), &ParseBody).await {
+            Err(SdkError::ResponseError { err, .. }) => {
+                assert!(err.downcast_ref::<ResponseBodyTooLarge>().is_some())
+            }
+            other => panic!(
+                "expected a response error, got {:?}",
+                other.map(|o| o.parsed)
+            ),
+        }
+    }
+
+    /// Runs tasks inline, counting how many it ran
+    #[derive(Clone, Debug, Default)]
+    struct CountingExecutor(Arc<AtomicUsize>);
+
+    impl RunBlocking for CountingExecutor {
+        fn run_blocking(&self, task: Box<dyn FnOnce() + Send + 'static>) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            task();
+        }
+    }
+
+    /// Drops tasks without running them
+    #[derive(Debug)]
+    struct FailingExecutor;
+
+    impl RunBlocking for FailingExecutor {
+        fn run_blocking(&self, _task: Box<dyn FnOnce() + Send + 'static>) {}
+    }
+
+    fn offloaded_response(body: &'static str, offload: ParseOffload) -> operation::Response {
+        let mut response = response(SdkBody::from(body), None);
+        response.properties_mut().insert(offload);
+        response
+    }
+
+    #[tokio::test]
+    async fn bodies_at_the_threshold_are_offloaded() {
+        let executor = CountingExecutor::default();
+        let offload = ParseOffload::new(5, executor.clone());
+        let result = load_response_offloaded(
+            offloaded_response("data", offload.clone()),
+            Arc::new(ParseBody),
+        )
+        .await
+        .expect("success");
+        assert_eq!(Bytes::from_static(b"data"), result.parsed);
+        assert_eq!(0, executor.0.load(Ordering::SeqCst));
+
+        let result =
+            load_response_offloaded(offloaded_response("data!", offload), Arc::new(ParseBody))
+                .await
+                .expect("success");
+        assert_eq!(Bytes::from_static(b"data!"), result.parsed);
+        assert_eq!(Some(&b"data!"[..]), result.raw.http().body().bytes());
+        assert_eq!(1, executor.0.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn executor_failures_keep_the_response() {
+        let offload = ParseOffload::new(0, FailingExecutor);
+        match load_response_offloaded(offloaded_response("data", offload), Arc::new(ParseBody))
+            .await
+        {
+            Err(SdkError::ResponseError { raw, err }) => {
+                assert_eq!(
+                    "the offload executor didn't parse the response",
+                    err.to_string()
+                );
+                assert_eq!(Some(&b"data"[..]), raw.http().body().bytes());
+            }
+            other => panic!(
+                "expected a response error, got {:?}",
+                other.map(|o| o.parsed)
+            ),
+        }
+    }
+
+    #[tokio::test]
+    async fn parse_errors_are_response_errors() {
+        /// An operation error wrapping the cause of the failure, like generated errors do
+        #[derive(Debug)]
+        struct OperationError(Box<dyn std::error::Error + Send + Sync>);
+
+        impl std::fmt::Display for OperationError {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "operation error")
+            }
+        }
+
+        impl std::error::Error for OperationError {
+            fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+                Some(self.0.as_ref())
+            }
+        }
+
+        struct ParseStatus;
+
+        impl ParseHttpResponse for ParseStatus {
+            type Output = Result<(), OperationError>;
+
+            fn parse_unloaded(&self, _response: &mut operation::Response) -> Option<Self::Output> {
+                None
+            }
+
+            fn parse_loaded(&self, response: &http::Response<Bytes>) -> Self::Output {
+                match response.body().as_ref() {
+                    b"parse error" => Err(OperationError(Box::new(ResponseParseError::new(
+                        "invalid JSON",
+                    )))),
+                    _ => Err(OperationError("modeled error".into())),
+                }
+            }
+        }
+
+        match load_response(response(SdkBody::from("parse error"), None), &ParseStatus).await {
+            Err(SdkError::ResponseError { err, raw }) => {
+                let err = err
+                    .downcast_ref::<ResponseParseError>()
+                    .expect("response parse error");
+                assert_eq!(
+                    "failed to parse the response: invalid JSON",
+                    err.to_string()
+                );
+                assert_eq!(Some(&b"parse error"[..]), raw.http().body().bytes());
+            }
+            other => panic!("expected a response error, got {:?}", other),
+        }
+        match load_response(response(SdkBody::from("error"), None), &ParseStatus).await {
+            Err(SdkError::ServiceError { .. }) => {}
+            other => panic!("expected a service error, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn parsed_response_hooks_replace_the_result() {
+        #[derive(Debug)]
+        struct ErrorInBody;
+
+        impl std::fmt::Display for ErrorInBody {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "the response body contained an error")
+            }
+        }
+
+        impl std::error::Error for ErrorInBody {}
+
+        struct ParseBodyOrError;
+
+        impl ParseHttpResponse for ParseBodyOrError {
+            type Output = Result<Bytes, ErrorInBody>;
+
+            fn parse_unloaded(&self, _response: &mut operation::Response) -> Option<Self::Output> {
+                None
+            }
+
+            fn parse_loaded(&self, response: &http::Response<Bytes>) -> Self::Output {
+                Ok(response.body().clone())
+            }
+        }
+
+        let hooked = |body: &'static str| {
+            let mut response = response(SdkBody::from(body), None);
+            response.properties_mut().insert(ParsedResponseHook::new(
+                |parsed: Result<Bytes, ErrorInBody>, response| match parsed {
+                    Ok(body) if body.starts_with(b"<Error>") => {
+                        response.properties_mut().insert(RetryClassification::new(
+                            RetryKind::Error(ErrorKind::TransientError),
+                        ));
+                        Err(ErrorInBody)
+                    }
+                    other => other,
+                },
+            ));
+            response
+        };
+
+        match load_response(hooked("<Error>InternalError</Error>"), &ParseBodyOrError).await {
+            Err(SdkError::ServiceError { raw, .. }) => assert_eq!(
+                Some(&RetryClassification::new(RetryKind::Error(
+                    ErrorKind::TransientError
+                ))),
+                raw.properties().get::<RetryClassification>()
+            ),
+            other => panic!("expected a service error, got {:?}", other),
+        }
+        let success = load_response(hooked("<Result/>"), &ParseBodyOrError)
+            .await
+            .expect("success");
+        assert_eq!(Bytes::from_static(b"<Result/>"), success.parsed);
+        assert!(success
+            .raw
+            .properties()
+            .get::<RetryClassification>()
+            .is_none());
+    }
+
+    /// Blocks until another task on the runtime has run, which requires the worker thread to be
+    /// handed off while parsing
+    #[cfg(feature = "rt-tokio")]
+    struct ParseWhileOtherTasksRun;
+
+    #[cfg(feature = "rt-tokio")]
+    impl ParseHttpResponse for ParseWhileOtherTasksRun {
+        type Output = Result<bool, Infallible>;
+
+        fn parse_unloaded(&self, _response: &mut operation::Response) -> Option<Self::Output> {
+            None
+        }
+
+        fn parse_loaded(&self, _response: &http::Response<Bytes>) -> Self::Output {
+            let (tx, rx) = std::sync::mpsc::channel();
+            tokio::spawn(async move { tx.send(()).unwrap() });
+            Ok(rx.recv_timeout(std::time::Duration::from_secs(5)).is_ok())
+        }
+    }
+
+    #[cfg(feature = "rt-tokio")]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn tokio_keeps_running_other_tasks_while_parsing() {
+        let result = load_response_offloaded(
+            offloaded_response("data", ParseOffload::tokio(1)),
+            Arc::new(ParseWhileOtherTasksRun),
+        )
+        .await
+        .expect("success");
+        assert!(result.parsed, "the spawned task should run while parsing");
+    }
+
+    #[cfg(feature = "rt-tokio")]
+    #[tokio::test]
+    async fn tokio_offloads_on_a_current_thread_runtime() {
+        let result = load_response_offloaded(
+            offloaded_response("data", ParseOffload::tokio(1)),
+            Arc::new(ParseWhileOtherTasksRun),
+        )
+        .await
+        .expect("success");
+        assert!(result.parsed, "the spawned task should run while parsing");
+        assert_eq!(Some(&b"data"[..]), result.raw.http().body().bytes());
+    }
+
+    #[tokio::test]
+    async fn offloaded_parses_can_run_on_other_threads() {
+        /// Runs each task on a new thread
+        #[derive(Debug)]
+        struct ThreadExecutor;
+
+        impl RunBlocking for ThreadExecutor {
+            fn run_blocking(&self, task: Box<dyn FnOnce() + Send + 'static>) {
+                std::thread::spawn(task);
+            }
+        }
+
+        let result = load_response_offloaded(
+            offloaded_response("data", ParseOffload::new(1, ThreadExecutor)),
+            Arc::new(ParseBody),
+        )
+        .await
+        .expect("success");
+        assert_eq!(Bytes::from_static(b"data"), result.parsed);
     }
 }
@@ -4,15 +4,24 @@
  */
 
 use crate::body::SdkBody;
+use crate::interceptor::{Interceptor, Interceptors, SharedInterceptor};
 use crate::property_bag::{PropertyBag, SharedPropertyBag};
 use aws_smithy_types::date_time::DateTimeFormatError;
+use aws_smithy_types::retry::RetryConfig;
+use aws_smithy_types::timeout;
+use aws_smithy_types::validation::ConstraintViolation;
 use http::uri::InvalidUri;
 use std::borrow::Cow;
 use std::error::Error;
 use std::fmt::{Display, Formatter};
 use std::ops::{Deref, DerefMut};
+use std::time::Duration;
 
-#[derive(Clone, Debug)]
+/// The service and operation that a request is being made for
+///
+/// [`Operation::with_metadata`] also stores the metadata in the request's property bag so that
+/// middleware (e.g. for user agents, tracing, or metrics) can tell which operation it is processing.
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Metadata {
     operation: Cow<'static, str>,
     service: Cow<'static, str>,
@@ -38,6 +47,88 @@ impl Metadata {
     }
 }
 
+/// Identifies one invocation of an operation across all of its attempts.
+///
+/// The client stores one in the property bag of each operation it dispatches, so that it can be
+/// sent to the service and used to correlate the attempts of a retried operation.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct InvocationId(Cow<'static, str>);
+
+impl InvocationId {
+    /// Creates a new `InvocationId`
+    pub fn new(id: impl Into<Cow<'static, str>>) -> Self {
+        Self(id.into())
+    }
+
+    /// Returns the invocation ID as a string
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Display for InvocationId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Requests that large bodies be sent with `Expect: 100-continue`.
+///
+/// When stored in the property bag of an operation, requests whose body is at least
+/// [`threshold`](ExpectContinue::threshold) bytes (or of unknown length) are sent with an
+/// `Expect: 100-continue` header. This gives the service a chance to reject the request (e.g.
+/// because of an invalid signature or a missing bucket) before the payload is uploaded.
+///
+/// By default, the body is sent right after the headers, leaving it to the connection to honor
+/// the header. [`with_timeout`](ExpectContinue::with_timeout) holds the body back until the
+/// connector receives `100 Continue`, the service responds, or the timeout elapses, whichever
+/// comes first. Holding back the body requires a sleep implementation to be configured on the
+/// client. Without one, only the header is sent.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ExpectContinue {
+    threshold: u64,
+    timeout: Option<Duration>,
+}
+
+impl Default for ExpectContinue {
+    fn default() -> Self {
+        Self {
+            threshold: 2 * 1024 * 1024,
+            timeout: None,
+        }
+    }
+}
+
+impl ExpectContinue {
+    /// Sends bodies of at least 2 MiB with `Expect: 100-continue`, without holding back the body.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only send `Expect: 100-continue` for bodies of at least `threshold` bytes.
+    pub fn with_threshold(mut self, threshold: u64) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Hold back the body until the service sends `100 Continue` or responds, for at most `timeout`.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Returns the minimum body size for which `Expect: 100-continue` is sent.
+    pub fn threshold(&self) -> u64 {
+        self.threshold
+    }
+
+    /// Returns the maximum amount of time the body is held back, if it is held back at all.
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+}
+
 #[non_exhaustive]
 #[derive(Clone, Debug)]
 pub struct Parts<H, R> {
@@ -51,6 +142,11 @@ pub struct Parts<H, R> {
 /// These are almost always due to user error caused by limitations of specific fields due to
 /// protocol serialization (e.g. fields that can only be a subset ASCII because they are serialized
 /// as the name of an HTTP header)
+///
+/// When an operation fails to build while it's being sent, this is the error in
+/// [`SdkError::ConstructionFailure`](crate::result::SdkError::ConstructionFailure), and can be
+/// retrieved with [`SdkError::build_error`](crate::result::SdkError::build_error). Use
+/// [`BuildError::field`] to find out which field was at fault.
 #[non_exhaustive]
 #[derive(Debug)]
 pub enum BuildError {
@@ -64,6 +160,15 @@ pub enum BuildError {
         field: &'static str,
         details: &'static str,
     },
+    /// A field bound to a label in the URI had a value that can't be used as a label
+    ///
+    /// For example, labels can't be empty.
+    InvalidLabel {
+        field: &'static str,
+        details: &'static str,
+    },
+    /// A field's value violated a constraint of the model, such as a length or range
+    ConstraintViolation(ConstraintViolation),
     /// The serializer could not serialize the input
     SerializationError(SerializationError),
 
@@ -80,6 +185,25 @@ pub enum BuildError {
     Other(Box<dyn Error + Send + Sync + 'static>),
 }
 
+impl BuildError {
+    /// Returns the name of the field that caused the error, if the error was caused by a field
+    pub fn field(&self) -> Option<&'static str> {
+        match self {
+            BuildError::InvalidField { field, .. }
+            | BuildError::MissingField { field, .. }
+            | BuildError::InvalidLabel { field, .. } => Some(field),
+            BuildError::ConstraintViolation(violation) => Some(violation.field()),
+            _ => None,
+        }
+    }
+}
+
+impl From<ConstraintViolation> for BuildError {
+    fn from(violation: ConstraintViolation) -> Self {
+        BuildError::ConstraintViolation(violation)
+    }
+}
+
 impl From<SerializationError> for BuildError {
     fn from(err: SerializationError) -> Self {
         BuildError::SerializationError(err)
@@ -103,6 +227,10 @@ impl Display for BuildError {
             BuildError::MissingField { field, details } => {
                 write!(f, "{} was missing. {}", field, details)
             }
+            BuildError::InvalidLabel { field, details } => {
+                write!(f, "{} cannot be used in the URI: {}", field, details)
+            }
+            BuildError::ConstraintViolation(violation) => write!(f, "{}", violation),
             BuildError::SerializationError(inner) => {
                 write!(f, "failed to serialize input: {}", inner)
             }
@@ -189,10 +317,44 @@ impl<H, R> Operation<H, R> {
     }
 
     pub fn with_metadata(mut self, metadata: Metadata) -> Self {
+        self.request.properties_mut().insert(metadata.clone());
         self.parts.metadata = Some(metadata);
         self
     }
 
+    /// Overrides the client's retry config for this operation
+    ///
+    /// Only the maximum number of attempts is taken from `retry_config`.
+    pub fn with_retry_config(mut self, retry_config: RetryConfig) -> Self {
+        self.request.properties_mut().insert(retry_config);
+        self
+    }
+
+    /// Overrides the client's API timeouts for this operation
+    ///
+    /// Timeouts left unset in `timeout` fall back to the client's.
+    pub fn with_timeout(mut self, timeout: timeout::Api) -> Self {
+        self.request.properties_mut().insert(timeout);
+        self
+    }
+
+    /// Adds an [`Interceptor`] that runs for every attempt of this operation
+    ///
+    /// Interceptors run in the order they were added.
+    pub fn with_interceptor(mut self, interceptor: impl Interceptor + 'static) -> Self {
+        {
+            let mut properties = self.request.properties_mut();
+            if properties.get::<Interceptors>().is_none() {
+                properties.insert(Interceptors::new());
+            }
+            properties
+                .get_mut::<Interceptors>()
+                .expect("inserted above")
+                .push(SharedInterceptor::new(interceptor));
+        }
+        self
+    }
+
     pub fn with_retry_policy<R2>(self, retry_policy: R2) -> Operation<H, R2> {
         Operation {
             request: self.request,
@@ -208,6 +370,11 @@ impl<H, R> Operation<H, R> {
         &self.parts.retry_policy
     }
 
+    /// Returns the operation's [`Metadata`] (its name and the name of its service), if it was set
+    pub fn metadata(&self) -> Option<&Metadata> {
+        self.parts.metadata.as_ref()
+    }
+
     pub fn try_clone(&self) -> Option<Self>
     where
         H: Clone,
@@ -366,6 +533,37 @@ impl Response {
         &self.inner
     }
 
+    /// Returns the HTTP status code of the response.
+    pub fn status(&self) -> http::StatusCode {
+        self.inner.status()
+    }
+
+    /// Returns the headers of the response.
+    pub fn headers(&self) -> &http::HeaderMap {
+        self.inner.headers()
+    }
+
+    /// Returns the request ID assigned by the service, if the response included one.
+    pub fn request_id(&self) -> Option<&str> {
+        crate::header::request_id(self.inner.headers())
+    }
+
+    /// Returns the ID of the operation invocation this is a response to, if the client assigned
+    /// one.
+    pub fn invocation_id(&self) -> Option<InvocationId> {
+        self.properties.acquire().get::<InvocationId>().cloned()
+    }
+
+    /// Returns how long each stage of the attempt that produced this response took.
+    ///
+    /// See [`TimingInfo`](crate::timing::TimingInfo) for details.
+    pub fn timing_info(&self) -> Option<crate::timing::TimingInfo> {
+        self.properties
+            .acquire()
+            .get::<crate::timing::TimingInfo>()
+            .cloned()
+    }
+
     /// Consumes the operation `Request` and returns the underlying HTTP response and properties.
     pub fn into_parts(self) -> (http::Response<SdkBody>, SharedPropertyBag) {
         (self.inner, self.properties)
@@ -380,9 +578,15 @@ impl Response {
 #[cfg(test)]
 mod test {
     use crate::body::SdkBody;
-    use crate::operation::Request;
+    use crate::interceptor::{Interceptor, Interceptors};
+    use crate::operation::{BuildError, InvocationId, Metadata, Operation, Request, Response};
+    use crate::property_bag::PropertyBag;
+    use aws_smithy_types::retry::RetryConfig;
+    use aws_smithy_types::timeout;
+    use aws_smithy_types::tristate::TriState;
     use http::header::{AUTHORIZATION, CONTENT_LENGTH};
     use http::Uri;
+    use std::time::Duration;
 
     #[test]
     fn try_clone_clones_all_data() {
@@ -410,4 +614,109 @@ mod test {
         assert_eq!(request.body().bytes().unwrap(), "hello world!".as_bytes());
         assert_eq!(config.acquire().get::<&str>(), Some(&"hello"));
     }
+
+    #[test]
+    fn metadata_is_available_to_middleware() {
+        let request = Request::new(http::Request::new(SdkBody::from("")));
+        let operation =
+            Operation::new(request, ()).with_metadata(Metadata::new("ListBuckets", "s3"));
+        let (request, parts) = operation.into_request_response();
+        assert_eq!(
+            request.properties().get::<Metadata>(),
+            parts.metadata.as_ref()
+        );
+        assert_eq!(
+            request.properties().get::<Metadata>().map(|m| m.name()),
+            Some("ListBuckets")
+        );
+    }
+
+    #[test]
+    fn per_operation_overrides_are_stored_in_the_property_bag() {
+        #[derive(Debug)]
+        struct Named(&'static str);
+        impl Interceptor for Named {
+            fn modify_before_transmit(
+                &self,
+                request: &mut http::Request<SdkBody>,
+                _properties: &PropertyBag,
+            ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+                request
+                    .headers_mut()
+                    .append("x-interceptor", http::HeaderValue::from_static(self.0));
+                Ok(())
+            }
+        }
+
+        let request = Request::new(http::Request::new(SdkBody::from("")));
+        let operation = Operation::new(request, ())
+            .with_retry_config(RetryConfig::new().with_max_attempts(5))
+            .with_timeout(
+                timeout::Api::new().with_call_timeout(TriState::Set(Duration::from_secs(2))),
+            )
+            .with_interceptor(Named("first"))
+            .with_interceptor(Named("second"));
+        let properties = operation.properties();
+        assert_eq!(
+            properties.get::<RetryConfig>().map(|c| c.max_attempts()),
+            Some(5)
+        );
+        assert_eq!(
+            properties
+                .get::<timeout::Api>()
+                .and_then(|t| t.call_timeout().into()),
+            Some(Duration::from_secs(2))
+        );
+        let mut request = http::Request::new(SdkBody::empty());
+        for interceptor in properties.get::<Interceptors>().unwrap().iter() {
+            interceptor
+                .modify_before_transmit(&mut request, &properties)
+                .unwrap();
+        }
+        let order: Vec<_> = request.headers().get_all("x-interceptor").iter().collect();
+        assert_eq!(order, vec!["first", "second"]);
+    }
+
+    #[test]
+    fn response_accessors() {
+        let mut response = Response::new(
+            http::Response::builder()
+                .status(404)
+                .header("x-amzn-requestid", "request-id")
+                .body(SdkBody::from("not found"))
+                .expect("valid response"),
+        );
+        assert_eq!(response.status(), http::StatusCode::NOT_FOUND);
+        assert_eq!(response.headers().len(), 1);
+        assert_eq!(response.request_id(), Some("request-id"));
+        assert_eq!(response.invocation_id(), None);
+
+        response
+            .properties_mut()
+            .insert(InvocationId::new("invocation-id"));
+        assert_eq!(
+            response.invocation_id(),
+            Some(InvocationId::new("invocation-id"))
+        );
+    }
+
+    #[test]
+    fn build_errors_report_the_field_at_fault() {
+        let missing = BuildError::MissingField {
+            field: "bucket",
+            details: "bucket was not specified but it is required when building GetObjectInput",
+        };
+        assert_eq!(Some("bucket"), missing.field());
+        let label = BuildError::InvalidLabel {
+            field: "key",
+            details: "cannot be empty",
+        };
+        assert_eq!(Some("key"), label.field());
+        assert_eq!(
+            "key cannot be used in the URI: cannot be empty",
+            label.to_string()
+        );
+        let other = BuildError::Other("checksum failed".into());
+        assert_eq!(None, other.field());
+    }
 }
@@ -0,0 +1,115 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Per-operation control over which middleware stages run.
+//!
+//! By default every stage in the middleware stack runs for every operation. An operation can opt
+//! out of individual stages by inserting a [`PipelineConfig`] into its property bag. This is needed
+//! when re-dispatching a presigned request, which must not be signed or have its URI changed
+//! again, and when sending requests to non-AWS endpoints through the same client.
+
+use crate::property_bag::PropertyBag;
+
+/// A middleware stage that an operation can skip
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Stage {
+    /// Resolving credentials and signing the request
+    Signing,
+    /// Adding the `User-Agent` and `x-amz-user-agent` headers
+    UserAgent,
+    /// Resolving the endpoint and applying it to the request URI
+    ///
+    /// When this is skipped, the request URI is sent as-is and no signing region or service is
+    /// derived from the endpoint.
+    Endpoint,
+}
+
+/// Which middleware stages to run for an operation
+///
+/// # Examples
+/// ```rust
+/// use aws_smithy_http::pipeline::{PipelineConfig, Stage};
+/// use aws_smithy_http::property_bag::PropertyBag;
+///
+/// let mut properties = PropertyBag::new();
+/// PipelineConfig::new()
+///     .skip(Stage::Signing)
+///     .skip(Stage::Endpoint)
+///     .apply(&mut properties);
+/// assert!(PipelineConfig::skips(&properties, Stage::Signing));
+/// assert!(!PipelineConfig::skips(&properties, Stage::UserAgent));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PipelineConfig {
+    skipped: Vec<Stage>,
+}
+
+impl PipelineConfig {
+    /// Creates a `PipelineConfig` that runs every stage
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Skips `stage` for the operation
+    pub fn skip(mut self, stage: Stage) -> Self {
+        self.set_skip(stage, true);
+        self
+    }
+
+    /// Sets whether `stage` is skipped for the operation
+    pub fn set_skip(&mut self, stage: Stage, skip: bool) -> &mut Self {
+        self.skipped.retain(|skipped| *skipped != stage);
+        if skip {
+            self.skipped.push(stage);
+        }
+        self
+    }
+
+    /// Returns true if `stage` is skipped
+    pub fn is_skipped(&self, stage: Stage) -> bool {
+        self.skipped.contains(&stage)
+    }
+
+    /// Inserts this configuration into `properties`, replacing any existing configuration
+    pub fn apply(self, properties: &mut PropertyBag) {
+        properties.insert(self);
+    }
+
+    /// Returns true if the [`PipelineConfig`] in `properties` skips `stage`
+    ///
+    /// Stages are run when there is no `PipelineConfig` in `properties`.
+    pub fn skips(properties: &PropertyBag, stage: Stage) -> bool {
+        properties
+            .get::<PipelineConfig>()
+            .map(|config| config.is_skipped(stage))
+            .unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::pipeline::{PipelineConfig, Stage};
+    use crate::property_bag::PropertyBag;
+
+    #[test]
+    fn stages_run_by_default() {
+        let properties = PropertyBag::new();
+        assert!(!PipelineConfig::skips(&properties, Stage::Signing));
+        assert!(!PipelineConfig::skips(&properties, Stage::UserAgent));
+        assert!(!PipelineConfig::skips(&properties, Stage::Endpoint));
+    }
+
+    #[test]
+    fn skipped_stages_can_be_reenabled() {
+        let mut config = PipelineConfig::new()
+            .skip(Stage::Signing)
+            .skip(Stage::Signing);
+        assert!(config.is_skipped(Stage::Signing));
+        config.set_skip(Stage::Signing, false);
+        assert!(!config.is_skipped(Stage::Signing));
+        assert_eq!(config, PipelineConfig::new());
+    }
+}
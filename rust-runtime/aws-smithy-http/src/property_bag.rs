@@ -15,7 +15,29 @@ use std::hash::{BuildHasherDefault, Hasher};
 use std::ops::{Deref, DerefMut};
 use std::sync::{Arc, Mutex};
 
-type AnyMap = HashMap<TypeId, Box<dyn Any + Send + Sync>, BuildHasherDefault<IdHasher>>;
+type AnyMap = HashMap<TypeId, NamedType, BuildHasherDefault<IdHasher>>;
+
+/// A value stored in the bag along with the name of its type (for debugging)
+struct NamedType {
+    name: &'static str,
+    value: Box<dyn Any + Send + Sync>,
+}
+
+impl NamedType {
+    fn new<T: Send + Sync + 'static>(value: T) -> Self {
+        Self {
+            name: std::any::type_name::<T>(),
+            value: Box::new(value),
+        }
+    }
+
+    fn assume<T: Send + Sync + 'static>(self) -> Option<T> {
+        (self.value as Box<dyn Any + 'static>)
+            .downcast()
+            .ok()
+            .map(|boxed| *boxed)
+    }
+}
 
 // With TypeIds as keys, there's no need to hash them. They are already hashes
 // themselves, coming from the compiler. The IdHasher just holds the u64 of
@@ -79,13 +101,8 @@ impl PropertyBag {
     /// ```
     pub fn insert<T: Send + Sync + 'static>(&mut self, val: T) -> Option<T> {
         self.map
-            .insert(TypeId::of::<T>(), Box::new(val))
-            .and_then(|boxed| {
-                (boxed as Box<dyn Any + 'static>)
-                    .downcast()
-                    .ok()
-                    .map(|boxed| *boxed)
-            })
+            .insert(TypeId::of::<T>(), NamedType::new(val))
+            .and_then(NamedType::assume)
     }
 
     /// Get a reference to a type previously inserted on this `PropertyBag`.
@@ -103,7 +120,7 @@ impl PropertyBag {
     pub fn get<T: Send + Sync + 'static>(&self) -> Option<&T> {
         self.map
             .get(&TypeId::of::<T>())
-            .and_then(|boxed| (&**boxed as &(dyn Any + 'static)).downcast_ref())
+            .and_then(|named| (&*named.value as &(dyn Any + 'static)).downcast_ref())
     }
 
     /// Get a mutable reference to a type previously inserted on this `PropertyBag`.
@@ -121,7 +138,7 @@ impl PropertyBag {
     pub fn get_mut<T: Send + Sync + 'static>(&mut self) -> Option<&mut T> {
         self.map
             .get_mut(&TypeId::of::<T>())
-            .and_then(|boxed| (&mut **boxed as &mut (dyn Any + 'static)).downcast_mut())
+            .and_then(|named| (&mut *named.value as &mut (dyn Any + 'static)).downcast_mut())
     }
 
     /// Remove a type from this `PropertyBag`.
@@ -138,12 +155,9 @@ impl PropertyBag {
     /// assert!(props.get::<i32>().is_none());
     /// ```
     pub fn remove<T: Send + Sync + 'static>(&mut self) -> Option<T> {
-        self.map.remove(&TypeId::of::<T>()).and_then(|boxed| {
-            (boxed as Box<dyn Any + 'static>)
-                .downcast()
-                .ok()
-                .map(|boxed| *boxed)
-        })
+        self.map
+            .remove(&TypeId::of::<T>())
+            .and_then(NamedType::assume)
     }
 
     /// Clear the `PropertyBag` of all inserted extensions.
@@ -161,11 +175,32 @@ impl PropertyBag {
     pub fn clear(&mut self) {
         self.map.clear();
     }
+
+    /// Returns the names of the types stored in this `PropertyBag`, sorted alphabetically.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use aws_smithy_http::property_bag::PropertyBag;
+    /// let mut props = PropertyBag::new();
+    /// props.insert(5i32);
+    /// props.insert(true);
+    ///
+    /// assert_eq!(props.type_names(), vec!["bool", "i32"]);
+    /// ```
+    pub fn type_names(&self) -> Vec<&'static str> {
+        let mut names: Vec<_> = self.map.values().map(|named| named.name).collect();
+        names.sort_unstable();
+        names
+    }
 }
 
 impl fmt::Debug for PropertyBag {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_struct("PropertyBag").finish()
+        // values aren't required to implement `Debug`, so only the type names are listed
+        f.debug_struct("PropertyBag")
+            .field("contents", &self.type_names())
+            .finish()
     }
 }
 
@@ -241,3 +276,13 @@ fn test_extensions() {
     assert_eq!(extensions.get::<bool>(), None);
     assert_eq!(extensions.get(), Some(&MyType(10)));
 }
+
+#[cfg(test)]
+#[test]
+fn debug_lists_stored_types() {
+    struct MyType;
+
+    let mut bag = PropertyBag::new();
+    bag.insert(MyType);
+    assert!(format!("{:?}", bag).contains("MyType"));
+}
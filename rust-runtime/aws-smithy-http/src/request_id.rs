@@ -0,0 +1,181 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Request IDs assigned by services to the responses they return.
+//!
+//! Services report the ID of a request in one of a few response headers, depending on the
+//! protocol: `x-amzn-requestid` or `x-amz-request-id`. S3 additionally returns an "extended"
+//! request ID (also known as the host ID) in `x-amz-id-2`. Both are necessary when contacting
+//! AWS Support about a specific request.
+//!
+//! Generated operation outputs and errors implement [`RequestId`] so that the IDs are available
+//! regardless of whether the operation succeeded.
+
+use crate::operation;
+use crate::result::SdkError;
+use http::HeaderMap;
+
+/// Response header that carries S3's extended request ID.
+const EXTENDED_REQUEST_ID_HEADER: &str = "x-amz-id-2";
+
+/// Key the extended request ID is stored under in [`aws_smithy_types::Error`]'s extras.
+pub const EXTENDED_REQUEST_ID: &str = "s3_extended_request_id";
+
+/// Implemented by types that know the ID of the request they came from.
+pub trait RequestId {
+    /// Returns the request ID, if the service returned one.
+    fn request_id(&self) -> Option<&str>;
+
+    /// Returns S3's extended request ID, if the service returned one.
+    ///
+    /// Read more at <https://aws.amazon.com/premiumsupport/knowledge-center/s3-request-id-values/>.
+    fn extended_request_id(&self) -> Option<&str> {
+        None
+    }
+}
+
+impl RequestId for HeaderMap {
+    fn request_id(&self) -> Option<&str> {
+        crate::header::request_id(self)
+    }
+
+    fn extended_request_id(&self) -> Option<&str> {
+        extended_request_id(self)
+    }
+}
+
+impl RequestId for operation::Response {
+    fn request_id(&self) -> Option<&str> {
+        self.http().headers().request_id()
+    }
+
+    fn extended_request_id(&self) -> Option<&str> {
+        self.http().headers().extended_request_id()
+    }
+}
+
+impl RequestId for aws_smithy_types::Error {
+    fn request_id(&self) -> Option<&str> {
+        aws_smithy_types::Error::request_id(self)
+    }
+
+    fn extended_request_id(&self) -> Option<&str> {
+        self.extra(EXTENDED_REQUEST_ID)
+    }
+}
+
+impl<E> RequestId for SdkError<E>
+where
+    E: RequestId,
+{
+    fn request_id(&self) -> Option<&str> {
+        match self {
+            SdkError::ServiceError { err, raw } => err.request_id().or_else(|| raw.request_id()),
+            SdkError::ResponseError { raw, .. } => raw.request_id(),
+            _ => None,
+        }
+    }
+
+    fn extended_request_id(&self) -> Option<&str> {
+        match self {
+            SdkError::ServiceError { err, raw } => err
+                .extended_request_id()
+                .or_else(|| raw.extended_request_id()),
+            SdkError::ResponseError { raw, .. } => raw.extended_request_id(),
+            _ => None,
+        }
+    }
+}
+
+/// Returns S3's extended request ID from a response's headers, if the service returned one.
+pub fn extended_request_id(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(EXTENDED_REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+}
+
+/// Fills in the request IDs of a generic error from the response headers.
+///
+/// Request IDs that were already parsed out of the error body are kept.
+pub fn apply_request_id(
+    error: aws_smithy_types::Error,
+    headers: &HeaderMap,
+) -> aws_smithy_types::Error {
+    let missing_request_id = error.request_id().is_none();
+    let missing_extended_request_id = error.extra(EXTENDED_REQUEST_ID).is_none();
+    let mut builder = error.into_builder();
+    if missing_request_id {
+        if let Some(request_id) = headers.request_id() {
+            builder.request_id(request_id);
+        }
+    }
+    if missing_extended_request_id {
+        if let Some(extended_request_id) = headers.extended_request_id() {
+            builder.custom(EXTENDED_REQUEST_ID, extended_request_id);
+        }
+    }
+    builder.build()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{apply_request_id, RequestId};
+    use crate::body::SdkBody;
+    use crate::operation;
+    use crate::result::SdkError;
+    use http::HeaderMap;
+
+    #[test]
+    fn request_ids_from_headers() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(None, headers.request_id());
+        assert_eq!(None, headers.extended_request_id());
+
+        headers.insert("x-amz-request-id", "request-id".parse().unwrap());
+        headers.insert("x-amz-id-2", "extended-request-id".parse().unwrap());
+        assert_eq!(Some("request-id"), headers.request_id());
+        assert_eq!(Some("extended-request-id"), headers.extended_request_id());
+    }
+
+    #[test]
+    fn apply_request_ids_to_generic_error() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-amzn-requestid", "request-id".parse().unwrap());
+        headers.insert("x-amz-id-2", "extended-request-id".parse().unwrap());
+
+        let error = apply_request_id(aws_smithy_types::Error::builder().build(), &headers);
+        assert_eq!(Some("request-id"), RequestId::request_id(&error));
+        assert_eq!(Some("extended-request-id"), error.extended_request_id());
+
+        // request IDs parsed from the error body take precedence
+        let error = apply_request_id(
+            aws_smithy_types::Error::builder()
+                .request_id("from-body")
+                .build(),
+            &headers,
+        );
+        assert_eq!(Some("from-body"), RequestId::request_id(&error));
+    }
+
+    #[test]
+    fn request_ids_from_sdk_errors() {
+        let raw = operation::Response::new(
+            http::Response::builder()
+                .header("x-amzn-requestid", "from-headers")
+                .header("x-amz-id-2", "extended-request-id")
+                .body(SdkBody::empty())
+                .unwrap(),
+        );
+        let err: SdkError<aws_smithy_types::Error> = SdkError::ServiceError {
+            err: aws_smithy_types::Error::builder().build(),
+            raw,
+        };
+        assert_eq!(Some("from-headers"), err.request_id());
+        assert_eq!(Some("extended-request-id"), err.extended_request_id());
+
+        let err: SdkError<aws_smithy_types::Error> = SdkError::ConstructionFailure("bad".into());
+        assert_eq!(None, err.request_id());
+    }
+}
@@ -0,0 +1,241 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Validation of requests before they are dispatched
+//!
+//! `http` accepts some header values and URIs that are rejected by HTTP connectors or by services
+//! (e.g. header values containing non-ASCII bytes, or query strings containing characters that
+//! should have been percent-encoded). When that happens at dispatch time, the resulting error
+//! doesn't say which part of the request was invalid. [`RequestValidationStage`] checks requests
+//! up front and names the offending header or query parameter instead.
+
+use crate::middleware::MapRequest;
+use crate::operation::Request;
+use std::error::Error;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+
+/// A request that can't be sent
+///
+/// The offending value itself is not included, since it may be sensitive.
+#[non_exhaustive]
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum InvalidRequestError {
+    /// A header value contains characters other than visible ASCII, spaces, and tabs
+    HeaderValue {
+        /// The name of the header
+        name: String,
+    },
+
+    /// A query parameter contains characters that must be percent-encoded
+    QueryParameter {
+        /// The name of the query parameter, as it appears in the URI
+        name: String,
+    },
+}
+
+impl Display for InvalidRequestError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            InvalidRequestError::HeaderValue { name } => write!(
+                f,
+                "the value of header `{}` is invalid: header values may only contain visible ASCII characters, spaces, and tabs",
+                name
+            ),
+            InvalidRequestError::QueryParameter { name } => write!(
+                f,
+                "query parameter `{}` is invalid: it contains characters that must be percent-encoded",
+                name
+            ),
+        }
+    }
+}
+
+impl Error for InvalidRequestError {}
+
+/// Middleware stage that rejects requests with invalid header values or query strings
+///
+/// Failures are returned as an [`InvalidRequestError`], which the client surfaces as a
+/// [`ConstructionFailure`](crate::result::SdkError::ConstructionFailure). This stage should run
+/// after every stage that adds headers or query parameters, right before dispatch.
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+pub struct RequestValidationStage;
+
+impl RequestValidationStage {
+    /// Creates a new request validation stage
+    pub fn new() -> Self {
+        RequestValidationStage
+    }
+}
+
+impl MapRequest for RequestValidationStage {
+    type Error = InvalidRequestError;
+
+    fn apply(&self, request: Request) -> Result<Request, Self::Error> {
+        request.augment(|request, _properties| {
+            validate(&request)?;
+            Ok(request)
+        })
+    }
+
+    fn name(&self) -> &'static str {
+        "request_validation"
+    }
+}
+
+fn validate<B>(request: &http::Request<B>) -> Result<(), InvalidRequestError> {
+    for (name, value) in request.headers() {
+        validate_header_value(name.as_str(), value.as_bytes())?;
+    }
+    if let Some(query) = request.uri().query() {
+        validate_query(query)?;
+    }
+    Ok(())
+}
+
+fn validate_header_value(name: &str, value: &[u8]) -> Result<(), InvalidRequestError> {
+    if value.iter().all(|&b| is_valid_header_byte(b)) {
+        Ok(())
+    } else {
+        Err(InvalidRequestError::HeaderValue {
+            name: name.to_string(),
+        })
+    }
+}
+
+fn validate_query(query: &str) -> Result<(), InvalidRequestError> {
+    for param in query.split('&') {
+        if !is_valid_query_component(param) {
+            let name = param.split('=').next().unwrap_or(param);
+            return Err(InvalidRequestError::QueryParameter {
+                name: name.to_string(),
+            });
+        }
+    }
+    Ok(())
+}
+
+fn is_valid_header_byte(b: u8) -> bool {
+    b == b'\t' || (b' '..=b'~').contains(&b)
+}
+
+/// Returns true if `component` only contains the characters allowed in a query by
+/// [RFC 3986](https://datatracker.ietf.org/doc/html/rfc3986#section-3.4), and every `%` starts a
+/// percent-encoded byte
+fn is_valid_query_component(component: &str) -> bool {
+    let bytes = component.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' => {
+                let encoded = bytes.get(i + 1..i + 3);
+                if !matches!(encoded, Some(hex) if hex.iter().all(u8::is_ascii_hexdigit)) {
+                    return false;
+                }
+                i += 3;
+                continue;
+            }
+            // unreserved
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {}
+            // sub-delims
+            b'!' | b'$' | b'&' | b'\'' | b'(' | b')' | b'*' | b'+' | b',' | b';' | b'=' => {}
+            b':' | b'@' | b'/' | b'?' => {}
+            _ => return false,
+        }
+        i += 1;
+    }
+    true
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        validate, validate_header_value, validate_query, InvalidRequestError,
+        RequestValidationStage,
+    };
+    use crate::body::SdkBody;
+    use crate::middleware::MapRequest;
+    use crate::operation;
+    use http::HeaderValue;
+
+    fn request(uri: &str) -> http::Request<SdkBody> {
+        http::Request::builder()
+            .uri(uri)
+            .body(SdkBody::empty())
+            .unwrap()
+    }
+
+    #[test]
+    fn valid_requests_pass_through() {
+        let mut req = request("https://example.com/path?list-type=2&prefix=a%2Fb&flag&k=v:w@x/y?z");
+        req.headers_mut().insert(
+            "authorization",
+            HeaderValue::from_static("AWS4-HMAC-SHA256 Credential=abc/123, Signature=def"),
+        );
+        req.headers_mut()
+            .insert("x-amz-meta-tab", HeaderValue::from_static("a\tb"));
+        let req = RequestValidationStage::new()
+            .apply(operation::Request::new(req))
+            .expect("request is valid");
+        assert_eq!(
+            req.http().uri().query(),
+            Some("list-type=2&prefix=a%2Fb&flag&k=v:w@x/y?z")
+        );
+    }
+
+    #[test]
+    fn non_ascii_header_values_are_rejected() {
+        let mut req = request("https://example.com/");
+        req.headers_mut().insert(
+            "x-amz-meta-name",
+            HeaderValue::from_bytes("café".as_bytes()).unwrap(),
+        );
+        assert_eq!(
+            validate(&req),
+            Err(InvalidRequestError::HeaderValue {
+                name: "x-amz-meta-name".into()
+            })
+        );
+    }
+
+    #[test]
+    fn control_characters_in_header_values_are_rejected() {
+        // `http` rejects these when the `HeaderValue` is created, but connectors and other
+        // middleware may build header values without validating them
+        let err = validate_header_value("x-amz-meta-note", b"a\r\nx-injected: b")
+            .expect_err("header contains a line break");
+        assert_eq!(
+            err,
+            InvalidRequestError::HeaderValue {
+                name: "x-amz-meta-note".into()
+            }
+        );
+        assert!(!err.to_string().contains("injected"), "{}", err);
+        assert!(validate_header_value("x-amz-meta-note", b"a\x7fb").is_err());
+    }
+
+    #[test]
+    fn unencoded_query_characters_are_rejected() {
+        for (query, name) in [
+            ("prefix=a%2Fb&key={id}", "key"),
+            ("k=v&quote=\"a\"", "quote"),
+            ("pipe|name=v", "pipe|name"),
+            ("k=100%", "k"),
+            ("k=%zz", "k"),
+        ] {
+            assert_eq!(
+                validate_query(query),
+                Err(InvalidRequestError::QueryParameter { name: name.into() }),
+                "{}",
+                query
+            );
+        }
+        assert_eq!(
+            validate(&request("https://example.com/?k=100%")),
+            Err(InvalidRequestError::QueryParameter { name: "k".into() })
+        );
+    }
+}
@@ -5,6 +5,8 @@
 
 use crate::operation;
 use bytes::Bytes;
+use std::fmt;
+use std::sync::Arc;
 
 /// `ParseHttpResponse` is a generic trait for parsing structured data from HTTP responses.
 ///
@@ -18,6 +20,10 @@ use bytes::Bytes;
 ///
 /// It also enables this critical and core trait to avoid being async, and it makes code that uses
 /// the trait easier to test.
+///
+/// The trait is object safe: neither method is generic over the body, since unloaded responses
+/// carry a type-erased [`SdkBody`](crate::body::SdkBody). Use [`SharedResponseHandler`] to store
+/// a handler as a trait object.
 pub trait ParseHttpResponse {
     /// Output type of the HttpResponse.
     ///
@@ -81,11 +87,312 @@ impl<T: ParseStrictResponse> ParseHttpResponse for T {
     }
 }
 
+/// A type-erased, cheaply cloneable [`ParseHttpResponse`] implementation
+///
+/// This allows response handlers of different types (e.g. chosen at runtime) to be used
+/// interchangeably as the handler of an [`Operation`](operation::Operation).
+pub struct SharedResponseHandler<O>(Arc<dyn ParseHttpResponse<Output = O> + Send + Sync>);
+
+impl<O> SharedResponseHandler<O> {
+    /// Wraps `handler` so that it can be stored as a trait object
+    pub fn new(handler: impl ParseHttpResponse<Output = O> + Send + Sync + 'static) -> Self {
+        Self(Arc::new(handler))
+    }
+}
+
+impl<O> Clone for SharedResponseHandler<O> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<O> fmt::Debug for SharedResponseHandler<O> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SharedResponseHandler").finish()
+    }
+}
+
+impl<O> ParseHttpResponse for SharedResponseHandler<O> {
+    type Output = O;
+
+    fn parse_unloaded(&self, response: &mut operation::Response) -> Option<Self::Output> {
+        self.0.parse_unloaded(response)
+    }
+
+    fn parse_loaded(&self, response: &http::Response<Bytes>) -> Self::Output {
+        self.0.parse_loaded(response)
+    }
+}
+
+const DEFAULT_MAX_PREALLOCATION: u64 = 8 * 1024 * 1024;
+
+/// Limits applied when a response body is read into memory to be parsed.
+///
+/// Insert this into an operation's property bag (or configure it on the client) to cap how much
+/// memory a single response can use. Streaming response bodies that are handed directly to the
+/// caller are never read into memory, and so are not affected.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ResponseLimits {
+    max_body_size: Option<u64>,
+    max_preallocation: Option<u64>,
+    #[cfg(feature = "gzip")]
+    decompress_gzip: bool,
+}
+
+impl ResponseLimits {
+    /// Creates limits that allow response bodies of any size.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fail responses whose body is larger than `max_body_size` bytes.
+    pub fn with_max_body_size(mut self, max_body_size: u64) -> Self {
+        self.max_body_size = Some(max_body_size);
+        self
+    }
+
+    /// Pre-size the buffer a body is read into from its `Content-Length` only when it's at most
+    /// `max_preallocation` bytes.
+    ///
+    /// This guards against allocating a large buffer for a response that declares a length it
+    /// never sends. Defaults to 8 MiB.
+    pub fn with_max_preallocation(mut self, max_preallocation: u64) -> Self {
+        self.max_preallocation = Some(max_preallocation);
+        self
+    }
+
+    /// Decompress response bodies sent with `Content-Encoding: gzip`.
+    ///
+    /// The maximum body size also applies to the decompressed body, which protects against
+    /// bodies that expand to an excessive size.
+    #[cfg(feature = "gzip")]
+    pub fn with_gzip_decompression(mut self) -> Self {
+        self.decompress_gzip = true;
+        self
+    }
+
+    /// Returns the maximum body size, if one is set.
+    pub fn max_body_size(&self) -> Option<u64> {
+        self.max_body_size
+    }
+
+    /// Returns the largest `Content-Length` for which the body buffer is pre-sized.
+    pub fn max_preallocation(&self) -> u64 {
+        self.max_preallocation.unwrap_or(DEFAULT_MAX_PREALLOCATION)
+    }
+
+    /// Returns true if gzip-encoded response bodies will be decompressed.
+    #[cfg(feature = "gzip")]
+    pub fn decompress_gzip(&self) -> bool {
+        self.decompress_gzip
+    }
+}
+
+/// Runs tasks that would block an async runtime's worker thread
+///
+/// [`ParseOffload`] uses this to parse large responses. The task owns everything it needs, so it
+/// can be handed to a thread pool, e.g. tokio's `spawn_blocking` with [`TokioRunBlocking`], or a
+/// rayon pool. The task reports its result back to the async task that's waiting for it.
+pub trait RunBlocking: Send + Sync + fmt::Debug {
+    /// Runs `task`, on this thread or another one
+    ///
+    /// This may return before the task has completed. If the task can't be run, implementations
+    /// should drop it, which fails the parse that was waiting for it.
+    fn run_blocking(&self, task: Box<dyn FnOnce() + Send + 'static>);
+}
+
+/// Runs blocking tasks with tokio's `spawn_blocking`
+///
+/// Outside of a tokio runtime, tasks are run inline, since there's no blocking pool to run them
+/// on.
+#[cfg(feature = "rt-tokio")]
+#[non_exhaustive]
+#[derive(Clone, Debug, Default)]
+pub struct TokioRunBlocking;
+
+#[cfg(feature = "rt-tokio")]
+impl TokioRunBlocking {
+    /// Creates a new `TokioRunBlocking`
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[cfg(feature = "rt-tokio")]
+impl RunBlocking for TokioRunBlocking {
+    fn run_blocking(&self, task: Box<dyn FnOnce() + Send + 'static>) {
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                // Panics in the task drop its result, which fails the parse that's waiting for it
+                drop(handle.spawn_blocking(task));
+            }
+            Err(_) => task(),
+        }
+    }
+}
+
+/// Parse response bodies of at least a threshold size without stalling the async runtime
+///
+/// Parsing a multi-megabyte JSON or XML response can take long enough to stall the other tasks on
+/// the runtime. When a body that was read into memory is at least `threshold` bytes long, it's
+/// parsed with a [`RunBlocking`] instead of inline. Streaming response bodies are never parsed
+/// this way.
+///
+/// Insert this into an operation's property bag (or configure it on the client) to opt in.
+#[derive(Clone, Debug)]
+pub struct ParseOffload {
+    threshold: u64,
+    executor: Arc<dyn RunBlocking>,
+}
+
+impl ParseOffload {
+    /// Parses bodies of at least `threshold` bytes with `executor`
+    pub fn new(threshold: u64, executor: impl RunBlocking + 'static) -> Self {
+        Self {
+            threshold,
+            executor: Arc::new(executor),
+        }
+    }
+
+    /// Parses bodies of at least `threshold` bytes with tokio's `spawn_blocking`
+    #[cfg(feature = "rt-tokio")]
+    pub fn tokio(threshold: u64) -> Self {
+        Self::new(threshold, TokioRunBlocking::new())
+    }
+
+    /// Returns the body size at and above which responses are parsed with the executor
+    pub fn threshold(&self) -> u64 {
+        self.threshold
+    }
+
+    /// Returns true if a body of `len` bytes should be parsed with the executor
+    pub fn applies_to(&self, len: u64) -> bool {
+        len >= self.threshold
+    }
+
+    /// Runs `task` with the executor
+    pub fn run_blocking(&self, task: Box<dyn FnOnce() + Send + 'static>) {
+        self.executor.run_blocking(task)
+    }
+}
+
+type HookFn<T, E> = dyn Fn(Result<T, E>, &mut operation::Response) -> Result<T, E> + Send + Sync;
+
+/// A hook that runs after a response has been deserialized
+///
+/// Some services report failures in the body of a response that has a success status code, e.g.
+/// S3's `CopyObject` can fail after it has already sent a `200 OK`. Codegen customizations can
+/// store a `ParsedResponseHook` in the property bag of such operations. Once the response has
+/// been parsed, the hook receives the parsed result along with the raw response, and the result
+/// it returns replaces the parsed one. Returning an `Err` turns the response into an
+/// [`SdkError::ServiceError`](crate::result::SdkError::ServiceError). To change how that error is
+/// retried, the hook can insert a [`RetryClassification`](crate::retry::RetryClassification)
+/// into the response's property bag.
+///
+/// `T` and `E` must match the output and error types of the operation's response handler.
+pub struct ParsedResponseHook<T, E> {
+    hook: Arc<HookFn<T, E>>,
+}
+
+impl<T, E> ParsedResponseHook<T, E> {
+    /// Create a new `ParsedResponseHook` from a function of the parsed result and the raw response
+    pub fn new(
+        hook: impl Fn(Result<T, E>, &mut operation::Response) -> Result<T, E> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            hook: Arc::new(hook),
+        }
+    }
+
+    /// Runs the hook on the result that was parsed from `response`
+    pub fn apply(&self, parsed: Result<T, E>, response: &mut operation::Response) -> Result<T, E> {
+        (self.hook)(parsed, response)
+    }
+}
+
+impl<T, E> Clone for ParsedResponseHook<T, E> {
+    fn clone(&self) -> Self {
+        Self {
+            hook: self.hook.clone(),
+        }
+    }
+}
+
+impl<T, E> fmt::Debug for ParsedResponseHook<T, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ParsedResponseHook").finish()
+    }
+}
+
+/// Error returned when a response body exceeds [`ResponseLimits::max_body_size`].
+///
+/// This is returned as the `err` of an [`SdkError::ResponseError`](crate::result::SdkError::ResponseError).
+#[non_exhaustive]
+#[derive(Debug)]
+pub struct ResponseBodyTooLarge {
+    limit: u64,
+}
+
+impl ResponseBodyTooLarge {
+    pub(crate) fn new(limit: u64) -> Self {
+        Self { limit }
+    }
+
+    /// The maximum body size that was exceeded.
+    pub fn limit(&self) -> u64 {
+        self.limit
+    }
+}
+
+impl std::fmt::Display for ResponseBodyTooLarge {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "response body exceeded the maximum size of {} bytes",
+            self.limit
+        )
+    }
+}
+
+impl std::error::Error for ResponseBodyTooLarge {}
+
+/// Error returned when the response body stream fails before it was fully read.
+///
+/// This typically happens when the connection is reset (or an HTTP/2 stream is reset) after the
+/// response headers were received. It is returned as the `err` of an
+/// [`SdkError::ResponseError`](crate::result::SdkError::ResponseError), and may be retried for
+/// operations marked [`Idempotent`](crate::retry::Idempotent).
+#[non_exhaustive]
+#[derive(Debug)]
+pub struct IncompleteBody {
+    source: Box<dyn std::error::Error + Send + Sync>,
+}
+
+impl IncompleteBody {
+    /// Creates an `IncompleteBody` error caused by `source`.
+    pub fn new(source: Box<dyn std::error::Error + Send + Sync>) -> Self {
+        Self { source }
+    }
+}
+
+impl std::fmt::Display for IncompleteBody {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "the response body could not be read to completion")
+    }
+}
+
+impl std::error::Error for IncompleteBody {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(self.source.as_ref())
+    }
+}
+
 #[cfg(test)]
 mod test {
     use crate::body::SdkBody;
     use crate::operation;
-    use crate::response::ParseHttpResponse;
+    use crate::response::{ParseHttpResponse, ParseStrictResponse, SharedResponseHandler};
     use bytes::Bytes;
     use std::mem;
 
@@ -111,4 +418,51 @@ mod test {
             }
         }
     }
+
+    #[test]
+    fn handlers_can_be_shared_as_trait_objects() {
+        struct Status;
+
+        impl ParseStrictResponse for Status {
+            type Output = u16;
+
+            fn parse(&self, response: &http::Response<Bytes>) -> Self::Output {
+                response.status().as_u16()
+            }
+        }
+
+        struct BodyLength;
+
+        impl ParseHttpResponse for BodyLength {
+            type Output = u16;
+
+            fn parse_unloaded(&self, response: &mut operation::Response) -> Option<Self::Output> {
+                response
+                    .http()
+                    .body()
+                    .content_length()
+                    .map(|len| len as u16)
+            }
+
+            fn parse_loaded(&self, _response: &http::Response<Bytes>) -> Self::Output {
+                unreachable!("the length is always known")
+            }
+        }
+
+        let handlers = [
+            SharedResponseHandler::new(Status),
+            SharedResponseHandler::new(BodyLength),
+        ];
+        let mut response = operation::Response::new(http::Response::new(SdkBody::from("hello")));
+        let loaded = http::Response::new(Bytes::from_static(b"hello"));
+        let parsed: Vec<_> = handlers
+            .iter()
+            .map(|handler| {
+                handler
+                    .parse_unloaded(&mut response)
+                    .unwrap_or_else(|| handler.parse_loaded(&loaded))
+            })
+            .collect();
+        assert_eq!(vec![200, 5], parsed);
+    }
 }
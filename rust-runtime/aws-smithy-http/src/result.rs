@@ -12,11 +12,15 @@
 
 //! `Result` wrapper types for [success](SdkSuccess) and [failure](SdkError) responses.
 
+use crate::diagnostic::DiagnosticContext;
 use crate::operation;
+use crate::timing::TimingInfo;
 use aws_smithy_types::retry::ErrorKind;
 use std::error::Error;
 use std::fmt;
 use std::fmt::{Debug, Display, Formatter};
+use std::sync::Arc;
+use std::time::Duration;
 
 type BoxError = Box<dyn Error + Send + Sync>;
 
@@ -30,6 +34,29 @@ pub struct SdkSuccess<O> {
     pub parsed: O,
 }
 
+impl<O> SdkSuccess<O> {
+    /// Returns the raw response from the service.
+    ///
+    /// Use this to access response metadata that isn't modeled, such as the
+    /// [request ID](operation::Response::request_id) or [headers](operation::Response::headers).
+    pub fn raw(&self) -> &operation::Response {
+        &self.raw
+    }
+
+    /// Returns the ID the client assigned to this invocation of the operation, if any.
+    ///
+    /// The ID is the same for every attempt of an operation, so it can be used to find all of the
+    /// attempts that were made before this response was received.
+    pub fn invocation_id(&self) -> Option<operation::InvocationId> {
+        self.raw.invocation_id()
+    }
+
+    /// Returns how long each stage of the attempt that produced this response took.
+    pub fn timing_info(&self) -> Option<TimingInfo> {
+        self.raw.timing_info()
+    }
+}
+
 /// Failed SDK Result
 #[derive(Debug)]
 pub enum SdkError<E, R = operation::Response> {
@@ -61,6 +88,38 @@ pub enum SdkError<E, R = operation::Response> {
     },
 }
 
+/// Error from parsing a successful response that doesn't match the protocol
+///
+/// Generated response parsers wrap failures to parse a successful response's body (e.g. a JSON
+/// syntax error) in this error. When the error returned by a parser has a `ResponseParseError` in
+/// its [source](Error::source) chain, it's reported as an [`SdkError::ResponseError`] rather
+/// than as a service error, since the service didn't send an error.
+#[derive(Debug, Clone)]
+pub struct ResponseParseError {
+    err: Arc<dyn Error + Send + Sync>,
+}
+
+impl ResponseParseError {
+    /// Creates a new `ResponseParseError` from the error that failed parsing
+    pub fn new(err: impl Into<BoxError>) -> Self {
+        Self {
+            err: Arc::from(err.into()),
+        }
+    }
+}
+
+impl Display for ResponseParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "failed to parse the response: {}", self.err)
+    }
+}
+
+impl Error for ResponseParseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(self.err.as_ref())
+    }
+}
+
 /// Error from the underlying Connector
 ///
 /// Connector exists to attach a `ConnectorErrorKind` to what would otherwise be an opaque `Box<dyn Error>`
@@ -71,6 +130,8 @@ pub enum SdkError<E, R = operation::Response> {
 pub struct ConnectorError {
     err: BoxError,
     kind: ConnectorErrorKind,
+    invocation_id: Option<operation::InvocationId>,
+    diagnostic_context: Option<Box<DiagnosticContext>>,
 }
 
 impl Display for ConnectorError {
@@ -93,6 +154,8 @@ impl ConnectorError {
         Self {
             err,
             kind: ConnectorErrorKind::Timeout,
+            invocation_id: None,
+            diagnostic_context: None,
         }
     }
 
@@ -101,6 +164,8 @@ impl ConnectorError {
         Self {
             err,
             kind: ConnectorErrorKind::User,
+            invocation_id: None,
+            diagnostic_context: None,
         }
     }
 
@@ -109,6 +174,8 @@ impl ConnectorError {
         Self {
             err,
             kind: ConnectorErrorKind::Io,
+            invocation_id: None,
+            diagnostic_context: None,
         }
     }
 
@@ -119,6 +186,8 @@ impl ConnectorError {
         Self {
             err,
             kind: ConnectorErrorKind::Other(kind),
+            invocation_id: None,
+            diagnostic_context: None,
         }
     }
 
@@ -144,10 +213,110 @@ impl ConnectorError {
             _ => None,
         }
     }
+
+    /// Returns the kind of this error
+    ///
+    /// Retry classifiers match on the kind to decide whether a failed dispatch can be retried.
+    pub fn kind(&self) -> ConnectorErrorKind {
+        self.kind
+    }
+
+    /// Sets the ID of the operation invocation whose request failed to be dispatched
+    pub fn with_invocation_id(mut self, invocation_id: Option<operation::InvocationId>) -> Self {
+        self.invocation_id = invocation_id;
+        self
+    }
+
+    /// Returns the ID of the operation invocation whose request failed to be dispatched, if any
+    pub fn invocation_id(&self) -> Option<&operation::InvocationId> {
+        self.invocation_id.as_ref()
+    }
+
+    /// Sets what was done while sending the operation, up to and including the failed attempt
+    pub fn with_diagnostic_context(mut self, context: Option<DiagnosticContext>) -> Self {
+        self.diagnostic_context = context.map(Box::new);
+        self
+    }
+
+    /// Returns what was done while sending the operation, if it was recorded
+    pub fn diagnostic_context(&self) -> Option<&DiagnosticContext> {
+        self.diagnostic_context.as_deref()
+    }
 }
 
+/// Error from an operation, or one of its attempts, that didn't complete before its timeout
+///
+/// This is the source of the [`SdkError::TimeoutError`]s returned by the client's API call and
+/// API call attempt timeouts.
 #[derive(Debug)]
-enum ConnectorErrorKind {
+pub struct RequestTimeoutError {
+    kind: &'static str,
+    duration: Duration,
+    invocation_id: Option<operation::InvocationId>,
+    diagnostic_context: Option<DiagnosticContext>,
+}
+
+impl RequestTimeoutError {
+    /// Creates a new `RequestTimeoutError` for a `kind` of timeout that elapsed after `duration`
+    pub fn new(kind: &'static str, duration: Duration) -> Self {
+        Self {
+            kind,
+            duration,
+            invocation_id: None,
+            diagnostic_context: None,
+        }
+    }
+
+    /// Sets the ID of the operation invocation that timed out
+    pub fn with_invocation_id(mut self, invocation_id: Option<operation::InvocationId>) -> Self {
+        self.invocation_id = invocation_id;
+        self
+    }
+
+    /// Returns the kind of timeout that elapsed, e.g. "API call (single attempt)"
+    pub fn kind(&self) -> &'static str {
+        self.kind
+    }
+
+    /// Returns how long the timeout was
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// Returns the ID of the operation invocation that timed out, if any
+    pub fn invocation_id(&self) -> Option<&operation::InvocationId> {
+        self.invocation_id.as_ref()
+    }
+
+    /// Sets what was done while sending the operation before the timeout elapsed
+    pub fn with_diagnostic_context(mut self, context: Option<DiagnosticContext>) -> Self {
+        self.diagnostic_context = context;
+        self
+    }
+
+    /// Returns what was done while sending the operation before the timeout elapsed, if it was
+    /// recorded
+    pub fn diagnostic_context(&self) -> Option<&DiagnosticContext> {
+        self.diagnostic_context.as_ref()
+    }
+}
+
+impl Display for RequestTimeoutError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} timeout occurred after {:?}",
+            self.kind, self.duration
+        )
+    }
+}
+
+impl Error for RequestTimeoutError {}
+
+/// The kind of a [`ConnectorError`]
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConnectorErrorKind {
     /// A timeout occurred while processing the request
     Timeout,
 
@@ -173,6 +342,108 @@ impl Display for ConnectorErrorKind {
     }
 }
 
+impl<E, R> SdkError<E, R> {
+    /// Returns the [`BuildError`](operation::BuildError) if the request failed to be constructed
+    ///
+    /// This is `None` for construction failures that weren't caused by the operation's input,
+    /// such as a missing sleep implementation.
+    pub fn build_error(&self) -> Option<&operation::BuildError> {
+        match self {
+            SdkError::ConstructionFailure(err) => err.downcast_ref(),
+            _ => None,
+        }
+    }
+
+    /// Returns the connector error if the request failed during dispatch
+    ///
+    /// Use [`ConnectorError::kind`] to tell, for example, a timeout from an IO error.
+    pub fn dispatch_failure(&self) -> Option<&ConnectorError> {
+        match self {
+            SdkError::DispatchFailure(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl<E> SdkError<E, operation::Response> {
+    /// Returns the ID the client assigned to the failed invocation of the operation, if any.
+    ///
+    /// The ID is available once the request has been dispatched, i.e. for every error except
+    /// [`ConstructionFailure`](SdkError::ConstructionFailure). Timeouts only carry it when their
+    /// source is a [`RequestTimeoutError`].
+    pub fn invocation_id(&self) -> Option<operation::InvocationId> {
+        match self {
+            SdkError::ResponseError { raw, .. } | SdkError::ServiceError { raw, .. } => {
+                raw.invocation_id()
+            }
+            SdkError::DispatchFailure(err) => err.invocation_id().cloned(),
+            SdkError::TimeoutError(err) => err
+                .downcast_ref::<RequestTimeoutError>()
+                .and_then(|err| err.invocation_id().cloned()),
+            SdkError::ConstructionFailure(_) => None,
+        }
+    }
+
+    /// Returns how long each stage of the attempt that failed took.
+    ///
+    /// Like the [invocation ID](SdkError::invocation_id), this is only available when a response
+    /// was received.
+    pub fn timing_info(&self) -> Option<TimingInfo> {
+        match self {
+            SdkError::ResponseError { raw, .. } | SdkError::ServiceError { raw, .. } => {
+                raw.timing_info()
+            }
+            _ => None,
+        }
+    }
+}
+
+impl<E> SdkError<E, operation::Response>
+where
+    E: Error + 'static,
+{
+    /// Returns a report explaining why the operation failed.
+    ///
+    /// Along with the error and its causes, the report includes what's known about the request
+    /// (see [`DiagnosticContext`](crate::diagnostic::DiagnosticContext)): the outcome of every
+    /// attempt and how long the client waited between them, the endpoint, the region and service
+    /// the request was signed for, and where the credentials came from. For
+    /// [`DispatchFailure`](SdkError::DispatchFailure)s and [`TimeoutError`](SdkError::TimeoutError)s,
+    /// these come from the [`ConnectorError`] or [`RequestTimeoutError`] when the client
+    /// attached them. They aren't available for
+    /// [`ConstructionFailure`](SdkError::ConstructionFailure)s.
+    ///
+    /// The report is intended for humans, and its format may change.
+    pub fn diagnostic(&self) -> String {
+        use crate::diagnostic::Report;
+        let (summary, err, report): (_, &(dyn Error + 'static), _) = match self {
+            SdkError::ConstructionFailure(err) => {
+                ("Construction failure", err.as_ref(), Report::Unavailable)
+            }
+            SdkError::TimeoutError(err) => (
+                "Timeout",
+                err.as_ref(),
+                err.downcast_ref::<RequestTimeoutError>()
+                    .and_then(RequestTimeoutError::diagnostic_context)
+                    .map(Report::NoResponse)
+                    .unwrap_or(Report::Unavailable),
+            ),
+            SdkError::DispatchFailure(err) => (
+                "Dispatch failure",
+                err,
+                err.diagnostic_context()
+                    .map(Report::NoResponse)
+                    .unwrap_or(Report::Unavailable),
+            ),
+            SdkError::ResponseError { err, raw } => {
+                ("Response error", err.as_ref(), Report::Response(raw))
+            }
+            SdkError::ServiceError { err, raw } => ("Service error", err, Report::Response(raw)),
+        };
+        crate::diagnostic::render(summary, err, report)
+    }
+}
+
 impl<E, R> Display for SdkError<E, R>
 where
     E: Error,
@@ -204,3 +475,102 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::body::SdkBody;
+    use crate::operation;
+    use crate::result::{ConnectorError, ConnectorErrorKind, RequestTimeoutError, SdkError};
+    use aws_smithy_types::retry::ErrorKind;
+    use std::error::Error;
+    use std::fmt;
+    use std::time::Duration;
+
+    #[derive(Debug)]
+    struct TestError;
+
+    impl fmt::Display for TestError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "test error")
+        }
+    }
+
+    impl Error for TestError {}
+
+    #[test]
+    fn sdk_error_is_send_sync_error() {
+        fn assert_send_sync_error<T: Error + Send + Sync + 'static>() {}
+        assert_send_sync_error::<SdkError<TestError>>();
+    }
+
+    #[test]
+    fn sdk_error_converts_into_boxed_error() {
+        fn fails() -> Result<(), Box<dyn Error + Send + Sync>> {
+            Err(SdkError::ServiceError {
+                err: TestError,
+                raw: operation::Response::new(http::Response::new(SdkBody::from("body"))),
+            })?;
+            Ok(())
+        }
+        let err = fails().expect_err("should fail");
+        assert_eq!("test error", format!("{}", err));
+        let source = err.source().expect("service error is the source");
+        assert!(source.downcast_ref::<TestError>().is_some());
+    }
+
+    #[test]
+    fn dispatch_failure_exposes_the_connector_error_kind() {
+        let err: SdkError<TestError> =
+            SdkError::DispatchFailure(ConnectorError::io("reset".into()));
+        assert_eq!(
+            Some(ConnectorErrorKind::Io),
+            err.dispatch_failure().map(ConnectorError::kind)
+        );
+
+        let err: SdkError<TestError> = SdkError::DispatchFailure(ConnectorError::other(
+            "incomplete".into(),
+            Some(ErrorKind::TransientError),
+        ));
+        assert_eq!(
+            Some(ConnectorErrorKind::Other(Some(ErrorKind::TransientError))),
+            err.dispatch_failure().map(ConnectorError::kind)
+        );
+
+        let err: SdkError<TestError> = SdkError::TimeoutError("timeout".into());
+        assert!(err.dispatch_failure().is_none());
+    }
+
+    #[test]
+    fn construction_failure_exposes_the_build_error() {
+        let err: SdkError<TestError> =
+            SdkError::ConstructionFailure(Box::new(operation::BuildError::MissingField {
+                field: "bucket",
+                details: "bucket is required",
+            }));
+        assert_eq!(
+            Some("bucket"),
+            err.build_error().and_then(operation::BuildError::field)
+        );
+
+        let err: SdkError<TestError> = SdkError::ConstructionFailure("no sleep impl".into());
+        assert!(err.build_error().is_none());
+    }
+
+    #[test]
+    fn dispatch_failures_and_timeouts_expose_the_invocation_id() {
+        let invocation_id = operation::InvocationId::new("invocation-id");
+        let err: SdkError<TestError> = SdkError::DispatchFailure(
+            ConnectorError::io("reset".into()).with_invocation_id(Some(invocation_id.clone())),
+        );
+        assert_eq!(Some(invocation_id.clone()), err.invocation_id());
+
+        let err: SdkError<TestError> = SdkError::TimeoutError(Box::new(
+            RequestTimeoutError::new("API call", Duration::from_secs(1))
+                .with_invocation_id(Some(invocation_id.clone())),
+        ));
+        assert_eq!(Some(invocation_id), err.invocation_id());
+
+        let err: SdkError<TestError> = SdkError::TimeoutError("timeout".into());
+        assert_eq!(None, err.invocation_id());
+    }
+}
@@ -8,6 +8,7 @@
 //! For protocol agnostic retries, see `aws_smithy_types::Retry`.
 
 use aws_smithy_types::retry::RetryKind;
+use std::time::SystemTime;
 
 pub trait ClassifyResponse<T, E>: Clone {
     fn classify(&self, response: Result<&T, &E>) -> RetryKind;
@@ -18,3 +19,91 @@ impl<T, E> ClassifyResponse<T, E> for () {
         RetryKind::Unnecessary
     }
 }
+
+/// The number of attempts made for the request currently being dispatched, including the
+/// current attempt.
+///
+/// This is stored in the property bag of each [`operation::Request`](crate::operation::Request)
+/// before it is handed to the middleware, so the first attempt sees `1`, the first retry `2`,
+/// and so on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RequestAttempts(u32);
+
+impl RequestAttempts {
+    /// Create a new `RequestAttempts` for the given attempt number.
+    pub fn new(attempts: u32) -> Self {
+        Self(attempts)
+    }
+
+    /// The attempt number, starting at `1` for the initial attempt.
+    pub fn attempts(&self) -> u32 {
+        self.0
+    }
+}
+
+/// The limits the client places on the operation currently being dispatched.
+///
+/// This is stored in the property bag of each [`operation::Request`](crate::operation::Request)
+/// so that middleware can communicate them to the service, alongside [`RequestAttempts`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AttemptLimits {
+    max_attempts: Option<u32>,
+    deadline: Option<SystemTime>,
+}
+
+impl AttemptLimits {
+    /// Create a new `AttemptLimits` with no limits set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the maximum number of attempts the operation may make, including the initial attempt.
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = Some(max_attempts);
+        self
+    }
+
+    /// Set the time by which the operation must complete.
+    pub fn with_deadline(mut self, deadline: SystemTime) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// The maximum number of attempts the operation may make, if it is limited.
+    pub fn max_attempts(&self) -> Option<u32> {
+        self.max_attempts
+    }
+
+    /// The time by which the operation must complete, if it has a deadline.
+    pub fn deadline(&self) -> Option<SystemTime> {
+        self.deadline
+    }
+}
+
+/// Marks an operation as safe to retry after the service may have already processed it.
+///
+/// This is stored in the property bag of operations modeled as `@readonly` or `@idempotent`. Retry
+/// classifiers use it to decide whether failures that happen after the request was sent, like an
+/// [`IncompleteBody`](crate::response::IncompleteBody), can be retried.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Idempotent;
+
+/// The retry classification chosen for a response by a
+/// [`ParsedResponseHook`](crate::response::ParsedResponseHook)
+///
+/// Hooks insert this into the property bag of the raw response. Retry classifiers should prefer it
+/// over their default behavior.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RetryClassification(RetryKind);
+
+impl RetryClassification {
+    /// Create a new `RetryClassification` for the given retry kind
+    pub fn new(kind: RetryKind) -> Self {
+        Self(kind)
+    }
+
+    /// The retry kind chosen for the response
+    pub fn kind(&self) -> &RetryKind {
+        &self.0
+    }
+}
@@ -0,0 +1,27 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Test utilities for loading and parsing responses
+
+use crate::operation;
+use crate::response::ParseHttpResponse;
+use bytes::Bytes;
+use std::convert::Infallible;
+
+/// Response parser whose output is the loaded response body
+#[derive(Clone, Debug)]
+pub struct ParseBody;
+
+impl ParseHttpResponse for ParseBody {
+    type Output = Result<Bytes, Infallible>;
+
+    fn parse_unloaded(&self, _response: &mut operation::Response) -> Option<Self::Output> {
+        None
+    }
+
+    fn parse_loaded(&self, response: &http::Response<Bytes>) -> Self::Output {
+        Ok(response.body().clone())
+    }
+}
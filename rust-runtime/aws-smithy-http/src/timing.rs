@@ -0,0 +1,146 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Per-stage timing of request attempts, for performance debugging.
+//!
+//! Each attempt of an operation records a [`TimingInfo`] in the operation's property bag. Middleware
+//! stages add to it with [`TimingInfo::record`] as they run, and it's available from the response
+//! with [`SdkSuccess::timing_info`](crate::result::SdkSuccess::timing_info) and
+//! [`SdkError::timing_info`](crate::result::SdkError::timing_info).
+//!
+//! Connectors report how long it took to establish the connection a request was sent on by
+//! inserting a [`ConnectTiming`] into the response's extensions.
+
+use crate::property_bag::PropertyBag;
+use aws_smithy_async::time::Instant;
+use std::time::Duration;
+
+/// Time spent in each stage of a single request attempt
+///
+/// Stages that didn't run for an attempt (e.g. signing for an anonymous request) are `None`.
+#[non_exhaustive]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TimingInfo {
+    /// Time spent resolving credentials
+    pub credentials: Option<Duration>,
+    /// Time spent signing the request
+    pub signing: Option<Duration>,
+    /// Time spent establishing a new connection, or zero if an existing connection was reused
+    ///
+    /// This is `None` if the connector doesn't report [`ConnectTiming`].
+    pub connect: Option<Duration>,
+    /// Time from dispatching the request until the response headers were received
+    ///
+    /// This includes [`connect`](TimingInfo::connect).
+    pub time_to_first_byte: Option<Duration>,
+    /// Time spent reading and parsing the response body
+    ///
+    /// For operations with streaming output, only the time taken to parse the response headers is
+    /// included.
+    pub body_read: Option<Duration>,
+}
+
+impl TimingInfo {
+    /// Creates an empty `TimingInfo`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the `TimingInfo` for the current attempt from `properties`, adding it if necessary
+    pub fn record(properties: &mut PropertyBag) -> &mut TimingInfo {
+        if properties.get::<TimingInfo>().is_none() {
+            properties.insert(TimingInfo::new());
+        }
+        properties.get_mut::<TimingInfo>().expect("inserted above")
+    }
+
+    /// Returns the sum of the recorded stages
+    pub fn total(&self) -> Duration {
+        [
+            self.credentials,
+            self.signing,
+            self.time_to_first_byte,
+            self.body_read,
+        ]
+        .iter()
+        .flatten()
+        .sum()
+    }
+}
+
+/// How long it took to establish a connection
+///
+/// Connectors insert this into the extensions of every response received on the connection, so
+/// that the time can be attributed to the first request sent on it.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ConnectTiming {
+    /// When the connection was established
+    pub established_at: Instant,
+    /// How long it took to establish the connection, including any TLS handshake
+    pub duration: Duration,
+}
+
+impl ConnectTiming {
+    /// Creates a new `ConnectTiming`
+    pub fn new(established_at: Instant, duration: Duration) -> Self {
+        Self {
+            established_at,
+            duration,
+        }
+    }
+
+    /// Returns the time spent connecting on behalf of a request dispatched at `dispatched_at`
+    ///
+    /// Connections established before the request was dispatched were reused, so no time was
+    /// spent connecting.
+    pub fn connect_time_since(&self, dispatched_at: Instant) -> Duration {
+        if self.established_at >= dispatched_at {
+            self.duration
+        } else {
+            Duration::ZERO
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::property_bag::PropertyBag;
+    use crate::timing::{ConnectTiming, TimingInfo};
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn stages_record_into_the_same_timing_info() {
+        let mut properties = PropertyBag::new();
+        TimingInfo::record(&mut properties).credentials = Some(Duration::from_millis(5));
+        TimingInfo::record(&mut properties).signing = Some(Duration::from_millis(1));
+        TimingInfo::record(&mut properties).time_to_first_byte = Some(Duration::from_millis(20));
+
+        let timing = properties.get::<TimingInfo>().unwrap();
+        assert_eq!(timing.credentials, Some(Duration::from_millis(5)));
+        assert_eq!(timing.signing, Some(Duration::from_millis(1)));
+        assert_eq!(timing.body_read, None);
+        assert_eq!(timing.total(), Duration::from_millis(26));
+    }
+
+    #[test]
+    fn reused_connections_take_no_time() {
+        let dispatched_at = Instant::now();
+        let new = ConnectTiming::new(
+            dispatched_at + Duration::from_millis(30),
+            Duration::from_millis(30),
+        );
+        assert_eq!(
+            new.connect_time_since(dispatched_at),
+            Duration::from_millis(30)
+        );
+
+        let reused = ConnectTiming::new(
+            dispatched_at - Duration::from_secs(1),
+            Duration::from_millis(30),
+        );
+        assert_eq!(reused.connect_time_since(dispatched_at), Duration::ZERO);
+    }
+}
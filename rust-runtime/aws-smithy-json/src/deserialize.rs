@@ -4,11 +4,14 @@
  */
 
 use aws_smithy_types::Number;
+use memchr::memchr2;
+use std::iter::Peekable;
 
 mod error;
+pub mod stream;
 pub mod token;
 
-pub use error::{Error, ErrorReason};
+pub use error::{Error, ErrorReason, Position};
 pub use token::{EscapeError, EscapedStr, Offset, Token};
 
 use ErrorReason::*;
@@ -34,6 +37,18 @@ pub fn json_token_iter(input: &[u8]) -> JsonTokenIterator {
     }
 }
 
+/// Parses the JSON document `input` with `parse`
+///
+/// Errors returned by `parse` are given the line and column where they occurred in `input`
+/// (see [`Error::with_position`]).
+pub fn parse_document<'a, T>(
+    input: &'a [u8],
+    parse: impl FnOnce(&mut Peekable<JsonTokenIterator<'a>>) -> Result<T, Error>,
+) -> Result<T, Error> {
+    let mut tokens = json_token_iter(input).peekable();
+    parse(&mut tokens).map_err(|err| err.with_position(input))
+}
+
 /// Internal parser state for the iterator. Used to context between successive `next` calls.
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 enum State {
@@ -182,6 +197,7 @@ impl<'a> JsonTokenIterator<'a> {
         // Read bytes until a non-escaped end-quote, unescaping sequences as needed on the fly
         let start = self.index;
         loop {
+            self.skip_unescaped_run()?;
             match self.peek_expect()? {
                 b'"' => {
                     let value = std::str::from_utf8(&self.input[start..self.index])
@@ -205,6 +221,21 @@ impl<'a> JsonTokenIterator<'a> {
         }
     }
 
+    /// Advances to the next quote or backslash in a string, failing on unescaped control characters.
+    ///
+    /// The search uses [`memchr2`], which checks many bytes at a time with SIMD instructions where
+    /// they're available, so long strings aren't inspected one byte at a time.
+    fn skip_unescaped_run(&mut self) -> Result<(), Error> {
+        let remaining = &self.input[self.index..];
+        let run = memchr2(b'"', b'\\', remaining).unwrap_or(remaining.len());
+        if let Some(control) = remaining[..run].iter().position(|&byte| byte < 0x20) {
+            self.index += control;
+            return Err(self.error(UnexpectedControlCharacter(remaining[control])));
+        }
+        self.index += run;
+        Ok(())
+    }
+
     /// Expects the given literal to be next in the stream.
     fn expect_literal(&mut self, expected: &[u8]) -> Result<(), Error> {
         let (start, end) = (self.index, self.index + expected.len());
@@ -320,30 +351,36 @@ impl<'a> JsonTokenIterator<'a> {
         let number_str = unsafe { std::str::from_utf8_unchecked(number_slice) };
 
         use std::str::FromStr;
+        let parse_float = |number_str: &str| {
+            f64::from_str(number_str)
+                .ok()
+                .and_then(|f| must_be_finite(f).ok())
+                .map(Number::Float)
+                .ok_or_else(|| self.error_at(start, InvalidNumber))
+        };
+        // Like serde_json, integers that don't fit into 64 bits are parsed as floats,
+        // and so is `-0`, so that non-negative integers are always `PosInt`
         Ok(Token::ValueNumber {
             offset,
             value: if floating {
-                Number::Float(
-                    f64::from_str(number_str)
-                        .map_err(|_| self.error_at(start, InvalidNumber))
-                        .and_then(|f| {
-                            must_be_finite(f).map_err(|_| self.error_at(start, InvalidNumber))
-                        })?,
-                )
+                parse_float(number_str)?
             } else if negative {
-                // If the negative value overflows, then stuff it into an f64
-                let positive = u64::from_str(&number_str[1..])
-                    .map_err(|_| self.error_at(start, InvalidNumber))?;
-                let negative = positive.wrapping_neg() as i64;
-                if negative > 0 {
-                    Number::Float(-(positive as f64))
-                } else {
-                    Number::NegInt(negative as i64)
+                match u64::from_str(&number_str[1..]) {
+                    Ok(positive) => {
+                        let negative = positive.wrapping_neg() as i64;
+                        if negative >= 0 {
+                            Number::Float(-(positive as f64))
+                        } else {
+                            Number::NegInt(negative)
+                        }
+                    }
+                    Err(_) => parse_float(number_str)?,
                 }
             } else {
-                Number::PosInt(
-                    u64::from_str(number_str).map_err(|_| self.error_at(start, InvalidNumber))?,
-                )
+                match u64::from_str(number_str) {
+                    Ok(positive) => Number::PosInt(positive),
+                    Err(_) => parse_float(number_str)?,
+                }
             },
         })
     }
@@ -508,7 +545,10 @@ mod tests {
         end_array, end_object, object_key, start_array, start_object, value_bool, value_null,
         value_number, value_string,
     };
-    use crate::deserialize::{json_token_iter, Error, ErrorReason, EscapedStr, Token};
+    use crate::deserialize::token::{expect_number_or_null, expect_start_array};
+    use crate::deserialize::{
+        json_token_iter, parse_document, Error, ErrorReason, EscapedStr, Token,
+    };
     use aws_smithy_types::Number;
     use proptest::prelude::*;
 
@@ -609,6 +649,35 @@ mod tests {
             assert_eq!(value_number(0, Number::Float(input)), iter.next());
             assert_eq!(None, iter.next());
         }
+
+        #[test]
+        fn unsigned_integer_prop_test(input: u64) {
+            let json = serde_json::to_string(&input).unwrap();
+            let mut iter = json_token_iter(json.as_bytes());
+            assert_eq!(value_number(0, Number::PosInt(input)), iter.next());
+            assert_eq!(None, iter.next());
+        }
+
+        #[test]
+        fn number_matches_serde_json(input in "-?(0|[1-9][0-9]{0,24})(\\.[0-9]{1,5})?([eE][+-]?[0-9]{1,2})?") {
+            let expected = match serde_json::from_str::<serde_json::Number>(&input).unwrap() {
+                number if number.is_u64() => Number::PosInt(number.as_u64().unwrap()),
+                number if number.is_i64() => Number::NegInt(number.as_i64().unwrap()),
+                number => Number::Float(number.as_f64().unwrap()),
+            };
+            let actual = match json_token_iter(input.as_bytes()).next() {
+                Some(Ok(Token::ValueNumber { value, .. })) => value,
+                other => panic!("expected a number, got {:?}", other),
+            };
+            match (expected, actual) {
+                // serde_json's default float parsing isn't always correctly rounded
+                (Number::Float(expected), Number::Float(actual)) => {
+                    let tolerance = 2.0 * f64::EPSILON * expected.abs();
+                    assert!((expected - actual).abs() <= tolerance, "{} != {}", expected, actual);
+                }
+                (expected, actual) => assert_eq!(expected, actual),
+            }
+        }
     }
 
     #[test]
@@ -628,6 +697,29 @@ mod tests {
             Number::Float(-18446744073709551615.0),
             b"-18446744073709551615",
         );
+
+        // integers that don't fit into 64 bits become floats, like in serde_json
+        expect(Number::PosInt(u64::MAX), b"18446744073709551615");
+        expect(
+            Number::Float(18446744073709551616.0),
+            b"18446744073709551616",
+        );
+        expect(
+            Number::Float(-18446744073709551616.0),
+            b"-18446744073709551616",
+        );
+        expect(Number::NegInt(i64::MIN), b"-9223372036854775808");
+        expect(
+            Number::Float(-9223372036854775809.0),
+            b"-9223372036854775809",
+        );
+        assert!(matches!(
+            json_token_iter(b"-0").next(),
+            Some(Ok(Token::ValueNumber {
+                value: Number::Float(zero),
+                ..
+            })) if zero == 0.0 && zero.is_sign_negative()
+        ));
     }
 
     // These cases actually shouldn't parse according to the spec, but it's easier
@@ -791,6 +883,58 @@ mod tests {
         assert!(json_token_iter(b"\"test\ttest\"").next().unwrap().is_err());
     }
 
+    #[test]
+    fn long_strings() {
+        let long = "a".repeat(1000);
+        let json = format!("\"{}\\n{}\"", long, long);
+        let mut iter = json_token_iter(json.as_bytes());
+        assert_eq!(value_string(0, &json[1..(json.len() - 1)]), iter.next());
+        assert_eq!(None, iter.next());
+
+        // control characters are reported at their own offset, even far into a string
+        let json = format!("[\"{}\x01\"]", long);
+        let mut iter = json_token_iter(json.as_bytes());
+        assert_eq!(start_array(0), iter.next());
+        assert_eq!(
+            Some(Err(Error::new(
+                ErrorReason::UnexpectedControlCharacter(0x01),
+                Some(1002)
+            ))),
+            iter.next()
+        );
+    }
+
+    #[test]
+    fn parse_document_adds_positions() {
+        let json = b"[\n  1,\n  \"two\"\n]";
+        let err = parse_document(json, |tokens| {
+            expect_start_array(tokens.next())?;
+            let mut numbers = Vec::new();
+            while let Some(number) = expect_number_or_null(tokens.next())? {
+                numbers.push(number);
+            }
+            Ok(numbers)
+        })
+        .expect_err("not a number");
+        let position = err.position().expect("position");
+        assert_eq!((3, 3), (position.line(), position.column()));
+    }
+
+    #[test]
+    fn error_positions() {
+        let json = b"{\n  \"a\": 1,\n  \"b\": tru\n}";
+        let err = json_token_iter(json)
+            .find_map(Result::err)
+            .expect("invalid literal")
+            .with_position(json);
+        let position = err.position().expect("position");
+        assert_eq!((3, 8), (position.line(), position.column()));
+        assert_eq!(
+            "Error at line 3, column 8 (offset 19): expected literal: true",
+            err.to_string()
+        );
+    }
+
     #[test]
     fn escaped_str() {
         let escaped = EscapedStr::new("foo\\nbar");
@@ -4,6 +4,7 @@
  */
 
 use crate::escape::EscapeError;
+use aws_smithy_types::number::TryFromNumberError;
 use std::borrow::Cow;
 use std::fmt;
 use std::str::Utf8Error;
@@ -22,29 +23,97 @@ pub enum ErrorReason {
 }
 use ErrorReason::*;
 
+/// The line and column of a byte offset in a JSON document
+///
+/// Both are 1-based, and columns are counted in bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+    line: usize,
+    column: usize,
+}
+
+impl Position {
+    /// Locates `offset` in `input`
+    fn locate(input: &[u8], offset: usize) -> Self {
+        let before = &input[..offset.min(input.len())];
+        let line_start = before
+            .iter()
+            .rposition(|&byte| byte == b'\n')
+            .map(|index| index + 1)
+            .unwrap_or(0);
+        Position {
+            line: before.iter().filter(|&&byte| byte == b'\n').count() + 1,
+            column: offset - line_start + 1,
+        }
+    }
+
+    /// The line of the position, starting at 1
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// The column of the position in bytes, starting at 1
+    pub fn column(&self) -> usize {
+        self.column
+    }
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub struct Error {
     reason: ErrorReason,
     offset: Option<usize>,
+    position: Option<Position>,
 }
 
 impl Error {
     pub fn new(reason: ErrorReason, offset: Option<usize>) -> Self {
-        Error { reason, offset }
+        Error {
+            reason,
+            offset,
+            position: None,
+        }
     }
 
     /// Returns a custom error without an offset.
     pub fn custom(message: impl Into<Cow<'static, str>>) -> Error {
         Error::new(ErrorReason::Custom(message.into()), None)
     }
+
+    /// Returns the error with the line and column of its offset in `input`, the document that
+    /// was being parsed.
+    ///
+    /// Tokens only know their byte offset, so the line and column are computed when an error
+    /// leaves the parser rather than while tokenizing.
+    pub fn with_position(mut self, input: &[u8]) -> Self {
+        if let Some(offset) = self.offset {
+            self.position = Some(Position::locate(input, offset));
+        }
+        self
+    }
+
+    /// The byte offset in the document where the error occurred, if known
+    pub fn offset(&self) -> Option<usize> {
+        self.offset
+    }
+
+    /// The line and column in the document where the error occurred, if known
+    pub fn position(&self) -> Option<Position> {
+        self.position
+    }
 }
 
 impl std::error::Error for Error {}
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        if let Some(offset) = self.offset {
-            write!(f, "Error at offset {}: ", offset)?;
+        match (self.offset, self.position) {
+            (Some(offset), Some(position)) => write!(
+                f,
+                "Error at line {}, column {} (offset {}): ",
+                position.line, position.column, offset
+            )?,
+            (Some(offset), None) => write!(f, "Error at offset {}: ", offset)?,
+            _ => {}
         }
         match &self.reason {
             Custom(msg) => write!(f, "failed to parse JSON: {}", msg),
@@ -76,9 +145,42 @@ impl From<Utf8Error> for ErrorReason {
 
 impl From<EscapeError> for Error {
     fn from(err: EscapeError) -> Self {
-        Error {
-            reason: ErrorReason::UnescapeFailed(err),
-            offset: None,
-        }
+        Error::new(ErrorReason::UnescapeFailed(err), None)
+    }
+}
+
+impl From<TryFromNumberError> for Error {
+    fn from(err: TryFromNumberError) -> Self {
+        Error::custom(err.to_string())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Error, ErrorReason, Position};
+
+    #[test]
+    fn locate_position() {
+        let input = b"{\n  \"a\": 1,\n  \"b\": x\n}";
+        assert_eq!(Position { line: 1, column: 1 }, Position::locate(input, 0));
+        assert_eq!(Position { line: 2, column: 3 }, Position::locate(input, 4));
+        assert_eq!(Position { line: 3, column: 8 }, Position::locate(input, 19));
+        // the end of the input is a valid location for unexpected EOS errors
+        assert_eq!(Position { line: 4, column: 2 }, Position::locate(input, 22));
+    }
+
+    #[test]
+    fn display_position() {
+        let err = Error::new(ErrorReason::InvalidNumber, Some(4));
+        assert_eq!("Error at offset 4: invalid number", err.to_string());
+        let err = err.with_position(b"[1,\n-x]");
+        assert_eq!(Some(4), err.offset());
+        assert_eq!(
+            "Error at line 2, column 1 (offset 4): invalid number",
+            err.to_string()
+        );
+        let err = Error::custom("oops").with_position(b"{}");
+        assert_eq!(None, err.position());
+        assert_eq!("failed to parse JSON: oops", err.to_string());
     }
 }
@@ -0,0 +1,496 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Incremental parsing of a large list in a JSON document.
+//!
+//! Responses such as a DynamoDB `Scan` are dominated by a single list. [`JsonListStream`] is fed
+//! the document in chunks (e.g. as they arrive from a streaming response body) and yields the
+//! elements of that list one at a time, so that the whole document never has to be held in
+//! memory. Each element is yielded as the bytes of a complete JSON value, which can be parsed
+//! with [`json_token_iter`](crate::deserialize::json_token_iter) by the same code that parses list
+//! members of a fully loaded document.
+//!
+//! Everything outside of the list is collected into an _envelope_: the original document with the
+//! list's elements removed. Once the list has been consumed, the envelope can be parsed as usual
+//! to get the rest of the response's fields.
+//!
+//! ```rust
+//! use aws_smithy_json::deserialize::stream::{JsonListStream, ListEvent};
+//!
+//! let chunks: &[&[u8]] = &[br#"{"Count": 2, "Items": [{"id": 1}, {"#, br#""id": 2}]}"#];
+//! let mut stream = JsonListStream::new(&["Items"]);
+//! let mut items = Vec::new();
+//! let mut chunks = chunks.iter();
+//! loop {
+//!     match stream.next_item().unwrap() {
+//!         ListEvent::Item(item) => items.push(std::str::from_utf8(item).unwrap().to_string()),
+//!         ListEvent::NeedMoreData => match chunks.next() {
+//!             Some(chunk) => stream.feed(chunk),
+//!             None => stream.finish(),
+//!         },
+//!         ListEvent::End => break,
+//!     }
+//! }
+//! assert_eq!(vec![r#"{"id": 1}"#, r#"{"id": 2}"#], items);
+//! assert_eq!(br#"{"Count": 2, "Items": []}"#, stream.envelope());
+//! ```
+//!
+//! Only enough of the document is examined to find the boundaries of each element. Elements and
+//! the envelope are fully validated when they're parsed.
+
+use crate::deserialize::error::{Error, ErrorReason};
+use crate::deserialize::EscapedStr;
+
+/// The result of [`JsonListStream::next_item`]
+#[derive(Debug, PartialEq, Eq)]
+pub enum ListEvent<'a> {
+    /// The bytes of the next element of the list
+    Item(&'a [u8]),
+    /// More input is needed, either with [`JsonListStream::feed`] or by calling
+    /// [`JsonListStream::finish`] if there is none
+    NeedMoreData,
+    /// The whole document has been read, and [`JsonListStream::envelope`] is complete
+    End,
+}
+
+/// Pull parser that yields the elements of one list in a JSON document as it is read
+///
+/// See the [module documentation](crate::deserialize::stream) for an example.
+#[derive(Debug)]
+pub struct JsonListStream {
+    /// Object keys leading to the list, outermost first
+    path: Vec<String>,
+    /// Input that hasn't been consumed yet
+    buffer: Vec<u8>,
+    /// Offset of `buffer[0]` in the document
+    base: usize,
+    /// Index in `buffer` of the next byte to examine
+    pos: usize,
+    /// True once there is no more input
+    finished: bool,
+    /// Set while inside a string
+    string: Option<StringState>,
+    /// Arrays and objects enclosing the current position, outside of the list
+    frames: Vec<Frame>,
+    /// Set while inside the list
+    list: Option<ListState>,
+    /// The escaped key being read
+    key: Vec<u8>,
+    envelope: Vec<u8>,
+}
+
+#[derive(Debug)]
+struct StringState {
+    escaped: bool,
+    is_key: bool,
+}
+
+#[derive(Debug)]
+struct Frame {
+    is_object: bool,
+    /// The escaped key of the object's current field
+    key: Option<Vec<u8>>,
+    expect_key: bool,
+}
+
+#[derive(Debug)]
+struct ListState {
+    /// Nesting depth inside the current element
+    depth: usize,
+    /// Index in `buffer` where the current element starts
+    item_start: Option<usize>,
+}
+
+impl JsonListStream {
+    /// Creates a stream that yields the elements of the list found by following `path`
+    ///
+    /// `path` is the sequence of object keys from the root of the document to the list. If it is
+    /// empty, the document itself must be the list.
+    pub fn new(path: &[&str]) -> Self {
+        Self {
+            path: path.iter().map(|key| key.to_string()).collect(),
+            buffer: Vec::new(),
+            base: 0,
+            pos: 0,
+            finished: false,
+            string: None,
+            frames: Vec::new(),
+            list: None,
+            key: Vec::new(),
+            envelope: Vec::new(),
+        }
+    }
+
+    /// Adds the next chunk of the document
+    pub fn feed(&mut self, chunk: &[u8]) {
+        debug_assert!(!self.finished, "fed data after finish");
+        self.buffer.extend_from_slice(chunk);
+    }
+
+    /// Signals that the whole document has been fed
+    pub fn finish(&mut self) {
+        self.finished = true;
+    }
+
+    /// Returns the document without the list's elements
+    ///
+    /// This is only complete once [`next_item`](JsonListStream::next_item) has returned
+    /// [`ListEvent::End`]. If the list wasn't found, it's the whole document.
+    pub fn envelope(&self) -> &[u8] {
+        &self.envelope
+    }
+
+    /// Returns the next element of the list, or what's needed to find it
+    pub fn next_item(&mut self) -> Result<ListEvent<'_>, Error> {
+        self.compact();
+        while self.pos < self.buffer.len() {
+            let byte = self.buffer[self.pos];
+            let item = match self.list {
+                Some(_) => self.list_byte(byte)?,
+                None => {
+                    self.envelope_byte(byte)?;
+                    None
+                }
+            };
+            if let Some(item) = item {
+                return Ok(ListEvent::Item(&self.buffer[item]));
+            }
+        }
+        if !self.finished {
+            return Ok(ListEvent::NeedMoreData);
+        }
+        if self.string.is_some() || self.list.is_some() || !self.frames.is_empty() {
+            return Err(self.error(ErrorReason::UnexpectedEos));
+        }
+        Ok(ListEvent::End)
+    }
+
+    /// Drops input that has been consumed
+    fn compact(&mut self) {
+        let consumed = match &self.list {
+            Some(ListState {
+                item_start: Some(start),
+                ..
+            }) => *start,
+            _ => self.pos,
+        };
+        self.buffer.drain(..consumed);
+        self.base += consumed;
+        self.pos -= consumed;
+        if let Some(ListState {
+            item_start: Some(start),
+            ..
+        }) = &mut self.list
+        {
+            *start -= consumed;
+        }
+    }
+
+    fn error(&self, reason: ErrorReason) -> Error {
+        Error::new(reason, Some(self.base + self.pos))
+    }
+
+    /// Returns true if a list starting at the current position is the one being streamed
+    fn at_path(&self) -> bool {
+        self.frames.len() == self.path.len()
+            && self
+                .frames
+                .iter()
+                .zip(&self.path)
+                .all(|(frame, expected)| frame.is_object && key_matches(&frame.key, expected))
+    }
+
+    /// Examines a byte outside of the list, copying it into the envelope
+    fn envelope_byte(&mut self, byte: u8) -> Result<(), Error> {
+        self.envelope.push(byte);
+        if let Some(string) = &mut self.string {
+            let is_key = string.is_key;
+            if string.escaped {
+                string.escaped = false;
+            } else if byte == b'\\' {
+                string.escaped = true;
+            } else if byte == b'"' {
+                self.string = None;
+                if is_key {
+                    let key = std::mem::take(&mut self.key);
+                    if let Some(frame) = self.frames.last_mut() {
+                        frame.key = Some(key);
+                        frame.expect_key = false;
+                    }
+                }
+                self.pos += 1;
+                return Ok(());
+            }
+            if is_key {
+                self.key.push(byte);
+            }
+            self.pos += 1;
+            return Ok(());
+        }
+        match byte {
+            b'"' => {
+                let is_key = matches!(self.frames.last(), Some(frame) if frame.is_object && frame.expect_key);
+                self.string = Some(StringState {
+                    escaped: false,
+                    is_key,
+                });
+            }
+            b'{' => self.frames.push(Frame {
+                is_object: true,
+                key: None,
+                expect_key: true,
+            }),
+            b'[' if self.at_path() => {
+                self.list = Some(ListState {
+                    depth: 0,
+                    item_start: None,
+                })
+            }
+            b'[' => self.frames.push(Frame {
+                is_object: false,
+                key: None,
+                expect_key: false,
+            }),
+            b'}' | b']' => match self.frames.pop() {
+                Some(frame) if frame.is_object == (byte == b'}') => {}
+                _ => {
+                    return Err(self.error(ErrorReason::UnexpectedToken(
+                        byte.into(),
+                        "a value, or the end of the enclosing array or object",
+                    )))
+                }
+            },
+            b',' => {
+                if let Some(frame) = self.frames.last_mut() {
+                    frame.expect_key = frame.is_object;
+                }
+            }
+            _ => {}
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    /// Examines a byte inside the list, returning the range of an element once it is complete
+    fn list_byte(&mut self, byte: u8) -> Result<Option<std::ops::Range<usize>>, Error> {
+        let pos = self.pos;
+        let list = self.list.as_mut().expect("only called inside the list");
+        if let Some(string) = &mut self.string {
+            if string.escaped {
+                string.escaped = false;
+            } else if byte == b'\\' {
+                string.escaped = true;
+            } else if byte == b'"' {
+                self.string = None;
+            }
+            self.pos += 1;
+            return Ok(None);
+        }
+        if list.depth > 0 {
+            match byte {
+                b'"' => {
+                    self.string = Some(StringState {
+                        escaped: false,
+                        is_key: false,
+                    })
+                }
+                b'[' | b'{' => list.depth += 1,
+                b']' | b'}' => list.depth -= 1,
+                _ => {}
+            }
+            self.pos += 1;
+            return Ok(None);
+        }
+        match byte {
+            b' ' | b'\t' | b'\r' | b'\n' => {}
+            b',' => match list.item_start.take() {
+                Some(start) => {
+                    self.pos += 1;
+                    return Ok(Some(self.trim_end(start..pos)));
+                }
+                None => return Err(self.error(ErrorReason::UnexpectedToken(',', "a list element"))),
+            },
+            b']' => match list.item_start.take() {
+                // The end of the list is handled on the next call
+                Some(start) => return Ok(Some(self.trim_end(start..pos))),
+                None => {
+                    self.list = None;
+                    self.envelope.push(b']');
+                }
+            },
+            _ => {
+                list.item_start.get_or_insert(pos);
+                match byte {
+                    b'"' => {
+                        self.string = Some(StringState {
+                            escaped: false,
+                            is_key: false,
+                        })
+                    }
+                    b'[' | b'{' => list.depth += 1,
+                    _ => {}
+                }
+            }
+        }
+        self.pos += 1;
+        Ok(None)
+    }
+}
+
+impl JsonListStream {
+    /// Removes whitespace between the end of an element and the `,` or `]` that follows it
+    fn trim_end(&self, mut range: std::ops::Range<usize>) -> std::ops::Range<usize> {
+        while range.end > range.start
+            && matches!(self.buffer[range.end - 1], b' ' | b'\t' | b'\r' | b'\n')
+        {
+            range.end -= 1;
+        }
+        range
+    }
+}
+
+fn key_matches(key: &Option<Vec<u8>>, expected: &str) -> bool {
+    key.as_deref()
+        .and_then(|key| std::str::from_utf8(key).ok())
+        .and_then(|key| EscapedStr::new(key).to_unescaped().ok())
+        .map(|key| key == expected)
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::deserialize::error::{Error, ErrorReason};
+    use crate::deserialize::stream::{JsonListStream, ListEvent};
+    use crate::deserialize::{json_token_iter, Token};
+
+    /// Feeds `input` in chunks of `chunk_size`, returning the items and the envelope
+    fn stream(path: &[&str], input: &[u8], chunk_size: usize) -> (Vec<String>, String) {
+        let mut stream = JsonListStream::new(path);
+        let mut chunks = input.chunks(chunk_size);
+        let mut items = Vec::new();
+        loop {
+            match stream.next_item().expect("valid input") {
+                ListEvent::Item(item) => items.push(std::str::from_utf8(item).unwrap().to_string()),
+                ListEvent::NeedMoreData => match chunks.next() {
+                    Some(chunk) => stream.feed(chunk),
+                    None => stream.finish(),
+                },
+                ListEvent::End => break,
+            }
+        }
+        (
+            items,
+            String::from_utf8(stream.envelope().to_vec()).unwrap(),
+        )
+    }
+
+    const SCAN: &[u8] = br#"{
+        "Count": 3,
+        "Items": [
+            {"id": {"S": "a,]"}, "tags": {"L": [{"S": "x"}]}},
+            {"id": {"S": "b\"}"}},
+            {"id": {"S": "c"}}
+        ],
+        "LastEvaluatedKey": {"id": {"S": "c"}}
+    }"#;
+
+    #[test]
+    fn items_are_yielded_for_any_chunk_size() {
+        for chunk_size in 1..SCAN.len() + 1 {
+            let (items, envelope) = stream(&["Items"], SCAN, chunk_size);
+            assert_eq!(
+                vec![
+                    r#"{"id": {"S": "a,]"}, "tags": {"L": [{"S": "x"}]}}"#,
+                    r#"{"id": {"S": "b\"}"}}"#,
+                    r#"{"id": {"S": "c"}}"#,
+                ],
+                items,
+                "chunk size: {}",
+                chunk_size
+            );
+            assert!(envelope.contains(r#""Items": [],"#));
+            assert!(envelope.contains(r#""LastEvaluatedKey": {"id": {"S": "c"}}"#));
+        }
+    }
+
+    #[test]
+    fn items_and_envelope_are_valid_json() {
+        let (items, envelope) = stream(&["Items"], SCAN, 7);
+        for item in items
+            .iter()
+            .map(String::as_str)
+            .chain(Some(envelope.as_str()))
+        {
+            let tokens: Result<Vec<Token>, Error> = json_token_iter(item.as_bytes()).collect();
+            tokens.expect("valid JSON");
+        }
+    }
+
+    #[test]
+    fn nested_paths_and_escaped_keys() {
+        let input = br#"{"Items": 5, "Outer": {"Items": [1, "two", null, [3]]}}"#;
+        let (items, envelope) = stream(&["Outer", "Items"], input, 3);
+        assert_eq!(vec!["1", "\"two\"", "null", "[3]"], items);
+        assert_eq!(r#"{"Items": 5, "Outer": {"Items": []}}"#, envelope);
+    }
+
+    #[test]
+    fn the_document_can_be_the_list() {
+        let (items, envelope) = stream(&[], br#" [ {"a": []} , true ] "#, 4);
+        assert_eq!(vec![r#"{"a": []}"#, "true"], items);
+        assert_eq!(" [] ", envelope);
+    }
+
+    #[test]
+    fn lists_in_other_places_are_not_streamed() {
+        let input = br#"{"Other": [1, 2], "Nested": {"Items": [3]}, "Items": [[4]]}"#;
+        let (items, envelope) = stream(&["Items"], input, 5);
+        assert_eq!(vec!["[4]"], items);
+        assert_eq!(
+            r#"{"Other": [1, 2], "Nested": {"Items": [3]}, "Items": []}"#,
+            envelope
+        );
+    }
+
+    #[test]
+    fn missing_list_yields_the_whole_document() {
+        let (items, envelope) = stream(&["Items"], br#"{"Count": 0}"#, 2);
+        assert!(items.is_empty());
+        assert_eq!(r#"{"Count": 0}"#, envelope);
+    }
+
+    #[test]
+    fn truncated_documents_are_errors() {
+        let mut stream = JsonListStream::new(&["Items"]);
+        stream.feed(br#"{"Items": [1, {"a": "#);
+        stream.finish();
+        assert_eq!(Ok(ListEvent::Item(b"1")), stream.next_item());
+        assert_eq!(
+            Err(Error::new(ErrorReason::UnexpectedEos, Some(20))),
+            stream.next_item()
+        );
+    }
+
+    #[test]
+    fn empty_elements_are_errors() {
+        let mut stream = JsonListStream::new(&[]);
+        stream.feed(b"[1,,2]");
+        stream.finish();
+        assert_eq!(Ok(ListEvent::Item(b"1")), stream.next_item());
+        assert!(stream.next_item().is_err());
+    }
+
+    #[test]
+    fn consumed_input_is_released() {
+        let mut stream = JsonListStream::new(&[]);
+        stream.feed(b"[");
+        for _ in 0..1000 {
+            stream.feed(br#"{"a": 1},"#);
+            assert_eq!(Ok(ListEvent::Item(br#"{"a": 1}"#)), stream.next_item());
+        }
+        assert_eq!(Ok(ListEvent::NeedMoreData), stream.next_item());
+        assert!(stream.buffer.len() < 16, "{}", stream.buffer.len());
+    }
+}
@@ -143,7 +143,12 @@ macro_rules! expect_value_or_null_fn {
             match token.transpose()? {
                 Some(Token::ValueNull { .. }) => Ok(None),
                 Some(Token::$token { value, .. }) => Ok(Some(value)),
-                _ => Err(Error::custom(concat!(
+                Some(token) => Err(token.error(Cow::Borrowed(concat!(
+                    "expected ",
+                    stringify!($token),
+                    " or ValueNull"
+                )))),
+                None => Err(Error::custom(concat!(
                     "expected ",
                     stringify!($token),
                     " or ValueNull"
@@ -187,7 +192,10 @@ pub fn expect_number_or_null(
                     )
                 }),
         },
-        _ => Err(Error::custom(
+        Some(token) => Err(token.error(Cow::Borrowed(
+            "expected ValueString, ValueNumber, or ValueNull",
+        ))),
+        None => Err(Error::custom(
             "expected ValueString, ValueNumber, or ValueNull",
         )),
     }
@@ -522,9 +530,13 @@ pub mod test {
             Ok(Some(EscapedStr("test\\n"))),
             expect_string_or_null(value_string(0, "test\\n"))
         );
+        assert_eq!(
+            Err(Offset(3).error("expected ValueString or ValueNull".into())),
+            expect_string_or_null(value_bool(3, true))
+        );
         assert_eq!(
             Err(Error::custom("expected ValueString or ValueNull")),
-            expect_string_or_null(value_bool(0, true))
+            expect_string_or_null(None)
         );
     }
 
@@ -536,9 +548,7 @@ pub mod test {
             expect_number_or_null(value_number(0, Number::PosInt(5)))
         );
         assert_eq!(
-            Err(Error::custom(
-                "expected ValueString, ValueNumber, or ValueNull"
-            )),
+            Err(Offset(0).error("expected ValueString, ValueNumber, or ValueNull".into())),
             expect_number_or_null(value_bool(0, true))
         );
         assert_eq!(
@@ -567,7 +577,7 @@ pub mod test {
             expect_blob_or_null(value_string(0, "aGVsbG8h"))
         );
         assert_eq!(
-            Err(Error::custom("expected ValueString or ValueNull")),
+            Err(Offset(0).error("expected ValueString or ValueNull".into())),
             expect_blob_or_null(value_bool(0, true))
         );
     }
@@ -604,7 +614,7 @@ pub mod test {
             expect_timestamp_or_null(value_string(0, "wrong"), Format::EpochSeconds)
         );
         assert_eq!(
-            Err(Error::custom("expected ValueString or ValueNull")),
+            Err(Offset(0).error("expected ValueString or ValueNull".into())),
             expect_timestamp_or_null(value_number(0, Number::Float(0.0)), Format::DateTime)
         );
     }
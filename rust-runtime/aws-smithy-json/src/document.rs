@@ -0,0 +1,115 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Conversions between [`Document`] and JSON text.
+
+use crate::deserialize::token::expect_document;
+use crate::deserialize::{parse_document, Error};
+use crate::serialize::JsonValueWriter;
+use aws_smithy_types::Document;
+
+/// Parses the JSON value in `input` into a [`Document`].
+///
+/// Fails if `input` isn't valid JSON, or if it contains anything other than whitespace after
+/// the first value.
+pub fn from_json(input: &[u8]) -> Result<Document, Error> {
+    parse_document(input, |tokens| {
+        let document = expect_document(tokens)?;
+        match tokens.next() {
+            None => Ok(document),
+            Some(Ok(_)) => Err(Error::custom(
+                "found more JSON tokens after completing parsing",
+            )),
+            Some(Err(err)) => Err(err),
+        }
+    })
+}
+
+/// Writes `document` as compact JSON text.
+///
+/// Non-finite floats are written as the strings `"NaN"`, `"Infinity"` and `"-Infinity"`,
+/// so they are read back as [`Document::String`].
+pub fn to_json(document: &Document) -> String {
+    let mut output = String::new();
+    JsonValueWriter::new(&mut output).document(document);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{from_json, to_json};
+    use aws_smithy_types::{Document, Number};
+    use proptest::prelude::*;
+
+    #[test]
+    fn round_trip() {
+        let document = Document::Object(
+            vec![
+                ("null".to_string(), Document::Null),
+                ("bool".to_string(), Document::Bool(true)),
+                ("pos".to_string(), Document::Number(Number::PosInt(5))),
+                ("neg".to_string(), Document::Number(Number::NegInt(-5))),
+                ("float".to_string(), Document::Number(Number::Float(1.5))),
+                (
+                    "string".to_string(),
+                    Document::String("quote \" newline \n ✓".into()),
+                ),
+                (
+                    "array".to_string(),
+                    Document::Array(vec![
+                        Document::Array(Vec::new()),
+                        Document::Object(Default::default()),
+                    ]),
+                ),
+            ]
+            .into_iter()
+            .collect(),
+        );
+        assert_eq!(document, from_json(to_json(&document).as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn parse_ignores_surrounding_whitespace() {
+        assert_eq!(
+            Document::Array(vec![Document::Null]),
+            from_json(b" \n[ null ]\t").unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_rejects_trailing_values() {
+        assert!(from_json(b"null true").is_err());
+        assert!(from_json(b"{} }").is_err());
+    }
+
+    #[test]
+    fn parse_rejects_invalid_json() {
+        let err = from_json(b"{\"foo\":\n  nul}").expect_err("invalid JSON");
+        assert_eq!(2, err.position().unwrap().line());
+    }
+
+    fn arb_document() -> impl Strategy<Value = Document> {
+        let leaf = prop_oneof![
+            Just(Document::Null),
+            any::<bool>().prop_map(Document::Bool),
+            any::<u64>().prop_map(|v| Document::Number(Number::PosInt(v))),
+            (i64::MIN..0).prop_map(|v| Document::Number(Number::NegInt(v))),
+            any::<String>().prop_map(Document::String),
+        ];
+        leaf.prop_recursive(4, 32, 4, |inner| {
+            prop_oneof![
+                prop::collection::vec(inner.clone(), 0..4).prop_map(Document::Array),
+                prop::collection::hash_map(any::<String>(), inner, 0..4).prop_map(Document::Object),
+            ]
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn round_trips(document in arb_document()) {
+            prop_assert_eq!(&document, &from_json(to_json(&document).as_bytes()).unwrap());
+        }
+    }
+}
@@ -6,5 +6,6 @@
 //! JSON Abstractions for Smithy
 
 pub mod deserialize;
+pub mod document;
 mod escape;
 pub mod serialize;
@@ -11,6 +11,11 @@ use aws_smithy_types::{DateTime, Number};
 use std::borrow::Cow;
 use urlencoding::encode;
 
+/// Writes the form-URL-encoded body of an AWS Query request.
+///
+/// Nested members are written with dot-separated names. Lists are written as
+/// `Name.member.N` (or `Name.N` when flattened) and maps as `Name.entry.N.key`/`Name.entry.N.value`
+/// (or `Name.N.key`/`Name.N.value` when flattened), with `N` starting at `1`.
 pub struct QueryWriter<'a> {
     output: &'a mut String,
 }
@@ -303,6 +308,46 @@ mod tests {
         );
     }
 
+    #[test]
+    fn nested_collections() {
+        let mut out = String::new();
+        let mut writer = QueryWriter::new(&mut out, "SomeAction", "1.0");
+
+        let mut outer = writer.prefix("NestedList").start_list(false, None);
+        let mut inner = outer.entry().start_list(true, None);
+        inner.entry().string("a");
+        inner.entry().string("b");
+        inner.finish();
+        outer.finish();
+
+        let mut map = writer.prefix("MapOfLists").start_map(false, "key", "value");
+        let mut list = map.entry("foo").start_list(false, None);
+        list.entry().string("bar");
+        list.finish();
+        map.finish();
+
+        let mut list = writer.prefix("ListOfStructs").start_list(true, None);
+        let mut entry = list.entry();
+        entry.prefix("Name").string("first");
+        entry.prefix("Value").number(Number::PosInt(1));
+        list.finish();
+
+        writer.finish();
+
+        assert_eq!(
+            "Action=SomeAction\
+            &Version=1.0\
+            &NestedList.member.1.1=a\
+            &NestedList.member.1.2=b\
+            &MapOfLists.entry.1.key=foo\
+            &MapOfLists.entry.1.value.member.1=bar\
+            &ListOfStructs.1.Name=first\
+            &ListOfStructs.1.Value=1\
+            ",
+            out
+        );
+    }
+
     #[test]
     fn prefixes() {
         let mut out = String::new();
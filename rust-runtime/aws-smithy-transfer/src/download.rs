@@ -0,0 +1,624 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Ranged downloads
+//!
+//! A [`Downloader`] splits an object into ranges of [`part_size`](Downloader::with_part_size)
+//! bytes and fetches up to [`concurrency`](Downloader::with_concurrency) ranges at a time. Ranges
+//! are written out in order as soon as every range before them has arrived, so at most
+//! `concurrency` ranges are held in memory at once.
+//!
+//! The requests themselves are made by an implementation of [`DownloadParts`]. For S3, this maps to
+//! `HeadObject` to find the length and version of the object, and `GetObject` with a `Range` header
+//! to fetch each range, and is implemented by `ObjectDownload` in the `transfer` module of
+//! `aws-sdk-s3`.
+//!
+//! Every range is read from the same version of the object, identified by an [`ObjectVersion`].
+//! If the object is overwritten during the download, the remaining ranges fail instead of mixing
+//! the content of both versions.
+//!
+//! Each range is fetched with its own request, so a range that fails is retried on its own by the
+//! client's retry policy, with backoff. The download fails once a range has failed every attempt,
+//! or if a range returns the wrong number of bytes.
+//!
+//! The total length of the download is always verified. If the object's checksum is known, it
+//! can be verified as well with an [`ExpectedChecksum`].
+
+use crate::{BoxError, BoxFuture};
+use aws_smithy_http::byte_stream::ByteStream;
+use aws_smithy_http::callback::BodyCallback;
+use bytes::Bytes;
+use futures_util::stream::{self, Stream, StreamExt};
+use http::HeaderValue;
+use std::error::Error;
+use std::fmt;
+use std::ops::Range;
+use std::sync::Arc;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+const DEFAULT_PART_SIZE: u64 = 8 * 1024 * 1024;
+const DEFAULT_CONCURRENCY: usize = 8;
+
+/// The service-specific requests that make up a ranged download
+///
+/// Each method returns a `'static` future, so implementations typically hold a cheaply cloneable
+/// client and clone it into each request. Requests should be sent with
+/// [`Client::call`](https://docs.rs/aws-smithy-client), e.g. through a generated fluent builder, so
+/// that failed requests are retried according to the client's retry policy.
+pub trait DownloadParts: Send + Sync {
+    /// Looks up the current version of the object, which every range is then read from
+    fn object(&self) -> BoxFuture<ObjectVersion>;
+
+    /// Fetches the bytes in `range` (which is never empty) of the given version of the object
+    ///
+    /// The request must fail if the object no longer matches `object`. For S3, this means sending
+    /// the ETag in an `If-Match` header, and the version ID (if there is one) as `versionId`.
+    ///
+    /// Note that `range` excludes its end, while HTTP `Range` headers are inclusive: the header
+    /// for this range is `bytes={range.start}-{range.end - 1}`.
+    fn get_range(&self, object: &ObjectVersion, range: Range<u64>) -> BoxFuture<Bytes>;
+
+    /// Returns the checksum the downloaded object is expected to have, if it's known
+    ///
+    /// This is called once per download, after [`object`](DownloadParts::object).
+    fn checksum(&self) -> Option<ExpectedChecksum> {
+        None
+    }
+}
+
+/// The version of an object that a download reads every range from
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ObjectVersion {
+    content_length: u64,
+    e_tag: String,
+    version_id: Option<String>,
+}
+
+impl ObjectVersion {
+    /// Creates a version of an object that is `content_length` bytes long and has the given ETag
+    pub fn new(content_length: u64, e_tag: impl Into<String>) -> Self {
+        Self {
+            content_length,
+            e_tag: e_tag.into(),
+            version_id: None,
+        }
+    }
+
+    /// Sets the version ID of the object, for services that keep multiple versions of an object
+    pub fn with_version_id(mut self, version_id: impl Into<String>) -> Self {
+        self.version_id = Some(version_id.into());
+        self
+    }
+
+    /// The total length of the object, in bytes
+    pub fn content_length(&self) -> u64 {
+        self.content_length
+    }
+
+    /// The ETag of the object
+    pub fn e_tag(&self) -> &str {
+        &self.e_tag
+    }
+
+    /// The version ID of the object, if it has one
+    pub fn version_id(&self) -> Option<&str> {
+        self.version_id.as_deref()
+    }
+}
+
+/// A checksum that the downloaded object must match
+///
+/// `callback` is updated with the object's bytes, in order. When the download finishes, the
+/// value of the header returned from its [`trailers`](BodyCallback::trailers) must be equal to
+/// `expected`. The checksum callbacks in `aws-smithy-checksums` can be used here with the
+/// corresponding `x-amz-checksum-*` header from the object.
+///
+/// Checksums of objects that were uploaded in parts are usually computed over the checksums of
+/// each part, rather than over the whole object, and so can't be verified this way.
+pub struct ExpectedChecksum {
+    callback: Box<dyn BodyCallback>,
+    expected: HeaderValue,
+}
+
+impl ExpectedChecksum {
+    /// Creates a checksum that is computed by `callback` and must be equal to `expected`
+    pub fn new(callback: Box<dyn BodyCallback>, expected: HeaderValue) -> Self {
+        Self { callback, expected }
+    }
+
+    fn verify(&self) -> Result<(), DownloadError> {
+        let actual = self
+            .callback
+            .trailers()
+            .map_err(DownloadError::Checksum)?
+            .and_then(|trailers| trailers.values().next().cloned());
+        match actual {
+            Some(actual) if actual == self.expected => Ok(()),
+            actual => Err(DownloadError::Checksum(
+                format!(
+                    "expected checksum {:?} but the downloaded object's checksum was {:?}",
+                    self.expected, actual
+                )
+                .into(),
+            )),
+        }
+    }
+}
+
+impl fmt::Debug for ExpectedChecksum {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ExpectedChecksum")
+            .field("expected", &self.expected)
+            .finish()
+    }
+}
+
+/// Downloads objects in ranges, concurrently
+///
+/// See the [module documentation](crate::download) for details.
+#[derive(Clone, Debug)]
+pub struct Downloader {
+    part_size: u64,
+    concurrency: usize,
+}
+
+impl Default for Downloader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Downloader {
+    /// Creates a downloader that fetches 8 MiB ranges, 8 at a time
+    pub fn new() -> Self {
+        Self {
+            part_size: DEFAULT_PART_SIZE,
+            concurrency: DEFAULT_CONCURRENCY,
+        }
+    }
+
+    /// Sets the size of each range, in bytes. Every range but the last is exactly this size.
+    ///
+    /// # Panics
+    /// If `part_size` is zero.
+    pub fn with_part_size(mut self, part_size: u64) -> Self {
+        assert!(part_size > 0, "part size must be greater than zero");
+        self.part_size = part_size;
+        self
+    }
+
+    /// Sets the maximum number of ranges fetched at once
+    ///
+    /// # Panics
+    /// If `concurrency` is zero.
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        assert!(concurrency > 0, "concurrency must be greater than zero");
+        self.concurrency = concurrency;
+        self
+    }
+
+    /// Downloads the object from `target` into `writer`, returning the number of bytes written
+    ///
+    /// If the download fails, `writer` may have been partially written to.
+    pub async fn download<D, W>(&self, target: D, writer: &mut W) -> Result<u64, DownloadError>
+    where
+        D: DownloadParts + 'static,
+        W: AsyncWrite + Unpin,
+    {
+        let mut chunks = Box::pin(self.chunks(target).await?);
+        let mut written = 0;
+        while let Some(chunk) = chunks.next().await {
+            let chunk = chunk?;
+            writer
+                .write_all(&chunk)
+                .await
+                .map_err(DownloadError::Write)?;
+            written += chunk.len() as u64;
+        }
+        writer.flush().await.map_err(DownloadError::Write)?;
+        Ok(written)
+    }
+
+    /// Returns a [`ByteStream`] of the object from `target`
+    ///
+    /// The version of the object is looked up before this returns. Ranges are then fetched as the
+    /// stream is read. If a range fails, or the download can't be verified, the stream returns a
+    /// [`DownloadError`].
+    pub async fn download_stream<D>(&self, target: D) -> Result<ByteStream, DownloadError>
+    where
+        D: DownloadParts + 'static,
+    {
+        let chunks = self.chunks(target).await?;
+        Ok(ByteStream::from(hyper::Body::wrap_stream(chunks)))
+    }
+
+    /// Looks up the version of the object, and returns its verified chunks in order
+    async fn chunks<D>(
+        &self,
+        target: D,
+    ) -> Result<impl Stream<Item = Result<Bytes, DownloadError>> + Send + 'static, DownloadError>
+    where
+        D: DownloadParts + 'static,
+    {
+        let object = Arc::new(target.object().await.map_err(DownloadError::Object)?);
+        let content_length = object.content_length();
+        let verifier = Verifier {
+            content_length,
+            received: 0,
+            checksum: target.checksum(),
+        };
+
+        let target = Arc::new(target);
+        let part_size = self.part_size;
+        let ranges = (0..content_length)
+            .step_by(part_size as usize)
+            .map(move |start| start..content_length.min(start + part_size));
+        let parts = stream::iter(ranges)
+            .map(move |range| {
+                let (target, object) = (target.clone(), object.clone());
+                async move { get_range(target.as_ref(), &object, range).await }
+            })
+            .buffered(self.concurrency);
+
+        Ok(stream::unfold(
+            (Box::pin(parts), Some(verifier)),
+            |(mut parts, verifier)| async move {
+                let mut verifier = verifier?;
+                let item = match parts.next().await {
+                    Some(Ok(chunk)) => match verifier.update(&chunk) {
+                        Ok(()) => return Some((Ok(chunk), (parts, Some(verifier)))),
+                        Err(err) => err,
+                    },
+                    Some(Err(err)) => err,
+                    None => verifier.finish().err()?,
+                };
+                Some((Err(item), (parts, None)))
+            },
+        ))
+    }
+}
+
+/// Fetches `range` of `object`, failing if it doesn't return exactly the bytes in the range
+async fn get_range<D: DownloadParts>(
+    target: &D,
+    object: &ObjectVersion,
+    range: Range<u64>,
+) -> Result<Bytes, DownloadError> {
+    let expected_len = range.end - range.start;
+    let source: BoxError = match target.get_range(object, range.clone()).await {
+        Ok(chunk) if chunk.len() as u64 == expected_len => return Ok(chunk),
+        Ok(chunk) => format!(
+            "expected {} bytes but received {}",
+            expected_len,
+            chunk.len()
+        )
+        .into(),
+        Err(err) => err,
+    };
+    Err(DownloadError::Part { range, source })
+}
+
+/// Checks the total length and checksum of the chunks of a download
+struct Verifier {
+    content_length: u64,
+    received: u64,
+    checksum: Option<ExpectedChecksum>,
+}
+
+impl Verifier {
+    fn update(&mut self, chunk: &[u8]) -> Result<(), DownloadError> {
+        self.received += chunk.len() as u64;
+        if let Some(checksum) = &mut self.checksum {
+            checksum
+                .callback
+                .update(chunk)
+                .map_err(DownloadError::Checksum)?;
+        }
+        Ok(())
+    }
+
+    fn finish(&self) -> Result<(), DownloadError> {
+        if self.received != self.content_length {
+            return Err(DownloadError::Length {
+                expected: self.content_length,
+                received: self.received,
+            });
+        }
+        match &self.checksum {
+            Some(checksum) => checksum.verify(),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Error returned when a ranged download fails
+#[non_exhaustive]
+#[derive(Debug)]
+pub enum DownloadError {
+    /// The version of the object couldn't be looked up
+    Object(BoxError),
+
+    /// A range failed to download
+    Part {
+        /// The range that failed, excluding its end
+        range: Range<u64>,
+        /// The error returned for the range
+        source: BoxError,
+    },
+
+    /// The downloaded object didn't have the expected length
+    Length {
+        /// The length of the object
+        expected: u64,
+        /// The number of bytes that were downloaded
+        received: u64,
+    },
+
+    /// The downloaded object didn't match its expected checksum
+    Checksum(BoxError),
+
+    /// The downloaded object couldn't be written out
+    Write(std::io::Error),
+}
+
+impl fmt::Display for DownloadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DownloadError::Object(_) => write!(f, "failed to look up the object"),
+            DownloadError::Part { range, .. } => write!(
+                f,
+                "failed to download bytes {}-{}",
+                range.start,
+                range.end - 1
+            ),
+            DownloadError::Length { expected, received } => {
+                write!(f, "expected {} bytes but downloaded {}", expected, received)
+            }
+            DownloadError::Checksum(_) => write!(f, "failed to verify the object's checksum"),
+            DownloadError::Write(_) => write!(f, "failed to write the downloaded object"),
+        }
+    }
+}
+
+impl Error for DownloadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            DownloadError::Object(source)
+            | DownloadError::Part { source, .. }
+            | DownloadError::Checksum(source) => Some(source.as_ref()),
+            DownloadError::Write(source) => Some(source),
+            DownloadError::Length { .. } => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::download::{
+        DownloadError, DownloadParts, Downloader, ExpectedChecksum, ObjectVersion,
+    };
+    use crate::test_util::{data, FakeService};
+    use crate::{BoxError, BoxFuture};
+    use aws_smithy_http::callback::BodyCallback;
+    use bytes::Bytes;
+    use http::{HeaderMap, HeaderValue};
+    use std::error::Error;
+    use std::ops::Range;
+    use std::sync::{Arc, Mutex};
+
+    /// Serves ranges of an object held in memory. Requests are keyed by the start of their range.
+    #[derive(Default)]
+    struct InMemory {
+        service: FakeService,
+        /// The ETag and content of the current version of the object
+        object: Mutex<(String, Bytes)>,
+        /// Truncate every range to at most this many bytes
+        truncate: Option<usize>,
+        checksum: Option<String>,
+    }
+
+    impl InMemory {
+        fn with_data(data: Vec<u8>) -> Self {
+            Self {
+                object: Mutex::new(("etag-1".to_string(), data.into())),
+                ..Default::default()
+            }
+        }
+    }
+
+    #[derive(Clone, Default)]
+    struct Target(Arc<InMemory>);
+
+    impl Target {
+        fn new(data: Vec<u8>) -> Self {
+            Self(Arc::new(InMemory::with_data(data)))
+        }
+
+        /// Replaces the object with a new version
+        fn overwrite(&self, e_tag: &str, data: Vec<u8>) {
+            *self.0.object.lock().unwrap() = (e_tag.to_string(), data.into());
+        }
+    }
+
+    impl DownloadParts for Target {
+        fn object(&self) -> BoxFuture<ObjectVersion> {
+            let (e_tag, data) = self.0.object.lock().unwrap().clone();
+            Box::pin(async move { Ok(ObjectVersion::new(data.len() as u64, e_tag)) })
+        }
+
+        fn get_range(&self, object: &ObjectVersion, range: Range<u64>) -> BoxFuture<Bytes> {
+            let state = self.0.clone();
+            let e_tag = object.e_tag().to_string();
+            Box::pin(async move {
+                state.service.request(range.start).await?;
+                let (current_e_tag, data) = state.object.lock().unwrap().clone();
+                if e_tag != current_e_tag {
+                    return Err("precondition failed: the ETag doesn't match".into());
+                }
+                let mut chunk = data.slice(range.start as usize..range.end as usize);
+                if let Some(truncate) = state.truncate {
+                    chunk.truncate(truncate);
+                }
+                Ok(chunk)
+            })
+        }
+
+        fn checksum(&self) -> Option<ExpectedChecksum> {
+            self.0.checksum.as_ref().map(|expected| {
+                ExpectedChecksum::new(
+                    Box::new(SumCallback::default()),
+                    HeaderValue::from_str(expected).unwrap(),
+                )
+            })
+        }
+    }
+
+    /// Sums every byte of the body
+    #[derive(Default)]
+    struct SumCallback(u64);
+
+    impl BodyCallback for SumCallback {
+        fn update(&mut self, bytes: &[u8]) -> Result<(), BoxError> {
+            self.0 += bytes.iter().map(|b| *b as u64).sum::<u64>();
+            Ok(())
+        }
+
+        fn trailers(&self) -> Result<Option<HeaderMap<HeaderValue>>, BoxError> {
+            let mut trailers = HeaderMap::new();
+            trailers.insert("x-sum", HeaderValue::from(self.0));
+            Ok(Some(trailers))
+        }
+
+        fn make_new(&self) -> Box<dyn BodyCallback> {
+            Box::new(SumCallback::default())
+        }
+    }
+
+    #[tokio::test]
+    async fn downloads_ranges_concurrently_in_order() {
+        let target = Target::new(data(10_500));
+        let mut output = Vec::new();
+        let written = Downloader::new()
+            .with_part_size(1000)
+            .with_concurrency(4)
+            .download(target.clone(), &mut output)
+            .await
+            .expect("success");
+        assert_eq!(10_500, written);
+        assert_eq!(data(10_500), output);
+        let max_in_flight = target.0.service.max_in_flight();
+        assert!(
+            max_in_flight > 1 && max_in_flight <= 4,
+            "{} ranges in flight",
+            max_in_flight
+        );
+    }
+
+    #[tokio::test]
+    async fn downloads_into_a_byte_stream() {
+        let stream = Downloader::new()
+            .with_part_size(1000)
+            .download_stream(Target::new(data(2500)))
+            .await
+            .expect("content length is available");
+        let output = stream.collect().await.expect("success").into_bytes();
+        assert_eq!(data(2500), output);
+
+        let stream = Downloader::new()
+            .download_stream(Target::new(Vec::new()))
+            .await
+            .expect("content length is available");
+        assert!(stream
+            .collect()
+            .await
+            .expect("success")
+            .into_bytes()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn failed_ranges_fail_the_download() {
+        let target = Target::new(data(3000));
+        target.0.service.fail(1000, 1);
+        let err = Downloader::new()
+            .with_part_size(1000)
+            .download(target, &mut Vec::new())
+            .await
+            .expect_err("the second range fails");
+        assert!(
+            matches!(&err, DownloadError::Part { range, .. } if range == &(1000..2000)),
+            "{:?}",
+            err
+        );
+    }
+
+    #[tokio::test]
+    async fn overwriting_the_object_fails_the_download() {
+        let target = Target::new(data(3000));
+        let stream = Downloader::new()
+            .with_part_size(1000)
+            .download_stream(target.clone())
+            .await
+            .expect("the object exists");
+        target.overwrite("etag-2", vec![0; 3000]);
+
+        let err = stream
+            .collect()
+            .await
+            .expect_err("ranges of the new version aren't mixed in");
+        let err =
+            std::iter::successors(Some(&err as &(dyn Error + 'static)), |err| (*err).source())
+                .find_map(|err| err.downcast_ref::<DownloadError>())
+                .expect("the download error is the source");
+        assert!(
+            matches!(err, DownloadError::Part { range, .. } if range == &(0..1000)),
+            "{:?}",
+            err
+        );
+    }
+
+    #[tokio::test]
+    async fn short_ranges_fail_the_download() {
+        let target = Target(Arc::new(InMemory {
+            truncate: Some(999),
+            ..InMemory::with_data(data(3000))
+        }));
+        let err = Downloader::new()
+            .with_part_size(1000)
+            .download(target, &mut Vec::new())
+            .await
+            .expect_err("every range is short");
+        assert!(
+            matches!(&err, DownloadError::Part { range, .. } if range == &(0..1000)),
+            "{:?}",
+            err
+        );
+    }
+
+    #[tokio::test]
+    async fn checksums_are_verified() {
+        let sum = data(3000).iter().map(|b| *b as u64).sum::<u64>();
+        let target = Target(Arc::new(InMemory {
+            checksum: Some(sum.to_string()),
+            ..InMemory::with_data(data(3000))
+        }));
+        Downloader::new()
+            .with_part_size(1000)
+            .download(target, &mut Vec::new())
+            .await
+            .expect("checksum matches");
+
+        let target = Target(Arc::new(InMemory {
+            checksum: Some("1234".to_string()),
+            ..InMemory::with_data(data(3000))
+        }));
+        let err = Downloader::new()
+            .with_part_size(1000)
+            .download(target, &mut Vec::new())
+            .await
+            .expect_err("checksum doesn't match");
+        assert!(matches!(err, DownloadError::Checksum(_)), "{:?}", err);
+    }
+}
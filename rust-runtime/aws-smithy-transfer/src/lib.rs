@@ -0,0 +1,38 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+#![warn(
+    missing_debug_implementations,
+    missing_docs,
+    rustdoc::all,
+    rust_2018_idioms
+)]
+
+//! Concurrent multipart transfers for smithy-rs clients.
+//!
+//! Payloads that are too large to send in a single request (e.g. S3 objects larger than 5 GB) are
+//! split into parts that are uploaded concurrently, and large objects are downloaded as concurrent
+//! ranged requests. The service-specific requests are supplied by implementing a trait
+//! ([`UploadParts`](upload::UploadParts) or [`DownloadParts`](download::DownloadParts)) that sends
+//! the generated operations with [`Client::call`](https://docs.rs/aws-smithy-client). Each part is
+//! its own request, so the client's retry policy retries a failed part, with backoff, without
+//! restarting the transfer.
+//!
+//! The AWS SDK for S3 implements both traits in its `transfer` module.
+
+use std::future::Future;
+use std::pin::Pin;
+
+pub mod download;
+pub mod upload;
+
+#[cfg(test)]
+mod test_util;
+
+/// A boxed error that is `Send` and `Sync`
+pub type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// A boxed future returned by the service-specific operations of a transfer
+pub type BoxFuture<T> = Pin<Box<dyn Future<Output = Result<T, BoxError>> + Send>>;
@@ -0,0 +1,55 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Fakes shared by the upload and download tests
+
+use crate::BoxError;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+/// A fake service that records how many requests are in flight at once, and fails requests on
+/// demand
+///
+/// Requests are identified by a key, such as a part number or the start of a range.
+#[derive(Debug, Default)]
+pub(crate) struct FakeService {
+    /// Number of times the requests for each key should fail before succeeding
+    failures: Mutex<HashMap<u64, u32>>,
+    in_flight: AtomicUsize,
+    max_in_flight: AtomicUsize,
+}
+
+impl FakeService {
+    /// Fails the next `times` requests for `key`
+    pub(crate) fn fail(&self, key: u64, times: u32) {
+        self.failures.lock().unwrap().insert(key, times);
+    }
+
+    /// Handles a request for `key`, yielding once while it's in flight
+    pub(crate) async fn request(&self, key: u64) -> Result<(), BoxError> {
+        let in_flight = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+        self.max_in_flight.fetch_max(in_flight, Ordering::SeqCst);
+        tokio::task::yield_now().await;
+        self.in_flight.fetch_sub(1, Ordering::SeqCst);
+        if let Some(failures) = self.failures.lock().unwrap().get_mut(&key) {
+            if *failures > 0 {
+                *failures -= 1;
+                return Err(format!("request {} failed", key).into());
+            }
+        }
+        Ok(())
+    }
+
+    /// The largest number of requests that were in flight at once
+    pub(crate) fn max_in_flight(&self) -> usize {
+        self.max_in_flight.load(Ordering::SeqCst)
+    }
+}
+
+/// Returns `len` bytes of test data
+pub(crate) fn data(len: usize) -> Vec<u8> {
+    (0..len).map(|i| i as u8).collect()
+}
@@ -0,0 +1,381 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Multipart uploads
+//!
+//! An [`Uploader`] splits a body into parts of [`part_size`](Uploader::with_part_size) bytes and
+//! uploads up to [`concurrency`](Uploader::with_concurrency) parts at a time. Once every part is
+//! uploaded, the upload is completed. If the upload can't be completed, it is aborted so that the
+//! service can discard the parts that were uploaded.
+//!
+//! The requests themselves are made by an implementation of [`UploadParts`]. For S3, this maps to
+//! the `CreateMultipartUpload`, `UploadPart`, `CompleteMultipartUpload` and `AbortMultipartUpload`
+//! operations, and is implemented by `ObjectUpload` in the `transfer` module of `aws-sdk-s3`.
+//!
+//! Each part is uploaded with its own request, so a part that fails is retried on its own by the
+//! client's retry policy, with backoff, without restarting the upload. The upload is aborted once
+//! a part has failed every attempt.
+//!
+//! At most `concurrency` parts are held in memory at once, so the memory used by an upload is
+//! bounded by `part_size * concurrency`.
+
+use crate::{BoxError, BoxFuture};
+use aws_smithy_http::byte_stream::ByteStream;
+use bytes::{Bytes, BytesMut};
+use futures_util::stream::{self, StreamExt, TryStreamExt};
+use std::error::Error;
+use std::fmt;
+
+const DEFAULT_PART_SIZE: usize = 8 * 1024 * 1024;
+const DEFAULT_CONCURRENCY: usize = 8;
+
+/// The service-specific requests that make up a multipart upload
+///
+/// Each method returns a `'static` future, so implementations typically hold a cheaply cloneable
+/// client and clone it into each request. Requests should be sent with
+/// [`Client::call`](https://docs.rs/aws-smithy-client), e.g. through a generated fluent builder, so
+/// that failed requests are retried according to the client's retry policy.
+pub trait UploadParts: Send + Sync {
+    /// Identifies the upload across requests
+    type UploadId: Send + Sync;
+
+    /// The result of uploading one part, passed back to [`complete`](UploadParts::complete)
+    type Part: Send;
+
+    /// The result of completing the upload
+    type Output;
+
+    /// Starts a new multipart upload
+    fn create(&self) -> BoxFuture<Self::UploadId>;
+
+    /// Uploads one part of the body. Part numbers start at 1.
+    fn upload_part(
+        &self,
+        upload_id: &Self::UploadId,
+        part_number: u32,
+        body: Bytes,
+    ) -> BoxFuture<Self::Part>;
+
+    /// Completes the upload. `parts` are ordered by part number.
+    fn complete(
+        &self,
+        upload_id: &Self::UploadId,
+        parts: Vec<Self::Part>,
+    ) -> BoxFuture<Self::Output>;
+
+    /// Aborts the upload, discarding any parts that were uploaded
+    fn abort(&self, upload_id: &Self::UploadId) -> BoxFuture<()>;
+}
+
+/// Uploads bodies in parts, concurrently
+///
+/// See the [module documentation](crate::upload) for details.
+#[derive(Clone, Debug)]
+pub struct Uploader {
+    part_size: usize,
+    concurrency: usize,
+}
+
+impl Default for Uploader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Uploader {
+    /// Creates an uploader that uploads 8 MiB parts, 8 at a time
+    pub fn new() -> Self {
+        Self {
+            part_size: DEFAULT_PART_SIZE,
+            concurrency: DEFAULT_CONCURRENCY,
+        }
+    }
+
+    /// Sets the size of each part, in bytes. Every part but the last is exactly this size.
+    ///
+    /// Services may require a minimum part size (5 MiB for S3).
+    ///
+    /// # Panics
+    /// If `part_size` is zero.
+    pub fn with_part_size(mut self, part_size: usize) -> Self {
+        assert!(part_size > 0, "part size must be greater than zero");
+        self.part_size = part_size;
+        self
+    }
+
+    /// Sets the maximum number of parts uploaded at once
+    ///
+    /// # Panics
+    /// If `concurrency` is zero.
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        assert!(concurrency > 0, "concurrency must be greater than zero");
+        self.concurrency = concurrency;
+        self
+    }
+
+    /// Uploads `body` with `target`, aborting the upload if it fails
+    pub async fn upload<U: UploadParts>(
+        &self,
+        target: &U,
+        body: ByteStream,
+    ) -> Result<U::Output, UploadError> {
+        let upload_id = target.create().await.map_err(UploadError::Create)?;
+        let result = match self.upload_parts(target, &upload_id, body).await {
+            Ok(parts) => target
+                .complete(&upload_id, parts)
+                .await
+                .map_err(UploadError::Complete),
+            Err(err) => Err(err),
+        };
+        if result.is_err() {
+            if let Err(err) = target.abort(&upload_id).await {
+                tracing::warn!(err = %err, "failed to abort multipart upload");
+            }
+        }
+        result
+    }
+
+    async fn upload_parts<U: UploadParts>(
+        &self,
+        target: &U,
+        upload_id: &U::UploadId,
+        body: ByteStream,
+    ) -> Result<Vec<U::Part>, UploadError> {
+        let mut parts: Vec<(u32, U::Part)> = read_parts(body, self.part_size)
+            .map(|part| async move {
+                let (part_number, body) = part.map_err(UploadError::Body)?;
+                let part = target
+                    .upload_part(upload_id, part_number, body)
+                    .await
+                    .map_err(|source| UploadError::Part {
+                        part_number,
+                        source,
+                    })?;
+                Ok((part_number, part))
+            })
+            .buffer_unordered(self.concurrency)
+            .try_collect()
+            .await?;
+        parts.sort_by_key(|(part_number, _)| *part_number);
+        Ok(parts.into_iter().map(|(_, part)| part).collect())
+    }
+}
+
+/// Splits `body` into numbered parts of `part_size` bytes
+///
+/// An empty body produces a single, empty part.
+fn read_parts(
+    body: ByteStream,
+    part_size: usize,
+) -> impl futures_util::Stream<Item = Result<(u32, Bytes), BoxError>> {
+    struct State {
+        body: ByteStream,
+        buffer: BytesMut,
+        next_part: u32,
+        finished: bool,
+    }
+
+    let state = State {
+        body,
+        buffer: BytesMut::new(),
+        next_part: 1,
+        finished: false,
+    };
+    stream::unfold(state, move |mut state| async move {
+        let mut body_done = false;
+        while !state.finished && !body_done && state.buffer.len() < part_size {
+            match state.body.next().await {
+                Some(Ok(data)) => state.buffer.extend_from_slice(&data),
+                Some(Err(err)) => {
+                    state.finished = true;
+                    return Some((Err(err.into()), state));
+                }
+                None => body_done = true,
+            }
+        }
+        if state.finished || (body_done && state.buffer.is_empty() && state.next_part > 1) {
+            return None;
+        }
+        if body_done && state.buffer.len() <= part_size {
+            state.finished = true;
+        }
+        let len = part_size.min(state.buffer.len());
+        let part = (state.next_part, state.buffer.split_to(len).freeze());
+        state.next_part += 1;
+        Some((Ok(part), state))
+    })
+}
+
+/// Error returned when a multipart upload fails
+///
+/// Unless the upload couldn't be created, it has been aborted.
+#[non_exhaustive]
+#[derive(Debug)]
+pub enum UploadError {
+    /// The upload couldn't be created
+    Create(BoxError),
+
+    /// The body couldn't be read
+    Body(BoxError),
+
+    /// A part failed to upload
+    Part {
+        /// The number of the part that failed
+        part_number: u32,
+        /// The error returned by the last attempt to upload the part
+        source: BoxError,
+    },
+
+    /// The upload couldn't be completed
+    Complete(BoxError),
+}
+
+impl fmt::Display for UploadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UploadError::Create(_) => write!(f, "failed to create the multipart upload"),
+            UploadError::Body(_) => write!(f, "failed to read the body to upload"),
+            UploadError::Part { part_number, .. } => {
+                write!(f, "failed to upload part {}", part_number)
+            }
+            UploadError::Complete(_) => write!(f, "failed to complete the multipart upload"),
+        }
+    }
+}
+
+impl Error for UploadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            UploadError::Create(source)
+            | UploadError::Body(source)
+            | UploadError::Part { source, .. }
+            | UploadError::Complete(source) => Some(source.as_ref()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_util::{data, FakeService};
+    use crate::upload::{UploadError, UploadParts, Uploader};
+    use crate::BoxFuture;
+    use aws_smithy_http::byte_stream::ByteStream;
+    use bytes::Bytes;
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    /// Records uploaded parts in memory
+    #[derive(Default)]
+    struct InMemory {
+        service: FakeService,
+        parts: Mutex<HashMap<u32, Bytes>>,
+        aborted: Mutex<bool>,
+    }
+
+    #[derive(Clone, Default)]
+    struct Target(Arc<InMemory>);
+
+    impl UploadParts for Target {
+        type UploadId = String;
+        type Part = u32;
+        type Output = Bytes;
+
+        fn create(&self) -> BoxFuture<Self::UploadId> {
+            Box::pin(async { Ok("upload-id".to_string()) })
+        }
+
+        fn upload_part(
+            &self,
+            upload_id: &Self::UploadId,
+            part_number: u32,
+            body: Bytes,
+        ) -> BoxFuture<Self::Part> {
+            assert_eq!("upload-id", upload_id);
+            let state = self.0.clone();
+            Box::pin(async move {
+                state.service.request(part_number.into()).await?;
+                state.parts.lock().unwrap().insert(part_number, body);
+                Ok(part_number)
+            })
+        }
+
+        fn complete(
+            &self,
+            _upload_id: &Self::UploadId,
+            parts: Vec<Self::Part>,
+        ) -> BoxFuture<Self::Output> {
+            let uploaded = self.0.parts.lock().unwrap().clone();
+            Box::pin(async move {
+                let mut output = Vec::new();
+                for part_number in parts {
+                    output.extend_from_slice(&uploaded[&part_number]);
+                }
+                Ok(output.into())
+            })
+        }
+
+        fn abort(&self, _upload_id: &Self::UploadId) -> BoxFuture<()> {
+            *self.0.aborted.lock().unwrap() = true;
+            Box::pin(async { Ok(()) })
+        }
+    }
+
+    #[tokio::test]
+    async fn uploads_parts_concurrently_in_order() {
+        let target = Target::default();
+        let output = Uploader::new()
+            .with_part_size(1000)
+            .with_concurrency(4)
+            .upload(&target, ByteStream::from(data(10_000)))
+            .await
+            .expect("success");
+        assert_eq!(data(10_000), output);
+        assert_eq!(10, target.0.parts.lock().unwrap().len());
+        let max_in_flight = target.0.service.max_in_flight();
+        assert!(
+            max_in_flight > 1 && max_in_flight <= 4,
+            "{} parts in flight",
+            max_in_flight
+        );
+        assert!(!*target.0.aborted.lock().unwrap());
+    }
+
+    #[tokio::test]
+    async fn last_part_may_be_short_and_empty_bodies_have_one_part() {
+        let target = Target::default();
+        let output = Uploader::new()
+            .with_part_size(1000)
+            .upload(&target, ByteStream::from(data(2500)))
+            .await
+            .expect("success");
+        assert_eq!(data(2500), output);
+        assert_eq!(500, target.0.parts.lock().unwrap()[&3].len());
+
+        let target = Target::default();
+        let output = Uploader::new()
+            .upload(&target, ByteStream::from(Vec::new()))
+            .await
+            .expect("success");
+        assert!(output.is_empty());
+        assert_eq!(1, target.0.parts.lock().unwrap().len());
+    }
+
+    #[tokio::test]
+    async fn upload_is_aborted_when_a_part_fails() {
+        let target = Target::default();
+        target.0.service.fail(2, 1);
+        let err = Uploader::new()
+            .with_part_size(1000)
+            .upload(&target, ByteStream::from(data(3000)))
+            .await
+            .expect_err("part 2 fails");
+        assert!(
+            matches!(err, UploadError::Part { part_number: 2, .. }),
+            "{:?}",
+            err
+        );
+        assert!(*target.0.aborted.lock().unwrap());
+    }
+}
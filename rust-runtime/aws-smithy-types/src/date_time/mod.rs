@@ -46,8 +46,10 @@ const NANOS_PER_SECOND_U32: u32 = 1_000_000_000;
 /// The [`aws-smithy-types-convert`](https://crates.io/crates/aws-smithy-types-convert) crate
 /// can be used for conversions to/from other libraries, such as
 /// [`time`](https://crates.io/crates/time) or [`chrono`](https://crates.io/crates/chrono).
-#[derive(Debug, PartialEq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
 pub struct DateTime {
+    // Field order matters: the derived `Ord` compares `seconds` first. `subsecond_nanos` is always
+    // a positive offset from `seconds`, even before the epoch, so this yields chronological order.
     seconds: i64,
     subsecond_nanos: u32,
 }
@@ -332,6 +334,28 @@ mod test {
     use time::format_description::well_known::Rfc3339;
     use time::OffsetDateTime;
 
+    #[test]
+    fn ordering() {
+        let mut date_times = vec![
+            DateTime::from_secs(1),
+            DateTime::from_nanos(-1).unwrap(),
+            DateTime::from_secs_and_nanos(0, 500_000_000),
+            DateTime::from_secs(-1),
+            DateTime::from_millis(-1_500),
+        ];
+        date_times.sort();
+        assert_eq!(
+            date_times,
+            vec![
+                DateTime::from_millis(-1_500),
+                DateTime::from_secs(-1),
+                DateTime::from_nanos(-1).unwrap(),
+                DateTime::from_secs_and_nanos(0, 500_000_000),
+                DateTime::from_secs(1),
+            ]
+        );
+    }
+
     #[test]
     fn test_fmt() {
         let date_time = DateTime::from_secs(1576540098);
@@ -0,0 +1,137 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! A resolved endpoint: the URL to send a request to along with the headers and properties
+//! (e.g. auth schemes) required to send it.
+
+use crate::Document;
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+type MaybeStatic = Cow<'static, str>;
+
+/// A resolved endpoint
+///
+/// In addition to the URL, an endpoint may carry headers that must be added to the request and
+/// arbitrary properties. By convention, the `authSchemes` property lists the auth schemes
+/// (and their signing parameters) that may be used with the endpoint.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Endpoint {
+    url: MaybeStatic,
+    headers: HashMap<MaybeStatic, Vec<MaybeStatic>>,
+    properties: HashMap<MaybeStatic, Document>,
+}
+
+impl Endpoint {
+    /// Returns the URL of this endpoint
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Returns the headers that must be added to requests sent to this endpoint
+    pub fn headers(&self) -> impl Iterator<Item = (&str, impl Iterator<Item = &str>)> {
+        self.headers
+            .iter()
+            .map(|(k, v)| (k.as_ref(), v.iter().map(|v| v.as_ref())))
+    }
+
+    /// Returns the properties of this endpoint
+    pub fn properties(&self) -> &HashMap<Cow<'static, str>, Document> {
+        &self.properties
+    }
+
+    /// Converts this endpoint back into a builder
+    pub fn into_builder(self) -> Builder {
+        Builder { endpoint: self }
+    }
+
+    /// Returns a builder for [`Endpoint`]
+    pub fn builder() -> Builder {
+        Builder::new()
+    }
+}
+
+/// Builder for [`Endpoint`]
+#[derive(Debug, Clone)]
+pub struct Builder {
+    endpoint: Endpoint,
+}
+
+impl Default for Builder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Builder {
+    /// Creates a new builder with an empty URL
+    pub fn new() -> Self {
+        Self {
+            endpoint: Endpoint {
+                url: Default::default(),
+                headers: HashMap::new(),
+                properties: HashMap::new(),
+            },
+        }
+    }
+
+    /// Sets the URL of the endpoint
+    pub fn url(mut self, url: impl Into<MaybeStatic>) -> Self {
+        self.endpoint.url = url.into();
+        self
+    }
+
+    /// Adds a header that must be sent with requests to this endpoint
+    ///
+    /// Calling this method multiple times with the same name will add multiple values.
+    pub fn header(mut self, name: impl Into<MaybeStatic>, value: impl Into<MaybeStatic>) -> Self {
+        self.endpoint
+            .headers
+            .entry(name.into())
+            .or_default()
+            .push(value.into());
+        self
+    }
+
+    /// Sets a property of the endpoint, replacing any existing value for `key`
+    pub fn property(mut self, key: impl Into<MaybeStatic>, value: impl Into<Document>) -> Self {
+        self.endpoint.properties.insert(key.into(), value.into());
+        self
+    }
+
+    /// Constructs the endpoint
+    pub fn build(self) -> Endpoint {
+        self.endpoint
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::endpoint::Endpoint;
+    use crate::Document;
+    use std::collections::HashMap;
+
+    #[test]
+    fn build_endpoint() {
+        let endpoint = Endpoint::builder()
+            .url("https://s3.us-east-1.amazonaws.com")
+            .header("x-amz-header", "a")
+            .header("x-amz-header", "b")
+            .property("authSchemes", Document::Array(vec![]))
+            .build();
+        assert_eq!(endpoint.url(), "https://s3.us-east-1.amazonaws.com");
+        let headers: HashMap<_, Vec<_>> =
+            endpoint.headers().map(|(k, v)| (k, v.collect())).collect();
+        assert_eq!(headers.get("x-amz-header"), Some(&vec!["a", "b"]));
+        assert_eq!(
+            endpoint.properties().get("authSchemes"),
+            Some(&Document::Array(vec![]))
+        );
+
+        let endpoint = endpoint.into_builder().url("https://example.com").build();
+        assert_eq!(endpoint.url(), "https://example.com");
+        assert_eq!(endpoint.headers().count(), 1);
+    }
+}
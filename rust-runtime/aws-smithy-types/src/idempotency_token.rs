@@ -0,0 +1,74 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Idempotency token providers.
+//!
+//! Operations with an `@idempotencyToken` member have that member filled in automatically when it
+//! is left unset. By default, tokens are random UUIDv4 strings. Implement
+//! [`ProvideIdempotencyToken`] and set it on the service config to control the tokens that are
+//! generated, e.g. with [`SequentialTokenProvider`] for reproducible tests.
+
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Generates idempotency tokens for operations that require them.
+pub trait ProvideIdempotencyToken: Send + Sync + Debug {
+    /// Returns a new idempotency token.
+    fn make_idempotency_token(&self) -> String;
+}
+
+/// Deterministic idempotency token provider for use in tests.
+///
+/// Returns UUID-formatted tokens that count up from a starting value, e.g.
+/// `00000000-0000-4000-8000-000000000000`, `00000000-0000-4000-8000-000000000001`, ...
+#[derive(Debug, Default)]
+pub struct SequentialTokenProvider {
+    next: AtomicU64,
+}
+
+impl SequentialTokenProvider {
+    /// Creates a provider whose first token ends in `000000000000`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a provider whose first token is `start`.
+    pub fn starting_at(start: u64) -> Self {
+        Self {
+            next: AtomicU64::new(start),
+        }
+    }
+}
+
+impl ProvideIdempotencyToken for SequentialTokenProvider {
+    fn make_idempotency_token(&self) -> String {
+        let next = self.next.fetch_add(1, Ordering::Relaxed);
+        format!("00000000-0000-4000-8000-{:012x}", next & 0xffff_ffff_ffff)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::idempotency_token::{ProvideIdempotencyToken, SequentialTokenProvider};
+
+    #[test]
+    fn sequential_tokens() {
+        let provider = SequentialTokenProvider::new();
+        assert_eq!(
+            provider.make_idempotency_token(),
+            "00000000-0000-4000-8000-000000000000"
+        );
+        assert_eq!(
+            provider.make_idempotency_token(),
+            "00000000-0000-4000-8000-000000000001"
+        );
+
+        let provider = SequentialTokenProvider::starting_at(0xabc);
+        assert_eq!(
+            provider.make_idempotency_token(),
+            "00000000-0000-4000-8000-000000000abc"
+        );
+    }
+}
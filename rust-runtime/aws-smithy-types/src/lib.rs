@@ -4,6 +4,13 @@
  */
 
 //! Protocol-agnostic types for smithy-rs.
+//!
+//! # Crate Features
+//!
+//! - `serde-serialize`: implements serde's `Serialize` for [`Blob`], [`DateTime`], [`Document`],
+//!   and [`Number`], so that they, and generated shapes that contain them, can be serialized.
+//! - `serde-deserialize`: implements serde's `Deserialize` for the same types.
+//! - `validation-pattern`: provides the `@pattern` check of the [`validation`] module.
 
 #![warn(
     missing_docs,
@@ -17,10 +24,18 @@ use std::collections::HashMap;
 
 pub mod base64;
 pub mod date_time;
+pub mod endpoint;
+pub mod idempotency_token;
+pub mod number;
 pub mod primitive;
 pub mod retry;
+pub mod sensitive;
 pub mod timeout;
 pub mod tristate;
+pub mod validation;
+
+#[cfg(any(feature = "serde-serialize", feature = "serde-deserialize"))]
+mod serde_impl;
 
 pub use crate::date_time::DateTime;
 
@@ -52,6 +67,120 @@ impl AsRef<[u8]> for Blob {
     }
 }
 
+impl From<Vec<u8>> for Blob {
+    fn from(input: Vec<u8>) -> Self {
+        Blob { inner: input }
+    }
+}
+
+impl From<&[u8]> for Blob {
+    fn from(input: &[u8]) -> Self {
+        Blob {
+            inner: input.to_vec(),
+        }
+    }
+}
+
+impl From<Blob> for Vec<u8> {
+    fn from(blob: Blob) -> Self {
+        blob.into_inner()
+    }
+}
+
+/// The value of an enum variant that was unknown when the enum was generated
+///
+/// Generated enums carry values that were added to the model since they were generated in an
+/// `Unknown(UnknownVariantValue)` variant, instead of failing to deserialize them. The value is kept
+/// as is, so that it round-trips when it's serialized again.
+///
+/// The value is opaque so that matching on it isn't mistaken for matching on a known variant: a
+/// value that's unknown today may get its own variant after a regeneration, and match arms on the
+/// `Unknown` variant would then silently stop matching. Compare against [`as_str`](Self::as_str)
+/// when the raw value is needed.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct UnknownVariantValue(String);
+
+impl UnknownVariantValue {
+    /// Returns the raw value of the variant
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for UnknownVariantValue {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl std::fmt::Display for UnknownVariantValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+// Generated code creates unknown values from the raw value on the wire. Users shouldn't, since the
+// value may match a known variant (see above).
+#[doc(hidden)]
+impl From<String> for UnknownVariantValue {
+    fn from(value: String) -> Self {
+        UnknownVariantValue(value)
+    }
+}
+
+#[doc(hidden)]
+impl From<&str> for UnknownVariantValue {
+    fn from(value: &str) -> Self {
+        UnknownVariantValue(value.to_owned())
+    }
+}
+
+/// The name of a union member that was unknown when the union was generated
+///
+/// Generated unions carry members that were added to the model since they were generated in an
+/// `Unknown(UnknownMemberName)` variant, instead of failing to deserialize them. Only the name of
+/// the member is kept; its value is discarded.
+///
+/// Like [`UnknownVariantValue`], the name is opaque so that matching on it isn't mistaken for
+/// matching on a known member. Compare against [`as_str`](Self::as_str) when the raw name is
+/// needed.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct UnknownMemberName(String);
+
+impl UnknownMemberName {
+    /// Returns the name of the member as it was received
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for UnknownMemberName {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl std::fmt::Display for UnknownMemberName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+// Generated code creates unknown member names from the name on the wire.
+#[doc(hidden)]
+impl From<String> for UnknownMemberName {
+    fn from(name: String) -> Self {
+        UnknownMemberName(name)
+    }
+}
+
+#[doc(hidden)]
+impl From<&str> for UnknownMemberName {
+    fn from(name: &str) -> Self {
+        UnknownMemberName(name.to_owned())
+    }
+}
+
 /* ANCHOR: document */
 
 /// Document Type
@@ -78,6 +207,10 @@ pub enum Document {
 
 /// A number type that implements Javascript / JSON semantics, modeled on serde_json:
 /// <https://docs.serde.rs/src/serde_json/number.rs.html#20-22>
+///
+/// Integers keep their full 64-bit precision. Non-negative integers are always `PosInt`, and
+/// negative integers are always `NegInt`. Use the `TryFrom` implementations in [`number`] for
+/// conversions that fail rather than lose precision.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Number {
     /// Unsigned 64-bit integer value
@@ -124,6 +257,112 @@ impl Number {
 
 /* ANCHOR_END: document */
 
+impl Document {
+    /// Returns the inner map if this document is an object.
+    pub fn as_object(&self) -> Option<&HashMap<String, Document>> {
+        match self {
+            Document::Object(object) => Some(object),
+            _ => None,
+        }
+    }
+
+    /// Returns the inner values if this document is an array.
+    pub fn as_array(&self) -> Option<&[Document]> {
+        match self {
+            Document::Array(array) => Some(array),
+            _ => None,
+        }
+    }
+
+    /// Returns the inner number if this document is a number.
+    pub fn as_number(&self) -> Option<Number> {
+        match self {
+            Document::Number(number) => Some(*number),
+            _ => None,
+        }
+    }
+
+    /// Returns the inner string if this document is a string.
+    pub fn as_string(&self) -> Option<&str> {
+        match self {
+            Document::String(string) => Some(string),
+            _ => None,
+        }
+    }
+
+    /// Returns the inner boolean if this document is a boolean.
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Document::Bool(boolean) => Some(*boolean),
+            _ => None,
+        }
+    }
+
+    /// Returns true if this document is null.
+    pub fn is_null(&self) -> bool {
+        matches!(self, Document::Null)
+    }
+}
+
+impl From<bool> for Document {
+    fn from(value: bool) -> Self {
+        Document::Bool(value)
+    }
+}
+
+impl From<String> for Document {
+    fn from(value: String) -> Self {
+        Document::String(value)
+    }
+}
+
+impl From<&str> for Document {
+    fn from(value: &str) -> Self {
+        Document::String(value.to_string())
+    }
+}
+
+impl From<Number> for Document {
+    fn from(value: Number) -> Self {
+        Document::Number(value)
+    }
+}
+
+impl From<u64> for Document {
+    fn from(value: u64) -> Self {
+        Document::Number(Number::PosInt(value))
+    }
+}
+
+impl From<i64> for Document {
+    fn from(value: i64) -> Self {
+        // Matches serde_json: non-negative integers are always represented as `PosInt`
+        if value < 0 {
+            Document::Number(Number::NegInt(value))
+        } else {
+            Document::Number(Number::PosInt(value as u64))
+        }
+    }
+}
+
+impl From<f64> for Document {
+    fn from(value: f64) -> Self {
+        Document::Number(Number::Float(value))
+    }
+}
+
+impl From<Vec<Document>> for Document {
+    fn from(values: Vec<Document>) -> Self {
+        Document::Array(values)
+    }
+}
+
+impl From<HashMap<String, Document>> for Document {
+    fn from(values: HashMap<String, Document>) -> Self {
+        Document::Object(values)
+    }
+}
+
 pub use error::Error;
 
 /// Generic errors for Smithy codegen
@@ -267,3 +506,47 @@ pub mod error {
 
     impl std::error::Error for Error {}
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{Blob, Document, Number, UnknownVariantValue};
+    use std::collections::HashMap;
+
+    #[test]
+    fn blob_conversions() {
+        let blob = Blob::from(vec![1, 2, 3]);
+        assert_eq!(blob.as_ref(), &[1, 2, 3]);
+        assert_eq!(Blob::from(&b"abc"[..]), Blob::new("abc"));
+        assert_eq!(Vec::from(blob), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn unknown_variant_value() {
+        let value = UnknownVariantValue::from("NewValue");
+        assert_eq!("NewValue", value.as_str());
+        assert_eq!(value, UnknownVariantValue::from("NewValue".to_string()));
+        assert_eq!("NewValue", value.to_string());
+    }
+
+    #[test]
+    fn document_conversions() {
+        assert_eq!(Document::from(true).as_bool(), Some(true));
+        assert_eq!(Document::from("hello").as_string(), Some("hello"));
+        assert_eq!(Document::from(5_i64).as_number(), Some(Number::PosInt(5)));
+        assert_eq!(Document::from(-5_i64).as_number(), Some(Number::NegInt(-5)));
+        assert_eq!(Document::from(1.5).as_number(), Some(Number::Float(1.5)));
+        assert!(Document::Null.is_null());
+
+        let array = Document::from(vec![Document::from(1_u64), Document::Null]);
+        assert_eq!(array.as_array().map(|values| values.len()), Some(2));
+        assert_eq!(array.as_object(), None);
+
+        let mut object = HashMap::new();
+        object.insert("key".to_string(), Document::from("value"));
+        let object = Document::from(object);
+        assert_eq!(
+            object.as_object().and_then(|o| o.get("key")),
+            Some(&Document::from("value"))
+        );
+    }
+}
@@ -0,0 +1,243 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Checked conversions from [`Number`]
+//!
+//! Unlike the lossy `to_*` methods of [`Number`], the `TryFrom<Number>` implementations in this
+//! module fail rather than truncate, wrap, or round the number:
+//! ```
+//! use aws_smithy_types::Number;
+//! use std::convert::TryFrom;
+//!
+//! assert_eq!(Ok(300), i32::try_from(Number::PosInt(300)));
+//! assert_eq!(Ok(3), u8::try_from(Number::Float(3.0)));
+//! u8::try_from(Number::PosInt(300)).expect_err("300 doesn't fit in a u8");
+//! u8::try_from(Number::Float(3.5)).expect_err("3.5 isn't an integer");
+//! f64::try_from(Number::PosInt(u64::MAX)).expect_err("u64::MAX can't be represented exactly");
+//! ```
+
+use crate::Number;
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+
+/// The largest integer such that it and all smaller integers can be represented exactly in an `f64`
+const F64_MAX_EXACT_INTEGER: u64 = 1 << f64::MANTISSA_DIGITS;
+/// The largest integer such that it and all smaller integers can be represented exactly in an `f32`
+const F32_MAX_EXACT_INTEGER: u64 = 1 << f32::MANTISSA_DIGITS;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TryFromNumberErrorKind {
+    /// The number is outside of the range of the target type
+    OutOfRange,
+    /// A floating-point number with a fractional part was converted into an integer
+    FractionalFloat,
+    /// An integer was converted into a floating-point type that can't represent it exactly
+    InexactInteger,
+}
+
+/// The error returned when a checked conversion from a [`Number`] fails
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TryFromNumberError {
+    kind: TryFromNumberErrorKind,
+}
+
+impl TryFromNumberError {
+    fn new(kind: TryFromNumberErrorKind) -> Self {
+        Self { kind }
+    }
+}
+
+impl fmt::Display for TryFromNumberError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            TryFromNumberErrorKind::OutOfRange => {
+                write!(f, "number is out of range of the target type")
+            }
+            TryFromNumberErrorKind::FractionalFloat => {
+                write!(f, "floating-point number has a fractional part")
+            }
+            TryFromNumberErrorKind::InexactInteger => write!(
+                f,
+                "integer cannot be represented exactly by the target floating-point type"
+            ),
+        }
+    }
+}
+
+impl Error for TryFromNumberError {}
+
+fn out_of_range<E>(_: E) -> TryFromNumberError {
+    TryFromNumberError::new(TryFromNumberErrorKind::OutOfRange)
+}
+
+/// Returns `value` if it's an integer in the range `[min, max_exclusive)`
+fn integral_float(value: f64, min: f64, max_exclusive: f64) -> Result<f64, TryFromNumberError> {
+    if !value.is_finite() || value < min || value >= max_exclusive {
+        return Err(out_of_range(()));
+    }
+    if value.fract() != 0.0 {
+        return Err(TryFromNumberError::new(
+            TryFromNumberErrorKind::FractionalFloat,
+        ));
+    }
+    Ok(value)
+}
+
+macro_rules! try_from_number_to_int {
+    ($typ:ident) => {
+        impl TryFrom<Number> for $typ {
+            type Error = TryFromNumberError;
+
+            fn try_from(value: Number) -> Result<Self, Self::Error> {
+                match value {
+                    Number::PosInt(value) => $typ::try_from(value).map_err(out_of_range),
+                    Number::NegInt(value) => $typ::try_from(value).map_err(out_of_range),
+                    // `MAX as f64` rounds up to the next power of two for 64-bit integers, so the
+                    // exclusive upper bound is `MAX + 1` for every integer type
+                    Number::Float(value) => {
+                        integral_float(value, $typ::MIN as f64, $typ::MAX as f64 + 1.0)
+                            .map(|value| value as $typ)
+                    }
+                }
+            }
+        }
+    };
+}
+
+try_from_number_to_int!(i8);
+try_from_number_to_int!(i16);
+try_from_number_to_int!(i32);
+try_from_number_to_int!(i64);
+
+try_from_number_to_int!(u8);
+try_from_number_to_int!(u16);
+try_from_number_to_int!(u32);
+try_from_number_to_int!(u64);
+
+/// Returns `value` if its magnitude is at most `max_exact`
+fn exact_integer(value: Number, max_exact: u64) -> Result<Number, TryFromNumberError> {
+    let magnitude = match value {
+        Number::PosInt(value) => value,
+        Number::NegInt(value) => value.unsigned_abs(),
+        Number::Float(_) => return Ok(value),
+    };
+    if magnitude > max_exact {
+        return Err(TryFromNumberError::new(
+            TryFromNumberErrorKind::InexactInteger,
+        ));
+    }
+    Ok(value)
+}
+
+impl TryFrom<Number> for f64 {
+    type Error = TryFromNumberError;
+
+    fn try_from(value: Number) -> Result<Self, Self::Error> {
+        Ok(exact_integer(value, F64_MAX_EXACT_INTEGER)?.to_f64())
+    }
+}
+
+impl TryFrom<Number> for f32 {
+    type Error = TryFromNumberError;
+
+    fn try_from(value: Number) -> Result<Self, Self::Error> {
+        match exact_integer(value, F32_MAX_EXACT_INTEGER)? {
+            // rounding to the nearest `f32` is expected, but overflowing into infinity is not
+            Number::Float(value) if value.is_finite() && (value as f32).is_infinite() => {
+                Err(out_of_range(()))
+            }
+            value => Ok(value.to_f32()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::Number;
+    use proptest::prelude::*;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn integer_conversions() {
+        assert_eq!(Ok(-1), i8::try_from(Number::NegInt(-1)));
+        assert_eq!(Ok(u64::MAX), u64::try_from(Number::PosInt(u64::MAX)));
+        assert_eq!(Ok(i64::MIN), i64::try_from(Number::NegInt(i64::MIN)));
+        i64::try_from(Number::PosInt(u64::MAX)).expect_err("out of range");
+        u32::try_from(Number::NegInt(-1)).expect_err("out of range");
+
+        assert_eq!(Ok(255), u8::try_from(Number::Float(255.0)));
+        assert_eq!(
+            Ok(i64::MIN),
+            i64::try_from(Number::Float(-9223372036854775808.0))
+        );
+        u8::try_from(Number::Float(256.0)).expect_err("out of range");
+        u64::try_from(Number::Float(18446744073709551616.0)).expect_err("out of range");
+        i64::try_from(Number::Float(9223372036854775808.0)).expect_err("out of range");
+        i32::try_from(Number::Float(0.5)).expect_err("fractional");
+        i32::try_from(Number::Float(f64::NAN)).expect_err("NaN");
+        i32::try_from(Number::Float(f64::INFINITY)).expect_err("infinity");
+    }
+
+    #[test]
+    fn float_conversions() {
+        assert_eq!(Ok(1.5), f64::try_from(Number::Float(1.5)));
+        assert_eq!(
+            Ok(9007199254740992.0),
+            f64::try_from(Number::PosInt(1 << 53))
+        );
+        f64::try_from(Number::PosInt((1 << 53) + 1)).expect_err("inexact");
+        f64::try_from(Number::NegInt(i64::MIN)).expect_err("inexact");
+        assert_eq!(Ok(-16777216.0), f32::try_from(Number::NegInt(-(1 << 24))));
+        f32::try_from(Number::PosInt((1 << 24) + 1)).expect_err("inexact");
+        f32::try_from(Number::Float(f64::MAX)).expect_err("out of range");
+        assert!(f32::try_from(Number::Float(f64::INFINITY))
+            .unwrap()
+            .is_infinite());
+    }
+
+    proptest! {
+        #[test]
+        fn pos_int_matches_std(value: u64) {
+            let number = Number::PosInt(value);
+            prop_assert_eq!(u8::try_from(value).ok(), u8::try_from(number).ok());
+            prop_assert_eq!(u32::try_from(value).ok(), u32::try_from(number).ok());
+            prop_assert_eq!(i16::try_from(value).ok(), i16::try_from(number).ok());
+            prop_assert_eq!(i64::try_from(value).ok(), i64::try_from(number).ok());
+            prop_assert_eq!(Ok(value), u64::try_from(number));
+        }
+
+        #[test]
+        fn neg_int_matches_std(value in i64::MIN..0) {
+            let number = Number::NegInt(value);
+            prop_assert_eq!(i8::try_from(value).ok(), i8::try_from(number).ok());
+            prop_assert_eq!(i32::try_from(value).ok(), i32::try_from(number).ok());
+            prop_assert_eq!(Ok(value), i64::try_from(number));
+            prop_assert!(u64::try_from(number).is_err());
+        }
+
+        #[test]
+        fn integral_floats_round_trip(value in -(1i64 << 53)..(1i64 << 53)) {
+            prop_assert_eq!(Ok(value), i64::try_from(Number::Float(value as f64)));
+            let number = if value < 0 { Number::NegInt(value) } else { Number::PosInt(value as u64) };
+            prop_assert_eq!(Ok(value as f64), f64::try_from(number));
+        }
+
+        #[test]
+        fn matches_serde_json(value: f64) {
+            // serde_json's `as_i64`/`as_u64` only convert integers, while these conversions also
+            // accept floats without a fractional part
+            let json = serde_json::Number::from_f64(value);
+            let number = Number::Float(value);
+            if let Some(json) = json {
+                prop_assert_eq!(json.as_f64(), f64::try_from(number).ok());
+            }
+            if value.fract() != 0.0 {
+                prop_assert!(i64::try_from(number).is_err());
+                prop_assert!(u64::try_from(number).is_err());
+            }
+        }
+    }
+}
@@ -0,0 +1,93 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Wrapper for values that must never be written to logs
+
+use std::fmt;
+
+const REDACTED: &str = "*** Sensitive Data Redacted ***";
+
+/// Wrapper that redacts its value from `Debug` and `Display` output
+///
+/// This is used when formatting credentials, signatures, and `@sensitive` members so that
+/// `tracing` output and error messages are safe to ship to log aggregators. The wrapped value can
+/// still be accessed directly.
+///
+/// ```
+/// use aws_smithy_types::sensitive::Sensitive;
+///
+/// let secret = Sensitive("hunter2");
+/// assert_eq!(format!("{}", secret), "*** Sensitive Data Redacted ***");
+/// assert_eq!(format!("{:?}", secret), "\"*** Sensitive Data Redacted ***\"");
+/// assert_eq!(secret.0, "hunter2");
+/// ```
+#[derive(Clone, Copy, Default, Eq, PartialEq, Hash)]
+pub struct Sensitive<T>(pub T);
+
+impl<T> Sensitive<T> {
+    /// Wraps `value` so that it is redacted when formatted
+    pub fn new(value: T) -> Self {
+        Sensitive(value)
+    }
+
+    /// Returns the wrapped value
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> From<T> for Sensitive<T> {
+    fn from(value: T) -> Self {
+        Sensitive(value)
+    }
+}
+
+/// Formatted as a quoted string, matching the `Debug` output of generated structs with
+/// `@sensitive` members
+impl<T> fmt::Debug for Sensitive<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(REDACTED, f)
+    }
+}
+
+impl<T> fmt::Display for Sensitive<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::sensitive::Sensitive;
+
+    #[derive(Debug)]
+    #[allow(dead_code)]
+    struct Credentials {
+        access_key_id: String,
+        secret_access_key: Sensitive<String>,
+    }
+
+    #[test]
+    fn sensitive_values_are_redacted_when_nested() {
+        let credentials = Credentials {
+            access_key_id: "AKID".into(),
+            secret_access_key: Sensitive("secret".into()),
+        };
+        let debug = format!("{:?}", credentials);
+        assert_eq!(
+            debug,
+            "Credentials { access_key_id: \"AKID\", secret_access_key: \"*** Sensitive Data Redacted ***\" }"
+        );
+        let pretty = format!("{:#?}", credentials);
+        assert!(!pretty.contains("secret\""), "{}", pretty);
+    }
+
+    #[test]
+    fn formatting_options_do_not_reveal_the_value() {
+        let secret = Sensitive(1234);
+        assert_eq!(format!("{:>40}", secret), "*** Sensitive Data Redacted ***");
+        assert_eq!(secret.into_inner(), 1234);
+    }
+}
@@ -0,0 +1,326 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! serde's `Serialize` and `Deserialize` for the types of this crate
+//!
+//! These are enabled by the `serde-serialize` and `serde-deserialize` features. In human-readable
+//! formats (e.g. JSON), a [`Blob`] is a base64 string and a [`DateTime`] is an RFC-3339 string.
+//! In binary formats, a `Blob` is a byte array and a `DateTime` is a `(seconds, nanoseconds)` tuple.
+//! A [`Document`] is represented by the equivalent value of the format in both cases.
+
+#[cfg(feature = "serde-serialize")]
+mod ser {
+    use crate::date_time::Format;
+    use crate::{Blob, DateTime, Document, Number, UnknownMemberName, UnknownVariantValue};
+    use serde::ser::Error;
+    use serde::{Serialize, Serializer};
+
+    impl Serialize for Blob {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            if serializer.is_human_readable() {
+                serializer.serialize_str(&crate::base64::encode(&self.inner))
+            } else {
+                serializer.serialize_bytes(&self.inner)
+            }
+        }
+    }
+
+    impl Serialize for DateTime {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            if serializer.is_human_readable() {
+                let formatted = self.fmt(Format::DateTime).map_err(S::Error::custom)?;
+                serializer.serialize_str(&formatted)
+            } else {
+                (self.secs(), self.subsec_nanos()).serialize(serializer)
+            }
+        }
+    }
+
+    impl Serialize for Number {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            match *self {
+                Number::PosInt(value) => serializer.serialize_u64(value),
+                Number::NegInt(value) => serializer.serialize_i64(value),
+                Number::Float(value) => serializer.serialize_f64(value),
+            }
+        }
+    }
+
+    impl Serialize for Document {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            match self {
+                Document::Object(object) => object.serialize(serializer),
+                Document::Array(array) => array.serialize(serializer),
+                Document::Number(number) => number.serialize(serializer),
+                Document::String(string) => serializer.serialize_str(string),
+                Document::Bool(boolean) => serializer.serialize_bool(*boolean),
+                Document::Null => serializer.serialize_unit(),
+            }
+        }
+    }
+
+    impl Serialize for UnknownVariantValue {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_str(self.as_str())
+        }
+    }
+
+    impl Serialize for UnknownMemberName {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_str(self.as_str())
+        }
+    }
+}
+
+#[cfg(feature = "serde-deserialize")]
+mod de {
+    use crate::date_time::Format;
+    use crate::{Blob, DateTime, Document, Number, UnknownMemberName, UnknownVariantValue};
+    use serde::de::{Error, MapAccess, SeqAccess, Visitor};
+    use serde::{Deserialize, Deserializer};
+    use std::collections::HashMap;
+    use std::fmt;
+
+    struct BlobVisitor;
+
+    impl<'de> Visitor<'de> for BlobVisitor {
+        type Value = Blob;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("a base64 encoded string or a byte array")
+        }
+
+        fn visit_str<E: Error>(self, value: &str) -> Result<Blob, E> {
+            crate::base64::decode(value)
+                .map(Blob::new)
+                .map_err(E::custom)
+        }
+
+        fn visit_bytes<E: Error>(self, value: &[u8]) -> Result<Blob, E> {
+            Ok(Blob::from(value))
+        }
+
+        fn visit_byte_buf<E: Error>(self, value: Vec<u8>) -> Result<Blob, E> {
+            Ok(Blob::new(value))
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Blob, A::Error> {
+            let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+            while let Some(byte) = seq.next_element()? {
+                bytes.push(byte);
+            }
+            Ok(Blob::new(bytes))
+        }
+    }
+
+    impl<'de> Deserialize<'de> for Blob {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            if deserializer.is_human_readable() {
+                deserializer.deserialize_str(BlobVisitor)
+            } else {
+                deserializer.deserialize_byte_buf(BlobVisitor)
+            }
+        }
+    }
+
+    impl<'de> Deserialize<'de> for DateTime {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            if deserializer.is_human_readable() {
+                let formatted = String::deserialize(deserializer)?;
+                DateTime::from_str(&formatted, Format::DateTime).map_err(D::Error::custom)
+            } else {
+                let (seconds, nanos) = <(i64, u32)>::deserialize(deserializer)?;
+                if nanos >= 1_000_000_000 {
+                    return Err(D::Error::custom(format!(
+                        "subsecond nanos must be less than 1_000_000_000, but were {}",
+                        nanos
+                    )));
+                }
+                Ok(DateTime::from_secs_and_nanos(seconds, nanos))
+            }
+        }
+    }
+
+    struct NumberVisitor;
+
+    impl<'de> Visitor<'de> for NumberVisitor {
+        type Value = Number;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("a number")
+        }
+
+        fn visit_u64<E: Error>(self, value: u64) -> Result<Number, E> {
+            Ok(Number::PosInt(value))
+        }
+
+        fn visit_i64<E: Error>(self, value: i64) -> Result<Number, E> {
+            // Matches serde_json: non-negative integers are always represented as `PosInt`
+            if value < 0 {
+                Ok(Number::NegInt(value))
+            } else {
+                Ok(Number::PosInt(value as u64))
+            }
+        }
+
+        fn visit_f64<E: Error>(self, value: f64) -> Result<Number, E> {
+            Ok(Number::Float(value))
+        }
+    }
+
+    impl<'de> Deserialize<'de> for Number {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            deserializer.deserialize_any(NumberVisitor)
+        }
+    }
+
+    struct DocumentVisitor;
+
+    impl<'de> Visitor<'de> for DocumentVisitor {
+        type Value = Document;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("a document")
+        }
+
+        fn visit_bool<E: Error>(self, value: bool) -> Result<Document, E> {
+            Ok(Document::Bool(value))
+        }
+
+        fn visit_u64<E: Error>(self, value: u64) -> Result<Document, E> {
+            NumberVisitor.visit_u64(value).map(Document::Number)
+        }
+
+        fn visit_i64<E: Error>(self, value: i64) -> Result<Document, E> {
+            NumberVisitor.visit_i64(value).map(Document::Number)
+        }
+
+        fn visit_f64<E: Error>(self, value: f64) -> Result<Document, E> {
+            NumberVisitor.visit_f64(value).map(Document::Number)
+        }
+
+        fn visit_str<E: Error>(self, value: &str) -> Result<Document, E> {
+            Ok(Document::String(value.to_string()))
+        }
+
+        fn visit_string<E: Error>(self, value: String) -> Result<Document, E> {
+            Ok(Document::String(value))
+        }
+
+        fn visit_unit<E: Error>(self) -> Result<Document, E> {
+            Ok(Document::Null)
+        }
+
+        fn visit_none<E: Error>(self) -> Result<Document, E> {
+            Ok(Document::Null)
+        }
+
+        fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Document, D::Error> {
+            Document::deserialize(deserializer)
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Document, A::Error> {
+            let mut array = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+            while let Some(value) = seq.next_element()? {
+                array.push(value);
+            }
+            Ok(Document::Array(array))
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Document, A::Error> {
+            let mut object = HashMap::with_capacity(map.size_hint().unwrap_or(0));
+            while let Some((key, value)) = map.next_entry()? {
+                object.insert(key, value);
+            }
+            Ok(Document::Object(object))
+        }
+    }
+
+    impl<'de> Deserialize<'de> for Document {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            deserializer.deserialize_any(DocumentVisitor)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for UnknownVariantValue {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            String::deserialize(deserializer).map(UnknownVariantValue::from)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for UnknownMemberName {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            String::deserialize(deserializer).map(UnknownMemberName::from)
+        }
+    }
+}
+
+#[cfg(all(test, feature = "serde-serialize", feature = "serde-deserialize"))]
+mod test {
+    use crate::{Blob, DateTime, Document, Number, UnknownMemberName, UnknownVariantValue};
+    use serde::de::DeserializeOwned;
+    use serde::Serialize;
+    use std::collections::HashMap;
+    use std::fmt::Debug;
+
+    fn round_trip<T>(value: T, json: &str)
+    where
+        T: Serialize + DeserializeOwned + PartialEq + Debug,
+    {
+        assert_eq!(json, serde_json::to_string(&value).unwrap());
+        assert_eq!(value, serde_json::from_str::<T>(json).unwrap());
+    }
+
+    #[test]
+    fn blob() {
+        round_trip(Blob::new("hello"), r#""aGVsbG8=""#);
+        serde_json::from_str::<Blob>(r#""not base64!""#).expect_err("invalid base64");
+    }
+
+    #[test]
+    fn date_time() {
+        round_trip(
+            DateTime::from_secs_and_nanos(1576540098, 520_000_000),
+            r#""2019-12-16T23:48:18.52Z""#,
+        );
+        serde_json::from_str::<DateTime>(r#""yesterday""#).expect_err("invalid date time");
+    }
+
+    #[test]
+    fn number() {
+        round_trip(Number::PosInt(u64::MAX), "18446744073709551615");
+        round_trip(Number::NegInt(i64::MIN), "-9223372036854775808");
+        round_trip(Number::Float(1.5), "1.5");
+    }
+
+    #[test]
+    fn document() {
+        let mut object = HashMap::new();
+        object.insert(
+            "list".to_string(),
+            Document::Array(vec![
+                Document::from(1_u64),
+                Document::from(-1_i64),
+                Document::from("two"),
+                Document::Bool(true),
+                Document::Null,
+            ]),
+        );
+        round_trip(
+            Document::Object(object),
+            r#"{"list":[1,-1,"two",true,null]}"#,
+        );
+    }
+
+    #[test]
+    fn unknown_variant_value() {
+        round_trip(UnknownVariantValue::from("NewValue"), r#""NewValue""#);
+    }
+
+    #[test]
+    fn unknown_member_name() {
+        round_trip(UnknownMemberName::from("newMember"), r#""newMember""#);
+    }
+}
@@ -51,6 +51,20 @@ impl Http {
         self
     }
 
+    /// Return this config's TLS negotiation timeout
+    ///
+    /// A limit on the amount of time a TLS handshake takes from when the CLIENT HELLO message is
+    /// sent to the time the client and server have fully negotiated ciphers and exchanged keys.
+    pub fn tls_negotiation_timeout(&self) -> TriState<Duration> {
+        self.tls_negotiation.clone()
+    }
+
+    /// Mutate this `timeout::Http` config, setting the TLS negotiation timeout
+    pub fn with_tls_negotiation_timeout(mut self, timeout: TriState<Duration>) -> Self {
+        self.tls_negotiation = timeout;
+        self
+    }
+
     /// Return true if any timeouts are intentionally set or disabled
     pub fn has_timeouts(&self) -> bool {
         !self.is_unset()
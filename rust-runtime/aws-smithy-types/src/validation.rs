@@ -0,0 +1,365 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Validation of Smithy constraint traits
+//!
+//! This module implements the checks of the `@length`, `@range`, `@pattern`, and `@uniqueItems`
+//! traits. Each check returns a [`ConstraintViolation`] describing the first problem it finds,
+//! identified by the name of the member being validated.
+//!
+//! The `@pattern` check requires the `validation-pattern` feature.
+//!
+//! ```
+//! use aws_smithy_types::validation::{check_length, check_range};
+//!
+//! let bucket = "my-bucket";
+//! check_length("bucket", bucket, Some(3), Some(63)).unwrap();
+//! check_range("max_keys", 1001, Some(1), Some(1000)).expect_err("out of range");
+//! ```
+
+use crate::Blob;
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::fmt;
+
+/// A value that doesn't satisfy a constraint trait of the model
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConstraintViolation {
+    field: &'static str,
+    details: String,
+}
+
+impl ConstraintViolation {
+    /// Create a violation of a constraint on `field`
+    pub fn new(field: &'static str, details: impl Into<String>) -> Self {
+        Self {
+            field,
+            details: details.into(),
+        }
+    }
+
+    /// The name of the member that violated the constraint
+    pub fn field(&self) -> &'static str {
+        self.field
+    }
+
+    /// A description of the constraint and how it was violated
+    pub fn details(&self) -> &str {
+        &self.details
+    }
+}
+
+impl fmt::Display for ConstraintViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} violated a constraint: {}", self.field, self.details)
+    }
+}
+
+impl Error for ConstraintViolation {}
+
+fn violation(field: &'static str, details: String) -> ConstraintViolation {
+    ConstraintViolation::new(field, details)
+}
+
+fn fmt_bounds<T: fmt::Display>(min: Option<T>, max: Option<T>) -> String {
+    match (min, max) {
+        (Some(min), Some(max)) => format!("between {} and {}", min, max),
+        (Some(min), None) => format!("at least {}", min),
+        (None, Some(max)) => format!("at most {}", max),
+        (None, None) => "unbounded".to_string(),
+    }
+}
+
+/// The length of a value, as measured by the `@length` trait
+///
+/// Strings are measured in Unicode scalar values, blobs in bytes, and collections in elements.
+pub trait ConstraintLength {
+    /// The length of this value
+    fn constraint_length(&self) -> usize;
+}
+
+impl ConstraintLength for str {
+    fn constraint_length(&self) -> usize {
+        self.chars().count()
+    }
+}
+
+impl ConstraintLength for String {
+    fn constraint_length(&self) -> usize {
+        self.as_str().constraint_length()
+    }
+}
+
+impl ConstraintLength for Blob {
+    fn constraint_length(&self) -> usize {
+        self.as_ref().len()
+    }
+}
+
+impl<T> ConstraintLength for [T] {
+    fn constraint_length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T> ConstraintLength for Vec<T> {
+    fn constraint_length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<K, V, S> ConstraintLength for HashMap<K, V, S> {
+    fn constraint_length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<K, V> ConstraintLength for BTreeMap<K, V> {
+    fn constraint_length(&self) -> usize {
+        self.len()
+    }
+}
+
+/// Check that the length of `value` is within the inclusive bounds of the `@length` trait
+pub fn check_length<T: ConstraintLength + ?Sized>(
+    field: &'static str,
+    value: &T,
+    min: Option<usize>,
+    max: Option<usize>,
+) -> Result<(), ConstraintViolation> {
+    let length = value.constraint_length();
+    if matches!(min, Some(min) if length < min) || matches!(max, Some(max) if length > max) {
+        return Err(violation(
+            field,
+            format!(
+                "must have a length {}, but had a length of {}",
+                fmt_bounds(min, max),
+                length
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Check that `value` is within the inclusive bounds of the `@range` trait
+pub fn check_range<T: PartialOrd + fmt::Display>(
+    field: &'static str,
+    value: T,
+    min: Option<T>,
+    max: Option<T>,
+) -> Result<(), ConstraintViolation> {
+    // comparisons with NaN are always false, so NaN is reported as out of range
+    let above_min = match &min {
+        Some(min) => value >= *min,
+        None => true,
+    };
+    let below_max = match &max {
+        Some(max) => value <= *max,
+        None => true,
+    };
+    if above_min && below_max {
+        return Ok(());
+    }
+    Err(violation(
+        field,
+        format!("must be {}, but was {}", fmt_bounds(min, max), value),
+    ))
+}
+
+#[cfg(feature = "validation-pattern")]
+pub use pattern::{check_pattern, Pattern, UnsupportedPattern};
+
+#[cfg(feature = "validation-pattern")]
+mod pattern {
+    use super::{violation, ConstraintViolation};
+    use once_cell::sync::OnceCell;
+    use regex::Regex;
+    use std::error::Error;
+    use std::fmt;
+
+    /// The regular expression of a `@pattern` trait, compiled the first time it's used
+    ///
+    /// Patterns are not implicitly anchored: a value matches if any part of it matches.
+    #[derive(Debug)]
+    pub struct Pattern {
+        pattern: &'static str,
+        regex: OnceCell<Result<Regex, String>>,
+    }
+
+    /// A `@pattern` that can't be evaluated
+    ///
+    /// `@pattern` uses ECMA-262 regular expressions, and some of their features (such as
+    /// lookaround and backreferences) aren't supported by the `regex` crate.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct UnsupportedPattern {
+        pattern: &'static str,
+        message: String,
+    }
+
+    impl UnsupportedPattern {
+        /// The regular expression that couldn't be compiled
+        pub fn pattern(&self) -> &'static str {
+            self.pattern
+        }
+    }
+
+    impl fmt::Display for UnsupportedPattern {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(
+                f,
+                "unsupported @pattern `{}`: {}",
+                self.pattern, self.message
+            )
+        }
+    }
+
+    impl Error for UnsupportedPattern {}
+
+    impl Pattern {
+        /// Create a pattern from the regular expression of a `@pattern` trait
+        pub const fn new(pattern: &'static str) -> Self {
+            Self {
+                pattern,
+                regex: OnceCell::new(),
+            }
+        }
+
+        /// The regular expression of this pattern
+        pub fn as_str(&self) -> &'static str {
+            self.pattern
+        }
+
+        /// Returns true if `value` matches this pattern
+        ///
+        /// Returns an error if the pattern uses features that the `regex` crate doesn't support.
+        pub fn is_match(&self, value: &str) -> Result<bool, UnsupportedPattern> {
+            match self
+                .regex
+                .get_or_init(|| Regex::new(self.pattern).map_err(|err| err.to_string()))
+            {
+                Ok(regex) => Ok(regex.is_match(value)),
+                Err(message) => Err(UnsupportedPattern {
+                    pattern: self.pattern,
+                    message: message.clone(),
+                }),
+            }
+        }
+    }
+
+    /// Check that `value` matches the regular expression of the `@pattern` trait
+    ///
+    /// If the pattern can't be evaluated, the check is skipped and the value is left for the
+    /// service to validate.
+    pub fn check_pattern(
+        field: &'static str,
+        value: &str,
+        pattern: &Pattern,
+    ) -> Result<(), ConstraintViolation> {
+        match pattern.is_match(value) {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(violation(
+                field,
+                format!("must match the pattern `{}`", pattern.as_str()),
+            )),
+            Err(_) => Ok(()),
+        }
+    }
+}
+
+/// Check that no item of `items` is equal to another, as required by the `@uniqueItems` trait
+pub fn check_unique_items<T: PartialEq>(
+    field: &'static str,
+    items: &[T],
+) -> Result<(), ConstraintViolation> {
+    // generated types don't implement `Hash`, so this compares every pair of items
+    for (index, item) in items.iter().enumerate() {
+        if items[..index].contains(item) {
+            return Err(violation(
+                field,
+                format!(
+                    "must not contain duplicate items, but the item at index {} is a duplicate",
+                    index
+                ),
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{check_length, check_range, check_unique_items};
+    use crate::Blob;
+    use std::collections::HashMap;
+
+    #[test]
+    fn length() {
+        // strings are measured in Unicode scalar values, not bytes
+        check_length("a", "héllo", Some(5), Some(5)).unwrap();
+        check_length("a", &Blob::new("héllo"), Some(6), Some(6)).unwrap();
+        check_length("a", &vec![1, 2, 3], None, Some(3)).unwrap();
+        check_length("a", &HashMap::<String, i32>::new(), None, Some(3)).unwrap();
+
+        let err = check_length("name", "", Some(1), None).unwrap_err();
+        assert_eq!("name", err.field());
+        assert_eq!(
+            "name violated a constraint: must have a length at least 1, but had a length of 0",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn range() {
+        check_range("a", 5, Some(1), Some(10)).unwrap();
+        check_range("a", 1.5, None, Some(1.5)).unwrap();
+        let err = check_range("count", -1i64, Some(0), Some(10)).unwrap_err();
+        assert_eq!(
+            "count violated a constraint: must be between 0 and 10, but was -1",
+            err.to_string()
+        );
+        check_range("a", f64::NAN, Some(0.0), None).expect_err("NaN is out of range");
+    }
+
+    #[cfg(feature = "validation-pattern")]
+    #[test]
+    fn pattern() {
+        use super::{check_pattern, Pattern};
+
+        static PATTERN: Pattern = Pattern::new("^[a-z]+$");
+        static UNANCHORED: Pattern = Pattern::new("[0-9]");
+        check_pattern("a", "abc", &PATTERN).unwrap();
+        check_pattern("a", "abc1", &UNANCHORED).unwrap();
+        let err = check_pattern("id", "ABC", &PATTERN).unwrap_err();
+        assert_eq!(
+            "id violated a constraint: must match the pattern `^[a-z]+$`",
+            err.to_string()
+        );
+    }
+
+    #[cfg(feature = "validation-pattern")]
+    #[test]
+    fn unsupported_pattern() {
+        use super::{check_pattern, Pattern};
+
+        // lookahead is valid in ECMA-262 but not supported by the `regex` crate
+        static LOOKAHEAD: Pattern = Pattern::new("^(?!aws:).*$");
+        let err = LOOKAHEAD.is_match("aws:tag").unwrap_err();
+        assert_eq!("^(?!aws:).*$", err.pattern());
+        // the value is left for the service to validate
+        check_pattern("key", "aws:tag", &LOOKAHEAD).unwrap();
+    }
+
+    #[test]
+    fn unique_items() {
+        check_unique_items::<i32>("a", &[]).unwrap();
+        check_unique_items("a", &["a", "b", "c"]).unwrap();
+        let err = check_unique_items("tags", &["a", "b", "a"]).unwrap_err();
+        assert_eq!("tags", err.field());
+        assert_eq!(
+            "tags violated a constraint: must not contain duplicate items, but the item at index 2 is a duplicate",
+            err.to_string()
+        );
+    }
+}
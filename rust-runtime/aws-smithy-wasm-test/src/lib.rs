@@ -0,0 +1,10 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Tests that run the smithy-rs runtime on `wasm32-unknown-unknown`.
+//!
+//! `cargo check` can't catch code that compiles for `wasm32` but panics at runtime (for example,
+//! `std::time::Instant::now`), so these tests make requests through a client in a JavaScript
+//! host. Run them with `wasm-pack test --node` from this directory.
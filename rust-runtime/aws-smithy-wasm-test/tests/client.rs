@@ -0,0 +1,93 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+#![cfg(target_arch = "wasm32")]
+
+use aws_smithy_async::rt::sleep::WasmSleep;
+use aws_smithy_async::time::Instant;
+use aws_smithy_client::erase::DynConnector;
+use aws_smithy_http::body::SdkBody;
+use aws_smithy_http::operation::{self, Operation};
+use aws_smithy_http::response::ParseHttpResponse;
+use aws_smithy_http::result::ConnectorError;
+use bytes::Bytes;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+use tower::layer::util::Identity;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+#[derive(Debug)]
+struct TestError;
+
+impl fmt::Display for TestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the response wasn't successful")
+    }
+}
+
+impl std::error::Error for TestError {}
+
+#[derive(Clone)]
+struct TestParser;
+
+impl ParseHttpResponse for TestParser {
+    type Output = Result<String, TestError>;
+
+    fn parse_unloaded(&self, _response: &mut operation::Response) -> Option<Self::Output> {
+        None
+    }
+
+    fn parse_loaded(&self, response: &http::Response<Bytes>) -> Self::Output {
+        if response.status().is_success() {
+            Ok(String::from_utf8_lossy(response.body()).into_owned())
+        } else {
+            Err(TestError)
+        }
+    }
+}
+
+fn test_operation() -> Operation<TestParser, ()> {
+    let request = operation::Request::new(
+        http::Request::builder()
+            .uri("https://test-service.test-region.amazonaws.com/")
+            .body(SdkBody::from("request body"))
+            .unwrap(),
+    );
+    Operation::new(request, TestParser)
+}
+
+fn test_client() -> aws_smithy_client::Client<DynConnector, Identity> {
+    let connector = DynConnector::new(tower::service_fn(|_request: http::Request<SdkBody>| {
+        std::future::ready(Ok::<_, ConnectorError>(
+            http::Response::builder()
+                .status(200)
+                .body(SdkBody::from("hello"))
+                .unwrap(),
+        ))
+    }));
+    aws_smithy_client::Builder::new()
+        .connector(connector)
+        .middleware(Identity::new())
+        .sleep_impl(Some(Arc::new(WasmSleep::new())))
+        .build()
+}
+
+// Dispatching and parsing time each attempt, which must not read the clock with
+// `std::time::Instant::now`
+#[wasm_bindgen_test]
+async fn call() {
+    let output = test_client().call(test_operation()).await.unwrap();
+    assert_eq!("hello", output);
+}
+
+#[wasm_bindgen_test]
+async fn call_with_deadline() {
+    let output = test_client()
+        .call_with_deadline(test_operation(), Instant::now() + Duration::from_secs(5))
+        .await
+        .unwrap();
+    assert_eq!("hello", output);
+}
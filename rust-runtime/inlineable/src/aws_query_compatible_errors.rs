@@ -0,0 +1,121 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Error metadata for services that migrated from AWS Query to AWS JSON (`@awsQueryCompatible`)
+
+use aws_smithy_types::Error as SmithyError;
+use http::{HeaderMap, HeaderValue};
+
+const X_AMZN_QUERY_ERROR: &str = "x-amzn-query-error";
+const QUERY_ERROR_CODE: &str = "aws_query_error_code";
+const QUERY_ERROR_TYPE: &str = "aws_query_error_type";
+
+/// Provides the error code and type the service would have returned over AWS Query
+///
+/// Services with the `@awsQueryCompatible` trait send these in the `x-amzn-query-error` header so
+/// that clients written against the AWS Query protocol can keep matching on the legacy codes.
+/// The AWS JSON error code remains available from [`code`](aws_smithy_types::Error::code).
+pub trait QueryCompatibleErrorExt {
+    /// Returns the AWS Query error code, e.g. `AWS.SimpleQueueService.NonExistentQueue`
+    fn aws_query_error_code(&self) -> Option<&str>;
+
+    /// Returns the AWS Query error type, `Sender` or `Receiver`
+    fn aws_query_error_type(&self) -> Option<&str>;
+}
+
+impl QueryCompatibleErrorExt for SmithyError {
+    fn aws_query_error_code(&self) -> Option<&str> {
+        self.extra(QUERY_ERROR_CODE)
+    }
+
+    fn aws_query_error_type(&self) -> Option<&str> {
+        self.extra(QUERY_ERROR_TYPE)
+    }
+}
+
+/// Parses the `x-amzn-query-error` header, which has the form `Code;Type`
+pub fn parse_aws_query_compatible_error(headers: &HeaderMap<HeaderValue>) -> Option<(&str, &str)> {
+    let header = headers.get(X_AMZN_QUERY_ERROR)?.to_str().ok()?;
+    let (code, error_type) = header.split_once(';')?;
+    if code.is_empty() || error_type.is_empty() {
+        return None;
+    }
+    Some((code, error_type))
+}
+
+/// Records the AWS Query error code and type from `headers` on the error, if they were sent
+pub fn apply_aws_query_compatible_error(
+    generic: SmithyError,
+    headers: &HeaderMap<HeaderValue>,
+) -> SmithyError {
+    match parse_aws_query_compatible_error(headers) {
+        Some((code, error_type)) => generic
+            .into_builder()
+            .custom(QUERY_ERROR_CODE, code)
+            .custom(QUERY_ERROR_TYPE, error_type)
+            .build(),
+        None => generic,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::aws_query_compatible_errors::{
+        apply_aws_query_compatible_error, parse_aws_query_compatible_error, QueryCompatibleErrorExt,
+    };
+    use aws_smithy_types::Error;
+    use http::{HeaderMap, HeaderValue};
+
+    fn headers(value: &'static str) -> HeaderMap<HeaderValue> {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-amzn-query-error", HeaderValue::from_static(value));
+        headers
+    }
+
+    #[test]
+    fn parse_header() {
+        assert_eq!(
+            Some(("AWS.SimpleQueueService.NonExistentQueue", "Sender")),
+            parse_aws_query_compatible_error(&headers(
+                "AWS.SimpleQueueService.NonExistentQueue;Sender"
+            ))
+        );
+        assert_eq!(None, parse_aws_query_compatible_error(&HeaderMap::new()));
+        assert_eq!(
+            None,
+            parse_aws_query_compatible_error(&headers("InvalidHeader"))
+        );
+        assert_eq!(None, parse_aws_query_compatible_error(&headers(";Sender")));
+        assert_eq!(None, parse_aws_query_compatible_error(&headers("Code;")));
+    }
+
+    #[test]
+    fn keeps_both_codes() {
+        let generic = Error::builder()
+            .code("QueueDoesNotExist")
+            .message("The specified queue does not exist.")
+            .build();
+        let error = apply_aws_query_compatible_error(
+            generic,
+            &headers("AWS.SimpleQueueService.NonExistentQueue;Sender"),
+        );
+        assert_eq!(Some("QueueDoesNotExist"), error.code());
+        assert_eq!(Some("The specified queue does not exist."), error.message());
+        assert_eq!(
+            Some("AWS.SimpleQueueService.NonExistentQueue"),
+            error.aws_query_error_code()
+        );
+        assert_eq!(Some("Sender"), error.aws_query_error_type());
+    }
+
+    #[test]
+    fn missing_header_is_a_noop() {
+        let generic = Error::builder().code("QueueDoesNotExist").build();
+        let error = apply_aws_query_compatible_error(generic.clone(), &HeaderMap::new());
+        assert_eq!(generic, error);
+        assert_eq!(None, error.aws_query_error_code());
+        assert_eq!(None, error.aws_query_error_type());
+    }
+}
@@ -36,7 +36,8 @@ pub fn parse_generic_error(body: &[u8]) -> Result<aws_smithy_types::Error, XmlEr
                     }
                 }
             }
-            "RequestId" => {
+            // EC2 returns `RequestID`, but accept the casing used by other query protocols too
+            "RequestID" | "RequestId" => {
                 err_builder.request_id(try_data(&mut tag)?);
             }
             _ => {}
@@ -96,6 +97,24 @@ mod test {
         assert_eq!(parsed.code(), Some("InvalidGreeting"));
     }
 
+    #[test]
+    fn parse_ec2_request_id() {
+        let xml = br#"
+        <Response>
+            <Errors>
+                <Error>
+                    <Code>InvalidInstanceID.NotFound</Code>
+                    <Message>The instance ID 'i-1a2b3c4d' does not exist</Message>
+                </Error>
+            </Errors>
+            <RequestID>ea966190-f9aa-478e-9ede-example</RequestID>
+        </Response>
+        "#;
+        let parsed = parse_generic_error(xml).expect("valid xml");
+        assert_eq!(parsed.request_id(), Some("ea966190-f9aa-478e-9ede-example"));
+        assert_eq!(parsed.code(), Some("InvalidInstanceID.NotFound"));
+    }
+
     #[test]
     fn test_error_scope() {
         let xml: &[u8] = br#"
@@ -3,6 +3,8 @@
  * SPDX-License-Identifier: Apache-2.0.
  */
 
+use aws_smithy_types::idempotency_token::ProvideIdempotencyToken;
+use std::fmt;
 use std::sync::Mutex;
 
 pub(crate) fn uuid_v4(input: u128) -> String {
@@ -37,6 +39,9 @@ pub(crate) fn uuid_v4(input: u128) -> String {
 /// for testing, two options are available:
 /// 1. Utilize the From<&'static str>` implementation to hard code an idempotency token
 /// 2. Seed the token provider with [`IdempotencyTokenProvider::with_seed`](IdempotencyTokenProvider::with_seed)
+///
+/// Any other [`ProvideIdempotencyToken`] implementation can be wrapped with
+/// [`IdempotencyTokenProvider::custom`](IdempotencyTokenProvider::custom).
 pub struct IdempotencyTokenProvider {
     inner: Inner,
 }
@@ -44,6 +49,19 @@ pub struct IdempotencyTokenProvider {
 enum Inner {
     Static(&'static str),
     Random(Mutex<fastrand::Rng>),
+    Custom(Box<dyn ProvideIdempotencyToken>),
+}
+
+impl fmt::Debug for IdempotencyTokenProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut formatter = f.debug_struct("IdempotencyTokenProvider");
+        match &self.inner {
+            Inner::Static(token) => formatter.field("fixed", token),
+            Inner::Random(_) => formatter.field("random", &true),
+            Inner::Custom(provider) => formatter.field("custom", provider),
+        };
+        formatter.finish()
+    }
 }
 
 pub fn default_provider() -> IdempotencyTokenProvider {
@@ -64,6 +82,7 @@ impl IdempotencyTokenProvider {
                 let input: u128 = rng.lock().unwrap().u128(..);
                 uuid_v4(input)
             }
+            Inner::Custom(provider) => provider.make_idempotency_token(),
         }
     }
 
@@ -84,4 +103,16 @@ impl IdempotencyTokenProvider {
             inner: Inner::Static(token),
         }
     }
+
+    pub fn custom(provider: impl ProvideIdempotencyToken + 'static) -> Self {
+        Self {
+            inner: Inner::Custom(Box::new(provider)),
+        }
+    }
+}
+
+impl ProvideIdempotencyToken for IdempotencyTokenProvider {
+    fn make_idempotency_token(&self) -> String {
+        IdempotencyTokenProvider::make_idempotency_token(self)
+    }
 }
@@ -156,6 +156,35 @@ mod test {
         );
     }
 
+    #[test]
+    fn header_takes_priority() {
+        let response = http::Response::builder()
+            .header(
+                "x-amzn-errortype",
+                "aws.protocoltests.restjson#FooError:http://internal.amazon.com/coral/com.amazon.coral.validate/",
+            )
+            .body(Bytes::from_static(
+                br#"{ "code": "BarError", "__type": "BazError" }"#,
+            ))
+            .unwrap();
+        assert_eq!(
+            parse_generic_error(response.body(), response.headers()).unwrap(),
+            Error::builder().code("FooError").build()
+        );
+    }
+
+    #[test]
+    fn empty_body_uses_header() {
+        let response = http::Response::builder()
+            .header("x-amzn-errortype", "FooError")
+            .body(Bytes::new())
+            .unwrap();
+        assert_eq!(
+            parse_generic_error(response.body(), response.headers()).unwrap(),
+            Error::builder().code("FooError").build()
+        );
+    }
+
     #[test]
     fn ignore_unrecognized_fields() {
         assert_eq!(
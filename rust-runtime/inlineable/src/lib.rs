@@ -3,6 +3,8 @@
  * SPDX-License-Identifier: Apache-2.0.
  */
 
+#[allow(dead_code)]
+mod aws_query_compatible_errors;
 #[allow(dead_code)]
 mod ec2_query_errors;
 #[allow(dead_code)]
@@ -22,6 +24,7 @@ mod server_operation_handler_trait;
 mod test {
     use crate::idempotency_token;
     use crate::idempotency_token::{uuid_v4, IdempotencyTokenProvider};
+    use aws_smithy_types::idempotency_token::SequentialTokenProvider;
     use proptest::prelude::*;
     use regex::Regex;
 
@@ -43,6 +46,19 @@ mod test {
         );
     }
 
+    #[test]
+    fn custom_token_generator() {
+        let provider = IdempotencyTokenProvider::custom(SequentialTokenProvider::new());
+        assert_eq!(
+            provider.make_idempotency_token(),
+            "00000000-0000-4000-8000-000000000000"
+        );
+        assert_eq!(
+            provider.make_idempotency_token(),
+            "00000000-0000-4000-8000-000000000001"
+        );
+    }
+
     #[test]
     fn token_generator() {
         let provider = IdempotencyTokenProvider::random();
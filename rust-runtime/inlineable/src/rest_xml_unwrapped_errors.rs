@@ -64,4 +64,21 @@ mod test {
         assert_eq!(parsed.message(), Some("Hi"));
         assert_eq!(parsed.code(), Some("InvalidGreeting"));
     }
+
+    #[test]
+    fn successful_body_is_not_error() {
+        let xml = br#"<?xml version="1.0" encoding="UTF-8"?>
+<CompleteMultipartUploadResult xmlns="http://s3.amazonaws.com/doc/2006-03-01/">
+    <Bucket>bucket</Bucket>
+    <Key>key</Key>
+</CompleteMultipartUploadResult>"#;
+        assert!(!body_is_error(xml).unwrap());
+
+        let error = br#"<?xml version="1.0" encoding="UTF-8"?>
+<Error>
+    <Code>InternalError</Code>
+    <Message>We encountered an internal error. Please try again.</Message>
+</Error>"#;
+        assert!(body_is_error(error).unwrap());
+    }
 }